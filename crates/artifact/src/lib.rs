@@ -0,0 +1,247 @@
+//! Compiled program wrapped with the metadata needed to check compatibility and reproduce the build.
+
+// Coding conventions
+#![deny(unsafe_code)]
+#![deny(non_upper_case_globals)]
+#![deny(non_camel_case_types)]
+#![deny(non_snake_case)]
+#![deny(unused_mut)]
+// #![deny(dead_code)]
+#![deny(unused_imports)]
+#![deny(missing_docs)]
+// Clippy exclusions
+#![deny(rustdoc::broken_intra_doc_links)]
+#![deny(clippy::wildcard_enum_match_arm)]
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+// #![deny(clippy::todo)]
+#![deny(clippy::unimplemented)]
+#![deny(clippy::panic)]
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Version of the artifact format. Bumped on incompatible changes of [`CompilationArtifact`].
+pub const ARTIFACT_FORMAT_VERSION: u32 = 2;
+
+/// Target VM of the compiled program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+    /// Miden VM
+    Miden,
+    /// Triton VM
+    Triton,
+    /// Valida VM
+    Valida,
+}
+
+/// The compiler that produced the artifact
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompilerInfo {
+    /// Crate version of the compiler
+    pub version: String,
+    /// Git commit hash of the compiler (if known at build time, see `OZK_GIT_HASH` env var)
+    pub git_hash: Option<String>,
+}
+
+impl CompilerInfo {
+    /// Info of the compiler this crate is built with
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: option_env!("OZK_GIT_HASH").map(str::to_string),
+        }
+    }
+}
+
+/// I/O used by the program
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IoManifest {
+    /// I/O functions (intrinsics) imported by the program, e.g. `ozk_stdlib_pub_input`
+    pub imports: Vec<String>,
+}
+
+/// Runtime helper (compiler-provided code, not present in the source) included in the program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeHelper {
+    /// Name of the helper function (procedure) in the program
+    pub name: String,
+    /// Memory reserved for the helper (slot name -> address)
+    pub reserved_memory: BTreeMap<String, i64>,
+}
+
+/// The compiled program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Program {
+    /// Program in the target assembly (source) form
+    Source(String),
+    /// Program in the target binary form
+    Binary(Vec<u8>),
+}
+
+/// A compiled program with metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompilationArtifact {
+    /// See [`ARTIFACT_FORMAT_VERSION`]
+    pub format_version: u32,
+    /// Target VM
+    pub target: Target,
+    /// The compiler that produced the program
+    pub compiler: CompilerInfo,
+    /// Names of the passes run, in order
+    pub passes: Vec<String>,
+    /// Memory layout (region name -> start address)
+    pub memory_layout: BTreeMap<String, i64>,
+    /// I/O used by the program
+    pub io: IoManifest,
+    /// Runtime helpers included in the program
+    #[serde(default)]
+    pub runtime_helpers: Vec<RuntimeHelper>,
+    /// The program itself
+    pub program: Program,
+}
+
+/// Artifact errors
+#[derive(Debug, Error)]
+pub enum ArtifactError {
+    /// IO error on saving/loading
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    /// (De)serialization error
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    /// The artifact was produced with an incompatible format version
+    #[error("unsupported artifact format version {found}, expected {expected}")]
+    UnsupportedFormatVersion {
+        /// Version of the artifact
+        found: u32,
+        /// Version supported by this crate
+        expected: u32,
+    },
+    /// The artifact was produced for another target
+    #[error("artifact target is {found:?}, expected {expected:?}")]
+    TargetMismatch {
+        /// Target of the artifact
+        found: Target,
+        /// Expected target
+        expected: Target,
+    },
+}
+
+impl CompilationArtifact {
+    /// Create an artifact for the program compiled with the current compiler
+    pub fn new(target: Target, program: Program) -> Self {
+        Self {
+            format_version: ARTIFACT_FORMAT_VERSION,
+            target,
+            compiler: CompilerInfo::current(),
+            passes: Vec::new(),
+            memory_layout: BTreeMap::new(),
+            io: IoManifest::default(),
+            runtime_helpers: Vec::new(),
+            program,
+        }
+    }
+
+    /// Serialize to JSON
+    pub fn to_json(&self) -> Result<String, ArtifactError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Deserialize from JSON and check the format version
+    pub fn from_json(json: &str) -> Result<Self, ArtifactError> {
+        let artifact: Self = serde_json::from_str(json)?;
+        if artifact.format_version != ARTIFACT_FORMAT_VERSION {
+            return Err(ArtifactError::UnsupportedFormatVersion {
+                found: artifact.format_version,
+                expected: ARTIFACT_FORMAT_VERSION,
+            });
+        }
+        Ok(artifact)
+    }
+
+    /// Save to a file
+    pub fn save(&self, path: &Path) -> Result<(), ArtifactError> {
+        Ok(std::fs::write(path, self.to_json()?)?)
+    }
+
+    /// Load from a file
+    pub fn load(path: &Path) -> Result<Self, ArtifactError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Check that the artifact was compiled for the expected target
+    pub fn check_target(&self, expected: Target) -> Result<(), ArtifactError> {
+        if self.target != expected {
+            return Err(ArtifactError::TargetMismatch {
+                found: self.target,
+                expected,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns true if the artifact was produced by the same compiler build as the current one
+    pub fn is_reproducible_with_current_compiler(&self) -> bool {
+        self.compiler == CompilerInfo::current()
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifact() -> CompilationArtifact {
+        let mut artifact =
+            CompilationArtifact::new(Target::Miden, Program::Source("begin\nend".to_string()));
+        artifact.passes = vec!["wasm-explicit-func-args".to_string()];
+        artifact
+            .memory_layout
+            .insert("globals".to_string(), 2147475455);
+        artifact.io.imports = vec!["ozk_stdlib_pub_input".to_string()];
+        artifact.runtime_helpers = vec![RuntimeHelper {
+            name: "next_br_propagation".to_string(),
+            reserved_memory: BTreeMap::from([("br_propagation".to_string(), 2147483647)]),
+        }];
+        artifact
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let artifact = artifact();
+        let loaded = CompilationArtifact::from_json(&artifact.to_json().unwrap()).unwrap();
+        assert_eq!(loaded, artifact);
+        assert!(loaded.is_reproducible_with_current_compiler());
+        assert!(loaded.check_target(Target::Miden).is_ok());
+        assert!(matches!(
+            loaded.check_target(Target::Valida),
+            Err(ArtifactError::TargetMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn without_runtime_helpers() {
+        let mut artifact = artifact();
+        artifact.runtime_helpers = Vec::new();
+        let mut json: serde_json::Value =
+            serde_json::from_str(&artifact.to_json().unwrap()).unwrap();
+        json.as_object_mut().unwrap().remove("runtime_helpers");
+        let loaded = CompilationArtifact::from_json(&json.to_string()).unwrap();
+        assert_eq!(loaded, artifact);
+    }
+
+    #[test]
+    fn unsupported_format_version() {
+        let mut artifact = artifact();
+        artifact.format_version = ARTIFACT_FORMAT_VERSION + 1;
+        assert!(matches!(
+            CompilationArtifact::from_json(&artifact.to_json().unwrap()),
+            Err(ArtifactError::UnsupportedFormatVersion { .. })
+        ));
+    }
+}
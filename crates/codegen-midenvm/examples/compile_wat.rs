@@ -0,0 +1,39 @@
+//! Compile a WAT module to Miden assembly and run it on the Miden VM.
+//!
+//! The public inputs are taken from the command line and put on the operand stack:
+//!
+//! ```sh
+//! cargo run -p ozk-codegen-midenvm --example compile_wat -- 1 2
+//! ```
+
+use ozk_codegen_midenvm::compile_wasm;
+use ozk_codegen_midenvm::vm;
+
+const WAT: &str = r#"
+(module
+    (start $main)
+    (func $square (param i32) (result i32)
+        local.get 0
+        local.get 0
+        i32.mul)
+    (func $main
+        i32.const 7
+        call $square
+        i32.const 2
+        i32.sub
+        return)
+)
+"#;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let input = std::env::args()
+        .skip(1)
+        .map(|arg| arg.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let wasm = wat::parse_str(WAT)?;
+    let masm = compile_wasm(&wasm)?;
+    println!("{masm}");
+    let execution = vm::execute(&masm, input, vec![])?;
+    println!("stack on exit: {:?}", execution.stack);
+    Ok(())
+}
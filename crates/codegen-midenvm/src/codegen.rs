@@ -3,18 +3,33 @@
 
 mod inst_buf;
 
+pub use inst_buf::format_masm;
+pub use inst_buf::format_masm_source;
 pub use inst_buf::InstBuffer;
+pub use inst_buf::MasmFormat;
 mod emit;
 pub use emit::*;
 mod miden_inst;
 pub use miden_inst::*;
+use ozk_artifact::CompilationArtifact;
+use ozk_artifact::Program;
+use ozk_artifact::RuntimeHelper;
+use ozk_artifact::Target;
+use ozk_frontend_wasm::WasmFrontendConfig;
+use ozk_ir_transform::runtime_helpers::find_runtime_helper;
+use ozk_ir_transform::wasm::single_func::run_passes_on_func;
+use ozk_ir_transform::wasm::single_func::run_passes_on_module;
 use ozk_miden_dialect::ops::*;
+use ozk_wasm_dialect::ops as wasm;
 use pliron::context::Context;
 use pliron::dialects::builtin::op_interfaces::get_callees_syms;
 use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
 use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
 use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::with_context::AttachContext;
 use rustc_hash::FxHashMap;
 use thiserror::Error;
 use topological_sort::TopologicalSort;
@@ -45,12 +60,182 @@ pub fn emit_prog(
         .collect();
     let sorted_procs = topo_sort_procedures(ctx, procs.into_iter())?;
     let mut b = MidenAssemblyBuilder::new(InstBuffer::new(target_config));
+    if uses_stdlib_u64(ctx, prog_op) {
+        b.use_module("std::math::u64");
+    }
+    let mut over_budget = Vec::new();
     for proc_name in sorted_procs {
         #[allow(clippy::unwrap_used)] // topo sort should not introduce new proc syms
         let proc_op = proc_map.get(&proc_name).unwrap();
         let is_main_proc = proc_name == prog_op.get_main_proc_sym(ctx);
+        let start = b.inst_count();
         emit_proc(ctx, proc_op, is_main_proc, target_config, &mut b)?;
+        if let Some(max_insts) = target_config.proc_size_budgets.get(&proc_name) {
+            // without the opening and closing lines
+            let insts = b.inst_count() - start - 2;
+            if insts > *max_insts {
+                over_budget.push(format!("{proc_name} ({insts} > {max_insts} instructions)"));
+            }
+        }
     }
+    if !over_budget.is_empty() {
+        return Err(MidenError::SizeBudget(over_budget.join(", ")));
+    }
+    Ok(b.build())
+}
+
+/// Whether the program calls the `std::math::u64` procedures of the Miden stdlib
+fn uses_stdlib_u64(ctx: &Context, prog_op: &ProgramOp) -> bool {
+    let mut found = false;
+    prog_op
+        .get_operation()
+        .walk_only::<U64UncheckedDivOp>(ctx, WalkOrder::PostOrder, &mut |_| {
+            found = true;
+            WalkResult::Advance
+        });
+    prog_op
+        .get_operation()
+        .walk_only::<U64UncheckedModOp>(ctx, WalkOrder::PostOrder, &mut |_| {
+            found = true;
+            WalkResult::Advance
+        });
+    prog_op
+        .get_operation()
+        .walk_only::<U64UncheckedShlOp>(ctx, WalkOrder::PostOrder, &mut |_| {
+            found = true;
+            WalkResult::Advance
+        });
+    prog_op
+        .get_operation()
+        .walk_only::<U64UncheckedShrOp>(ctx, WalkOrder::PostOrder, &mut |_| {
+            found = true;
+            WalkResult::Advance
+        });
+    prog_op
+        .get_operation()
+        .walk_only::<U64UncheckedRotlOp>(ctx, WalkOrder::PostOrder, &mut |_| {
+            found = true;
+            WalkResult::Advance
+        });
+    prog_op
+        .get_operation()
+        .walk_only::<U64UncheckedRotrOp>(ctx, WalkOrder::PostOrder, &mut |_| {
+            found = true;
+            WalkResult::Advance
+        });
+    found
+}
+
+/// Parse the Wasm binary and run the Miden passes on it, returning the Miden program.
+pub fn compile_module(
+    ctx: &mut Context,
+    source: &[u8],
+    frontend_config: &WasmFrontendConfig,
+    target_config: &MidenTargetConfig,
+) -> Result<ProgramOp, MidenError> {
+    target_config.register(ctx);
+    let wasm_module_op = ozk_frontend_wasm::translate(ctx, source, frontend_config)?;
+    let op = run_passes_on_module(ctx, wasm_module_op, &target_config.pass_manager)
+        .map_err(|e| MidenError::Conversion(e.to_string()))?;
+    let Ok(prog_op) = op.deref(ctx).get_op(ctx).downcast::<ProgramOp>() else {
+        return Err(MidenError::Conversion(format!(
+            "expected miden.program, got {}",
+            op.deref(ctx).with_ctx(ctx)
+        )));
+    };
+    Ok(*prog_op)
+}
+
+/// Compile the Wasm binary with the default configs and return the Miden assembly source.
+pub fn compile_wasm(source: &[u8]) -> Result<String, MidenError> {
+    let mut ctx = Context::default();
+    let target_config = MidenTargetConfig::default();
+    let prog_op = compile_module(
+        &mut ctx,
+        source,
+        &WasmFrontendConfig::default(),
+        &target_config,
+    )?;
+    Ok(emit_prog(&ctx, &prog_op, &target_config)?.pretty_print())
+}
+
+/// Emit the program wrapped in a [CompilationArtifact] with the target metadata
+pub fn emit_artifact(
+    ctx: &Context,
+    prog_op: &ProgramOp,
+    target_config: &MidenTargetConfig,
+) -> Result<CompilationArtifact, MidenError> {
+    let inst_buf = emit_prog(ctx, prog_op, target_config)?;
+    let mut artifact =
+        CompilationArtifact::new(Target::Miden, Program::Source(inst_buf.pretty_print()));
+    artifact.memory_layout = target_config.memory_layout.regions();
+    artifact.runtime_helpers = runtime_helpers(ctx, prog_op, target_config);
+    Ok(artifact)
+}
+
+/// The runtime helpers among the program's procedures with their reserved memory addresses
+fn runtime_helpers(
+    ctx: &Context,
+    prog_op: &ProgramOp,
+    target_config: &MidenTargetConfig,
+) -> Vec<RuntimeHelper> {
+    let reserved_slots = target_config.memory_layout.reserved_slots();
+    let mut helpers = Vec::new();
+    for op in prog_op.get_body(ctx, 0).deref(ctx).iter(ctx) {
+        let Some(proc_op) = op.deref(ctx).get_op(ctx).downcast_ref::<ProcOp>().cloned() else {
+            continue;
+        };
+        let Some(helper) = find_runtime_helper(&proc_op.get_symbol_name(ctx)) else {
+            continue;
+        };
+        let reserved_memory = helper
+            .reserved_slots
+            .iter()
+            .filter_map(|slot| {
+                reserved_slots
+                    .get(*slot)
+                    .map(|address| (slot.to_string(), i64::from(u32::from(*address))))
+            })
+            .collect();
+        helpers.push(RuntimeHelper {
+            name: helper.name.to_string(),
+            reserved_memory,
+        });
+    }
+    helpers
+}
+
+/// Compile a single Wasm function and return the instructions of its procedure.
+/// Lets the lowering tests target a particular construct without crafting a whole module
+/// (see [run_passes_on_func] for the requirements on the function).
+pub fn compile_function(
+    ctx: &mut Context,
+    func_op: wasm::FuncOp,
+    target_config: &MidenTargetConfig,
+) -> Result<InstBuffer, MidenError> {
+    let func_sym = func_op.get_symbol_name(ctx);
+    let op = run_passes_on_func(ctx, func_op, &target_config.pass_manager)
+        .map_err(|e| MidenError::Conversion(e.to_string()))?;
+    let Ok(prog_op) = op.deref(ctx).get_op(ctx).downcast::<ProgramOp>() else {
+        return Err(MidenError::Conversion(format!(
+            "expected miden.program, got {}",
+            op.deref(ctx).with_ctx(ctx)
+        )));
+    };
+    let proc_op = prog_op
+        .get_body(ctx, 0)
+        .deref(ctx)
+        .iter(ctx)
+        .find_map(|op| {
+            op.deref(ctx)
+                .get_op(ctx)
+                .downcast_ref::<ProcOp>()
+                .filter(|proc_op| proc_op.get_symbol_name(ctx) == func_sym)
+                .cloned()
+        })
+        .ok_or_else(|| MidenError::Conversion(format!("procedure {func_sym} not found")))?;
+    let mut b = MidenAssemblyBuilder::new(InstBuffer::new(target_config));
+    emit_proc(ctx, &proc_op, false, target_config, &mut b)?;
     Ok(b.build())
 }
 
@@ -1,5 +1,8 @@
+use ozk_miden_dialect::ops::IfOp;
+use ozk_miden_dialect::ops::WhileOp;
 use pliron::context::Context;
 use pliron::context::Ptr;
+use pliron::linked_list::ContainsLinkedList;
 use pliron::op::op_cast;
 use pliron::operation::Operation;
 use pliron::with_context::AttachContext;
@@ -21,9 +24,19 @@ pub fn emit_op(
     config: &MidenTargetConfig,
     b: &mut MidenAssemblyBuilder,
 ) -> Result<(), EmitError> {
+    if let Some(if_op) = op.deref(ctx).get_op(ctx).downcast_ref::<IfOp>() {
+        return emit_if(ctx, if_op, config, b);
+    }
+    if let Some(while_op) = op.deref(ctx).get_op(ctx).downcast_ref::<WhileOp>() {
+        return emit_while(ctx, while_op, config, b);
+    }
     #[allow(clippy::panic)] // all ops should be emitable
     if let Some(emitable_op) = op_cast::<dyn EmitMasm>(op.deref(ctx).get_op(ctx).as_ref()) {
+        let first_inst_idx = b.inst_count();
         emitable_op.emit_masm(ctx, b);
+        if b.inst_count() > first_inst_idx {
+            b.comment(first_inst_idx, op.with_ctx(ctx).to_string());
+        }
     } else {
         panic!(
             "missing EmitMasm impl for op: {}",
@@ -33,6 +46,43 @@ pub fn emit_op(
     Ok(())
 }
 
+/// Emit `if.true .. else .. end` with the nested ops, `else` is omitted for the empty else block
+fn emit_if(
+    ctx: &Context,
+    if_op: &IfOp,
+    config: &MidenTargetConfig,
+    b: &mut MidenAssemblyBuilder,
+) -> Result<(), EmitError> {
+    b.if_true();
+    for op in if_op.get_then_block(ctx).deref(ctx).iter(ctx) {
+        emit_op(ctx, op, config, b)?;
+    }
+    let else_block = if_op.get_else_block(ctx);
+    if else_block.deref(ctx).iter(ctx).next().is_some() {
+        b.if_else();
+        for op in else_block.deref(ctx).iter(ctx) {
+            emit_op(ctx, op, config, b)?;
+        }
+    }
+    b.end();
+    Ok(())
+}
+
+/// Emit `while.true .. end` with the nested ops
+fn emit_while(
+    ctx: &Context,
+    while_op: &WhileOp,
+    config: &MidenTargetConfig,
+    b: &mut MidenAssemblyBuilder,
+) -> Result<(), EmitError> {
+    b.while_true();
+    for op in while_op.get_body_block(ctx).deref(ctx).iter(ctx) {
+        emit_op(ctx, op, config, b)?;
+    }
+    b.end();
+    Ok(())
+}
+
 /*
 #[allow(unused_variables)]
 pub fn emit_inst(
@@ -1,8 +1,51 @@
 use intertrait::cast_to;
 use ozk_miden_dialect::ops::AddOp;
+use ozk_miden_dialect::ops::AssertOp;
+use ozk_miden_dialect::ops::CDropOp;
+use ozk_miden_dialect::ops::ClkOp;
 use ozk_miden_dialect::ops::ConstantOp;
+use ozk_miden_dialect::ops::DropOp;
+use ozk_miden_dialect::ops::DupOp;
+use ozk_miden_dialect::ops::EqOp;
 use ozk_miden_dialect::ops::ExecOp;
+use ozk_miden_dialect::ops::GtOp;
+use ozk_miden_dialect::ops::GteOp;
 use ozk_miden_dialect::ops::LocLoadOp;
+use ozk_miden_dialect::ops::LtOp;
+use ozk_miden_dialect::ops::LteOp;
+use ozk_miden_dialect::ops::MemLoadOp;
+use ozk_miden_dialect::ops::MemStoreOp;
+use ozk_miden_dialect::ops::MulOp;
+use ozk_miden_dialect::ops::NeqOp;
+use ozk_miden_dialect::ops::SubOp;
+use ozk_miden_dialect::ops::SwapOp;
+use ozk_miden_dialect::ops::U32Assert2Op;
+use ozk_miden_dialect::ops::U32CheckedAddOp;
+use ozk_miden_dialect::ops::U32CheckedAndOp;
+use ozk_miden_dialect::ops::U32CheckedDivOp;
+use ozk_miden_dialect::ops::U32CheckedEqOp;
+use ozk_miden_dialect::ops::U32CheckedGtOp;
+use ozk_miden_dialect::ops::U32CheckedGteOp;
+use ozk_miden_dialect::ops::U32CheckedLtOp;
+use ozk_miden_dialect::ops::U32CheckedLteOp;
+use ozk_miden_dialect::ops::U32CheckedModOp;
+use ozk_miden_dialect::ops::U32CheckedNeqOp;
+use ozk_miden_dialect::ops::U32CheckedOrOp;
+use ozk_miden_dialect::ops::U32CheckedRotlOp;
+use ozk_miden_dialect::ops::U32CheckedRotrOp;
+use ozk_miden_dialect::ops::U32CheckedShlOp;
+use ozk_miden_dialect::ops::U32CheckedShrOp;
+use ozk_miden_dialect::ops::U32CheckedSubOp;
+use ozk_miden_dialect::ops::U32CheckedXorOp;
+use ozk_miden_dialect::ops::U32SplitOp;
+use ozk_miden_dialect::ops::U32WrappingMulOp;
+use ozk_miden_dialect::ops::U32WrappingSubOp;
+use ozk_miden_dialect::ops::U64UncheckedDivOp;
+use ozk_miden_dialect::ops::U64UncheckedModOp;
+use ozk_miden_dialect::ops::U64UncheckedRotlOp;
+use ozk_miden_dialect::ops::U64UncheckedRotrOp;
+use ozk_miden_dialect::ops::U64UncheckedShlOp;
+use ozk_miden_dialect::ops::U64UncheckedShrOp;
 use pliron::context::Context;
 use pliron::op::Op;
 
@@ -35,6 +78,49 @@ macro_rules! emit_masm_param {
 }
 
 emit_masm!(AddOp, add);
+emit_masm!(ClkOp, clk);
+emit_masm!(DropOp, drop);
+emit_masm!(U32CheckedAddOp, u32checked_add);
+emit_masm!(U32CheckedSubOp, u32checked_sub);
+emit_masm!(U32WrappingSubOp, u32wrapping_sub);
+emit_masm!(U32WrappingMulOp, u32wrapping_mul);
+emit_masm!(U32CheckedDivOp, u32checked_div);
+emit_masm!(U32CheckedModOp, u32checked_mod);
+emit_masm!(U32CheckedAndOp, u32checked_and);
+emit_masm!(U32CheckedOrOp, u32checked_or);
+emit_masm!(U32CheckedXorOp, u32checked_xor);
+emit_masm!(U32CheckedShlOp, u32checked_shl);
+emit_masm!(U32CheckedShrOp, u32checked_shr);
+emit_masm!(U32CheckedRotlOp, u32checked_rotl);
+emit_masm!(U32CheckedRotrOp, u32checked_rotr);
+emit_masm!(U32CheckedEqOp, u32checked_eq);
+emit_masm!(U32CheckedNeqOp, u32checked_neq);
+emit_masm!(U32CheckedLtOp, u32checked_lt);
+emit_masm!(U32CheckedLteOp, u32checked_lte);
+emit_masm!(U32CheckedGtOp, u32checked_gt);
+emit_masm!(U32CheckedGteOp, u32checked_gte);
+emit_masm!(EqOp, eq);
+emit_masm!(NeqOp, neq);
+emit_masm!(LtOp, lt);
+emit_masm!(LteOp, lte);
+emit_masm!(GtOp, gt);
+emit_masm!(GteOp, gte);
+emit_masm!(CDropOp, cdrop);
+emit_masm!(SubOp, sub);
+emit_masm!(MulOp, mul);
+emit_masm!(AssertOp, assert);
+emit_masm!(U32Assert2Op, u32assert2);
+emit_masm!(U32SplitOp, u32split);
+emit_masm!(U64UncheckedDivOp, u64unchecked_div);
+emit_masm!(U64UncheckedModOp, u64unchecked_mod);
+emit_masm!(U64UncheckedShlOp, u64unchecked_shl);
+emit_masm!(U64UncheckedShrOp, u64unchecked_shr);
+emit_masm!(U64UncheckedRotlOp, u64unchecked_rotl);
+emit_masm!(U64UncheckedRotrOp, u64unchecked_rotr);
+emit_masm!(MemLoadOp, mem_load);
+emit_masm!(MemStoreOp, mem_store);
 emit_masm_param!(ConstantOp, push, get_value);
 emit_masm_param!(ExecOp, exec, get_callee_sym);
 emit_masm_param!(LocLoadOp, loc_load, get_index_as_u32);
+emit_masm_param!(DupOp, dup, get_index_as_u8);
+emit_masm_param!(SwapOp, swap, get_index_as_u8);
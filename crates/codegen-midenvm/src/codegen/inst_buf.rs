@@ -16,22 +16,131 @@ impl InstBuffer {
         }
     }
 
+    /// Format the instructions with the default [MasmFormat]
     pub fn pretty_print(&self) -> String {
-        self.inner
-            .iter()
-            .map(|inst| {
-                let str = String::from(inst.clone());
-                if str != "end" {
-                    str
-                } else {
-                    format!("{str}\n")
-                }
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
+        self.pretty_print_with(&MasmFormat::default())
+    }
+
+    /// Format the instructions with the given [MasmFormat]
+    pub fn pretty_print_with(&self, format: &MasmFormat) -> String {
+        format_masm(
+            self.inner.iter().map(|inst| (inst.inst(), inst.comment())),
+            format,
+        )
+    }
+
+    /// Number of the emitted instructions (with the procedure and block delimiters)
+    pub fn inst_count(&self) -> usize {
+        self.inner.len()
     }
 
     pub(crate) fn push(&mut self, inst: MidenInst) {
         self.inner.push(inst);
     }
+
+    pub(crate) fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub(crate) fn set_comment(&mut self, inst_idx: usize, comment: String) {
+        if let Some(inst) = self.inner.get_mut(inst_idx) {
+            *inst = MidenInst::with_comment(inst.inst().to_string(), comment);
+        }
+    }
+}
+
+/// Formatting options of the emitted Miden assembly source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MasmFormat {
+    /// Number of spaces per nesting level (procedure bodies, `while`, `if` and `repeat` blocks).
+    /// 0 gives a flat instruction list.
+    pub indent: usize,
+    /// Put a blank line after each procedure and the program body
+    pub blank_line_between_procs: bool,
+    /// Emit the per-instruction comments (the IR op an instruction was emitted for)
+    /// on a separate line before the instruction
+    pub comments: bool,
+}
+
+impl Default for MasmFormat {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            blank_line_between_procs: true,
+            comments: false,
+        }
+    }
+}
+
+impl MasmFormat {
+    /// Flat instruction list without the blank lines and comments
+    pub fn flat() -> Self {
+        Self {
+            indent: 0,
+            blank_line_between_procs: false,
+            comments: false,
+        }
+    }
+}
+
+/// Format the Miden assembly instructions with their optional comments
+pub fn format_masm<'a>(
+    insts: impl Iterator<Item = (&'a str, Option<&'a str>)>,
+    format: &MasmFormat,
+) -> String {
+    let mut lines = Vec::new();
+    let mut depth: usize = 0;
+    for (inst, comment) in insts {
+        let inst = inst.trim();
+        if inst == "end" {
+            depth = depth.saturating_sub(1);
+        }
+        let level = if inst == "else" {
+            depth.saturating_sub(1)
+        } else {
+            depth
+        };
+        let indent = " ".repeat(level * format.indent);
+        if format.comments {
+            if let Some(comment) = comment {
+                lines.push(format!("{indent}# {comment}"));
+            }
+        }
+        lines.push(format!("{indent}{inst}"));
+        if opens_block(inst) {
+            depth += 1;
+        }
+        if inst == "end" && depth == 0 && format.blank_line_between_procs {
+            lines.push(String::new());
+        }
+    }
+    lines.join("\n")
+}
+
+/// Re-format the Miden assembly source (e.g. the [CompilationArtifact](ozk_artifact::CompilationArtifact)
+/// program), the comment lines are attached to the instruction that follows them.
+pub fn format_masm_source(source: &str, format: &MasmFormat) -> String {
+    let mut insts = Vec::new();
+    let mut comment: Option<&str> = None;
+    for line in source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        if let Some(line_comment) = line.strip_prefix('#') {
+            comment = Some(line_comment.trim());
+        } else {
+            insts.push((line, comment.take()));
+        }
+    }
+    format_masm(insts.into_iter(), format)
+}
+
+fn opens_block(inst: &str) -> bool {
+    inst == "begin"
+        || inst.starts_with("proc.")
+        || inst.starts_with("export.")
+        || inst.starts_with("while.")
+        || inst.starts_with("if.")
+        || inst.starts_with("repeat.")
 }
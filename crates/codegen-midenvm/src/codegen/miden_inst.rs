@@ -1,12 +1,46 @@
-use derive_more::From;
-use derive_more::Into;
 use winter_math::fields::f64::BaseElement;
 use winter_math::StarkField;
 
 use crate::InstBuffer;
 
-#[derive(Debug, Clone, Into, From)]
-pub struct MidenInst(String);
+#[derive(Debug, Clone)]
+pub struct MidenInst {
+    inst: String,
+    /// Optional comment, emitted only if enabled in [MasmFormat](crate::MasmFormat)
+    comment: Option<String>,
+}
+
+impl MidenInst {
+    pub fn inst(&self) -> &str {
+        &self.inst
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    pub(crate) fn with_comment(inst: String, comment: String) -> Self {
+        Self {
+            inst,
+            comment: Some(comment),
+        }
+    }
+}
+
+impl From<String> for MidenInst {
+    fn from(inst: String) -> Self {
+        Self {
+            inst,
+            comment: None,
+        }
+    }
+}
+
+impl From<MidenInst> for String {
+    fn from(inst: MidenInst) -> Self {
+        inst.inst
+    }
+}
 
 pub struct MidenAssemblyBuilder {
     sink: InstBuffer,
@@ -21,6 +55,16 @@ impl MidenAssemblyBuilder {
         self.sink
     }
 
+    /// Number of the emitted instructions
+    pub fn inst_count(&self) -> usize {
+        self.sink.len()
+    }
+
+    /// Attach a comment to the instruction with the given index (see [MidenAssemblyBuilder::inst_count])
+    pub fn comment(&mut self, inst_idx: usize, comment: String) {
+        self.sink.set_comment(inst_idx, comment);
+    }
+
     pub fn begin(&mut self) {
         self.sink.push("begin".to_string().into());
     }
@@ -98,10 +142,157 @@ impl MidenAssemblyBuilder {
         self.sink.push("neq".to_string().into());
     }
 
+    pub(crate) fn eq(&mut self) {
+        self.sink.push("eq".to_string().into());
+    }
+
+    pub(crate) fn lt(&mut self) {
+        self.sink.push("lt".to_string().into());
+    }
+
+    pub(crate) fn lte(&mut self) {
+        self.sink.push("lte".to_string().into());
+    }
+
+    pub(crate) fn gt(&mut self) {
+        self.sink.push("gt".to_string().into());
+    }
+
+    pub(crate) fn gte(&mut self) {
+        self.sink.push("gte".to_string().into());
+    }
+
+    pub(crate) fn assert(&mut self) {
+        self.sink.push("assert".to_string().into());
+    }
+
+    pub(crate) fn u32assert2(&mut self) {
+        self.sink.push("u32assert.2".to_string().into());
+    }
+
+    pub(crate) fn cdrop(&mut self) {
+        self.sink.push("cdrop".to_string().into());
+    }
+
+    pub(crate) fn clk(&mut self) {
+        self.sink.push("clk".to_string().into());
+    }
+
     pub(crate) fn drop(&mut self) {
         self.sink.push("drop".to_string().into());
     }
 
+    pub(crate) fn u32checked_add(&mut self) {
+        self.sink.push("u32checked_add".to_string().into());
+    }
+
+    pub(crate) fn u32checked_sub(&mut self) {
+        self.sink.push("u32checked_sub".to_string().into());
+    }
+
+    pub(crate) fn u32wrapping_sub(&mut self) {
+        self.sink.push("u32wrapping_sub".to_string().into());
+    }
+
+    pub(crate) fn u32wrapping_mul(&mut self) {
+        self.sink.push("u32wrapping_mul".to_string().into());
+    }
+
+    pub(crate) fn u32split(&mut self) {
+        self.sink.push("u32split".to_string().into());
+    }
+
+    pub(crate) fn u64unchecked_div(&mut self) {
+        self.sink.push("exec.u64::unchecked_div".to_string().into());
+    }
+
+    pub(crate) fn u64unchecked_mod(&mut self) {
+        self.sink.push("exec.u64::unchecked_mod".to_string().into());
+    }
+
+    pub(crate) fn u64unchecked_shl(&mut self) {
+        self.sink.push("exec.u64::unchecked_shl".to_string().into());
+    }
+
+    pub(crate) fn u64unchecked_shr(&mut self) {
+        self.sink.push("exec.u64::unchecked_shr".to_string().into());
+    }
+
+    pub(crate) fn u64unchecked_rotl(&mut self) {
+        self.sink
+            .push("exec.u64::unchecked_rotl".to_string().into());
+    }
+
+    pub(crate) fn u64unchecked_rotr(&mut self) {
+        self.sink
+            .push("exec.u64::unchecked_rotr".to_string().into());
+    }
+
+    /// Import a library module, e.g. `std::math::u64`
+    pub(crate) fn use_module(&mut self, path: &str) {
+        self.sink.push(format!("use.{path}").into());
+    }
+
+    pub(crate) fn u32checked_div(&mut self) {
+        self.sink.push("u32checked_div".to_string().into());
+    }
+
+    pub(crate) fn u32checked_mod(&mut self) {
+        self.sink.push("u32checked_mod".to_string().into());
+    }
+
+    pub(crate) fn u32checked_and(&mut self) {
+        self.sink.push("u32checked_and".to_string().into());
+    }
+
+    pub(crate) fn u32checked_or(&mut self) {
+        self.sink.push("u32checked_or".to_string().into());
+    }
+
+    pub(crate) fn u32checked_xor(&mut self) {
+        self.sink.push("u32checked_xor".to_string().into());
+    }
+
+    pub(crate) fn u32checked_shl(&mut self) {
+        self.sink.push("u32checked_shl".to_string().into());
+    }
+
+    pub(crate) fn u32checked_shr(&mut self) {
+        self.sink.push("u32checked_shr".to_string().into());
+    }
+
+    pub(crate) fn u32checked_rotl(&mut self) {
+        self.sink.push("u32checked_rotl".to_string().into());
+    }
+
+    pub(crate) fn u32checked_rotr(&mut self) {
+        self.sink.push("u32checked_rotr".to_string().into());
+    }
+
+    pub(crate) fn u32checked_eq(&mut self) {
+        self.sink.push("u32checked_eq".to_string().into());
+    }
+
+    pub(crate) fn u32checked_neq(&mut self) {
+        self.sink.push("u32checked_neq".to_string().into());
+    }
+
+    pub(crate) fn u32checked_lt(&mut self) {
+        self.sink.push("u32checked_lt".to_string().into());
+    }
+
+    pub(crate) fn u32checked_lte(&mut self) {
+        self.sink.push("u32checked_lte".to_string().into());
+    }
+
+    pub(crate) fn u32checked_gt(&mut self) {
+        self.sink.push("u32checked_gt".to_string().into());
+    }
+
+    pub(crate) fn u32checked_gte(&mut self) {
+        self.sink.push("u32checked_gte".to_string().into());
+    }
+
     pub(crate) fn if_true(&mut self) {
         self.sink.push("if.true".to_string().into());
     }
@@ -1,34 +1,96 @@
 #![allow(unused_imports)]
 
+use std::collections::BTreeMap;
+
+use ozk_ir_transform::ir_diff;
 use ozk_ir_transform::miden::lowering::call_op_lowering::WasmToMidenCallOpLoweringPass;
+use ozk_ir_transform::miden::lowering::MidenControlFlow;
 use ozk_ir_transform::miden::lowering::WasmToMidenArithLoweringPass;
 use ozk_ir_transform::miden::lowering::WasmToMidenCFLoweringPass;
 use ozk_ir_transform::miden::lowering::WasmToMidenFinalLoweringPass;
+use ozk_ir_transform::miden::lowering::WasmToMidenMemLoweringPass;
+use ozk_ir_transform::u64_emulation::U64Emulation;
+use ozk_ir_transform::wasm::br_if_fusion::WasmBrIfFusionPass;
+use ozk_ir_transform::wasm::br_table::WasmBrTableToBrIfPass;
+use ozk_ir_transform::wasm::call_depth::WasmCallDepthLimitPass;
+use ozk_ir_transform::wasm::call_indirect::WasmCallIndirectToCallPass;
+use ozk_ir_transform::wasm::canonicalize::WasmCanonicalizePass;
+use ozk_ir_transform::wasm::checked_arith::WasmCheckedArithPass;
+use ozk_ir_transform::wasm::const_func_call::WasmConstFuncCallToConstPass;
+use ozk_ir_transform::wasm::data_init::WasmDataInitPass;
 use ozk_ir_transform::wasm::explicit_func_args_pass::WasmExplicitFuncArgsPass;
+use ozk_ir_transform::wasm::foreign_imports::WasmForeignImportsCheckPass;
+use ozk_ir_transform::wasm::globals_init::WasmGlobalsInitPass;
 use ozk_ir_transform::wasm::globals_to_mem::WasmGlobalsToMemPass;
+use ozk_ir_transform::wasm::import_globals::ImportGlobalBinding;
+use ozk_ir_transform::wasm::import_globals::WasmImportGlobalsPass;
+use ozk_ir_transform::wasm::intrinsics::WasmIntrinsicsToOzkPass;
+use ozk_ir_transform::wasm::link_check::WasmLinkCheckPass;
+use ozk_ir_transform::wasm::link_runtime::WasmLinkRuntimePass;
+use ozk_ir_transform::wasm::mem_check::WasmMemoryCheckPass;
+use ozk_ir_transform::wasm::memory_size::WasmMemorySizeToSlotPass;
+use ozk_ir_transform::wasm::multi_memory::WasmMultiMemoryPass;
+use ozk_ir_transform::wasm::no_io::WasmNoIoPass;
+use ozk_ir_transform::wasm::partial_loads::WasmPartialLoadsPass;
+use ozk_ir_transform::wasm::passive_data::WasmPassiveDataPass;
+use ozk_ir_transform::wasm::prologue::WasmEmitProloguePass;
+use ozk_ir_transform::wasm::rename_symbols::WasmRenameSymbolsPass;
+use ozk_ir_transform::wasm::reserved_slots::WasmResolveReservedSlotsPass;
+use ozk_ir_transform::wasm::return_call::WasmReturnCallToCallPass;
+use ozk_ir_transform::wasm::stack_check::StackBounds;
+use ozk_ir_transform::wasm::stack_check::WasmStackCheckPass;
+use ozk_ir_transform::wasm::tables_to_mem::WasmTablesToMemPass;
 use pliron::context::Context;
+use pliron::pass::Pass;
 use pliron::pass::PassManager;
 
 use crate::MidenMemoryLayout;
+use crate::MIDEN_RUNTIME_WAT;
+
+/// Miden assembly keywords that cannot be used as procedure names
+pub const MIDEN_RESERVED_SYMBOLS: &[&str] = &[
+    "begin", "end", "proc", "export", "use", "exec", "call", "syscall", "if", "else", "while",
+    "repeat", "const",
+];
 
 pub struct MidenTargetConfig {
     pub output_format: MidenOutputFormat,
     pub pass_manager: PassManager,
     pub memory_layout: MidenMemoryLayout,
+    /// Maximum number of the emitted instructions of the procedures (by name), exceeding it
+    /// fails the emission (see [MidenError::SizeBudget](crate::MidenError::SizeBudget))
+    pub proc_size_budgets: BTreeMap<String, usize>,
+    options: MidenTargetOptions,
+}
+
+/// Options of the Miden pass pipeline, set with the `with_*` builder methods of
+/// [MidenTargetConfig]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MidenTargetOptions {
+    pub u64_emulation: U64Emulation,
+    pub control_flow: MidenControlFlow,
+    /// Bounds-check every load and store (see [WasmMemoryCheckPass])
+    pub memory_check: bool,
+    /// Check the shadow stack pointer against the bounds (see [WasmStackCheckPass])
+    pub stack_check: Option<StackBounds>,
+    /// Commit a digest as the only public output (see [WasmNoIoPass])
+    pub no_io: bool,
+    /// Replace the calls to the constant functions with the constants
+    /// (see [WasmConstFuncCallToConstPass])
+    pub const_func_calls: bool,
+    /// Fail the compilation if the worst-case call depth exceeds it
+    /// (see [WasmCallDepthLimitPass])
+    pub max_call_depth: Option<u32>,
+    /// Host values of the imported globals by their module and name
+    /// (see [WasmImportGlobalsPass])
+    pub import_globals: BTreeMap<(String, String), ImportGlobalBinding>,
 }
 
 impl Default for MidenTargetConfig {
     fn default() -> Self {
         let memory_layout = MidenMemoryLayout::default();
-        let mut pass_manager = PassManager::new();
-        pass_manager.add_pass(Box::<WasmExplicitFuncArgsPass>::default());
-        pass_manager.add_pass(Box::<WasmToMidenCallOpLoweringPass>::default());
-        pass_manager.add_pass(Box::<WasmToMidenCFLoweringPass>::default());
-        pass_manager.add_pass(Box::new(WasmGlobalsToMemPass::new(
-            memory_layout.globals_start_address,
-        )));
-        pass_manager.add_pass(Box::<WasmToMidenArithLoweringPass>::default());
-        // pass_manager.add_pass(Box::<WasmToMidenFinalLoweringPass>::default());
+        let options = MidenTargetOptions::default();
+        let pass_manager = new_pass_manager(&options, &memory_layout);
         Self {
             output_format: MidenOutputFormat::Source,
             // ir_passes: vec![
@@ -42,17 +104,177 @@ impl Default for MidenTargetConfig {
             // ],
             memory_layout,
             pass_manager,
+            proc_size_budgets: BTreeMap::new(),
+            options,
         }
     }
 }
 
 impl MidenTargetConfig {
+    /// The options the pass pipeline is built from
+    pub fn options(&self) -> &MidenTargetOptions {
+        &self.options
+    }
+
+    /// Use the given i64 emulation strategy
+    pub fn with_u64_emulation(self, u64_emulation: U64Emulation) -> Self {
+        self.with_options(|options| options.u64_emulation = u64_emulation)
+    }
+
+    /// Bounds-check every load and store (see [WasmMemoryCheckPass]).
+    /// For debugging, an out-of-bounds access aborts the execution instead of silently
+    /// producing a wrong output.
+    pub fn with_memory_check(self) -> Self {
+        self.with_options(|options| options.memory_check = true)
+    }
+
+    /// Check the shadow stack pointer against the given bounds (see [WasmStackCheckPass]).
+    /// For debugging, a stack overflow aborts the execution instead of silently corrupting
+    /// the memory below the stack.
+    pub fn with_stack_check(self, stack_bounds: StackBounds) -> Self {
+        self.with_options(|options| options.stack_check = Some(stack_bounds))
+    }
+
+    /// Build the no-io programs (see [WasmNoIoPass]). The only public output is the digest of
+    /// the bytes committed with `ozk_stdlib::commit()`, the program may not write the public
+    /// outputs itself.
+    pub fn with_no_io(self) -> Self {
+        self.with_options(|options| options.no_io = true)
+    }
+
+    /// Replace the calls to the functions that always return the same constant (e.g. the
+    /// configuration getters) with the constant and remove such functions
+    /// (see [WasmConstFuncCallToConstPass])
+    pub fn with_const_func_calls(self) -> Self {
+        self.with_options(|options| options.const_func_calls = true)
+    }
+
+    /// Fail the compilation if the worst-case call depth of the program exceeds `max_depth`
+    /// (see [WasmCallDepthLimitPass]). In the [MidenControlFlow::Outline] mode each nested block
+    /// and loop counts as a call.
+    pub fn with_max_call_depth(self, max_depth: u32) -> Self {
+        self.with_options(|options| options.max_call_depth = Some(max_depth))
+    }
+
+    /// Bind the imported globals to the host values by their module and name
+    /// (see [WasmImportGlobalsPass])
+    pub fn with_import_globals(
+        self,
+        import_globals: BTreeMap<(String, String), ImportGlobalBinding>,
+    ) -> Self {
+        self.with_options(|options| options.import_globals = import_globals)
+    }
+
+    /// Use the given lowering of the blocks, loops and branches. The structured one keeps them
+    /// in `if.true`/`while.true` instead of the outlined procs (see [MidenControlFlow])
+    pub fn with_control_flow(self, control_flow: MidenControlFlow) -> Self {
+        self.with_options(|options| options.control_flow = control_flow)
+    }
+
+    /// Change the options and rebuild the pass pipeline from them
+    fn with_options(mut self, f: impl FnOnce(&mut MidenTargetOptions)) -> Self {
+        f(&mut self.options);
+        self.pass_manager = new_pass_manager(&self.options, &self.memory_layout);
+        self
+    }
+
+    /// Limit the number of the emitted instructions of the procedure, e.g. to keep a hot loop
+    /// body small
+    pub fn with_proc_size_budget(mut self, proc_name: &str, max_insts: usize) -> Self {
+        self.proc_size_budgets
+            .insert(proc_name.to_string(), max_insts);
+        self
+    }
+
     pub fn register(&self, ctx: &mut Context) {
         ozk_miden_dialect::register(ctx);
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MidenOutputFormat {
     Binary,
     Source,
 }
+
+fn new_pass_manager(
+    options: &MidenTargetOptions,
+    memory_layout: &MidenMemoryLayout,
+) -> PassManager {
+    let mut passes: Vec<Box<dyn Pass>> = vec![
+        Box::<WasmReturnCallToCallPass>::default(),
+        Box::<WasmCheckedArithPass>::default(),
+        // after the checked arithmetic, it matches the `local.tee` of the rustc output
+        Box::<WasmCanonicalizePass>::default(),
+    ];
+    if options.const_func_calls {
+        passes.push(Box::<WasmConstFuncCallToConstPass>::default());
+    }
+    if options.memory_check {
+        passes.push(Box::<WasmMemoryCheckPass>::default());
+    }
+    if let Some(stack_bounds) = options.stack_check {
+        passes.push(Box::new(WasmStackCheckPass::new(stack_bounds)));
+    }
+    passes.extend([
+        // after the memory check, it covers the memory 0 only
+        Box::new(WasmMultiMemoryPass::new(
+            memory_layout.memory_addresses.clone(),
+        )) as Box<dyn Pass>,
+        // after the memory check: the tables are outside of the Wasm memory and the data
+        // segments are known to be in bounds
+        Box::new(WasmTablesToMemPass::new(
+            memory_layout.tables_address,
+            memory_layout.max_table_bytes,
+        )),
+        Box::<WasmCallIndirectToCallPass>::default(),
+        Box::new(WasmImportGlobalsPass::new(options.import_globals.clone())),
+        Box::<WasmGlobalsInitPass>::default(),
+        Box::<WasmDataInitPass>::default(),
+        Box::new(WasmPassiveDataPass::new(
+            memory_layout.passive_data_address,
+            memory_layout.max_passive_data_bytes,
+        )),
+    ]);
+    if options.no_io {
+        passes.push(Box::new(WasmNoIoPass::new(memory_layout.digest_address)));
+    }
+    passes.extend([
+        Box::new(WasmLinkRuntimePass::new(MIDEN_RUNTIME_WAT)) as Box<dyn Pass>,
+        Box::new(WasmMemorySizeToSlotPass::new(
+            memory_layout.max_memory_pages(),
+        )),
+        Box::new(WasmPartialLoadsPass::new(memory_layout.byte_layout)),
+        Box::<WasmEmitProloguePass>::default(),
+        Box::<WasmForeignImportsCheckPass>::default(),
+        Box::new(WasmRenameSymbolsPass::new(MIDEN_RESERVED_SYMBOLS)),
+        Box::new(WasmIntrinsicsToOzkPass::new("Miden", true, true)),
+        Box::<WasmExplicitFuncArgsPass>::default(),
+        Box::<WasmLinkCheckPass>::default(),
+    ]);
+    if let Some(max_depth) = options.max_call_depth {
+        // after all the functions are linked, before the blocks are lowered
+        let call_depth_limit = match options.control_flow {
+            MidenControlFlow::Outline => WasmCallDepthLimitPass::new(max_depth),
+            MidenControlFlow::Structured => WasmCallDepthLimitPass::new_structured(max_depth),
+        };
+        passes.push(Box::new(call_depth_limit));
+    }
+    passes.extend([
+        Box::<WasmToMidenCallOpLoweringPass>::default() as Box<dyn Pass>,
+        Box::<WasmBrTableToBrIfPass>::default(),
+        // after all the br_if ops are emitted (checked arithmetic, br_table, runtime)
+        Box::<WasmBrIfFusionPass>::default(),
+        Box::new(WasmToMidenCFLoweringPass::new(options.control_flow)),
+        Box::new(WasmResolveReservedSlotsPass::new(
+            memory_layout.reserved_slots(),
+        )),
+        Box::new(WasmGlobalsToMemPass::new(
+            memory_layout.globals_start_address,
+        )),
+        Box::new(WasmToMidenMemLoweringPass::new(memory_layout.byte_layout)),
+        Box::new(WasmToMidenArithLoweringPass::new(options.u64_emulation)),
+        // Box::<WasmToMidenFinalLoweringPass>::default(),
+    ]);
+    ir_diff::new_pass_manager(passes)
+}
@@ -1,3 +1,4 @@
+use ozk_frontend_wasm::WasmError;
 use thiserror::Error;
 
 use crate::EmitError;
@@ -11,4 +12,12 @@ pub enum MidenError {
     Emit(#[from] EmitError),
     #[error("Topological sort error: {0:?}")]
     TopoSortError(#[from] TopoSortError),
+    #[error("Wasm frontend error: {0}")]
+    Frontend(#[from] WasmError),
+    #[error("Conversion error: {0}")]
+    Conversion(String),
+    #[error("Miden VM error: {0}")]
+    Vm(String),
+    #[error("Procedures over their size budget: {0}")]
+    SizeBudget(String),
 }
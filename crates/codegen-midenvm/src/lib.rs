@@ -22,8 +22,13 @@ mod codegen;
 mod config;
 mod error;
 mod memory;
+mod runtime;
+
+#[cfg(feature = "vm")]
+pub mod vm;
 
 pub use crate::codegen::*;
 pub use crate::config::*;
 pub use crate::error::*;
 pub use crate::memory::*;
+pub use crate::runtime::*;
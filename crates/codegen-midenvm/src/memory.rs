@@ -1,4 +1,15 @@
+use std::collections::BTreeMap;
+
+use ozk_ir_transform::byte_layout::ByteLayout;
+use ozk_ir_transform::byte_layout::CELL_BYTES;
+use ozk_ir_transform::wasm::br_propagation::BrPropagationStorage;
+use ozk_ir_transform::wasm::multi_memory::default_memory_addresses;
+use ozk_ir_transform::wasm::reserved_slots::BR_PROPAGATION_SLOT;
+use ozk_ir_transform::wasm::reserved_slots::MEMORY_SIZE_SLOT;
+use ozk_ir_transform::wasm::reserved_slots::SCRATCH_SLOT;
 use ozk_wasm_dialect::types::MemAddress;
+use ozk_wasm_dialect::types::MAX_WASM32_PAGES;
+use ozk_wasm_dialect::types::WASM_PAGE_SIZE;
 
 /// Miden memory layout.
 /// Addresses start from the max and decrease as new values are stored.
@@ -10,6 +21,31 @@ pub struct MidenMemoryLayout {
     pub pub_outputs_start_address: i32,
     /// The address of the first global variable. Global variables are stored in memory according to their index.
     pub globals_start_address: MemAddress,
+    /// The address of the br-propagation depth of the flattened blocks
+    /// (see [BrPropagationStorage::Reserved]). Placed after the space reserved for the globals.
+    pub br_propagation_address: MemAddress,
+    /// The address of the scratch cell used by the lowering passes
+    pub scratch_address: MemAddress,
+    /// The address of the current Wasm memory size in pages (`memory.size`, `memory.grow`)
+    pub memory_size_address: MemAddress,
+    /// The address of the copies of the passive data segments (see
+    /// [ozk_ir_transform::wasm::passive_data]). Placed after the memory size cell.
+    pub passive_data_address: MemAddress,
+    /// The space reserved for the passive data segments in bytes
+    pub max_passive_data_bytes: u32,
+    /// The address of the tables changed at runtime (see
+    /// [ozk_ir_transform::wasm::tables_to_mem]). Placed after the passive data segments.
+    pub tables_address: MemAddress,
+    /// The space reserved for the tables in bytes
+    pub max_table_bytes: u32,
+    /// The address of the digest state of the no-io programs (see
+    /// [ozk_ir_transform::wasm::no_io]). Placed after the tables.
+    pub digest_address: MemAddress,
+    /// Layout of the Wasm memory bytes in the memory cells
+    pub byte_layout: ByteLayout,
+    /// The addresses of the Wasm memories 1, 2, etc. of the multi-memory modules (see
+    /// [ozk_ir_transform::wasm::multi_memory]), the memory 0 starts at 0
+    pub memory_addresses: Vec<MemAddress>,
 }
 
 impl Default for MidenMemoryLayout {
@@ -20,10 +56,109 @@ impl Default for MidenMemoryLayout {
         let i64_size: u32 = 8;
         let outputs_offset: u32 = max_public_inputs * i64_size;
         let globals_offset: u32 = outputs_offset + max_public_outputs * i64_size;
+        let max_globals: u32 = 1024;
+        let br_propagation_offset: u32 = globals_offset + max_globals * i64_size;
+        let scratch_offset: u32 = br_propagation_offset + i64_size;
+        let memory_size_offset: u32 = scratch_offset + i64_size;
+        let max_passive_data_bytes: u32 = 64 * 1024;
+        let passive_data_offset: u32 = memory_size_offset + i64_size + max_passive_data_bytes;
+        let max_table_bytes: u32 = 16 * 1024;
+        let tables_offset: u32 = passive_data_offset + max_table_bytes;
+        let digest_offset: u32 = tables_offset + i64_size;
         Self {
             pub_inputs_start_address: i32::MAX,
             pub_outputs_start_address: i32::MAX - inputs_offset as i32,
             globals_start_address: ((i32::MAX - globals_offset as i32) as u32).into(),
+            br_propagation_address: ((i32::MAX - br_propagation_offset as i32) as u32).into(),
+            scratch_address: ((i32::MAX - scratch_offset as i32) as u32).into(),
+            memory_size_address: ((i32::MAX - memory_size_offset as i32) as u32).into(),
+            // cell-aligned, the segments are copied a cell at a time
+            passive_data_address: ((i32::MAX - passive_data_offset as i32) as u32
+                & !(CELL_BYTES - 1))
+                .into(),
+            max_passive_data_bytes,
+            tables_address: ((i32::MAX - tables_offset as i32) as u32 & !(CELL_BYTES - 1)).into(),
+            max_table_bytes,
+            // i64-aligned, the state is a single i64
+            digest_address: ((i32::MAX - digest_offset as i32) as u32 & !(i64_size - 1)).into(),
+            byte_layout: ByteLayout::default(),
+            memory_addresses: default_memory_addresses(),
+        }
+    }
+}
+
+impl MidenMemoryLayout {
+    /// Start addresses of the memory regions by name
+    pub fn regions(&self) -> BTreeMap<String, i64> {
+        let mut regions = BTreeMap::from([
+            (
+                "pub_inputs".to_string(),
+                i64::from(self.pub_inputs_start_address),
+            ),
+            (
+                "pub_outputs".to_string(),
+                i64::from(self.pub_outputs_start_address),
+            ),
+            (
+                "globals".to_string(),
+                i64::from(u32::from(self.globals_start_address)),
+            ),
+            (
+                "br_propagation".to_string(),
+                i64::from(u32::from(self.br_propagation_address)),
+            ),
+            (
+                "scratch".to_string(),
+                i64::from(u32::from(self.scratch_address)),
+            ),
+            (
+                "memory_size".to_string(),
+                i64::from(u32::from(self.memory_size_address)),
+            ),
+            (
+                "passive_data".to_string(),
+                i64::from(u32::from(self.passive_data_address)),
+            ),
+            (
+                "tables".to_string(),
+                i64::from(u32::from(self.tables_address)),
+            ),
+            (
+                "digest".to_string(),
+                i64::from(u32::from(self.digest_address)),
+            ),
+        ]);
+        for (index, address) in self.memory_addresses.iter().enumerate() {
+            regions.insert(
+                format!("memory_{}", index + 1),
+                i64::from(u32::from(*address)),
+            );
         }
+        regions
+    }
+
+    /// The number of Wasm pages reserved for the memory 0, it ends at the start of the lowest
+    /// region (the next memory or the reserved cells)
+    pub fn max_memory_pages(&self) -> u32 {
+        let end = self
+            .regions()
+            .into_values()
+            .min()
+            .unwrap_or(i64::from(i32::MAX));
+        (end / i64::from(WASM_PAGE_SIZE)).clamp(0, i64::from(MAX_WASM32_PAGES)) as u32
+    }
+
+    /// Addresses of the named reserved slots (see [ozk_ir_transform::wasm::reserved_slots])
+    pub fn reserved_slots(&self) -> BTreeMap<String, MemAddress> {
+        BTreeMap::from([
+            (BR_PROPAGATION_SLOT.to_string(), self.br_propagation_address),
+            (SCRATCH_SLOT.to_string(), self.scratch_address),
+            (MEMORY_SIZE_SLOT.to_string(), self.memory_size_address),
+        ])
+    }
+
+    /// Storage of the br-propagation depth on Miden
+    pub fn br_propagation_storage(&self) -> BrPropagationStorage {
+        BrPropagationStorage::Reserved
     }
 }
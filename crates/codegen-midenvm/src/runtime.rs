@@ -0,0 +1,4 @@
+//! The Miden VM runtime (see `ozk_ir_transform::wasm::link_runtime`)
+
+/// The runtime module in WAT, linked by the `WasmLinkRuntimePass`
+pub const MIDEN_RUNTIME_WAT: &str = include_str!("runtime.wat");
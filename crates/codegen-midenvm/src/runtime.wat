@@ -0,0 +1,174 @@
+;; The Miden VM runtime: the functions the passes call through the imports from the
+;; `ozk_runtime` module, linked into the program by `WasmLinkRuntimePass`.
+(module
+    ;; the program's memory
+    (memory 0)
+    ;; the frontend requires a start function, it's not linked
+    (start $start)
+    (func $start
+        return)
+
+    ;; memory.init: copies $n bytes at $src in the passive data segment at $segment (its length
+    ;; in a cell followed by its bytes) to $dest, traps if either range is out of bounds.
+    ;; The bytes are written with the read-modify-write of their cells, there is no byte store.
+    (func $memory_init (param $dest i32) (param $src i32) (param $n i32) (param $segment i32)
+        (local $i i32) (local $addr i32) (local $shift i32)
+        ;; n > len || src > len - n || n > memory bytes || dest > memory bytes - n
+        local.get $n
+        local.get $segment
+        i32.load
+        i32.gt_u
+        local.get $src
+        local.get $segment
+        i32.load
+        local.get $n
+        i32.sub
+        i32.gt_u
+        i32.or
+        local.get $n
+        memory.size
+        i32.const 16
+        i32.shl
+        i32.gt_u
+        i32.or
+        local.get $dest
+        memory.size
+        i32.const 16
+        i32.shl
+        local.get $n
+        i32.sub
+        i32.gt_u
+        i32.or
+        (if
+            (then
+                unreachable))
+        (block
+            (loop
+                ;; while i < n
+                local.get $i
+                local.get $n
+                i32.ge_u
+                br_if 1
+                ;; addr = dest + i
+                local.get $dest
+                local.get $i
+                i32.add
+                local.set $addr
+                ;; the cell of addr
+                local.get $addr
+                i32.const -4
+                i32.and
+                ;; with the byte at addr cleared
+                local.get $addr
+                i32.const -4
+                i32.and
+                i32.load
+                i32.const 0xff
+                local.get $addr
+                i32.const 3
+                i32.and
+                i32.const 3
+                i32.shl
+                local.tee $shift
+                i32.shl
+                i32.const -1
+                i32.xor
+                i32.and
+                ;; or the segment byte at src + i (after the length) shifted into its place
+                local.get $segment
+                local.get $src
+                i32.add
+                local.get $i
+                i32.add
+                i32.load8_u offset=4
+                local.get $shift
+                i32.shl
+                i32.or
+                i32.store
+                ;; i += 1
+                local.get $i
+                i32.const 1
+                i32.add
+                local.set $i
+                br 0))
+        return)
+
+    ;; table.get: the entry $index of the table of $size entries (a cell each) at $table,
+    ;; traps if out of bounds
+    (func $table_get (param $index i32) (param $table i32) (param $size i32) (result i32)
+        local.get $index
+        local.get $size
+        i32.ge_u
+        (if
+            (then
+                unreachable))
+        local.get $table
+        local.get $index
+        i32.const 2
+        i32.shl
+        i32.add
+        i32.load
+        return)
+
+    ;; table.set: sets the entry $index of the table of $size entries at $table to $value,
+    ;; traps if out of bounds
+    (func $table_set (param $index i32) (param $value i32) (param $table i32) (param $size i32)
+        local.get $index
+        local.get $size
+        i32.ge_u
+        (if
+            (then
+                unreachable))
+        local.get $table
+        local.get $index
+        i32.const 2
+        i32.shl
+        i32.add
+        local.get $value
+        i32.store
+        return)
+
+    ;; The digest of the no-io programs, 64-bit FNV-1a of the committed bytes. The state (8 bytes)
+    ;; is at $state.
+    (func $digest_init (param $state i32)
+        local.get $state
+        i64.const 0xcbf29ce484222325
+        i64.store
+        return)
+
+    ;; absorbs the $n bytes at $addr into the digest state at $state
+    (func $digest_update (param $addr i32) (param $n i32) (param $state i32)
+        (local $i i32)
+        (block
+            (loop
+                ;; while i < n
+                local.get $i
+                local.get $n
+                i32.ge_u
+                br_if 1
+                ;; state = (state ^ byte) * prime
+                local.get $state
+                local.get $state
+                i64.load
+                local.get $addr
+                local.get $i
+                i32.add
+                i64.load8_u
+                i64.xor
+                i64.const 0x100000001b3
+                i64.mul
+                i64.store
+                ;; i += 1
+                local.get $i
+                i32.const 1
+                i32.add
+                local.set $i
+                br 0))
+        return)
+
+    ;; the digest of the bytes absorbed into the state at $state
+    (func $digest_final (param $state i32) (result i64)
+        local.get $state
+        i64.load
+        return)
+)
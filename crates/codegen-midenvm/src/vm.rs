@@ -0,0 +1,102 @@
+//! Execution and proving of the emitted program with the Miden VM.
+
+use miden_assembly::Assembler;
+use miden_processor::AdviceInputs;
+use miden_processor::MemAdviceProvider;
+use miden_processor::Program;
+use miden_processor::StackInputs;
+use miden_prover::ExecutionProof;
+use miden_prover::ProofOptions;
+use miden_stdlib::StdLibrary;
+
+use crate::MidenError;
+
+/// Outputs of the program execution
+#[derive(Debug, Clone)]
+pub struct MidenExecution {
+    /// Operand stack on exit (top first)
+    pub stack: Vec<u64>,
+    /// STARK proof of the execution (if requested)
+    pub proof: Option<ExecutionProof>,
+}
+
+/// Assemble the program (Miden assembly source) linking the Miden stdlib.
+pub fn assemble(source: &str) -> Result<Program, MidenError> {
+    Assembler::default()
+        .with_library(&StdLibrary::default())
+        .map_err(|e| MidenError::Vm(e.to_string()))?
+        .compile(source)
+        .map_err(|e| MidenError::Vm(e.to_string()))
+}
+
+/// Execute the program with public `input` (operand stack) and `secret_input` (advice stack).
+pub fn execute(
+    source: &str,
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+) -> Result<MidenExecution, MidenError> {
+    let program = assemble(source)?;
+    let (stack_inputs, adv_provider) = inputs(input, secret_input)?;
+    let trace = miden_processor::execute(&program, stack_inputs, adv_provider)
+        .map_err(|e| MidenError::Vm(e.to_string()))?;
+    Ok(MidenExecution {
+        stack: trace.stack_outputs().stack().to_vec(),
+        proof: None,
+    })
+}
+
+/// Execute the program and return the number of VM cycles it took.
+pub fn count_cycles(
+    source: &str,
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+) -> Result<u32, MidenError> {
+    let program = assemble(source)?;
+    let (stack_inputs, adv_provider) = inputs(input, secret_input)?;
+    let mut cycles = 0;
+    for vm_state in miden_processor::execute_iter(&program, stack_inputs, adv_provider) {
+        cycles = vm_state.map_err(|e| MidenError::Vm(e.to_string()))?.clk;
+    }
+    Ok(cycles)
+}
+
+/// Execute the program generating a STARK proof of the execution and verify the proof.
+pub fn prove(
+    source: &str,
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+) -> Result<MidenExecution, MidenError> {
+    let program = assemble(source)?;
+    let (stack_inputs, adv_provider) = inputs(input, secret_input)?;
+    let (stack_outputs, proof) = miden_prover::prove(
+        &program,
+        stack_inputs.clone(),
+        adv_provider,
+        ProofOptions::default(),
+    )
+    .map_err(|e| MidenError::Vm(e.to_string()))?;
+    miden_verifier::verify(
+        program.hash(),
+        stack_inputs,
+        stack_outputs.clone(),
+        proof.clone(),
+    )
+    .map_err(|e| MidenError::Vm(e.to_string()))?;
+    Ok(MidenExecution {
+        stack: stack_outputs.stack().to_vec(),
+        proof: Some(proof),
+    })
+}
+
+fn inputs(
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+) -> Result<(StackInputs, MemAdviceProvider), MidenError> {
+    let stack_inputs =
+        StackInputs::try_from_values(input).map_err(|e| MidenError::Vm(e.to_string()))?;
+    let adv_provider: MemAdviceProvider = AdviceInputs::default()
+        .with_stack_values(secret_input)
+        .map_err(|e| MidenError::Vm(e.to_string()))?
+        .into();
+    Ok((stack_inputs, adv_provider))
+}
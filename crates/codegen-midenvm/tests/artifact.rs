@@ -0,0 +1,57 @@
+use ozk_codegen_midenvm::emit_artifact;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use pliron::context::Context;
+use sem_tests::compile_to_miden_dialect;
+
+mod sem_tests;
+
+fn artifact_runtime_helpers(source: &str) -> Vec<ozk_artifact::RuntimeHelper> {
+    let wasm = wat::parse_str(source).unwrap();
+    let mut ctx = Context::default();
+    let target_config = MidenTargetConfig::default();
+    let prog_op = compile_to_miden_dialect(&mut ctx, &wasm, &target_config);
+    emit_artifact(&ctx, &prog_op, &target_config)
+        .unwrap()
+        .runtime_helpers
+}
+
+#[test]
+fn test_no_runtime_helpers() {
+    let helpers = artifact_runtime_helpers(
+        r#"
+(module
+    (start $main)
+    (func $main
+        return)
+)"#,
+    );
+    assert!(helpers.is_empty(), "{helpers:?}");
+}
+
+#[test]
+fn test_runtime_helpers() {
+    let helpers = artifact_runtime_helpers(
+        r#"
+(module
+    (start $main)
+    (func $next_br_propagation (result i32)
+        i32.const 0
+        return)
+    (func $main
+        call $next_br_propagation
+        drop
+        return)
+)"#,
+    );
+    let names: Vec<&str> = helpers.iter().map(|helper| helper.name.as_str()).collect();
+    assert_eq!(names, vec!["next_br_propagation"]);
+    let br_propagation_address = i64::from(u32::from(
+        MidenTargetConfig::default()
+            .memory_layout
+            .br_propagation_address,
+    ));
+    assert_eq!(
+        helpers[0].reserved_memory.get("br_propagation"),
+        Some(&br_propagation_address)
+    );
+}
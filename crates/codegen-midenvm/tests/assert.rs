@@ -0,0 +1,95 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::vm;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use pliron::context::Context;
+use sem_tests::check_miden;
+use sem_tests::compile;
+use sem_tests::execution_error;
+use sem_tests::run_miden;
+
+mod sem_tests;
+
+fn assert_wat(value: i32) -> String {
+    format!(
+        r#"
+(module
+    (import "env" "ozk_stdlib_assert" (func $assert (param i32)))
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $check (result i32)
+        i32.const {value}
+        call $assert
+        i32.const 3
+        return)
+    (func $main
+        call $check
+        return)
+)"#
+    )
+}
+
+#[test]
+fn test_assert_passes() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![3];
+    check_miden(
+        &assert_wat(7),
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.check.0
+                push.7
+                push.0
+                neq
+                assert
+                push.3
+            end
+
+            proc.main.0
+                exec.check
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_assert_fails() {
+    // panics if the execution succeeds
+    execution_error(
+        &assert_wat(0),
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+    );
+}
+
+#[test]
+fn test_assert_bundle_native() {
+    let main_assert = ozk_rust_wasm_tests_helper::wrap_main_with_io(
+        &ozk_rust_wasm_tests_assert::assert::main_assert,
+    );
+    assert_eq!(main_assert(vec![11, 7, 18], vec![]), vec![18]);
+    let mismatch = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        main_assert(vec![11, 7, 19], vec![])
+    }));
+    assert!(mismatch.is_err());
+}
+
+#[ignore]
+#[test]
+fn test_assert_bundle() {
+    let wasm_bytes = ozk_rust_wasm_tests_helper::compile_rust_wasm_tests("assert-bin", "assert");
+    let mut ctx = Context::default();
+    let program = compile(&mut ctx, &wasm_bytes);
+    let stack = run_miden(program.clone(), vec![11, 7, 18], vec![]);
+    assert_eq!(stack[0], 18);
+    assert!(vm::execute(&program, vec![11, 7, 19], vec![]).is_err());
+}
@@ -0,0 +1,188 @@
+use expect_test::expect;
+use sem_tests::check_miden;
+use sem_tests::conversion_error;
+
+mod sem_tests;
+
+#[test]
+fn test_i32_popcnt_ctz_clz() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![31, 3, 8];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $popcnt (result i32)
+        i32.const 61680
+        i32.popcnt
+        return)
+    (func $ctz (result i32)
+        i32.const 40
+        i32.ctz
+        return)
+    (func $clz (result i32)
+        i32.const 1
+        i32.clz
+        return)
+    (func $main
+        call $popcnt
+        call $ctz
+        call $clz
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.popcnt.0
+                push.61680
+                dup.0
+                push.1
+                u32checked_shr
+                push.1431655765
+                u32checked_and
+                u32wrapping_sub
+                dup.0
+                push.858993459
+                u32checked_and
+                swap.1
+                push.2
+                u32checked_shr
+                push.858993459
+                u32checked_and
+                add
+                dup.0
+                push.4
+                u32checked_shr
+                add
+                push.252645135
+                u32checked_and
+                push.16843009
+                u32wrapping_mul
+                push.24
+                u32checked_shr
+            end
+
+            proc.ctz.0
+                push.40
+                dup.0
+                push.1
+                u32wrapping_sub
+                swap.1
+                push.4294967295
+                u32checked_xor
+                u32checked_and
+                dup.0
+                push.1
+                u32checked_shr
+                push.1431655765
+                u32checked_and
+                u32wrapping_sub
+                dup.0
+                push.858993459
+                u32checked_and
+                swap.1
+                push.2
+                u32checked_shr
+                push.858993459
+                u32checked_and
+                add
+                dup.0
+                push.4
+                u32checked_shr
+                add
+                push.252645135
+                u32checked_and
+                push.16843009
+                u32wrapping_mul
+                push.24
+                u32checked_shr
+            end
+
+            proc.clz.0
+                push.1
+                dup.0
+                push.1
+                u32checked_shr
+                u32checked_or
+                dup.0
+                push.2
+                u32checked_shr
+                u32checked_or
+                dup.0
+                push.4
+                u32checked_shr
+                u32checked_or
+                dup.0
+                push.8
+                u32checked_shr
+                u32checked_or
+                dup.0
+                push.16
+                u32checked_shr
+                u32checked_or
+                push.4294967295
+                u32checked_xor
+                dup.0
+                push.1
+                u32checked_shr
+                push.1431655765
+                u32checked_and
+                u32wrapping_sub
+                dup.0
+                push.858993459
+                u32checked_and
+                swap.1
+                push.2
+                u32checked_shr
+                push.858993459
+                u32checked_and
+                add
+                dup.0
+                push.4
+                u32checked_shr
+                add
+                push.252645135
+                u32checked_and
+                push.16843009
+                u32wrapping_mul
+                push.24
+                u32checked_shr
+            end
+
+            proc.main.0
+                exec.popcnt
+                exec.ctz
+                exec.clz
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_popcnt_unsupported() {
+    let err = conversion_error(
+        r#"
+(module
+    (start $main)
+    (func $main
+        (local i64)
+        i64.const 12
+        i64.popcnt
+        local.set 0
+        return)
+)"#,
+    );
+    assert!(
+        err.contains("i64.popcnt is not supported by Miden"),
+        "{err}"
+    );
+}
@@ -0,0 +1,303 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use sem_tests::check_miden;
+use sem_tests::check_miden_output_with_config;
+
+mod sem_tests;
+
+#[test]
+fn test_i32_and_or_xor() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![6, 14, 8];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $and (result i32)
+        i32.const 12
+        i32.const 10
+        i32.and
+        return)
+    (func $or (result i32)
+        i32.const 12
+        i32.const 10
+        i32.or
+        return)
+    (func $xor (result i32)
+        i32.const 12
+        i32.const 10
+        i32.xor
+        return)
+    (func $main
+        call $and
+        call $or
+        call $xor
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.and.0
+                push.12
+                push.10
+                u32checked_and
+            end
+
+            proc.or.0
+                push.12
+                push.10
+                u32checked_or
+            end
+
+            proc.xor.0
+                push.12
+                push.10
+                u32checked_xor
+            end
+
+            proc.main.0
+                exec.and
+                exec.or
+                exec.xor
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_and_or_xor() {
+    let input = vec![];
+    let secret_input = vec![];
+    // the operands don't fit into a u32
+    let a: u64 = 0x1234_5678_9abc_def0;
+    let b: u64 = 0x0f0f_0f0f_f0f0_f0f0;
+    let expected_output = vec![a ^ b, a | b, a & b];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i64)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $and (result i64)
+        i64.const 0x123456789abcdef0
+        i64.const 0x0f0f0f0ff0f0f0f0
+        i64.and
+        return)
+    (func $or (result i64)
+        i64.const 0x123456789abcdef0
+        i64.const 0x0f0f0f0ff0f0f0f0
+        i64.or
+        return)
+    (func $xor (result i64)
+        i64.const 0x123456789abcdef0
+        i64.const 0x0f0f0f0ff0f0f0f0
+        i64.xor
+        return)
+    (func $main
+        call $and
+        call $or
+        call $xor
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.and.0
+                push.1311768467463790320
+                push.1085102596360827120
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                swap.2
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                swap.2
+                u32checked_and
+                swap.2
+                u32checked_and
+                dup.0
+                push.31
+                u32checked_shr
+                dup.0
+                swap.2
+                swap.1
+                sub
+                push.4294967296
+                mul
+                add
+                add
+            end
+
+            proc.or.0
+                push.1311768467463790320
+                push.1085102596360827120
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                swap.2
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                swap.2
+                u32checked_or
+                swap.2
+                u32checked_or
+                dup.0
+                push.31
+                u32checked_shr
+                dup.0
+                swap.2
+                swap.1
+                sub
+                push.4294967296
+                mul
+                add
+                add
+            end
+
+            proc.xor.0
+                push.1311768467463790320
+                push.1085102596360827120
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                swap.2
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                swap.2
+                u32checked_xor
+                swap.2
+                u32checked_xor
+                dup.0
+                push.31
+                u32checked_shr
+                dup.0
+                swap.2
+                swap.1
+                sub
+                push.4294967296
+                mul
+                add
+                add
+            end
+
+            proc.main.0
+                exec.and
+                exec.or
+                exec.xor
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_and_or_xor_negative() {
+    // the negative operands are combined as the two's complement
+    let expected_output = vec![1; 4];
+    check_miden_output_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $and_neg (result i32)
+        i64.const -6
+        i64.const -3
+        i64.and
+        i64.const -8
+        i64.eq
+        return)
+    (func $or_neg (result i32)
+        i64.const -6
+        i64.const 5
+        i64.or
+        i64.const -1
+        i64.eq
+        return)
+    (func $xor_neg (result i32)
+        i64.const -6
+        i64.const 0x100000000
+        i64.xor
+        i64.const -4294967302
+        i64.eq
+        return)
+    (func $and_wide_neg (result i32)
+        i64.const -0x100000001
+        i64.const 0x7fff0000ffff
+        i64.and
+        i64.const 140728898486271
+        i64.eq
+        return)
+    (func $main
+        call $and_neg
+        call $or_neg
+        call $xor_neg
+        call $and_wide_neg
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+        expected_output,
+    );
+}
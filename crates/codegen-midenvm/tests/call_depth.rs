@@ -0,0 +1,37 @@
+use ozk_codegen_midenvm::MidenTargetConfig;
+use ozk_ir_transform::miden::lowering::MidenControlFlow;
+use sem_tests::check_miden_output_with_config;
+use sem_tests::conversion_error_with_config;
+
+mod sem_tests;
+
+const NESTED_CALL_WAT: &str = r#"
+(module
+    (start $main)
+    (func $get (result i32)
+        i32.const 7
+        return)
+    (func $main
+        (block (result i32)
+            call $get)
+        return)
+)"#;
+
+#[test]
+fn test_call_depth_limit_counts_outlined_blocks() {
+    // main -> block -> get
+    let target_config = MidenTargetConfig::default().with_max_call_depth(1);
+    assert_eq!(target_config.options().max_call_depth, Some(1));
+    let err = conversion_error_with_config(NESTED_CALL_WAT, &target_config);
+    assert!(err.contains("call depth 2 exceeds the limit of 1"), "{err}");
+    assert!(err.contains("main -> get"), "{err}");
+}
+
+#[test]
+fn test_call_depth_limit_structured() {
+    // the block is not outlined, main -> get
+    let target_config = MidenTargetConfig::default()
+        .with_control_flow(MidenControlFlow::Structured)
+        .with_max_call_depth(1);
+    check_miden_output_with_config(NESTED_CALL_WAT, &target_config, vec![], vec![], vec![7]);
+}
@@ -0,0 +1,48 @@
+use ozk_rust_wasm_tests_helper::build_rust_wasm_tests;
+use ozk_rust_wasm_tests_helper::BuildProfile;
+use ozk_rust_wasm_tests_helper::RustWasmBuildOptions;
+use sem_tests::check_wasm_output;
+use sem_tests::wasm_execution_error;
+
+mod sem_tests;
+
+/// The bundle built with the dev profile, so `+` and `-` on u32 are overflow-checked
+fn compile_checked_math() -> Vec<u8> {
+    let options = RustWasmBuildOptions {
+        profile: BuildProfile::Dev,
+        ..Default::default()
+    };
+    build_rust_wasm_tests("checked-math-bin", "checked_math", &options).unwrap()
+}
+
+#[ignore]
+#[test]
+fn test_checked_math_in_range() {
+    let input = vec![7, 3];
+    let secret_input = vec![4];
+    let expected_output = vec![6];
+    let native_output = ozk_rust_wasm_tests_helper::wrap_main_with_io(
+        &ozk_rust_wasm_tests_checked_math::checked_math::main_checked_math,
+    )(input.clone(), secret_input.clone());
+    assert_eq!(native_output, expected_output);
+    check_wasm_output(
+        &compile_checked_math(),
+        input,
+        secret_input,
+        expected_output,
+    );
+}
+
+#[ignore]
+#[test]
+fn test_checked_math_add_overflow() {
+    let err = wasm_execution_error(&compile_checked_math(), vec![u32::MAX as u64, 1], vec![0]);
+    assert!(!err.is_empty());
+}
+
+#[ignore]
+#[test]
+fn test_checked_math_sub_underflow() {
+    let err = wasm_execution_error(&compile_checked_math(), vec![1, 1], vec![5]);
+    assert!(!err.is_empty());
+}
@@ -0,0 +1,325 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use sem_tests::check_miden;
+use sem_tests::check_miden_output_with_config;
+
+mod sem_tests;
+
+#[test]
+fn test_i32_unsigned_cmp() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![1, 0, 1, 0, 1, 0, 1];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $lt_u (result i32)
+        i32.const 3
+        i32.const 5
+        i32.lt_u
+        return)
+    (func $gt_u (result i32)
+        i32.const 3
+        i32.const 5
+        i32.gt_u
+        return)
+    (func $le_u (result i32)
+        i32.const 5
+        i32.const 5
+        i32.le_u
+        return)
+    (func $ge_u (result i32)
+        i32.const 3
+        i32.const 5
+        i32.ge_u
+        return)
+    (func $is_eq (result i32)
+        i32.const 7
+        i32.const 7
+        i32.eq
+        return)
+    (func $is_ne (result i32)
+        i32.const 7
+        i32.const 7
+        i32.ne
+        return)
+    (func $is_zero (result i32)
+        i32.const 0
+        i32.eqz
+        return)
+    (func $main
+        call $lt_u
+        call $gt_u
+        call $le_u
+        call $ge_u
+        call $is_eq
+        call $is_ne
+        call $is_zero
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.lt_u.0
+                push.3
+                push.5
+                u32checked_lt
+            end
+
+            proc.gt_u.0
+                push.3
+                push.5
+                u32checked_gt
+            end
+
+            proc.le_u.0
+                push.5
+                push.5
+                u32checked_lte
+            end
+
+            proc.ge_u.0
+                push.3
+                push.5
+                u32checked_gte
+            end
+
+            proc.is_eq.0
+                push.7
+                push.7
+                u32checked_eq
+            end
+
+            proc.is_ne.0
+                push.7
+                push.7
+                u32checked_neq
+            end
+
+            proc.is_zero.0
+                push.0
+                push.0
+                u32checked_eq
+            end
+
+            proc.main.0
+                exec.lt_u
+                exec.gt_u
+                exec.le_u
+                exec.ge_u
+                exec.is_eq
+                exec.is_ne
+                exec.is_zero
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i32_signed_cmp() {
+    // the last call is on top
+    let expected_output = vec![1, 1, 0, 1, 1, 1];
+    check_miden_output_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $lt_s (result i32)
+        i32.const -1
+        i32.const 1
+        i32.lt_s
+        return)
+    (func $gt_s (result i32)
+        i32.const 3
+        i32.const -5
+        i32.gt_s
+        return)
+    (func $le_s (result i32)
+        i32.const -5
+        i32.const -5
+        i32.le_s
+        return)
+    (func $ge_s (result i32)
+        i32.const -7
+        i32.const 2
+        i32.ge_s
+        return)
+    (func $min_lt_max (result i32)
+        i32.const 0x80000000
+        i32.const 0x7fffffff
+        i32.lt_s
+        return)
+    (func $wrapped_gt_s (result i32)
+        ;; the wrapped u32 difference -3 is compared with the constant -4
+        i32.const 2
+        i32.const 5
+        i32.sub
+        i32.const -4
+        i32.gt_s
+        return)
+    (func $main
+        call $lt_s
+        call $gt_s
+        call $le_s
+        call $ge_s
+        call $min_lt_max
+        call $wrapped_gt_s
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+        expected_output,
+    );
+}
+
+#[test]
+fn test_i64_unsigned_cmp() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![1, 1, 0, 1];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $lt_u (result i32)
+        i64.const 3
+        i64.const 5000000000
+        i64.lt_u
+        return)
+    (func $ge_u (result i32)
+        i64.const 3
+        i64.const 5000000000
+        i64.ge_u
+        return)
+    (func $is_ne (result i32)
+        i64.const 7
+        i64.const 5000000000
+        i64.ne
+        return)
+    (func $is_zero (result i32)
+        i64.const 0
+        i64.eqz
+        return)
+    (func $main
+        call $lt_u
+        call $ge_u
+        call $is_ne
+        call $is_zero
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.lt_u.0
+                push.3
+                push.5000000000
+                lt
+            end
+
+            proc.ge_u.0
+                push.3
+                push.5000000000
+                gte
+            end
+
+            proc.is_ne.0
+                push.7
+                push.5000000000
+                neq
+            end
+
+            proc.is_zero.0
+                push.0
+                push.0
+                eq
+            end
+
+            proc.main.0
+                exec.lt_u
+                exec.ge_u
+                exec.is_ne
+                exec.is_zero
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_signed_cmp() {
+    // the last call is on top
+    let expected_output = vec![1, 1, 0, 1, 1, 1];
+    check_miden_output_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $lt_s (result i32)
+        i64.const -1
+        i64.const 1
+        i64.lt_s
+        return)
+    (func $gt_s (result i32)
+        i64.const 3
+        i64.const -5000000000
+        i64.gt_s
+        return)
+    (func $le_s (result i32)
+        i64.const -5000000000
+        i64.const -5000000000
+        i64.le_s
+        return)
+    (func $ge_s (result i32)
+        i64.const -7
+        i64.const 2
+        i64.ge_s
+        return)
+    (func $wide_lt_s (result i32)
+        i64.const -0x7fffffff00000000
+        i64.const 0x7fffffff00000000
+        i64.lt_s
+        return)
+    (func $sum_gt_s (result i32)
+        ;; the field sum -5 is compared with the constant -6
+        i64.const -7
+        i64.const 2
+        i64.add
+        i64.const -6
+        i64.gt_s
+        return)
+    (func $main
+        call $lt_s
+        call $gt_s
+        call $le_s
+        call $ge_s
+        call $wide_lt_s
+        call $sum_gt_s
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+        expected_output,
+    );
+}
@@ -0,0 +1,42 @@
+#![allow(clippy::unwrap_used)]
+
+use expect_test::expect;
+use ozk_codegen_midenvm::compile_function;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use ozk_frontend_wasm::WasmFrontendConfig;
+use pliron::context::Context;
+
+#[test]
+fn test_compile_single_function() {
+    let source = wat::parse_str(
+        r#"
+(module
+    (start $main)
+    (func $and (result i32)
+        i32.const 12
+        i32.const 10
+        i32.and
+        return)
+    (func $main
+        return)
+)"#,
+    )
+    .unwrap();
+    let mut ctx = Context::default();
+    let target_config = MidenTargetConfig::default();
+    let frontend_config = WasmFrontendConfig::default();
+    frontend_config.register(&mut ctx);
+    target_config.register(&mut ctx);
+    let module_op = ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
+    let func_op = module_op.get_func(&ctx, &"and".into()).unwrap();
+    module_op.remove_function(&mut ctx, func_op).unwrap();
+    let inst_buf = compile_function(&mut ctx, func_op, &target_config).unwrap();
+    expect![[r#"
+        proc.and.0
+            push.12
+            push.10
+            u32checked_and
+        end
+    "#]]
+    .assert_eq(&inst_buf.pretty_print());
+}
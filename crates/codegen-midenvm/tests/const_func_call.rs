@@ -0,0 +1,43 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use sem_tests::check_miden_with_config;
+
+mod sem_tests;
+
+#[test]
+fn test_const_func_calls() {
+    let target_config = MidenTargetConfig::default().with_const_func_calls();
+    assert!(target_config.options().const_func_calls);
+    check_miden_with_config(
+        r#"
+(module
+    (start $main)
+    (func $base (result i32)
+        i32.const 3
+        return)
+    (func $scaled (result i32)
+        call $base
+        i32.const 7
+        i32.mul
+        i32.const 1
+        i32.add
+        return)
+    (func $main
+        call $scaled
+        return)
+)"#,
+        &target_config,
+        vec![],
+        vec![],
+        vec![22],
+        expect![[r#"
+            proc.main.0
+                push.22
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
@@ -0,0 +1,33 @@
+use sem_tests::uncovered_procs;
+
+mod sem_tests;
+
+#[test]
+fn test_uncovered_procs() {
+    let uncovered = uncovered_procs(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $taken (result i32)
+        i32.const 3
+        return)
+    (func $not_taken (result i32)
+        i32.const 4
+        return)
+    (func $main
+        i32.const 1
+        (if (result i32)
+            (then
+                call $taken)
+            (else
+                call $not_taken))
+        return)
+)"#,
+        vec![],
+        vec![],
+    );
+    assert_eq!(uncovered, vec!["not_taken".to_string()]);
+}
@@ -0,0 +1,374 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use sem_tests::check_miden;
+use sem_tests::check_miden_output_with_config;
+use sem_tests::execution_error;
+
+mod sem_tests;
+
+#[test]
+fn test_i32_div_rem_u() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![2, 3];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $div (result i32)
+        i32.const 17
+        i32.const 5
+        i32.div_u
+        return)
+    (func $rem (result i32)
+        i32.const 17
+        i32.const 5
+        i32.rem_u
+        return)
+    (func $main
+        call $div
+        call $rem
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.div.0
+                push.17
+                push.5
+                u32checked_div
+            end
+
+            proc.rem.0
+                push.17
+                push.5
+                u32checked_mod
+            end
+
+            proc.main.0
+                exec.div
+                exec.rem
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_div_rem_u() {
+    let input = vec![];
+    let secret_input = vec![];
+    // the quotient doesn't fit into a u32, the divisor is wider than a u32
+    let a: u64 = 0x7fff_ffff_0000_0000;
+    let expected_output = vec![a % 0x100000003, a / 10];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i64)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $div (result i64)
+        i64.const 0x7fffffff00000000
+        i64.const 10
+        i64.div_u
+        return)
+    (func $rem (result i64)
+        i64.const 0x7fffffff00000000
+        i64.const 0x100000003
+        i64.rem_u
+        return)
+    (func $main
+        call $div
+        call $rem
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            use.std::math::u64
+            proc.div.0
+                push.9223372032559808512
+                push.10
+                swap.1
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                swap.1
+                swap.2
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                exec.u64::unchecked_div
+                dup.0
+                push.31
+                u32checked_shr
+                dup.0
+                swap.2
+                swap.1
+                sub
+                push.4294967296
+                mul
+                add
+                add
+            end
+
+            proc.rem.0
+                push.9223372032559808512
+                push.4294967299
+                swap.1
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                swap.1
+                swap.2
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                exec.u64::unchecked_mod
+                dup.0
+                push.31
+                u32checked_shr
+                dup.0
+                swap.2
+                swap.1
+                sub
+                push.4294967296
+                mul
+                add
+                add
+            end
+
+            proc.main.0
+                exec.div
+                exec.rem
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_div_u_by_zero_fails() {
+    let err = execution_error(
+        r#"
+(module
+    (start $main)
+    (func $main
+        i64.const 17
+        i64.const 0
+        i64.div_u
+        drop
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+    );
+    assert!(!err.is_empty());
+}
+
+#[test]
+fn test_i32_div_rem_s() {
+    // the negative results are the u32 two's complement
+    let expected_output = vec![2, (-2i32) as u32 as u64, 3, (-3i32) as u32 as u64];
+    check_miden_output_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $div_neg (result i32)
+        i32.const -17
+        i32.const 5
+        i32.div_s
+        return)
+    (func $div_both_neg (result i32)
+        i32.const -17
+        i32.const -5
+        i32.div_s
+        return)
+    (func $rem_neg_dividend (result i32)
+        i32.const -17
+        i32.const 5
+        i32.rem_s
+        return)
+    (func $rem_neg_divisor (result i32)
+        i32.const 17
+        i32.const -5
+        i32.rem_s
+        return)
+    (func $main
+        call $div_neg
+        call $div_both_neg
+        call $rem_neg_dividend
+        call $rem_neg_divisor
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+        expected_output,
+    );
+}
+
+#[test]
+fn test_i32_div_s_by_zero_fails() {
+    let err = execution_error(
+        r#"
+(module
+    (start $main)
+    (func $main
+        i32.const -17
+        i32.const 0
+        i32.div_s
+        drop
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+    );
+    assert!(!err.is_empty());
+}
+
+#[test]
+fn test_i32_div_s_overflow_fails() {
+    let err = execution_error(
+        r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 0x80000000
+        i32.const -1
+        i32.div_s
+        drop
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+    );
+    assert!(!err.is_empty());
+}
+
+#[test]
+fn test_i64_div_rem_s() {
+    // the negative operands are divided as the absolute values, `div_u` takes the two's complement
+    let expected_output = vec![1; 5];
+    check_miden_output_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $div_neg (result i32)
+        i64.const -17
+        i64.const 5
+        i64.div_s
+        i64.const -3
+        i64.eq
+        return)
+    (func $div_both_neg (result i32)
+        i64.const -50000000000
+        i64.const -7
+        i64.div_s
+        i64.const 7142857142
+        i64.eq
+        return)
+    (func $rem_neg_dividend (result i32)
+        i64.const -17
+        i64.const 5
+        i64.rem_s
+        i64.const -2
+        i64.eq
+        return)
+    (func $rem_neg_divisor (result i32)
+        i64.const 50000000000
+        i64.const -7
+        i64.rem_s
+        i64.const 6
+        i64.eq
+        return)
+    (func $div_u_neg (result i32)
+        i64.const -10
+        i64.const 0x100000000
+        i64.div_u
+        i64.const 0xffffffff
+        i64.eq
+        return)
+    (func $main
+        call $div_neg
+        call $div_both_neg
+        call $rem_neg_dividend
+        call $rem_neg_divisor
+        call $div_u_neg
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+        expected_output,
+    );
+}
+
+#[test]
+fn test_i64_div_s_by_zero_fails() {
+    let err = execution_error(
+        r#"
+(module
+    (start $main)
+    (func $main
+        i64.const -17
+        i64.const 0
+        i64.div_s
+        drop
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+    );
+    assert!(!err.is_empty());
+}
@@ -0,0 +1,46 @@
+use expect_test::expect;
+use sem_tests::check_miden;
+
+mod sem_tests;
+
+#[test]
+fn test_drop() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![3];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $keep_first (result i32)
+        i32.const 3
+        i32.const 5
+        drop
+        return)
+    (func $main
+        call $keep_first
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.keep_first.0
+                push.3
+                push.5
+                drop
+            end
+
+            proc.main.0
+                exec.keep_first
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
@@ -0,0 +1,96 @@
+use expect_test::expect;
+use sem_tests::check_miden;
+
+mod sem_tests;
+
+#[test]
+fn test_if_else() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![3, 9, 7];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $then_taken (result i32)
+        i32.const 5
+        (if (result i32)
+            (then
+                i32.const 7)
+            (else
+                i32.const 9))
+        return)
+    (func $else_taken (result i32)
+        i32.const 0
+        (if (result i32)
+            (then
+                i32.const 7)
+            (else
+                i32.const 9))
+        return)
+    (func $no_else (result i32)
+        i32.const 3
+        i32.const 1
+        (if
+            (then
+                i32.const 4
+                drop))
+        return)
+    (func $main
+        call $then_taken
+        call $else_taken
+        call $no_else
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.then_taken.0
+                push.5
+                push.0
+                neq
+                if.true
+                    push.7
+                else
+                    push.9
+                end
+            end
+
+            proc.else_taken.0
+                push.0
+                push.0
+                neq
+                if.true
+                    push.7
+                else
+                    push.9
+                end
+            end
+
+            proc.no_else.0
+                push.3
+                push.1
+                push.0
+                neq
+                if.true
+                    push.4
+                    drop
+                end
+            end
+
+            proc.main.0
+                exec.then_taken
+                exec.else_taken
+                exec.no_else
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
@@ -0,0 +1,65 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::format_masm_source;
+use ozk_codegen_midenvm::MasmFormat;
+
+const SOURCE: &str = r#"
+proc.count.1
+# miden.loc_load 0
+loc_load.0
+while.true
+if.true
+push.1
+else
+push.2
+end
+end
+end
+begin
+exec.count
+end
+"#;
+
+#[test]
+fn test_format_default() {
+    expect![[r#"
+        proc.count.1
+            loc_load.0
+            while.true
+                if.true
+                    push.1
+                else
+                    push.2
+                end
+            end
+        end
+
+        begin
+            exec.count
+        end
+    "#]]
+    .assert_eq(&format_masm_source(SOURCE, &MasmFormat::default()));
+}
+
+#[test]
+fn test_format_flat_with_comments() {
+    let format = MasmFormat {
+        comments: true,
+        ..MasmFormat::flat()
+    };
+    expect![[r#"
+        proc.count.1
+        # miden.loc_load 0
+        loc_load.0
+        while.true
+        if.true
+        push.1
+        else
+        push.2
+        end
+        end
+        end
+        begin
+        exec.count
+        end"#]]
+    .assert_eq(&format_masm_source(SOURCE, &format));
+}
@@ -0,0 +1,91 @@
+use ozk_codegen_midenvm::MidenTargetConfig;
+use sem_tests::check_miden_output_with_config;
+
+mod sem_tests;
+
+#[test]
+fn test_partial_loads_from_data() {
+    // the 16-bit load at 19 spans two cells, the i32 load reads the cell at 20 via the offset
+    check_miden_output_with_config(
+        r#"
+(module
+    (memory 1)
+    (data (i32.const 16) "\01\80\ff\7f\34\12")
+    (start $main)
+    (func $main
+        i32.const 17
+        i32.load8_u
+        i32.const 17
+        i32.load8_s
+        i32.const 19
+        i32.load16_u
+        i32.const 16
+        i32.load offset=4
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+        vec![0x1234, 0x347f, 0xffff_ff80, 0x80],
+    );
+}
+
+#[test]
+fn test_i64_store_load() {
+    // the low half of the i64 is in the cell with the lower address
+    check_miden_output_with_config(
+        r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 32
+        i64.const 0x100000002
+        i64.store
+        i32.const 32
+        i64.load
+        i32.const 36
+        i32.load
+        i32.const 28
+        i32.const 7
+        i32.store offset=4
+        i32.const 32
+        i32.load
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+        vec![7, 1, 0x1_0000_0002],
+    );
+}
+
+#[test]
+fn test_memarg_offsets() {
+    // the static offsets are added to the address on the stack, for the partial loads as well
+    check_miden_output_with_config(
+        r#"
+(module
+    (memory 1)
+    (data (i32.const 40) "\05\06\07\08\09\0a\0b\0c")
+    (start $main)
+    (func $main
+        i32.const 40
+        i32.load8_u offset=5
+        i32.const 38
+        i32.load16_u offset=3
+        i32.const 36
+        i64.load offset=4
+        i32.const 8
+        i64.const 3
+        i64.store offset=40
+        i32.const 48
+        i64.load
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+        vec![3, 0x0c0b_0a09_0807_0605, 0x0706, 0x0a],
+    );
+}
@@ -0,0 +1,71 @@
+use expect_test::expect;
+use sem_tests::check_miden;
+use sem_tests::conversion_error;
+
+mod sem_tests;
+
+#[test]
+fn test_return_skips_unreachable_ops() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![3];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $get (result i32)
+        i32.const 3
+        return
+        i32.const 4
+        return)
+    (func $main
+        call $get
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.get.0
+                push.3
+            end
+
+            proc.main.0
+                exec.get
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_early_return_in_nested_block() {
+    let err = conversion_error(
+        r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        block
+            loop
+                local.get 0
+                br_if 1
+                return
+            end
+            i32.const 1
+            local.set 0
+        end
+        return)
+)"#,
+    );
+    assert!(
+        err.contains("early return from proc > block > loop in function main"),
+        "{err}"
+    );
+}
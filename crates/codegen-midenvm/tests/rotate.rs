@@ -0,0 +1,118 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use sem_tests::check_miden;
+use sem_tests::check_miden_output_with_config;
+
+mod sem_tests;
+
+#[test]
+fn test_i32_rotl_rotr() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![3, 5];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $rotl (result i32)
+        i32.const 0x40000001
+        i32.const 2
+        i32.rotl
+        return)
+    (func $rotr (result i32)
+        i32.const 6
+        i32.const 33
+        i32.rotr
+        return)
+    (func $main
+        call $rotl
+        call $rotr
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.rotl.0
+                push.1073741825
+                push.2
+                push.31
+                u32checked_and
+                u32checked_rotl
+            end
+
+            proc.rotr.0
+                push.6
+                push.33
+                push.31
+                u32checked_and
+                u32checked_rotr
+            end
+
+            proc.main.0
+                exec.rotl
+                exec.rotr
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_rotl_rotr() {
+    // the amount is masked to the low 6 bits, the negative values rotate as the two's complement
+    let expected_output = vec![1; 4];
+    check_miden_output_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $rotl_neg (result i32)
+        i64.const -2
+        i64.const 1
+        i64.rotl
+        i64.const -3
+        i64.eq
+        return)
+    (func $rotl_wide (result i32)
+        i64.const 0x100000002
+        i64.const 36
+        i64.rotl
+        i64.const 137438953488
+        i64.eq
+        return)
+    (func $rotr_neg (result i32)
+        i64.const -4
+        i64.const 2
+        i64.rotr
+        i64.const 4611686018427387903
+        i64.eq
+        return)
+    (func $rotr_masks_amount (result i32)
+        i64.const 6
+        i64.const 65
+        i64.rotr
+        i64.const 3
+        i64.eq
+        return)
+    (func $main
+        call $rotl_neg
+        call $rotl_wide
+        call $rotr_neg
+        call $rotr_masks_amount
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+        expected_output,
+    );
+}
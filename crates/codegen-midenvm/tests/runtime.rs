@@ -0,0 +1,19 @@
+#![allow(clippy::unwrap_used)]
+
+use ozk_codegen_midenvm::MIDEN_RUNTIME_WAT;
+use ozk_frontend_wasm::WasmFrontendConfig;
+use pliron::context::Context;
+
+#[test]
+fn test_runtime_translates() {
+    let source = wat::parse_str(MIDEN_RUNTIME_WAT).unwrap();
+    let mut ctx = Context::default();
+    let frontend_config = WasmFrontendConfig::default();
+    frontend_config.register(&mut ctx);
+    let module_op = ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
+    let func_syms = module_op.get_func_syms(&ctx);
+    assert!(
+        func_syms.iter().any(|sym| sym.as_ref() == "memory_init"),
+        "{func_syms:?}"
+    );
+}
@@ -0,0 +1,130 @@
+use expect_test::expect;
+use sem_tests::check_miden;
+
+mod sem_tests;
+
+#[test]
+fn test_select() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![5, 3];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $select_first (result i32)
+        i32.const 3
+        i32.const 5
+        i32.const 7
+        select
+        return)
+    (func $select_second (result i32)
+        i32.const 3
+        i32.const 5
+        i32.const 0
+        select (result i32)
+        return)
+    (func $main
+        call $select_first
+        call $select_second
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.select_first.0
+                push.3
+                push.5
+                push.7
+                push.0
+                eq
+                cdrop
+            end
+
+            proc.select_second.0
+                push.3
+                push.5
+                push.0
+                push.0
+                eq
+                cdrop
+            end
+
+            proc.main.0
+                exec.select_first
+                exec.select_second
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_select() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![5000000000, 3];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i64)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $select_first (result i64)
+        i64.const 3
+        i64.const 5000000000
+        i32.const 1
+        select
+        return)
+    (func $select_second (result i64)
+        i64.const 3
+        i64.const 5000000000
+        i32.const 0
+        select (result i64)
+        return)
+    (func $main
+        call $select_first
+        call $select_second
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.select_first.0
+                push.3
+                push.5000000000
+                push.1
+                push.0
+                eq
+                cdrop
+            end
+
+            proc.select_second.0
+                push.3
+                push.5000000000
+                push.0
+                push.0
+                eq
+                cdrop
+            end
+
+            proc.main.0
+                exec.select_first
+                exec.select_second
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
@@ -14,32 +14,31 @@ use miden_processor::StackInputs;
 use miden_processor::VmState;
 use miden_processor::VmStateIterator;
 use miden_stdlib::StdLibrary;
+use ozk_codegen_midenvm::compile_module;
 use ozk_codegen_midenvm::emit_prog;
+use ozk_codegen_midenvm::vm;
 use ozk_codegen_midenvm::MidenTargetConfig;
 use ozk_frontend_wasm::WasmFrontendConfig;
 use ozk_miden_dialect::ops::ProgramOp;
-use ozk_wasm_dialect::ops::ModuleOp;
 use pliron::context::Context;
-use pliron::context::Ptr;
-use pliron::dialects::builtin;
-use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
-use pliron::linked_list::ContainsLinkedList;
-use pliron::op::Op;
-use pliron::operation::Operation;
 use pliron::with_context::AttachContext;
 use wasmtime::*;
 use winter_math::StarkField;
 
+/// Frontend config with all the optional Wasm features enabled
+fn frontend_config() -> WasmFrontendConfig {
+    WasmFrontendConfig {
+        sign_extension: true,
+        tail_call: true,
+        ..Default::default()
+    }
+}
+
 pub fn check_ir(input: &str, expected_tree: expect_test::Expect) {
     let source = wat::parse_str(input).unwrap();
     let mut ctx = Context::default();
     let target_config = MidenTargetConfig::default();
-    let frontend_config = WasmFrontendConfig::default();
-    frontend_config.register(&mut ctx);
-    target_config.register(&mut ctx);
-    let wasm_module_op =
-        ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
-    let miden_prog = run_conversion_passes(&mut ctx, wasm_module_op, &target_config);
+    let miden_prog = compile_to_miden_dialect(&mut ctx, &source, &target_config);
     expected_tree.assert_eq(miden_prog.with_ctx(&ctx).to_string().as_str());
 }
 
@@ -48,47 +47,61 @@ pub fn compile_to_miden_dialect(
     source: &[u8],
     target_config: &MidenTargetConfig,
 ) -> ProgramOp {
-    let frontend_config = WasmFrontendConfig::default();
-    frontend_config.register(ctx);
-    target_config.register(ctx);
-    let wasm_module_op = ozk_frontend_wasm::parse_module(ctx, source, &frontend_config).unwrap();
-    run_conversion_passes(ctx, wasm_module_op, target_config)
+    compile_module(ctx, source, &frontend_config(), target_config).unwrap()
 }
 
 pub fn compile(ctx: &mut Context, source: &[u8]) -> String {
-    let target_config = MidenTargetConfig::default();
-    let miden_prog = compile_to_miden_dialect(ctx, source, &target_config);
-    let inst_buf = emit_prog(ctx, &miden_prog, &target_config).unwrap();
-    inst_buf.pretty_print()
+    compile_with_config(ctx, source, &MidenTargetConfig::default())
 }
 
-fn run_conversion_passes(
+pub fn compile_with_config(
     ctx: &mut Context,
-    wasm_module: ModuleOp,
+    source: &[u8],
     target_config: &MidenTargetConfig,
-) -> ProgramOp {
-    // we need to wrap the wasm in an op because passes cannot replace the root op
-    let wrapper_module = builtin::ops::ModuleOp::new(ctx, "wrapper");
-    wasm_module
-        .get_operation()
-        .insert_at_back(wrapper_module.get_body(ctx, 0), ctx);
-    target_config
-        .pass_manager
-        .run(ctx, wrapper_module.get_operation())
-        .unwrap();
-    let inner_module = wrapper_module
-        .get_body(ctx, 0)
-        .deref(ctx)
-        .iter(ctx)
-        .collect::<Vec<Ptr<Operation>>>()
-        .first()
-        .cloned()
-        .unwrap();
-    *inner_module
-        .deref(ctx)
-        .get_op(ctx)
-        .downcast::<ProgramOp>()
-        .unwrap_or_else(|_| panic!("Expected ProgramOp"))
+) -> String {
+    let miden_prog = compile_to_miden_dialect(ctx, source, target_config);
+    let inst_buf = emit_prog(ctx, &miden_prog, target_config).unwrap();
+    inst_buf.pretty_print()
+}
+
+/// Compile the WAT source with the given config and return the Miden VM execution error
+/// (panics if the execution succeeds)
+pub fn execution_error(
+    source: &str,
+    target_config: &MidenTargetConfig,
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+) -> String {
+    let wasm = wat::parse_str(source).unwrap();
+    let mut ctx = Context::default();
+    let program = compile_with_config(&mut ctx, &wasm, target_config);
+    vm::execute(&program, input, secret_input)
+        .unwrap_err()
+        .to_string()
+}
+
+/// Compile the Wasm binary, execute it and return the Miden VM execution error
+/// (panics if the execution succeeds)
+pub fn wasm_execution_error(source: &[u8], input: Vec<u64>, secret_input: Vec<u64>) -> String {
+    let mut ctx = Context::default();
+    let program = compile(&mut ctx, source);
+    vm::execute(&program, input, secret_input)
+        .unwrap_err()
+        .to_string()
+}
+
+/// Run the Miden conversion passes on the WAT source and return the error (panics if the passes succeed)
+pub fn conversion_error(input: &str) -> String {
+    conversion_error_with_config(input, &MidenTargetConfig::default())
+}
+
+/// Same as [conversion_error], with the given config
+pub fn conversion_error_with_config(input: &str, target_config: &MidenTargetConfig) -> String {
+    let source = wat::parse_str(input).unwrap();
+    let mut ctx = Context::default();
+    compile_module(&mut ctx, &source, &frontend_config(), target_config)
+        .unwrap_err()
+        .to_string()
 }
 
 pub fn check_wasm(
@@ -105,18 +118,228 @@ pub fn check_wasm(
     check_miden(&wat, input, secret_input, expected_output, expected_miden);
 }
 
+/// Same as [check_wasm], but checks only the output. For the Wasm built from Rust, where
+/// the emitted code changes with the toolchain.
+pub fn check_wasm_output(
+    source: &[u8],
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+    expected_output: Vec<u64>,
+) {
+    let mut ctx = Context::default();
+    let program = compile(&mut ctx, source);
+    assert_miden_output(program, input, secret_input, expected_output);
+}
+
+/// Same as [check_miden_with_config], but checks only the output. For the programs with the
+/// memory ops of the reserved slots, where the addresses follow the memory layout.
+pub fn check_miden_output_with_config(
+    source: &str,
+    target_config: &MidenTargetConfig,
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+    expected_output: Vec<u64>,
+) {
+    let wasm = wat::parse_str(source).unwrap();
+    let mut ctx = Context::default();
+    let program = compile_with_config(&mut ctx, &wasm, target_config);
+    assert_miden_output(program, input, secret_input, expected_output);
+}
+
 pub fn check_miden(
     source: &str,
     input: Vec<u64>,
     secret_input: Vec<u64>,
     expected_output: Vec<u64>,
     expected_miden: expect_test::Expect,
+) {
+    check_miden_with_config(
+        source,
+        &MidenTargetConfig::default(),
+        input,
+        secret_input,
+        expected_output,
+        expected_miden,
+    );
+}
+
+pub fn check_miden_with_config(
+    source: &str,
+    target_config: &MidenTargetConfig,
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+    expected_output: Vec<u64>,
+    expected_miden: expect_test::Expect,
+) {
+    let wasm = wat::parse_str(source).unwrap();
+    let mut ctx = Context::default();
+    let program = compile_with_config(&mut ctx, &wasm, target_config);
+    expected_miden.assert_eq(&program);
+    assert_miden_output(program, input, secret_input, expected_output);
+}
+
+/// Same as [check_miden], but runs the program for every set of the public and secret inputs
+/// and checks the output against the one computed by `expected_output` from the inputs.
+/// The program is compiled (and checked against `expected_miden`) once.
+pub fn check_miden_for_inputs(
+    source: &str,
+    inputs: Vec<(Vec<u64>, Vec<u64>)>,
+    expected_output: impl Fn(&[u64], &[u64]) -> Vec<u64>,
+    expected_miden: expect_test::Expect,
 ) {
     let wasm = wat::parse_str(source).unwrap();
     let mut ctx = Context::default();
     let program = compile(&mut ctx, &wasm);
     expected_miden.assert_eq(&program);
+    for (input, secret_input) in inputs {
+        let expected = expected_output(&input, &secret_input);
+        assert_miden_output(program.clone(), input, secret_input, expected);
+    }
+}
+
+/// Execute the Miden program and compare the stack on exit with the expected output
+fn assert_miden_output(
+    program: String,
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+    expected_output: Vec<u64>,
+) {
+    let inputs = format!("input: {input:?}, secret input: {secret_input:?}");
+    let vm_state = execute_miden(program.clone(), input, secret_input);
+    if coverage_enabled() {
+        eprintln!(
+            "never executed procedures: {:?}",
+            unexecuted_procs(&program, &vm_state)
+        );
+    }
+    let stack = pretty_stack_felt(&vm_state.last().unwrap().stack);
+    // fill expected_output with zeros if it's shorter than stack
+    let expected_output = expected_output
+        .into_iter()
+        .chain(std::iter::repeat(0))
+        .take(stack.len())
+        .collect::<Vec<_>>();
+    let trace = if trace_enabled() {
+        format_trace(&vm_state, TRACE_LAST_OPS)
+    } else {
+        format!("set {TRACE_ENV_VAR} env var to get the execution trace")
+    };
+    assert_eq!(stack, expected_output, "{inputs}\n{trace}");
+}
+
+/// Env var that turns on the execution trace in the assertion messages.
+const TRACE_ENV_VAR: &str = "OZK_SEM_TESTS_TRACE";
+/// Number of the last executed instructions to show in the trace.
+const TRACE_LAST_OPS: usize = 20;
+
+fn trace_enabled() -> bool {
+    std::env::var(TRACE_ENV_VAR).is_ok()
+}
+
+/// Env var that turns on the report of the emitted procedures that were never executed.
+const COVERAGE_ENV_VAR: &str = "OZK_SEM_TESTS_COVERAGE";
+
+fn coverage_enabled() -> bool {
+    std::env::var(COVERAGE_ENV_VAR).is_ok()
+}
+
+/// Names of the procedures defined in the Miden source
+fn defined_procs(program: &str) -> Vec<String> {
+    program
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("proc."))
+        .filter_map(|decl| decl.split('.').next())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// The procedures defined in the Miden source that have no executed instructions.
+/// The VM state should be collected with the assembler's debug mode on.
+fn unexecuted_procs(program: &str, vm_state: &[VmState]) -> Vec<String> {
+    let executed: Vec<&str> = vm_state
+        .iter()
+        .filter_map(|state| state.asmop.as_ref())
+        .map(|asmop| asmop.context_name())
+        .collect();
+    defined_procs(program)
+        .into_iter()
+        .filter(|name| {
+            !executed.iter().any(|context_name| {
+                *context_name == name || context_name.ends_with(&format!("::{name}"))
+            })
+        })
+        .collect()
+}
+
+/// Compile the WAT source, execute it and return the emitted procedures that were never executed
+pub fn uncovered_procs(source: &str, input: Vec<u64>, secret_input: Vec<u64>) -> Vec<String> {
+    let wasm = wat::parse_str(source).unwrap();
+    let mut ctx = Context::default();
+    let program = compile(&mut ctx, &wasm);
+    let vm_state = execute_miden_with_debug(program.clone(), input, secret_input, true);
+    unexecuted_procs(&program, &vm_state)
+}
+
+/// Per-procedure cycle counts and the last `last_n` executed instructions.
+fn format_trace(vm_state: &[VmState], last_n: usize) -> String {
+    let mut cycles_per_proc: Vec<(String, usize)> = Vec::new();
+    for state in vm_state {
+        let proc_name = state
+            .asmop
+            .as_ref()
+            .map(|asmop| asmop.context_name().to_string())
+            .unwrap_or_else(|| "<unknown>".to_string());
+        match cycles_per_proc
+            .iter_mut()
+            .find(|(name, _)| *name == proc_name)
+        {
+            Some((_, cycles)) => *cycles += 1,
+            None => cycles_per_proc.push((proc_name, 1)),
+        }
+    }
+    let mut out = String::from("cycles per procedure:\n");
+    for (name, cycles) in cycles_per_proc {
+        out.push_str(&format!("  {name}: {cycles}\n"));
+    }
+    out.push_str(&format!("last {last_n} executed instructions:\n"));
+    let skip = vm_state.len().saturating_sub(last_n);
+    for state in vm_state.iter().skip(skip) {
+        let op = state
+            .asmop
+            .as_ref()
+            .map(|asmop| asmop.op().to_string())
+            .unwrap_or_else(|| format!("{:?}", state.op));
+        out.push_str(&format!("  clk={} {op}\n", state.clk));
+    }
+    out
+}
+
+/// Assemble and execute the Miden program, returning the stack on exit.
+pub fn run_miden(program: String, input: Vec<u64>, secret_input: Vec<u64>) -> Vec<u64> {
+    vm::execute(&program, input, secret_input).unwrap().stack
+}
+
+/// Assemble and execute the Miden program generating and verifying the STARK proof,
+/// returning the stack on exit.
+pub fn prove_miden(program: String, input: Vec<u64>, secret_input: Vec<u64>) -> Vec<u64> {
+    vm::prove(&program, input, secret_input).unwrap().stack
+}
+
+/// Assemble and execute the Miden program, returning the VM state for every cycle.
+fn execute_miden(program: String, input: Vec<u64>, secret_input: Vec<u64>) -> Vec<VmState> {
+    let debug_mode = trace_enabled() || coverage_enabled();
+    execute_miden_with_debug(program, input, secret_input, debug_mode)
+}
+
+/// Same as [execute_miden], in the debug mode the VM state has the executed assembly ops.
+fn execute_miden_with_debug(
+    program: String,
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+    debug_mode: bool,
+) -> Vec<VmState> {
     let assembler = Assembler::default()
+        .with_debug_mode(debug_mode)
         .with_library(&StdLibrary::default())
         .unwrap();
     let program = assembler.compile(program).unwrap();
@@ -139,14 +362,7 @@ pub fn check_miden(
     );
     // assert_eq!(0, 1);
     // let stack = pretty_stack(trace.stack_outputs().stack());
-    let stack = pretty_stack_felt(&vm_state.last().unwrap().stack);
-    // fill expected_output with zeros if it's shorter than stack
-    let expected_output = expected_output
-        .into_iter()
-        .chain(std::iter::repeat(0))
-        .take(stack.len())
-        .collect::<Vec<_>>();
-    assert_eq!(stack, expected_output);
+    vm_state
 }
 
 pub fn check_wat(
@@ -156,6 +372,33 @@ pub fn check_wat(
     expected_output: Vec<u64>,
     expected_miden: expect_test::Expect,
 ) {
+    assert_eq!(
+        run_wasmtime(source, input.clone(), secret_input.clone()),
+        expected_output
+    );
+    check_miden(source, input, secret_input, expected_output, expected_miden);
+}
+
+/// Same as [check_wat], but runs the program for every set of the public and secret inputs
+/// (see [check_miden_for_inputs]).
+pub fn check_wat_for_inputs(
+    source: &str,
+    inputs: Vec<(Vec<u64>, Vec<u64>)>,
+    expected_output: impl Fn(&[u64], &[u64]) -> Vec<u64>,
+    expected_miden: expect_test::Expect,
+) {
+    for (input, secret_input) in &inputs {
+        assert_eq!(
+            run_wasmtime(source, input.clone(), secret_input.clone()),
+            expected_output(input, secret_input),
+            "input: {input:?}, secret input: {secret_input:?}"
+        );
+    }
+    check_miden_for_inputs(source, inputs, expected_output, expected_miden);
+}
+
+/// Run the WAT source in wasmtime with the ozk stdlib I/O imports, returning the public output
+fn run_wasmtime(source: &str, input: Vec<u64>, secret_input: Vec<u64>) -> Vec<u64> {
     struct Io {
         input: Vec<u64>,
         secret_input: Vec<u64>,
@@ -165,8 +408,8 @@ pub fn check_wat(
     let mut store = Store::new(
         &Engine::default(),
         Io {
-            input: input.clone().into_iter().rev().collect(),
-            secret_input: secret_input.clone().into_iter().rev().collect(),
+            input: input.into_iter().rev().collect(),
+            secret_input: secret_input.into_iter().rev().collect(),
             output: Vec::new(),
         },
     );
@@ -190,9 +433,7 @@ pub fn check_wat(
         ozk_stdlib_secret_input.into(),
     ];
     let _ = Instance::new(&mut store, &module, &imports).unwrap();
-
-    assert_eq!(store.data().output, expected_output);
-    check_miden(source, input, secret_input, expected_output, expected_miden);
+    store.into_data().output
 }
 
 fn pretty_stack_felt(stack: &[Felt]) -> Vec<u64> {
@@ -0,0 +1,267 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use sem_tests::check_miden;
+use sem_tests::check_miden_output_with_config;
+use sem_tests::conversion_error;
+
+mod sem_tests;
+
+#[test]
+fn test_i32_shl_shr_u_masks_amount() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![5, 6];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $shl (result i32)
+        i32.const 3
+        i32.const 33
+        i32.shl
+        return)
+    (func $shr (result i32)
+        i32.const 40
+        i32.const 3
+        i32.shr_u
+        return)
+    (func $main
+        call $shl
+        call $shr
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.shl.0
+                push.3
+                push.33
+                push.31
+                u32checked_and
+                u32checked_shl
+            end
+
+            proc.shr.0
+                push.40
+                push.3
+                push.31
+                u32checked_and
+                u32checked_shr
+            end
+
+            proc.main.0
+                exec.shl
+                exec.shr
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_shl_shr_u_masks_amount() {
+    let input = vec![];
+    let secret_input = vec![];
+    // the amount 100 is masked to 36, the limbs cross the u32 boundary
+    let a: u64 = 0x1234_5678_9abc_def0;
+    let expected_output = vec![a >> 36, a << 36];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i64)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $shl (result i64)
+        i64.const 0x123456789abcdef0
+        i64.const 100
+        i64.shl
+        return)
+    (func $shr (result i64)
+        i64.const 0x123456789abcdef0
+        i64.const 36
+        i64.shr_u
+        return)
+    (func $main
+        call $shl
+        call $shr
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            use.std::math::u64
+            proc.shl.0
+                push.1311768467463790320
+                push.100
+                u32split
+                drop
+                push.63
+                u32checked_and
+                swap.1
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                swap.1
+                swap.2
+                exec.u64::unchecked_shl
+                dup.0
+                push.31
+                u32checked_shr
+                dup.0
+                swap.2
+                swap.1
+                sub
+                push.4294967296
+                mul
+                add
+                add
+            end
+
+            proc.shr.0
+                push.1311768467463790320
+                push.36
+                u32split
+                drop
+                push.63
+                u32checked_and
+                swap.1
+                dup.0
+                push.9223372034707292160
+                gt
+                swap.1
+                dup.1
+                sub
+                u32split
+                swap.1
+                swap.2
+                add
+                swap.1
+                swap.2
+                exec.u64::unchecked_shr
+                dup.0
+                push.31
+                u32checked_shr
+                dup.0
+                swap.2
+                swap.1
+                sub
+                push.4294967296
+                mul
+                add
+                add
+            end
+
+            proc.main.0
+                exec.shl
+                exec.shr
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_shifts_negative() {
+    // the negative values are shifted as the two's complement, `shr_s` fills the sign bit
+    let expected_output = vec![1; 6];
+    check_miden_output_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $shr_s_neg (result i32)
+        i64.const -16
+        i64.const 2
+        i64.shr_s
+        i64.const -4
+        i64.eq
+        return)
+    (func $shr_s_masks_amount (result i32)
+        i64.const -5
+        i64.const 70
+        i64.shr_s
+        i64.const -1
+        i64.eq
+        return)
+    (func $shr_s_pos (result i32)
+        i64.const 100
+        i64.const 3
+        i64.shr_s
+        i64.const 12
+        i64.eq
+        return)
+    (func $shr_u_neg (result i32)
+        i64.const -1
+        i64.const 60
+        i64.shr_u
+        i64.const 15
+        i64.eq
+        return)
+    (func $shr_u_masks_amount (result i32)
+        i64.const -3
+        i64.const 68
+        i64.shr_u
+        i64.const 1152921504606846975
+        i64.eq
+        return)
+    (func $shl_neg (result i32)
+        i64.const -3
+        i64.const 4
+        i64.shl
+        i64.const -48
+        i64.eq
+        return)
+    (func $main
+        call $shr_s_neg
+        call $shr_s_masks_amount
+        call $shr_s_pos
+        call $shr_u_neg
+        call $shr_u_masks_amount
+        call $shl_neg
+        return)
+)"#,
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+        expected_output,
+    );
+}
+
+#[test]
+fn test_i32_shr_s_unsupported() {
+    let err = conversion_error(
+        r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const -8
+        i32.const 1
+        i32.shr_s
+        local.set 0
+        return)
+)"#,
+    );
+    assert!(err.contains("i32.shr_s is not supported by Miden"), "{err}");
+}
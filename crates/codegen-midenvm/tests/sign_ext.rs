@@ -0,0 +1,86 @@
+use expect_test::expect;
+use sem_tests::check_miden;
+use sem_tests::conversion_error;
+
+mod sem_tests;
+
+#[test]
+fn test_i32_extend8_s_extend16_s() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![32767, 4294967168];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $ext8 (result i32)
+        i32.const 384
+        i32.extend8_s
+        return)
+    (func $ext16 (result i32)
+        i32.const 98303
+        i32.extend16_s
+        return)
+    (func $main
+        call $ext8
+        call $ext16
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.ext8.0
+                push.384
+                push.255
+                u32checked_and
+                push.128
+                u32checked_xor
+                push.128
+                u32wrapping_sub
+            end
+
+            proc.ext16.0
+                push.98303
+                push.65535
+                u32checked_and
+                push.32768
+                u32checked_xor
+                push.32768
+                u32wrapping_sub
+            end
+
+            proc.main.0
+                exec.ext8
+                exec.ext16
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_extend32_s_unsupported() {
+    let err = conversion_error(
+        r#"
+(module
+    (start $main)
+    (func $main
+        (local i64)
+        i64.const 12
+        i64.extend32_s
+        local.set 0
+        return)
+)"#,
+    );
+    assert!(
+        err.contains("i64.extend32_s is not supported by Miden"),
+        "{err}"
+    );
+}
@@ -0,0 +1,50 @@
+use ozk_codegen_midenvm::emit_prog;
+use ozk_codegen_midenvm::MidenError;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use pliron::context::Context;
+use sem_tests::compile_to_miden_dialect;
+
+mod sem_tests;
+
+const SOURCE: &str = r#"
+(module
+    (start $main)
+    (func $add (param i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        i32.add
+        return)
+    (func $main
+        i32.const 1
+        i32.const 2
+        call $add
+        drop
+        return)
+)"#;
+
+fn emit(target_config: &MidenTargetConfig) -> Result<String, MidenError> {
+    let wasm = wat::parse_str(SOURCE).unwrap();
+    let mut ctx = Context::default();
+    let prog_op = compile_to_miden_dialect(&mut ctx, &wasm, target_config);
+    emit_prog(&ctx, &prog_op, target_config).map(|inst_buf| inst_buf.pretty_print())
+}
+
+#[test]
+fn within_budget() {
+    let target_config = MidenTargetConfig::default().with_proc_size_budget("add", 1000);
+    assert_eq!(
+        emit(&target_config).unwrap(),
+        emit(&MidenTargetConfig::default()).unwrap()
+    );
+}
+
+#[test]
+fn over_budget() {
+    let target_config = MidenTargetConfig::default()
+        .with_proc_size_budget("add", 1)
+        .with_proc_size_budget("main", 1000);
+    let err = emit(&target_config).unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("add ("), "{msg}");
+    assert!(!msg.contains("main"), "{msg}");
+}
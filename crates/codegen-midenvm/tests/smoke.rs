@@ -1,7 +1,10 @@
 use crate::sem_tests::check_miden;
 
 use expect_test::expect;
+use pliron::context::Context;
 use sem_tests::check_ir;
+use sem_tests::compile;
+use sem_tests::prove_miden;
 
 mod sem_tests;
 
@@ -50,14 +53,34 @@ fn test_smoke_add_wo_imports() {
         expected_output,
         expect![[r#"
             proc.main.0
-            push.1
-            push.2
-            add
+                push.1
+                push.2
+                add
             end
 
             begin
-            exec.main
+                exec.main
             end
         "#]],
     );
 }
+
+#[test]
+fn test_smoke_prove_add() {
+    let wasm = wat::parse_str(
+        r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 1
+        i32.const 2
+        i32.add
+        return)
+)"#,
+    )
+    .unwrap();
+    let mut ctx = Context::default();
+    let program = compile(&mut ctx, &wasm);
+    let stack = prove_miden(program, vec![], vec![]);
+    assert_eq!(stack.first(), Some(&3));
+}
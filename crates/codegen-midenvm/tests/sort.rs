@@ -0,0 +1,17 @@
+mod sem_tests;
+use crate::sem_tests::check_wasm_output;
+
+#[ignore]
+#[test]
+fn test_sort() {
+    // blocked on the lowering of the locals (loc_store) and of the public I/O imports
+    let input = vec![4, 9, 3, 7, 1];
+    let secret_input = vec![];
+    let expected_output = vec![1, 3, 7, 9, 73];
+    let native_output = ozk_rust_wasm_tests_helper::wrap_main_with_io(
+        &ozk_rust_wasm_tests_sort::sort::sort_and_sum,
+    )(input.clone(), secret_input.clone());
+    assert_eq!(native_output, expected_output);
+    let wasm_bytes = ozk_rust_wasm_tests_helper::compile_rust_wasm_tests("sort-bin", "sort");
+    check_wasm_output(&wasm_bytes, input, secret_input, expected_output);
+}
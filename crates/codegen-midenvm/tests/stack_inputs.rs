@@ -0,0 +1,44 @@
+use expect_test::expect;
+use sem_tests::check_miden_for_inputs;
+
+mod sem_tests;
+
+#[test]
+fn test_inputs_stay_below_results() {
+    // the program doesn't read the public inputs, so they are left on the stack
+    check_miden_for_inputs(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $get (result i32)
+        i32.const 3
+        return)
+    (func $main
+        call $get
+        return)
+)"#,
+        vec![
+            (vec![], vec![]),
+            (vec![1], vec![]),
+            (vec![42], vec![7]),
+            (vec![u32::MAX as u64], vec![]),
+        ],
+        |input, _secret_input| std::iter::once(3).chain(input.iter().copied()).collect(),
+        expect![[r#"
+            proc.get.0
+                push.3
+            end
+
+            proc.main.0
+                exec.get
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
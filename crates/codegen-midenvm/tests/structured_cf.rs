@@ -0,0 +1,452 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use ozk_ir_transform::miden::lowering::MidenControlFlow;
+use sem_tests::check_miden_output_with_config;
+use sem_tests::check_miden_with_config;
+use sem_tests::execution_error;
+
+mod sem_tests;
+
+#[test]
+fn test_structured_block_br_if() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![4, 5, 9, 7];
+    check_miden_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $br_if_taken (result i32)
+        (block (result i32)
+            i32.const 7
+            i32.const 1
+            br_if 0
+            drop
+            i32.const 9)
+        return)
+    (func $br_if_not_taken (result i32)
+        (block (result i32)
+            i32.const 7
+            i32.const 0
+            br_if 0
+            drop
+            i32.const 9)
+        return)
+    (func $nested_br_if (result i32)
+        (block (result i32)
+            (block
+                i32.const 5
+                i32.const 1
+                br_if 1
+                drop)
+            i32.const 8)
+        return)
+    (func $early_return (result i32)
+        i32.const 1
+        (if
+            (then
+                i32.const 4
+                return))
+        i32.const 5
+        return)
+    (func $main
+        call $br_if_taken
+        call $br_if_not_taken
+        call $nested_br_if
+        call $early_return
+        return)
+)"#,
+        &MidenTargetConfig::default().with_control_flow(MidenControlFlow::Structured),
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.br_if_taken.0
+                push.7
+                push.1
+                push.0
+                neq
+                if.true
+                    push.1
+                else
+                    drop
+                    push.9
+                    push.0
+                end
+                drop
+            end
+
+            proc.br_if_not_taken.0
+                push.7
+                push.0
+                push.0
+                neq
+                if.true
+                    push.1
+                else
+                    drop
+                    push.9
+                    push.0
+                end
+                drop
+            end
+
+            proc.nested_br_if.0
+                push.5
+                push.1
+                push.0
+                neq
+                if.true
+                    push.2
+                else
+                    drop
+                    push.0
+                end
+                dup.0
+                push.0
+                neq
+                sub
+                dup.0
+                push.0
+                eq
+                if.true
+                    drop
+                    push.8
+                    push.0
+                end
+                drop
+            end
+
+            proc.early_return.0
+                push.1
+                push.0
+                neq
+                if.true
+                    push.4
+                    push.2
+                else
+                    push.0
+                end
+                dup.0
+                push.0
+                neq
+                sub
+                dup.0
+                push.0
+                eq
+                if.true
+                    drop
+                    push.5
+                    push.0
+                end
+                drop
+            end
+
+            proc.main.0
+                exec.br_if_taken
+                exec.br_if_not_taken
+                exec.nested_br_if
+                exec.early_return
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_structured_br_if_fused_eqz() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![9, 9, 7];
+    check_miden_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $i32_eqz (result i32)
+        (block (result i32)
+            i32.const 7
+            i32.const 0
+            i32.eqz
+            br_if 0
+            drop
+            i32.const 9)
+        return)
+    (func $i64_eqz (result i32)
+        (block (result i32)
+            i32.const 7
+            i64.const 5
+            i64.eqz
+            br_if 0
+            drop
+            i32.const 9)
+        return)
+    (func $i64_eqz_eqz (result i32)
+        (block (result i32)
+            i32.const 7
+            i64.const 0
+            i64.eqz
+            i32.eqz
+            br_if 0
+            drop
+            i32.const 9)
+        return)
+    (func $main
+        call $i32_eqz
+        call $i64_eqz
+        call $i64_eqz_eqz
+        return)
+)"#,
+        &MidenTargetConfig::default().with_control_flow(MidenControlFlow::Structured),
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.i32_eqz.0
+                push.7
+                push.0
+                push.0
+                eq
+                if.true
+                    push.1
+                else
+                    drop
+                    push.9
+                    push.0
+                end
+                drop
+            end
+
+            proc.i64_eqz.0
+                push.7
+                push.5
+                push.0
+                eq
+                if.true
+                    push.1
+                else
+                    drop
+                    push.9
+                    push.0
+                end
+                drop
+            end
+
+            proc.i64_eqz_eqz.0
+                push.7
+                push.0
+                push.0
+                neq
+                if.true
+                    push.1
+                else
+                    drop
+                    push.9
+                    push.0
+                end
+                drop
+            end
+
+            proc.main.0
+                exec.i32_eqz
+                exec.i64_eqz
+                exec.i64_eqz_eqz
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_structured_br_table() {
+    // the index is kept in a reserved slot, a case passing the value to the outer block
+    // discards the 100 below it
+    check_miden_output_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $case_0 (result i32)
+        (block (result i32)
+            i32.const 100
+            (block (result i32)
+                i32.const 10
+                i32.const 0
+                br_table 0 1)
+            i32.add)
+        return)
+    (func $case_1 (result i32)
+        (block (result i32)
+            i32.const 100
+            (block (result i32)
+                i32.const 10
+                i32.const 1
+                br_table 0 1)
+            i32.add)
+        return)
+    (func $case_default (result i32)
+        (block (result i32)
+            i32.const 100
+            (block (result i32)
+                i32.const 10
+                i32.const 7
+                br_table 0 1)
+            i32.add)
+        return)
+    (func $main
+        call $case_0
+        call $case_1
+        call $case_default
+        return)
+)"#,
+        &MidenTargetConfig::default().with_control_flow(MidenControlFlow::Structured),
+        vec![],
+        vec![],
+        vec![10, 10, 110],
+    );
+}
+
+#[test]
+fn test_structured_loop() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![3, 6];
+    check_miden_with_config(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $loop_exit (result i32)
+        (block
+            (loop
+                i32.const 1
+                br_if 1
+                br 0))
+        i32.const 6
+        return)
+    (func $loop_fall_through (result i32)
+        (loop
+            i32.const 0
+            br_if 0)
+        i32.const 3
+        return)
+    (func $main
+        call $loop_exit
+        call $loop_fall_through
+        return)
+)"#,
+        &MidenTargetConfig::default().with_control_flow(MidenControlFlow::Structured),
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.loop_exit.0
+                push.0
+                push.1
+                while.true
+                    drop
+                    push.1
+                    push.0
+                    neq
+                    if.true
+                        push.2
+                    else
+                        push.1
+                    end
+                    dup.0
+                    push.1
+                    eq
+                end
+                dup.0
+                push.0
+                neq
+                sub
+                dup.0
+                push.0
+                eq
+                if.true
+                    drop
+                    push.0
+                end
+                drop
+                push.6
+            end
+
+            proc.loop_fall_through.0
+                push.0
+                push.1
+                while.true
+                    drop
+                    push.0
+                    push.0
+                    neq
+                    if.true
+                        push.1
+                    else
+                        push.0
+                    end
+                    dup.0
+                    push.1
+                    eq
+                end
+                drop
+                push.3
+            end
+
+            proc.main.0
+                exec.loop_exit
+                exec.loop_fall_through
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_structured_with_memory_check() {
+    let target_config = MidenTargetConfig::default()
+        .with_control_flow(MidenControlFlow::Structured)
+        .with_memory_check();
+    assert_eq!(
+        target_config.options().control_flow,
+        MidenControlFlow::Structured
+    );
+    assert!(target_config.options().memory_check);
+    // the out-of-bounds store in the block aborts
+    execution_error(
+        r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        (block
+            i32.const 65536
+            i32.const 1
+            i32.store
+            i32.const 1
+            br_if 0)
+        return)
+)"#,
+        &target_config,
+        vec![],
+        vec![],
+    );
+}
@@ -0,0 +1,59 @@
+use expect_test::expect;
+use sem_tests::check_miden;
+
+mod sem_tests;
+
+#[test]
+fn test_i32_sub_mul() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![21, 12];
+    check_miden(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $sub (result i32)
+        i32.const 17
+        i32.const 5
+        i32.sub
+        return)
+    (func $mul (result i32)
+        i32.const 3
+        i32.const 7
+        i32.mul
+        return)
+    (func $main
+        call $sub
+        call $mul
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.sub.0
+                push.17
+                push.5
+                u32wrapping_sub
+            end
+
+            proc.mul.0
+                push.3
+                push.7
+                u32wrapping_mul
+            end
+
+            proc.main.0
+                exec.sub
+                exec.mul
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
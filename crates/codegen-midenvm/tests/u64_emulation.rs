@@ -0,0 +1,227 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use ozk_ir_transform::u64_emulation::U64Emulation;
+use sem_tests::check_miden_with_config;
+use sem_tests::execution_error;
+
+mod sem_tests;
+
+const IN_RANGE_WAT: &str = r#"
+(module
+    (type (;0;) (func (result i64)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $add (result i64)
+        i64.const 2
+        i64.const 3
+        i64.add
+        return)
+    (func $sub (result i64)
+        i64.const 17
+        i64.const 5
+        i64.sub
+        return)
+    (func $mul (result i64)
+        i64.const 3
+        i64.const 7
+        i64.mul
+        return)
+    (func $main
+        call $add
+        call $sub
+        call $mul
+        return)
+)"#;
+
+#[test]
+fn test_i64_in_range_unchecked() {
+    check_miden_with_config(
+        IN_RANGE_WAT,
+        &MidenTargetConfig::default().with_u64_emulation(U64Emulation::Unchecked),
+        vec![],
+        vec![],
+        vec![21, 12, 5],
+        expect![[r#"
+            proc.add.0
+                push.2
+                push.3
+                add
+            end
+
+            proc.sub.0
+                push.17
+                push.5
+                sub
+            end
+
+            proc.mul.0
+                push.3
+                push.7
+                mul
+            end
+
+            proc.main.0
+                exec.add
+                exec.sub
+                exec.mul
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_in_range_checked() {
+    check_miden_with_config(
+        IN_RANGE_WAT,
+        &MidenTargetConfig::default().with_u64_emulation(U64Emulation::Checked),
+        vec![],
+        vec![],
+        vec![21, 12, 5],
+        expect![[r#"
+            proc.add.0
+                push.2
+                push.3
+                dup.1
+                add
+                dup.0
+                swap.2
+                gte
+                assert
+            end
+
+            proc.sub.0
+                push.17
+                push.5
+                dup.1
+                dup.1
+                gte
+                assert
+                sub
+            end
+
+            proc.mul.0
+                push.3
+                push.7
+                u32assert.2
+                mul
+            end
+
+            proc.main.0
+                exec.add
+                exec.sub
+                exec.mul
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+const ADD_OVERFLOW_WAT: &str = r#"
+(module
+    (start $main)
+    (func $main
+        i64.const 0x7fffffffffffffff
+        i64.const 0x7fffffffffffffff
+        i64.add
+        return)
+)"#;
+
+const SUB_OVERFLOW_WAT: &str = r#"
+(module
+    (start $main)
+    (func $main
+        i64.const 5
+        i64.const 17
+        i64.sub
+        return)
+)"#;
+
+const MUL_OVERFLOW_WAT: &str = r#"
+(module
+    (start $main)
+    (func $main
+        i64.const 0x100000000
+        i64.const 0x100000000
+        i64.mul
+        return)
+)"#;
+
+#[test]
+fn test_i64_overflow_unchecked_wraps_around_field_modulus() {
+    let config = MidenTargetConfig::default().with_u64_emulation(U64Emulation::Unchecked);
+    // field modulus p = 2^64 - 2^32 + 1
+    // (2^63 - 1) * 2 - p = 2^32 - 3
+    check_miden_with_config(
+        ADD_OVERFLOW_WAT,
+        &config,
+        vec![],
+        vec![],
+        vec![4294967293],
+        expect![[r#"
+            proc.main.0
+                push.9223372036854775807
+                push.9223372036854775807
+                add
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+    // 5 - 17 + p
+    check_miden_with_config(
+        SUB_OVERFLOW_WAT,
+        &config,
+        vec![],
+        vec![],
+        vec![18446744069414584309],
+        expect![[r#"
+            proc.main.0
+                push.5
+                push.17
+                sub
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+    // 2^64 mod p = 2^32 - 1
+    check_miden_with_config(
+        MUL_OVERFLOW_WAT,
+        &config,
+        vec![],
+        vec![],
+        vec![4294967295],
+        expect![[r#"
+            proc.main.0
+                push.4294967296
+                push.4294967296
+                mul
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_i64_overflow_checked_fails() {
+    let config = MidenTargetConfig::default().with_u64_emulation(U64Emulation::Checked);
+    for wat in [ADD_OVERFLOW_WAT, SUB_OVERFLOW_WAT, MUL_OVERFLOW_WAT] {
+        // panics if the execution succeeds
+        execution_error(wat, &config, vec![], vec![]);
+    }
+}
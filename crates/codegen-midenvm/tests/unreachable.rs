@@ -0,0 +1,72 @@
+use expect_test::expect;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use sem_tests::check_miden;
+use sem_tests::execution_error;
+
+mod sem_tests;
+
+fn unreachable_wat(value: i32) -> String {
+    format!(
+        r#"
+(module
+    (type (;0;) (func (result i32)))
+    (type (;2;) (func))
+    (export "main" (func $main))
+    (start $main)
+    (func $check (result i32)
+        i32.const {value}
+        (if
+            (then
+                unreachable))
+        i32.const 3
+        return)
+    (func $main
+        call $check
+        return)
+)"#
+    )
+}
+
+#[test]
+fn test_unreachable_not_reached() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = vec![3];
+    check_miden(
+        &unreachable_wat(0),
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            proc.check.0
+                push.0
+                push.0
+                neq
+                if.true
+                    push.0
+                    assert
+                end
+                push.3
+            end
+
+            proc.main.0
+                exec.check
+            end
+
+            begin
+                exec.main
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn test_unreachable_traps() {
+    // panics if the execution succeeds
+    execution_error(
+        &unreachable_wat(1),
+        &MidenTargetConfig::default(),
+        vec![],
+        vec![],
+    );
+}
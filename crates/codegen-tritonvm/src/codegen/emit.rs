@@ -22,7 +22,10 @@ pub fn emit_inst(
     func_names: &HashMap<FuncIndex, String>,
 ) -> Result<(), TritonError> {
     match ins {
-        Inst::Unreachable => (),
+        Inst::Unreachable => sink.append(vec![
+            AnInstruction::Push(0u32.into()),
+            AnInstruction::Assert,
+        ]),
         Inst::Nop => sink.push(AnInstruction::Nop),
         Inst::End => sink.push(AnInstruction::Return),
         Inst::Return => sink.push(AnInstruction::Return),
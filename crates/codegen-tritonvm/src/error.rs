@@ -1,5 +1,16 @@
+use ozk_artifact::ArtifactError;
+
 #[derive(Debug)]
 pub enum TritonError {
     UnexpectedInst(String),
     InvalidInst(String),
+    InvalidTasm(String),
+    Io(std::io::Error),
+    Artifact(ArtifactError),
+}
+
+impl From<ArtifactError> for TritonError {
+    fn from(e: ArtifactError) -> Self {
+        TritonError::Artifact(e)
+    }
 }
@@ -22,10 +22,12 @@ mod codegen;
 mod config;
 mod error;
 mod target;
+mod tasm;
 mod ty;
 
 pub use crate::codegen::*;
 pub use crate::config::*;
 pub use crate::error::*;
 pub use crate::target::*;
+pub use crate::tasm::*;
 pub use crate::ty::*;
@@ -0,0 +1,88 @@
+//! `.tasm` files: TritonVM assembly with the artifact metadata in the header comment.
+//!
+//! The first line is `// ozk-artifact: <metadata JSON>`, the rest is the program source.
+
+use std::path::Path;
+
+use ozk_artifact::CompilationArtifact;
+use ozk_artifact::Program;
+use ozk_artifact::Target;
+
+use crate::TritonError;
+
+/// File extension of the TritonVM assembly files
+pub const TASM_EXTENSION: &str = "tasm";
+
+const HEADER_PREFIX: &str = "// ozk-artifact: ";
+
+/// Render the artifact as `.tasm` file contents
+pub fn to_tasm(artifact: &CompilationArtifact) -> Result<String, TritonError> {
+    artifact.check_target(Target::Triton)?;
+    let Program::Source(source) = &artifact.program else {
+        return Err(TritonError::InvalidTasm(
+            "only the source form can be saved as .tasm".to_string(),
+        ));
+    };
+    let mut metadata = artifact.clone();
+    metadata.program = Program::Source(String::new());
+    let metadata_json =
+        serde_json::to_string(&metadata).map_err(|e| TritonError::InvalidTasm(e.to_string()))?;
+    Ok(format!("{HEADER_PREFIX}{metadata_json}\n{source}"))
+}
+
+/// Parse the `.tasm` file contents
+pub fn from_tasm(tasm: &str) -> Result<CompilationArtifact, TritonError> {
+    let (header, source) = tasm.split_once('\n').unwrap_or((tasm, ""));
+    let metadata_json = header.strip_prefix(HEADER_PREFIX).ok_or_else(|| {
+        TritonError::InvalidTasm(format!(
+            "expected the header line starting with {HEADER_PREFIX:?}"
+        ))
+    })?;
+    let mut artifact = CompilationArtifact::from_json(metadata_json)?;
+    artifact.check_target(Target::Triton)?;
+    artifact.program = Program::Source(source.to_string());
+    Ok(artifact)
+}
+
+/// Save the artifact as a `.tasm` file
+pub fn save_tasm(artifact: &CompilationArtifact, path: &Path) -> Result<(), TritonError> {
+    std::fs::write(path, to_tasm(artifact)?).map_err(TritonError::Io)
+}
+
+/// Load the artifact from a `.tasm` file
+pub fn load_tasm(path: &Path) -> Result<CompilationArtifact, TritonError> {
+    from_tasm(&std::fs::read_to_string(path).map_err(TritonError::Io)?)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tasm_roundtrip() {
+        let mut artifact = CompilationArtifact::new(
+            Target::Triton,
+            Program::Source("call main\nhalt\nmain:\npush 1\nreturn".to_string()),
+        );
+        artifact.passes = vec!["wasm-explicit-func-args".to_string()];
+        let tasm = to_tasm(&artifact).unwrap();
+        assert!(tasm.starts_with(HEADER_PREFIX));
+        assert!(tasm.ends_with("\ncall main\nhalt\nmain:\npush 1\nreturn"));
+        assert_eq!(from_tasm(&tasm).unwrap(), artifact);
+    }
+
+    #[test]
+    fn missing_header() {
+        assert!(matches!(
+            from_tasm("call main\nhalt"),
+            Err(TritonError::InvalidTasm(_))
+        ));
+    }
+
+    #[test]
+    fn wrong_target() {
+        let artifact = CompilationArtifact::new(Target::Miden, Program::Source(String::new()));
+        assert!(matches!(to_tasm(&artifact), Err(TritonError::Artifact(_))));
+    }
+}
@@ -0,0 +1,33 @@
+use ozk_rust_wasm_tests_helper::conformance::run_conformance_suite;
+use ozk_rust_wasm_tests_helper::conformance::CaseOutcome;
+use ozk_rust_wasm_tests_helper::conformance::ConformanceCase;
+use ozk_rust_wasm_tests_helper::conformance::ConformanceTarget;
+use ozk_rust_wasm_tests_helper::conformance::Feature;
+
+struct TritonTarget;
+
+impl ConformanceTarget for TritonTarget {
+    fn name(&self) -> &str {
+        "triton"
+    }
+
+    /// The Triton codegen is disabled until it's ported to the pliron IR,
+    /// so every case is reported as skipped
+    fn supports(&self, _feature: Feature) -> bool {
+        false
+    }
+
+    fn check(&self, case: &ConformanceCase) -> Result<(), String> {
+        Err(format!("{}: the Triton codegen is disabled", case.name))
+    }
+}
+
+#[test]
+fn test_conformance() {
+    let report = run_conformance_suite(&TritonTarget);
+    eprintln!("{report}");
+    assert!(report
+        .outcomes
+        .iter()
+        .all(|(_, _, outcome)| *outcome == CaseOutcome::Skip));
+}
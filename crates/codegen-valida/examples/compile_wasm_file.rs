@@ -0,0 +1,27 @@
+//! Compile a Wasm binary (e.g. built from Rust) to Valida and run it on the Valida emulator.
+//!
+//! Without arguments the `add` bin of the Rust-to-Wasm test bundle is built and compiled:
+//!
+//! ```sh
+//! cargo run -p ozk-codegen-valida --example compile_wasm_file -- path/to/module.wasm
+//! ```
+
+use ozk_codegen_valida::compile_wasm;
+use ozk_codegen_valida::emulator::run_program;
+use ozk_rust_wasm_tests_helper::build_rust_wasm_tests;
+use ozk_rust_wasm_tests_helper::RustWasmBuildOptions;
+use valida_machine::PublicMemory;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let wasm = match std::env::args().nth(1) {
+        Some(path) => std::fs::read(path)?,
+        None => build_rust_wasm_tests("add-bin", "add", &RustWasmBuildOptions::default())?,
+    };
+    // the Rust-built modules use i64, which is not supported by Valida yet,
+    // in this case the conversion error is reported
+    let program = compile_wasm(&wasm)?;
+    println!("{} instructions", program.len());
+    let execution = run_program(program, PublicMemory::default())?;
+    println!("return value: {:?}", execution.return_value);
+    Ok(())
+}
@@ -0,0 +1,29 @@
+//! Compile a WAT module to Valida and run it on the Valida emulator.
+//!
+//! ```sh
+//! cargo run -p ozk-codegen-valida --example compile_wat
+//! ```
+
+use ozk_codegen_valida::compile_wasm;
+use ozk_codegen_valida::emulator::run_program;
+use valida_machine::PublicMemory;
+
+const WAT: &str = r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 30
+        i32.const 12
+        i32.add
+        return)
+)
+"#;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let wasm = wat::parse_str(WAT)?;
+    let program = compile_wasm(&wasm)?;
+    println!("{} instructions", program.len());
+    let execution = run_program(program, PublicMemory::default())?;
+    println!("return value: {:?}", execution.return_value);
+    Ok(())
+}
@@ -2,4 +2,77 @@ mod emit;
 pub use emit::*;
 
 mod valida_inst_builder;
+use ozk_frontend_wasm::WasmFrontendConfig;
+use ozk_ir_transform::wasm::single_func::run_passes_on_func;
+use ozk_ir_transform::wasm::single_func::run_passes_on_module;
+use ozk_valida_dialect::ops::ProgramOp;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::op::Op;
+use pliron::with_context::AttachContext;
 pub use valida_inst_builder::*;
+use valida_machine::InstructionWord;
+
+use crate::ValidaError;
+use crate::ValidaTargetConfig;
+
+/// Parse the Wasm binary and run the Valida passes on it, returning the Valida program.
+pub fn compile_module(
+    ctx: &mut Context,
+    source: &[u8],
+    frontend_config: &WasmFrontendConfig,
+    target_config: &ValidaTargetConfig,
+) -> Result<ProgramOp, ValidaError> {
+    target_config.register(ctx);
+    let wasm_module_op = ozk_frontend_wasm::translate(ctx, source, frontend_config)?;
+    let op = run_passes_on_module(ctx, wasm_module_op, &target_config.pass_manager)
+        .map_err(|e| ValidaError::Conversion(e.to_string()))?;
+    let Ok(prog_op) = op.deref(ctx).get_op(ctx).downcast::<ProgramOp>() else {
+        return Err(ValidaError::Conversion(format!(
+            "expected valida.program, got {}",
+            op.deref(ctx).with_ctx(ctx)
+        )));
+    };
+    Ok(*prog_op)
+}
+
+/// Compile the Wasm binary with the default configs and return the program instructions.
+pub fn compile_wasm(source: &[u8]) -> Result<Vec<InstructionWord<i32>>, ValidaError> {
+    let mut ctx = Context::default();
+    let target_config = ValidaTargetConfig::default();
+    let prog_op = compile_module(
+        &mut ctx,
+        source,
+        &WasmFrontendConfig::default(),
+        &target_config,
+    )?;
+    let mut builder = ValidaInstrBuilder::default();
+    emit_op(&ctx, prog_op.get_operation(), &mut builder);
+    Ok(builder.build())
+}
+
+/// Compile a single Wasm function and return the instructions of its body.
+/// Lets the lowering tests target a particular construct without crafting a whole module
+/// (see [run_passes_on_func] for the requirements on the function).
+pub fn compile_function(
+    ctx: &mut Context,
+    func_op: wasm::FuncOp,
+    target_config: &ValidaTargetConfig,
+) -> Result<Vec<InstructionWord<i32>>, ValidaError> {
+    let func_sym = func_op.get_symbol_name(ctx);
+    let op = run_passes_on_func(ctx, func_op, &target_config.pass_manager)
+        .map_err(|e| ValidaError::Conversion(e.to_string()))?;
+    let Ok(prog_op) = op.deref(ctx).get_op(ctx).downcast::<ProgramOp>() else {
+        return Err(ValidaError::Conversion(format!(
+            "expected valida.program, got {}",
+            op.deref(ctx).with_ctx(ctx)
+        )));
+    };
+    let valida_func_op = prog_op
+        .get_func(ctx, &func_sym)
+        .ok_or_else(|| ValidaError::Conversion(format!("function {func_sym} not found")))?;
+    let mut builder = ValidaInstrBuilder::default();
+    emit_op(ctx, valida_func_op.get_operation(), &mut builder);
+    Ok(builder.build())
+}
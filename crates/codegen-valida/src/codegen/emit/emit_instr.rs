@@ -1,13 +1,25 @@
 use intertrait::cast_to;
 use ozk_valida_dialect::op_interfaces::HasOperands;
 use ozk_valida_dialect::ops::AddOp;
+use ozk_valida_dialect::ops::AndOp;
+use ozk_valida_dialect::ops::BeqOp;
+use ozk_valida_dialect::ops::DivOp;
 use ozk_valida_dialect::ops::ExitOp;
 use ozk_valida_dialect::ops::FuncOp;
 use ozk_valida_dialect::ops::Imm32Op;
 use ozk_valida_dialect::ops::JalOp;
 use ozk_valida_dialect::ops::JalvOp;
+use ozk_valida_dialect::ops::LabelOp;
+use ozk_valida_dialect::ops::LtOp;
+use ozk_valida_dialect::ops::MulOp;
+use ozk_valida_dialect::ops::OrOp;
 use ozk_valida_dialect::ops::ProgramOp;
+use ozk_valida_dialect::ops::ShlOp;
+use ozk_valida_dialect::ops::ShrOp;
+use ozk_valida_dialect::ops::SubOp;
 use ozk_valida_dialect::ops::SwOp;
+use ozk_valida_dialect::ops::TrapOp;
+use ozk_valida_dialect::ops::XorOp;
 use pliron::context::Context;
 use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
@@ -26,6 +38,20 @@ impl EmitInstr for ExitOp {
     }
 }
 
+#[cast_to]
+impl EmitInstr for TrapOp {
+    fn emit_instr(&self, _ctx: &Context, builder: &mut ValidaInstrBuilder) {
+        builder.trap();
+    }
+}
+
+#[cast_to]
+impl EmitInstr for LabelOp {
+    fn emit_instr(&self, _ctx: &Context, _builder: &mut ValidaInstrBuilder) {
+        // labels are resolved to pc and take no space in the program
+    }
+}
+
 #[cast_to]
 impl EmitInstr for ProgramOp {
     fn emit_instr(&self, ctx: &Context, builder: &mut ValidaInstrBuilder) {
@@ -72,6 +98,16 @@ macro_rules! emit_instr {
 
 emit_instr!(Imm32Op, imm32);
 emit_instr!(AddOp, add);
+emit_instr!(SubOp, sub);
+emit_instr!(MulOp, mul);
+emit_instr!(DivOp, div);
+emit_instr!(AndOp, and);
+emit_instr!(OrOp, or);
+emit_instr!(XorOp, xor);
+emit_instr!(ShlOp, shl);
+emit_instr!(ShrOp, shr);
+emit_instr!(LtOp, lt);
 emit_instr!(JalvOp, jalv);
 emit_instr!(JalOp, jal);
 emit_instr!(SwOp, sw);
+emit_instr!(BeqOp, beq);
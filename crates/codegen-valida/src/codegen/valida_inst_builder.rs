@@ -1,6 +1,16 @@
 use ozk_valida_dialect::types::Operands;
 use valida_alu_u32::add::Add32Instruction;
+use valida_alu_u32::bitwise::And32Instruction;
+use valida_alu_u32::bitwise::Or32Instruction;
+use valida_alu_u32::bitwise::Xor32Instruction;
+use valida_alu_u32::div::Div32Instruction;
+use valida_alu_u32::lt::Lt32Instruction;
+use valida_alu_u32::mul::Mul32Instruction;
+use valida_alu_u32::shift::Shl32Instruction;
+use valida_alu_u32::shift::Shr32Instruction;
+use valida_alu_u32::sub::Sub32Instruction;
 use valida_basic::BasicMachine;
+use valida_cpu::BeqInstruction;
 use valida_cpu::Imm32Instruction;
 use valida_cpu::JalInstruction;
 use valida_cpu::JalvInstruction;
@@ -33,8 +43,21 @@ impl ValidaInstrBuilder {
             operands: valida_machine::Operands::default(),
         });
     }
+
+    /// Emit an instruction that fails the execution.
+    /// Valida has no dedicated instruction, so the opcode no chip implements is used,
+    /// executing it aborts the machine.
+    pub fn trap(&mut self) {
+        self.sink.push(InstructionWord {
+            opcode: TRAP_OPCODE,
+            operands: valida_machine::Operands::default(),
+        });
+    }
 }
 
+/// Opcode of the [ValidaInstrBuilder::trap] instruction, not implemented by any chip
+pub const TRAP_OPCODE: u32 = u32::MAX;
+
 macro_rules! impl_op {
     ($op:ident, $valida_op:ty) => {
         impl ValidaInstrBuilder {
@@ -49,7 +72,17 @@ macro_rules! impl_op {
 }
 
 impl_op!(add, Add32Instruction);
+impl_op!(sub, Sub32Instruction);
+impl_op!(mul, Mul32Instruction);
+impl_op!(div, Div32Instruction);
+impl_op!(and, And32Instruction);
+impl_op!(or, Or32Instruction);
+impl_op!(xor, Xor32Instruction);
+impl_op!(shl, Shl32Instruction);
+impl_op!(shr, Shr32Instruction);
+impl_op!(lt, Lt32Instruction);
 impl_op!(imm32, Imm32Instruction);
 impl_op!(jalv, JalvInstruction);
 impl_op!(jal, JalInstruction);
 impl_op!(sw, Store32Instruction);
+impl_op!(beq, BeqInstruction);
@@ -1,11 +1,25 @@
 #![allow(unused_imports)]
 
+use ozk_ir_transform::byte_layout::ByteLayout;
+use ozk_ir_transform::ir_diff;
+use ozk_ir_transform::valida::cleanup::ValidaCleanupPass;
 use ozk_ir_transform::valida::lowering::arith_op_lowering::WasmToValidaArithLoweringPass;
 use ozk_ir_transform::valida::lowering::func_lowering::WasmToValidaFuncLoweringPass;
 use ozk_ir_transform::valida::lowering::module_lowering::WasmToValidaModuleLoweringPass;
 use ozk_ir_transform::valida::lowering::resolve_target_sym_to_pc::ValidaResolveTargetSymToPcPass;
 use ozk_ir_transform::valida::lowering::WasmToValidaFinalLoweringPass;
 use ozk_ir_transform::valida::track_pc::ValidaTrackProgramCounterPass;
+use ozk_ir_transform::wasm::br_if_fusion::WasmBrIfFusionPass;
+use ozk_ir_transform::wasm::canonicalize::WasmCanonicalizePass;
+use ozk_ir_transform::wasm::data_init::WasmDataInitPass;
+use ozk_ir_transform::wasm::foreign_imports::WasmForeignImportsCheckPass;
+use ozk_ir_transform::wasm::globals_init::WasmGlobalsInitPass;
+use ozk_ir_transform::wasm::import_globals::WasmImportGlobalsPass;
+use ozk_ir_transform::wasm::intrinsics::WasmIntrinsicsToOzkPass;
+use ozk_ir_transform::wasm::link_check::WasmLinkCheckPass;
+use ozk_ir_transform::wasm::multi_memory::WasmMultiMemoryPass;
+use ozk_ir_transform::wasm::prologue::WasmEmitProloguePass;
+use ozk_ir_transform::wasm::rename_symbols::WasmRenameSymbolsPass;
 use ozk_ir_transform::wasm::resolve_call_op::WasmCallOpToOzkCallOpPass;
 use ozk_ir_transform::wasm::track_stack_depth::WasmTrackStackDepthPass;
 use pliron::context::Context;
@@ -13,22 +27,41 @@ use pliron::pass::PassManager;
 
 pub struct ValidaTargetConfig {
     pub pass_manager: PassManager,
+    /// Layout of the Wasm memory bytes in the memory cells
+    pub byte_layout: ByteLayout,
 }
 
 impl Default for ValidaTargetConfig {
     fn default() -> Self {
-        let mut pass_manager = PassManager::new();
-        pass_manager.add_pass(Box::<WasmCallOpToOzkCallOpPass>::default());
-        pass_manager.add_pass(Box::new(
-            WasmTrackStackDepthPass::new_reserve_space_for_locals(),
-        ));
-        pass_manager.add_pass(Box::<WasmToValidaArithLoweringPass>::default());
-        pass_manager.add_pass(Box::<WasmToValidaFuncLoweringPass>::default());
-        pass_manager.add_pass(Box::<WasmToValidaModuleLoweringPass>::default());
-        pass_manager.add_pass(Box::<ValidaTrackProgramCounterPass>::default());
-        pass_manager.add_pass(Box::<ValidaResolveTargetSymToPcPass>::default());
-        pass_manager.add_pass(Box::<WasmToValidaFinalLoweringPass>::default());
-        Self { pass_manager }
+        let pass_manager = ir_diff::new_pass_manager(vec![
+            Box::<WasmCanonicalizePass>::default(),
+            Box::<WasmMultiMemoryPass>::default(),
+            Box::<WasmImportGlobalsPass>::default(),
+            Box::<WasmGlobalsInitPass>::default(),
+            Box::<WasmDataInitPass>::default(),
+            Box::<WasmEmitProloguePass>::default(),
+            Box::<WasmForeignImportsCheckPass>::default(),
+            // the functions are called by pc, no keywords to avoid
+            Box::new(WasmRenameSymbolsPass::new(&[])),
+            // no cycle counter and no instruction to abort the execution
+            Box::new(WasmIntrinsicsToOzkPass::new("Valida", false, false)),
+            Box::<WasmCallOpToOzkCallOpPass>::default(),
+            Box::<WasmLinkCheckPass>::default(),
+            // before the stack depth tracking, it drops the eqz ops
+            Box::<WasmBrIfFusionPass>::default(),
+            Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+            Box::<WasmToValidaArithLoweringPass>::default(),
+            Box::<WasmToValidaFuncLoweringPass>::default(),
+            Box::<WasmToValidaModuleLoweringPass>::default(),
+            Box::<ValidaCleanupPass>::default(),
+            Box::<ValidaTrackProgramCounterPass>::default(),
+            Box::<ValidaResolveTargetSymToPcPass>::default(),
+            Box::<WasmToValidaFinalLoweringPass>::default(),
+        ]);
+        Self {
+            pass_manager,
+            byte_layout: ByteLayout::default(),
+        }
     }
 }
 
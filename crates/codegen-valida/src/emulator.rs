@@ -0,0 +1,58 @@
+//! Running the compiled program on the reference Valida emulator.
+
+use ozk_valida_dialect::ops::ProgramOp;
+use pliron::context::Context;
+use pliron::op::Op;
+use valida_basic::BasicMachine;
+use valida_cpu::MachineWithCpuChip;
+use valida_machine::InstructionWord;
+use valida_machine::Machine;
+use valida_machine::ProgramROM;
+use valida_machine::PublicMemory;
+use valida_machine::Word;
+use valida_memory::MachineWithMemoryChip;
+
+use crate::emit_op;
+use crate::ValidaError;
+use crate::ValidaInstrBuilder;
+
+/// Frame pointer of the program entry
+pub const ENTRY_FP: u32 = 0x1000;
+
+/// Result of the program execution
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidaExecution {
+    /// Value returned by the start function
+    pub return_value: Word<u8>,
+}
+
+/// Emit the program and run it on the emulator with the given public memory.
+pub fn run_prog(
+    ctx: &Context,
+    prog_op: &ProgramOp,
+    public_mem: PublicMemory,
+) -> Result<ValidaExecution, ValidaError> {
+    let mut builder = ValidaInstrBuilder::default();
+    emit_op(ctx, prog_op.get_operation(), &mut builder);
+    run_program(builder.build(), public_mem)
+}
+
+/// Run the emitted program on the emulator with the given public memory.
+pub fn run_program(
+    program: Vec<InstructionWord<i32>>,
+    public_mem: PublicMemory,
+) -> Result<ValidaExecution, ValidaError> {
+    let mut machine = BasicMachine::default();
+    let rom = ProgramROM::new(program);
+    machine.cpu_mut().fp = ENTRY_FP;
+    machine.cpu_mut().save_register_state();
+    machine.run(rom, public_mem);
+    // the start function stores its result in the caller's frame
+    let return_value = machine
+        .mem()
+        .cells
+        .get(&(ENTRY_FP + 4))
+        .copied()
+        .ok_or(ValidaError::NoReturnValue)?;
+    Ok(ValidaExecution { return_value })
+}
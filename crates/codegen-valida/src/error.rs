@@ -1,3 +1,4 @@
+use ozk_frontend_wasm::WasmError;
 use thiserror::Error;
 
 use crate::EmitError;
@@ -8,6 +9,12 @@ pub enum ValidaError {
     InvalidInst(String),
     #[error("Emit error: {0:?}")]
     Emit(#[from] EmitError),
+    #[error("Wasm frontend error: {0}")]
+    Frontend(#[from] WasmError),
+    #[error("Conversion error: {0}")]
+    Conversion(String),
+    #[error("The program did not store a return value")]
+    NoReturnValue,
     // #[error("Topological sort error: {0:?}")]
     // TopoSortError(#[from] TopoSortError),
 }
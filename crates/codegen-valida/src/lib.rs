@@ -22,6 +22,9 @@ mod codegen;
 mod config;
 mod error;
 
+#[cfg(feature = "emulator")]
+pub mod emulator;
+
 pub use crate::codegen::*;
 pub use crate::config::*;
 pub use crate::error::*;
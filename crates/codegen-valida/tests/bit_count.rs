@@ -0,0 +1,28 @@
+use sem_tests::run_valida;
+use valida_machine::Word;
+
+mod sem_tests;
+
+#[test]
+fn test_i32_popcnt_ctz_clz() {
+    let wasm = wat::parse_str(
+        r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 61680
+        i32.popcnt
+        i32.const 40
+        i32.ctz
+        i32.add
+        i32.const 1
+        i32.clz
+        i32.add
+        return)
+)
+"#,
+    )
+    .unwrap();
+    // 8 + 3 + 31
+    assert_eq!(run_valida(&wasm), Word::from(42u32));
+}
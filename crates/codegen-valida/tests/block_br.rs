@@ -0,0 +1,124 @@
+use sem_tests::run_valida;
+use valida_machine::Word;
+
+mod sem_tests;
+
+#[test]
+fn test_loop_sum() {
+    let wasm = wat::parse_str(
+        r#"
+(module
+    (start $main)
+    (func $sum (param i32) (result i32)
+        (local i32)
+        (block
+            (loop
+                local.get 0
+                i32.eqz
+                br_if 1
+                local.get 1
+                local.get 0
+                i32.add
+                local.set 1
+                local.get 0
+                i32.const 1
+                i32.sub
+                local.set 0
+                br 0))
+        local.get 1
+        return)
+    (func $main
+        i32.const 5
+        call $sum
+        return)
+)
+"#,
+    )
+    .unwrap();
+    // the fused `i32.eqz; br_if` exits the loop
+    assert_eq!(run_valida(&wasm), Word::from(15u32));
+}
+
+#[test]
+fn test_br_if_with_value() {
+    let wasm = wat::parse_str(
+        r#"
+(module
+    (start $main)
+    (func $pick (param i32) (result i32)
+        (block (result i32)
+            i32.const 100
+            (block (result i32)
+                i32.const 7
+                local.get 0
+                br_if 1
+                drop
+                i32.const 2)
+            i32.add)
+        return)
+    (func $pick_eqz (param i32) (result i32)
+        (block (result i32)
+            i32.const 100
+            (block (result i32)
+                i32.const 7
+                local.get 0
+                i32.eqz
+                br_if 1
+                drop
+                i32.const 2)
+            i32.add)
+        return)
+    (func $main
+        i32.const 1
+        call $pick
+        i32.const 0
+        call $pick
+        i32.add
+        i32.const 0
+        call $pick_eqz
+        i32.add
+        i32.const 1
+        call $pick_eqz
+        i32.add
+        return)
+)
+"#,
+    )
+    .unwrap();
+    // the value passed to the outer block replaces the 100 below it: 7 + 102 + 7 + 102
+    assert_eq!(run_valida(&wasm), Word::from(218u32));
+}
+
+#[test]
+fn test_br_table() {
+    let wasm = wat::parse_str(
+        r#"
+(module
+    (start $main)
+    (func $pick (param i32) (result i32)
+        (block (result i32)
+            i32.const 100
+            (block (result i32)
+                i32.const 10
+                local.get 0
+                br_table 0 1)
+            i32.add)
+        return)
+    (func $main
+        i32.const 0
+        call $pick
+        i32.const 1
+        call $pick
+        i32.add
+        i32.const 7
+        call $pick
+        i32.add
+        return)
+)
+"#,
+    )
+    .unwrap();
+    // case 0 falls into the inner block's end (110), case 1 and the default pass the value
+    // to the outer block (10)
+    assert_eq!(run_valida(&wasm), Word::from(130u32));
+}
@@ -0,0 +1,28 @@
+use sem_tests::compile_error;
+
+mod sem_tests;
+
+#[test]
+fn test_call_indirect_unsupported() {
+    let err = compile_error(
+        r#"
+(module
+    (type $ret_i32 (func (result i32)))
+    (table 1 funcref)
+    (elem (i32.const 0) $seven)
+    (start $main)
+    (func $seven (result i32)
+        i32.const 7
+        return)
+    (func $main
+        i32.const 0
+        call_indirect (type $ret_i32)
+        return)
+)
+"#,
+    );
+    assert!(
+        err.contains("call_indirect in main is not supported by Valida"),
+        "{err}"
+    );
+}
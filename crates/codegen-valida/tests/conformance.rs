@@ -0,0 +1,53 @@
+use ozk_rust_wasm_tests_helper::conformance::run_conformance_suite;
+use ozk_rust_wasm_tests_helper::conformance::ConformanceCase;
+use ozk_rust_wasm_tests_helper::conformance::ConformanceTarget;
+use ozk_rust_wasm_tests_helper::conformance::Feature;
+use sem_tests::run_valida;
+use valida_machine::Word;
+
+mod sem_tests;
+
+struct ValidaTarget;
+
+impl ConformanceTarget for ValidaTarget {
+    fn name(&self) -> &str {
+        "valida"
+    }
+
+    fn supports(&self, feature: Feature) -> bool {
+        // Valida has no cycle counter
+        feature != Feature::Clock
+    }
+
+    fn expected_failures(&self) -> Vec<&'static str> {
+        vec![
+            // global.get/set are not lowered yet
+            "globals_set_get",
+            // loads and stores are not lowered yet
+            "memory_store_load",
+            // the public and secret inputs are not supported yet
+            "pub_input_to_output",
+        ]
+    }
+
+    fn check(&self, case: &ConformanceCase) -> Result<(), String> {
+        let wasm = wat::parse_str(case.wat).map_err(|e| e.to_string())?;
+        let [expected] = case.expected_output[..] else {
+            return Err("only a single return value is supported".to_string());
+        };
+        let expected: Word<u8> = (expected as u32).into();
+        let output = run_valida(&wasm);
+        if output == expected {
+            Ok(())
+        } else {
+            Err(format!("expected {:?}, got {:?}", expected, output))
+        }
+    }
+}
+
+#[test]
+fn test_conformance() {
+    let report = run_conformance_suite(&ValidaTarget);
+    eprintln!("{report}");
+    assert!(report.is_success(), "{report}");
+}
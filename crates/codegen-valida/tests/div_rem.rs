@@ -0,0 +1,63 @@
+use sem_tests::run_valida;
+use valida_machine::Word;
+
+mod sem_tests;
+
+/// Run `a op b` on Valida, the result is returned by the start function
+fn run_div_rem(op: &str, a: i32, b: i32) -> Word<u8> {
+    let wasm = wat::parse_str(format!(
+        r#"
+(module
+    (start $main)
+    (func $main
+        i32.const {a}
+        i32.const {b}
+        i32.{op}
+        return)
+)
+"#
+    ))
+    .unwrap();
+    run_valida(&wasm)
+}
+
+#[test]
+fn test_i32_div_rem_u() {
+    for (a, b) in [(17u32, 5u32), (5, 17), (u32::MAX, 2), (u32::MAX, u32::MAX)] {
+        assert_eq!(
+            run_div_rem("div_u", a as i32, b as i32),
+            Word::from(a / b),
+            "{a} / {b}"
+        );
+        assert_eq!(
+            run_div_rem("rem_u", a as i32, b as i32),
+            Word::from(a % b),
+            "{a} % {b}"
+        );
+    }
+}
+
+#[test]
+fn test_i32_div_rem_s() {
+    for (a, b) in [
+        (17, 5),
+        (-17, 5),
+        (17, -5),
+        (-17, -5),
+        (i32::MIN, 1),
+        (i32::MIN, 2),
+        (i32::MAX, -1),
+        (-1, i32::MIN),
+    ] {
+        assert_eq!(
+            run_div_rem("div_s", a, b),
+            Word::from(a.wrapping_div(b) as u32),
+            "{a} / {b}"
+        );
+        assert_eq!(
+            run_div_rem("rem_s", a, b),
+            Word::from(a.wrapping_rem(b) as u32),
+            "{a} % {b}"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Globals declared in the global section, with their initial values.
+//! The expected output is checked against wasmtime first, the Valida lowering of the globals
+//! (`global.get/set`, the initial values are set with them in a prologue function) is not
+//! implemented yet.
+
+use expect_test::expect;
+
+mod sem_tests;
+use crate::sem_tests::check_wat;
+
+#[ignore]
+#[test]
+fn test_globals_initial_values() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = 42;
+    check_wat(
+        r#"
+(module
+    (type (;0;) (func (result i64)))
+    (type (;1;) (func (param i64)))
+    (type (;2;) (func))
+    (import "env" "ozk_stdlib_pub_input" (func $ozk_stdlib_pub_input (;0;) (type 0)))
+    (import "env" "ozk_stdlib_pub_output" (func $ozk_stdlib_pub_output (;1;) (type 1)))
+    (import "env" "ozk_stdlib_secret_input" (func $ozk_stdlib_secret_input (;2;) (type 0)))
+    (global $mutable (mut i64) (i64.const 5))
+    (global $immutable i64 (i64.const 37))
+    (export "main" (func $main))
+    (start $main)
+    (func $main
+        global.get $mutable
+        global.get $immutable
+        i64.add
+        call $ozk_stdlib_pub_output
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#""#]],
+    );
+}
+
+#[ignore]
+#[test]
+fn test_globals_set_get_across_calls() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = 42;
+    check_wat(
+        r#"
+(module
+    (type (;0;) (func (result i64)))
+    (type (;1;) (func (param i64)))
+    (type (;2;) (func))
+    (import "env" "ozk_stdlib_pub_input" (func $ozk_stdlib_pub_input (;0;) (type 0)))
+    (import "env" "ozk_stdlib_pub_output" (func $ozk_stdlib_pub_output (;1;) (type 1)))
+    (import "env" "ozk_stdlib_secret_input" (func $ozk_stdlib_secret_input (;2;) (type 0)))
+    (global $counter (mut i64) (i64.const 40))
+    (global $step i64 (i64.const 1))
+    (export "main" (func $main))
+    (start $main)
+    (func $inc
+        global.get $counter
+        global.get $step
+        i64.add
+        global.set $counter
+        return)
+    (func $main
+        call $inc
+        call $inc
+        global.get $counter
+        call $ozk_stdlib_pub_output
+        return)
+)"#,
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#""#]],
+    );
+}
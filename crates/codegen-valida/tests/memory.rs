@@ -0,0 +1,23 @@
+use sem_tests::compile_error;
+
+mod sem_tests;
+
+#[test]
+fn test_load_unsupported() {
+    let err = compile_error(
+        r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 17
+        i32.load8_u
+        return)
+)
+"#,
+    );
+    assert!(
+        err.contains("is not supported by Valida (no loads and stores of the Wasm memory)"),
+        "{err}"
+    );
+}
@@ -0,0 +1,36 @@
+use sem_tests::run_valida;
+use valida_machine::Word;
+
+mod sem_tests;
+
+#[test]
+fn test_recursive_factorial() {
+    let wasm = wat::parse_str(
+        r#"
+(module
+    (start $main)
+    (func $fact (param i32) (result i32)
+        local.get 0
+        i32.eqz
+        if (result i32)
+            i32.const 1
+        else
+            local.get 0
+            local.get 0
+            i32.const 1
+            i32.sub
+            call $fact
+            i32.mul
+        end
+        return)
+    (func $main
+        i32.const 5
+        call $fact
+        return)
+)
+"#,
+    )
+    .unwrap();
+    // every activation of $fact keeps `n` on its stack across the recursive call
+    assert_eq!(run_valida(&wasm), Word::from(120u32));
+}
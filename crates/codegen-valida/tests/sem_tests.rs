@@ -5,66 +5,36 @@
 #![allow(unused_variables)]
 #![allow(dead_code)]
 
+use ozk_codegen_valida::compile_module;
 use ozk_codegen_valida::emit_op;
+use ozk_codegen_valida::emulator::run_program;
 use ozk_codegen_valida::ValidaInstrBuilder;
 use ozk_codegen_valida::ValidaTargetConfig;
 use ozk_frontend_wasm::WasmFrontendConfig;
 use ozk_valida_dialect::ops::ProgramOp;
-use ozk_wasm_dialect::ops::ModuleOp;
 use pliron::context::Context;
-use pliron::context::Ptr;
-use pliron::dialects::builtin;
-use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
-use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
-use pliron::operation::Operation;
 use pliron::with_context::AttachContext;
-use valida_basic::BasicMachine;
+use valida_machine::PublicMemory;
 use valida_machine::Word;
 use wasmtime::*;
 
+/// Frontend config with all the optional Wasm features enabled
+fn frontend_config() -> WasmFrontendConfig {
+    WasmFrontendConfig {
+        tail_call: true,
+        ..Default::default()
+    }
+}
+
 pub fn check_ir(input: &str, expected_tree: expect_test::Expect) {
     let source = wat::parse_str(input).unwrap();
     let mut ctx = Context::default();
     let target_config = ValidaTargetConfig::default();
-    let frontend_config = WasmFrontendConfig::default();
-    frontend_config.register(&mut ctx);
-    target_config.register(&mut ctx);
-    let wasm_module_op =
-        ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
-    let prog = run_conversion_passes(&mut ctx, wasm_module_op, &target_config);
+    let prog = compile_to_valida_dialect(&mut ctx, &source, &target_config);
     expected_tree.assert_eq(prog.with_ctx(&ctx).to_string().as_str());
 }
 
-fn run_conversion_passes(
-    ctx: &mut Context,
-    wasm_module: ModuleOp,
-    target_config: &ValidaTargetConfig,
-) -> ProgramOp {
-    // we need to wrap the wasm in an op because passes cannot replace the root op
-    let wrapper_module = builtin::ops::ModuleOp::new(ctx, "wrapper");
-    wasm_module
-        .get_operation()
-        .insert_at_back(wrapper_module.get_body(ctx, 0), ctx);
-    target_config
-        .pass_manager
-        .run(ctx, wrapper_module.get_operation())
-        .unwrap();
-    let inner_module = wrapper_module
-        .get_body(ctx, 0)
-        .deref(ctx)
-        .iter(ctx)
-        .collect::<Vec<Ptr<Operation>>>()
-        .first()
-        .cloned()
-        .unwrap();
-    *inner_module
-        .deref(ctx)
-        .get_op(ctx)
-        .downcast::<ProgramOp>()
-        .unwrap_or_else(|_| panic!("Expected ProgramOp"))
-}
-
 pub fn check_wasm(
     source: &[u8],
     input: Vec<u32>,
@@ -102,27 +72,23 @@ pub fn check_valida(
     let mut builder = ValidaInstrBuilder::default();
     emit_op(&ctx, prog_op.get_operation(), &mut builder);
     let program = builder.build();
-    exec_valida(program, expected_output);
+    assert_eq!(exec_valida(program), expected_output);
 }
 
-fn exec_valida(program: Vec<valida_machine::InstructionWord<i32>>, expected_output: Word<u8>) {
-    use valida_cpu::MachineWithCpuChip;
-    use valida_machine::Word;
-    use valida_machine::{Machine, ProgramROM, PublicMemory};
-    use valida_memory::MachineWithMemoryChip;
-
-    // run valida program and check expected output
-    let mut machine = BasicMachine::default();
-    let rom = ProgramROM::new(program);
-    let public_mem = PublicMemory::default();
-    machine.cpu_mut().fp = 0x1000;
-    machine.cpu_mut().save_register_state();
-    machine.run(rom, public_mem);
-
-    assert_eq!(
-        *machine.mem().cells.get(&(0x1000 + 4)).unwrap(), // Return value
-        expected_output
-    );
+/// Compile and execute the Wasm program, returning the value returned by the start function.
+pub fn run_valida(source: &[u8]) -> Word<u8> {
+    let mut ctx = Context::default();
+    let target_config = ValidaTargetConfig::default();
+    let prog_op = compile_to_valida_dialect(&mut ctx, source, &target_config);
+    let mut builder = ValidaInstrBuilder::default();
+    emit_op(&ctx, prog_op.get_operation(), &mut builder);
+    exec_valida(builder.build())
+}
+
+fn exec_valida(program: Vec<valida_machine::InstructionWord<i32>>) -> Word<u8> {
+    run_program(program, PublicMemory::default())
+        .unwrap()
+        .return_value
 }
 
 pub fn compile_to_valida_dialect(
@@ -130,11 +96,17 @@ pub fn compile_to_valida_dialect(
     source: &[u8],
     target_config: &ValidaTargetConfig,
 ) -> ProgramOp {
-    let frontend_config = WasmFrontendConfig::default();
-    frontend_config.register(ctx);
-    target_config.register(ctx);
-    let wasm_module_op = ozk_frontend_wasm::parse_module(ctx, source, &frontend_config).unwrap();
-    run_conversion_passes(ctx, wasm_module_op, target_config)
+    compile_module(ctx, source, &frontend_config(), target_config).unwrap()
+}
+
+/// Compile the WAT source and return the error (panics if the compilation succeeds)
+pub fn compile_error(source: &str) -> String {
+    let wasm = wat::parse_str(source).unwrap();
+    let mut ctx = Context::default();
+    let target_config = ValidaTargetConfig::default();
+    compile_module(&mut ctx, &wasm, &frontend_config(), &target_config)
+        .unwrap_err()
+        .to_string()
 }
 
 pub fn check_wat(
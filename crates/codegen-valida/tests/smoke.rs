@@ -76,6 +76,46 @@ fn test_smoke_add() {
     );
 }
 
+#[test]
+fn test_smoke_local_var() {
+    let input = vec![];
+    let secret_input = vec![];
+    let expected_output = 7.into();
+    check_valida(
+        r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 3
+        i32.const 7
+        local.set 0
+        local.get 0
+        return)
+)
+"#
+        .to_string(),
+        input,
+        secret_input,
+        expected_output,
+        expect![[r#"
+            valida.program {
+              entry():
+                valida.imm32 -24(fp) 0 0 0 28
+                valida.jal -28(fp) 4 -28 0 0
+                valida.sw 0 4(fp) -20(fp) 0 0
+                valida.exit
+              block_4_1():
+                valida.func @main pc=4 {
+                  entry():
+                    valida.imm32 -8(fp) 0 0 0 3
+                    valida.imm32 8(fp) 0 0 0 7
+                    valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                }
+            }"#]],
+    );
+}
+
 #[test]
 fn test_smoke_func_call() {
     let input = vec![];
@@ -121,8 +161,11 @@ fn test_smoke_func_call() {
                   entry():
                     valida.imm32 -4(fp) 0 0 0 3
                     valida.imm32 -8(fp) 0 0 0 4
-                    valida.imm32 -16(fp) 0 0 0 20
-                    valida.jal -20(fp) 4 -20 0 0
+                    valida.sw 0 -24(fp) -8(fp) 0 0
+                    valida.sw 0 -20(fp) -4(fp) 0 0
+                    valida.imm32 -32(fp) 0 0 0 36
+                    valida.jal -36(fp) 4 -36 0 0
+                    valida.sw 0 -4(fp) -20(fp) 0 0
                     valida.sw 0 8(fp) -4(fp) 0 0
                     valida.jalv -4(fp) 0(fp) 4(fp) 0 0
                 }
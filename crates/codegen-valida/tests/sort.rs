@@ -0,0 +1,16 @@
+use ozk_codegen_valida::compile_wasm;
+
+#[test]
+fn test_sort() {
+    let input = vec![4, 9, 3, 7, 1];
+    let secret_input = vec![];
+    let expected_output = vec![1, 3, 7, 9, 73];
+    let native_output = ozk_rust_wasm_tests_helper::wrap_main_with_io(
+        &ozk_rust_wasm_tests_sort::sort::sort_and_sum,
+    )(input, secret_input);
+    assert_eq!(native_output, expected_output);
+    let wasm_bytes = ozk_rust_wasm_tests_helper::compile_rust_wasm_tests("sort-bin", "sort");
+    // the bundle uses i64, the public I/O and the memory, which are rejected on Valida
+    let err = compile_wasm(&wasm_bytes).unwrap_err().to_string();
+    assert!(err.contains("is not supported by Valida"), "{err}");
+}
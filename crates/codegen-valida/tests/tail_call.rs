@@ -0,0 +1,75 @@
+use sem_tests::run_valida;
+use valida_machine::Word;
+
+mod sem_tests;
+
+#[test]
+fn test_tail_recursive_sum() {
+    let wasm = wat::parse_str(
+        r#"
+(module
+    (start $main)
+    (func $result (param i32) (result i32)
+        local.get 0
+        return)
+    (func $sum (param i32 i32) (result i32)
+        local.get 0
+        i32.eqz
+        if (result i32)
+            local.get 1
+            return_call $result
+        else
+            local.get 0
+            i32.const 1
+            i32.sub
+            local.get 1
+            local.get 0
+            i32.add
+            return_call $sum
+        end
+        return)
+    (func $start_sum (param i32) (result i32)
+        local.get 0
+        i32.const 0
+        return_call $sum)
+    (func $main
+        i32.const 1000
+        call $start_sum
+        return)
+)
+"#,
+    )
+    .unwrap();
+    // the tail calls change the number of args both ways and don't grow the call stack
+    assert_eq!(run_valida(&wasm), Word::from(500500u32));
+}
+
+#[test]
+fn test_return_call_after_stripped_intrinsic_import() {
+    let wasm = wat::parse_str(
+        r#"
+(module
+    (import "env" "ozk_stdlib_debug_print" (func $debug_print (param i64)))
+    (start $main)
+    (func $add_six (param i32) (result i32)
+        local.get 0
+        i32.const 6
+        i32.add
+        return)
+    (func $tail (param i32) (result i32)
+        local.get 0
+        return_call $add_six)
+    (func $main
+        i64.const 42
+        call $debug_print
+        i32.const 1
+        call $tail
+        return)
+)
+"#,
+    )
+    .unwrap();
+    // the intrinsic import is removed before the tail call is resolved,
+    // the tail call must follow the shifted index of $add_six
+    assert_eq!(run_valida(&wasm), Word::from(7u32));
+}
@@ -53,12 +53,27 @@ impl FieldElemAttr {
         FieldElemAttr { ty, val }
     }
 
+    /// Create a new [FieldElemAttr] holding the u32 `value`.
+    pub fn from_u32(ctx: &mut Context, value: u32) -> Self {
+        FieldElemAttr::create(FieldElemType::get(ctx), FieldElem::new(value as u64))
+    }
+
+    /// Create a new [FieldElemAttr] holding the u64 `value` (less than the field modulus).
+    pub fn from_u64(ctx: &mut Context, value: u64) -> Self {
+        FieldElemAttr::create(FieldElemType::get(ctx), FieldElem::new(value))
+    }
+
+    /// Create a new [FieldElemAttr] from an i32 or i64 `int_attr`, the negative values wrap
+    /// around the field modulus (see [apint_to_oxfoi]).
     pub fn from_integer_attr(
         ctx: &mut Context,
         int_attr: IntegerAttr,
     ) -> Result<FieldElemAttr, FieldElemError> {
         let ty = FieldElemType::get(ctx);
-        if int_attr.get_type() == IntegerType::get(ctx, 32, Signedness::Signed) {
+        let int_ty = int_attr.get_type();
+        if int_ty == IntegerType::get(ctx, 32, Signedness::Signed)
+            || int_ty == IntegerType::get(ctx, 64, Signedness::Signed)
+        {
             Ok(FieldElemAttr::create(ty, apint_to_oxfoi(int_attr.into())))
         } else {
             Err(FieldElemError::TooLarge(int_attr.into()))
@@ -2,8 +2,10 @@
 
 use std::ops::Deref;
 
-use apint::ApInt;
 use intertrait::cast_to;
+use ozk_ozk_dialect::attributes::to_u32_checked;
+use ozk_ozk_dialect::attributes::u32_attr;
+use ozk_ozk_dialect::ord_n::Ord16;
 use ozk_ozk_dialect::types::FuncSym;
 use pliron::attribute;
 use pliron::attribute::attr_cast;
@@ -392,12 +394,10 @@ impl LocLoadOp {
     }
 
     /// Get the index of the local variable as u32.
-    #[allow(clippy::unwrap_used)]
     pub fn get_index_as_u32(&self, ctx: &Context) -> u32 {
         let attr = self.get_index(ctx);
-        #[allow(clippy::unwrap_used)]
-        let apint: ApInt = attr.downcast_ref::<IntegerAttr>().unwrap().clone().into();
-        apint.try_to_u32().unwrap()
+        #[allow(clippy::expect_used)]
+        to_u32_checked(ctx, &attr).expect("index should be u32")
     }
 
     /// Create a new [LocalGetOp].
@@ -453,6 +453,640 @@ impl Verify for LocLoadOp {
     }
 }
 
+declare_op!(
+    /// Push the current clock cycle on the stack.
+    ClkOp,
+    "clk",
+    "miden"
+);
+
+impl ClkOp {
+    /// Create a new [ClkOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> ClkOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        ClkOp { op }
+    }
+}
+
+impl DisplayWithContext for ClkOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx))
+    }
+}
+
+impl Verify for ClkOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Remove the top element of the stack.
+    DropOp,
+    "drop",
+    "miden"
+);
+
+impl DropOp {
+    /// Create a new [DropOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> DropOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        DropOp { op }
+    }
+}
+
+impl DisplayWithContext for DropOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx))
+    }
+}
+
+impl Verify for DropOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Declares an op without attributes that only works with the operand stack
+/// (pops its operands and pushes the result).
+macro_rules! declare_stack_op {
+    ($(#[$outer:meta])* $op:ident, $op_name:literal) => {
+        declare_op!(
+            $(#[$outer])*
+            $op,
+            $op_name,
+            "miden"
+        );
+
+        impl $op {
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context) -> $op {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                $op { op }
+            }
+        }
+
+        impl DisplayWithContext for $op {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", self.get_opid().with_ctx(ctx))
+            }
+        }
+
+        impl Verify for $op {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                let op = &*self.get_operation().deref(ctx);
+                if op.get_opid() != Self::get_opid_static() {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect OpId".to_string(),
+                    });
+                }
+                if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect number of results or operands".to_string(),
+                    });
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+declare_stack_op!(
+    /// Pop b and a, push a + b (u32).
+    /// Fails if a or b is not a u32 or the sum overflows.
+    U32CheckedAddOp,
+    "u32checked_add"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push a - b (u32).
+    /// Fails if a or b is not a u32 or b > a.
+    U32CheckedSubOp,
+    "u32checked_sub"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push a - b (u32, wrapping on underflow).
+    /// Undefined if a or b is not a u32.
+    U32WrappingSubOp,
+    "u32wrapping_sub"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push a * b (u32, keeping the low 32 bits of the product).
+    /// Undefined if a or b is not a u32.
+    U32WrappingMulOp,
+    "u32wrapping_mul"
+);
+
+declare_stack_op!(
+    /// Pop the divisor b and the dividend a, push the quotient of a / b (u32).
+    /// Fails if a or b is not a u32 or b is zero.
+    U32CheckedDivOp,
+    "u32checked_div"
+);
+
+declare_stack_op!(
+    /// Pop the divisor b and the dividend a, push the remainder of a / b (u32).
+    /// Fails if a or b is not a u32 or b is zero.
+    U32CheckedModOp,
+    "u32checked_mod"
+);
+
+declare_stack_op!(
+    /// Pop two u32 values, push their bitwise and.
+    /// Fails if any of the values is not a u32.
+    U32CheckedAndOp,
+    "u32checked_and"
+);
+
+declare_stack_op!(
+    /// Pop two u32 values, push their bitwise or.
+    /// Fails if any of the values is not a u32.
+    U32CheckedOrOp,
+    "u32checked_or"
+);
+
+declare_stack_op!(
+    /// Pop two u32 values, push their bitwise exclusive or.
+    /// Fails if any of the values is not a u32.
+    U32CheckedXorOp,
+    "u32checked_xor"
+);
+
+declare_stack_op!(
+    /// Pop the shift amount b and the value a, push (a << b) mod 2^32.
+    /// Fails if a is not a u32 or b > 31.
+    U32CheckedShlOp,
+    "u32checked_shl"
+);
+
+declare_stack_op!(
+    /// Pop the shift amount b and the value a, push a >> b.
+    /// Fails if a is not a u32 or b > 31.
+    U32CheckedShrOp,
+    "u32checked_shr"
+);
+
+declare_stack_op!(
+    /// Pop the rotation amount b and the value a, push a rotated left by b bits.
+    /// Fails if a is not a u32 or b > 31.
+    U32CheckedRotlOp,
+    "u32checked_rotl"
+);
+
+declare_stack_op!(
+    /// Pop the rotation amount b and the value a, push a rotated right by b bits.
+    /// Fails if a is not a u32 or b > 31.
+    U32CheckedRotrOp,
+    "u32checked_rotr"
+);
+
+declare_stack_op!(
+    /// Pop two u32 values, push 1 if they are equal, 0 otherwise.
+    /// Fails if any of the values is not a u32.
+    U32CheckedEqOp,
+    "u32checked_eq"
+);
+
+declare_stack_op!(
+    /// Pop two u32 values, push 1 if they are not equal, 0 otherwise.
+    /// Fails if any of the values is not a u32.
+    U32CheckedNeqOp,
+    "u32checked_neq"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push 1 if a < b, 0 otherwise.
+    /// Fails if any of the values is not a u32.
+    U32CheckedLtOp,
+    "u32checked_lt"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push 1 if a <= b, 0 otherwise.
+    /// Fails if any of the values is not a u32.
+    U32CheckedLteOp,
+    "u32checked_lte"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push 1 if a > b, 0 otherwise.
+    /// Fails if any of the values is not a u32.
+    U32CheckedGtOp,
+    "u32checked_gt"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push 1 if a >= b, 0 otherwise.
+    /// Fails if any of the values is not a u32.
+    U32CheckedGteOp,
+    "u32checked_gte"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push 1 if a == b, 0 otherwise (field elements).
+    EqOp,
+    "eq"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push 1 if a != b, 0 otherwise (field elements).
+    NeqOp,
+    "neq"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push 1 if a < b, 0 otherwise (field elements).
+    LtOp,
+    "lt"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push 1 if a <= b, 0 otherwise (field elements).
+    LteOp,
+    "lte"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push 1 if a > b, 0 otherwise (field elements).
+    GtOp,
+    "gt"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push 1 if a >= b, 0 otherwise (field elements).
+    GteOp,
+    "gte"
+);
+
+declare_stack_op!(
+    /// Pop c, b and a, push b if c = 1, a if c = 0. Fails if c is not binary.
+    CDropOp,
+    "cdrop"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push a - b (field elements).
+    SubOp,
+    "sub"
+);
+
+declare_stack_op!(
+    /// Pop b and a, push a * b (field elements).
+    MulOp,
+    "mul"
+);
+
+declare_stack_op!(
+    /// Pop a and fail if it's not 1.
+    AssertOp,
+    "assert"
+);
+
+declare_stack_op!(
+    /// Fail if any of the two top stack items is not a u32 (the items stay on the stack).
+    U32Assert2Op,
+    "u32assert.2"
+);
+
+declare_stack_op!(
+    /// Pop a, push the low 32 bits of a, then the high 32 bits.
+    U32SplitOp,
+    "u32split"
+);
+
+declare_stack_op!(
+    /// Pop the divisor b and the dividend a (u64 as two u32 limbs, the high limb on top),
+    /// push the quotient of a / b (the same limb order). Fails if b is zero.
+    /// Calls `u64::unchecked_div` of the Miden stdlib (the limbs are assumed to be u32).
+    U64UncheckedDivOp,
+    "u64unchecked_div"
+);
+
+declare_stack_op!(
+    /// Pop the divisor b and the dividend a (u64 as two u32 limbs, the high limb on top),
+    /// push the remainder of a / b (the same limb order). Fails if b is zero.
+    /// Calls `u64::unchecked_mod` of the Miden stdlib (the limbs are assumed to be u32).
+    U64UncheckedModOp,
+    "u64unchecked_mod"
+);
+
+declare_stack_op!(
+    /// Pop the shift amount b (below 64) and a (u64 as two u32 limbs, the high limb on top),
+    /// push a << b (the same limb order, the high bits are discarded).
+    /// Calls `u64::unchecked_shl` of the Miden stdlib (the limbs are assumed to be u32).
+    U64UncheckedShlOp,
+    "u64unchecked_shl"
+);
+
+declare_stack_op!(
+    /// Pop the shift amount b (below 64) and a (u64 as two u32 limbs, the high limb on top),
+    /// push a >> b (the same limb order, logical shift).
+    /// Calls `u64::unchecked_shr` of the Miden stdlib (the limbs are assumed to be u32).
+    U64UncheckedShrOp,
+    "u64unchecked_shr"
+);
+
+declare_stack_op!(
+    /// Pop the rotation amount b (below 64) and a (u64 as two u32 limbs, the high limb on top),
+    /// push a rotated left by b (the same limb order).
+    /// Calls `u64::unchecked_rotl` of the Miden stdlib (the limbs are assumed to be u32).
+    U64UncheckedRotlOp,
+    "u64unchecked_rotl"
+);
+
+declare_stack_op!(
+    /// Pop the rotation amount b (below 64) and a (u64 as two u32 limbs, the high limb on top),
+    /// push a rotated right by b (the same limb order).
+    /// Calls `u64::unchecked_rotr` of the Miden stdlib (the limbs are assumed to be u32).
+    U64UncheckedRotrOp,
+    "u64unchecked_rotr"
+);
+
+declare_stack_op!(
+    /// Pop the address a, push the first element of the memory word at a.
+    MemLoadOp,
+    "mem_load"
+);
+
+declare_stack_op!(
+    /// Pop the address a and the value v below it, store v as the first element of the memory
+    /// word at a.
+    MemStoreOp,
+    "mem_store"
+);
+
+/// Declares an op that works with the stack item at the given index
+/// (index 0 is the top of the stack).
+macro_rules! declare_stack_index_op {
+    ($(#[$outer:meta])* $op:ident, $op_name:literal) => {
+        declare_op!(
+            $(#[$outer])*
+            ///
+            /// Attributes:
+            ///
+            /// | key | value |
+            /// |-----|-------|
+            /// | `ATTR_KEY_INDEX` | [IntegerAttr] |
+            ///
+            $op,
+            $op_name,
+            "miden"
+        );
+
+        impl $op {
+            /// Attribute key for the stack item index
+            pub const ATTR_KEY_INDEX: &str = concat!($op_name, ".index");
+
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context, index: Ord16) -> $op {
+                let index_attr = u32_attr(ctx, index.into());
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                op.deref_mut(ctx)
+                    .attributes
+                    .insert(Self::ATTR_KEY_INDEX, index_attr);
+                $op { op }
+            }
+
+            /// Get the stack item index.
+            #[allow(clippy::expect_used)]
+            pub fn get_index(&self, ctx: &Context) -> Ord16 {
+                let op = self.get_operation().deref(ctx);
+                let attr = op
+                    .attributes
+                    .get(Self::ATTR_KEY_INDEX)
+                    .expect("no attribute for index found");
+                let index = to_u32_checked(ctx, attr).expect("index is not a u32");
+                index.try_into().expect("index is not an Ord16")
+            }
+
+            /// Get the stack item index as u8 (for the emitter).
+            pub fn get_index_as_u8(&self, ctx: &Context) -> u8 {
+                u32::from(self.get_index(ctx)) as u8
+            }
+        }
+
+        impl DisplayWithContext for $op {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{} {}", self.get_opid().with_ctx(ctx), self.get_index(ctx))
+            }
+        }
+
+        impl Verify for $op {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                let op = &*self.get_operation().deref(ctx);
+                if op.get_opid() != Self::get_opid_static() {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect OpId".to_string(),
+                    });
+                }
+                if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect number of results or operands".to_string(),
+                    });
+                }
+                let attr = op.attributes.get(Self::ATTR_KEY_INDEX).ok_or_else(|| {
+                    CompilerError::VerificationError {
+                        msg: "Missing index attribute".to_string(),
+                    }
+                })?;
+                let index =
+                    to_u32_checked(ctx, attr).map_err(|e| CompilerError::VerificationError {
+                        msg: format!("Invalid index: {e}"),
+                    })?;
+                Ord16::try_from(index).map_err(|e| CompilerError::VerificationError {
+                    msg: format!("Invalid index: {e}"),
+                })?;
+                Ok(())
+            }
+        }
+    };
+}
+
+declare_stack_index_op!(
+    /// Push a copy of the stack item at the given index.
+    DupOp,
+    "dup"
+);
+
+declare_stack_index_op!(
+    /// Swap the top stack item with the item at the given index.
+    SwapOp,
+    "swap"
+);
+
+declare_op!(
+    /// Pop the condition (must be 0 or 1) and run the then block on 1, the else block on 0.
+    /// Emitted as `if.true .. else .. end` (no `else` if the else block is empty).
+    IfOp,
+    "if.true",
+    "miden"
+);
+
+impl IfOp {
+    /// Create a new [IfOp] with the empty then and else blocks.
+    /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> IfOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 2);
+        for (idx, label) in ["then", "else"].into_iter().enumerate() {
+            let region = op.deref(ctx).get_region(idx);
+            let body = BasicBlock::new(ctx, Some(label.to_string()), vec![]);
+            body.insert_at_front(region, ctx);
+        }
+        IfOp { op }
+    }
+
+    /// Get the block that runs if the condition is 1.
+    pub fn get_then_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        self.get_region_block(ctx, 0)
+    }
+
+    /// Get the block that runs if the condition is 0.
+    pub fn get_else_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        self.get_region_block(ctx, 1)
+    }
+
+    fn get_region_block(&self, ctx: &Context, idx: usize) -> Ptr<BasicBlock> {
+        let region = self.get_operation().deref(ctx).get_region(idx);
+        #[allow(clippy::unwrap_used)]
+        region.deref(ctx).get_head().unwrap()
+    }
+}
+
+impl DisplayWithContext for IfOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let op = self.get_operation().deref(ctx);
+        let then_region = op.get_region(0).with_ctx(ctx).to_string();
+        let else_region = op.get_region(1).with_ctx(ctx).to_string();
+        write!(
+            f,
+            "{} {{\n{}}} else {{\n{}}}",
+            self.get_opid().with_ctx(ctx),
+            indent::indent_all_by(2, then_region),
+            indent::indent_all_by(2, else_region),
+        )
+    }
+}
+
+impl Verify for IfOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        if op.get_num_regions() != 2 {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected the then and else regions".to_string(),
+            });
+        }
+        self.get_then_block(ctx).verify(ctx)?;
+        self.get_else_block(ctx).verify(ctx)?;
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pop the condition (must be 0 or 1) and run the body while it is 1, the body has to leave
+    /// the next condition on the top of the stack.
+    /// Emitted as `while.true .. end`.
+    WhileOp,
+    "while.true",
+    "miden"
+);
+
+impl WhileOp {
+    /// Create a new [WhileOp] with the empty body block.
+    /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> WhileOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
+        let region = op.deref(ctx).get_region(0);
+        let body = BasicBlock::new(ctx, Some("body".to_string()), vec![]);
+        body.insert_at_front(region, ctx);
+        WhileOp { op }
+    }
+
+    /// Get the block that runs while the condition is 1.
+    pub fn get_body_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        let region = self.get_operation().deref(ctx).get_region(0);
+        #[allow(clippy::unwrap_used)]
+        region.deref(ctx).get_head().unwrap()
+    }
+}
+
+impl DisplayWithContext for WhileOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let region = self
+            .get_operation()
+            .deref(ctx)
+            .get_region(0)
+            .with_ctx(ctx)
+            .to_string();
+        write!(
+            f,
+            "{} {{\n{}}}",
+            self.get_opid().with_ctx(ctx),
+            indent::indent_all_by(2, region),
+        )
+    }
+}
+
+impl Verify for WhileOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        if op.get_num_regions() != 1 {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected the body region".to_string(),
+            });
+        }
+        self.get_body_block(ctx).verify(ctx)?;
+        Ok(())
+    }
+}
+
 pub(crate) fn register(ctx: &mut Context, dialect: &mut Dialect) {
     ConstantOp::register(ctx, dialect);
     AddOp::register(ctx, dialect);
@@ -460,4 +1094,49 @@ pub(crate) fn register(ctx: &mut Context, dialect: &mut Dialect) {
     LocLoadOp::register(ctx, dialect);
     ProgramOp::register(ctx, dialect);
     ProcOp::register(ctx, dialect);
+    ClkOp::register(ctx, dialect);
+    DropOp::register(ctx, dialect);
+    U32CheckedAddOp::register(ctx, dialect);
+    U32CheckedSubOp::register(ctx, dialect);
+    U32WrappingSubOp::register(ctx, dialect);
+    U32WrappingMulOp::register(ctx, dialect);
+    U32CheckedDivOp::register(ctx, dialect);
+    U32CheckedModOp::register(ctx, dialect);
+    U32CheckedAndOp::register(ctx, dialect);
+    U32CheckedOrOp::register(ctx, dialect);
+    U32CheckedXorOp::register(ctx, dialect);
+    U32CheckedShlOp::register(ctx, dialect);
+    U32CheckedShrOp::register(ctx, dialect);
+    U32CheckedRotlOp::register(ctx, dialect);
+    U32CheckedRotrOp::register(ctx, dialect);
+    U32CheckedEqOp::register(ctx, dialect);
+    U32CheckedNeqOp::register(ctx, dialect);
+    U32CheckedLtOp::register(ctx, dialect);
+    U32CheckedLteOp::register(ctx, dialect);
+    U32CheckedGtOp::register(ctx, dialect);
+    U32CheckedGteOp::register(ctx, dialect);
+    EqOp::register(ctx, dialect);
+    NeqOp::register(ctx, dialect);
+    LtOp::register(ctx, dialect);
+    LteOp::register(ctx, dialect);
+    GtOp::register(ctx, dialect);
+    GteOp::register(ctx, dialect);
+    CDropOp::register(ctx, dialect);
+    SubOp::register(ctx, dialect);
+    MulOp::register(ctx, dialect);
+    AssertOp::register(ctx, dialect);
+    U32Assert2Op::register(ctx, dialect);
+    U32SplitOp::register(ctx, dialect);
+    U64UncheckedDivOp::register(ctx, dialect);
+    U64UncheckedModOp::register(ctx, dialect);
+    U64UncheckedShlOp::register(ctx, dialect);
+    U64UncheckedShrOp::register(ctx, dialect);
+    U64UncheckedRotlOp::register(ctx, dialect);
+    U64UncheckedRotrOp::register(ctx, dialect);
+    MemLoadOp::register(ctx, dialect);
+    MemStoreOp::register(ctx, dialect);
+    DupOp::register(ctx, dialect);
+    SwapOp::register(ctx, dialect);
+    IfOp::register(ctx, dialect);
+    WhileOp::register(ctx, dialect);
 }
@@ -185,3 +185,97 @@ pub fn apint_to_u32(value: ApInt) -> u32 {
     #[allow(clippy::expect_used)]
     i.try_to_u32().expect("unsigned 32-bit integer")
 }
+
+#[derive(Debug, Error)]
+pub enum IntConversionError {
+    #[error("expected IntegerAttr")]
+    NotAnInteger,
+    #[error("expected a {expected} integer, got a {actual} one")]
+    SignednessMismatch {
+        expected: &'static str,
+        actual: &'static str,
+    },
+    #[error("ApInt {value:?} does not fit in {target}")]
+    Overflow { value: ApInt, target: &'static str },
+}
+
+fn signedness_name(signedness: Signedness) -> &'static str {
+    if signedness == Signedness::Signed {
+        "signed"
+    } else if signedness == Signedness::Unsigned {
+        "unsigned"
+    } else {
+        "signless"
+    }
+}
+
+/// Returns the integer attribute and the signedness of its type
+fn int_attr_parts(
+    ctx: &Context,
+    attr: &AttrObj,
+) -> Result<(ApInt, Signedness), IntConversionError> {
+    let int_attr = attr
+        .downcast_ref::<IntegerAttr>()
+        .ok_or(IntConversionError::NotAnInteger)?;
+    let ty = int_attr.get_type();
+    let signedness = ty
+        .deref(ctx)
+        .downcast_ref::<IntegerType>()
+        .ok_or(IntConversionError::NotAnInteger)?
+        .get_signedness();
+    Ok((int_attr.clone().into(), signedness))
+}
+
+/// Converts an unsigned (or signless) integer attribute to u32.
+/// Fails on a signed integer type or if the value does not fit in u32.
+pub fn to_u32_checked(ctx: &Context, attr: &AttrObj) -> Result<u32, IntConversionError> {
+    let (value, signedness) = int_attr_parts(ctx, attr)?;
+    if signedness == Signedness::Signed {
+        return Err(IntConversionError::SignednessMismatch {
+            expected: signedness_name(Signedness::Unsigned),
+            actual: signedness_name(signedness),
+        });
+    }
+    UInt::from(value.clone())
+        .try_to_u32()
+        .map_err(|_| IntConversionError::Overflow {
+            value,
+            target: "u32",
+        })
+}
+
+/// Converts a signed (or signless) integer attribute to i32.
+/// Fails on an unsigned integer type or if the value does not fit in i32.
+pub fn to_i32_checked(ctx: &Context, attr: &AttrObj) -> Result<i32, IntConversionError> {
+    let (value, signedness) = int_attr_parts(ctx, attr)?;
+    if signedness == Signedness::Unsigned {
+        return Err(IntConversionError::SignednessMismatch {
+            expected: signedness_name(Signedness::Signed),
+            actual: signedness_name(signedness),
+        });
+    }
+    Int::from(value.clone())
+        .try_to_i32()
+        .map_err(|_| IntConversionError::Overflow {
+            value,
+            target: "i32",
+        })
+}
+
+/// Converts a signed (or signless) integer attribute to i64.
+/// Fails on an unsigned integer type or if the value does not fit in i64.
+pub fn to_i64_checked(ctx: &Context, attr: &AttrObj) -> Result<i64, IntConversionError> {
+    let (value, signedness) = int_attr_parts(ctx, attr)?;
+    if signedness == Signedness::Unsigned {
+        return Err(IntConversionError::SignednessMismatch {
+            expected: signedness_name(Signedness::Signed),
+            actual: signedness_name(signedness),
+        });
+    }
+    Int::from(value.clone())
+        .try_to_i64()
+        .map_err(|_| IntConversionError::Overflow {
+            value,
+            target: "i64",
+        })
+}
@@ -21,7 +21,7 @@ use pliron::r#type::Type;
 use pliron::r#type::TypeObj;
 use pliron::with_context::AttachContext;
 
-use crate::attributes::apint_to_i32;
+use crate::attributes::to_u32_checked;
 use crate::attributes::u32_attr;
 use crate::attributes::FieldElemAttr;
 use crate::ord_n::Ord16;
@@ -124,13 +124,7 @@ impl SwapOp {
             .attributes
             .get(Self::ATTR_KEY_INDEX)
             .expect("no attribute for index found");
-        let value_u32 = apint_to_i32(
-            value
-                .downcast_ref::<IntegerAttr>()
-                .expect("index is not an IntegerAttr")
-                .clone()
-                .into(),
-        ) as u32;
+        let value_u32 = to_u32_checked(ctx, value).expect("index is not a u32");
         value_u32.try_into().expect("index is not an Ord16")
     }
 
@@ -172,6 +166,17 @@ impl Verify for SwapOp {
                 msg: "Incorrect number of results or operands".to_string(),
             });
         }
+        let attr = op.attributes.get(Self::ATTR_KEY_INDEX).ok_or_else(|| {
+            CompilerError::VerificationError {
+                msg: "Missing index attribute".to_string(),
+            }
+        })?;
+        let index = to_u32_checked(ctx, attr).map_err(|e| CompilerError::VerificationError {
+            msg: format!("Invalid index: {e}"),
+        })?;
+        Ord16::try_from(index).map_err(|e| CompilerError::VerificationError {
+            msg: format!("Invalid index: {e}"),
+        })?;
         Ok(())
     }
 }
@@ -219,6 +224,14 @@ impl CallOp {
         func_sym
     }
 
+    /// Set the target function symbol
+    pub fn set_func_sym(&self, ctx: &mut Context, func_sym: FuncSym) {
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_SYM, StringAttr::create(func_sym.into()));
+    }
+
     /// Get the function signature (type).
     pub fn get_func_type_attr(&self, ctx: &Context) -> Ptr<TypeObj> {
         let opref = self.get_operation().deref(ctx);
@@ -269,8 +282,604 @@ impl Verify for CallOp {
     }
 }
 
+declare_op!(
+    /// Tail call: call a function in place of the current one, the callee returns
+    /// directly to the caller of the current function
+    ///
+    ReturnCallOp,
+    "return_call",
+    "ozk"
+);
+
+impl ReturnCallOp {
+    const ATTR_KEY_FUNC_SYM: &str = "return_call.func_sym";
+    const ATTR_KEY_FUNC_TYPE: &str = "return_call.func_type";
+
+    /// Create a new [ReturnCallOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        func_sym: FuncSym,
+        func_type: FunctionType,
+    ) -> ReturnCallOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_SYM, StringAttr::create(func_sym.into()));
+        let ty = Type::register_instance(func_type, ctx);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_TYPE, TypeAttr::create(ty));
+        ReturnCallOp { op }
+    }
+
+    /// Get the target function symbol
+    pub fn get_func_sym(&self, ctx: &Context) -> String {
+        let op = self.get_operation().deref(ctx);
+        #[allow(clippy::expect_used)]
+        let func_sym_attr = op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_SYM)
+            .expect("no attribute found");
+        #[allow(clippy::expect_used)]
+        let func_sym: String = func_sym_attr
+            .downcast_ref::<StringAttr>()
+            .expect("expected StringAttr")
+            .clone()
+            .into();
+        func_sym
+    }
+
+    /// Set the target function symbol
+    pub fn set_func_sym(&self, ctx: &mut Context, func_sym: FuncSym) {
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_SYM, StringAttr::create(func_sym.into()));
+    }
+
+    /// Get the function signature (type).
+    pub fn get_func_type_attr(&self, ctx: &Context) -> Ptr<TypeObj> {
+        let opref = self.get_operation().deref(ctx);
+        #[allow(clippy::unwrap_used)]
+        let ty_attr = opref.attributes.get(Self::ATTR_KEY_FUNC_TYPE).unwrap();
+        #[allow(clippy::unwrap_used)]
+        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .unwrap()
+            .get_type()
+    }
+
+    /// Get the target function signature (type).
+    pub fn get_func_type(&self, ctx: &Context) -> FunctionType {
+        let func_type_obj = self.get_func_type_attr(ctx).deref(ctx);
+        #[allow(clippy::panic)]
+        let Some(func_type) = func_type_obj.downcast_ref::<FunctionType>() else {
+            panic!("FuncOp type is not a FunctionType");
+        };
+        func_type.clone()
+    }
+}
+
+impl DisplayWithContext for ReturnCallOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_func_sym(ctx)
+        )
+    }
+}
+
+impl Verify for ReturnCallOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Call a host function (a function imported from the runtime, e.g. the I/O).
+    /// Unlike [CallOp], the target has no body in the module and is implemented by the backend.
+    ///
+    HostCallOp,
+    "host_call",
+    "ozk"
+);
+
+impl HostCallOp {
+    const ATTR_KEY_FUNC_SYM: &str = "host_call.func_sym";
+    const ATTR_KEY_FUNC_TYPE: &str = "host_call.func_type";
+
+    /// Create a new [HostCallOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        func_sym: FuncSym,
+        func_type: FunctionType,
+    ) -> HostCallOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_SYM, StringAttr::create(func_sym.into()));
+        let ty = Type::register_instance(func_type, ctx);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_TYPE, TypeAttr::create(ty));
+        HostCallOp { op }
+    }
+
+    /// Get the host function symbol
+    pub fn get_func_sym(&self, ctx: &Context) -> String {
+        let op = self.get_operation().deref(ctx);
+        let func_sym_attr = op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_SYM)
+            .expect("no attribute found");
+        func_sym_attr
+            .downcast_ref::<StringAttr>()
+            .expect("expected StringAttr")
+            .clone()
+            .into()
+    }
+
+    /// Set the host function symbol
+    pub fn set_func_sym(&self, ctx: &mut Context, func_sym: FuncSym) {
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_SYM, StringAttr::create(func_sym.into()));
+    }
+
+    /// Get the host function signature (type).
+    pub fn get_func_type(&self, ctx: &Context) -> FunctionType {
+        let opref = self.get_operation().deref(ctx);
+        let ty_attr = opref
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_TYPE)
+            .expect("no attribute found");
+        let func_type_obj = attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .expect("expected TypedAttrInterface")
+            .get_type()
+            .deref(ctx);
+        #[allow(clippy::panic)]
+        let Some(func_type) = func_type_obj.downcast_ref::<FunctionType>() else {
+            panic!("HostCallOp type is not a FunctionType");
+        };
+        func_type.clone()
+    }
+}
+
+impl DisplayWithContext for HostCallOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_func_sym(ctx)
+        )
+    }
+}
+
+impl Verify for HostCallOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Push the current VM cycle (clock) count on the stack.
+    /// Lowered only for the targets that expose the cycle counter.
+    ClockOp,
+    "clock",
+    "ozk"
+);
+
+impl ClockOp {
+    /// Create a new [ClockOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> ClockOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        ClockOp { op }
+    }
+}
+
+impl DisplayWithContext for ClockOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx))
+    }
+}
+
+impl Verify for ClockOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pop the value from the stack and write it to the debug output.
+    /// The targets without a debug output drop the value.
+    DebugPrintOp,
+    "debug_print",
+    "ozk"
+);
+
+impl DebugPrintOp {
+    /// Create a new [DebugPrintOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> DebugPrintOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        DebugPrintOp { op }
+    }
+}
+
+impl DisplayWithContext for DebugPrintOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx))
+    }
+}
+
+impl Verify for DebugPrintOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pop the exit code from the stack and stop the program.
+    HaltOp,
+    "halt",
+    "ozk"
+);
+
+impl HaltOp {
+    /// Create a new [HaltOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> HaltOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        HaltOp { op }
+    }
+}
+
+impl DisplayWithContext for HaltOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx))
+    }
+}
+
+impl Verify for HaltOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Abort the program (the execution fails).
+    TrapOp,
+    "trap",
+    "ozk"
+);
+
+impl TrapOp {
+    /// Create a new [TrapOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> TrapOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        TrapOp { op }
+    }
+}
+
+impl DisplayWithContext for TrapOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx))
+    }
+}
+
+impl Verify for TrapOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pop the value from the stack and abort the program (the execution fails) if it is zero.
+    AssertOp,
+    "assert",
+    "ozk"
+);
+
+impl AssertOp {
+    /// Create a new [AssertOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> AssertOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        AssertOp { op }
+    }
+}
+
+impl DisplayWithContext for AssertOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx))
+    }
+}
+
+impl Verify for AssertOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pop the i32 values b and a, push a + b. Abort the program if the unsigned sum overflows.
+    U32CheckedAddOp,
+    "u32checked_add",
+    "ozk"
+);
+
+impl U32CheckedAddOp {
+    /// Create a new [U32CheckedAddOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> U32CheckedAddOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        U32CheckedAddOp { op }
+    }
+}
+
+impl DisplayWithContext for U32CheckedAddOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx))
+    }
+}
+
+impl Verify for U32CheckedAddOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pop the i32 values b and a, push a - b. Abort the program if b > a (unsigned).
+    U32CheckedSubOp,
+    "u32checked_sub",
+    "ozk"
+);
+
+impl U32CheckedSubOp {
+    /// Create a new [U32CheckedSubOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> U32CheckedSubOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        U32CheckedSubOp { op }
+    }
+}
+
+impl DisplayWithContext for U32CheckedSubOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx))
+    }
+}
+
+impl Verify for U32CheckedSubOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Declares an op that accesses a named reserved slot (a memory cell or a global
+/// that is not visible to the program). The slot name is resolved to the concrete location
+/// by the target's memory layout late in the pipeline, so the passes that emit these ops
+/// do not depend on the layout.
+macro_rules! declare_reserved_slot_op {
+    ($(#[$outer:meta])* $op:ident, $op_name:literal) => {
+        declare_op!(
+            $(#[$outer])*
+            ///
+            /// Attributes:
+            ///
+            /// | key | value |
+            /// |-----|-------|
+            /// | `ATTR_KEY_SLOT_NAME` | [StringAttr] |
+            ///
+            $op,
+            $op_name,
+            "ozk"
+        );
+
+        impl $op {
+            /// Attribute key for the reserved slot name
+            pub const ATTR_KEY_SLOT_NAME: &str = "reserved.slot_name";
+
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context, slot_name: &str) -> $op {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                op.deref_mut(ctx).attributes.insert(
+                    Self::ATTR_KEY_SLOT_NAME,
+                    StringAttr::create(slot_name.to_string()),
+                );
+                $op { op }
+            }
+
+            /// Get the reserved slot name
+            pub fn get_slot_name(&self, ctx: &Context) -> String {
+                let op = self.get_operation().deref(ctx);
+                #[allow(clippy::expect_used)]
+                let slot_name_attr = op
+                    .attributes
+                    .get(Self::ATTR_KEY_SLOT_NAME)
+                    .expect("no attribute found");
+                #[allow(clippy::expect_used)]
+                let slot_name: String = slot_name_attr
+                    .downcast_ref::<StringAttr>()
+                    .expect("expected StringAttr")
+                    .clone()
+                    .into();
+                slot_name
+            }
+        }
+
+        impl DisplayWithContext for $op {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(
+                    f,
+                    "{} {}",
+                    self.get_opid().with_ctx(ctx),
+                    self.get_slot_name(ctx)
+                )
+            }
+        }
+
+        impl Verify for $op {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                let op = &*self.get_operation().deref(ctx);
+                if op.get_opid() != Self::get_opid_static() {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect OpId".to_string(),
+                    });
+                }
+                if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect number of results or operands".to_string(),
+                    });
+                }
+                if !op
+                    .attributes
+                    .get(Self::ATTR_KEY_SLOT_NAME)
+                    .map_or(false, |attr| attr.is::<StringAttr>())
+                {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Expected the reserved slot name".to_string(),
+                    });
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+declare_reserved_slot_op!(
+    /// Push the i32 value of the reserved slot on the stack.
+    ReservedGetOp,
+    "reserved_get"
+);
+
+declare_reserved_slot_op!(
+    /// Pop the i32 value from the stack and store it in the reserved slot.
+    ReservedSetOp,
+    "reserved_set"
+);
+
 pub(crate) fn register(ctx: &mut Context, dialect: &mut Dialect) {
     ConstantOp::register(ctx, dialect);
     SwapOp::register(ctx, dialect);
     CallOp::register(ctx, dialect);
+    ReturnCallOp::register(ctx, dialect);
+    HostCallOp::register(ctx, dialect);
+    ClockOp::register(ctx, dialect);
+    DebugPrintOp::register(ctx, dialect);
+    HaltOp::register(ctx, dialect);
+    TrapOp::register(ctx, dialect);
+    AssertOp::register(ctx, dialect);
+    U32CheckedAddOp::register(ctx, dialect);
+    U32CheckedSubOp::register(ctx, dialect);
+    ReservedGetOp::register(ctx, dialect);
+    ReservedSetOp::register(ctx, dialect);
 }
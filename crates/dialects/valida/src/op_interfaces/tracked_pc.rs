@@ -1,13 +1,13 @@
 #![allow(clippy::expect_used)]
 
-use ozk_ozk_dialect::attributes::apint_to_u32;
+use ozk_ozk_dialect::attributes::to_u32_checked;
 use ozk_ozk_dialect::attributes::u32_attr;
 use pliron::context::Context;
-use pliron::dialects::builtin::attributes::IntegerAttr;
 use pliron::error::CompilerError;
 use pliron::op::Op;
 
 use crate::ops::FuncOp;
+use crate::ops::LabelOp;
 use crate::ops::ProgramOp;
 use crate::types::ProgramCounter;
 
@@ -23,14 +23,11 @@ pub trait TrackedProgramCounter: Op {
 
     fn get_pc_opt(&self, ctx: &Context) -> Option<ProgramCounter> {
         let self_op = self.get_operation().deref(ctx);
-        self_op
-            .attributes
-            .get(ATTR_KEY_TRACK_PC)
-            .and_then(|attr_obj| {
-                attr_obj
-                    .downcast_ref::<IntegerAttr>()
-                    .map(|attr| apint_to_u32(attr.clone().into()).into())
-            })
+        self_op.attributes.get(ATTR_KEY_TRACK_PC).map(|attr_obj| {
+            to_u32_checked(ctx, attr_obj)
+                .expect("program counter should be u32")
+                .into()
+        })
     }
 
     fn get_pc(&self, ctx: &Context) -> ProgramCounter {
@@ -50,6 +47,9 @@ pub trait TrackedProgramCounter: Op {
 #[intertrait::cast_to]
 impl TrackedProgramCounter for FuncOp {}
 
+#[intertrait::cast_to]
+impl TrackedProgramCounter for LabelOp {}
+
 /// An interface for operations with custom pc
 pub trait CustomProgramCountChange: Op {
     /// Get the stack depth change for this operation.
@@ -69,3 +69,4 @@ macro_rules! custom_pc_change {
 
 custom_pc_change!(FuncOp, 0);
 custom_pc_change!(ProgramOp, 0);
+custom_pc_change!(LabelOp, 0);
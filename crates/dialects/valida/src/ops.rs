@@ -1,4 +1,6 @@
 use intertrait::cast_to;
+use ozk_ozk_dialect::attributes::to_u32_checked;
+use ozk_ozk_dialect::attributes::u32_attr;
 use pliron::basic_block::BasicBlock;
 use pliron::common_traits::DisplayWithContext;
 use pliron::common_traits::Verify;
@@ -17,7 +19,9 @@ use pliron::with_context::AttachContext;
 
 use crate::op_interfaces::HasOperands;
 use crate::op_interfaces::TrackedProgramCounter;
+use crate::types::FramePointer;
 use crate::types::Operands;
+use crate::types::OperandsError;
 
 declare_op!(
     /// Write the immediate values b,c,d,e to the cell located at offset a.
@@ -35,6 +39,17 @@ impl Imm32Op {
         op_op.set_operands(ctx, operands);
         op_op
     }
+
+    /// Create a new [Imm32Op] writing `value` to the cell at `fp` offset.
+    /// Fails if the operands are out of the ranges defined by the Valida ISA.
+    pub fn new_checked(
+        ctx: &mut Context,
+        fp: FramePointer,
+        value: u32,
+    ) -> Result<Imm32Op, OperandsError> {
+        let operands = Operands::checked_imm32(fp, value)?;
+        Ok(Self::new_unlinked(ctx, operands))
+    }
 }
 
 #[intertrait::cast_to]
@@ -58,8 +73,19 @@ impl DisplayWithContext for Imm32Op {
 }
 
 impl Verify for Imm32Op {
-    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
-        todo!()
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
     }
 }
 
@@ -145,6 +171,9 @@ declare_op!(
 );
 
 impl FuncOp {
+    /// Attribute key for the frame size in bytes (see [Self::set_frame_size])
+    pub const ATTR_KEY_FRAME_SIZE: &str = "func.frame_size";
+
     /// Create a new [FuncOp].
     /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
     /// The returned function has a single region with an empty `entry` block.
@@ -173,6 +202,27 @@ impl FuncOp {
             .iter(ctx)
             .flat_map(|bb| bb.deref(ctx).iter(ctx))
     }
+
+    /// Get the frame size in bytes if it was computed
+    #[allow(clippy::expect_used)]
+    pub fn get_frame_size(&self, ctx: &Context) -> Option<u32> {
+        let self_op = self.get_operation().deref(ctx);
+        self_op
+            .attributes
+            .get(Self::ATTR_KEY_FRAME_SIZE)
+            .map(|attr_obj| to_u32_checked(ctx, attr_obj).expect("frame size should be u32"))
+    }
+
+    /// Record the frame size in bytes: the cells below fp (locals, stack and scratch cells)
+    /// and the linkage area of the callee (return address, return fp and return value).
+    /// The callee frame starts below it (see the call lowering).
+    pub fn set_frame_size(&self, ctx: &mut Context, frame_size: u32) {
+        let frame_size_attr = u32_attr(ctx, frame_size);
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FRAME_SIZE, frame_size_attr);
+    }
 }
 
 impl OneRegionInterface for FuncOp {}
@@ -293,6 +343,12 @@ impl JalvOp {
         jalv_op.set_operands(ctx, operands);
         jalv_op
     }
+
+    /// Is this the return from the function (see [Self::new_return_pseudo_op])?
+    pub fn is_return_pseudo_op(&self, ctx: &Context) -> bool {
+        let operands = self.get_operands(ctx);
+        operands.a().as_i32() == -4 && operands.b().as_i32() == 0 && operands.c().as_i32() == 4
+    }
 }
 
 impl DisplayWithContext for JalvOp {
@@ -367,6 +423,123 @@ impl Verify for SwOp {
 #[intertrait::cast_to]
 impl HasOperands for SwOp {}
 
+/// Declares a U32 ALU op that computes `[a] = [b] op [c]` for the cell offsets a, b and c.
+macro_rules! declare_alu_op {
+    ($(#[$outer:meta])* $op:ident, $op_name:literal) => {
+        declare_op!(
+            $(#[$outer])*
+            $op,
+            $op_name,
+            "valida"
+        );
+
+        impl $op {
+            /// Create a new op writing the result of `[arg1_fp] op [arg2_fp]` to `result_fp`
+            pub fn new(ctx: &mut Context, result_fp: i32, arg1_fp: i32, arg2_fp: i32) -> $op {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                let op_op = $op { op };
+                let operands = Operands::from_i32(result_fp, arg1_fp, arg2_fp, 0, 0);
+                op_op.set_operands(ctx, operands);
+                op_op
+            }
+        }
+
+        impl DisplayWithContext for $op {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                let operands = self.get_operands(ctx);
+                write!(
+                    f,
+                    "{} {}(fp) {}(fp) {}(fp) {} {}",
+                    self.get_opid().with_ctx(ctx),
+                    operands.a(),
+                    operands.b(),
+                    operands.c(),
+                    operands.d(),
+                    operands.e(),
+                )
+            }
+        }
+
+        impl Verify for $op {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                if self.get_operation().deref(ctx).get_opid() != Self::get_opid_static() {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect OpId".to_string(),
+                    });
+                }
+                Ok(())
+            }
+        }
+
+        #[intertrait::cast_to]
+        impl HasOperands for $op {}
+    };
+}
+
+declare_alu_op!(
+    /// Compute the unchecked (wrapping) subtraction of the U32 values at cell offsets b and c
+    /// and write the difference to cell offset a.
+    SubOp,
+    "sub"
+);
+
+declare_alu_op!(
+    /// Compute the unchecked (wrapping) multiplication of the U32 values at cell offsets b and c
+    /// and write the product to cell offset a.
+    MulOp,
+    "mul"
+);
+
+declare_alu_op!(
+    /// Compute the unsigned division of the U32 value at cell offset b by the one at offset c
+    /// and write the quotient to cell offset a.
+    /// The result is unconstrained if the divisor is zero (no trap).
+    DivOp,
+    "div"
+);
+
+declare_alu_op!(
+    /// Compute the bitwise and of the U32 values at cell offsets b and c
+    /// and write the result to cell offset a.
+    AndOp,
+    "and"
+);
+
+declare_alu_op!(
+    /// Compute the bitwise or of the U32 values at cell offsets b and c
+    /// and write the result to cell offset a.
+    OrOp,
+    "or"
+);
+
+declare_alu_op!(
+    /// Compute the bitwise exclusive or of the U32 values at cell offsets b and c
+    /// and write the result to cell offset a.
+    XorOp,
+    "xor"
+);
+
+declare_alu_op!(
+    /// Shift the U32 value at cell offset b left by the value at offset c
+    /// and write the result to cell offset a.
+    ShlOp,
+    "shl"
+);
+
+declare_alu_op!(
+    /// Shift the U32 value at cell offset b right (zero-filling) by the value at offset c
+    /// and write the result to cell offset a.
+    ShrOp,
+    "shr"
+);
+
+declare_alu_op!(
+    /// Compare the U32 values at cell offsets b and c
+    /// and write 1 to cell offset a if b < c, 0 otherwise.
+    LtOp,
+    "lt"
+);
+
 declare_op!(
     /// jump to address and link
     /// Store the pc + 1 to local stack variable at offset "a" then set pc to field element "b".
@@ -505,6 +678,202 @@ impl Verify for JalSymOp {
 #[intertrait::cast_to]
 impl HasOperands for JalSymOp {}
 
+declare_op!(
+    /// branch if equal
+    /// Set pc to field element "a" if the value at cell offset "b" is equal to the one at offset "c"
+    /// (or to the immediate value "c" if "e" is 1).
+    BeqOp,
+    "beq",
+    "valida"
+);
+
+impl BeqOp {
+    /// Create a new [BeqOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn from_operands(ctx: &mut Context, operands: Operands) -> BeqOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        let beq_op = BeqOp { op };
+        beq_op.set_operands(ctx, operands);
+        beq_op
+    }
+}
+
+impl DisplayWithContext for BeqOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let operands = self.get_operands(ctx);
+        write!(
+            f,
+            "{} {} {}(fp) {} {} {}",
+            self.get_opid().with_ctx(ctx),
+            operands.a(),
+            operands.b(),
+            operands.c(),
+            operands.d(),
+            operands.e()
+        )
+    }
+}
+
+impl Verify for BeqOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        if self.get_operation().deref(ctx).get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[intertrait::cast_to]
+impl HasOperands for BeqOp {}
+
+declare_op!(
+    /// branch if equal (symbolic label version)
+    /// Jump to the [LabelOp] with the given name if the value at cell offset "b" is equal
+    /// to the one at offset "c" (or to the immediate value "c" if "e" is 1).
+    BeqSymOp,
+    "beqsym",
+    "valida"
+);
+
+impl BeqSymOp {
+    const ATTR_KEY_TARGET_LABEL: &str = "beqsym.target_label";
+
+    /// Jump to `target_label` if the value at `arg_fp` is equal to `imm`
+    pub fn new_imm(ctx: &mut Context, target_label: String, arg_fp: i32, imm: i32) -> BeqSymOp {
+        Self::new(ctx, target_label, Operands::from_i32(0, arg_fp, imm, 0, 1))
+    }
+
+    /// Jump to `target_label` unconditionally (the value at `arg_fp` is compared with itself)
+    pub fn new_always(ctx: &mut Context, target_label: String, arg_fp: i32) -> BeqSymOp {
+        Self::new(
+            ctx,
+            target_label,
+            Operands::from_i32(0, arg_fp, arg_fp, 0, 0),
+        )
+    }
+
+    fn new(ctx: &mut Context, target_label: String, operands: Operands) -> BeqSymOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        op.deref_mut(ctx).attributes.insert(
+            Self::ATTR_KEY_TARGET_LABEL,
+            StringAttr::create(target_label),
+        );
+        let beqsym_op = BeqSymOp { op };
+        beqsym_op.set_operands(ctx, operands);
+        beqsym_op
+    }
+
+    /// Get the target label
+    pub fn get_target_label(&self, ctx: &Context) -> String {
+        let op = self.get_operation().deref(ctx);
+        #[allow(clippy::expect_used)]
+        let label_attr = op
+            .attributes
+            .get(Self::ATTR_KEY_TARGET_LABEL)
+            .expect("no attribute found");
+        #[allow(clippy::expect_used)]
+        let label: String = label_attr
+            .downcast_ref::<StringAttr>()
+            .expect("expected StringAttr")
+            .clone()
+            .into();
+        label
+    }
+}
+
+impl DisplayWithContext for BeqSymOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let operands = self.get_operands(ctx);
+        write!(
+            f,
+            "{} {} {}(fp) {} {} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_target_label(ctx),
+            operands.b(),
+            operands.c(),
+            operands.d(),
+            operands.e()
+        )
+    }
+}
+
+impl Verify for BeqSymOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        if self.get_operation().deref(ctx).get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[intertrait::cast_to]
+impl HasOperands for BeqSymOp {}
+
+declare_op!(
+    /// A named position in the function body, a target of [BeqSymOp].
+    /// Takes no space in the program, its pc is the pc of the next instruction.
+    LabelOp,
+    "label",
+    "valida"
+);
+
+impl LabelOp {
+    const ATTR_KEY_NAME: &str = "label.name";
+
+    /// Create a new [LabelOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context, name: String) -> LabelOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_NAME, StringAttr::create(name));
+        LabelOp { op }
+    }
+
+    /// Get the label name
+    pub fn get_name(&self, ctx: &Context) -> String {
+        let op = self.get_operation().deref(ctx);
+        #[allow(clippy::expect_used)]
+        let name_attr = op
+            .attributes
+            .get(Self::ATTR_KEY_NAME)
+            .expect("no attribute found");
+        #[allow(clippy::expect_used)]
+        let name: String = name_attr
+            .downcast_ref::<StringAttr>()
+            .expect("expected StringAttr")
+            .clone()
+            .into();
+        name
+    }
+}
+
+impl DisplayWithContext for LabelOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_name(ctx)
+        )
+    }
+}
+
+impl Verify for LabelOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        if self.get_operation().deref(ctx).get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
 declare_op!(
     /// Exit the program (halts execution)
     ExitOp,
@@ -533,14 +902,55 @@ impl Verify for ExitOp {
     }
 }
 
+declare_op!(
+    /// Fail the program (aborts execution)
+    TrapOp,
+    "trap",
+    "valida"
+);
+
+impl TrapOp {
+    /// Create a new [TrapOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context) -> TrapOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        TrapOp { op }
+    }
+}
+
+impl DisplayWithContext for TrapOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx),)
+    }
+}
+
+impl Verify for TrapOp {
+    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
+        Ok(())
+    }
+}
+
 pub(crate) fn register(ctx: &mut Context, dialect: &mut Dialect) {
     Imm32Op::register(ctx, dialect);
     ProgramOp::register(ctx, dialect);
     FuncOp::register(ctx, dialect);
     AddOp::register(ctx, dialect);
+    SubOp::register(ctx, dialect);
+    MulOp::register(ctx, dialect);
+    DivOp::register(ctx, dialect);
+    AndOp::register(ctx, dialect);
+    OrOp::register(ctx, dialect);
+    XorOp::register(ctx, dialect);
+    ShlOp::register(ctx, dialect);
+    ShrOp::register(ctx, dialect);
+    LtOp::register(ctx, dialect);
     JalvOp::register(ctx, dialect);
     SwOp::register(ctx, dialect);
     JalOp::register(ctx, dialect);
     JalSymOp::register(ctx, dialect);
+    BeqOp::register(ctx, dialect);
+    BeqSymOp::register(ctx, dialect);
+    LabelOp::register(ctx, dialect);
     ExitOp::register(ctx, dialect);
+    TrapOp::register(ctx, dialect);
 }
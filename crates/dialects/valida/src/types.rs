@@ -83,6 +83,8 @@ pub struct Mersenne31(i32);
 
 impl Mersenne31 {
     pub const ZERO: Self = Self(0);
+    /// The field modulus, 2^31 - 1
+    pub const MODULUS: i64 = (1 << 31) - 1;
 
     pub fn as_i32(self) -> i32 {
         self.0
@@ -106,6 +108,20 @@ impl TryFrom<&IntegerAttr> for Mersenne31 {
     }
 }
 
+/// Errors of the checked [Operands] constructors
+#[derive(Clone, Error, Debug, PartialEq, Eq)]
+pub enum OperandsError {
+    #[error("frame pointer offset {0} is not word aligned")]
+    UnalignedFpOffset(i32),
+    #[error("operand {name} = {value} is out of range {min}..={max}")]
+    OutOfRange {
+        name: &'static str,
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+}
+
 #[derive(Copy, Clone, Default)]
 pub struct Operands([Mersenne31; 5]);
 
@@ -124,10 +140,22 @@ impl Operands {
         ])
     }
 
+    /// Operands of `imm32` writing `value` to the cell at `fp` offset.
+    /// Each of the operands b, c, d, e holds a byte of the value (b is the most significant one).
+    pub fn checked_imm32(fp: FramePointer, value: u32) -> Result<Self, OperandsError> {
+        let a = checked_fp_offset(fp)?;
+        let [b, c, d, e] = value.to_be_bytes().map(i32::from);
+        Ok(Self::from_i32(a, b, c, d, e))
+    }
+
     pub fn a(&self) -> Mersenne31 {
         self.0[0]
     }
 
+    pub fn set_a(&mut self, value: i32) {
+        self.0[0] = Mersenne31(value);
+    }
+
     pub fn b(&self) -> Mersenne31 {
         self.0[1]
     }
@@ -149,6 +177,23 @@ impl Operands {
     }
 }
 
+/// Checks that the frame pointer offset is word aligned and fits in the field
+fn checked_fp_offset(fp: FramePointer) -> Result<i32, OperandsError> {
+    let offset = i32::from(fp);
+    if offset % 4 != 0 {
+        return Err(OperandsError::UnalignedFpOffset(offset));
+    }
+    if i64::from(offset).abs() >= Mersenne31::MODULUS {
+        return Err(OperandsError::OutOfRange {
+            name: "a",
+            value: offset.into(),
+            min: 1 - Mersenne31::MODULUS,
+            max: Mersenne31::MODULUS - 1,
+        });
+    }
+    Ok(offset)
+}
+
 impl From<Operands> for valida_machine::Operands<i32> {
     fn from(value: Operands) -> Self {
         valida_machine::Operands([
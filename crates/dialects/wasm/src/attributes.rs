@@ -1,16 +1,180 @@
 //! Attributes for the wasm dialect.
 
-// use pliron::attribute::AttrObj;
-// use pliron::attribute::Attribute;
-// use pliron::common_traits::DisplayWithContext;
-// use pliron::common_traits::Verify;
-// use pliron::context::Context;
-// use pliron::context::Ptr;
-// use pliron::dialects::builtin::attr_interfaces::TypedAttrInterface;
-// use pliron::dialects::builtin::attributes::IntegerAttr;
-// use pliron::error::CompilerError;
-// use pliron::impl_attr;
-// use pliron::r#type::TypeObj;
-
-// use intertrait::cast_to;
-// use pliron::with_context::AttachContext;
+use pliron::attribute::AttrObj;
+use pliron::attribute::Attribute;
+use pliron::common_traits::DisplayWithContext;
+use pliron::common_traits::Verify;
+use pliron::context::Context;
+use pliron::error::CompilerError;
+use pliron::impl_attr;
+
+use crate::types::BinaryOpcode;
+use crate::types::DataIndex;
+use crate::types::FuncIndex;
+use crate::types::GlobalIndex;
+use crate::types::LocalIndex;
+use crate::types::MemoryIndex;
+use crate::types::TableIndex;
+use crate::types::TypeIndex;
+
+/// Declares an attribute holding a typed index.
+macro_rules! index_attr {
+    ($(#[$outer:meta])* $attr:ident, $index:ty, $attr_name:literal) => {
+        $(#[$outer])*
+        #[derive(PartialEq, Eq, Clone, Debug)]
+        pub struct $attr($index);
+        impl_attr!($attr, $attr_name, "wasm");
+
+        impl $attr {
+            /// Create a new attribute.
+            pub fn create(index: $index) -> AttrObj {
+                Box::new($attr(index))
+            }
+
+            /// Get the index.
+            pub fn get_index(&self) -> $index {
+                self.0
+            }
+        }
+
+        impl From<$attr> for $index {
+            fn from(value: $attr) -> Self {
+                value.0
+            }
+        }
+
+        impl DisplayWithContext for $attr {
+            fn fmt(&self, _ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl Verify for $attr {
+            fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
+                Ok(())
+            }
+        }
+    };
+}
+
+index_attr!(
+    /// An attribute containing a [LocalIndex].
+    LocalIndexAttr,
+    LocalIndex,
+    "LocalIndex"
+);
+index_attr!(
+    /// An attribute containing a [GlobalIndex].
+    GlobalIndexAttr,
+    GlobalIndex,
+    "GlobalIndex"
+);
+index_attr!(
+    /// An attribute containing a [FuncIndex].
+    FuncIndexAttr,
+    FuncIndex,
+    "FuncIndex"
+);
+index_attr!(
+    /// An attribute containing a [TypeIndex].
+    TypeIndexAttr,
+    TypeIndex,
+    "TypeIndex"
+);
+index_attr!(
+    /// An attribute containing a [TableIndex].
+    TableIndexAttr,
+    TableIndex,
+    "TableIndex"
+);
+index_attr!(
+    /// An attribute containing a [MemoryIndex].
+    MemoryIndexAttr,
+    MemoryIndex,
+    "MemoryIndex"
+);
+index_attr!(
+    /// An attribute containing a [DataIndex].
+    DataIndexAttr,
+    DataIndex,
+    "DataIndex"
+);
+
+/// An attribute containing raw bytes (e.g. the contents of a data segment).
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct BytesAttr(Vec<u8>);
+impl_attr!(BytesAttr, "Bytes", "wasm");
+
+impl BytesAttr {
+    /// Create a new attribute.
+    pub fn create(bytes: Vec<u8>) -> AttrObj {
+        Box::new(BytesAttr(bytes))
+    }
+
+    /// Get the bytes.
+    pub fn get_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl DisplayWithContext for BytesAttr {
+    /// A WAT string literal, the non-printable bytes as `\hh`
+    fn fmt(&self, _ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "\"")?;
+        for byte in &self.0 {
+            match byte {
+                b'"' | b'\\' => write!(f, "\\{}", *byte as char)?,
+                0x20..=0x7e => write!(f, "{}", *byte as char)?,
+                _ => write!(f, "\\{byte:02x}")?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+impl Verify for BytesAttr {
+    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
+        Ok(())
+    }
+}
+
+/// An attribute containing a [BinaryOpcode].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct BinaryOpcodeAttr(BinaryOpcode);
+impl_attr!(BinaryOpcodeAttr, "BinaryOpcode", "wasm");
+
+impl BinaryOpcodeAttr {
+    /// Create a new attribute.
+    pub fn create(opcode: BinaryOpcode) -> AttrObj {
+        Box::new(BinaryOpcodeAttr(opcode))
+    }
+
+    /// Get the opcode.
+    pub fn get_opcode(&self) -> BinaryOpcode {
+        self.0
+    }
+}
+
+impl DisplayWithContext for BinaryOpcodeAttr {
+    fn fmt(&self, _ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0.mnemonic())
+    }
+}
+
+impl Verify for BinaryOpcodeAttr {
+    fn verify(&self, _ctx: &Context) -> Result<(), CompilerError> {
+        Ok(())
+    }
+}
+
+pub(crate) fn register(dialect: &mut pliron::dialect::Dialect) {
+    LocalIndexAttr::register_attr_in_dialect(dialect);
+    GlobalIndexAttr::register_attr_in_dialect(dialect);
+    FuncIndexAttr::register_attr_in_dialect(dialect);
+    TypeIndexAttr::register_attr_in_dialect(dialect);
+    TableIndexAttr::register_attr_in_dialect(dialect);
+    MemoryIndexAttr::register_attr_in_dialect(dialect);
+    DataIndexAttr::register_attr_in_dialect(dialect);
+    BytesAttr::register_attr_in_dialect(dialect);
+    BinaryOpcodeAttr::register_attr_in_dialect(dialect);
+}
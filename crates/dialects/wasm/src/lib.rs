@@ -32,7 +32,7 @@ pub fn register(ctx: &mut Context) {
     let mut dialect = Dialect::new(WASM_DIALECT_NAME());
     ops::register(ctx, &mut dialect);
     // types::register(&mut dialect);
-    // attributes::register(&mut dialect);
+    attributes::register(&mut dialect);
     dialect.register(ctx);
 }
 
@@ -2,19 +2,82 @@
 
 #![allow(clippy::expect_used)]
 
-use apint::ApInt;
+use ozk_ozk_dialect::attributes::to_u32_checked;
 use ozk_ozk_dialect::attributes::u32_attr;
-use pliron::attribute;
 use pliron::context::Context;
-use pliron::dialects::builtin::attributes::IntegerAttr;
 use pliron::error::CompilerError;
 use pliron::op::Op;
 
-use crate::ops::AddOp;
+use crate::ops::BinaryArithOp;
+use crate::ops::BlockOp;
+use crate::ops::BrIfOp;
+use crate::ops::BrOp;
+use crate::ops::BrTableOp;
+use crate::ops::CallIndirectOp;
 use crate::ops::ConstantOp;
+use crate::ops::DataDropOp;
+use crate::ops::DropOp;
+use crate::ops::GlobalGetOp;
+use crate::ops::GlobalSetOp;
+use crate::ops::I32ClzOp;
+use crate::ops::I32CtzOp;
+use crate::ops::I32EqOp;
+use crate::ops::I32EqzOp;
+use crate::ops::I32Extend16SOp;
+use crate::ops::I32Extend8SOp;
+use crate::ops::I32GeSOp;
+use crate::ops::I32GeUOp;
+use crate::ops::I32GtSOp;
+use crate::ops::I32GtUOp;
+use crate::ops::I32LeSOp;
+use crate::ops::I32LeUOp;
+use crate::ops::I32Load16SOp;
+use crate::ops::I32Load16UOp;
+use crate::ops::I32Load8SOp;
+use crate::ops::I32Load8UOp;
+use crate::ops::I32LtSOp;
+use crate::ops::I32LtUOp;
+use crate::ops::I32NeOp;
+use crate::ops::I32PopcntOp;
+use crate::ops::I64ClzOp;
+use crate::ops::I64CtzOp;
+use crate::ops::I64EqOp;
+use crate::ops::I64EqzOp;
+use crate::ops::I64Extend16SOp;
+use crate::ops::I64Extend32SOp;
+use crate::ops::I64Extend8SOp;
+use crate::ops::I64GeSOp;
+use crate::ops::I64GeUOp;
+use crate::ops::I64GtSOp;
+use crate::ops::I64GtUOp;
+use crate::ops::I64LeSOp;
+use crate::ops::I64LeUOp;
+use crate::ops::I64Load16SOp;
+use crate::ops::I64Load16UOp;
+use crate::ops::I64Load32SOp;
+use crate::ops::I64Load32UOp;
+use crate::ops::I64Load8SOp;
+use crate::ops::I64Load8UOp;
+use crate::ops::I64LtSOp;
+use crate::ops::I64LtUOp;
+use crate::ops::I64NeOp;
+use crate::ops::I64PopcntOp;
+use crate::ops::IfOp;
+use crate::ops::LoadOp;
 use crate::ops::LocalGetOp;
 use crate::ops::LocalSetOp;
+use crate::ops::LocalTeeOp;
+use crate::ops::LoopOp;
+use crate::ops::MemoryGrowOp;
+use crate::ops::MemoryInitOp;
+use crate::ops::MemorySizeOp;
+use crate::ops::ReturnCallIndirectOp;
 use crate::ops::ReturnOp;
+use crate::ops::SelectOp;
+use crate::ops::StoreOp;
+use crate::ops::TableGetOp;
+use crate::ops::TableSetOp;
+use crate::ops::UnreachableOp;
 use crate::types::StackDepth;
 
 /// The attribute key for the stack depth.
@@ -29,13 +92,9 @@ pub trait TrackedStackDepth: Op {
             .attributes
             .get(ATTR_KEY_STACK_DEPTH)
             .expect("no stack depth attribute found, expected it to be set by the special pass");
-        let attr = attribute::clone::<IntegerAttr>(value);
-        let apint: ApInt = attr
-            .downcast_ref::<IntegerAttr>()
-            .expect("IntegerAttr expected")
-            .clone()
-            .into();
-        apint.try_to_u32().expect("expected u32").into()
+        to_u32_checked(ctx, value)
+            .expect("stack depth should be u32")
+            .into()
     }
 
     /// Set a name for the symbol defined by this operation.
@@ -73,6 +132,52 @@ impl StackDepthChange for ozk_ozk_dialect::ops::CallOp {
     }
 }
 
+#[intertrait::cast_to]
+impl TrackedStackDepth for ozk_ozk_dialect::ops::ReturnCallOp {}
+
+#[intertrait::cast_to]
+impl StackDepthChange for ozk_ozk_dialect::ops::ReturnCallOp {
+    fn get_stack_depth_change(&self, ctx: &Context) -> i32 {
+        // the results are left for the caller of the current function
+        let func_type = self.get_func_type(ctx);
+        -(func_type.get_inputs().len() as i32) + func_type.get_results().len() as i32
+    }
+}
+
+#[intertrait::cast_to]
+impl TrackedStackDepth for ozk_ozk_dialect::ops::HostCallOp {}
+
+#[intertrait::cast_to]
+impl StackDepthChange for ozk_ozk_dialect::ops::HostCallOp {
+    fn get_stack_depth_change(&self, ctx: &Context) -> i32 {
+        let func_type = self.get_func_type(ctx);
+        -(func_type.get_inputs().len() as i32) + func_type.get_results().len() as i32
+    }
+}
+
+#[intertrait::cast_to]
+impl TrackedStackDepth for CallIndirectOp {}
+
+#[intertrait::cast_to]
+impl StackDepthChange for CallIndirectOp {
+    fn get_stack_depth_change(&self, ctx: &Context) -> i32 {
+        let func_type = self.get_func_type(ctx);
+        // the table index is popped as well
+        -1 - (func_type.get_inputs().len() as i32) + func_type.get_results().len() as i32
+    }
+}
+
+#[intertrait::cast_to]
+impl TrackedStackDepth for ReturnCallIndirectOp {}
+
+#[intertrait::cast_to]
+impl StackDepthChange for ReturnCallIndirectOp {
+    fn get_stack_depth_change(&self, ctx: &Context) -> i32 {
+        let func_type = self.get_func_type(ctx);
+        -1 - (func_type.get_inputs().len() as i32) + func_type.get_results().len() as i32
+    }
+}
+
 macro_rules! stack_depth_change {
     ($op:ty, $change:expr) => {
         #[intertrait::cast_to]
@@ -88,7 +193,86 @@ macro_rules! stack_depth_change {
 }
 
 stack_depth_change!(ConstantOp, 1);
-stack_depth_change!(AddOp, -1);
+stack_depth_change!(ozk_ozk_dialect::ops::ClockOp, 1);
+stack_depth_change!(ozk_ozk_dialect::ops::DebugPrintOp, -1);
+stack_depth_change!(ozk_ozk_dialect::ops::HaltOp, -1);
+stack_depth_change!(ozk_ozk_dialect::ops::TrapOp, 0);
+stack_depth_change!(ozk_ozk_dialect::ops::AssertOp, -1);
+stack_depth_change!(ozk_ozk_dialect::ops::U32CheckedAddOp, -1);
+stack_depth_change!(ozk_ozk_dialect::ops::U32CheckedSubOp, -1);
+stack_depth_change!(ozk_ozk_dialect::ops::ReservedGetOp, 1);
+stack_depth_change!(ozk_ozk_dialect::ops::ReservedSetOp, -1);
+stack_depth_change!(BinaryArithOp, -1);
+stack_depth_change!(SelectOp, -2);
+stack_depth_change!(DropOp, -1);
 stack_depth_change!(ReturnOp, 0);
+// the code after `unreachable` is unreachable
+stack_depth_change!(UnreachableOp, 0);
 stack_depth_change!(LocalGetOp, 1);
 stack_depth_change!(LocalSetOp, -1);
+stack_depth_change!(LocalTeeOp, 0);
+stack_depth_change!(GlobalGetOp, 1);
+stack_depth_change!(GlobalSetOp, -1);
+stack_depth_change!(MemorySizeOp, 1);
+// pops the delta, pushes the previous size
+stack_depth_change!(MemoryGrowOp, 0);
+// pops the destination, the offset in the segment and the number of bytes
+stack_depth_change!(MemoryInitOp, -3);
+stack_depth_change!(DataDropOp, 0);
+// pops the index, pushes the entry
+stack_depth_change!(TableGetOp, 0);
+stack_depth_change!(TableSetOp, -2);
+stack_depth_change!(LoadOp, 0);
+stack_depth_change!(StoreOp, -2);
+// the block body ops account for the block params and results
+stack_depth_change!(BlockOp, 0);
+stack_depth_change!(LoopOp, 0);
+// pops the condition, the branch bodies account for the params and results
+stack_depth_change!(IfOp, -1);
+// the code after `br` is unreachable
+stack_depth_change!(BrOp, 0);
+stack_depth_change!(BrIfOp, -1);
+stack_depth_change!(BrTableOp, -1);
+stack_depth_change!(I32EqzOp, 0);
+stack_depth_change!(I32EqOp, -1);
+stack_depth_change!(I32NeOp, -1);
+stack_depth_change!(I32LtSOp, -1);
+stack_depth_change!(I32LtUOp, -1);
+stack_depth_change!(I32GtSOp, -1);
+stack_depth_change!(I32GtUOp, -1);
+stack_depth_change!(I32LeSOp, -1);
+stack_depth_change!(I32LeUOp, -1);
+stack_depth_change!(I32GeSOp, -1);
+stack_depth_change!(I32GeUOp, -1);
+stack_depth_change!(I64EqzOp, 0);
+stack_depth_change!(I64EqOp, -1);
+stack_depth_change!(I64NeOp, -1);
+stack_depth_change!(I64LtSOp, -1);
+stack_depth_change!(I64LtUOp, -1);
+stack_depth_change!(I64GtSOp, -1);
+stack_depth_change!(I64GtUOp, -1);
+stack_depth_change!(I64LeSOp, -1);
+stack_depth_change!(I64LeUOp, -1);
+stack_depth_change!(I64GeSOp, -1);
+stack_depth_change!(I64GeUOp, -1);
+stack_depth_change!(I32Extend8SOp, 0);
+stack_depth_change!(I32Extend16SOp, 0);
+stack_depth_change!(I64Extend8SOp, 0);
+stack_depth_change!(I64Extend16SOp, 0);
+stack_depth_change!(I64Extend32SOp, 0);
+stack_depth_change!(I32ClzOp, 0);
+stack_depth_change!(I32CtzOp, 0);
+stack_depth_change!(I32PopcntOp, 0);
+stack_depth_change!(I64ClzOp, 0);
+stack_depth_change!(I64CtzOp, 0);
+stack_depth_change!(I64PopcntOp, 0);
+stack_depth_change!(I32Load8SOp, 0);
+stack_depth_change!(I32Load8UOp, 0);
+stack_depth_change!(I32Load16SOp, 0);
+stack_depth_change!(I32Load16UOp, 0);
+stack_depth_change!(I64Load8SOp, 0);
+stack_depth_change!(I64Load8UOp, 0);
+stack_depth_change!(I64Load16SOp, 0);
+stack_depth_change!(I64Load16UOp, 0);
+stack_depth_change!(I64Load32SOp, 0);
+stack_depth_change!(I64Load32UOp, 0);
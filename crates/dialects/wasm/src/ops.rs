@@ -5,14 +5,19 @@
 #![allow(clippy::panic)]
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::ops::Deref;
 
 use apint::ApInt;
 use derive_more::Display;
 use intertrait::cast_to;
-use ozk_ozk_dialect::attributes::apint_to_i32;
 use ozk_ozk_dialect::attributes::i32_attr;
+use ozk_ozk_dialect::attributes::i64_attr;
+use ozk_ozk_dialect::attributes::to_i32_checked;
+use ozk_ozk_dialect::attributes::to_i64_checked;
+use ozk_ozk_dialect::attributes::to_u32_checked;
 use ozk_ozk_dialect::attributes::u32_attr;
+use ozk_ozk_dialect::attributes::IntConversionError;
 use ozk_ozk_dialect::types::i32_type;
 use ozk_ozk_dialect::types::i64_type;
 use ozk_ozk_dialect::types::u32_type_unwrapped;
@@ -49,10 +54,31 @@ use pliron::operation::WalkResult;
 use pliron::r#type::TypeObj;
 use pliron::with_context::AttachContext;
 
+use crate::attributes::BinaryOpcodeAttr;
+use crate::attributes::BytesAttr;
+use crate::attributes::DataIndexAttr;
+use crate::attributes::FuncIndexAttr;
+use crate::attributes::GlobalIndexAttr;
+use crate::attributes::LocalIndexAttr;
+use crate::attributes::MemoryIndexAttr;
+use crate::attributes::TableIndexAttr;
+use crate::attributes::TypeIndexAttr;
+use crate::types::BinaryOpcode;
+use crate::types::DataIndex;
+use crate::types::ElemSegment;
 use crate::types::FuncIndex;
 use crate::types::GlobalIndex;
+use crate::types::GlobalInit;
+use crate::types::ImportGlobal;
 use crate::types::LocalIndex;
+use crate::types::MemArg;
+use crate::types::MemoryIndex;
+use crate::types::MemoryLimits;
+use crate::types::PrologueStage;
 use crate::types::RelativeDepth;
+use crate::types::StackDepth;
+use crate::types::TableIndex;
+use crate::types::TypeIndex;
 
 declare_op!(
     /// Represents a Wasm module, a top level container operation.
@@ -68,6 +94,12 @@ declare_op!(
     /// |-----|-------|
     /// | [ATTR_KEY_SYM_NAME](super::ATTR_KEY_SYM_NAME) | [StringAttr](super::attributes::StringAttr) |
     /// | [ATTR_KEY_START_FUNC_SYM](ModuleOp::ATTR_KEY_START_FUNC_SYM) | [StringAttr](super::attributes::StringAttr) |
+    /// | [ATTR_KEY_TABLE_SIZES](ModuleOp::ATTR_KEY_TABLE_SIZES) | [VecAttr](super::attributes::VecAttr) |
+    /// | [ATTR_KEY_ELEM_SEGMENTS](ModuleOp::ATTR_KEY_ELEM_SEGMENTS) | [VecAttr](super::attributes::VecAttr) |
+    /// | [ATTR_KEY_PROLOGUE_FUNCS](ModuleOp::ATTR_KEY_PROLOGUE_FUNCS) | [VecAttr](super::attributes::VecAttr) |
+    /// | [ATTR_KEY_MEMORY_LIMITS](ModuleOp::ATTR_KEY_MEMORY_LIMITS) | [VecAttr](super::attributes::VecAttr) |
+    /// | [ATTR_KEY_PASSIVE_DATA](ModuleOp::ATTR_KEY_PASSIVE_DATA) | [VecAttr](super::attributes::VecAttr) |
+    /// | [ATTR_KEY_CUSTOM_SECTIONS](ModuleOp::ATTR_KEY_CUSTOM_SECTIONS) | [VecAttr](super::attributes::VecAttr) |
     ModuleOp,
     "module",
     "wasm"
@@ -88,8 +120,17 @@ impl DisplayWithContext for ModuleOp {
 
 impl Verify for ModuleOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        // TODO: check that the start function is defined.
         self.verify_interfaces(ctx)?;
+        if let Some(start_func_sym) = self.try_get_start_func_sym(ctx) {
+            if self.get_func_index(ctx, start_func_sym.clone()).is_none() {
+                return Err(CompilerError::VerificationError {
+                    msg: format!("Start function {start_func_sym:?} is not defined"),
+                });
+            }
+        }
+        self.verify_func_indices(ctx)?;
+        self.verify_elem_segments(ctx)?;
+        self.verify_prologue_funcs(ctx)?;
         self.get_region(ctx).deref(ctx).verify(ctx)
     }
 }
@@ -107,15 +148,31 @@ impl ModuleOp {
     pub const ATTR_KEY_IMPORT_FUNC_TYPES: &str = "module.import_func_types";
     /// Attribute key for the import function modules.
     pub const ATTR_KEY_IMPORT_FUNC_MODULES: &str = "module.import_func_modules";
+    /// Attribute key for the (initial) sizes of the function tables.
+    pub const ATTR_KEY_TABLE_SIZES: &str = "module.table_sizes";
+    /// Attribute key for the active element segments (table index, offset, function indices).
+    pub const ATTR_KEY_ELEM_SEGMENTS: &str = "module.elem_segments";
+    /// Attribute key for the prologue functions (stage, function symbol) in the order they were added.
+    pub const ATTR_KEY_PROLOGUE_FUNCS: &str = "module.prologue_funcs";
+    /// Attribute key for the limits (in pages) of the linear memories.
+    pub const ATTR_KEY_MEMORY_LIMITS: &str = "module.memory_limits";
+    /// Attribute key for the bytes of the passive data segments.
+    pub const ATTR_KEY_PASSIVE_DATA: &str = "module.passive_data";
+    /// Attribute key for the imported globals (module, name, value type, mutability).
+    pub const ATTR_KEY_IMPORT_GLOBALS: &str = "module.import_globals";
+    /// Attribute key for the custom sections (name, bytes) not interpreted by the frontend.
+    pub const ATTR_KEY_CUSTOM_SECTIONS: &str = "module.custom_sections";
 
     /// Create a new [ModuleOp].
     /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
     /// The returned module has a single [crate::region::Region] with a single (BasicBlock)[crate::basic_block::BasicBlock].
+    /// Function indices are derived from the symbol table: imported functions come first (in the
+    /// order of `import_func_syms`) followed by `functions` in the order they are appended.
     pub fn new(
         ctx: &mut Context,
         name: &str,
         start_func_name: FuncSym,
-        all_func_syms: Vec<FuncSym>,
+        import_func_syms: Vec<FuncSym>,
         functions: Vec<FuncOp>,
         import_func_types: Vec<Ptr<TypeObj>>,
         import_func_modules: Vec<String>,
@@ -131,7 +188,7 @@ impl ModuleOp {
             opref.attributes.insert(
                 Self::ATTR_KEY_FUNC_INDICES,
                 VecAttr::create(
-                    all_func_syms
+                    import_func_syms
                         .into_iter()
                         .map(|func_sym| StringAttr::create(func_sym.into()))
                         .collect(),
@@ -172,6 +229,21 @@ impl ModuleOp {
         opop
     }
 
+    /// Create a new [ModuleOp] with the given function as its only (and start) function.
+    /// Used to compile a function in isolation, so the function must not call other functions.
+    pub fn new_single_func(ctx: &mut Context, name: &str, func_op: FuncOp) -> ModuleOp {
+        let start_func_sym = FuncSym::from(func_op.get_symbol_name(ctx));
+        Self::new(
+            ctx,
+            name,
+            start_func_sym,
+            vec![],
+            vec![func_op],
+            vec![],
+            vec![],
+        )
+    }
+
     /// Add an [Operation] into this module.
     pub fn append_function(&self, ctx: &mut Context, func_op: FuncOp) -> FuncIndex {
         let func_index = {
@@ -192,23 +264,431 @@ impl ModuleOp {
         func_index.into()
     }
 
-    /// Return the start function symbol name
-    pub fn get_start_func_sym(&self, ctx: &Context) -> FuncSym {
+    /// Add the imported function into this module (after the other imports).
+    /// The indices of the defined functions (and the calls to them) are shifted up.
+    #[allow(clippy::expect_used)]
+    pub fn append_import(
+        &self,
+        ctx: &mut Context,
+        func_sym: FuncSym,
+        func_type: Ptr<TypeObj>,
+        module: &str,
+    ) -> FuncIndex {
+        let import_count = self.get_import_func_types(ctx).len();
+        let shifted = |func_index: FuncIndex| {
+            let index = usize::from(func_index);
+            if index < import_count {
+                func_index
+            } else {
+                (index + 1).into()
+            }
+        };
+        for call_op in self.call_ops(ctx) {
+            let func_index = call_op.get_func_index(ctx);
+            call_op.set_func_index(ctx, shifted(func_index));
+        }
+        let mut elem_segments = self.get_elem_segments(ctx);
+        for func_index in elem_segments
+            .iter_mut()
+            .flat_map(|seg| seg.func_indices.iter_mut())
+        {
+            *func_index = shifted(*func_index);
+        }
+        let table_sizes = self.get_table_sizes(ctx);
+        self.set_tables(ctx, table_sizes, elem_segments);
+        let mut self_op = self.get_operation().deref_mut(ctx);
+        for (key, import_attr) in [
+            (
+                Self::ATTR_KEY_IMPORT_FUNC_TYPES,
+                TypeAttr::create(func_type),
+            ),
+            (
+                Self::ATTR_KEY_IMPORT_FUNC_MODULES,
+                StringAttr::create(module.to_string()),
+            ),
+        ] {
+            self_op
+                .attributes
+                .get_mut(key)
+                .and_then(|attr| attr.downcast_mut::<VecAttr>())
+                .expect("ModuleOp has no import vector attribute")
+                .0
+                .push(import_attr);
+        }
+        self_op
+            .attributes
+            .get_mut(Self::ATTR_KEY_FUNC_INDICES)
+            .and_then(|attr| attr.downcast_mut::<VecAttr>())
+            .expect("ModuleOp has no function symbols vector attribute")
+            .0
+            .insert(import_count, StringAttr::create(func_sym.into()));
+        import_count.into()
+    }
+
+    /// Remove the function from this module.
+    /// The indices of the functions that follow it (and the calls to them) are shifted down.
+    /// The function must not be the start function and must not be called in this module.
+    pub fn remove_function(&self, ctx: &mut Context, func_op: FuncOp) -> Result<(), CompilerError> {
+        let func_sym = FuncSym::from(func_op.get_symbol_name(ctx));
+        if self.try_get_start_func_sym(ctx).as_ref() == Some(&func_sym) {
+            return Err(CompilerError::VerificationError {
+                msg: format!("Cannot remove the start function {func_sym:?}"),
+            });
+        }
+        let func_index = self.get_func_index(ctx, func_sym.clone()).ok_or_else(|| {
+            CompilerError::VerificationError {
+                msg: format!("Cannot remove function {func_sym:?}: not found in the module"),
+            }
+        })?;
+        if self.is_func_called(ctx, func_index) {
+            return Err(CompilerError::VerificationError {
+                msg: format!("Cannot remove function {func_sym:?}: it is still called"),
+            });
+        }
+        let mapping = self.removal_mapping(ctx, func_index);
+        self.remap_func_indices(ctx, &mapping)?;
+        func_op.get_operation().unlink(ctx);
+        Ok(())
+    }
+
+    /// Remove the imported function from this module.
+    /// The indices of the functions that follow it (and the calls to them) are shifted down.
+    /// The function must not be called in this module.
+    pub fn remove_import(
+        &self,
+        ctx: &mut Context,
+        func_sym: &FuncSym,
+    ) -> Result<(), CompilerError> {
+        let import_count = self.get_import_func_types(ctx).len();
+        let func_index = self
+            .get_func_index(ctx, func_sym.clone())
+            .filter(|func_index| usize::from(*func_index) < import_count)
+            .ok_or_else(|| CompilerError::VerificationError {
+                msg: format!("Cannot remove import {func_sym:?}: not imported in the module"),
+            })?;
+        if self.is_func_called(ctx, func_index) {
+            return Err(CompilerError::VerificationError {
+                msg: format!("Cannot remove import {func_sym:?}: it is still called"),
+            });
+        }
+        let mapping = self.removal_mapping(ctx, func_index);
+        self.remap_func_indices(ctx, &mapping)
+    }
+
+    /// Rewrite the function index space of this module: the function index table, the imported
+    /// function types and modules, and the `wasm.call` and `wasm.return_call` ops.
+    /// `mapping[i]` is the new index of the function with the old index `i`,
+    /// `None` removes the function from the index table (the [FuncOp] itself is not unlinked).
+    /// The new indices must be dense, the imports must stay in front of the defined functions,
+    /// and the defined functions must keep their relative order (the module body order).
+    /// Fails without changing the module if the mapping is invalid
+    /// or a removed function is still called.
+    pub fn remap_func_indices(
+        &self,
+        ctx: &mut Context,
+        mapping: &[Option<FuncIndex>],
+    ) -> Result<(), CompilerError> {
+        let func_syms = self.get_func_syms(ctx);
+        if mapping.len() != func_syms.len() {
+            return Err(CompilerError::VerificationError {
+                msg: format!(
+                    "Function index mapping has {} entries, expected {}",
+                    mapping.len(),
+                    func_syms.len()
+                ),
+            });
+        }
+        let import_count = self.get_import_func_types(ctx).len();
+        let new_len = mapping.iter().flatten().count();
+        let new_import_count = mapping[..import_count.min(mapping.len())]
+            .iter()
+            .flatten()
+            .count();
+        let mut new_func_syms: Vec<Option<FuncSym>> = vec![None; new_len];
+        let mut last_defined_index: Option<usize> = None;
+        for (old_index, new_index) in mapping.iter().enumerate() {
+            let Some(new_index) = new_index.map(usize::from) else {
+                continue;
+            };
+            let is_import = old_index < import_count;
+            let misplaced = new_index >= new_len
+                || new_func_syms[new_index].is_some()
+                || is_import != (new_index < new_import_count)
+                || (!is_import && last_defined_index.map_or(false, |last| new_index < last));
+            if misplaced {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "Invalid function index mapping {old_index} -> {new_index} for {:?}",
+                        func_syms[old_index]
+                    ),
+                });
+            }
+            if !is_import {
+                last_defined_index = Some(new_index);
+            }
+            new_func_syms[new_index] = Some(func_syms[old_index].clone());
+        }
+        let call_ops = self.call_ops(ctx);
+        for call_op in &call_ops {
+            let old_index = usize::from(call_op.get_func_index(ctx));
+            if mapping.get(old_index).map_or(false, Option::is_none) {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "Cannot remove function {:?}: it is still called",
+                        func_syms[old_index]
+                    ),
+                });
+            }
+        }
+        let mut elem_segments = self.get_elem_segments(ctx);
+        for func_index in elem_segments.iter().flat_map(|seg| seg.func_indices.iter()) {
+            if mapping
+                .get(usize::from(*func_index))
+                .map_or(false, Option::is_none)
+            {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "Cannot remove function {:?}: it is in a table",
+                        func_syms[usize::from(*func_index)]
+                    ),
+                });
+            }
+        }
+        for call_op in call_ops {
+            let old_index = usize::from(call_op.get_func_index(ctx));
+            if let Some(Some(new_index)) = mapping.get(old_index) {
+                call_op.set_func_index(ctx, *new_index);
+            }
+        }
+        for func_index in elem_segments
+            .iter_mut()
+            .flat_map(|seg| seg.func_indices.iter_mut())
+        {
+            if let Some(Some(new_index)) = mapping.get(usize::from(*func_index)) {
+                *func_index = *new_index;
+            }
+        }
+        let table_sizes = self.get_table_sizes(ctx);
+        self.set_tables(ctx, table_sizes, elem_segments);
+        let remap_imports = |attrs: &mut Vec<AttrObj>| {
+            let mut new_attrs: Vec<Option<AttrObj>> = (0..new_import_count).map(|_| None).collect();
+            for (old_index, attr) in attrs.drain(..).enumerate() {
+                if let Some(Some(new_index)) = mapping.get(old_index) {
+                    new_attrs[usize::from(*new_index)] = Some(attr);
+                }
+            }
+            *attrs = new_attrs.into_iter().flatten().collect();
+        };
+        let mut self_op = self.get_operation().deref_mut(ctx);
+        for key in [
+            Self::ATTR_KEY_IMPORT_FUNC_TYPES,
+            Self::ATTR_KEY_IMPORT_FUNC_MODULES,
+        ] {
+            if let Some(vec_attr) = self_op
+                .attributes
+                .get_mut(key)
+                .and_then(|attr| attr.downcast_mut::<VecAttr>())
+            {
+                remap_imports(&mut vec_attr.0);
+            }
+        }
+        let func_indices_attr = self_op
+            .attributes
+            .get_mut(Self::ATTR_KEY_FUNC_INDICES)
+            .expect("ModuleOp has no function symbols vector attribute")
+            .downcast_mut::<VecAttr>()
+            .expect("ModuleOp function symbols vector attribute is not a VecAttr");
+        func_indices_attr.0 = new_func_syms
+            .into_iter()
+            .flatten()
+            .map(|func_sym| StringAttr::create(func_sym.into()))
+            .collect();
+        Ok(())
+    }
+
+    /// Index mapping that removes the function with the given index and shifts the following ones
+    fn removal_mapping(&self, ctx: &Context, removed_index: FuncIndex) -> Vec<Option<FuncIndex>> {
+        let removed_index = usize::from(removed_index);
+        (0..self.get_func_syms(ctx).len())
+            .map(|index| match index.cmp(&removed_index) {
+                std::cmp::Ordering::Less => Some(index.into()),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some((index - 1).into()),
+            })
+            .collect()
+    }
+
+    /// The `wasm.call` and `wasm.return_call` ops, both refer to the callee by function index
+    fn call_ops(&self, ctx: &Context) -> Vec<DirectCallOp> {
+        let mut call_ops = Vec::new();
+        self.get_operation()
+            .walk_only::<CallOp>(ctx, WalkOrder::PostOrder, &mut |call_op| {
+                call_ops.push(DirectCallOp::Call(*call_op));
+                WalkResult::Advance
+            });
+        self.get_operation().walk_only::<ReturnCallOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |return_call_op| {
+                call_ops.push(DirectCallOp::ReturnCall(*return_call_op));
+                WalkResult::Advance
+            },
+        );
+        call_ops
+    }
+
+    /// Whether the function is called directly (including tail calls), can be called through
+    /// a table or is a prologue function
+    pub fn is_func_called(&self, ctx: &Context, func_index: FuncIndex) -> bool {
+        self.call_ops(ctx)
+            .iter()
+            .any(|call_op| call_op.get_func_index(ctx) == func_index)
+            || self
+                .get_elem_segments(ctx)
+                .iter()
+                .any(|seg| seg.func_indices.contains(&func_index))
+            || self
+                .get_prologue_functions(ctx)
+                .into_iter()
+                .any(|func_sym| self.get_func_index(ctx, func_sym) == Some(func_index))
+    }
+
+    /// Rename the defined function, keeping its function index. The start function symbol, the
+    /// prologue functions and the `ozk.call` ops are updated, the `wasm.call` ops refer to the
+    /// function by index.
+    /// The original name is recorded in the function (see [FuncOp::get_original_name]).
+    pub fn rename_function(
+        &self,
+        ctx: &mut Context,
+        func_sym: &FuncSym,
+        new_func_sym: FuncSym,
+    ) -> Result<(), CompilerError> {
+        if self.get_func_index(ctx, new_func_sym.clone()).is_some() {
+            return Err(CompilerError::VerificationError {
+                msg: format!(
+                    "Cannot rename function {func_sym:?}: {new_func_sym:?} already exists"
+                ),
+            });
+        }
+        let (Some(func_index), Some(func_op)) = (
+            self.get_func_index(ctx, func_sym.clone()),
+            self.get_func(ctx, func_sym),
+        ) else {
+            return Err(CompilerError::VerificationError {
+                msg: format!("Cannot rename function {func_sym:?}: not defined in the module"),
+            });
+        };
+        {
+            let mut self_op = self.get_operation().deref_mut(ctx);
+            let func_indices_attr = self_op
+                .attributes
+                .get_mut(Self::ATTR_KEY_FUNC_INDICES)
+                .expect("ModuleOp has no function symbols vector attribute")
+                .downcast_mut::<VecAttr>()
+                .expect("ModuleOp function symbols vector attribute is not a VecAttr");
+            func_indices_attr.0[usize::from(func_index)] =
+                StringAttr::create(new_func_sym.clone().into());
+        }
+        if func_op.get_original_name(ctx).is_none() {
+            func_op.set_original_name(ctx, func_sym.as_ref());
+        }
+        func_op.set_symbol_name(ctx, new_func_sym.as_ref());
+        if self.try_get_start_func_sym(ctx).as_ref() == Some(func_sym) {
+            self.set_start_func_sym(ctx, new_func_sym.clone())?;
+        }
+        let prologue_funcs = self
+            .get_prologue_entries(ctx)
+            .into_iter()
+            .map(|(stage, sym)| {
+                if &sym == func_sym {
+                    (stage, new_func_sym.clone())
+                } else {
+                    (stage, sym)
+                }
+            })
+            .collect();
+        self.set_prologue_entries(ctx, prologue_funcs);
+        let mut ozk_call_ops = Vec::new();
+        self.get_operation()
+            .walk_only::<ozk_ozk_dialect::ops::CallOp>(ctx, WalkOrder::PostOrder, &mut |call_op| {
+                ozk_call_ops.push(*call_op);
+                WalkResult::Advance
+            });
+        for call_op in ozk_call_ops {
+            if call_op.get_func_sym(ctx) == func_sym.as_ref() {
+                call_op.set_func_sym(ctx, new_func_sym.clone());
+            }
+        }
+        let mut host_call_ops = Vec::new();
+        self.get_operation()
+            .walk_only::<ozk_ozk_dialect::ops::HostCallOp>(
+                ctx,
+                WalkOrder::PostOrder,
+                &mut |call_op| {
+                    host_call_ops.push(*call_op);
+                    WalkResult::Advance
+                },
+            );
+        for call_op in host_call_ops {
+            if call_op.get_func_sym(ctx) == func_sym.as_ref() {
+                call_op.set_func_sym(ctx, new_func_sym.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the start function symbol name. Fails if it's not set
+    /// (e.g. removed with [Self::clear_start_func_sym]).
+    pub fn get_start_func_sym(&self, ctx: &Context) -> Result<FuncSym, CompilerError> {
+        self.try_get_start_func_sym(ctx)
+            .ok_or_else(|| CompilerError::VerificationError {
+                msg: "Module has no start function".to_string(),
+            })
+    }
+
+    /// Return the start function symbol name if it is set
+    pub fn try_get_start_func_sym(&self, ctx: &Context) -> Option<FuncSym> {
         let self_op = self.get_operation().deref(ctx);
-        let s_attr = self_op
-            .attributes
-            .get(Self::ATTR_KEY_START_FUNC_SYM)
-            .expect("ModuleOp has no start function symbol attribute");
-        String::from(
-            s_attr
-                .downcast_ref::<StringAttr>()
-                .expect("ModuleOp start function symbol attribute is not a StringAttr")
-                .clone(),
+        let s_attr = self_op.attributes.get(Self::ATTR_KEY_START_FUNC_SYM)?;
+        Some(
+            String::from(
+                s_attr
+                    .downcast_ref::<StringAttr>()
+                    .expect("ModuleOp start function symbol attribute is not a StringAttr")
+                    .clone(),
+            )
+            .into(),
         )
-        .into()
     }
 
-    fn get_func_syms(&self, ctx: &Context) -> Vec<FuncSym> {
+    /// Set the start function symbol name. The function must be defined or imported in this module.
+    pub fn set_start_func_sym(
+        &self,
+        ctx: &mut Context,
+        func_sym: FuncSym,
+    ) -> Result<(), CompilerError> {
+        if self.get_func_index(ctx, func_sym.clone()).is_none() {
+            return Err(CompilerError::VerificationError {
+                msg: format!("Cannot set start function: {func_sym:?} is not defined"),
+            });
+        }
+        self.get_operation().deref_mut(ctx).attributes.insert(
+            Self::ATTR_KEY_START_FUNC_SYM,
+            StringAttr::create(func_sym.into()),
+        );
+        Ok(())
+    }
+
+    /// Remove the start function symbol name
+    pub fn clear_start_func_sym(&self, ctx: &mut Context) {
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .remove(Self::ATTR_KEY_START_FUNC_SYM);
+    }
+
+    /// Return all function (imports + defined) symbols ordered by their function index.
+    pub fn get_func_syms(&self, ctx: &Context) -> Vec<FuncSym> {
         let self_op = self.get_operation().deref(ctx);
         let v_attr = self_op
             .attributes
@@ -245,6 +725,7 @@ impl ModuleOp {
             .map(Into::into)
     }
 
+    /// Return the function with the given symbol name.
     pub fn get_func(&self, ctx: &Context, func_sym: &FuncSym) -> Option<FuncOp> {
         for op in self.get_body(ctx, 0).deref(ctx).iter(ctx) {
             let deref_op = &op.deref(ctx).get_op(ctx);
@@ -257,140 +738,2246 @@ impl ModuleOp {
         }
         None
     }
-}
 
-impl OneRegionInterface for ModuleOp {}
-impl SingleBlockRegionInterface for ModuleOp {}
-#[cast_to]
-impl SymbolOpInterface for ModuleOp {}
-
-declare_op!(
-    /// Function
-    FuncOp,
-    "func",
-    "wasm"
-);
-
-impl FuncOp {
-    /// Attribute key for the function type
-    pub const ATTR_KEY_FUNC_TYPE: &str = "func.type";
-    pub const ATTR_KEY_FUNC_LOCALS: &str = "func.locals";
+    /// Add the data segment into this module.
+    pub fn append_data(&self, ctx: &mut Context, data_op: DataOp) {
+        self.append_operation(ctx, data_op.get_operation(), 0);
+    }
 
-    /// Create a new [FuncOp].
-    /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
-    /// The returned function has a single region with a passed `entry` block.
-    pub fn new_unlinked_with_block(
-        ctx: &mut Context,
-        name: FuncSym,
-        ty: Ptr<TypeObj>,
-        entry_block: Ptr<BasicBlock>,
-        locals: Vec<Ptr<TypeObj>>,
-    ) -> FuncOp {
-        let ty_attr = TypeAttr::create(ty);
-        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
-        {
-            let opref = &mut *op.deref_mut(ctx);
-            opref.attributes.insert(Self::ATTR_KEY_FUNC_TYPE, ty_attr);
-            opref.attributes.insert(
-                Self::ATTR_KEY_FUNC_LOCALS,
-                VecAttr::create(locals.into_iter().map(TypeAttr::create).collect()),
-            );
+    /// Add the global into this module (after the other globals, before the functions).
+    pub fn append_global(&self, ctx: &mut Context, global_op: GlobalOp) {
+        match self.get_global_ops(ctx).last() {
+            Some(last_global) => global_op
+                .get_operation()
+                .insert_after(ctx, last_global.get_operation()),
+            None => global_op
+                .get_operation()
+                .insert_at_front(self.get_body(ctx, 0), ctx),
         }
-        let opop = FuncOp { op };
-        // Create an empty entry block.
-        let region = opop.get_region(ctx);
-        entry_block.insert_at_front(region, ctx);
-
-        opop.set_symbol_name(ctx, name.as_ref());
-
-        opop
     }
 
-    /// Get the function signature (type).
-    pub fn get_type_attr(&self, ctx: &Context) -> Ptr<TypeObj> {
-        let opref = self.get_operation().deref(ctx);
-        #[allow(clippy::unwrap_used)]
-        let ty_attr = opref.attributes.get(Self::ATTR_KEY_FUNC_TYPE).unwrap();
-        #[allow(clippy::unwrap_used)]
-        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
-            .unwrap()
-            .get_type()
+    /// Return the defined globals in the order of their indices (the imported globals come
+    /// first in the index space, see [Self::get_import_globals]).
+    pub fn get_global_ops(&self, ctx: &Context) -> Vec<GlobalOp> {
+        self.get_body(ctx, 0)
+            .deref(ctx)
+            .iter(ctx)
+            .filter_map(|op| {
+                op.deref(ctx)
+                    .get_op(ctx)
+                    .downcast_ref::<GlobalOp>()
+                    .cloned()
+            })
+            .collect()
     }
 
-    /// Get the function signature (type).
-    pub fn get_type(&self, ctx: &Context) -> FunctionType {
-        let func_type_obj = self.get_type_attr(ctx).deref(ctx);
-        #[allow(clippy::panic)]
-        let Some(func_type) = func_type_obj.downcast_ref::<FunctionType>() else {
-            panic!("FuncOp type is not a FunctionType");
-        };
-        func_type.clone()
+    /// Set the imported globals ordered by their global index.
+    pub fn set_import_globals(&self, ctx: &mut Context, import_globals: Vec<ImportGlobal>) {
+        let import_globals_attr = VecAttr::create(
+            import_globals
+                .into_iter()
+                .map(|import_global| {
+                    VecAttr::create(vec![
+                        StringAttr::create(import_global.module),
+                        StringAttr::create(import_global.name),
+                        TypeAttr::create(import_global.ty),
+                        u32_attr(ctx, import_global.mutable as u32),
+                    ])
+                })
+                .collect(),
+        );
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_IMPORT_GLOBALS, import_globals_attr);
     }
 
-    /// Get the entry block of this function.
-    pub fn get_entry_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
-        #[allow(clippy::unwrap_used)]
-        self.get_region(ctx).deref(ctx).get_head().unwrap()
+    /// Return the imported globals ordered by their global index.
+    pub fn get_import_globals(&self, ctx: &Context) -> Vec<ImportGlobal> {
+        let self_op = self.get_operation().deref(ctx);
+        let Some(v_attr) = self_op.attributes.get(Self::ATTR_KEY_IMPORT_GLOBALS) else {
+            return Vec::new();
+        };
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp import globals attribute is not a VecAttr")
+            .0
+            .iter()
+            .map(|import_global_attr| {
+                let fields = &import_global_attr
+                    .downcast_ref::<VecAttr>()
+                    .expect("ModuleOp import global entry is not a VecAttr")
+                    .0;
+                let [module, name, ty, mutable] = fields.as_slice() else {
+                    panic!("ModuleOp import global entry should have 4 fields");
+                };
+                let string = |attr: &AttrObj| -> String {
+                    attr.downcast_ref::<StringAttr>()
+                        .expect("ModuleOp import global name is not a StringAttr")
+                        .clone()
+                        .into()
+                };
+                ImportGlobal {
+                    module: string(module),
+                    name: string(name),
+                    ty: attr_cast::<dyn TypedAttrInterface>(&**ty)
+                        .expect("ModuleOp import global type is not a TypeAttr")
+                        .get_type(),
+                    mutable: to_u32_checked(ctx, mutable)
+                        .expect("ModuleOp import global mutability should be u32")
+                        != 0,
+                }
+            })
+            .collect()
     }
 
-    /// Get an iterator over all operations.
-    pub fn op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
-        self.get_region(ctx)
+    /// Return the data segments in the order they were added.
+    pub fn get_data_ops(&self, ctx: &Context) -> Vec<DataOp> {
+        self.get_body(ctx, 0)
             .deref(ctx)
             .iter(ctx)
-            .flat_map(|bb| bb.deref(ctx).iter(ctx))
+            .filter_map(|op| op.deref(ctx).get_op(ctx).downcast_ref::<DataOp>().cloned())
+            .collect()
     }
 
-    /// Get the local variables types
-    pub fn get_locals(&self, ctx: &Context) -> Vec<Ptr<TypeObj>> {
+    /// Set the bytes of the passive data segments ordered by their data index. The active
+    /// segments ([DataOp]) are in the same index space and have no bytes here, as they are
+    /// dropped after the instantiation.
+    pub fn set_passive_data(&self, ctx: &mut Context, segments: Vec<Vec<u8>>) {
+        let segments_attr = VecAttr::create(segments.into_iter().map(BytesAttr::create).collect());
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_PASSIVE_DATA, segments_attr);
+    }
+
+    /// Return the bytes of the passive data segments ordered by their data index
+    /// (empty for the active segments).
+    pub fn get_passive_data(&self, ctx: &Context) -> Vec<Vec<u8>> {
         let self_op = self.get_operation().deref(ctx);
-        let v_attr = self_op
+        let Some(v_attr) = self_op.attributes.get(Self::ATTR_KEY_PASSIVE_DATA) else {
+            return Vec::new();
+        };
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp passive data attribute is not a VecAttr")
+            .0
+            .iter()
+            .map(|bytes_attr| {
+                bytes_attr
+                    .downcast_ref::<BytesAttr>()
+                    .expect("ModuleOp passive data entry is not a BytesAttr")
+                    .get_bytes()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    /// Set the custom sections (name, bytes) in the order they appear in the binary. They are
+    /// opaque to the compiler and carried through for the passes and backends that want them
+    /// (producers, target_features, debug info).
+    pub fn set_custom_sections(&self, ctx: &mut Context, sections: Vec<(String, Vec<u8>)>) {
+        let sections_attr = VecAttr::create(
+            sections
+                .into_iter()
+                .map(|(name, bytes)| {
+                    VecAttr::create(vec![StringAttr::create(name), BytesAttr::create(bytes)])
+                })
+                .collect(),
+        );
+        self.get_operation()
+            .deref_mut(ctx)
             .attributes
-            .get(Self::ATTR_KEY_FUNC_LOCALS)
-            .expect("FuncOp has no locals attribute");
+            .insert(Self::ATTR_KEY_CUSTOM_SECTIONS, sections_attr);
+    }
+
+    /// Return the custom sections (name, bytes) in the order they appear in the binary.
+    pub fn get_custom_sections(&self, ctx: &Context) -> Vec<(String, Vec<u8>)> {
+        let self_op = self.get_operation().deref(ctx);
+        let Some(v_attr) = self_op.attributes.get(Self::ATTR_KEY_CUSTOM_SECTIONS) else {
+            return Vec::new();
+        };
         v_attr
             .downcast_ref::<VecAttr>()
-            .expect("FuncOp locals attribute is not a VecAttr")
+            .expect("ModuleOp custom sections attribute is not a VecAttr")
             .0
             .iter()
-            .map(|attr: &AttrObj| {
-                attr.downcast_ref::<TypeAttr>()
-                    .expect("FuncOp local is not a TypeAttr")
+            .map(|section_attr| {
+                let fields = &section_attr
+                    .downcast_ref::<VecAttr>()
+                    .expect("ModuleOp custom section entry is not a VecAttr")
+                    .0;
+                let [name, bytes] = fields.as_slice() else {
+                    panic!("ModuleOp custom section entry should have 2 fields");
+                };
+                let name: String = name
+                    .downcast_ref::<StringAttr>()
+                    .expect("ModuleOp custom section name is not a StringAttr")
                     .clone()
-                    .get_type()
+                    .into();
+                let bytes = bytes
+                    .downcast_ref::<BytesAttr>()
+                    .expect("ModuleOp custom section bytes is not a BytesAttr")
+                    .get_bytes()
+                    .to_vec();
+                (name, bytes)
             })
             .collect()
     }
-}
 
-impl OneRegionInterface for FuncOp {}
-#[cast_to]
-impl SymbolOpInterface for FuncOp {}
-
-impl DisplayWithContext for FuncOp {
-    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let region = self.get_region(ctx).with_ctx(ctx).to_string();
-        write!(
-            f,
-            "{} @{}{} {{\n{}}}",
-            self.get_opid().with_ctx(ctx),
-            self.get_symbol_name(ctx),
-            self.get_type_attr(ctx).with_ctx(ctx),
-            indent::indent_all_by(2, region),
-        )
+    /// Return the bytes of the first custom section with the given name.
+    pub fn get_custom_section(&self, ctx: &Context, name: &str) -> Option<Vec<u8>> {
+        self.get_custom_sections(ctx)
+            .into_iter()
+            .find(|(section_name, _)| section_name == name)
+            .map(|(_, bytes)| bytes)
     }
-}
+
+    /// Return the types of the imported functions ordered by their function index.
+    pub fn get_import_func_types(&self, ctx: &Context) -> Vec<FunctionType> {
+        let self_op = self.get_operation().deref(ctx);
+        let Some(v_attr) = self_op.attributes.get(Self::ATTR_KEY_IMPORT_FUNC_TYPES) else {
+            return Vec::new();
+        };
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp import function types attribute is not a VecAttr")
+            .0
+            .iter()
+            .map(|attr: &AttrObj| {
+                let ty = attr_cast::<dyn TypedAttrInterface>(&**attr)
+                    .expect("ModuleOp import function type is not a TypeAttr")
+                    .get_type();
+                let Some(func_type) = ty.deref(ctx).downcast_ref::<FunctionType>().cloned() else {
+                    panic!("ModuleOp import function type is not a FunctionType");
+                };
+                func_type
+            })
+            .collect()
+    }
+
+    /// Return the module names of the imported functions ordered by their function index.
+    pub fn get_import_func_modules(&self, ctx: &Context) -> Vec<String> {
+        let self_op = self.get_operation().deref(ctx);
+        let Some(v_attr) = self_op.attributes.get(Self::ATTR_KEY_IMPORT_FUNC_MODULES) else {
+            return Vec::new();
+        };
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp import function modules attribute is not a VecAttr")
+            .0
+            .iter()
+            .map(|attr: &AttrObj| {
+                attr.downcast_ref::<StringAttr>()
+                    .expect("ModuleOp import function module is not a StringAttr")
+                    .clone()
+                    .into()
+            })
+            .collect()
+    }
+
+    /// Return the type of the function (imported or defined) with the given function index.
+    pub fn get_func_type(&self, ctx: &Context, func_index: FuncIndex) -> Option<FunctionType> {
+        let import_func_types = self.get_import_func_types(ctx);
+        let index = usize::from(func_index);
+        if index < import_func_types.len() {
+            return import_func_types.get(index).cloned();
+        }
+        let func_sym = self.get_func_sym(ctx, func_index)?;
+        self.get_func(ctx, &func_sym)
+            .map(|func_op| func_op.get_type(ctx))
+    }
+
+    /// Set the function tables: their (initial) sizes and the active element segments
+    /// that fill them.
+    pub fn set_tables(
+        &self,
+        ctx: &mut Context,
+        table_sizes: Vec<u32>,
+        elem_segments: Vec<ElemSegment>,
+    ) {
+        let sizes_attr = VecAttr::create(
+            table_sizes
+                .into_iter()
+                .map(|size| u32_attr(ctx, size))
+                .collect(),
+        );
+        let segments_attr = VecAttr::create(
+            elem_segments
+                .into_iter()
+                .map(|seg| {
+                    VecAttr::create(vec![
+                        TableIndexAttr::create(seg.table_index),
+                        u32_attr(ctx, seg.offset),
+                        VecAttr::create(
+                            seg.func_indices
+                                .into_iter()
+                                .map(FuncIndexAttr::create)
+                                .collect(),
+                        ),
+                    ])
+                })
+                .collect(),
+        );
+        let mut self_op = self.get_operation().deref_mut(ctx);
+        self_op
+            .attributes
+            .insert(Self::ATTR_KEY_TABLE_SIZES, sizes_attr);
+        self_op
+            .attributes
+            .insert(Self::ATTR_KEY_ELEM_SEGMENTS, segments_attr);
+    }
+
+    /// Return the (initial) sizes of the function tables ordered by their table index.
+    pub fn get_table_sizes(&self, ctx: &Context) -> Vec<u32> {
+        let self_op = self.get_operation().deref(ctx);
+        let Some(v_attr) = self_op.attributes.get(Self::ATTR_KEY_TABLE_SIZES) else {
+            return Vec::new();
+        };
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp table sizes attribute is not a VecAttr")
+            .0
+            .iter()
+            .map(|attr| to_u32_checked(ctx, attr).expect("ModuleOp table size should be u32"))
+            .collect()
+    }
+
+    /// Return the active element segments in the order they are applied.
+    pub fn get_elem_segments(&self, ctx: &Context) -> Vec<ElemSegment> {
+        let self_op = self.get_operation().deref(ctx);
+        let Some(v_attr) = self_op.attributes.get(Self::ATTR_KEY_ELEM_SEGMENTS) else {
+            return Vec::new();
+        };
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp element segments attribute is not a VecAttr")
+            .0
+            .iter()
+            .map(|seg_attr| {
+                let fields = &seg_attr
+                    .downcast_ref::<VecAttr>()
+                    .expect("ModuleOp element segment is not a VecAttr")
+                    .0;
+                let [table_index, offset, func_indices] = fields.as_slice() else {
+                    panic!("ModuleOp element segment should have 3 fields");
+                };
+                ElemSegment {
+                    table_index: table_index
+                        .downcast_ref::<TableIndexAttr>()
+                        .expect("ModuleOp element segment table is not a TableIndexAttr")
+                        .get_index(),
+                    offset: to_u32_checked(ctx, offset)
+                        .expect("ModuleOp element segment offset should be u32"),
+                    func_indices: func_indices
+                        .downcast_ref::<VecAttr>()
+                        .expect("ModuleOp element segment functions is not a VecAttr")
+                        .0
+                        .iter()
+                        .map(|attr| {
+                            attr.downcast_ref::<FuncIndexAttr>()
+                                .expect("ModuleOp element segment function is not a FuncIndexAttr")
+                                .get_index()
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Return the contents of the table after the element segments are applied,
+    /// `None` for the null entries. Empty if the table is not declared.
+    pub fn get_table_entries(
+        &self,
+        ctx: &Context,
+        table_index: TableIndex,
+    ) -> Vec<Option<FuncIndex>> {
+        let Some(size) = self
+            .get_table_sizes(ctx)
+            .get(u32::from(table_index) as usize)
+            .copied() else {
+            return Vec::new();
+        };
+        let mut entries = vec![None; size as usize];
+        for seg in self.get_elem_segments(ctx) {
+            if seg.table_index != table_index {
+                continue;
+            }
+            for (pos, func_index) in seg.func_indices.into_iter().enumerate() {
+                if let Some(entry) = entries.get_mut(seg.offset as usize + pos) {
+                    *entry = Some(func_index);
+                }
+            }
+        }
+        entries
+    }
+
+    /// Set the limits of the linear memories ordered by their memory index.
+    pub fn set_memory_limits(&self, ctx: &mut Context, memory_limits: Vec<MemoryLimits>) {
+        let limits_attr = VecAttr::create(
+            memory_limits
+                .into_iter()
+                .map(|limits| {
+                    let mut fields = vec![u32_attr(ctx, limits.minimum)];
+                    if let Some(maximum) = limits.maximum {
+                        fields.push(u32_attr(ctx, maximum));
+                    }
+                    VecAttr::create(fields)
+                })
+                .collect(),
+        );
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_MEMORY_LIMITS, limits_attr);
+    }
+
+    /// Return the limits of the linear memories ordered by their memory index.
+    pub fn get_memory_limits(&self, ctx: &Context) -> Vec<MemoryLimits> {
+        let self_op = self.get_operation().deref(ctx);
+        let Some(v_attr) = self_op.attributes.get(Self::ATTR_KEY_MEMORY_LIMITS) else {
+            return Vec::new();
+        };
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp memory limits attribute is not a VecAttr")
+            .0
+            .iter()
+            .map(|limits_attr| {
+                let fields = &limits_attr
+                    .downcast_ref::<VecAttr>()
+                    .expect("ModuleOp memory limits entry is not a VecAttr")
+                    .0;
+                let pages = |attr: &AttrObj| {
+                    to_u32_checked(ctx, attr).expect("ModuleOp memory limit should be u32")
+                };
+                match fields.as_slice() {
+                    [minimum] => MemoryLimits {
+                        minimum: pages(minimum),
+                        maximum: None,
+                    },
+                    [minimum, maximum] => MemoryLimits {
+                        minimum: pages(minimum),
+                        maximum: Some(pages(maximum)),
+                    },
+                    _ => panic!("ModuleOp memory limits entry should have 1 or 2 fields"),
+                }
+            })
+            .collect()
+    }
+
+    /// Add the function into this module as a prologue function of the given stage,
+    /// i.e. a function that is called before the body of the start function.
+    /// The prologue function must take no parameters and return no results.
+    /// See [PrologueStage] for the order the prologue functions are called in.
+    pub fn add_prologue_function(
+        &self,
+        ctx: &mut Context,
+        func_op: FuncOp,
+        stage: PrologueStage,
+    ) -> FuncIndex {
+        let func_sym = FuncSym::from(func_op.get_symbol_name(ctx));
+        let func_index = self.append_function(ctx, func_op);
+        let mut prologue_funcs = self.get_prologue_entries(ctx);
+        prologue_funcs.push((stage, func_sym));
+        self.set_prologue_entries(ctx, prologue_funcs);
+        func_index
+    }
+
+    /// Return the prologue functions in the order they should be called:
+    /// by their stage, then in the order they were added.
+    pub fn get_prologue_functions(&self, ctx: &Context) -> Vec<FuncSym> {
+        let mut prologue_funcs = self.get_prologue_entries(ctx);
+        // stable, keeps the order of addition within a stage
+        prologue_funcs.sort_by_key(|(stage, _)| *stage);
+        prologue_funcs
+            .into_iter()
+            .map(|(_, func_sym)| func_sym)
+            .collect()
+    }
+
+    /// Forget the prologue functions (e.g. after the calls to them are emitted).
+    /// The functions themselves stay in the module.
+    pub fn clear_prologue_functions(&self, ctx: &mut Context) {
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .remove(Self::ATTR_KEY_PROLOGUE_FUNCS);
+    }
+
+    /// Prologue functions with their stages in the order they were added
+    fn get_prologue_entries(&self, ctx: &Context) -> Vec<(PrologueStage, FuncSym)> {
+        let self_op = self.get_operation().deref(ctx);
+        let Some(v_attr) = self_op.attributes.get(Self::ATTR_KEY_PROLOGUE_FUNCS) else {
+            return Vec::new();
+        };
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("ModuleOp prologue functions attribute is not a VecAttr")
+            .0
+            .iter()
+            .map(|entry_attr| {
+                let fields = &entry_attr
+                    .downcast_ref::<VecAttr>()
+                    .expect("ModuleOp prologue function entry is not a VecAttr")
+                    .0;
+                let [stage, func_sym] = fields.as_slice() else {
+                    panic!("ModuleOp prologue function entry should have 2 fields");
+                };
+                let stage = to_u32_checked(ctx, stage)
+                    .ok()
+                    .and_then(PrologueStage::from_index)
+                    .expect("ModuleOp prologue function stage is unknown");
+                let func_sym = String::from(
+                    func_sym
+                        .downcast_ref::<StringAttr>()
+                        .expect("ModuleOp prologue function symbol is not a StringAttr")
+                        .clone(),
+                );
+                (stage, func_sym.into())
+            })
+            .collect()
+    }
+
+    fn set_prologue_entries(&self, ctx: &mut Context, entries: Vec<(PrologueStage, FuncSym)>) {
+        if entries.is_empty() {
+            self.clear_prologue_functions(ctx);
+            return;
+        }
+        let entries_attr = VecAttr::create(
+            entries
+                .into_iter()
+                .map(|(stage, func_sym)| {
+                    VecAttr::create(vec![
+                        u32_attr(ctx, stage.index()),
+                        StringAttr::create(func_sym.into()),
+                    ])
+                })
+                .collect(),
+        );
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_PROLOGUE_FUNCS, entries_attr);
+    }
+
+    /// Check that the prologue functions are defined in this module and take no parameters
+    /// and return no results.
+    fn verify_prologue_funcs(&self, ctx: &Context) -> Result<(), CompilerError> {
+        for func_sym in self.get_prologue_functions(ctx) {
+            let Some(func_op) = self.get_func(ctx, &func_sym) else {
+                return Err(CompilerError::VerificationError {
+                    msg: format!("Prologue function {func_sym:?} is not defined"),
+                });
+            };
+            let func_type = func_op.get_type(ctx);
+            if !func_type.get_inputs().is_empty() || !func_type.get_results().is_empty() {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "Prologue function {func_sym:?} should have no parameters and results"
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the element segments fit into their tables and refer to the known functions.
+    fn verify_elem_segments(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let table_sizes = self.get_table_sizes(ctx);
+        let func_count = self.get_func_syms(ctx).len();
+        for seg in self.get_elem_segments(ctx) {
+            let Some(size) = table_sizes.get(u32::from(seg.table_index) as usize) else {
+                return Err(CompilerError::VerificationError {
+                    msg: format!("Element segment refers to an unknown table {}", seg.table_index),
+                });
+            };
+            if seg.offset as usize + seg.func_indices.len() > *size as usize {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "Element segment at offset {} with {} functions is out of bounds of the table {} of size {size}",
+                        seg.offset,
+                        seg.func_indices.len(),
+                        seg.table_index
+                    ),
+                });
+            }
+            if let Some(func_index) = seg
+                .func_indices
+                .iter()
+                .find(|func_index| usize::from(**func_index) >= func_count)
+            {
+                return Err(CompilerError::VerificationError {
+                    msg: format!("Element segment refers to an unknown function {func_index}"),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the defined functions are the last entries of the function index table
+    /// and are in the same order as in the module body.
+    fn verify_func_indices(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let func_syms = self.get_func_syms(ctx);
+        let mut defined_func_syms = Vec::new();
+        for op in self.get_body(ctx, 0).deref(ctx).iter(ctx) {
+            if let Some(func_op) = op.deref(ctx).get_op(ctx).downcast_ref::<FuncOp>() {
+                defined_func_syms.push(FuncSym::from(func_op.get_symbol_name(ctx)));
+            }
+        }
+        if defined_func_syms.len() > func_syms.len()
+            || func_syms[func_syms.len() - defined_func_syms.len()..] != defined_func_syms[..]
+        {
+            return Err(CompilerError::VerificationError {
+                msg: format!(
+                    "Function index table {func_syms:?} does not correspond to the defined functions {defined_func_syms:?}"
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Verify the module as a whole: every op in the body and the invariants between them and
+    /// the module attributes (the start function exists and has an empty signature, the
+    /// function index table matches the imports and the defined functions, the import
+    /// attributes have consistent lengths, no symbol is defined twice).
+    /// Unlike [Verify::verify], returns all the violations instead of the first one.
+    pub fn verify_module(&self, ctx: &Context) -> Result<(), Vec<CompilerError>> {
+        let mut errors = Vec::new();
+        let verification_error = |msg: String| CompilerError::VerificationError { msg };
+        if let Err(err) = self.verify_interfaces(ctx) {
+            errors.push(err);
+        }
+
+        if let Some(start_func_sym) = self.try_get_start_func_sym(ctx) {
+            match self.get_func_index(ctx, start_func_sym.clone()) {
+                None => errors.push(verification_error(format!(
+                    "Start function {start_func_sym:?} is not defined"
+                ))),
+                Some(func_index) => {
+                    if let Some(func_type) = self.get_func_type(ctx, func_index) {
+                        if !func_type.get_inputs().is_empty() || !func_type.get_results().is_empty()
+                        {
+                            errors.push(verification_error(format!(
+                                "Start function {start_func_sym:?} should have no parameters and results"
+                            )));
+                        }
+                    }
+                }
+            }
+        }
+
+        let func_syms = self.get_func_syms(ctx);
+        let mut defined_func_syms = Vec::new();
+        for op in self.get_body(ctx, 0).deref(ctx).iter(ctx) {
+            if let Some(func_op) = op.deref(ctx).get_op(ctx).downcast_ref::<FuncOp>() {
+                defined_func_syms.push(FuncSym::from(func_op.get_symbol_name(ctx)));
+            }
+        }
+        if let Err(err) = self.verify_func_indices(ctx) {
+            errors.push(err);
+        }
+        let import_func_types = self.get_import_func_types(ctx);
+        let import_func_modules = self.get_import_func_modules(ctx);
+        if import_func_types.len() != import_func_modules.len() {
+            errors.push(verification_error(format!(
+                "{} imported function types but {} imported function modules",
+                import_func_types.len(),
+                import_func_modules.len()
+            )));
+        }
+        if func_syms.len() != import_func_types.len() + defined_func_syms.len() {
+            errors.push(verification_error(format!(
+                "Function index table has {} entries, expected {} imported and {} defined functions",
+                func_syms.len(),
+                import_func_types.len(),
+                defined_func_syms.len()
+            )));
+        }
+        for (what, syms) in [
+            ("function index table", &func_syms),
+            ("module body", &defined_func_syms),
+        ] {
+            let mut seen = HashSet::new();
+            let mut reported = HashSet::new();
+            for sym in syms {
+                if !seen.insert(sym) && reported.insert(sym) {
+                    errors.push(verification_error(format!(
+                        "Function {sym:?} is defined more than once in the {what}"
+                    )));
+                }
+            }
+        }
+
+        if let Err(err) = self.verify_elem_segments(ctx) {
+            errors.push(err);
+        }
+        if let Err(err) = self.verify_prologue_funcs(ctx) {
+            errors.push(err);
+        }
+        for op in self.get_body(ctx, 0).deref(ctx).iter(ctx) {
+            if let Err(err) = op.deref(ctx).get_op(ctx).verify(ctx) {
+                errors.push(err);
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl OneRegionInterface for ModuleOp {}
+impl SingleBlockRegionInterface for ModuleOp {}
+#[cast_to]
+impl SymbolOpInterface for ModuleOp {}
+
+declare_op!(
+    /// Function
+    FuncOp,
+    "func",
+    "wasm"
+);
+
+impl FuncOp {
+    /// Attribute key for the function type
+    pub const ATTR_KEY_FUNC_TYPE: &str = "func.type";
+    pub const ATTR_KEY_FUNC_LOCALS: &str = "func.locals";
+    /// Attribute key for the original (source) name of a renamed function
+    pub const ATTR_KEY_ORIGINAL_NAME: &str = "func.original_name";
+    /// Attribute key for the maximum stack depth (see [Self::set_max_stack_depth])
+    pub const ATTR_KEY_MAX_STACK_DEPTH: &str = "func.max_stack_depth";
+
+    /// Create a new [FuncOp].
+    /// The underlying [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
+    /// The returned function has a single region with a passed `entry` block.
+    pub fn new_unlinked_with_block(
+        ctx: &mut Context,
+        name: FuncSym,
+        ty: Ptr<TypeObj>,
+        entry_block: Ptr<BasicBlock>,
+        locals: Vec<Ptr<TypeObj>>,
+    ) -> FuncOp {
+        let ty_attr = TypeAttr::create(ty);
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
+        {
+            let opref = &mut *op.deref_mut(ctx);
+            opref.attributes.insert(Self::ATTR_KEY_FUNC_TYPE, ty_attr);
+            opref.attributes.insert(
+                Self::ATTR_KEY_FUNC_LOCALS,
+                VecAttr::create(locals.into_iter().map(TypeAttr::create).collect()),
+            );
+        }
+        let opop = FuncOp { op };
+        // Create an empty entry block.
+        let region = opop.get_region(ctx);
+        entry_block.insert_at_front(region, ctx);
+
+        opop.set_symbol_name(ctx, name.as_ref());
+
+        opop
+    }
+
+    /// Get the function signature (type).
+    pub fn get_type_attr(&self, ctx: &Context) -> Ptr<TypeObj> {
+        let opref = self.get_operation().deref(ctx);
+        #[allow(clippy::unwrap_used)]
+        let ty_attr = opref.attributes.get(Self::ATTR_KEY_FUNC_TYPE).unwrap();
+        #[allow(clippy::unwrap_used)]
+        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .unwrap()
+            .get_type()
+    }
+
+    /// Get the function signature (type).
+    pub fn get_type(&self, ctx: &Context) -> FunctionType {
+        let func_type_obj = self.get_type_attr(ctx).deref(ctx);
+        #[allow(clippy::panic)]
+        let Some(func_type) = func_type_obj.downcast_ref::<FunctionType>() else {
+            panic!("FuncOp type is not a FunctionType");
+        };
+        func_type.clone()
+    }
+
+    /// Get the entry block of this function.
+    pub fn get_entry_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        #[allow(clippy::unwrap_used)]
+        self.get_region(ctx).deref(ctx).get_head().unwrap()
+    }
+
+    /// Get an iterator over all operations.
+    pub fn op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
+        self.get_region(ctx)
+            .deref(ctx)
+            .iter(ctx)
+            .flat_map(|bb| bb.deref(ctx).iter(ctx))
+    }
+
+    /// Get the original (source) name if the function was renamed
+    pub fn get_original_name(&self, ctx: &Context) -> Option<String> {
+        let self_op = self.get_operation().deref(ctx);
+        let attr = self_op.attributes.get(Self::ATTR_KEY_ORIGINAL_NAME)?;
+        Some(
+            attr.downcast_ref::<StringAttr>()
+                .expect("FuncOp original name attribute is not a StringAttr")
+                .clone()
+                .into(),
+        )
+    }
+
+    /// Record the original (source) name of the function
+    pub fn set_original_name(&self, ctx: &mut Context, name: &str) {
+        self.get_operation().deref_mut(ctx).attributes.insert(
+            Self::ATTR_KEY_ORIGINAL_NAME,
+            StringAttr::create(name.to_string()),
+        );
+    }
+
+    /// Get the maximum stack depth reached in the function if it was tracked
+    pub fn get_max_stack_depth(&self, ctx: &Context) -> Option<StackDepth> {
+        let self_op = self.get_operation().deref(ctx);
+        let attr = self_op.attributes.get(Self::ATTR_KEY_MAX_STACK_DEPTH)?;
+        Some(
+            to_u32_checked(ctx, attr)
+                .expect("FuncOp max stack depth should be u32")
+                .into(),
+        )
+    }
+
+    /// Record the maximum stack depth reached in the function (including the space reserved
+    /// for the locals, if any)
+    pub fn set_max_stack_depth(&self, ctx: &mut Context, depth: StackDepth) {
+        let depth_attr = u32_attr(ctx, depth.into());
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_MAX_STACK_DEPTH, depth_attr);
+    }
+
+    /// Get the local variables types
+    pub fn get_locals(&self, ctx: &Context) -> Vec<Ptr<TypeObj>> {
+        let self_op = self.get_operation().deref(ctx);
+        let v_attr = self_op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_LOCALS)
+            .expect("FuncOp has no locals attribute");
+        v_attr
+            .downcast_ref::<VecAttr>()
+            .expect("FuncOp locals attribute is not a VecAttr")
+            .0
+            .iter()
+            .map(|attr: &AttrObj| {
+                attr.downcast_ref::<TypeAttr>()
+                    .expect("FuncOp local is not a TypeAttr")
+                    .clone()
+                    .get_type()
+            })
+            .collect()
+    }
+
+    /// Append a local variable of the given type, returns its index (the parameters come first)
+    pub fn add_local(&self, ctx: &mut Context, ty: Ptr<TypeObj>) -> u32 {
+        let mut locals = self.get_locals(ctx);
+        locals.push(ty);
+        let index = self.get_type(ctx).get_inputs().len() + locals.len() - 1;
+        self.get_operation().deref_mut(ctx).attributes.insert(
+            Self::ATTR_KEY_FUNC_LOCALS,
+            VecAttr::create(locals.into_iter().map(TypeAttr::create).collect()),
+        );
+        index as u32
+    }
+}
+
+impl OneRegionInterface for FuncOp {}
+#[cast_to]
+impl SymbolOpInterface for FuncOp {}
+
+impl DisplayWithContext for FuncOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut body = self
+            .header_comment(ctx)
+            .map(|header| format!("// {header}\n"))
+            .unwrap_or_default();
+        body.push_str(&self.get_region(ctx).with_ctx(ctx).to_string());
+        write!(
+            f,
+            "{} @{}{} {{\n{}}}",
+            self.get_opid().with_ctx(ctx),
+            self.get_symbol_name(ctx),
+            self.get_type_attr(ctx).with_ctx(ctx),
+            indent::indent_all_by(2, body),
+        )
+    }
+}
+
+impl FuncOp {
+    /// The header comment of the printed function: the parameters and locals with their
+    /// indices (as used by `local.get/set`) and the maximum stack depth if it was tracked.
+    /// `None` for a function without locals and the tracked stack depth, the signature says
+    /// it all.
+    fn header_comment(&self, ctx: &Context) -> Option<String> {
+        let locals = self.get_locals(ctx);
+        let max_stack_depth = self.get_max_stack_depth(ctx);
+        if locals.is_empty() && max_stack_depth.is_none() {
+            return None;
+        }
+        let func_type = self.get_type(ctx);
+        let params = func_type.get_inputs();
+        let indexed = |types: &[Ptr<TypeObj>], first_index: usize| {
+            types
+                .iter()
+                .enumerate()
+                .map(|(index, ty)| format!("{}: {}", first_index + index, ty.with_ctx(ctx)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut parts = Vec::new();
+        if !params.is_empty() {
+            parts.push(format!("params: [{}]", indexed(params, 0)));
+        }
+        if !locals.is_empty() {
+            parts.push(format!("locals: [{}]", indexed(&locals, params.len())));
+        }
+        if let Some(max_stack_depth) = max_stack_depth {
+            parts.push(format!("max stack depth: {max_stack_depth}"));
+        }
+        Some(parts.join(", "))
+    }
+}
 
 impl Verify for FuncOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        let ty = self.get_type_attr(ctx);
+        let ty = self.get_type_attr(ctx);
+
+        if !(ty.deref(ctx).is::<FunctionType>()) {
+            return Err(CompilerError::VerificationError {
+                msg: "Unexpected Func type".to_string(),
+            });
+        }
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        self.verify_interfaces(ctx)?;
+        self.get_entry_block(ctx).verify(ctx)?;
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Push numeric constant on stack.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_VALUE](ConstantOp::ATTR_KEY_VALUE) | [IntegerAttr] or [FloatAttr] |
+    ///
+    ConstantOp,
+    "const",
+    "wasm"
+);
+
+impl ConstantOp {
+    /// Attribute key for the constant value.
+    pub const ATTR_KEY_VALUE: &str = "const.value";
+    /// Get the constant value that this Op defines.
+    pub fn get_value(&self, ctx: &Context) -> AttrObj {
+        let op = self.get_operation().deref(ctx);
+        #[allow(clippy::expect_used)]
+        let value = op
+            .attributes
+            .get(Self::ATTR_KEY_VALUE)
+            .expect("no attribute found");
+        if value.is::<IntegerAttr>() {
+            attribute::clone::<IntegerAttr>(value)
+        } else {
+            attribute::clone::<FloatAttr>(value)
+        }
+    }
+
+    /// Create a new [ConstOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context, val: AttrObj) -> ConstantOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_VALUE, val);
+        ConstantOp { op }
+    }
+
+    /// Create a new i32 [ConstOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_i32_unlinked(ctx: &mut Context, val: i32) -> ConstantOp {
+        let val_attr = i32_attr(ctx, val);
+        Self::new_unlinked(ctx, val_attr)
+    }
+
+    /// Create a new i64 [ConstOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_i64_unlinked(ctx: &mut Context, val: i64) -> ConstantOp {
+        let val_attr = i64_attr(ctx, val);
+        Self::new_unlinked(ctx, val_attr)
+    }
+
+    /// Create a new u32 [ConstOp] (e.g. an address). The underlying [Operation] is not linked
+    /// to a [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_u32_unlinked(ctx: &mut Context, val: u32) -> ConstantOp {
+        let val_attr = u32_attr(ctx, val);
+        Self::new_unlinked(ctx, val_attr)
+    }
+
+    /// Get the value of a signed (or signless) integer constant that fits in i32.
+    /// Fails on a float, an unsigned constant or a value out of the i32 range.
+    pub fn get_i32(&self, ctx: &Context) -> Result<i32, IntConversionError> {
+        to_i32_checked(ctx, &self.get_value(ctx))
+    }
+
+    /// Get the value of a signed (or signless) integer constant that fits in i64.
+    /// Fails on a float or an unsigned constant.
+    pub fn get_i64(&self, ctx: &Context) -> Result<i64, IntConversionError> {
+        to_i64_checked(ctx, &self.get_value(ctx))
+    }
+
+    /// Get the value of an unsigned (or signless) integer constant that fits in u32.
+    /// Fails on a float, a signed constant or a value out of the u32 range.
+    pub fn get_u32(&self, ctx: &Context) -> Result<u32, IntConversionError> {
+        to_u32_checked(ctx, &self.get_value(ctx))
+    }
+}
+
+impl DisplayWithContext for ConstantOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_value(ctx).with_ctx(ctx)
+        )
+    }
+}
+
+impl Verify for ConstantOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let value = self.get_value(ctx);
+        if !(value.is::<IntegerAttr>() || value.is::<FloatAttr>()) {
+            return Err(CompilerError::VerificationError {
+                msg: "Unexpected constant type".to_string(),
+            });
+        }
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+// TODO: store expected operand types (poped from stack)?
+
+declare_op!(
+    /// A binary integer instruction (`i32.add`, `i64.div_u`, `i32.rotl`, etc.).
+    /// Pops two operands from the stack and pushes the result. For the shifts and rotations
+    /// the top operand is the amount, for the division ops it is the divisor.
+    /// Division by zero (and `div_s` overflow) traps.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_OPCODE](Self::ATTR_KEY_OPCODE) | [BinaryOpcodeAttr] |
+    /// |[ATTR_KEY_OP_TYPE](Self::ATTR_KEY_OP_TYPE) | [TypeAttr] |
+    ///
+    BinaryArithOp,
+    "binary",
+    "wasm"
+);
+
+impl BinaryArithOp {
+    /// Attribute key for the opcode
+    pub const ATTR_KEY_OPCODE: &str = "binary.opcode";
+    /// Attribute key for the type of the operands and the result
+    pub const ATTR_KEY_OP_TYPE: &str = "binary.type";
+
+    /// Create a new op. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context, opcode: BinaryOpcode, ty: Ptr<TypeObj>) -> Self {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_OPCODE, BinaryOpcodeAttr::create(opcode));
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_OP_TYPE, TypeAttr::create(ty));
+        BinaryArithOp { op }
+    }
+
+    /// Create a new i32 op (e.g. `i32.add`).
+    pub fn new_i32_unlinked(ctx: &mut Context, opcode: BinaryOpcode) -> Self {
+        let ty = i32_type(ctx);
+        Self::new_unlinked(ctx, opcode, ty)
+    }
+
+    /// Create a new i64 op (e.g. `i64.add`).
+    pub fn new_i64_unlinked(ctx: &mut Context, opcode: BinaryOpcode) -> Self {
+        let ty = i64_type(ctx);
+        Self::new_unlinked(ctx, opcode, ty)
+    }
+
+    /// Get the opcode.
+    pub fn get_opcode(&self, ctx: &Context) -> BinaryOpcode {
+        self.get_operation()
+            .deref(ctx)
+            .attributes
+            .get(Self::ATTR_KEY_OPCODE)
+            .and_then(|attr| attr.downcast_ref::<BinaryOpcodeAttr>())
+            .expect("no BinaryOpcodeAttr attribute found")
+            .get_opcode()
+    }
+
+    /// Get the type of the operands and the result of this operation.
+    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
+        let opref = self.get_operation().deref(ctx);
+        let ty_attr = opref
+            .attributes
+            .get(Self::ATTR_KEY_OP_TYPE)
+            .expect("no type attribute");
+        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .expect("invalid type attribute")
+            .get_type()
+    }
+}
+
+impl DisplayWithContext for BinaryArithOp {
+    /// The Wasm instruction name, e.g. `wasm.i32.div_u`
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let ty = self.get_type(ctx);
+        let mnemonic = self.get_opcode(ctx).mnemonic();
+        if ty == i32_type(ctx) {
+            write!(f, "wasm.i32.{mnemonic}")
+        } else if ty == i64_type(ctx) {
+            write!(f, "wasm.i64.{mnemonic}")
+        } else {
+            write!(f, "wasm.{}.{mnemonic}", ty.with_ctx(ctx))
+        }
+    }
+}
+
+impl Verify for BinaryArithOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_OPCODE)
+            .map_or(false, |attr| attr.is::<BinaryOpcodeAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected BinaryOpcodeAttr for opcode".to_string(),
+            });
+        }
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_OP_TYPE)
+            .map_or(false, |attr| attr.is::<TypeAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected TypeAttr for type".to_string(),
+            });
+        }
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pops the i32 condition and two values, pushes the first value if the condition
+    /// is non-zero, the second one otherwise.
+    ///
+    /// https://webassembly.github.io/spec/core/syntax/instructions.html#parametric-instructions
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_OP_TYPE](SelectOp::ATTR_KEY_OP_TYPE) | [TypeAttr] (only for `select (result t)`) |
+    ///
+    SelectOp,
+    "select",
+    "wasm"
+);
+
+impl SelectOp {
+    /// Attribute key of the operand type (typed `select`)
+    pub const ATTR_KEY_OP_TYPE: &str = "select.type";
+
+    /// Create a new op. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    /// The type is [None] for the untyped `select`.
+    pub fn new_unlinked(ctx: &mut Context, ty: Option<Ptr<TypeObj>>) -> SelectOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        if let Some(ty) = ty {
+            op.deref_mut(ctx)
+                .attributes
+                .insert(Self::ATTR_KEY_OP_TYPE, TypeAttr::create(ty));
+        }
+        SelectOp { op }
+    }
+
+    /// Get the type of the selected values if it's specified (typed `select`).
+    pub fn get_type(&self, ctx: &Context) -> Option<Ptr<TypeObj>> {
+        let opref = self.get_operation().deref(ctx);
+        let ty_attr = opref.attributes.get(Self::ATTR_KEY_OP_TYPE)?;
+        #[allow(clippy::expect_used)]
+        let ty = attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .expect("invalid type attribute")
+            .get_type();
+        Some(ty)
+    }
+}
+
+impl DisplayWithContext for SelectOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.get_type(ctx) {
+            Some(ty) => write!(f, "{} {}", self.get_opid().with_ctx(ctx), ty.with_ctx(ctx)),
+            None => write!(f, "{}", self.get_opid().with_ctx(ctx)),
+        }
+    }
+}
+
+impl Verify for SelectOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A call referring to the callee by function index
+#[derive(Clone, Copy)]
+enum DirectCallOp {
+    Call(CallOp),
+    ReturnCall(ReturnCallOp),
+}
+
+impl DirectCallOp {
+    fn get_func_index(&self, ctx: &Context) -> FuncIndex {
+        match self {
+            DirectCallOp::Call(call_op) => call_op.get_func_index(ctx),
+            DirectCallOp::ReturnCall(return_call_op) => return_call_op.get_func_index(ctx),
+        }
+    }
+
+    fn set_func_index(&self, ctx: &mut Context, func_index: FuncIndex) {
+        match self {
+            DirectCallOp::Call(call_op) => call_op.set_func_index(ctx, func_index),
+            DirectCallOp::ReturnCall(return_call_op) => {
+                return_call_op.set_func_index(ctx, func_index)
+            }
+        }
+    }
+}
+
+declare_op!(
+    /// Call a function by it's index in the module
+    ///
+    /// https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-instr-control
+    ///
+    CallOp,
+    "call",
+    "wasm"
+);
+
+impl CallOp {
+    const ATTR_KEY_FUNC_INDEX: &str = "call.func_index";
+
+    /// Get the function index
+    pub fn get_func_index(&self, ctx: &Context) -> FuncIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_FUNC_INDEX)
+            .and_then(|attr| attr.downcast_ref::<FuncIndexAttr>())
+            .expect("no FuncIndexAttr attribute found")
+            .get_index()
+    }
+
+    /// Set the function index
+    pub fn set_func_index(&self, ctx: &mut Context, func_index: FuncIndex) {
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_INDEX, FuncIndexAttr::create(func_index));
+    }
+
+    /// Create a new [CallOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context, func_index: FuncIndex) -> CallOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_INDEX, FuncIndexAttr::create(func_index));
+        CallOp { op }
+    }
+}
+
+impl DisplayWithContext for CallOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_func_index(ctx)
+        )
+    }
+}
+
+impl Verify for CallOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pop the i32 index and call the function stored at that position of the table.
+    /// Fails if the entry is null or the function type differs from the expected one.
+    ///
+    /// https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-instr-control
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_TYPE_INDEX](Self::ATTR_KEY_TYPE_INDEX) | [TypeIndexAttr] |
+    /// | [ATTR_KEY_TABLE_INDEX](Self::ATTR_KEY_TABLE_INDEX) | [TableIndexAttr] |
+    /// | [ATTR_KEY_FUNC_TYPE](Self::ATTR_KEY_FUNC_TYPE) | [TypeAttr](super::attributes::TypeAttr) |
+    CallIndirectOp,
+    "call_indirect",
+    "wasm"
+);
+
+impl CallIndirectOp {
+    /// Attribute key for the index of the expected function type in the module's type section
+    pub const ATTR_KEY_TYPE_INDEX: &str = "call_indirect.type_index";
+    /// Attribute key for the index of the table
+    pub const ATTR_KEY_TABLE_INDEX: &str = "call_indirect.table_index";
+    /// Attribute key for the expected function type
+    pub const ATTR_KEY_FUNC_TYPE: &str = "call_indirect.func_type";
+
+    /// Create a new [CallIndirectOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    /// `func_type` is the function type with the `type_index` index in the module.
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        type_index: TypeIndex,
+        table_index: TableIndex,
+        func_type: Ptr<TypeObj>,
+    ) -> CallIndirectOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        {
+            let opref = &mut *op.deref_mut(ctx);
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_TYPE_INDEX, TypeIndexAttr::create(type_index));
+            opref.attributes.insert(
+                Self::ATTR_KEY_TABLE_INDEX,
+                TableIndexAttr::create(table_index),
+            );
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_FUNC_TYPE, TypeAttr::create(func_type));
+        }
+        CallIndirectOp { op }
+    }
+
+    /// Get the index of the expected function type
+    pub fn get_type_index(&self, ctx: &Context) -> TypeIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_TYPE_INDEX)
+            .and_then(|attr| attr.downcast_ref::<TypeIndexAttr>())
+            .expect("no TypeIndexAttr attribute found")
+            .get_index()
+    }
+
+    /// Get the index of the table
+    pub fn get_table_index(&self, ctx: &Context) -> TableIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_TABLE_INDEX)
+            .and_then(|attr| attr.downcast_ref::<TableIndexAttr>())
+            .expect("no TableIndexAttr attribute found")
+            .get_index()
+    }
+
+    /// Get the expected function type
+    pub fn get_func_type(&self, ctx: &Context) -> FunctionType {
+        let op = self.get_operation().deref(ctx);
+        let ty_attr = op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_TYPE)
+            .expect("no function type attribute found");
+        let ty = attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .expect("function type attribute is not a TypeAttr")
+            .get_type();
+        let Some(func_type) = ty.deref(ctx).downcast_ref::<FunctionType>().cloned() else {
+            panic!("call_indirect function type is not a FunctionType");
+        };
+        func_type
+    }
+}
+
+impl DisplayWithContext for CallIndirectOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {} (type {})",
+            self.get_opid().with_ctx(ctx),
+            self.get_table_index(ctx),
+            self.get_type_index(ctx)
+        )
+    }
+}
+
+impl Verify for CallIndirectOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        let is_func_type = op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_TYPE)
+            .and_then(|attr| attr_cast::<dyn TypedAttrInterface>(&**attr))
+            .map_or(false, |ty_attr| {
+                ty_attr.get_type().deref(ctx).is::<FunctionType>()
+            });
+        if !is_func_type {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected the function type attribute".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Tail call: call a function by it's index in the module in place of the current
+    /// function, the callee returns directly to the caller of the current function
+    /// (tail-call proposal)
+    ///
+    /// https://github.com/WebAssembly/tail-call/blob/main/proposals/tail-call/Overview.md
+    ///
+    ReturnCallOp,
+    "return_call",
+    "wasm"
+);
+
+impl ReturnCallOp {
+    const ATTR_KEY_FUNC_INDEX: &str = "return_call.func_index";
+
+    /// Get the function index
+    pub fn get_func_index(&self, ctx: &Context) -> FuncIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_FUNC_INDEX)
+            .and_then(|attr| attr.downcast_ref::<FuncIndexAttr>())
+            .expect("no FuncIndexAttr attribute found")
+            .get_index()
+    }
+
+    /// Set the function index
+    pub fn set_func_index(&self, ctx: &mut Context, func_index: FuncIndex) {
+        self.get_operation()
+            .deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_INDEX, FuncIndexAttr::create(func_index));
+    }
+
+    /// Create a new [ReturnCallOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    pub fn new_unlinked(ctx: &mut Context, func_index: FuncIndex) -> ReturnCallOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_FUNC_INDEX, FuncIndexAttr::create(func_index));
+        ReturnCallOp { op }
+    }
+}
+
+impl DisplayWithContext for ReturnCallOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_func_index(ctx)
+        )
+    }
+}
+
+impl Verify for ReturnCallOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Tail call through the table, see [CallIndirectOp] and [ReturnCallOp]
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_TYPE_INDEX](Self::ATTR_KEY_TYPE_INDEX) | [TypeIndexAttr] |
+    /// | [ATTR_KEY_TABLE_INDEX](Self::ATTR_KEY_TABLE_INDEX) | [TableIndexAttr] |
+    /// | [ATTR_KEY_FUNC_TYPE](Self::ATTR_KEY_FUNC_TYPE) | [TypeAttr](super::attributes::TypeAttr) |
+    ReturnCallIndirectOp,
+    "return_call_indirect",
+    "wasm"
+);
+
+impl ReturnCallIndirectOp {
+    /// Attribute key for the index of the expected function type in the module's type section
+    pub const ATTR_KEY_TYPE_INDEX: &str = "return_call_indirect.type_index";
+    /// Attribute key for the index of the table
+    pub const ATTR_KEY_TABLE_INDEX: &str = "return_call_indirect.table_index";
+    /// Attribute key for the expected function type
+    pub const ATTR_KEY_FUNC_TYPE: &str = "return_call_indirect.func_type";
+
+    /// Create a new [ReturnCallIndirectOp]. The underlying [Operation] is not linked to a
+    /// [BasicBlock](crate::basic_block::BasicBlock).
+    /// `func_type` is the function type with the `type_index` index in the module.
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        type_index: TypeIndex,
+        table_index: TableIndex,
+        func_type: Ptr<TypeObj>,
+    ) -> ReturnCallIndirectOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        {
+            let opref = &mut *op.deref_mut(ctx);
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_TYPE_INDEX, TypeIndexAttr::create(type_index));
+            opref.attributes.insert(
+                Self::ATTR_KEY_TABLE_INDEX,
+                TableIndexAttr::create(table_index),
+            );
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_FUNC_TYPE, TypeAttr::create(func_type));
+        }
+        ReturnCallIndirectOp { op }
+    }
+
+    /// Get the index of the expected function type
+    pub fn get_type_index(&self, ctx: &Context) -> TypeIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_TYPE_INDEX)
+            .and_then(|attr| attr.downcast_ref::<TypeIndexAttr>())
+            .expect("no TypeIndexAttr attribute found")
+            .get_index()
+    }
+
+    /// Get the index of the table
+    pub fn get_table_index(&self, ctx: &Context) -> TableIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_TABLE_INDEX)
+            .and_then(|attr| attr.downcast_ref::<TableIndexAttr>())
+            .expect("no TableIndexAttr attribute found")
+            .get_index()
+    }
+
+    /// Get the expected function type attribute
+    pub fn get_func_type_attr(&self, ctx: &Context) -> Ptr<TypeObj> {
+        let op = self.get_operation().deref(ctx);
+        let ty_attr = op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_TYPE)
+            .expect("no function type attribute found");
+        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .expect("function type attribute is not a TypeAttr")
+            .get_type()
+    }
+
+    /// Get the expected function type
+    pub fn get_func_type(&self, ctx: &Context) -> FunctionType {
+        let ty = self.get_func_type_attr(ctx);
+        let Some(func_type) = ty.deref(ctx).downcast_ref::<FunctionType>().cloned() else {
+            panic!("return_call_indirect function type is not a FunctionType");
+        };
+        func_type
+    }
+}
+
+impl DisplayWithContext for ReturnCallIndirectOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {} (type {})",
+            self.get_opid().with_ctx(ctx),
+            self.get_table_index(ctx),
+            self.get_type_index(ctx)
+        )
+    }
+}
+
+impl Verify for ReturnCallIndirectOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        let is_func_type = op
+            .attributes
+            .get(Self::ATTR_KEY_FUNC_TYPE)
+            .and_then(|attr| attr_cast::<dyn TypedAttrInterface>(&**attr))
+            .map_or(false, |ty_attr| {
+                ty_attr.get_type().deref(ctx).is::<FunctionType>()
+            });
+        if !is_func_type {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected the function type attribute".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Return (branch to the outermost block)
+    /// https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-instr-control
+    ReturnOp,
+    "return",
+    "wasm"
+);
+
+impl ReturnOp {
+    /// Create a new op
+    pub fn new_unlinked(ctx: &mut Context) -> ReturnOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        ReturnOp { op }
+    }
+}
+
+impl DisplayWithContext for ReturnOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx),)
+    }
+}
+
+impl Verify for ReturnOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Unconditionally fails the execution (trap).
+    /// https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-instr-control
+    UnreachableOp,
+    "unreachable",
+    "wasm"
+);
+
+impl UnreachableOp {
+    /// Create a new op
+    pub fn new_unlinked(ctx: &mut Context) -> UnreachableOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        UnreachableOp { op }
+    }
+}
+
+impl DisplayWithContext for UnreachableOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx))
+    }
+}
+
+impl Verify for UnreachableOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pops a value of any type from the stack and discards it.
+    /// https://webassembly.github.io/spec/core/syntax/instructions.html#parametric-instructions
+    DropOp,
+    "drop",
+    "wasm"
+);
+
+impl DropOp {
+    /// Create a new op
+    pub fn new_unlinked(ctx: &mut Context) -> DropOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        DropOp { op }
+    }
+}
+
+impl DisplayWithContext for DropOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.get_opid().with_ctx(ctx),)
+    }
+}
+
+impl Verify for DropOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        // the dropped value is on the Wasm stack, not an operand
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// A block operation containing a single region.
+    BlockOp,
+    "block",
+    "wasm"
+);
+
+impl BlockOp {
+    /// Attribute key for the function type
+    pub const ATTR_KEY_BLOCK_TYPE: &str = "block.type";
+
+    /// Create a new [BlockOp].
+    pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> BlockOp {
+        let ty_attr = TypeAttr::create(ty);
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
+        {
+            let opref = &mut *op.deref_mut(ctx);
+            // Set function type attributes.
+            opref.attributes.insert(Self::ATTR_KEY_BLOCK_TYPE, ty_attr);
+        }
+        let opop = BlockOp { op };
+        // Create an empty block.
+        #[allow(clippy::expect_used)]
+        let region = opop.get_region(ctx);
+        let body = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+        body.insert_at_front(region, ctx);
+
+        opop
+    }
+
+    /// Get the signature (type).
+    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
+        let opref = self.get_operation().deref(ctx);
+        #[allow(clippy::unwrap_used)]
+        let ty_attr = opref.attributes.get(Self::ATTR_KEY_BLOCK_TYPE).unwrap();
+        #[allow(clippy::unwrap_used)]
+        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .unwrap()
+            .get_type()
+    }
+
+    /// Get the bb of this block.
+    pub fn get_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        #[allow(clippy::unwrap_used)]
+        self.get_region(ctx).deref(ctx).get_head().unwrap()
+    }
+
+    /// Get an iterator over all operations.
+    pub fn op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
+        self.get_region(ctx)
+            .deref(ctx)
+            .iter(ctx)
+            .flat_map(|bb| bb.deref(ctx).iter(ctx))
+    }
+}
+
+impl OneRegionInterface for BlockOp {}
+impl DisplayWithContext for BlockOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let region = self.get_region(ctx).with_ctx(ctx).to_string();
+        write!(
+            f,
+            "{} {} {{\n{}}}",
+            self.get_opid().with_ctx(ctx),
+            self.get_type(ctx).with_ctx(ctx),
+            indent::indent_all_by(2, region),
+        )
+    }
+}
+
+impl Verify for BlockOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let ty = self.get_type(ctx);
+
+        if !(ty.deref(ctx).is::<FunctionType>()) {
+            return Err(CompilerError::VerificationError {
+                msg: "Unexpected Block type".to_string(),
+            });
+        }
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        self.verify_interfaces(ctx)?;
+        self.get_block(ctx).verify(ctx)?;
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// A loop block operation containing a single region.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_BLOCK_TYPE](Self::ATTR_KEY_BLOCK_TYPE) | [TypeAttr](super::attributes::TypeAttr) |
+    LoopOp,
+    "loop",
+    "wasm"
+);
+
+impl LoopOp {
+    /// Attribute key for the function type
+    pub const ATTR_KEY_BLOCK_TYPE: &str = "block.type";
+
+    /// Create a new [LoopOp].
+    pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> LoopOp {
+        let ty_attr = TypeAttr::create(ty);
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
+        {
+            let opref = &mut *op.deref_mut(ctx);
+            // Set function type attributes.
+            opref.attributes.insert(Self::ATTR_KEY_BLOCK_TYPE, ty_attr);
+        }
+        let opop = LoopOp { op };
+        // Create an empty block.
+        #[allow(clippy::expect_used)]
+        let region = opop.get_region(ctx);
+        let body = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+        body.insert_at_front(region, ctx);
+
+        opop
+    }
+
+    /// Get the signature (type).
+    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
+        let opref = self.get_operation().deref(ctx);
+        #[allow(clippy::unwrap_used)]
+        let ty_attr = opref.attributes.get(Self::ATTR_KEY_BLOCK_TYPE).unwrap();
+        #[allow(clippy::unwrap_used)]
+        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .unwrap()
+            .get_type()
+    }
+
+    /// Get the bb of this block.
+    pub fn get_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        #[allow(clippy::unwrap_used)]
+        self.get_region(ctx).deref(ctx).get_head().unwrap()
+    }
+
+    /// Get an iterator over all operations.
+    pub fn op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
+        self.get_region(ctx)
+            .deref(ctx)
+            .iter(ctx)
+            .flat_map(|bb| bb.deref(ctx).iter(ctx))
+    }
+}
+
+impl OneRegionInterface for LoopOp {}
+impl DisplayWithContext for LoopOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let region = self.get_region(ctx).with_ctx(ctx).to_string();
+        write!(
+            f,
+            "{} {} {{\n{}}}",
+            self.get_opid().with_ctx(ctx),
+            self.get_type(ctx).with_ctx(ctx),
+            indent::indent_all_by(2, region),
+        )
+    }
+}
+
+impl Verify for LoopOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let ty = self.get_type(ctx);
+
+        if !(ty.deref(ctx).is::<FunctionType>()) {
+            return Err(CompilerError::VerificationError {
+                msg: "Unexpected Block type".to_string(),
+            });
+        }
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        self.verify_interfaces(ctx)?;
+        self.get_block(ctx).verify(ctx)?;
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pops the i32 condition and runs the then region if it's non-zero, the else region
+    /// otherwise. Both regions contain a single block.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// | [ATTR_KEY_BLOCK_TYPE](Self::ATTR_KEY_BLOCK_TYPE) | [TypeAttr](super::attributes::TypeAttr) |
+    IfOp,
+    "if",
+    "wasm"
+);
+
+impl IfOp {
+    /// Attribute key for the function type
+    pub const ATTR_KEY_BLOCK_TYPE: &str = "block.type";
+
+    /// Create a new [IfOp] with the empty then and else blocks.
+    pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> IfOp {
+        let ty_attr = TypeAttr::create(ty);
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 2);
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_BLOCK_TYPE, ty_attr);
+        for (idx, label) in ["then", "else"].into_iter().enumerate() {
+            let region = op.deref(ctx).get_region(idx);
+            let body = BasicBlock::new(ctx, Some(label.to_string()), vec![]);
+            body.insert_at_front(region, ctx);
+        }
+        IfOp { op }
+    }
+
+    /// Get the signature (type).
+    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
+        let opref = self.get_operation().deref(ctx);
+        #[allow(clippy::unwrap_used)]
+        let ty_attr = opref.attributes.get(Self::ATTR_KEY_BLOCK_TYPE).unwrap();
+        #[allow(clippy::unwrap_used)]
+        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
+            .unwrap()
+            .get_type()
+    }
+
+    /// Get the bb of the then region.
+    pub fn get_then_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        self.get_region_block(ctx, 0)
+    }
+
+    /// Get the bb of the else region.
+    pub fn get_else_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
+        self.get_region_block(ctx, 1)
+    }
+
+    fn get_region_block(&self, ctx: &Context, idx: usize) -> Ptr<BasicBlock> {
+        let region = self.get_operation().deref(ctx).get_region(idx);
+        #[allow(clippy::unwrap_used)]
+        region.deref(ctx).get_head().unwrap()
+    }
+}
+
+impl DisplayWithContext for IfOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let op = self.get_operation().deref(ctx);
+        let then_region = op.get_region(0).with_ctx(ctx).to_string();
+        let else_region = op.get_region(1).with_ctx(ctx).to_string();
+        write!(
+            f,
+            "{} {} {{\n{}}} else {{\n{}}}",
+            self.get_opid().with_ctx(ctx),
+            self.get_type(ctx).with_ctx(ctx),
+            indent::indent_all_by(2, then_region),
+            indent::indent_all_by(2, else_region),
+        )
+    }
+}
+
+impl Verify for IfOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let ty = self.get_type(ctx);
+
+        if !(ty.deref(ctx).is::<FunctionType>()) {
+            return Err(CompilerError::VerificationError {
+                msg: "Unexpected Block type".to_string(),
+            });
+        }
+        let op = &*self.get_operation().deref(ctx);
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        if op.get_num_regions() != 2 {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected the then and else regions".to_string(),
+            });
+        }
+        self.get_then_block(ctx).verify(ctx)?;
+        self.get_else_block(ctx).verify(ctx)?;
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Push local variable with the given index onto the stack.
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_INDEX](Self::ATTR_KEY_INDEX) | [LocalIndexAttr] |
+    ///
+    LocalGetOp,
+    "local.get",
+    "wasm"
+);
+
+impl LocalGetOp {
+    /// Attribute key for the index
+    pub const ATTR_KEY_INDEX: &str = "local.get.index";
+
+    /// Get the index of the local variable.
+    pub fn get_index(&self, ctx: &Context) -> LocalIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .and_then(|attr| attr.downcast_ref::<LocalIndexAttr>())
+            .expect("no LocalIndexAttr attribute found")
+            .get_index()
+    }
+
+    /// Create a new [LocalGetOp].
+    pub fn new_unlinked(ctx: &mut Context, index: u32) -> LocalGetOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+
+        let index_attr = LocalIndexAttr::create(index.into());
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_INDEX, index_attr);
+        LocalGetOp { op }
+    }
+}
+
+impl DisplayWithContext for LocalGetOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_index(ctx)
+        )
+    }
+}
+
+impl Verify for LocalGetOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .map_or(false, |attr| attr.is::<LocalIndexAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected LocalIndexAttr for index".to_string(),
+            });
+        }
+        if op.get_opid() != Self::get_opid_static() {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect OpId".to_string(),
+            });
+        }
+        if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+            return Err(CompilerError::VerificationError {
+                msg: "Incorrect number of results or operands".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+declare_op!(
+    /// Pops the stack and save the value into the local variable with the given index
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_INDEX](Self::ATTR_KEY_INDEX) | [LocalIndexAttr] |
+    ///
+    LocalSetOp,
+    "local.set",
+    "wasm"
+);
+
+impl LocalSetOp {
+    /// Attribute key for the index
+    pub const ATTR_KEY_INDEX: &str = "local.set.index";
+
+    /// Get the index of the local variable.
+    pub fn get_index(&self, ctx: &Context) -> LocalIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .and_then(|attr| attr.downcast_ref::<LocalIndexAttr>())
+            .expect("no LocalIndexAttr attribute found")
+            .get_index()
+    }
+
+    /// Create a new [LocalSetOp].
+    pub fn new_unlinked(ctx: &mut Context, index: u32) -> LocalSetOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+
+        let index_attr = LocalIndexAttr::create(index.into());
+        op.deref_mut(ctx)
+            .attributes
+            .insert(Self::ATTR_KEY_INDEX, index_attr);
+        LocalSetOp { op }
+    }
+}
+
+impl DisplayWithContext for LocalSetOp {
+    fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_index(ctx)
+        )
+    }
+}
 
-        if !(ty.deref(ctx).is::<FunctionType>()) {
+impl Verify for LocalSetOp {
+    fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+        let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .map_or(false, |attr| attr.is::<LocalIndexAttr>())
+        {
             return Err(CompilerError::VerificationError {
-                msg: "Unexpected Func type".to_string(),
+                msg: "Expected LocalIndexAttr for index".to_string(),
             });
         }
-        let op = &*self.get_operation().deref(ctx);
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -401,86 +2988,73 @@ impl Verify for FuncOp {
                 msg: "Incorrect number of results or operands".to_string(),
             });
         }
-        self.verify_interfaces(ctx)?;
-        self.get_entry_block(ctx).verify(ctx)?;
         Ok(())
     }
 }
 
 declare_op!(
-    /// Push numeric constant on stack.
+    /// Saves the value from the stack (without popping it) into the local variable with the given index
     ///
     /// Attributes:
     ///
     /// | key | value |
     /// |-----|-------|
-    /// |[ATTR_KEY_VALUE](ConstantOp::ATTR_KEY_VALUE) | [IntegerAttr] or [FloatAttr] |
+    /// |[ATTR_KEY_INDEX](Self::ATTR_KEY_INDEX) | [LocalIndexAttr] |
     ///
-    ConstantOp,
-    "const",
+    LocalTeeOp,
+    "local.tee",
     "wasm"
 );
 
-impl ConstantOp {
-    /// Attribute key for the constant value.
-    pub const ATTR_KEY_VALUE: &str = "const.value";
-    /// Get the constant value that this Op defines.
-    pub fn get_value(&self, ctx: &Context) -> AttrObj {
+impl LocalTeeOp {
+    /// Attribute key for the index
+    pub const ATTR_KEY_INDEX: &str = "local.tee.index";
+
+    /// Get the index of the local variable.
+    pub fn get_index(&self, ctx: &Context) -> LocalIndex {
         let op = self.get_operation().deref(ctx);
-        #[allow(clippy::expect_used)]
-        let value = op
-            .attributes
-            .get(Self::ATTR_KEY_VALUE)
-            .expect("no attribute found");
-        if value.is::<IntegerAttr>() {
-            attribute::clone::<IntegerAttr>(value)
-        } else {
-            attribute::clone::<FloatAttr>(value)
-        }
+        op.attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .and_then(|attr| attr.downcast_ref::<LocalIndexAttr>())
+            .expect("no LocalIndexAttr attribute found")
+            .get_index()
     }
 
-    /// Create a new [ConstOp]. The underlying [Operation] is not linked to a
-    /// [BasicBlock](crate::basic_block::BasicBlock).
-    pub fn new_unlinked(ctx: &mut Context, val: AttrObj) -> ConstantOp {
+    /// Create a new [LocalTeeOp].
+    pub fn new_unlinked(ctx: &mut Context, index: u32) -> LocalTeeOp {
         let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-        op.deref_mut(ctx)
-            .attributes
-            .insert(Self::ATTR_KEY_VALUE, val);
-        ConstantOp { op }
-    }
 
-    /// Create a new i32 [ConstOp]. The underlying [Operation] is not linked to a
-    /// [BasicBlock](crate::basic_block::BasicBlock).
-    pub fn new_i32_unlinked(ctx: &mut Context, val: i32) -> ConstantOp {
-        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-        let val_attr = i32_attr(ctx, val);
+        let index_attr = LocalIndexAttr::create(index.into());
         op.deref_mut(ctx)
             .attributes
-            .insert(Self::ATTR_KEY_VALUE, val_attr);
-        ConstantOp { op }
+            .insert(Self::ATTR_KEY_INDEX, index_attr);
+        LocalTeeOp { op }
     }
 }
 
-impl DisplayWithContext for ConstantOp {
+impl DisplayWithContext for LocalTeeOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{} {}",
             self.get_opid().with_ctx(ctx),
-            self.get_value(ctx).with_ctx(ctx)
+            self.get_index(ctx)
         )
     }
 }
 
-impl Verify for ConstantOp {
+impl Verify for LocalTeeOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        let value = self.get_value(ctx);
-        if !(value.is::<IntegerAttr>() || value.is::<FloatAttr>()) {
+        let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .map_or(false, |attr| attr.is::<LocalIndexAttr>())
+        {
             return Err(CompilerError::VerificationError {
-                msg: "Unexpected constant type".to_string(),
+                msg: "Expected LocalIndexAttr for index".to_string(),
             });
         }
-        let op = &*self.get_operation().deref(ctx);
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -495,60 +3069,69 @@ impl Verify for ConstantOp {
     }
 }
 
-// TODO: store expected operand types (poped from stack)?
-
 declare_op!(
-    /// Push two top stack items, sums them and push result on stack
+    /// Pops the stack and save the value into the global variable with the given index
     ///
     /// Attributes:
     ///
     /// | key | value |
     /// |-----|-------|
-    /// | [ATTR_KEY_OP_TYPE](FuncOp::ATTR_KEY_OP_TYPE) | [TypeAttr](super::attributes::TypeAttr) |
+    /// |[ATTR_KEY_INDEX](Self::ATTR_KEY_INDEX) | [GlobalIndexAttr] |
     ///
-    AddOp,
-    "add",
+    GlobalSetOp,
+    "global.set",
     "wasm"
 );
 
-impl AddOp {
-    /// Attribute key
-    pub const ATTR_KEY_OP_TYPE: &str = "add.type";
-    /// Create a new [AddOp]. The underlying [Operation] is not linked to a
-    /// [BasicBlock](crate::basic_block::BasicBlock).
-    pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> ConstantOp {
-        let ty_attr = TypeAttr::create(ty);
-        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-        op.deref_mut(ctx)
-            .attributes
-            .insert(Self::ATTR_KEY_OP_TYPE, ty_attr);
-        ConstantOp { op }
+impl GlobalSetOp {
+    /// Attribute key for the index
+    pub const ATTR_KEY_INDEX: &str = "global.set.index";
+
+    /// Get the index of the global variable.
+    pub fn get_index(&self, ctx: &Context) -> GlobalIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .and_then(|attr| attr.downcast_ref::<GlobalIndexAttr>())
+            .expect("no GlobalIndexAttr attribute found")
+            .get_index()
     }
 
-    /// Get the type of the operands and the result of this operation.
-    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
-        let opref = self.get_operation().deref(ctx);
-        #[allow(clippy::expect_used)]
-        let ty_attr = opref
+    /// Create a new [GlobalSetOp].
+    pub fn new_unlinked(ctx: &mut Context, index: GlobalIndex) -> GlobalSetOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+
+        let index_attr = GlobalIndexAttr::create(index);
+        op.deref_mut(ctx)
             .attributes
-            .get(Self::ATTR_KEY_OP_TYPE)
-            .expect("no type attribute");
-        #[allow(clippy::expect_used)]
-        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
-            .expect("invalid type attribute")
-            .get_type()
+            .insert(Self::ATTR_KEY_INDEX, index_attr);
+        GlobalSetOp { op }
     }
 }
 
-impl DisplayWithContext for AddOp {
+impl DisplayWithContext for GlobalSetOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.get_opid().with_ctx(ctx),)
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_index(ctx)
+        )
     }
 }
 
-impl Verify for AddOp {
+impl Verify for GlobalSetOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
         let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .map_or(false, |attr| attr.is::<GlobalIndexAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected GlobalIndexAttr for index".to_string(),
+            });
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -564,63 +3147,68 @@ impl Verify for AddOp {
 }
 
 declare_op!(
-    /// Call a function by it's index in the module
+    /// Push global variable with the given index onto the stack.
     ///
-    /// https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-instr-control
+    /// Attributes:
     ///
-    CallOp,
-    "call",
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_INDEX](Self::ATTR_KEY_INDEX) | [GlobalIndexAttr] |
+    ///
+    GlobalGetOp,
+    "global.get",
     "wasm"
 );
 
-impl CallOp {
-    const ATTR_KEY_FUNC_INDEX: &str = "call.func_index";
+impl GlobalGetOp {
+    /// Attribute key for the index
+    pub const ATTR_KEY_INDEX: &str = "global.get.index";
 
-    /// Get the function index
-    pub fn get_func_index(&self, ctx: &Context) -> FuncIndex {
+    /// Get the index of the global variable.
+    pub fn get_index(&self, ctx: &Context) -> GlobalIndex {
         let op = self.get_operation().deref(ctx);
-        #[allow(clippy::expect_used)]
-        let func_index = op
-            .attributes
-            .get(Self::ATTR_KEY_FUNC_INDEX)
-            .expect("no attribute found");
-        #[allow(clippy::expect_used)]
-        let func_index = apint_to_i32(
-            func_index
-                .downcast_ref::<IntegerAttr>()
-                .expect("ModuleOp function index is not an IntegerAttr")
-                .clone()
-                .into(),
-        ) as u32;
-        func_index.into()
+        op.attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .and_then(|attr| attr.downcast_ref::<GlobalIndexAttr>())
+            .expect("no GlobalIndexAttr attribute found")
+            .get_index()
     }
 
-    /// Create a new [CallOp]. The underlying [Operation] is not linked to a
-    /// [BasicBlock](crate::basic_block::BasicBlock).
-    pub fn new_unlinked(ctx: &mut Context, func_index: FuncIndex) -> CallOp {
+    /// Create a new [GlobalGetOp].
+    pub fn new_unlinked(ctx: &mut Context, index: u32) -> GlobalGetOp {
         let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-        let func_index_attr = u32_attr(ctx, func_index.into());
+
+        let index_attr = GlobalIndexAttr::create(index.into());
         op.deref_mut(ctx)
             .attributes
-            .insert(Self::ATTR_KEY_FUNC_INDEX, func_index_attr);
-        CallOp { op }
+            .insert(Self::ATTR_KEY_INDEX, index_attr);
+        GlobalGetOp { op }
     }
 }
 
-impl DisplayWithContext for CallOp {
+impl DisplayWithContext for GlobalGetOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{} {}",
             self.get_opid().with_ctx(ctx),
-            self.get_func_index(ctx)
+            self.get_index(ctx)
         )
     }
 }
 
-impl Verify for CallOp {
+impl Verify for GlobalGetOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
         let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_INDEX)
+            .map_or(false, |attr| attr.is::<GlobalIndexAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected GlobalIndexAttr for index".to_string(),
+            });
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -636,30 +3224,68 @@ impl Verify for CallOp {
 }
 
 declare_op!(
-    /// Return (branch to the outermost block)
-    /// https://webassembly.github.io/spec/core/syntax/instructions.html#syntax-instr-control
-    ReturnOp,
-    "return",
+    /// Pushes the current size of the memory in pages (64 KiB).
+    /// https://webassembly.github.io/spec/core/syntax/instructions.html#memory-instructions
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_MEMORY_INDEX](Self::ATTR_KEY_MEMORY_INDEX) | [MemoryIndexAttr] |
+    ///
+    MemorySizeOp,
+    "memory.size",
     "wasm"
 );
 
-impl ReturnOp {
-    /// Create a new op
-    pub fn new_unlinked(ctx: &mut Context) -> ReturnOp {
+impl MemorySizeOp {
+    /// Attribute key for the memory index
+    pub const ATTR_KEY_MEMORY_INDEX: &str = "memory.size.index";
+
+    /// Get the index of the memory.
+    pub fn get_memory_index(&self, ctx: &Context) -> MemoryIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_MEMORY_INDEX)
+            .and_then(|attr| attr.downcast_ref::<MemoryIndexAttr>())
+            .expect("no MemoryIndexAttr attribute found")
+            .get_index()
+    }
+
+    /// Create a new [MemorySizeOp].
+    pub fn new_unlinked(ctx: &mut Context, memory_index: MemoryIndex) -> MemorySizeOp {
         let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-        ReturnOp { op }
+        op.deref_mut(ctx).attributes.insert(
+            Self::ATTR_KEY_MEMORY_INDEX,
+            MemoryIndexAttr::create(memory_index),
+        );
+        MemorySizeOp { op }
     }
 }
 
-impl DisplayWithContext for ReturnOp {
+impl DisplayWithContext for MemorySizeOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.get_opid().with_ctx(ctx),)
+        write!(
+            f,
+            "{} {}",
+            self.get_opid().with_ctx(ctx),
+            self.get_memory_index(ctx)
+        )
     }
 }
 
-impl Verify for ReturnOp {
+impl Verify for MemorySizeOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
         let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_MEMORY_INDEX)
+            .map_or(false, |attr| attr.is::<MemoryIndexAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected MemoryIndexAttr for memory index".to_string(),
+            });
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -668,92 +3294,76 @@ impl Verify for ReturnOp {
         if op.get_num_results() != 0 || op.get_num_operands() != 0 {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect number of results or operands".to_string(),
-            });
-        }
-        Ok(())
-    }
-}
-
-declare_op!(
-    /// A block operation containing a single region.
-    BlockOp,
-    "block",
-    "wasm"
-);
-
-impl BlockOp {
-    /// Attribute key for the function type
-    pub const ATTR_KEY_BLOCK_TYPE: &str = "block.type";
-
-    /// Create a new [BlockOp].
-    pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> BlockOp {
-        let ty_attr = TypeAttr::create(ty);
-        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
-        {
-            let opref = &mut *op.deref_mut(ctx);
-            // Set function type attributes.
-            opref.attributes.insert(Self::ATTR_KEY_BLOCK_TYPE, ty_attr);
-        }
-        let opop = BlockOp { op };
-        // Create an empty block.
-        #[allow(clippy::expect_used)]
-        let region = opop.get_region(ctx);
-        let body = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
-        body.insert_at_front(region, ctx);
-
-        opop
-    }
-
-    /// Get the signature (type).
-    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
-        let opref = self.get_operation().deref(ctx);
-        #[allow(clippy::unwrap_used)]
-        let ty_attr = opref.attributes.get(Self::ATTR_KEY_BLOCK_TYPE).unwrap();
-        #[allow(clippy::unwrap_used)]
-        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
-            .unwrap()
-            .get_type()
+            });
+        }
+        Ok(())
     }
+}
 
-    /// Get the bb of this block.
-    pub fn get_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
-        #[allow(clippy::unwrap_used)]
-        self.get_region(ctx).deref(ctx).get_head().unwrap()
+declare_op!(
+    /// Pops the number of pages (64 KiB) to grow the memory by, grows the memory and pushes
+    /// the previous size in pages, or -1 if the memory can't grow that much.
+    /// https://webassembly.github.io/spec/core/syntax/instructions.html#memory-instructions
+    ///
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_MEMORY_INDEX](Self::ATTR_KEY_MEMORY_INDEX) | [MemoryIndexAttr] |
+    ///
+    MemoryGrowOp,
+    "memory.grow",
+    "wasm"
+);
+
+impl MemoryGrowOp {
+    /// Attribute key for the memory index
+    pub const ATTR_KEY_MEMORY_INDEX: &str = "memory.grow.index";
+
+    /// Get the index of the memory.
+    pub fn get_memory_index(&self, ctx: &Context) -> MemoryIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_MEMORY_INDEX)
+            .and_then(|attr| attr.downcast_ref::<MemoryIndexAttr>())
+            .expect("no MemoryIndexAttr attribute found")
+            .get_index()
     }
 
-    /// Get an iterator over all operations.
-    pub fn op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
-        self.get_region(ctx)
-            .deref(ctx)
-            .iter(ctx)
-            .flat_map(|bb| bb.deref(ctx).iter(ctx))
+    /// Create a new [MemoryGrowOp].
+    pub fn new_unlinked(ctx: &mut Context, memory_index: MemoryIndex) -> MemoryGrowOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        op.deref_mut(ctx).attributes.insert(
+            Self::ATTR_KEY_MEMORY_INDEX,
+            MemoryIndexAttr::create(memory_index),
+        );
+        MemoryGrowOp { op }
     }
 }
 
-impl OneRegionInterface for BlockOp {}
-impl DisplayWithContext for BlockOp {
+impl DisplayWithContext for MemoryGrowOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let region = self.get_region(ctx).with_ctx(ctx).to_string();
         write!(
             f,
-            "{} {} {{\n{}}}",
+            "{} {}",
             self.get_opid().with_ctx(ctx),
-            self.get_type(ctx).with_ctx(ctx),
-            indent::indent_all_by(2, region),
+            self.get_memory_index(ctx)
         )
     }
 }
 
-impl Verify for BlockOp {
+impl Verify for MemoryGrowOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        let ty = self.get_type(ctx);
-
-        if !(ty.deref(ctx).is::<FunctionType>()) {
+        let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_MEMORY_INDEX)
+            .map_or(false, |attr| attr.is::<MemoryIndexAttr>())
+        {
             return Err(CompilerError::VerificationError {
-                msg: "Unexpected Block type".to_string(),
+                msg: "Expected MemoryIndexAttr for memory index".to_string(),
             });
         }
-        let op = &*self.get_operation().deref(ctx);
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -764,98 +3374,98 @@ impl Verify for BlockOp {
                 msg: "Incorrect number of results or operands".to_string(),
             });
         }
-        self.verify_interfaces(ctx)?;
-        self.get_block(ctx).verify(ctx)?;
         Ok(())
     }
 }
 
 declare_op!(
-    /// A loop block operation containing a single region.
+    /// An active data segment of the memory 0, the bytes copied into the memory at the offset
+    /// on the module instantiation. Placed in the [ModuleOp] body.
+    /// https://webassembly.github.io/spec/core/syntax/modules.html#data-segments
     ///
     /// Attributes:
     ///
     /// | key | value |
     /// |-----|-------|
-    /// | [ATTR_KEY_BLOCK_TYPE](Self::ATTR_KEY_BLOCK_TYPE) | [TypeAttr](super::attributes::TypeAttr) |
-    LoopOp,
-    "loop",
+    /// |[ATTR_KEY_OFFSET](Self::ATTR_KEY_OFFSET) | [IntegerAttr] |
+    /// |[ATTR_KEY_BYTES](Self::ATTR_KEY_BYTES) | [BytesAttr] |
+    ///
+    DataOp,
+    "data",
     "wasm"
 );
 
-impl LoopOp {
-    /// Attribute key for the function type
-    pub const ATTR_KEY_BLOCK_TYPE: &str = "block.type";
+impl DataOp {
+    /// Attribute key for the address of the first byte
+    pub const ATTR_KEY_OFFSET: &str = "data.offset";
+    /// Attribute key for the bytes
+    pub const ATTR_KEY_BYTES: &str = "data.bytes";
 
-    /// Create a new [LoopOp].
-    pub fn new_unlinked(ctx: &mut Context, ty: Ptr<TypeObj>) -> LoopOp {
-        let ty_attr = TypeAttr::create(ty);
-        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 1);
+    /// Create a new [DataOp].
+    pub fn new_unlinked(ctx: &mut Context, offset: u32, bytes: Vec<u8>) -> DataOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        let offset_attr = u32_attr(ctx, offset);
         {
             let opref = &mut *op.deref_mut(ctx);
-            // Set function type attributes.
-            opref.attributes.insert(Self::ATTR_KEY_BLOCK_TYPE, ty_attr);
+            opref.attributes.insert(Self::ATTR_KEY_OFFSET, offset_attr);
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_BYTES, BytesAttr::create(bytes));
         }
-        let opop = LoopOp { op };
-        // Create an empty block.
-        #[allow(clippy::expect_used)]
-        let region = opop.get_region(ctx);
-        let body = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
-        body.insert_at_front(region, ctx);
-
-        opop
-    }
-
-    /// Get the signature (type).
-    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
-        let opref = self.get_operation().deref(ctx);
-        #[allow(clippy::unwrap_used)]
-        let ty_attr = opref.attributes.get(Self::ATTR_KEY_BLOCK_TYPE).unwrap();
-        #[allow(clippy::unwrap_used)]
-        attr_cast::<dyn TypedAttrInterface>(&**ty_attr)
-            .unwrap()
-            .get_type()
+        DataOp { op }
     }
 
-    /// Get the bb of this block.
-    pub fn get_block(&self, ctx: &Context) -> Ptr<BasicBlock> {
-        #[allow(clippy::unwrap_used)]
-        self.get_region(ctx).deref(ctx).get_head().unwrap()
+    /// Get the address of the first byte.
+    pub fn get_offset(&self, ctx: &Context) -> u32 {
+        let op = self.get_operation().deref(ctx);
+        let attr = op
+            .attributes
+            .get(Self::ATTR_KEY_OFFSET)
+            .expect("no data offset attribute found");
+        to_u32_checked(ctx, attr).expect("data offset attribute should be u32")
     }
 
-    /// Get an iterator over all operations.
-    pub fn op_iter<'a>(&self, ctx: &'a Context) -> impl Iterator<Item = Ptr<Operation>> + 'a {
-        self.get_region(ctx)
-            .deref(ctx)
-            .iter(ctx)
-            .flat_map(|bb| bb.deref(ctx).iter(ctx))
+    /// Get the bytes.
+    pub fn get_bytes(&self, ctx: &Context) -> Vec<u8> {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_BYTES)
+            .and_then(|attr| attr.downcast_ref::<BytesAttr>())
+            .expect("no BytesAttr attribute found")
+            .get_bytes()
+            .to_vec()
     }
 }
 
-impl OneRegionInterface for LoopOp {}
-impl DisplayWithContext for LoopOp {
+impl DisplayWithContext for DataOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let region = self.get_region(ctx).with_ctx(ctx).to_string();
+        let op = self.get_operation().deref(ctx);
+        let bytes_attr = op
+            .attributes
+            .get(Self::ATTR_KEY_BYTES)
+            .expect("no BytesAttr attribute found");
         write!(
             f,
-            "{} {} {{\n{}}}",
+            "{} offset={} {}",
             self.get_opid().with_ctx(ctx),
-            self.get_type(ctx).with_ctx(ctx),
-            indent::indent_all_by(2, region),
+            self.get_offset(ctx),
+            bytes_attr.with_ctx(ctx)
         )
     }
 }
 
-impl Verify for LoopOp {
+impl Verify for DataOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        let ty = self.get_type(ctx);
-
-        if !(ty.deref(ctx).is::<FunctionType>()) {
+        let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_BYTES)
+            .map_or(false, |attr| attr.is::<BytesAttr>())
+        {
             return Err(CompilerError::VerificationError {
-                msg: "Unexpected Block type".to_string(),
+                msg: "Expected BytesAttr for data bytes".to_string(),
             });
         }
-        let op = &*self.get_operation().deref(ctx);
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -866,92 +3476,171 @@ impl Verify for LoopOp {
                 msg: "Incorrect number of results or operands".to_string(),
             });
         }
-        self.verify_interfaces(ctx)?;
-        self.get_block(ctx).verify(ctx)?;
         Ok(())
     }
 }
 
 declare_op!(
-    /// Push local variable with the given index onto the stack.
+    /// A global of the module, its value type, mutability and initial value (set on the module
+    /// instantiation). Placed in the [ModuleOp] body in the order of the global indices.
+    /// https://webassembly.github.io/spec/core/syntax/modules.html#globals
     ///
     /// Attributes:
     ///
     /// | key | value |
     /// |-----|-------|
-    /// |[ATTR_KEY_INDEX](Self::ATTR_KEY_INDEX) | [IntegerAttr] |
+    /// |[ATTR_KEY_TYPE](Self::ATTR_KEY_TYPE) | [TypeAttr] |
+    /// |[ATTR_KEY_MUTABLE](Self::ATTR_KEY_MUTABLE) | [IntegerAttr] |
+    /// |[ATTR_KEY_INIT_I32](Self::ATTR_KEY_INIT_I32) | [IntegerAttr] (`i32.const` initializer) |
+    /// |[ATTR_KEY_INIT_I64](Self::ATTR_KEY_INIT_I64) | [IntegerAttr] (`i64.const` initializer) |
+    /// |[ATTR_KEY_INIT_GLOBAL](Self::ATTR_KEY_INIT_GLOBAL) | [GlobalIndexAttr] (`global.get` initializer) |
     ///
-    LocalGetOp,
-    "local.get",
+    GlobalOp,
+    "global",
     "wasm"
 );
 
-impl LocalGetOp {
-    /// Attribute key for the index
-    pub const ATTR_KEY_INDEX: &str = "local.get.index";
+impl GlobalOp {
+    /// Attribute key for the value type
+    pub const ATTR_KEY_TYPE: &str = "global.type";
+    /// Attribute key for the mutability (0 or 1)
+    pub const ATTR_KEY_MUTABLE: &str = "global.mutable";
+    /// Attribute key for the value of the `i32.const` initializer
+    pub const ATTR_KEY_INIT_I32: &str = "global.init.i32";
+    /// Attribute key for the value of the `i64.const` initializer
+    pub const ATTR_KEY_INIT_I64: &str = "global.init.i64";
+    /// Attribute key for the global of the `global.get` initializer
+    pub const ATTR_KEY_INIT_GLOBAL: &str = "global.init.global";
+
+    /// Create a new [GlobalOp].
+    ///
+    /// # Panics
+    ///
+    /// If the initializer is not `i32.const`, `i64.const` or `global.get`
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        ty: Ptr<TypeObj>,
+        mutable: bool,
+        init: GlobalInit,
+    ) -> GlobalOp {
+        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+        let mutable_attr = u32_attr(ctx, mutable as u32);
+        let (init_key, init_attr) = match init {
+            GlobalInit::I32Const(value) => (Self::ATTR_KEY_INIT_I32, i32_attr(ctx, value)),
+            GlobalInit::I64Const(value) => (Self::ATTR_KEY_INIT_I64, i64_attr(ctx, value)),
+            GlobalInit::GetGlobal(index) => {
+                (Self::ATTR_KEY_INIT_GLOBAL, GlobalIndexAttr::create(index))
+            }
+            GlobalInit::F32Const(_)
+            | GlobalInit::F64Const(_)
+            | GlobalInit::V128Const(_)
+            | GlobalInit::RefNullConst
+            | GlobalInit::RefFunc(_)
+            | GlobalInit::Import => panic!("unsupported global initializer {init:?}"),
+        };
+        {
+            let opref = &mut *op.deref_mut(ctx);
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_TYPE, TypeAttr::create(ty));
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_MUTABLE, mutable_attr);
+            opref.attributes.insert(init_key, init_attr);
+        }
+        GlobalOp { op }
+    }
 
-    /// Get the index of the local variable.
-    pub fn get_index_as_attr(&self, ctx: &Context) -> AttrObj {
+    /// Get the value type.
+    pub fn get_type(&self, ctx: &Context) -> Ptr<TypeObj> {
         let op = self.get_operation().deref(ctx);
-        #[allow(clippy::expect_used)]
-        let value = op
-            .attributes
-            .get(Self::ATTR_KEY_INDEX)
-            .expect("no attribute found");
-        attribute::clone::<IntegerAttr>(value)
+        attr_cast::<dyn TypedAttrInterface>(
+            &**op
+                .attributes
+                .get(Self::ATTR_KEY_TYPE)
+                .expect("no global type attribute found"),
+        )
+        .expect("global type attribute should be a TypeAttr")
+        .get_type()
     }
 
-    /// Create a new [LocalGetOp].
-    pub fn new_unlinked(ctx: &mut Context, index: u32) -> LocalGetOp {
-        let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-
-        let index_attr = u32_attr(ctx, index);
-        op.deref_mut(ctx)
+    /// Whether the value may change at runtime.
+    pub fn is_mutable(&self, ctx: &Context) -> bool {
+        let op = self.get_operation().deref(ctx);
+        let attr = op
             .attributes
-            .insert(Self::ATTR_KEY_INDEX, index_attr);
-        LocalGetOp { op }
+            .get(Self::ATTR_KEY_MUTABLE)
+            .expect("no global mutability attribute found");
+        to_u32_checked(ctx, attr).expect("global mutability attribute should be u32") != 0
     }
 
-    /// Get the index of the local variable.
-    pub fn get_index(&self, ctx: &Context) -> LocalIndex {
-        let attr = self.get_index_as_attr(ctx);
-        let value_u32 = apint_to_i32(
-            attr.downcast_ref::<IntegerAttr>()
-                .expect("index is not an IntegerAttr")
-                .clone()
-                .into(),
-        ) as u32;
-        value_u32.into()
+    /// Get the initializer.
+    pub fn get_init(&self, ctx: &Context) -> GlobalInit {
+        let op = self.get_operation().deref(ctx);
+        if let Some(attr) = op.attributes.get(Self::ATTR_KEY_INIT_I32) {
+            GlobalInit::I32Const(
+                to_i32_checked(ctx, attr).expect("global i32 initializer should be i32"),
+            )
+        } else if let Some(attr) = op.attributes.get(Self::ATTR_KEY_INIT_I64) {
+            GlobalInit::I64Const(
+                to_i64_checked(ctx, attr).expect("global i64 initializer should be i64"),
+            )
+        } else {
+            GlobalInit::GetGlobal(
+                op.attributes
+                    .get(Self::ATTR_KEY_INIT_GLOBAL)
+                    .and_then(|attr| attr.downcast_ref::<GlobalIndexAttr>())
+                    .expect("no global initializer attribute found")
+                    .get_index(),
+            )
+        }
     }
 }
 
-impl DisplayWithContext for LocalGetOp {
+impl DisplayWithContext for GlobalOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let init = match self.get_init(ctx) {
+            GlobalInit::I32Const(value) => format!("i32.const {value}"),
+            GlobalInit::I64Const(value) => format!("i64.const {value}"),
+            GlobalInit::GetGlobal(index) => format!("global.get {index}"),
+            init => format!("{init:?}"),
+        };
         write!(
             f,
-            "{} {}",
+            "{} {}{} = {}",
             self.get_opid().with_ctx(ctx),
-            self.get_index(ctx)
+            if self.is_mutable(ctx) { "mut " } else { "" },
+            self.get_type(ctx).with_ctx(ctx),
+            init
         )
     }
 }
 
-impl Verify for LocalGetOp {
+impl Verify for GlobalOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        let index = self.get_index_as_attr(ctx);
-        if let Ok(index_attr) = index.downcast::<IntegerAttr>() {
-            #[allow(clippy::unwrap_used)]
-            if index_attr.get_type() != u32_type_unwrapped(ctx) {
-                return Err(CompilerError::VerificationError {
-                    msg: "Expected u32 for index".to_string(),
-                });
-            }
-        } else {
+        let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_TYPE)
+            .map_or(false, |attr| attr.is::<TypeAttr>())
+        {
             return Err(CompilerError::VerificationError {
-                msg: "Unexpected index type".to_string(),
+                msg: "Expected TypeAttr for global type".to_string(),
             });
-        };
-        let op = &*self.get_operation().deref(ctx);
+        }
+        let init_count = [
+            Self::ATTR_KEY_INIT_I32,
+            Self::ATTR_KEY_INIT_I64,
+            Self::ATTR_KEY_INIT_GLOBAL,
+        ]
+        .iter()
+        .filter(|key| op.attributes.get(**key).is_some())
+        .count();
+        if init_count != 1 {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected a single global initializer".to_string(),
+            });
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -965,87 +3654,103 @@ impl Verify for LocalGetOp {
         Ok(())
     }
 }
-
 declare_op!(
-    /// Pops the stack and save the value into the local variable with the given index
+    /// Pops the number of bytes, the offset in the passive data segment and the destination
+    /// address and copies the bytes of the segment into the memory.
+    /// https://webassembly.github.io/spec/core/syntax/instructions.html#memory-instructions
     ///
     /// Attributes:
     ///
     /// | key | value |
     /// |-----|-------|
-    /// |[ATTR_KEY_INDEX](Self::ATTR_KEY_INDEX) | [IntegerAttr] |
+    /// |[ATTR_KEY_MEMORY_INDEX](Self::ATTR_KEY_MEMORY_INDEX) | [MemoryIndexAttr] |
+    /// |[ATTR_KEY_DATA_INDEX](Self::ATTR_KEY_DATA_INDEX) | [DataIndexAttr] |
     ///
-    LocalSetOp,
-    "local.set",
+    MemoryInitOp,
+    "memory.init",
     "wasm"
 );
 
-impl LocalSetOp {
-    /// Attribute key for the index
-    pub const ATTR_KEY_INDEX: &str = "local.set.index";
+impl MemoryInitOp {
+    /// Attribute key for the memory index
+    pub const ATTR_KEY_MEMORY_INDEX: &str = "memory.init.memory_index";
+    /// Attribute key for the data segment index
+    pub const ATTR_KEY_DATA_INDEX: &str = "memory.init.data_index";
 
-    /// Get the index of the local variable.
-    pub fn get_index_attr(&self, ctx: &Context) -> AttrObj {
+    /// Get the index of the memory.
+    pub fn get_memory_index(&self, ctx: &Context) -> MemoryIndex {
         let op = self.get_operation().deref(ctx);
-        #[allow(clippy::expect_used)]
-        let value = op
-            .attributes
-            .get(Self::ATTR_KEY_INDEX)
-            .expect("no attribute found");
-        attribute::clone::<IntegerAttr>(value)
+        op.attributes
+            .get(Self::ATTR_KEY_MEMORY_INDEX)
+            .and_then(|attr| attr.downcast_ref::<MemoryIndexAttr>())
+            .expect("no MemoryIndexAttr attribute found")
+            .get_index()
     }
 
-    /// Get the index of the local variable.
-    pub fn get_index(&self, ctx: &Context) -> LocalIndex {
-        let attr = self.get_index_attr(ctx);
-        let value_u32 = apint_to_i32(
-            attr.downcast_ref::<IntegerAttr>()
-                .expect("index is not an IntegerAttr")
-                .clone()
-                .into(),
-        ) as u32;
-        value_u32.into()
+    /// Get the index of the data segment.
+    pub fn get_data_index(&self, ctx: &Context) -> DataIndex {
+        let op = self.get_operation().deref(ctx);
+        op.attributes
+            .get(Self::ATTR_KEY_DATA_INDEX)
+            .and_then(|attr| attr.downcast_ref::<DataIndexAttr>())
+            .expect("no DataIndexAttr attribute found")
+            .get_index()
     }
 
-    /// Create a new [LocalSetOp].
-    pub fn new_unlinked(ctx: &mut Context, index: u32) -> LocalSetOp {
+    /// Create a new [MemoryInitOp].
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        memory_index: MemoryIndex,
+        data_index: DataIndex,
+    ) -> MemoryInitOp {
         let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-
-        let index_attr = u32_attr(ctx, index);
-        op.deref_mut(ctx)
-            .attributes
-            .insert(Self::ATTR_KEY_INDEX, index_attr);
-        LocalSetOp { op }
+        {
+            let opref = &mut *op.deref_mut(ctx);
+            opref.attributes.insert(
+                Self::ATTR_KEY_MEMORY_INDEX,
+                MemoryIndexAttr::create(memory_index),
+            );
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_DATA_INDEX, DataIndexAttr::create(data_index));
+        }
+        MemoryInitOp { op }
     }
 }
 
-impl DisplayWithContext for LocalSetOp {
+impl DisplayWithContext for MemoryInitOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
-            "{} {}",
+            "{} {} {}",
             self.get_opid().with_ctx(ctx),
-            self.get_index_attr(ctx).with_ctx(ctx)
+            self.get_memory_index(ctx),
+            self.get_data_index(ctx)
         )
     }
 }
 
-impl Verify for LocalSetOp {
+impl Verify for MemoryInitOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        let index = self.get_index_attr(ctx);
-        if let Ok(index_attr) = index.downcast::<IntegerAttr>() {
-            #[allow(clippy::unwrap_used)]
-            if index_attr.get_type() != u32_type_unwrapped(ctx) {
-                return Err(CompilerError::VerificationError {
-                    msg: "Expected u32 for index".to_string(),
-                });
-            }
-        } else {
+        let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_MEMORY_INDEX)
+            .map_or(false, |attr| attr.is::<MemoryIndexAttr>())
+        {
             return Err(CompilerError::VerificationError {
-                msg: "Unexpected index type".to_string(),
+                msg: "Expected MemoryIndexAttr for memory index".to_string(),
             });
-        };
-        let op = &*self.get_operation().deref(ctx);
+        }
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_DATA_INDEX)
+            .map_or(false, |attr| attr.is::<DataIndexAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected DataIndexAttr for data index".to_string(),
+            });
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -1061,67 +3766,66 @@ impl Verify for LocalSetOp {
 }
 
 declare_op!(
-    /// Saves the value from the stack (without popping it) into the local variable with the given index
+    /// Drops the passive data segment, i.e. makes it empty for the following `memory.init`.
     ///
-    LocalTeeOp,
-    "local.tee",
+    /// Attributes:
+    ///
+    /// | key | value |
+    /// |-----|-------|
+    /// |[ATTR_KEY_DATA_INDEX](Self::ATTR_KEY_DATA_INDEX) | [DataIndexAttr] |
+    ///
+    DataDropOp,
+    "data.drop",
     "wasm"
 );
 
-impl LocalTeeOp {
-    /// Attribute key for the index
-    pub const ATTR_KEY_INDEX: &str = "local.tee.index";
+impl DataDropOp {
+    /// Attribute key for the data segment index
+    pub const ATTR_KEY_DATA_INDEX: &str = "data.drop.data_index";
 
-    /// Get the index of the local variable.
-    pub fn get_index(&self, ctx: &Context) -> AttrObj {
+    /// Get the index of the data segment.
+    pub fn get_data_index(&self, ctx: &Context) -> DataIndex {
         let op = self.get_operation().deref(ctx);
-        #[allow(clippy::expect_used)]
-        let value = op
-            .attributes
-            .get(Self::ATTR_KEY_INDEX)
-            .expect("no attribute found");
-        attribute::clone::<IntegerAttr>(value)
+        op.attributes
+            .get(Self::ATTR_KEY_DATA_INDEX)
+            .and_then(|attr| attr.downcast_ref::<DataIndexAttr>())
+            .expect("no DataIndexAttr attribute found")
+            .get_index()
     }
 
-    /// Create a new [LocalTeeOp].
-    pub fn new_unlinked(ctx: &mut Context, index: u32) -> LocalTeeOp {
+    /// Create a new [DataDropOp].
+    pub fn new_unlinked(ctx: &mut Context, data_index: DataIndex) -> DataDropOp {
         let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-
-        let index_attr = u32_attr(ctx, index);
         op.deref_mut(ctx)
             .attributes
-            .insert(Self::ATTR_KEY_INDEX, index_attr);
-        LocalTeeOp { op }
+            .insert(Self::ATTR_KEY_DATA_INDEX, DataIndexAttr::create(data_index));
+        DataDropOp { op }
     }
 }
 
-impl DisplayWithContext for LocalTeeOp {
+impl DisplayWithContext for DataDropOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{} {}",
             self.get_opid().with_ctx(ctx),
-            self.get_index(ctx).with_ctx(ctx)
+            self.get_data_index(ctx)
         )
     }
 }
 
-impl Verify for LocalTeeOp {
+impl Verify for DataDropOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        let index = self.get_index(ctx);
-        if let Ok(index_attr) = index.downcast::<IntegerAttr>() {
-            #[allow(clippy::unwrap_used)]
-            if index_attr.get_type() != u32_type_unwrapped(ctx) {
-                return Err(CompilerError::VerificationError {
-                    msg: "Expected u32 for index".to_string(),
-                });
-            }
-        } else {
+        let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_DATA_INDEX)
+            .map_or(false, |attr| attr.is::<DataIndexAttr>())
+        {
             return Err(CompilerError::VerificationError {
-                msg: "Unexpected index type".to_string(),
+                msg: "Expected DataIndexAttr for data index".to_string(),
             });
-        };
-        let op = &*self.get_operation().deref(ctx);
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -1137,80 +3841,67 @@ impl Verify for LocalTeeOp {
 }
 
 declare_op!(
-    /// Pops the stack and save the value into the global variable with the given index
+    /// Pushes the entry (a function reference) of the table at the popped index.
     ///
     /// Attributes:
     ///
     /// | key | value |
     /// |-----|-------|
-    /// |[ATTR_KEY_INDEX](Self::ATTR_KEY_INDEX) | [IntegerAttr] |
+    /// |[ATTR_KEY_TABLE_INDEX](Self::ATTR_KEY_TABLE_INDEX) | [TableIndexAttr] |
     ///
-    GlobalSetOp,
-    "global.set",
+    TableGetOp,
+    "OPTableGetOp",
     "wasm"
 );
 
-impl GlobalSetOp {
-    /// Attribute key for the index
-    pub const ATTR_KEY_INDEX: &str = "global.set.index";
+impl TableGetOp {
+    /// Attribute key for the table index
+    pub const ATTR_KEY_TABLE_INDEX: &str = "OPTableGetOp.table_index";
 
-    /// Get the index of the global variable.
-    pub fn get_index(&self, ctx: &Context) -> GlobalIndex {
+    /// Get the index of the table.
+    pub fn get_table_index(&self, ctx: &Context) -> TableIndex {
         let op = self.get_operation().deref(ctx);
-        #[allow(clippy::expect_used)]
-        let value = op
-            .attributes
-            .get(Self::ATTR_KEY_INDEX)
-            .expect("no attribute for index found");
-        let value_u32 = apint_to_i32(
-            value
-                .downcast_ref::<IntegerAttr>()
-                .expect("index is not an IntegerAttr")
-                .clone()
-                .into(),
-        ) as u32;
-        value_u32.into()
+        op.attributes
+            .get(Self::ATTR_KEY_TABLE_INDEX)
+            .and_then(|attr| attr.downcast_ref::<TableIndexAttr>())
+            .expect("no TableIndexAttr attribute found")
+            .get_index()
     }
 
-    /// Create a new [GlobalSetOp].
-    pub fn new_unlinked(ctx: &mut Context, index: GlobalIndex) -> GlobalSetOp {
+    /// Create a new [TableGetOp].
+    pub fn new_unlinked(ctx: &mut Context, table_index: TableIndex) -> TableGetOp {
         let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-
-        let index_attr = u32_attr(ctx, index.into());
-        op.deref_mut(ctx)
-            .attributes
-            .insert(Self::ATTR_KEY_INDEX, index_attr);
-        GlobalSetOp { op }
+        op.deref_mut(ctx).attributes.insert(
+            Self::ATTR_KEY_TABLE_INDEX,
+            TableIndexAttr::create(table_index),
+        );
+        TableGetOp { op }
     }
 }
 
-impl DisplayWithContext for GlobalSetOp {
+impl DisplayWithContext for TableGetOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{} {}",
             self.get_opid().with_ctx(ctx),
-            self.get_index(ctx)
+            self.get_table_index(ctx)
         )
     }
 }
 
-impl Verify for GlobalSetOp {
+impl Verify for TableGetOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
-        // let index = self.get_index(ctx);
-        // if let Ok(index_attr) = index.downcast::<IntegerAttr>() {
-        //     #[allow(clippy::unwrap_used)]
-        //     if index_attr.get_type() != u32_type_unwrapped(ctx) {
-        //         return Err(CompilerError::VerificationError {
-        //             msg: "Expected u32 for index".to_string(),
-        //         });
-        //     }
-        // } else {
-        //     return Err(CompilerError::VerificationError {
-        //         msg: "Unexpected index type".to_string(),
-        //     });
-        // };
         let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_TABLE_INDEX)
+            .map_or(false, |attr| attr.is::<TableIndexAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected TableIndexAttr for table index".to_string(),
+            });
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -1226,67 +3917,67 @@ impl Verify for GlobalSetOp {
 }
 
 declare_op!(
-    /// Push global variable with the given index onto the stack.
+    /// Pops the function reference and the index and sets the table entry at the index.
     ///
     /// Attributes:
     ///
     /// | key | value |
     /// |-----|-------|
-    /// |[ATTR_KEY_INDEX](Self::ATTR_KEY_INDEX) | [IntegerAttr] |
+    /// |[ATTR_KEY_TABLE_INDEX](Self::ATTR_KEY_TABLE_INDEX) | [TableIndexAttr] |
     ///
-    GlobalGetOp,
-    "global.get",
+    TableSetOp,
+    "OPTableSetOp",
     "wasm"
 );
 
-impl GlobalGetOp {
-    /// Attribute key for the index
-    pub const ATTR_KEY_INDEX: &str = "global.get.index";
+impl TableSetOp {
+    /// Attribute key for the table index
+    pub const ATTR_KEY_TABLE_INDEX: &str = "OPTableSetOp.table_index";
 
-    /// Get the index of the global variable.
-    pub fn get_index(&self, ctx: &Context) -> GlobalIndex {
+    /// Get the index of the table.
+    pub fn get_table_index(&self, ctx: &Context) -> TableIndex {
         let op = self.get_operation().deref(ctx);
-        #[allow(clippy::expect_used)]
-        let value = op
-            .attributes
-            .get(Self::ATTR_KEY_INDEX)
-            .expect("no attribute for index found");
-        let value_u32 = apint_to_i32(
-            value
-                .downcast_ref::<IntegerAttr>()
-                .expect("index is not an IntegerAttr")
-                .clone()
-                .into(),
-        ) as u32;
-        value_u32.into()
+        op.attributes
+            .get(Self::ATTR_KEY_TABLE_INDEX)
+            .and_then(|attr| attr.downcast_ref::<TableIndexAttr>())
+            .expect("no TableIndexAttr attribute found")
+            .get_index()
     }
 
-    /// Create a new [GlobalGetOp].
-    pub fn new_unlinked(ctx: &mut Context, index: u32) -> GlobalGetOp {
+    /// Create a new [TableSetOp].
+    pub fn new_unlinked(ctx: &mut Context, table_index: TableIndex) -> TableSetOp {
         let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-
-        let index_attr = u32_attr(ctx, index);
-        op.deref_mut(ctx)
-            .attributes
-            .insert(Self::ATTR_KEY_INDEX, index_attr);
-        GlobalGetOp { op }
+        op.deref_mut(ctx).attributes.insert(
+            Self::ATTR_KEY_TABLE_INDEX,
+            TableIndexAttr::create(table_index),
+        );
+        TableSetOp { op }
     }
 }
 
-impl DisplayWithContext for GlobalGetOp {
+impl DisplayWithContext for TableSetOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{} {}",
             self.get_opid().with_ctx(ctx),
-            self.get_index(ctx),
+            self.get_table_index(ctx)
         )
     }
 }
 
-impl Verify for GlobalGetOp {
+impl Verify for TableSetOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
         let op = &*self.get_operation().deref(ctx);
+        if !op
+            .attributes
+            .get(Self::ATTR_KEY_TABLE_INDEX)
+            .map_or(false, |attr| attr.is::<TableIndexAttr>())
+        {
+            return Err(CompilerError::VerificationError {
+                msg: "Expected TableIndexAttr for table index".to_string(),
+            });
+        }
         if op.get_opid() != Self::get_opid_static() {
             return Err(CompilerError::VerificationError {
                 msg: "Incorrect OpId".to_string(),
@@ -1310,6 +4001,77 @@ pub enum MemAccessOpValueType {
     I64,
 }
 
+impl MemAccessOpValueType {
+    /// Width of the value in bytes
+    pub fn width_bytes(&self) -> u32 {
+        match self {
+            MemAccessOpValueType::I32 => 4,
+            MemAccessOpValueType::I64 => 8,
+        }
+    }
+}
+
+const ATTR_KEY_MEMARG_OFFSET: &str = "memarg.offset";
+const ATTR_KEY_MEMARG_ALIGN: &str = "memarg.align";
+const ATTR_KEY_MEMARG_MEMORY: &str = "memarg.memory";
+
+/// Store the memarg of a memory access op in its attributes (the memory only if it's not 0)
+fn set_memarg(ctx: &mut Context, op: Ptr<Operation>, memarg: MemArg) {
+    let offset_attr = u32_attr(ctx, memarg.offset);
+    let align_attr = u32_attr(ctx, memarg.align);
+    let mut op_mut = op.deref_mut(ctx);
+    op_mut
+        .attributes
+        .insert(ATTR_KEY_MEMARG_OFFSET, offset_attr);
+    op_mut.attributes.insert(ATTR_KEY_MEMARG_ALIGN, align_attr);
+    if u32::from(memarg.memory) != 0 {
+        op_mut.attributes.insert(
+            ATTR_KEY_MEMARG_MEMORY,
+            MemoryIndexAttr::create(memarg.memory),
+        );
+    } else {
+        op_mut.attributes.remove(ATTR_KEY_MEMARG_MEMORY);
+    }
+}
+
+/// The memarg of a memory access op of `width_bytes` bytes, the default one if it is not set
+fn get_memarg(ctx: &Context, op: Ptr<Operation>, width_bytes: u32) -> MemArg {
+    let op_ref = op.deref(ctx);
+    let natural = MemArg::natural(width_bytes);
+    let get = |key: &str, default: u32| {
+        op_ref.attributes.get(key).map_or(default, |attr| {
+            to_u32_checked(ctx, attr).expect("memarg attribute should be u32")
+        })
+    };
+    let memory = op_ref
+        .attributes
+        .get(ATTR_KEY_MEMARG_MEMORY)
+        .map_or(natural.memory, |attr| {
+            attr.downcast_ref::<MemoryIndexAttr>()
+                .expect("memarg memory attribute should be MemoryIndexAttr")
+                .get_index()
+        });
+    MemArg {
+        offset: get(ATTR_KEY_MEMARG_OFFSET, natural.offset),
+        align: get(ATTR_KEY_MEMARG_ALIGN, natural.align),
+        memory,
+    }
+}
+
+/// Write the memarg in the WAT format (nothing for the default one)
+fn fmt_memarg(
+    f: &mut core::fmt::Formatter<'_>,
+    memarg: MemArg,
+    width_bytes: u32,
+) -> core::fmt::Result {
+    let wat = memarg.to_wat(width_bytes);
+    if wat.is_empty() {
+        Ok(())
+    } else {
+        write!(f, " {wat}")
+    }
+}
+
 declare_op!(
     /// Pops the i32 or i64 value and i32 addresss from stack and save the value at the address.
     ///
@@ -1335,6 +4097,31 @@ impl StoreOp {
         StoreOp { op }
     }
 
+    /// Create a new [StoreOp] with the given static offset and alignment.
+    pub fn new_unlinked_with_memarg(
+        ctx: &mut Context,
+        ty: MemAccessOpValueType,
+        memarg: MemArg,
+    ) -> StoreOp {
+        let op = Self::new_unlinked(ctx, ty);
+        set_memarg(ctx, op.get_operation(), memarg);
+        op
+    }
+
+    /// Get the static offset, alignment and memory.
+    pub fn get_memarg(&self, ctx: &Context) -> MemArg {
+        get_memarg(
+            ctx,
+            self.get_operation(),
+            self.get_value_type(ctx).width_bytes(),
+        )
+    }
+
+    /// Replace the static offset, alignment and memory.
+    pub fn set_memarg(&self, ctx: &mut Context, memarg: MemArg) {
+        set_memarg(ctx, self.get_operation(), memarg);
+    }
+
     /// Get the type of the value.
     pub fn get_value_type(&self, ctx: &Context) -> MemAccessOpValueType {
         let op = self.get_operation().deref(ctx);
@@ -1361,12 +4148,9 @@ impl StoreOp {
 
 impl DisplayWithContext for StoreOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(
-            f,
-            "{} {}",
-            self.get_opid().with_ctx(ctx),
-            self.get_value_type(ctx)
-        )
+        let ty = self.get_value_type(ctx);
+        write!(f, "{} {}", self.get_opid().with_ctx(ctx), ty)?;
+        fmt_memarg(f, self.get_memarg(ctx), ty.width_bytes())
     }
 }
 
@@ -1412,6 +4196,31 @@ impl LoadOp {
         LoadOp { op }
     }
 
+    /// Create a new [LoadOp] with the given static offset and alignment.
+    pub fn new_unlinked_with_memarg(
+        ctx: &mut Context,
+        ty: MemAccessOpValueType,
+        memarg: MemArg,
+    ) -> LoadOp {
+        let op = Self::new_unlinked(ctx, ty);
+        set_memarg(ctx, op.get_operation(), memarg);
+        op
+    }
+
+    /// Get the static offset, alignment and memory.
+    pub fn get_memarg(&self, ctx: &Context) -> MemArg {
+        get_memarg(
+            ctx,
+            self.get_operation(),
+            self.get_value_type(ctx).width_bytes(),
+        )
+    }
+
+    /// Replace the static offset, alignment and memory.
+    pub fn set_memarg(&self, ctx: &mut Context, memarg: MemArg) {
+        set_memarg(ctx, self.get_operation(), memarg);
+    }
+
     /// Get the type of the value.
     pub fn get_value_type(&self, ctx: &Context) -> MemAccessOpValueType {
         let op = self.get_operation().deref(ctx);
@@ -1438,12 +4247,9 @@ impl LoadOp {
 
 impl DisplayWithContext for LoadOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(
-            f,
-            "{} {}",
-            self.get_opid().with_ctx(ctx),
-            self.get_value_type(ctx)
-        )
+        let ty = self.get_value_type(ctx);
+        write!(f, "{} {}", self.get_opid().with_ctx(ctx), ty)?;
+        fmt_memarg(f, self.get_memarg(ctx), ty.width_bytes())
     }
 }
 
@@ -1484,12 +4290,7 @@ impl BrOp {
             .get(Self::ATTR_KEY_RELATIVE_DEPTH)
             .expect("no attribute found");
         #[allow(clippy::expect_used)]
-        let attr_val = apint_to_i32(
-            attr.downcast_ref::<IntegerAttr>()
-                .expect("expected IntegerAttr")
-                .clone()
-                .into(),
-        ) as u32;
+        let attr_val = to_u32_checked(ctx, attr).expect("relative depth should be u32");
         attr_val.into()
     }
 
@@ -1529,13 +4330,75 @@ impl Verify for BrOp {
                 msg: "Incorrect number of results or operands".to_string(),
             });
         }
+        let attr = op
+            .attributes
+            .get(Self::ATTR_KEY_RELATIVE_DEPTH)
+            .ok_or_else(|| CompilerError::VerificationError {
+                msg: "Missing relative depth attribute".to_string(),
+            })?;
+        to_u32_checked(ctx, attr).map_err(|e| CompilerError::VerificationError {
+            msg: format!("Invalid relative depth: {e}"),
+        })?;
         Ok(())
     }
 }
 
+/// The condition a [BrIfOp] checks on the popped value
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum BrIfCondition {
+    /// i32 value is not zero (plain Wasm `br_if`)
+    #[default]
+    I32NonZero,
+    /// i32 value is zero (`i32.eqz; br_if`)
+    I32Zero,
+    /// i64 value is not zero (`i64.eqz; i32.eqz; br_if`)
+    I64NonZero,
+    /// i64 value is zero (`i64.eqz; br_if`)
+    I64Zero,
+}
+
+impl BrIfCondition {
+    /// The condition that is true when this one is false (on the same value type)
+    pub fn inverted(self) -> Self {
+        match self {
+            BrIfCondition::I32NonZero => BrIfCondition::I32Zero,
+            BrIfCondition::I32Zero => BrIfCondition::I32NonZero,
+            BrIfCondition::I64NonZero => BrIfCondition::I64Zero,
+            BrIfCondition::I64Zero => BrIfCondition::I64NonZero,
+        }
+    }
+
+    /// Returns true if the condition checks an i64 value
+    pub fn is_i64(self) -> bool {
+        matches!(self, BrIfCondition::I64NonZero | BrIfCondition::I64Zero)
+    }
+
+    fn mnemonic(self) -> &'static str {
+        match self {
+            BrIfCondition::I32NonZero => "i32.nez",
+            BrIfCondition::I32Zero => "i32.eqz",
+            BrIfCondition::I64NonZero => "i64.nez",
+            BrIfCondition::I64Zero => "i64.eqz",
+        }
+    }
+
+    fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        [
+            BrIfCondition::I32NonZero,
+            BrIfCondition::I32Zero,
+            BrIfCondition::I64NonZero,
+            BrIfCondition::I64Zero,
+        ]
+        .into_iter()
+        .find(|cond| cond.mnemonic() == mnemonic)
+    }
+}
+
 declare_op!(
     /// Conditional branch op.
     /// Pop the value from the stack and if its true - transfers control to the end of outer block relative_depth levels up.
+    /// The check can be changed from the default non-zero i32 with [BrIfCondition] (e.g. after
+    /// fusing with the preceding `eqz` ops).
     ///
     BrIfOp,
     "br_if",
@@ -1544,6 +4407,34 @@ declare_op!(
 
 impl BrIfOp {
     const ATTR_KEY_RELATIVE_DEPTH: &str = "br_if.relative_depth";
+    const ATTR_KEY_CONDITION: &str = "br_if.condition";
+
+    /// Get the branch condition ([BrIfCondition::I32NonZero] if not set)
+    pub fn get_condition(&self, ctx: &Context) -> BrIfCondition {
+        let op = self.get_operation().deref(ctx);
+        let Some(attr) = op.attributes.get(Self::ATTR_KEY_CONDITION) else {
+            return BrIfCondition::default();
+        };
+        let mnemonic = String::from(
+            attr.downcast_ref::<StringAttr>()
+                .expect("BrIfOp condition attribute is not a StringAttr")
+                .clone(),
+        );
+        BrIfCondition::from_mnemonic(&mnemonic).expect("unknown BrIfOp condition")
+    }
+
+    /// Set the branch condition
+    pub fn set_condition(&self, ctx: &mut Context, condition: BrIfCondition) {
+        let mut op = self.get_operation().deref_mut(ctx);
+        if condition == BrIfCondition::default() {
+            op.attributes.remove(Self::ATTR_KEY_CONDITION);
+        } else {
+            op.attributes.insert(
+                Self::ATTR_KEY_CONDITION,
+                StringAttr::create(condition.mnemonic().to_string()),
+            );
+        }
+    }
 
     /// Get the function index
     pub fn get_relative_depth(&self, ctx: &Context) -> RelativeDepth {
@@ -1554,12 +4445,7 @@ impl BrIfOp {
             .get(Self::ATTR_KEY_RELATIVE_DEPTH)
             .expect("no attribute found");
         #[allow(clippy::expect_used)]
-        let attr_val = apint_to_i32(
-            attr.downcast_ref::<IntegerAttr>()
-                .expect("expected IntegerAttr")
-                .clone()
-                .into(),
-        ) as u32;
+        let attr_val = to_u32_checked(ctx, attr).expect("relative depth should be u32");
         attr_val.into()
     }
 
@@ -1582,7 +4468,12 @@ impl DisplayWithContext for BrIfOp {
             "{} {}",
             self.get_opid().with_ctx(ctx),
             self.get_relative_depth(ctx)
-        )
+        )?;
+        let condition = self.get_condition(ctx);
+        if condition != BrIfCondition::default() {
+            write!(f, " if {}", condition.mnemonic())?;
+        }
+        Ok(())
     }
 }
 
@@ -1599,34 +4490,115 @@ impl Verify for BrIfOp {
                 msg: "Incorrect number of results or operands".to_string(),
             });
         }
+        let attr = op
+            .attributes
+            .get(Self::ATTR_KEY_RELATIVE_DEPTH)
+            .ok_or_else(|| CompilerError::VerificationError {
+                msg: "Missing relative depth attribute".to_string(),
+            })?;
+        to_u32_checked(ctx, attr).map_err(|e| CompilerError::VerificationError {
+            msg: format!("Invalid relative depth: {e}"),
+        })?;
         Ok(())
     }
 }
 
 declare_op!(
-    /// Pops the i32 value from the stack and if its zero pushes 1 otherwise pushes 0 to the stack.
+    /// Branch table op.
+    /// Pop the i32 index from the stack and transfer control to the end of outer block
+    /// `targets[index]` levels up, or `default` levels up if the index is out of range.
     ///
-    I32EqzOp,
-    "i32.eqz",
+    BrTableOp,
+    "br_table",
     "wasm"
 );
 
-impl I32EqzOp {
-    /// Create a new [I32Eqz]. The underlying [Operation] is not linked to a
+impl BrTableOp {
+    const ATTR_KEY_TARGETS: &str = "br_table.targets";
+    const ATTR_KEY_DEFAULT: &str = "br_table.default";
+
+    /// Get the relative depths of the targets (indexed by the popped value)
+    pub fn get_targets(&self, ctx: &Context) -> Vec<RelativeDepth> {
+        let op = self.get_operation().deref(ctx);
+        #[allow(clippy::expect_used)]
+        let attr = op
+            .attributes
+            .get(Self::ATTR_KEY_TARGETS)
+            .expect("no attribute found");
+        #[allow(clippy::expect_used)]
+        let vec_attr = attr.downcast_ref::<VecAttr>().expect("VecAttr expected");
+        vec_attr
+            .0
+            .iter()
+            .map(|target| {
+                #[allow(clippy::expect_used)]
+                to_u32_checked(ctx, target)
+                    .expect("relative depth should be u32")
+                    .into()
+            })
+            .collect()
+    }
+
+    /// Get the relative depth of the default target
+    pub fn get_default(&self, ctx: &Context) -> RelativeDepth {
+        let op = self.get_operation().deref(ctx);
+        #[allow(clippy::expect_used)]
+        let attr = op
+            .attributes
+            .get(Self::ATTR_KEY_DEFAULT)
+            .expect("no attribute found");
+        #[allow(clippy::expect_used)]
+        let attr_val = to_u32_checked(ctx, attr).expect("relative depth should be u32");
+        attr_val.into()
+    }
+
+    /// Create a new [BrTableOp]. The underlying [Operation] is not linked to a
     /// [BasicBlock](crate::basic_block::BasicBlock).
-    pub fn new_unlinked(ctx: &mut Context) -> ConstantOp {
+    pub fn new_unlinked(
+        ctx: &mut Context,
+        targets: Vec<RelativeDepth>,
+        default: RelativeDepth,
+    ) -> BrTableOp {
         let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
-        ConstantOp { op }
+        let targets_attr = VecAttr::create(
+            targets
+                .into_iter()
+                .map(|target| u32_attr(ctx, target.into()))
+                .collect(),
+        );
+        let default_attr = u32_attr(ctx, default.into());
+        {
+            let opref = &mut *op.deref_mut(ctx);
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_TARGETS, targets_attr);
+            opref
+                .attributes
+                .insert(Self::ATTR_KEY_DEFAULT, default_attr);
+        }
+        BrTableOp { op }
     }
 }
 
-impl DisplayWithContext for I32EqzOp {
+impl DisplayWithContext for BrTableOp {
     fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.get_opid().with_ctx(ctx),)
+        let targets = self
+            .get_targets(ctx)
+            .iter()
+            .map(|target| target.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        write!(
+            f,
+            "{} [{}] {}",
+            self.get_opid().with_ctx(ctx),
+            targets,
+            self.get_default(ctx)
+        )
     }
 }
 
-impl Verify for I32EqzOp {
+impl Verify for BrTableOp {
     fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
         let op = &*self.get_operation().deref(ctx);
         if op.get_opid() != Self::get_opid_static() {
@@ -1639,27 +4611,465 @@ impl Verify for I32EqzOp {
                 msg: "Incorrect number of results or operands".to_string(),
             });
         }
+        let targets = op
+            .attributes
+            .get(Self::ATTR_KEY_TARGETS)
+            .and_then(|attr| attr.downcast_ref::<VecAttr>())
+            .ok_or_else(|| CompilerError::VerificationError {
+                msg: "Missing targets attribute".to_string(),
+            })?;
+        for target in &targets.0 {
+            to_u32_checked(ctx, target).map_err(|e| CompilerError::VerificationError {
+                msg: format!("Invalid target relative depth: {e}"),
+            })?;
+        }
+        let default = op.attributes.get(Self::ATTR_KEY_DEFAULT).ok_or_else(|| {
+            CompilerError::VerificationError {
+                msg: "Missing default relative depth attribute".to_string(),
+            }
+        })?;
+        to_u32_checked(ctx, default).map_err(|e| CompilerError::VerificationError {
+            msg: format!("Invalid default relative depth: {e}"),
+        })?;
         Ok(())
     }
 }
 
+/// Declares an op of the test/comparison family (`eqz`, `eq`, `lt_s`, etc.).
+/// Such ops have no attributes, pop their operands from the stack and push the i32 result
+/// (1 if the condition holds, 0 otherwise).
+macro_rules! declare_cmp_op {
+    ($(#[$outer:meta])* $op:ident, $op_name:literal) => {
+        declare_op!(
+            $(#[$outer])*
+            $op,
+            $op_name,
+            "wasm"
+        );
+
+        impl $op {
+            /// Create a new op. The underlying [Operation] is not linked to a
+            /// [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context) -> $op {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                $op { op }
+            }
+        }
+
+        impl DisplayWithContext for $op {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", self.get_opid().with_ctx(ctx),)
+            }
+        }
+
+        impl Verify for $op {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                let op = &*self.get_operation().deref(ctx);
+                if op.get_opid() != Self::get_opid_static() {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect OpId".to_string(),
+                    });
+                }
+                if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect number of results or operands".to_string(),
+                    });
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+declare_cmp_op!(
+    /// Pops the i32 value from the stack and if its zero pushes 1 otherwise pushes 0 to the stack.
+    I32EqzOp,
+    "i32.eqz"
+);
+declare_cmp_op!(
+    /// Pops two i32 values and pushes 1 if they are equal, 0 otherwise.
+    I32EqOp,
+    "i32.eq"
+);
+declare_cmp_op!(
+    /// Pops two i32 values and pushes 1 if they are not equal, 0 otherwise.
+    I32NeOp,
+    "i32.ne"
+);
+declare_cmp_op!(
+    /// Pops two i32 values and pushes 1 if the first is less than the second (signed).
+    I32LtSOp,
+    "i32.lt_s"
+);
+declare_cmp_op!(
+    /// Pops two i32 values and pushes 1 if the first is less than the second (unsigned).
+    I32LtUOp,
+    "i32.lt_u"
+);
+declare_cmp_op!(
+    /// Pops two i32 values and pushes 1 if the first is greater than the second (signed).
+    I32GtSOp,
+    "i32.gt_s"
+);
+declare_cmp_op!(
+    /// Pops two i32 values and pushes 1 if the first is greater than the second (unsigned).
+    I32GtUOp,
+    "i32.gt_u"
+);
+declare_cmp_op!(
+    /// Pops two i32 values and pushes 1 if the first is less than or equal to the second (signed).
+    I32LeSOp,
+    "i32.le_s"
+);
+declare_cmp_op!(
+    /// Pops two i32 values and pushes 1 if the first is less than or equal to the second (unsigned).
+    I32LeUOp,
+    "i32.le_u"
+);
+declare_cmp_op!(
+    /// Pops two i32 values and pushes 1 if the first is greater than or equal to the second (signed).
+    I32GeSOp,
+    "i32.ge_s"
+);
+declare_cmp_op!(
+    /// Pops two i32 values and pushes 1 if the first is greater than or equal to the second (unsigned).
+    I32GeUOp,
+    "i32.ge_u"
+);
+declare_cmp_op!(
+    /// Pops the i64 value from the stack and if its zero pushes 1 otherwise pushes 0 to the stack.
+    I64EqzOp,
+    "i64.eqz"
+);
+declare_cmp_op!(
+    /// Pops two i64 values and pushes 1 if they are equal, 0 otherwise.
+    I64EqOp,
+    "i64.eq"
+);
+declare_cmp_op!(
+    /// Pops two i64 values and pushes 1 if they are not equal, 0 otherwise.
+    I64NeOp,
+    "i64.ne"
+);
+declare_cmp_op!(
+    /// Pops two i64 values and pushes 1 if the first is less than the second (signed).
+    I64LtSOp,
+    "i64.lt_s"
+);
+declare_cmp_op!(
+    /// Pops two i64 values and pushes 1 if the first is less than the second (unsigned).
+    I64LtUOp,
+    "i64.lt_u"
+);
+declare_cmp_op!(
+    /// Pops two i64 values and pushes 1 if the first is greater than the second (signed).
+    I64GtSOp,
+    "i64.gt_s"
+);
+declare_cmp_op!(
+    /// Pops two i64 values and pushes 1 if the first is greater than the second (unsigned).
+    I64GtUOp,
+    "i64.gt_u"
+);
+declare_cmp_op!(
+    /// Pops two i64 values and pushes 1 if the first is less than or equal to the second (signed).
+    I64LeSOp,
+    "i64.le_s"
+);
+declare_cmp_op!(
+    /// Pops two i64 values and pushes 1 if the first is less than or equal to the second (unsigned).
+    I64LeUOp,
+    "i64.le_u"
+);
+declare_cmp_op!(
+    /// Pops two i64 values and pushes 1 if the first is greater than or equal to the second (signed).
+    I64GeSOp,
+    "i64.ge_s"
+);
+declare_cmp_op!(
+    /// Pops two i64 values and pushes 1 if the first is greater than or equal to the second (unsigned).
+    I64GeUOp,
+    "i64.ge_u"
+);
+
+/// Declares a sign-extension op (`extend8_s`, `extend16_s`, `extend32_s`). Such ops have no
+/// attributes, pop the value from the stack and push its low bits sign-extended to the full width.
+macro_rules! declare_sign_ext_op {
+    ($(#[$outer:meta])* $op:ident, $op_name:literal) => {
+        // same shape as the comparison ops
+        declare_cmp_op!($(#[$outer])* $op, $op_name);
+    };
+}
+
+declare_sign_ext_op!(
+    /// Pops the i32 value and pushes its low 8 bits sign-extended to i32.
+    I32Extend8SOp,
+    "i32.extend8_s"
+);
+declare_sign_ext_op!(
+    /// Pops the i32 value and pushes its low 16 bits sign-extended to i32.
+    I32Extend16SOp,
+    "i32.extend16_s"
+);
+declare_sign_ext_op!(
+    /// Pops the i64 value and pushes its low 8 bits sign-extended to i64.
+    I64Extend8SOp,
+    "i64.extend8_s"
+);
+declare_sign_ext_op!(
+    /// Pops the i64 value and pushes its low 16 bits sign-extended to i64.
+    I64Extend16SOp,
+    "i64.extend16_s"
+);
+declare_sign_ext_op!(
+    /// Pops the i64 value and pushes its low 32 bits sign-extended to i64.
+    I64Extend32SOp,
+    "i64.extend32_s"
+);
+
+/// Declares a bit-counting op (`clz`, `ctz`, `popcnt`). Such ops have no attributes, pop the
+/// value from the stack and push the count (of the same type).
+macro_rules! declare_bit_count_op {
+    ($(#[$outer:meta])* $op:ident, $op_name:literal) => {
+        // same shape as the comparison ops
+        declare_cmp_op!($(#[$outer])* $op, $op_name);
+    };
+}
+
+declare_bit_count_op!(
+    /// Pops the i32 value and pushes the number of its leading zero bits.
+    I32ClzOp,
+    "i32.clz"
+);
+declare_bit_count_op!(
+    /// Pops the i32 value and pushes the number of its trailing zero bits.
+    I32CtzOp,
+    "i32.ctz"
+);
+declare_bit_count_op!(
+    /// Pops the i32 value and pushes the number of its 1 bits.
+    I32PopcntOp,
+    "i32.popcnt"
+);
+declare_bit_count_op!(
+    /// Pops the i64 value and pushes the number of its leading zero bits.
+    I64ClzOp,
+    "i64.clz"
+);
+declare_bit_count_op!(
+    /// Pops the i64 value and pushes the number of its trailing zero bits.
+    I64CtzOp,
+    "i64.ctz"
+);
+declare_bit_count_op!(
+    /// Pops the i64 value and pushes the number of its 1 bits.
+    I64PopcntOp,
+    "i64.popcnt"
+);
+
+/// Declares a partial-width load op (`load8_s`, `load16_u`, etc.) of `$width` bytes.
+/// Such ops have the memarg attributes (see [LoadOp]), pop the i32 address from the stack and
+/// push the narrow value loaded from it extended to the full width.
+macro_rules! declare_partial_load_op {
+    ($(#[$outer:meta])* $op:ident, $op_name:literal, $width:literal) => {
+        declare_op!(
+            $(#[$outer])*
+            $op,
+            $op_name,
+            "wasm"
+        );
+
+        impl $op {
+            /// Number of bytes loaded from the memory
+            pub const WIDTH_BYTES: u32 = $width;
+
+            /// Create a new op without an offset and with the natural alignment. The underlying
+            /// [Operation] is not linked to a [BasicBlock](crate::basic_block::BasicBlock).
+            pub fn new_unlinked(ctx: &mut Context) -> $op {
+                let op = Operation::new(ctx, Self::get_opid_static(), vec![], vec![], 0);
+                $op { op }
+            }
+
+            /// Create a new op with the given static offset and alignment.
+            pub fn new_unlinked_with_memarg(ctx: &mut Context, memarg: MemArg) -> $op {
+                let op = Self::new_unlinked(ctx);
+                set_memarg(ctx, op.get_operation(), memarg);
+                op
+            }
+
+            /// Get the static offset, alignment and memory.
+            pub fn get_memarg(&self, ctx: &Context) -> MemArg {
+                get_memarg(ctx, self.get_operation(), $width)
+            }
+
+            /// Replace the static offset, alignment and memory.
+            pub fn set_memarg(&self, ctx: &mut Context, memarg: MemArg) {
+                set_memarg(ctx, self.get_operation(), memarg);
+            }
+        }
+
+        impl DisplayWithContext for $op {
+            fn fmt(&self, ctx: &Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", self.get_opid().with_ctx(ctx))?;
+                fmt_memarg(f, self.get_memarg(ctx), $width)
+            }
+        }
+
+        impl Verify for $op {
+            fn verify(&self, ctx: &Context) -> Result<(), CompilerError> {
+                let op = &*self.get_operation().deref(ctx);
+                if op.get_opid() != Self::get_opid_static() {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect OpId".to_string(),
+                    });
+                }
+                if op.get_num_results() != 0 || op.get_num_operands() != 0 {
+                    return Err(CompilerError::VerificationError {
+                        msg: "Incorrect number of results or operands".to_string(),
+                    });
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+declare_partial_load_op!(
+    /// Pops the i32 address and pushes the byte at it sign-extended to i32.
+    I32Load8SOp,
+    "i32.load8_s",
+    1
+);
+declare_partial_load_op!(
+    /// Pops the i32 address and pushes the byte at it zero-extended to i32.
+    I32Load8UOp,
+    "i32.load8_u",
+    1
+);
+declare_partial_load_op!(
+    /// Pops the i32 address and pushes the 16 bits at it sign-extended to i32.
+    I32Load16SOp,
+    "i32.load16_s",
+    2
+);
+declare_partial_load_op!(
+    /// Pops the i32 address and pushes the 16 bits at it zero-extended to i32.
+    I32Load16UOp,
+    "i32.load16_u",
+    2
+);
+declare_partial_load_op!(
+    /// Pops the i32 address and pushes the byte at it sign-extended to i64.
+    I64Load8SOp,
+    "i64.load8_s",
+    1
+);
+declare_partial_load_op!(
+    /// Pops the i32 address and pushes the byte at it zero-extended to i64.
+    I64Load8UOp,
+    "i64.load8_u",
+    1
+);
+declare_partial_load_op!(
+    /// Pops the i32 address and pushes the 16 bits at it sign-extended to i64.
+    I64Load16SOp,
+    "i64.load16_s",
+    2
+);
+declare_partial_load_op!(
+    /// Pops the i32 address and pushes the 16 bits at it zero-extended to i64.
+    I64Load16UOp,
+    "i64.load16_u",
+    2
+);
+declare_partial_load_op!(
+    /// Pops the i32 address and pushes the 32 bits at it sign-extended to i64.
+    I64Load32SOp,
+    "i64.load32_s",
+    4
+);
+declare_partial_load_op!(
+    /// Pops the i32 address and pushes the 32 bits at it zero-extended to i64.
+    I64Load32UOp,
+    "i64.load32_u",
+    4
+);
+
 pub(crate) fn register(ctx: &mut Context, dialect: &mut Dialect) {
     ModuleOp::register(ctx, dialect);
     ConstantOp::register(ctx, dialect);
     FuncOp::register(ctx, dialect);
-    AddOp::register(ctx, dialect);
+    BinaryArithOp::register(ctx, dialect);
+    SelectOp::register(ctx, dialect);
     CallOp::register(ctx, dialect);
+    CallIndirectOp::register(ctx, dialect);
+    ReturnCallOp::register(ctx, dialect);
+    ReturnCallIndirectOp::register(ctx, dialect);
     ReturnOp::register(ctx, dialect);
+    UnreachableOp::register(ctx, dialect);
+    DropOp::register(ctx, dialect);
     BlockOp::register(ctx, dialect);
     LoopOp::register(ctx, dialect);
+    IfOp::register(ctx, dialect);
     LocalGetOp::register(ctx, dialect);
     LocalSetOp::register(ctx, dialect);
     LocalTeeOp::register(ctx, dialect);
     GlobalSetOp::register(ctx, dialect);
     GlobalGetOp::register(ctx, dialect);
+    MemorySizeOp::register(ctx, dialect);
+    MemoryGrowOp::register(ctx, dialect);
+    DataOp::register(ctx, dialect);
+    GlobalOp::register(ctx, dialect);
+    MemoryInitOp::register(ctx, dialect);
+    DataDropOp::register(ctx, dialect);
+    TableGetOp::register(ctx, dialect);
+    TableSetOp::register(ctx, dialect);
     StoreOp::register(ctx, dialect);
     LoadOp::register(ctx, dialect);
     BrOp::register(ctx, dialect);
     BrIfOp::register(ctx, dialect);
+    BrTableOp::register(ctx, dialect);
     I32EqzOp::register(ctx, dialect);
+    I32EqOp::register(ctx, dialect);
+    I32NeOp::register(ctx, dialect);
+    I32LtSOp::register(ctx, dialect);
+    I32LtUOp::register(ctx, dialect);
+    I32GtSOp::register(ctx, dialect);
+    I32GtUOp::register(ctx, dialect);
+    I32LeSOp::register(ctx, dialect);
+    I32LeUOp::register(ctx, dialect);
+    I32GeSOp::register(ctx, dialect);
+    I32GeUOp::register(ctx, dialect);
+    I64EqzOp::register(ctx, dialect);
+    I64EqOp::register(ctx, dialect);
+    I64NeOp::register(ctx, dialect);
+    I64LtSOp::register(ctx, dialect);
+    I64LtUOp::register(ctx, dialect);
+    I64GtSOp::register(ctx, dialect);
+    I64GtUOp::register(ctx, dialect);
+    I64LeSOp::register(ctx, dialect);
+    I64LeUOp::register(ctx, dialect);
+    I64GeSOp::register(ctx, dialect);
+    I64GeUOp::register(ctx, dialect);
+    I32Extend8SOp::register(ctx, dialect);
+    I32Extend16SOp::register(ctx, dialect);
+    I64Extend8SOp::register(ctx, dialect);
+    I64Extend16SOp::register(ctx, dialect);
+    I64Extend32SOp::register(ctx, dialect);
+    I32ClzOp::register(ctx, dialect);
+    I32CtzOp::register(ctx, dialect);
+    I32PopcntOp::register(ctx, dialect);
+    I64ClzOp::register(ctx, dialect);
+    I64CtzOp::register(ctx, dialect);
+    I64PopcntOp::register(ctx, dialect);
+    I32Load8SOp::register(ctx, dialect);
+    I32Load8UOp::register(ctx, dialect);
+    I32Load16SOp::register(ctx, dialect);
+    I32Load16UOp::register(ctx, dialect);
+    I64Load8SOp::register(ctx, dialect);
+    I64Load8UOp::register(ctx, dialect);
+    I64Load16SOp::register(ctx, dialect);
+    I64Load16UOp::register(ctx, dialect);
+    I64Load32SOp::register(ctx, dialect);
+    I64Load32UOp::register(ctx, dialect);
 }
@@ -214,6 +214,152 @@ impl From<wasmparser::TableType> for Table {
     }
 }
 
+/// An active element segment, placing the functions into a table at the given offset
+/// when the module is instantiated.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct ElemSegment {
+    /// The table the functions are placed into.
+    pub table_index: TableIndex,
+    /// The position of the first function in the table.
+    pub offset: u32,
+    /// The functions placed into the table.
+    pub func_indices: Vec<FuncIndex>,
+}
+
+/// An imported global, provided by the host. The imported globals come first in the global
+/// index space, followed by the globals defined in the module.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ImportGlobal {
+    /// The import module name
+    pub module: String,
+    /// The global name
+    pub name: String,
+    /// The value type
+    pub ty: Ptr<TypeObj>,
+    /// Whether the value may change at runtime
+    pub mutable: bool,
+}
+
+/// Stage of a prologue function, a function called before the body of the start function
+/// (see `ModuleOp::add_prologue_function`). The prologue functions are called in the order of
+/// their stages and, within a stage, in the order they were added.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Display)]
+pub enum PrologueStage {
+    /// Initialization of the linear memory (data segments)
+    MemoryInit,
+    /// Initialization of the globals, may read the initialized memory
+    GlobalsInit,
+    /// Any other initialization, may use the initialized memory and globals
+    User,
+}
+
+impl PrologueStage {
+    /// All stages in the order they are called in
+    pub const ALL: [PrologueStage; 3] = [
+        PrologueStage::MemoryInit,
+        PrologueStage::GlobalsInit,
+        PrologueStage::User,
+    ];
+
+    /// Position of the stage in [PrologueStage::ALL]
+    pub fn index(self) -> u32 {
+        self as u32
+    }
+
+    /// Stage at the given position in [PrologueStage::ALL]
+    pub fn from_index(index: u32) -> Option<PrologueStage> {
+        PrologueStage::ALL.get(index as usize).copied()
+    }
+}
+
+/// Operation of a binary integer instruction (see `BinaryArithOp`). The same opcodes are
+/// shared by i32 and i64, the operand type is kept separately.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum BinaryOpcode {
+    /// Wrapping addition
+    Add,
+    /// Wrapping subtraction
+    Sub,
+    /// Wrapping multiplication
+    Mul,
+    /// Signed division, truncated toward zero
+    DivS,
+    /// Unsigned division
+    DivU,
+    /// Signed remainder, has the sign of the dividend
+    RemS,
+    /// Unsigned remainder
+    RemU,
+    /// Bitwise and
+    And,
+    /// Bitwise or
+    Or,
+    /// Bitwise exclusive or
+    Xor,
+    /// Shift left
+    Shl,
+    /// Shift right, sign-extending
+    ShrS,
+    /// Shift right, zero-filling
+    ShrU,
+    /// Rotate left
+    Rotl,
+    /// Rotate right
+    Rotr,
+}
+
+impl BinaryOpcode {
+    /// The instruction name without the type prefix, e.g. `div_s` for `i32.div_s`
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            BinaryOpcode::Add => "add",
+            BinaryOpcode::Sub => "sub",
+            BinaryOpcode::Mul => "mul",
+            BinaryOpcode::DivS => "div_s",
+            BinaryOpcode::DivU => "div_u",
+            BinaryOpcode::RemS => "rem_s",
+            BinaryOpcode::RemU => "rem_u",
+            BinaryOpcode::And => "and",
+            BinaryOpcode::Or => "or",
+            BinaryOpcode::Xor => "xor",
+            BinaryOpcode::Shl => "shl",
+            BinaryOpcode::ShrS => "shr_s",
+            BinaryOpcode::ShrU => "shr_u",
+            BinaryOpcode::Rotl => "rotl",
+            BinaryOpcode::Rotr => "rotr",
+        }
+    }
+
+    /// Whether the op traps on zero divisor (`div_s` also traps on overflow)
+    pub fn is_division(self) -> bool {
+        matches!(
+            self,
+            BinaryOpcode::DivS | BinaryOpcode::DivU | BinaryOpcode::RemS | BinaryOpcode::RemU
+        )
+    }
+
+    /// Whether the op is `and`, `or` or `xor`
+    pub fn is_bitwise(self) -> bool {
+        matches!(
+            self,
+            BinaryOpcode::And | BinaryOpcode::Or | BinaryOpcode::Xor
+        )
+    }
+
+    /// Whether the op is a shift or a rotation. The shift amount (top of the stack) is taken
+    /// modulo the bit width.
+    pub fn is_shift(self) -> bool {
+        matches!(
+            self,
+            BinaryOpcode::Shl
+                | BinaryOpcode::ShrS
+                | BinaryOpcode::ShrU
+                | BinaryOpcode::Rotl
+                | BinaryOpcode::Rotr
+        )
+    }
+}
+
 /// WebAssembly linear memory.
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 pub struct Memory {
@@ -238,6 +384,65 @@ impl From<wasmparser::MemoryType> for Memory {
     }
 }
 
+/// Size of a linear memory page in bytes
+pub const WASM_PAGE_SIZE: u32 = 0x10000;
+
+/// Maximum number of pages of a 32-bit linear memory (4 GiB)
+pub const MAX_WASM32_PAGES: u32 = 0x10000;
+
+/// Limits of a 32-bit linear memory in pages ([WASM_PAGE_SIZE])
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct MemoryLimits {
+    /// The initial number of pages
+    pub minimum: u32,
+    /// The maximum number of pages the memory can grow to, if declared
+    pub maximum: Option<u32>,
+}
+
+impl MemoryLimits {
+    /// The maximum number of pages, [MAX_WASM32_PAGES] if not declared
+    pub fn maximum_or_default(&self) -> u32 {
+        self.maximum.unwrap_or(MAX_WASM32_PAGES)
+    }
+}
+
+/// Static immediates of a memory access (`memarg`)
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct MemArg {
+    /// Added to the address popped from the stack
+    pub offset: u32,
+    /// The alignment hint as the log2 of the number of bytes
+    pub align: u32,
+    /// The accessed memory, other than 0 only with the multi-memory proposal
+    pub memory: MemoryIndex,
+}
+
+impl MemArg {
+    /// No offset and the natural alignment of an access of `width_bytes` bytes
+    pub fn natural(width_bytes: u32) -> Self {
+        Self {
+            offset: 0,
+            align: width_bytes.trailing_zeros(),
+            memory: MemoryIndex::from(0),
+        }
+    }
+
+    /// The immediates in the WAT format (`1 offset=4 align=1`), the default ones are omitted
+    pub fn to_wat(&self, width_bytes: u32) -> String {
+        let mut parts = Vec::new();
+        if u32::from(self.memory) != 0 {
+            parts.push(self.memory.to_string());
+        }
+        if self.offset != 0 {
+            parts.push(format!("offset={}", self.offset));
+        }
+        if self.align != width_bytes.trailing_zeros() {
+            parts.push(format!("align={}", 1u64 << self.align));
+        }
+        parts.join(" ")
+    }
+}
+
 /// WebAssembly event.
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 pub struct Tag {
@@ -276,7 +481,8 @@ pub fn from_val_type(ctx: &mut Context, val_type: &ValType) -> Ptr<TypeObj> {
         ValType::F32 => unimplemented!("no support for floating types"),
         ValType::F64 => unimplemented!("no support for floating types"),
         ValType::V128 => todo!(),
-        ValType::Ref(_) => todo!(),
+        // a function reference is an i32 key (0 is null), see the lowering of the tables
+        ValType::Ref(_) => i32_type(ctx),
     }
 }
 
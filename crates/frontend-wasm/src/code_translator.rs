@@ -1,6 +1,9 @@
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::types as wasm_types;
 use pliron::context::Context;
-use wasmparser::{FuncValidator, Operator, WasmModuleResources};
+use wasmparser::{FuncValidator, MemArg, Operator, WasmModuleResources};
 
+use crate::coverage::operator_name;
 use crate::{func_builder::FuncBuilder, mod_builder::ModuleBuilder, WasmError};
 
 /// Translates wasm operators into ozk IR instructions.
@@ -13,49 +16,215 @@ pub fn translate_operator(
     mod_builder: &mut ModuleBuilder,
 ) -> Result<(), WasmError> {
     match op {
-        Operator::Unreachable => {
-            func_builder.op().unreachable(ctx);
-        }
-        Operator::Nop => {
-            func_builder.op().nop(ctx);
-        }
         Operator::End => func_builder.op().end(ctx)?,
         Operator::Return => func_builder.op().ret(ctx)?,
+        Operator::Unreachable => func_builder.op().unreachable(ctx)?,
         Operator::Call { function_index } => {
             func_builder.op().call(ctx, *function_index)?;
         }
+        Operator::CallIndirect {
+            type_index,
+            table_index,
+            ..
+        } => {
+            let func_type = mod_builder.get_type((*type_index).into())?;
+            func_builder
+                .op()
+                .call_indirect(ctx, *type_index, *table_index, func_type)?;
+        }
+        Operator::ReturnCall { function_index } => {
+            func_builder.op().return_call(ctx, *function_index)?;
+        }
+        Operator::ReturnCallIndirect {
+            type_index,
+            table_index,
+        } => {
+            let func_type = mod_builder.get_type((*type_index).into())?;
+            func_builder
+                .op()
+                .return_call_indirect(ctx, *type_index, *table_index, func_type)?;
+        }
         Operator::Loop { blockty } => {
             func_builder.op().bloop(ctx, blockty)?;
         }
         Operator::Block { blockty } => {
             func_builder.op().block(ctx, blockty)?;
         }
+        Operator::If { blockty } => {
+            func_builder.op().bif(ctx, blockty)?;
+        }
+        Operator::Else => func_builder.op().belse(ctx)?,
         Operator::BrIf { relative_depth } => {
             func_builder.op().br_if(ctx, *relative_depth)?;
         }
         Operator::Br { relative_depth } => {
             func_builder.op().br(ctx, *relative_depth)?;
         }
+        Operator::BrTable { targets } => {
+            let relative_depths = targets.targets().collect::<Result<Vec<u32>, _>>()?;
+            func_builder
+                .op()
+                .br_table(ctx, relative_depths, targets.default())?;
+        }
         Operator::GlobalSet { global_index } => func_builder.op().global_set(ctx, *global_index)?,
         Operator::GlobalGet { global_index } => func_builder.op().global_get(ctx, *global_index)?,
+        Operator::MemorySize { mem, .. } => func_builder.op().memory_size(ctx, *mem)?,
+        Operator::MemoryGrow { mem, .. } => func_builder.op().memory_grow(ctx, *mem)?,
+        Operator::MemoryInit { data_index, mem } => {
+            func_builder.op().memory_init(ctx, *mem, *data_index)?
+        }
+        Operator::DataDrop { data_index } => func_builder.op().data_drop(ctx, *data_index)?,
+        Operator::TableGet { table } => func_builder.op().table_get(ctx, *table)?,
+        Operator::TableSet { table } => func_builder.op().table_set(ctx, *table)?,
+        Operator::I32Load { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder
+                .op()
+                .load(ctx, MemAccessOpValueType::I32, memarg)?
+        }
+        Operator::I64Load { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder
+                .op()
+                .load(ctx, MemAccessOpValueType::I64, memarg)?
+        }
+        Operator::I32Store { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder
+                .op()
+                .store(ctx, MemAccessOpValueType::I32, memarg)?
+        }
+        Operator::I64Store { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder
+                .op()
+                .store(ctx, MemAccessOpValueType::I64, memarg)?
+        }
+        Operator::I32Load8S { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder.op().i32load8s(ctx, memarg)?
+        }
+        Operator::I32Load8U { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder.op().i32load8u(ctx, memarg)?
+        }
+        Operator::I32Load16S { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder.op().i32load16s(ctx, memarg)?
+        }
+        Operator::I32Load16U { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder.op().i32load16u(ctx, memarg)?
+        }
+        Operator::I64Load8S { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder.op().i64load8s(ctx, memarg)?
+        }
+        Operator::I64Load8U { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder.op().i64load8u(ctx, memarg)?
+        }
+        Operator::I64Load16S { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder.op().i64load16s(ctx, memarg)?
+        }
+        Operator::I64Load16U { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder.op().i64load16u(ctx, memarg)?
+        }
+        Operator::I64Load32S { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder.op().i64load32s(ctx, memarg)?
+        }
+        Operator::I64Load32U { memarg } => {
+            let memarg = translate_memarg(memarg)?;
+            func_builder.op().i64load32u(ctx, memarg)?
+        }
         Operator::LocalGet { local_index } => func_builder.op().local_get(ctx, *local_index)?,
         Operator::LocalTee { local_index } => func_builder.op().local_tee(ctx, *local_index)?,
         Operator::LocalSet { local_index } => func_builder.op().local_set(ctx, *local_index)?,
         Operator::I32Const { value } => func_builder.op().i32const(ctx, *value)?,
         Operator::I64Const { value } => func_builder.op().i64const(ctx, *value)?,
         Operator::I32Add => func_builder.op().i32add(ctx)?,
+        Operator::I32Sub => func_builder.op().i32sub(ctx)?,
+        Operator::I32Mul => func_builder.op().i32mul(ctx)?,
+        Operator::Drop => func_builder.op().drop(ctx)?,
+        Operator::Select => func_builder.op().select(ctx)?,
+        Operator::TypedSelect { ty } => func_builder.op().typed_select(ctx, ty)?,
         Operator::I32Eqz => func_builder.op().i32eqz(ctx)?,
-        Operator::I32WrapI64 => func_builder.op().i32wrapi64(ctx),
-        Operator::I32GeU => func_builder.op().i32geu(ctx),
-        Operator::I32And => func_builder.op().i32and(ctx),
+        Operator::I32GeU => func_builder.op().i32geu(ctx)?,
+        Operator::I32Eq => func_builder.op().i32eq(ctx)?,
+        Operator::I32Ne => func_builder.op().i32ne(ctx)?,
+        Operator::I32LtS => func_builder.op().i32lts(ctx)?,
+        Operator::I32LtU => func_builder.op().i32ltu(ctx)?,
+        Operator::I32GtS => func_builder.op().i32gts(ctx)?,
+        Operator::I32GtU => func_builder.op().i32gtu(ctx)?,
+        Operator::I32LeS => func_builder.op().i32les(ctx)?,
+        Operator::I32LeU => func_builder.op().i32leu(ctx)?,
+        Operator::I32GeS => func_builder.op().i32ges(ctx)?,
+        Operator::I32DivS => func_builder.op().i32divs(ctx)?,
+        Operator::I32DivU => func_builder.op().i32divu(ctx)?,
+        Operator::I32RemS => func_builder.op().i32rems(ctx)?,
+        Operator::I32RemU => func_builder.op().i32remu(ctx)?,
+        Operator::I32And => func_builder.op().i32and(ctx)?,
+        Operator::I32Or => func_builder.op().i32or(ctx)?,
+        Operator::I32Xor => func_builder.op().i32xor(ctx)?,
+        Operator::I64And => func_builder.op().i64and(ctx)?,
+        Operator::I64Or => func_builder.op().i64or(ctx)?,
+        Operator::I64Xor => func_builder.op().i64xor(ctx)?,
+        Operator::I32Shl => func_builder.op().i32shl(ctx)?,
+        Operator::I32ShrS => func_builder.op().i32shrs(ctx)?,
+        Operator::I32ShrU => func_builder.op().i32shru(ctx)?,
+        Operator::I64Shl => func_builder.op().i64shl(ctx)?,
+        Operator::I64ShrS => func_builder.op().i64shrs(ctx)?,
+        Operator::I64ShrU => func_builder.op().i64shru(ctx)?,
+        Operator::I32Rotl => func_builder.op().i32rotl(ctx)?,
+        Operator::I32Rotr => func_builder.op().i32rotr(ctx)?,
+        Operator::I64Rotl => func_builder.op().i64rotl(ctx)?,
+        Operator::I64Rotr => func_builder.op().i64rotr(ctx)?,
+        Operator::I32Extend8S => func_builder.op().i32extend8s(ctx)?,
+        Operator::I32Extend16S => func_builder.op().i32extend16s(ctx)?,
+        Operator::I64Extend8S => func_builder.op().i64extend8s(ctx)?,
+        Operator::I64Extend16S => func_builder.op().i64extend16s(ctx)?,
+        Operator::I64Extend32S => func_builder.op().i64extend32s(ctx)?,
+        Operator::I32Clz => func_builder.op().i32clz(ctx)?,
+        Operator::I32Ctz => func_builder.op().i32ctz(ctx)?,
+        Operator::I32Popcnt => func_builder.op().i32popcnt(ctx)?,
+        Operator::I64Clz => func_builder.op().i64clz(ctx)?,
+        Operator::I64Ctz => func_builder.op().i64ctz(ctx)?,
+        Operator::I64Popcnt => func_builder.op().i64popcnt(ctx)?,
         Operator::I64Add => func_builder.op().i64add(ctx)?,
-        Operator::I64Eqz => func_builder.op().i64eqz(ctx),
-        Operator::I64And => func_builder.op().i64and(ctx),
-        Operator::I64GeU => func_builder.op().i64geu(ctx),
-        Operator::I64Ne => func_builder.op().i64ne(ctx),
-        Operator::I64Eq => func_builder.op().i64eq(ctx),
-        Operator::I64ExtendI32U => func_builder.op().i64extendi32u(ctx),
-        _ => todo!("Wasm op not implemented: {:?}", op),
+        Operator::I64Sub => func_builder.op().i64sub(ctx)?,
+        Operator::I64Mul => func_builder.op().i64mul(ctx)?,
+        Operator::I64DivS => func_builder.op().i64divs(ctx)?,
+        Operator::I64DivU => func_builder.op().i64divu(ctx)?,
+        Operator::I64RemS => func_builder.op().i64rems(ctx)?,
+        Operator::I64RemU => func_builder.op().i64remu(ctx)?,
+        Operator::I64Eqz => func_builder.op().i64eqz(ctx)?,
+        Operator::I64GeU => func_builder.op().i64geu(ctx)?,
+        Operator::I64Ne => func_builder.op().i64ne(ctx)?,
+        Operator::I64Eq => func_builder.op().i64eq(ctx)?,
+        Operator::I64LtS => func_builder.op().i64lts(ctx)?,
+        Operator::I64LtU => func_builder.op().i64ltu(ctx)?,
+        Operator::I64GtS => func_builder.op().i64gts(ctx)?,
+        Operator::I64GtU => func_builder.op().i64gtu(ctx)?,
+        Operator::I64LeS => func_builder.op().i64les(ctx)?,
+        Operator::I64LeU => func_builder.op().i64leu(ctx)?,
+        Operator::I64GeS => func_builder.op().i64ges(ctx)?,
+        _ => return Err(WasmError::UnsupportedOperator(operator_name(op))),
     };
     Ok(())
 }
+
+/// Only the accesses to the memory 0 are supported. The static offset is kept on the op and
+/// folded into the address arithmetic by the lowering.
+fn translate_memarg(memarg: &MemArg) -> Result<wasm_types::MemArg, WasmError> {
+    let offset = u32::try_from(memarg.offset).map_err(|_| {
+        WasmError::Unsupported(format!("memory access with the offset {}", memarg.offset))
+    })?;
+    Ok(wasm_types::MemArg {
+        offset,
+        align: memarg.align as u32,
+        memory: memarg.memory.into(),
+    })
+}
@@ -1,9 +1,69 @@
+use std::collections::HashMap;
+
 use pliron::context::Context;
 use pliron::dialects::builtin;
+use wasmparser::WasmFeatures;
+
+/// Module and name of an imported function
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImportFuncLabel {
+    /// Import module name
+    pub module: String,
+    /// Function name
+    pub name: String,
+}
+
+impl ImportFuncLabel {
+    /// Create a new label
+    pub fn new(module: &str, name: &str) -> Self {
+        Self {
+            module: module.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
 
 /// Translation(parsing) options for Wasm frontend
-#[derive(Default, Debug)]
-pub struct WasmFrontendConfig {}
+#[derive(Debug)]
+pub struct WasmFrontendConfig {
+    /// Skip the operators that are not supported by the frontend instead of failing
+    /// the translation. Skipped operators are collected in [`crate::UnsupportedOpsReport`]
+    /// returned by [`crate::parse_module_with_report`].
+    /// The resulting module is not executable if any operator was skipped.
+    pub skip_unsupported_ops: bool,
+    /// Import module names to rename, e.g. `c2zk` -> `env`, so that the imports match
+    /// the naming convention expected by the compiler without rebuilding the program.
+    pub import_module_renames: HashMap<String, String>,
+    /// Imported functions to remap to another module and name, e.g.
+    /// (`io`, `read_pub`) -> (`env`, `ozk_stdlib_pub_input`).
+    /// Takes precedence over `import_module_renames`.
+    pub import_func_remaps: HashMap<ImportFuncLabel, ImportFuncLabel>,
+    /// Accept the sign-extension operators (`i32.extend8_s`, `i64.extend32_s`, etc.)
+    /// emitted by default by the recent LLVM versions. On by default, as in the Wasm validator.
+    /// When off, a module using them fails the validation.
+    pub sign_extension: bool,
+    /// Accept the tail calls (`return_call`, `return_call_indirect`). When off, a module
+    /// using them fails the validation.
+    pub tail_call: bool,
+    /// Accept the multiple memories (the multi-memory proposal), e.g. to keep the public data
+    /// apart from the scratch space. The backends place the memories other than 0 in the target
+    /// memory (see `ozk_ir_transform::wasm::multi_memory`). When off, a module declaring more
+    /// than one memory fails the validation.
+    pub multi_memory: bool,
+}
+
+impl Default for WasmFrontendConfig {
+    fn default() -> Self {
+        Self {
+            skip_unsupported_ops: false,
+            import_module_renames: HashMap::new(),
+            import_func_remaps: HashMap::new(),
+            sign_extension: true,
+            tail_call: false,
+            multi_memory: false,
+        }
+    }
+}
 
 impl WasmFrontendConfig {
     /// Register dialects used in Wasm frontend
@@ -12,4 +72,26 @@ impl WasmFrontendConfig {
         ozk_ozk_dialect::register(ctx);
         builtin::register(ctx);
     }
+
+    /// Wasm features accepted by the validator
+    pub fn wasm_features(&self) -> WasmFeatures {
+        WasmFeatures {
+            sign_extension: self.sign_extension,
+            tail_call: self.tail_call,
+            multi_memory: self.multi_memory,
+            ..WasmFeatures::default()
+        }
+    }
+
+    /// Returns the module and name the imported function is translated as
+    pub fn resolve_import(&self, module: &str, name: &str) -> ImportFuncLabel {
+        let label = ImportFuncLabel::new(module, name);
+        if let Some(remapped) = self.import_func_remaps.get(&label) {
+            return remapped.clone();
+        }
+        match self.import_module_renames.get(module) {
+            Some(renamed_module) => ImportFuncLabel::new(renamed_module, name),
+            None => label,
+        }
+    }
 }
@@ -0,0 +1,88 @@
+//! Statistics of the Wasm operators that the frontend could not translate.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+use ozk_ozk_dialect::types::FuncSym;
+
+/// Occurrences of a single unsupported operator
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UnsupportedOpStats {
+    /// Number of occurrences in the module
+    pub count: usize,
+    /// Names of the functions containing the operator
+    pub funcs: BTreeSet<String>,
+}
+
+/// Unsupported operators found while translating a module (see
+/// [`crate::WasmFrontendConfig::skip_unsupported_ops`]).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UnsupportedOpsReport {
+    ops: BTreeMap<String, UnsupportedOpStats>,
+}
+
+impl UnsupportedOpsReport {
+    /// Record an occurrence of the unsupported operator in the function
+    pub fn record(&mut self, op_name: &str, func: &FuncSym) {
+        let stats = self.ops.entry(op_name.to_string()).or_default();
+        stats.count += 1;
+        stats.funcs.insert(func.as_ref().to_string());
+    }
+
+    /// Returns true if every operator was translated
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Stats for the given operator name (e.g. "I32Mul")
+    pub fn get(&self, op_name: &str) -> Option<&UnsupportedOpStats> {
+        self.ops.get(op_name)
+    }
+
+    /// Unsupported operators sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &UnsupportedOpStats)> {
+        self.ops.iter()
+    }
+
+    /// Total number of skipped operators
+    pub fn total_count(&self) -> usize {
+        self.ops.values().map(|stats| stats.count).sum()
+    }
+
+    /// Names of the functions that were not fully translated
+    pub fn incomplete_funcs(&self) -> BTreeSet<&String> {
+        self.ops
+            .values()
+            .flat_map(|stats| stats.funcs.iter())
+            .collect()
+    }
+}
+
+impl Display for UnsupportedOpsReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "all operators are supported");
+        }
+        writeln!(
+            f,
+            "{} unsupported operator(s) in {} function(s):",
+            self.total_count(),
+            self.incomplete_funcs().len()
+        )?;
+        for (op_name, stats) in self.iter() {
+            let funcs: Vec<&str> = stats.funcs.iter().map(String::as_str).collect();
+            writeln!(f, "  {op_name}: {} in {}", stats.count, funcs.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Operator name without its immediates, e.g. "I32Load" for `I32Load { memarg: .. }`
+pub(crate) fn operator_name(op: &wasmparser::Operator) -> String {
+    format!("{op:?}")
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
@@ -20,12 +20,25 @@ pub enum WasmError {
         offset: usize,
     },
 
+    /// The input is not a Wasm binary and can't be parsed as the WebAssembly text format (WAT).
+    #[error("Invalid input WebAssembly text: {0}")]
+    InvalidWat(String),
+
     /// A feature used by the WebAssembly code is not supported by the embedding environment.
     ///
     /// Embedding environments may have their own limitations and feature restrictions.
     #[error("Unsupported feature: {0}")]
     Unsupported(String),
 
+    /// A Wasm operator that the frontend cannot translate (yet).
+    #[error("Unsupported operator: {0}")]
+    UnsupportedOperator(String),
+
+    /// The module uses the floating point types or instructions, not supported by the targets
+    /// (there is no soft-float lowering). Lists every use (at most one per function).
+    #[error("Floats are not supported (no soft-float lowering), used in: {}", .0.join("; "))]
+    FloatsUnsupported(Vec<String>),
+
     /// Any user-defined error.
     #[error("User error: {0}")]
     User(String),
@@ -37,6 +50,32 @@ pub enum WasmError {
     /// Error in function builder
     #[error("FuncBuilderError: {0:?}")]
     FuncBuilderError(#[from] FuncBuilderError),
+
+    /// An error in a function body, located at the offending instruction.
+    #[error(
+        "in function {func_index}{} at offset {offset:#x}: {error}",
+        func_name.as_ref().map(|name| format!(" ({name})")).unwrap_or_default()
+    )]
+    InFunction {
+        /// The function index (the imported functions come first).
+        func_index: u32,
+        /// The function name from the name section, if present.
+        func_name: Option<String>,
+        /// The bytecode offset of the offending instruction.
+        offset: usize,
+        /// The error.
+        error: Box<WasmError>,
+    },
+}
+
+impl WasmError {
+    /// The error without its location (see [`WasmError::InFunction`]).
+    pub fn unlocated(&self) -> &WasmError {
+        match self {
+            WasmError::InFunction { error, .. } => error.unlocated(),
+            err => err,
+        }
+    }
 }
 
 /// Return an `Err(WasmError::Unsupported(msg))` where `msg` the string built by calling `format!`
@@ -0,0 +1,132 @@
+//! Early rejection of the floating point types and instructions. The targets have no floats,
+//! so instead of failing somewhere in the translation (or skipping the float operators, see
+//! [`crate::WasmFrontendConfig::skip_unsupported_ops`]) a module using them is rejected before
+//! it is translated, listing every offending function.
+//! There is no soft-float lowering (the wasm dialect has no float types), the floats have to be
+//! removed from the source program.
+
+use wasmparser::FunctionBody;
+use wasmparser::Parser;
+use wasmparser::Payload;
+use wasmparser::Type;
+use wasmparser::TypeRef;
+use wasmparser::ValType;
+
+use crate::coverage::operator_name;
+use crate::module_translator::func_name_in_name_section;
+
+fn is_float(ty: &ValType) -> bool {
+    matches!(ty, ValType::F32 | ValType::F64)
+}
+
+/// The uses of the floats in the module, e.g. `function 3 (sqrt): F64Sqrt at offset 0x2a`.
+/// The malformed parts are skipped, they are reported by the translation.
+pub(crate) fn find_float_uses(wasm: &[u8]) -> Vec<String> {
+    let mut uses = Vec::new();
+    // whether the function type has a float param or result, by type index
+    let mut float_types = Vec::new();
+    let mut defined_func_types = Vec::new();
+    let mut import_func_count = 0;
+    let mut import_global_count = 0;
+    let mut defined_func_count = 0;
+    for payload in Parser::new(0).parse_all(wasm) {
+        let Ok(payload) = payload else {
+            break;
+        };
+        match payload {
+            Payload::TypeSection(types) => {
+                for ty in types.into_iter().flatten() {
+                    let Type::Func(func_type) = ty;
+                    float_types.push(
+                        func_type
+                            .params()
+                            .iter()
+                            .chain(func_type.results())
+                            .any(is_float),
+                    );
+                }
+            }
+            Payload::ImportSection(imports) => {
+                for import in imports.into_iter().flatten() {
+                    match import.ty {
+                        TypeRef::Func(type_index) => {
+                            if float_types.get(type_index as usize) == Some(&true) {
+                                uses.push(format!(
+                                    "imported function {}.{}: float signature",
+                                    import.module, import.name
+                                ));
+                            }
+                            import_func_count += 1;
+                        }
+                        TypeRef::Global(global_type) => {
+                            if is_float(&global_type.content_type) {
+                                uses.push(format!(
+                                    "imported global {}.{}: float type",
+                                    import.module, import.name
+                                ));
+                            }
+                            import_global_count += 1;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Payload::FunctionSection(functions) => {
+                defined_func_types.extend(functions.into_iter().flatten());
+            }
+            Payload::GlobalSection(globals) => {
+                for (index, global) in globals.into_iter().flatten().enumerate() {
+                    if is_float(&global.ty.content_type) {
+                        uses.push(format!(
+                            "global {}: float type",
+                            import_global_count + index
+                        ));
+                    }
+                }
+            }
+            Payload::CodeSectionEntry(body) => {
+                let func_index = import_func_count + defined_func_count;
+                let float_signature = defined_func_types
+                    .get(defined_func_count as usize)
+                    .and_then(|type_index| float_types.get(*type_index as usize))
+                    == Some(&true);
+                defined_func_count += 1;
+                let float_use = if float_signature {
+                    Some("float signature".to_string())
+                } else {
+                    float_use_in_body(&body)
+                };
+                if let Some(float_use) = float_use {
+                    let func = match func_name_in_name_section(wasm, func_index) {
+                        Some(name) => format!("function {func_index} ({name})"),
+                        None => format!("function {func_index}"),
+                    };
+                    uses.push(format!("{func}: {float_use}"));
+                }
+            }
+            _ => {}
+        }
+    }
+    uses
+}
+
+/// The first float local or operator of the function body
+fn float_use_in_body(body: &FunctionBody) -> Option<String> {
+    let locals = body.get_locals_reader().ok()?;
+    if locals
+        .into_iter()
+        .flatten()
+        .any(|(_, local_type)| is_float(&local_type))
+    {
+        return Some("float local".to_string());
+    }
+    let mut operators = body.get_operators_reader().ok()?;
+    while !operators.eof() {
+        let (op, offset) = operators.read_with_offset().ok()?;
+        let name = operator_name(&op);
+        if name.contains("F32") || name.contains("F64") {
+            return Some(format!("{name} at offset {offset:#x}"));
+        }
+    }
+    None
+}
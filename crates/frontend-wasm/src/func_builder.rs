@@ -3,6 +3,7 @@
 use ozk_ozk_dialect::types::FuncSym;
 use ozk_wasm_dialect::ops::BlockOp;
 use ozk_wasm_dialect::ops::FuncOp;
+use ozk_wasm_dialect::ops::IfOp;
 use ozk_wasm_dialect::ops::LoopOp;
 use pliron::basic_block::BasicBlock;
 use pliron::context::Context;
@@ -51,6 +52,12 @@ impl FuncBuilder {
         let sig = self.sig.ok_or_else(|| {
             FuncBuilderError::MissingSignature(format!("FuncBuilder for {:?}", self.name))
         })?;
+        if self.blocks.len() > 1 {
+            return Err(FuncBuilderError::UnclosedBlocks(
+                self.name.clone(),
+                self.blocks.len() - 1,
+            ));
+        }
         match self.blocks.pop() {
             Some(BlockBuilder::FuncEntryBlock(entry_bb)) => {
                 let func_op = FuncOp::new_unlinked_with_block(
@@ -62,10 +69,25 @@ impl FuncBuilder {
                 );
                 Ok(func_op)
             }
-            _ => todo!("error"),
+            _ => Err(FuncBuilderError::PushOnEmptyBlocks(format!(
+                "build of {:?} without the entry block",
+                self.name
+            ))),
         }
     }
 
+    /// Pushes the operations to the current block (see [FuncBuilder::push])
+    pub fn push_all(
+        &mut self,
+        ctx: &mut Context,
+        ops: impl IntoIterator<Item = Ptr<Operation>>,
+    ) -> Result<(), FuncBuilderError> {
+        for op in ops {
+            self.push(ctx, op)?;
+        }
+        Ok(())
+    }
+
     /// Returns an OpBuilder for this FuncBuilder
     pub fn op(&mut self) -> OpBuilder {
         OpBuilder::new(self)
@@ -79,6 +101,8 @@ impl FuncBuilder {
             self.blocks.push(BlockBuilder::Block(*block));
         } else if let Some(loopop) = opop.downcast_ref::<LoopOp>() {
             self.blocks.push(BlockBuilder::Loop(*loopop));
+        } else if let Some(ifop) = opop.downcast_ref::<IfOp>() {
+            self.blocks.push(BlockBuilder::Then(*ifop));
         } else {
             let current_bb = self
                 .blocks
@@ -124,6 +148,17 @@ impl FuncBuilder {
                     loopop.get_operation().insert_at_back(current_bb, ctx);
                     Ok(())
                 }
+                BlockBuilder::Then(ifop) | BlockBuilder::Else(ifop) => {
+                    let current_bb = self
+                        .blocks
+                        .last()
+                        .ok_or(FuncBuilderError::PushOnEmptyBlocks(
+                            ifop.with_ctx(ctx).to_string(),
+                        ))?
+                        .get_bb(ctx);
+                    ifop.get_operation().insert_at_back(current_bb, ctx);
+                    Ok(())
+                }
             }
         } else {
             Err(FuncBuilderError::PushOnEmptyBlocks(
@@ -132,6 +167,23 @@ impl FuncBuilder {
         }
     }
 
+    /// Switches the current `if` to its else block
+    pub fn push_else(&mut self) -> Result<(), FuncBuilderError> {
+        match self.blocks.pop() {
+            Some(BlockBuilder::Then(ifop)) => {
+                self.blocks.push(BlockBuilder::Else(ifop));
+                Ok(())
+            }
+            Some(block_builder) => {
+                self.blocks.push(block_builder);
+                Err(FuncBuilderError::ElseWithoutIf)
+            }
+            None => Err(FuncBuilderError::PushOnEmptyBlocks(
+                "push_else called on empty blocks".into(),
+            )),
+        }
+    }
+
     /// Sets the function signature
     pub fn set_signature(&mut self, signature: Ptr<TypeObj>) {
         self.sig = Some(signature);
@@ -151,6 +203,10 @@ pub enum FuncBuilderError {
     MissingSignature(String),
     #[error("pushing {0} to empty block stack")]
     PushOnEmptyBlocks(String),
+    #[error("else outside of the then block of an if")]
+    ElseWithoutIf,
+    #[error("{1} unclosed block(s) in {0:?}")]
+    UnclosedBlocks(FuncSym, usize),
 }
 
 /// Block kinds for FuncBuilder
@@ -161,6 +217,10 @@ pub enum BlockBuilder {
     Block(BlockOp),
     /// Loop
     Loop(LoopOp),
+    /// Then block of the if
+    Then(IfOp),
+    /// Else block of the if
+    Else(IfOp),
 }
 
 impl BlockBuilder {
@@ -170,6 +230,8 @@ impl BlockBuilder {
             BlockBuilder::FuncEntryBlock(bb) => *bb,
             BlockBuilder::Block(block) => block.get_block(ctx),
             BlockBuilder::Loop(loopop) => loopop.get_block(ctx),
+            BlockBuilder::Then(ifop) => ifop.get_then_block(ctx),
+            BlockBuilder::Else(ifop) => ifop.get_else_block(ctx),
         }
     }
 }
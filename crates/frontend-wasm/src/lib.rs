@@ -1,4 +1,4 @@
-//! Performs translation from a wasm module in binary format to the in-memory form
+//! Performs translation from a wasm module in binary (or text) format to the in-memory form
 //! of ozk IR.
 
 // Coding conventions
@@ -24,15 +24,23 @@
 
 mod code_translator;
 mod config;
+mod coverage;
 mod error;
+mod floats;
 pub mod func_builder;
 mod mod_builder;
 mod module_translator;
 mod op_builder;
 
+pub use crate::config::ImportFuncLabel;
 pub use crate::config::WasmFrontendConfig;
+pub use crate::coverage::UnsupportedOpStats;
+pub use crate::coverage::UnsupportedOpsReport;
 pub use crate::error::WasmError;
 pub use crate::module_translator::parse_module;
+pub use crate::module_translator::parse_module_with_report;
+pub use crate::module_translator::translate;
+pub use crate::module_translator::translate_wat;
 
 // Convenience reexport of the wasmparser crate that we're linking against,
 // since a number of types in `wasmparser` show up in the public API of
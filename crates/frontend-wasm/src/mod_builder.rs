@@ -1,59 +1,68 @@
 use ozk_ozk_dialect::types::FuncSym;
 use pliron::common_traits::Verify;
-use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
 use pliron::error::CompilerError;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 
+use ozk_wasm_dialect::ops::DataOp;
+use ozk_wasm_dialect::ops::GlobalOp;
 use ozk_wasm_dialect::ops::ModuleOp;
+use ozk_wasm_dialect::types::ElemSegment;
 use ozk_wasm_dialect::types::FuncIndex;
+use ozk_wasm_dialect::types::GlobalInit;
+use ozk_wasm_dialect::types::ImportGlobal;
+use ozk_wasm_dialect::types::MemoryLimits;
+use ozk_wasm_dialect::types::Table;
 use ozk_wasm_dialect::types::TypeIndex;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::r#type::TypeObj;
 use thiserror::Error;
 
+use crate::config::ImportFuncLabel;
 use crate::func_builder::FuncBuilder;
 use crate::func_builder::FuncBuilderError;
 
-#[derive(Debug, Clone)]
-pub struct ImportFuncLabel {
-    pub module: String,
-    pub name: String,
-}
-
 pub struct ModuleBuilder {
     types: Vec<Ptr<TypeObj>>,
     start_func_idx: Option<FuncIndex>,
     functions: Vec<FuncBuilder>,
     import_functions: Vec<(ImportFuncLabel, TypeIndex)>,
-    func_names: HashMap<FuncIndex, FuncSym>,
+    func_names: BTreeMap<FuncIndex, FuncSym>,
     func_types: HashMap<FuncIndex, TypeIndex>,
+    tables: Vec<Table>,
+    elem_segments: Vec<ElemSegment>,
+    memories: Vec<MemoryLimits>,
+    /// Imported globals, they precede the defined ones in the global index space
+    import_globals: Vec<ImportGlobal>,
+    /// Defined globals (value type, mutability, initializer)
+    globals: Vec<(Ptr<TypeObj>, bool, GlobalInit)>,
+    /// Active data segments of the memory 0 (offset, bytes)
+    data_segments: Vec<(u32, Vec<u8>)>,
+    /// Bytes of the passive data segments by data index (empty for the active ones)
+    passive_data: Vec<Vec<u8>>,
+    /// Custom sections (name, bytes) not interpreted by the frontend
+    custom_sections: Vec<(String, Vec<u8>)>,
 }
 
 impl ModuleBuilder {
-    fn build_func_sym_vec(&self) -> Vec<FuncSym> {
-        let mut func_syms = Vec::new();
-        for func_idx in 0..self.functions.len() {
-            let func_idx = (func_idx as u32).into();
-            let func_sym = self
-                .func_names
-                .get(&func_idx)
-                .cloned()
-                .unwrap_or_else(|| "undefined".to_string().into());
-            func_syms.push(func_sym);
-        }
-        func_syms
-    }
-
     pub fn new() -> Self {
         Self {
             types: Vec::new(),
             start_func_idx: None,
             functions: Vec::new(),
             // import_func_body: ImportFuncBody::new_stdlib(),
-            func_names: HashMap::new(),
+            func_names: BTreeMap::new(),
             func_types: HashMap::new(),
             import_functions: Vec::new(),
+            tables: Vec::new(),
+            elem_segments: Vec::new(),
+            memories: Vec::new(),
+            import_globals: Vec::new(),
+            globals: Vec::new(),
+            data_segments: Vec::new(),
+            passive_data: Vec::new(),
+            custom_sections: Vec::new(),
         }
     }
 
@@ -64,16 +73,9 @@ impl ModuleBuilder {
     pub fn push_import_func(
         &mut self,
         type_idx: u32,
-        module: &str,
-        name: &str,
+        label: ImportFuncLabel,
     ) -> Result<(), ModuleBuilderError> {
-        self.import_functions.push((
-            ImportFuncLabel {
-                module: module.to_string(),
-                name: name.to_string(),
-            },
-            type_idx.into(),
-        ));
+        self.import_functions.push((label, type_idx.into()));
         Ok(())
     }
 
@@ -83,6 +85,40 @@ impl ModuleBuilder {
         self.func_types.insert(func_idx, type_idx);
     }
 
+    pub fn push_table(&mut self, table: Table) {
+        self.tables.push(table);
+    }
+
+    pub fn push_elem_segment(&mut self, elem_segment: ElemSegment) {
+        self.elem_segments.push(elem_segment);
+    }
+
+    pub fn push_memory(&mut self, memory: MemoryLimits) {
+        self.memories.push(memory);
+    }
+
+    pub fn push_import_global(&mut self, import_global: ImportGlobal) {
+        self.import_globals.push(import_global);
+    }
+
+    pub fn push_global(&mut self, ty: Ptr<TypeObj>, mutable: bool, init: GlobalInit) {
+        self.globals.push((ty, mutable, init));
+    }
+
+    pub fn push_data_segment(&mut self, offset: u32, bytes: Vec<u8>) {
+        self.data_segments.push((offset, bytes));
+        // dropped after the instantiation
+        self.passive_data.push(Vec::new());
+    }
+
+    pub fn push_passive_data_segment(&mut self, bytes: Vec<u8>) {
+        self.passive_data.push(bytes);
+    }
+
+    pub fn push_custom_section(&mut self, name: String, bytes: Vec<u8>) {
+        self.custom_sections.push((name, bytes));
+    }
+
     pub fn set_start_func(&mut self, func_idx: u32) {
         self.start_func_idx = Some(func_idx.into());
     }
@@ -105,45 +141,68 @@ impl ModuleBuilder {
             func_sigs.push(func_type);
         }
         if let Some(start_func_idx) = self.start_func_idx {
-            let start_func_name = self
-                .get_func_name(start_func_idx)
-                .ok_or(ModuleBuilderError::FuncNameNotFound(start_func_idx))?;
-            let _import_func_types = self
+            let start_func_name = self.func_name_or_default(start_func_idx);
+            let import_func_types = self
                 .import_functions
                 .iter()
-                .map(|(label, ty_idx)| self.get_type(*ty_idx).map(|ty| (label.clone(), ty)))
-                .collect::<Result<Vec<(ImportFuncLabel, Ptr<TypeObj>)>, ModuleBuilderError>>()?;
+                .map(|(_, ty_idx)| self.get_type(*ty_idx))
+                .collect::<Result<Vec<Ptr<TypeObj>>, ModuleBuilderError>>()?;
             let mut funcs = Vec::new();
             // TODO: since func indices should be shifted by imported funcs count change the storage and make it obvious
             let imported_funcs_count = self.import_functions.len() as u32;
+            let func_syms: Vec<FuncSym> = (0..self.functions.len() as u32)
+                .map(|func_idx| self.func_name_or_default((func_idx + imported_funcs_count).into()))
+                .collect();
             for (func_idx, func_builder) in self.functions.iter_mut().enumerate() {
-                if let Some(func_name) = self
-                    .func_names
-                    .get(&(func_idx as u32 + imported_funcs_count).into())
-                {
-                    func_builder.set_name(func_name.clone());
-                }
+                func_builder.set_name(func_syms[func_idx].clone());
                 func_builder.set_signature(func_sigs[func_idx]);
             }
-            let mut all_func_syms: Vec<FuncSym> = Vec::new();
-            for (label, _) in self.import_functions.iter() {
-                all_func_syms.push(label.name.clone().into());
-            }
+            let import_func_syms: Vec<FuncSym> = self
+                .import_functions
+                .iter()
+                .map(|(label, _)| label.name.clone().into())
+                .collect();
+            let import_func_modules: Vec<String> = self
+                .import_functions
+                .iter()
+                .map(|(label, _)| label.module.clone())
+                .collect();
             for func_builder in self.functions {
-                let func = func_builder.build(ctx)?;
-                funcs.push(func);
-                all_func_syms.push(func.get_symbol_name(ctx).into());
+                funcs.push(func_builder.build(ctx)?);
             }
 
             let module_op = ModuleOp::new(
                 ctx,
                 "module_name",
                 start_func_name,
-                all_func_syms,
+                import_func_syms,
                 funcs,
-                Vec::new(),
-                Vec::new(),
+                import_func_types,
+                import_func_modules,
             );
+            module_op.set_tables(
+                ctx,
+                self.tables.iter().map(|table| table.minimum).collect(),
+                self.elem_segments,
+            );
+            module_op.set_memory_limits(ctx, self.memories);
+            if !self.import_globals.is_empty() {
+                module_op.set_import_globals(ctx, self.import_globals);
+            }
+            for (ty, mutable, init) in self.globals {
+                let global_op = GlobalOp::new_unlinked(ctx, ty, mutable, init);
+                module_op.append_global(ctx, global_op);
+            }
+            for (offset, bytes) in self.data_segments {
+                let data_op = DataOp::new_unlinked(ctx, offset, bytes);
+                module_op.append_data(ctx, data_op);
+            }
+            if !self.passive_data.is_empty() {
+                module_op.set_passive_data(ctx, self.passive_data);
+            }
+            if !self.custom_sections.is_empty() {
+                module_op.set_custom_sections(ctx, self.custom_sections);
+            }
             module_op.verify(ctx)?;
             Ok(module_op)
         } else {
@@ -151,6 +210,11 @@ impl ModuleBuilder {
         }
     }
 
+    /// Number of the imported functions (they precede the defined ones in the index space)
+    pub fn import_func_count(&self) -> u32 {
+        self.import_functions.len() as u32
+    }
+
     pub fn next_func_idx(&self) -> FuncIndex {
         (self.functions.len() as u32).into()
     }
@@ -165,6 +229,31 @@ impl ModuleBuilder {
         self.func_names.get(&func_idx).cloned()
     }
 
+    /// The name from the name section, or `func_N` (N is the Wasm function index, the imported
+    /// functions included) if the function has no name. The default name gets a `_M` suffix
+    /// if it's taken by a declared or imported function.
+    pub fn func_name_or_default(&self, func_idx: FuncIndex) -> FuncSym {
+        if let Some(name) = self.get_func_name(func_idx) {
+            return name;
+        }
+        let default_name = format!("func_{}", u32::from(func_idx));
+        let mut name = FuncSym::from(default_name.as_str());
+        let mut suffix = 0;
+        while self.is_func_name_taken(&name) {
+            suffix += 1;
+            name = format!("{default_name}_{suffix}").into();
+        }
+        name
+    }
+
+    fn is_func_name_taken(&self, name: &FuncSym) -> bool {
+        self.func_names.values().any(|declared| declared == name)
+            || self
+                .import_functions
+                .iter()
+                .any(|(label, _)| label.name.as_str() == name.as_ref())
+    }
+
     pub fn get_func_type(&self, func_idx: FuncIndex) -> Result<Ptr<TypeObj>, ModuleBuilderError> {
         let type_idx = self
             .func_types
@@ -207,8 +296,6 @@ pub enum ModuleBuilderError {
     FuncBuilderError(#[from] FuncBuilderError),
     #[error("invalid type index: {0}")]
     InvalidTypeIndex(String),
-    #[error("func name not found for func index: {0:?}")]
-    FuncNameNotFound(FuncIndex),
     #[error("compiler error: {0:?}")]
     CompilerError(#[from] CompilerError),
 }
@@ -3,27 +3,74 @@
 //! Translation skeleton that traverses the whole WebAssembly module and call helper functions
 //! to deal with each part of it.
 
+use crate::coverage::UnsupportedOpsReport;
 use crate::error::WasmError;
+use crate::floats::find_float_uses;
 use crate::func_builder::FuncBuilder;
 use crate::WasmFrontendConfig;
 use crate::{code_translator::translate_operator, mod_builder::ModuleBuilder};
 use ozk_wasm_dialect::ops::ModuleOp;
-use ozk_wasm_dialect::types::{from_func_type, from_val_type, FuncIndex};
+use ozk_wasm_dialect::types::{
+    from_func_type, from_val_type, ElemSegment, FuncIndex, GlobalInit, ImportGlobal, Memory,
+    MemoryLimits, Table,
+};
 use pliron::context::Context;
 use pliron::dialects::builtin::types::FunctionType;
 use wasmparser::{
-    BinaryReader, ExternalKind, FuncValidator, FunctionBody, NameSectionReader, Naming, Parser,
-    Payload, Type, TypeRef, Validator, ValidatorResources, WasmModuleResources,
+    BinaryReader, DataKind, ElementItems, ElementKind, ExternalKind, FuncValidator, FunctionBody,
+    NameSectionReader, Naming, Operator, Parser, Payload, Type, TypeRef, Validator,
+    ValidatorResources, WasmModuleResources,
 };
 
+/// Translate a Wasm module into a `wasm.module` operation (pliron IR), registering the
+/// dialects used by the frontend in `ctx` first. The entry point for the backends, the
+/// IR transformations work on the returned module.
+/// Takes either a Wasm binary or the text format (WAT), the input without the binary magic
+/// number is parsed as text.
+pub fn translate(
+    ctx: &mut Context,
+    wasm: &[u8],
+    config: &WasmFrontendConfig,
+) -> Result<ModuleOp, WasmError> {
+    let wasm = wat::parse_bytes(wasm).map_err(|e| WasmError::InvalidWat(e.to_string()))?;
+    config.register(ctx);
+    parse_module(ctx, &wasm, config)
+}
+
+/// Same as [`translate`] for a module in the text format (WAT), e.g. a `.wat` file.
+pub fn translate_wat(
+    ctx: &mut Context,
+    wat: &str,
+    config: &WasmFrontendConfig,
+) -> Result<ModuleOp, WasmError> {
+    translate(ctx, wat.as_bytes(), config)
+}
+
 /// Translate a sequence of bytes forming a valid Wasm binary into a `wasm.module` operation.
+/// Expects the frontend dialects to be registered (see [`WasmFrontendConfig::register`]).
 pub fn parse_module(
     ctx: &mut Context,
     wasm: &[u8],
-    _config: &WasmFrontendConfig,
+    config: &WasmFrontendConfig,
 ) -> Result<ModuleOp, WasmError> {
-    let mut validator = Validator::new();
+    parse_module_with_report(ctx, wasm, config).map(|(module_op, _)| module_op)
+}
+
+/// Same as [`parse_module`], but also returns the operators that were skipped
+/// (see [`WasmFrontendConfig::skip_unsupported_ops`]).
+pub fn parse_module_with_report(
+    ctx: &mut Context,
+    wasm: &[u8],
+    config: &WasmFrontendConfig,
+) -> Result<(ModuleOp, UnsupportedOpsReport), WasmError> {
+    let float_uses = find_float_uses(wasm);
+    if !float_uses.is_empty() {
+        return Err(WasmError::FloatsUnsupported(float_uses));
+    }
+    let mut validator = Validator::new_with_features(config.wasm_features());
     let mut mod_builder = ModuleBuilder::new();
+    let mut report = UnsupportedOpsReport::default();
+    declare_func_names(wasm, &mut mod_builder);
 
     for payload in Parser::new(0).parse_all(wasm) {
         // dbg!(&mod_builder);
@@ -46,7 +93,7 @@ pub fn parse_module(
 
             Payload::ImportSection(imports) => {
                 validator.import_section(&imports)?;
-                parse_imports_section(imports, &mut mod_builder)?;
+                parse_imports_section(ctx, imports, &mut mod_builder, config)?;
             }
 
             Payload::FunctionSection(functions) => {
@@ -60,15 +107,16 @@ pub fn parse_module(
 
             Payload::TableSection(tables) => {
                 validator.table_section(&tables)?;
-                dbg!(
-                    "Table section: {:?}",
-                    tables.into_iter().collect::<Vec<_>>()
-                );
+                for table in tables {
+                    mod_builder.push_table(Table::from(table?));
+                }
             }
 
             Payload::MemorySection(memories) => {
                 validator.memory_section(&memories)?;
-                // todo!()
+                for memory in memories {
+                    mod_builder.push_memory(memory_limits(Memory::from(memory?))?);
+                }
             }
 
             Payload::TagSection(tags) => {
@@ -79,7 +127,7 @@ pub fn parse_module(
 
             Payload::GlobalSection(globals) => {
                 validator.global_section(&globals)?;
-                // todo!()
+                parse_global_section(ctx, globals, &mut mod_builder)?;
             }
 
             Payload::ExportSection(exports) => {
@@ -95,7 +143,7 @@ pub fn parse_module(
 
             Payload::ElementSection(elements) => {
                 validator.element_section(&elements)?;
-                todo!()
+                parse_element_section(elements, &mut mod_builder)?;
             }
 
             Payload::CodeSectionStart { count, range, .. } => {
@@ -105,33 +153,49 @@ pub fn parse_module(
             }
 
             Payload::CodeSectionEntry(body) => {
+                let func_index =
+                    mod_builder.import_func_count() + u32::from(mod_builder.next_func_idx());
+                let mut offset = body.get_binary_reader().original_position();
                 let mut func_validator = validator
                     .code_section_entry(&body)?
                     .into_validator(Default::default());
-                parse_code_section_entry(ctx, &mut mod_builder, &mut func_validator, body)?;
+                parse_code_section_entry(
+                    ctx,
+                    &mut mod_builder,
+                    &mut func_validator,
+                    body,
+                    config,
+                    &mut report,
+                    &mut offset,
+                )
+                .map_err(|error| WasmError::InFunction {
+                    func_index,
+                    func_name: mod_builder
+                        .get_func_name(func_index.into())
+                        .map(String::from),
+                    offset,
+                    error: Box::new(error),
+                })?;
             }
 
             Payload::DataSection(data) => {
                 validator.data_section(&data)?;
-                dbg!("Data section: {:?}", data.into_iter().collect::<Vec<_>>());
-                todo!()
+                parse_data_section(data, &mut mod_builder)?;
             }
 
             Payload::DataCountSection { count, range } => {
                 validator.data_count_section(count, &range)?;
-                todo!()
             }
 
             Payload::CustomSection(s) if s.name() == "name" => {
-                let subsections = NameSectionReader::new(s.data(), s.data_offset());
-                let result = parse_name_section(subsections, &mut mod_builder);
-                if let Err(e) = result {
-                    log::warn!("failed to parse name section {:?}", e);
-                }
+                // declared before the translation (see [declare_func_names])
             }
 
             Payload::CustomSection(custom_section) => {
-                dbg!("Custom section: {:?}", custom_section);
+                mod_builder.push_custom_section(
+                    custom_section.name().to_string(),
+                    custom_section.data().to_vec(),
+                );
             }
             other => {
                 validator.payload(&other)?;
@@ -139,7 +203,10 @@ pub fn parse_module(
             }
         }
     }
-    Ok(mod_builder.build(ctx)?)
+    if !report.is_empty() {
+        log::warn!("{}", report);
+    }
+    Ok((mod_builder.build(ctx)?, report))
 }
 
 fn parse_export_section(
@@ -165,6 +232,132 @@ fn parse_export_section(
     Ok(())
 }
 
+fn memory_limits(memory: Memory) -> Result<MemoryLimits, WasmError> {
+    if memory.memory64 || memory.shared {
+        return Err(WasmError::Unsupported(format!(
+            "memory {memory:?}, only unshared 32-bit memories are supported"
+        )));
+    }
+    // the validator checked that the 32-bit memory limits are at most 65536 pages
+    Ok(MemoryLimits {
+        minimum: memory.minimum as u32,
+        maximum: memory.maximum.map(|maximum| maximum as u32),
+    })
+}
+
+fn parse_element_section(
+    elements: wasmparser::ElementSectionReader,
+    mod_builder: &mut ModuleBuilder,
+) -> Result<(), WasmError> {
+    for element in elements {
+        let element = element?;
+        let ElementKind::Active {
+            table_index,
+            offset_expr,
+        } = element.kind else {
+            // passive and declared segments are only used by `table.init` and `ref.func`
+            continue;
+        };
+        let mut offset_reader = offset_expr.get_operators_reader();
+        let offset = match offset_reader.read()? {
+            Operator::I32Const { value } => value as u32,
+            op => {
+                return Err(WasmError::Unsupported(format!(
+                    "element segment offset {op:?}, only i32.const is supported"
+                )))
+            }
+        };
+        let mut func_indices = Vec::new();
+        match element.items {
+            ElementItems::Functions(funcs) => {
+                for func_idx in funcs {
+                    func_indices.push(FuncIndex::from(func_idx?));
+                }
+            }
+            ElementItems::Expressions(exprs) => {
+                for expr in exprs {
+                    match expr?.get_operators_reader().read()? {
+                        Operator::RefFunc { function_index } => {
+                            func_indices.push(FuncIndex::from(function_index))
+                        }
+                        op => {
+                            return Err(WasmError::Unsupported(format!(
+                                "element segment item {op:?}, only ref.func is supported"
+                            )))
+                        }
+                    }
+                }
+            }
+        }
+        mod_builder.push_elem_segment(ElemSegment {
+            table_index: table_index.into(),
+            offset,
+            func_indices,
+        });
+    }
+    Ok(())
+}
+
+fn parse_data_section(
+    data: wasmparser::DataSectionReader,
+    mod_builder: &mut ModuleBuilder,
+) -> Result<(), WasmError> {
+    for segment in data {
+        let segment = segment?;
+        let DataKind::Active {
+            memory_index,
+            offset_expr,
+        } = segment.kind else {
+            mod_builder.push_passive_data_segment(segment.data.to_vec());
+            continue;
+        };
+        if memory_index != 0 {
+            return Err(WasmError::Unsupported(format!(
+                "data segment of the memory {memory_index}, only the memory 0 is supported"
+            )));
+        }
+        let mut offset_reader = offset_expr.get_operators_reader();
+        let offset = match offset_reader.read()? {
+            Operator::I32Const { value } => value as u32,
+            op => {
+                return Err(WasmError::Unsupported(format!(
+                    "data segment offset {op:?}, only i32.const is supported"
+                )))
+            }
+        };
+        mod_builder.push_data_segment(offset, segment.data.to_vec());
+    }
+    Ok(())
+}
+
+fn parse_global_section(
+    ctx: &mut Context,
+    globals: wasmparser::GlobalSectionReader,
+    mod_builder: &mut ModuleBuilder,
+) -> Result<(), WasmError> {
+    for global in globals {
+        let global = global?;
+        let mut init_reader = global.init_expr.get_operators_reader();
+        let init = match init_reader.read()? {
+            Operator::I32Const { value } => GlobalInit::I32Const(value),
+            Operator::I64Const { value } => GlobalInit::I64Const(value),
+            Operator::GlobalGet { global_index } => GlobalInit::GetGlobal(global_index.into()),
+            op => {
+                return Err(WasmError::Unsupported(format!(
+                    "global initializer {op:?}, only i32.const, i64.const and global.get are \
+                     supported"
+                )))
+            }
+        };
+        mod_builder.push_global(
+            from_val_type(ctx, &global.ty.content_type),
+            global.ty.mutable,
+            init,
+        );
+    }
+    Ok(())
+}
+
 fn parse_type_section(
     ctx: &mut Context,
     types: wasmparser::TypeSectionReader,
@@ -180,18 +373,22 @@ fn parse_type_section(
     Ok(())
 }
 
+/// Translate the function body. On failure `offset` is the bytecode offset of the offending
+/// instruction (or local declaration).
 fn parse_code_section_entry(
     ctx: &mut Context,
     mod_builder: &mut ModuleBuilder,
     validator: &mut FuncValidator<ValidatorResources>,
     body: FunctionBody,
+    config: &WasmFrontendConfig,
+    report: &mut UnsupportedOpsReport,
+    offset: &mut usize,
 ) -> Result<(), WasmError> {
     let func_idx = mod_builder.next_func_idx();
     let func_name = mod_builder
-        .get_func_name(func_idx)
-        .unwrap_or(format!("f{}", u32::from(func_idx)).into());
+        .func_name_or_default((mod_builder.import_func_count() + u32::from(func_idx)).into());
     // dbg!(&func_name);
-    let mut builder = FuncBuilder::new(ctx, func_name);
+    let mut builder = FuncBuilder::new(ctx, func_name.clone());
     let mut reader = body.get_binary_reader();
     // take care of wasm parameters and pass the next local as num_params
     #[allow(clippy::unwrap_used)]
@@ -204,14 +401,27 @@ fn parse_code_section_entry(
         .len();
 
     // dbg!(&num_params);
-    parse_local_decls(ctx, &mut reader, &mut builder, num_params, validator)?;
+    parse_local_decls(
+        ctx,
+        &mut reader,
+        &mut builder,
+        num_params,
+        validator,
+        offset,
+    )?;
     while !reader.eof() {
         // dbg!(&builder);
         let pos = reader.original_position();
+        *offset = pos;
         let op = reader.read_operator()?;
         // dbg!(&op);
         validator.op(pos, &op)?;
-        translate_operator(ctx, validator, &op, &mut builder, mod_builder)?;
+        match translate_operator(ctx, validator, &op, &mut builder, mod_builder) {
+            Err(WasmError::UnsupportedOperator(op_name)) if config.skip_unsupported_ops => {
+                report.record(&op_name, &func_name);
+            }
+            res => res?,
+        }
     }
     mod_builder.push_func_builder(builder);
     Ok(())
@@ -224,10 +434,12 @@ fn parse_local_decls(
     builder: &mut FuncBuilder,
     _num_params: usize,
     validator: &mut FuncValidator<impl WasmModuleResources>,
+    offset: &mut usize,
 ) -> Result<(), WasmError> {
     let local_count = reader.read_var_u32()?;
     for _ in 0..local_count {
         let pos = reader.original_position();
+        *offset = pos;
         let count = reader.read_var_u32()?;
         let ty = reader.read::<wasmparser::ValType>()?;
         validator.define_locals(pos, count, ty)?;
@@ -237,14 +449,17 @@ fn parse_local_decls(
 }
 
 fn parse_imports_section(
+    ctx: &mut Context,
     imports: wasmparser::ImportSectionReader,
     mod_builder: &mut ModuleBuilder,
+    config: &WasmFrontendConfig,
 ) -> Result<(), WasmError> {
     for entry in imports {
         let import = entry?;
         match import.ty {
             TypeRef::Func(type_index) => {
-                mod_builder.push_import_func(type_index, import.module, import.name)?;
+                let label = config.resolve_import(import.module, import.name);
+                mod_builder.push_import_func(type_index, label)?;
             }
             TypeRef::Memory(_ty) => {
                 todo!()
@@ -252,8 +467,13 @@ fn parse_imports_section(
             TypeRef::Tag(_e) => {
                 todo!()
             }
-            TypeRef::Global(_ty) => {
-                todo!()
+            TypeRef::Global(ty) => {
+                mod_builder.push_import_global(ImportGlobal {
+                    module: import.module.to_string(),
+                    name: import.name.to_string(),
+                    ty: from_val_type(ctx, &ty.content_type),
+                    mutable: ty.mutable,
+                });
             }
             TypeRef::Table(_ty) => {
                 todo!()
@@ -305,6 +525,49 @@ pub fn parse_name_section(
     Ok(())
 }
 
+/// Declare the function names from the name section. It follows the code section, so it's
+/// parsed ahead for the names to be known while the function bodies are translated (in the
+/// diagnostics and the unsupported ops report).
+fn declare_func_names(wasm: &[u8], mod_builder: &mut ModuleBuilder) {
+    for payload in Parser::new(0).parse_all(wasm) {
+        let Ok(Payload::CustomSection(section)) = payload else {
+            continue;
+        };
+        if section.name() != "name" {
+            continue;
+        }
+        let subsections = NameSectionReader::new(section.data(), section.data_offset());
+        if let Err(e) = parse_name_section(subsections, mod_builder) {
+            log::warn!("failed to parse name section {:?}", e);
+        }
+    }
+}
+
+/// The function name from the name section (see [declare_func_names] for the translation).
+pub(crate) fn func_name_in_name_section(wasm: &[u8], func_index: u32) -> Option<String> {
+    for payload in Parser::new(0).parse_all(wasm) {
+        let Ok(Payload::CustomSection(section)) = payload else {
+            continue;
+        };
+        if section.name() != "name" {
+            continue;
+        }
+        for subsection in NameSectionReader::new(section.data(), section.data_offset()) {
+            let Ok(wasmparser::Name::Function(names)) = subsection else {
+                continue;
+            };
+            for naming in names {
+                if let Ok(Naming { index, name }) = naming {
+                    if index == func_index {
+                        return Some(name.to_string());
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
 fn parse_function_section(
     functions: wasmparser::FunctionSectionReader,
     mod_builder: &mut ModuleBuilder,
@@ -317,4 +580,908 @@ fn parse_function_section(
 
 #[allow(clippy::unwrap_used)]
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use ozk_ozk_dialect::types::FuncSym;
+    use ozk_wasm_dialect::ops::I32Extend8SOp;
+    use ozk_wasm_dialect::ops::I32Load8UOp;
+    use ozk_wasm_dialect::ops::I64Extend32SOp;
+    use ozk_wasm_dialect::ops::LoadOp;
+    use ozk_wasm_dialect::ops::MemoryGrowOp;
+    use ozk_wasm_dialect::ops::MemorySizeOp;
+    use ozk_wasm_dialect::ops::ReturnCallOp;
+    use ozk_wasm_dialect::ops::StoreOp;
+    use ozk_wasm_dialect::types::MemArg;
+    use ozk_wasm_dialect::types::MemoryIndex;
+    use pliron::dialects::builtin::attributes::StringAttr;
+    use pliron::dialects::builtin::attributes::VecAttr;
+    use pliron::error::CompilerError;
+    use pliron::op::Op;
+    use pliron::with_context::AttachContext;
+
+    use crate::config::ImportFuncLabel;
+
+    use super::*;
+
+    fn parse_wat(
+        ctx: &mut Context,
+        wat: &str,
+        config: &WasmFrontendConfig,
+    ) -> Result<(ModuleOp, UnsupportedOpsReport), WasmError> {
+        let source = wat::parse_str(wat).unwrap();
+        config.register(ctx);
+        parse_module_with_report(ctx, &source, config)
+    }
+
+    const WAT_WITH_EXTEND: &str = r#"
+(module
+    (start $main)
+    (func $widen (param i32) (result i64)
+        local.get 0
+        i64.extend_i32_u
+        return)
+    (func $main
+        i32.const 3
+        call $widen
+        i32.const 4
+        i64.extend_i32_u
+        i64.add
+        drop
+        return)
+)"#;
+
+    #[test]
+    fn unsupported_op_fails_by_default() {
+        let mut ctx = Context::default();
+        let res = parse_wat(&mut ctx, WAT_WITH_EXTEND, &WasmFrontendConfig::default());
+        assert!(matches!(
+            res.as_ref().map_err(WasmError::unlocated),
+            Err(WasmError::UnsupportedOperator(op)) if op == "I64ExtendI32U"
+        ));
+    }
+
+    #[test]
+    fn floats_rejected() {
+        let mut ctx = Context::default();
+        let err = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (import "env" "sqrt" (func $sqrt (param f64) (result f64)))
+    (start $main)
+    (func $half (param i32) (result i32)
+        local.get 0
+        f32.convert_i32_s
+        f32.const 0.5
+        f32.mul
+        i32.trunc_f32_s)
+    (func $main
+        i32.const 3
+        call $half
+        drop
+        return)
+)"#,
+            &WasmFrontendConfig {
+                skip_unsupported_ops: true,
+                ..Default::default()
+            },
+        )
+        .err();
+        assert!(
+            matches!(&err, Some(WasmError::FloatsUnsupported(uses)) if uses.len() == 2
+                && uses[0] == "imported function env.sqrt: float signature"
+                && uses[1].starts_with("function 1 (half): F32ConvertI32S at offset")),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn trunc_sat_rejected_as_float() {
+        // emitted by rustc for `as` casts of floats, no soft-float runtime to lower them to
+        let mut ctx = Context::default();
+        let err = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (import "env" "sqrt" (func $sqrt (result f64)))
+    (start $main)
+    (func $main
+        call $sqrt
+        i32.trunc_sat_f64_s
+        drop
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .err();
+        assert!(
+            matches!(&err, Some(WasmError::FloatsUnsupported(uses)) if uses.len() == 2
+                && uses[1].starts_with("function 1 (main): I32TruncSatF64S at offset")),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn error_located_in_function() {
+        let mut ctx = Context::default();
+        let source = wat::parse_str(WAT_WITH_EXTEND).unwrap();
+        let err = translate(&mut ctx, &source, &WasmFrontendConfig::default()).err();
+        // `i64.extend_i32_u` in `$widen`
+        assert!(
+            matches!(&err, Some(WasmError::InFunction {
+                func_index: 0,
+                func_name: Some(name),
+                offset,
+                ..
+            }) if name == "widen" && source[*offset] == 0xad),
+            "{err:?}"
+        );
+    }
+
+    const WAT_ADD: &str = r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 1
+        i32.const 2
+        i32.add
+        drop
+        return)
+)"#;
+
+    #[test]
+    fn translate_wat_and_binary() {
+        let config = WasmFrontendConfig::default();
+        let mut ctx = Context::default();
+        let from_wat = translate_wat(&mut ctx, WAT_ADD, &config).unwrap();
+        let from_wat = from_wat.get_operation().with_ctx(&ctx).to_string();
+        let mut ctx = Context::default();
+        let binary = wat::parse_str(WAT_ADD).unwrap();
+        let from_binary = translate(&mut ctx, &binary, &config).unwrap();
+        assert_eq!(
+            from_binary.get_operation().with_ctx(&ctx).to_string(),
+            from_wat
+        );
+        // the text is detected in the `translate` input as well
+        let mut ctx = Context::default();
+        let from_text_bytes = translate(&mut ctx, WAT_ADD.as_bytes(), &config).unwrap();
+        assert_eq!(
+            from_text_bytes.get_operation().with_ctx(&ctx).to_string(),
+            from_wat
+        );
+    }
+
+    #[test]
+    fn invalid_wat_rejected() {
+        let mut ctx = Context::default();
+        let err = translate_wat(
+            &mut ctx,
+            "(module (func $main i32.nonsense))",
+            &WasmFrontendConfig::default(),
+        )
+        .err();
+        assert!(
+            matches!(&err, Some(WasmError::InvalidWat(msg)) if msg.contains("nonsense")),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn unsupported_op_skipped_and_reported() {
+        let config = WasmFrontendConfig {
+            skip_unsupported_ops: true,
+            ..Default::default()
+        };
+        let mut ctx = Context::default();
+        let (_, report) = parse_wat(&mut ctx, WAT_WITH_EXTEND, &config).unwrap();
+        let stats = report.get("I64ExtendI32U").unwrap();
+        assert_eq!(stats.count, 2);
+        assert_eq!(
+            stats.funcs.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["main", "widen"]
+        );
+        assert_eq!(report.total_count(), 2);
+        assert_eq!(
+            report.to_string(),
+            "2 unsupported operator(s) in 2 function(s):\n  I64ExtendI32U: 2 in main, widen\n"
+        );
+    }
+
+    #[test]
+    fn import_remap() {
+        let mut config = WasmFrontendConfig::default();
+        config
+            .import_module_renames
+            .insert("c2zk".to_string(), "env".to_string());
+        config.import_func_remaps.insert(
+            ImportFuncLabel::new("io", "read_pub"),
+            ImportFuncLabel::new("env", "ozk_stdlib_pub_input"),
+        );
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (type (;0;) (func (result i64)))
+    (type (;1;) (func (param i64)))
+    (import "io" "read_pub" (func $read_pub (type 0)))
+    (import "c2zk" "ozk_stdlib_pub_output" (func $write_pub (type 1)))
+    (start $main)
+    (func $main
+        call $read_pub
+        call $write_pub
+        return)
+)"#,
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            module_op.get_func_syms(&ctx),
+            vec![
+                FuncSym::from("ozk_stdlib_pub_input"),
+                FuncSym::from("ozk_stdlib_pub_output"),
+                FuncSym::from("main"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unnamed_funcs_get_default_names() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (type (;0;) (func (result i64)))
+    (import "env" "ozk_stdlib_pub_input" (func (type 0)))
+    (start 2)
+    (func $named
+        return)
+    (func
+        call 1
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            module_op.get_func_syms(&ctx),
+            vec![
+                FuncSym::from("ozk_stdlib_pub_input"),
+                FuncSym::from("named"),
+                FuncSym::from("func_2"),
+            ]
+        );
+        assert_eq!(
+            module_op.get_start_func_sym(&ctx).unwrap(),
+            FuncSym::from("func_2")
+        );
+    }
+
+    #[test]
+    fn set_and_clear_start_func_sym() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (start $main)
+    (func $other
+        return)
+    (func $main
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        module_op
+            .set_start_func_sym(&mut ctx, FuncSym::from("other"))
+            .unwrap();
+        assert_eq!(
+            module_op.get_start_func_sym(&ctx).unwrap(),
+            FuncSym::from("other")
+        );
+        let err = module_op
+            .set_start_func_sym(&mut ctx, FuncSym::from("missing"))
+            .unwrap_err();
+        assert!(
+            matches!(&err, CompilerError::VerificationError { msg } if msg.contains("is not defined")),
+            "{err:?}"
+        );
+        assert_eq!(
+            module_op.get_start_func_sym(&ctx).unwrap(),
+            FuncSym::from("other")
+        );
+        module_op.clear_start_func_sym(&mut ctx);
+        assert_eq!(module_op.try_get_start_func_sym(&ctx), None);
+        assert!(module_op.get_start_func_sym(&ctx).is_err());
+    }
+
+    #[test]
+    fn default_func_names_do_not_clash_with_declared() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (start 2)
+    (func $func_2
+        return)
+    (func $func_2_1
+        return)
+    (func
+        call 0
+        call 1
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            module_op.get_func_syms(&ctx),
+            vec![
+                FuncSym::from("func_2"),
+                FuncSym::from("func_2_1"),
+                FuncSym::from("func_2_2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_and_elem_segments() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (type $nullary (func))
+    (table 4 funcref)
+    (elem (i32.const 1) $a $b)
+    (elem (i32.const 3) func $a)
+    (start $main)
+    (func $a
+        return)
+    (func $b
+        return)
+    (func $main
+        i32.const 2
+        call_indirect (type $nullary)
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(module_op.get_table_sizes(&ctx), vec![4]);
+        assert_eq!(module_op.get_elem_segments(&ctx).len(), 2);
+        assert_eq!(
+            module_op.get_table_entries(&ctx, 0.into()),
+            vec![
+                None,
+                Some(FuncIndex::from(0)),
+                Some(FuncIndex::from(1)),
+                Some(FuncIndex::from(0))
+            ]
+        );
+    }
+
+    #[test]
+    fn memory_limits_and_ops() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (memory 2 16)
+    (start $main)
+    (func $main
+        i32.const 1
+        memory.grow
+        drop
+        memory.size
+        drop
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            module_op.get_memory_limits(&ctx),
+            vec![MemoryLimits {
+                minimum: 2,
+                maximum: Some(16)
+            }]
+        );
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        let ops: Vec<_> = main_func.op_iter(&ctx).collect();
+        let memory_grow_op = ops[1].deref(&ctx).get_op(&ctx).downcast::<MemoryGrowOp>();
+        assert_eq!(
+            memory_grow_op
+                .map(|op| u32::from(op.get_memory_index(&ctx)))
+                .ok(),
+            Some(0)
+        );
+        assert!(ops[3]
+            .deref(&ctx)
+            .get_op(&ctx)
+            .downcast_ref::<MemorySizeOp>()
+            .is_some());
+    }
+
+    #[test]
+    fn active_data_segments() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (memory 1)
+    (data (i32.const 16) "hi\00")
+    (data (i32.const 1024) "\01\02")
+    (start $main)
+    (func $main
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        let data: Vec<(u32, Vec<u8>)> = module_op
+            .get_data_ops(&ctx)
+            .iter()
+            .map(|data_op| (data_op.get_offset(&ctx), data_op.get_bytes(&ctx)))
+            .collect();
+        assert_eq!(data, vec![(16, b"hi\0".to_vec()), (1024, vec![1, 2])]);
+        assert_eq!(
+            module_op.get_data_ops(&ctx)[0].with_ctx(&ctx).to_string(),
+            r#"wasm.data offset=16 "hi\00""#
+        );
+    }
+
+    #[test]
+    fn globals() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (global $__stack_pointer (mut i32) (i32.const 1048576))
+    (global $seed i64 (i64.const -5))
+    (start $main)
+    (func $main
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        let globals: Vec<String> = module_op
+            .get_global_ops(&ctx)
+            .iter()
+            .map(|global_op| global_op.with_ctx(&ctx).to_string())
+            .collect();
+        assert_eq!(
+            globals,
+            vec![
+                "wasm.global mut si32 = i32.const 1048576",
+                "wasm.global si64 = i64.const -5",
+            ]
+        );
+    }
+
+    #[test]
+    fn import_globals() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (import "env" "base" (global $base i32))
+    (import "host" "counter" (global $counter (mut i64)))
+    (global $g (mut i32) (global.get $base))
+    (start $main)
+    (func $main
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        let import_globals: Vec<String> = module_op
+            .get_import_globals(&ctx)
+            .iter()
+            .map(|import_global| {
+                format!(
+                    "{}.{}: {}{}",
+                    import_global.module,
+                    import_global.name,
+                    if import_global.mutable { "mut " } else { "" },
+                    import_global.ty.with_ctx(&ctx)
+                )
+            })
+            .collect();
+        assert_eq!(
+            import_globals,
+            vec!["env.base: si32", "host.counter: mut si64"]
+        );
+        let globals: Vec<String> = module_op
+            .get_global_ops(&ctx)
+            .iter()
+            .map(|global_op| global_op.with_ctx(&ctx).to_string())
+            .collect();
+        assert_eq!(globals, vec!["wasm.global mut si32 = global.get 0"]);
+    }
+
+    #[test]
+    fn passive_data_segments() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (memory 1)
+    (data (i32.const 16) "active")
+    (data "passive")
+    (start $main)
+    (func $main
+        i32.const 32
+        i32.const 1
+        i32.const 4
+        memory.init 1
+        data.drop 1
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(module_op.get_data_ops(&ctx).len(), 1);
+        assert_eq!(
+            module_op.get_passive_data(&ctx),
+            vec![Vec::new(), b"passive".to_vec()]
+        );
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        let ops: Vec<String> = main_func
+            .op_iter(&ctx)
+            .skip(3)
+            .map(|op| op.with_ctx(&ctx).to_string())
+            .collect();
+        assert_eq!(
+            ops,
+            vec!["wasm.memory.init 0 1", "wasm.data.drop 1", "wasm.return"]
+        );
+    }
+
+    #[test]
+    fn custom_sections_preserved() {
+        let mut ctx = Context::default();
+        let mut wasm = wat::parse_str(
+            r#"
+(module
+    (start $main)
+    (func $main
+        return)
+)"#,
+        )
+        .unwrap();
+        // append the custom sections (id 0, size, name, bytes)
+        for (name, bytes) in [
+            ("producers", b"rustc".as_slice()),
+            ("target_features", b"+"),
+        ] {
+            wasm.push(0);
+            wasm.push((1 + name.len() + bytes.len()) as u8);
+            wasm.push(name.len() as u8);
+            wasm.extend_from_slice(name.as_bytes());
+            wasm.extend_from_slice(bytes);
+        }
+        let config = WasmFrontendConfig::default();
+        config.register(&mut ctx);
+        let (module_op, _) = parse_module_with_report(&mut ctx, &wasm, &config).unwrap();
+        assert_eq!(
+            module_op.get_custom_sections(&ctx),
+            vec![
+                ("producers".to_string(), b"rustc".to_vec()),
+                ("target_features".to_string(), b"+".to_vec())
+            ]
+        );
+        assert_eq!(
+            module_op.get_custom_section(&ctx, "producers"),
+            Some(b"rustc".to_vec())
+        );
+        assert_eq!(module_op.get_custom_section(&ctx, "name"), None);
+    }
+
+    #[test]
+    fn table_get_and_set() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (table 2 funcref)
+    (elem (i32.const 0) $a $b)
+    (start $main)
+    (func $a
+        return)
+    (func $b
+        return)
+    (func $main
+        i32.const 0
+        i32.const 1
+        table.get 0
+        table.set 0
+        return)
+)"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        let ops: Vec<String> = main_func
+            .op_iter(&ctx)
+            .map(|op| op.with_ctx(&ctx).to_string())
+            .collect();
+        assert_eq!(
+            ops,
+            vec![
+                "wasm.const 0x0: si32",
+                "wasm.const 0x1: si32",
+                "wasm.table.get 0",
+                "wasm.table.set 0",
+                "wasm.return"
+            ]
+        );
+    }
+
+    const WAT_WITH_SIGN_EXT: &str = r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 255
+        i32.extend8_s
+        drop
+        i64.const 4294967295
+        i64.extend32_s
+        drop
+        return)
+)"#;
+
+    #[test]
+    fn translate_registers_dialects() {
+        let mut ctx = Context::default();
+        let source = wat::parse_str(
+            r#"
+(module
+    (start $main)
+    (func $main
+        return)
+)
+"#,
+        )
+        .unwrap();
+        let module_op = translate(&mut ctx, &source, &WasmFrontendConfig::default()).unwrap();
+        assert!(module_op.get_func(&ctx, &FuncSym::from("main")).is_some());
+    }
+
+    #[test]
+    fn verify_module_reports_all_violations() {
+        let mut ctx = Context::default();
+        let source = wat::parse_str(
+            r#"
+(module
+    (import "env" "log" (func $log (param i32)))
+    (start $main)
+    (func $main
+        i32.const 1
+        call $log
+        return)
+)
+"#,
+        )
+        .unwrap();
+        let module_op = translate(&mut ctx, &source, &WasmFrontendConfig::default()).unwrap();
+        assert!(module_op.verify_module(&ctx).is_ok());
+        {
+            let mut op = module_op.get_operation().deref_mut(&mut ctx);
+            op.attributes.insert(
+                ModuleOp::ATTR_KEY_START_FUNC_SYM,
+                StringAttr::create("missing".to_string()),
+            );
+            op.attributes.insert(
+                ModuleOp::ATTR_KEY_IMPORT_FUNC_MODULES,
+                VecAttr::create(vec![]),
+            );
+        }
+        let errors = module_op.verify_module(&ctx).unwrap_err();
+        assert_eq!(errors.len(), 2, "{errors:?}");
+    }
+
+    #[test]
+    fn sign_extension_rejected_when_off() {
+        let config = WasmFrontendConfig {
+            sign_extension: false,
+            ..Default::default()
+        };
+        let mut ctx = Context::default();
+        let err = parse_wat(&mut ctx, WAT_WITH_SIGN_EXT, &config).err();
+        assert!(
+            matches!(err.as_ref().map(WasmError::unlocated),
+                Some(WasmError::InvalidWebAssembly { message, .. })
+                if message.contains("sign extension")),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn sign_extension_ops() {
+        let mut ctx = Context::default();
+        let (module_op, _) =
+            parse_wat(&mut ctx, WAT_WITH_SIGN_EXT, &WasmFrontendConfig::default()).unwrap();
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        let ops: Vec<_> = main_func.op_iter(&ctx).collect();
+        assert!(ops[1]
+            .deref(&ctx)
+            .get_op(&ctx)
+            .downcast_ref::<I32Extend8SOp>()
+            .is_some());
+        assert!(ops[4]
+            .deref(&ctx)
+            .get_op(&ctx)
+            .downcast_ref::<I64Extend32SOp>()
+            .is_some());
+    }
+
+    const WAT_WITH_TAIL_CALL: &str = r#"
+(module
+    (start $main)
+    (func $count (param i32) (result i32)
+        local.get 0
+        i32.eqz
+        if (result i32)
+            i32.const 0
+        else
+            local.get 0
+            i32.const 1
+            i32.sub
+            return_call $count
+        end)
+    (func $main
+        i32.const 3
+        return_call $count)
+)"#;
+
+    #[test]
+    fn tail_call_rejected_by_default() {
+        let mut ctx = Context::default();
+        let err = parse_wat(&mut ctx, WAT_WITH_TAIL_CALL, &WasmFrontendConfig::default()).err();
+        assert!(
+            matches!(err.as_ref().map(WasmError::unlocated),
+                Some(WasmError::InvalidWebAssembly { message, .. })
+                if message.contains("tail call")),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn tail_call_ops() {
+        let config = WasmFrontendConfig {
+            tail_call: true,
+            ..Default::default()
+        };
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(&mut ctx, WAT_WITH_TAIL_CALL, &config).unwrap();
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        let ops: Vec<_> = main_func.op_iter(&ctx).collect();
+        let return_call_op = ops[1].deref(&ctx).get_op(&ctx).downcast::<ReturnCallOp>();
+        assert_eq!(
+            return_call_op.map(|op| op.get_func_index(&ctx)).ok(),
+            Some(FuncIndex::from(0))
+        );
+    }
+
+    const WAT_WITH_MULTI_MEMORY: &str = r#"
+(module
+    (memory 1)
+    (memory $scratch 1)
+    (start $main)
+    (func $main
+        i32.const 0
+        i32.load $scratch offset=4
+        drop
+        return)
+)
+"#;
+
+    #[test]
+    fn multi_memory_rejected_by_default() {
+        let mut ctx = Context::default();
+        let err = parse_wat(
+            &mut ctx,
+            WAT_WITH_MULTI_MEMORY,
+            &WasmFrontendConfig::default(),
+        )
+        .err();
+        assert!(
+            matches!(&err, Some(WasmError::InvalidWebAssembly { message, .. })
+                if message.contains("multiple memories")),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn multi_memory_access() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            WAT_WITH_MULTI_MEMORY,
+            &WasmFrontendConfig {
+                multi_memory: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(module_op.get_memory_limits(&ctx).len(), 2);
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        let ops: Vec<_> = main_func.op_iter(&ctx).collect();
+        let load_op = *ops[1]
+            .deref(&ctx)
+            .get_op(&ctx)
+            .downcast::<LoadOp>()
+            .ok()
+            .unwrap();
+        assert_eq!(
+            load_op.get_memarg(&ctx),
+            MemArg {
+                offset: 4,
+                align: 2,
+                memory: MemoryIndex::from(1),
+            }
+        );
+        assert_eq!(
+            load_op.with_ctx(&ctx).to_string(),
+            "wasm.load I32 1 offset=4"
+        );
+    }
+
+    #[test]
+    fn memory_access_memarg() {
+        let mut ctx = Context::default();
+        let (module_op, _) = parse_wat(
+            &mut ctx,
+            r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 0
+        i32.load8_u offset=4
+        i32.const 0
+        i64.load offset=8 align=4
+        i64.store
+        return)
+)
+"#,
+            &WasmFrontendConfig::default(),
+        )
+        .unwrap();
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        let ops: Vec<_> = main_func.op_iter(&ctx).collect();
+        let load8_op = ops[1].deref(&ctx).get_op(&ctx).downcast::<I32Load8UOp>();
+        assert_eq!(
+            load8_op.map(|op| op.get_memarg(&ctx)).ok(),
+            Some(MemArg {
+                offset: 4,
+                align: 0,
+                memory: MemoryIndex::from(0),
+            })
+        );
+        let load_op = ops[3].deref(&ctx).get_op(&ctx).downcast::<LoadOp>();
+        assert_eq!(
+            load_op.map(|op| op.get_memarg(&ctx)).ok(),
+            Some(MemArg {
+                offset: 8,
+                align: 2,
+                memory: MemoryIndex::from(0),
+            })
+        );
+        let store_op = ops[4].deref(&ctx).get_op(&ctx).downcast::<StoreOp>();
+        assert_eq!(
+            store_op.map(|op| op.get_memarg(&ctx)).ok(),
+            Some(MemArg::natural(8))
+        );
+    }
+}
@@ -1,25 +1,86 @@
-use ozk_ozk_dialect::attributes::i32_attr;
-use ozk_ozk_dialect::attributes::i64_attr;
-use ozk_ozk_dialect::types::i32_type;
-use ozk_ozk_dialect::types::i64_type;
-use ozk_wasm_dialect::ops::AddOp;
+use ozk_wasm_dialect::ops::BinaryArithOp;
 use ozk_wasm_dialect::ops::BlockOp;
 use ozk_wasm_dialect::ops::BrIfOp;
 use ozk_wasm_dialect::ops::BrOp;
+use ozk_wasm_dialect::ops::BrTableOp;
+use ozk_wasm_dialect::ops::CallIndirectOp;
 use ozk_wasm_dialect::ops::CallOp;
 use ozk_wasm_dialect::ops::ConstantOp;
+use ozk_wasm_dialect::ops::DataDropOp;
+use ozk_wasm_dialect::ops::DropOp;
 use ozk_wasm_dialect::ops::GlobalGetOp;
 use ozk_wasm_dialect::ops::GlobalSetOp;
+use ozk_wasm_dialect::ops::I32ClzOp;
+use ozk_wasm_dialect::ops::I32CtzOp;
+use ozk_wasm_dialect::ops::I32EqOp;
 use ozk_wasm_dialect::ops::I32EqzOp;
+use ozk_wasm_dialect::ops::I32Extend16SOp;
+use ozk_wasm_dialect::ops::I32Extend8SOp;
+use ozk_wasm_dialect::ops::I32GeSOp;
+use ozk_wasm_dialect::ops::I32GeUOp;
+use ozk_wasm_dialect::ops::I32GtSOp;
+use ozk_wasm_dialect::ops::I32GtUOp;
+use ozk_wasm_dialect::ops::I32LeSOp;
+use ozk_wasm_dialect::ops::I32LeUOp;
+use ozk_wasm_dialect::ops::I32Load16SOp;
+use ozk_wasm_dialect::ops::I32Load16UOp;
+use ozk_wasm_dialect::ops::I32Load8SOp;
+use ozk_wasm_dialect::ops::I32Load8UOp;
+use ozk_wasm_dialect::ops::I32LtSOp;
+use ozk_wasm_dialect::ops::I32LtUOp;
+use ozk_wasm_dialect::ops::I32NeOp;
+use ozk_wasm_dialect::ops::I32PopcntOp;
+use ozk_wasm_dialect::ops::I64ClzOp;
+use ozk_wasm_dialect::ops::I64CtzOp;
+use ozk_wasm_dialect::ops::I64EqOp;
+use ozk_wasm_dialect::ops::I64EqzOp;
+use ozk_wasm_dialect::ops::I64Extend16SOp;
+use ozk_wasm_dialect::ops::I64Extend32SOp;
+use ozk_wasm_dialect::ops::I64Extend8SOp;
+use ozk_wasm_dialect::ops::I64GeSOp;
+use ozk_wasm_dialect::ops::I64GeUOp;
+use ozk_wasm_dialect::ops::I64GtSOp;
+use ozk_wasm_dialect::ops::I64GtUOp;
+use ozk_wasm_dialect::ops::I64LeSOp;
+use ozk_wasm_dialect::ops::I64LeUOp;
+use ozk_wasm_dialect::ops::I64Load16SOp;
+use ozk_wasm_dialect::ops::I64Load16UOp;
+use ozk_wasm_dialect::ops::I64Load32SOp;
+use ozk_wasm_dialect::ops::I64Load32UOp;
+use ozk_wasm_dialect::ops::I64Load8SOp;
+use ozk_wasm_dialect::ops::I64Load8UOp;
+use ozk_wasm_dialect::ops::I64LtSOp;
+use ozk_wasm_dialect::ops::I64LtUOp;
+use ozk_wasm_dialect::ops::I64NeOp;
+use ozk_wasm_dialect::ops::I64PopcntOp;
+use ozk_wasm_dialect::ops::IfOp;
+use ozk_wasm_dialect::ops::LoadOp;
 use ozk_wasm_dialect::ops::LocalGetOp;
 use ozk_wasm_dialect::ops::LocalSetOp;
 use ozk_wasm_dialect::ops::LocalTeeOp;
 use ozk_wasm_dialect::ops::LoopOp;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::ops::MemoryGrowOp;
+use ozk_wasm_dialect::ops::MemoryInitOp;
+use ozk_wasm_dialect::ops::MemorySizeOp;
+use ozk_wasm_dialect::ops::ReturnCallIndirectOp;
+use ozk_wasm_dialect::ops::ReturnCallOp;
 use ozk_wasm_dialect::ops::ReturnOp;
+use ozk_wasm_dialect::ops::SelectOp;
+use ozk_wasm_dialect::ops::StoreOp;
+use ozk_wasm_dialect::ops::TableGetOp;
+use ozk_wasm_dialect::ops::TableSetOp;
+use ozk_wasm_dialect::ops::UnreachableOp;
 use ozk_wasm_dialect::types::from_block_type;
+use ozk_wasm_dialect::types::from_val_type;
+use ozk_wasm_dialect::types::BinaryOpcode;
+use ozk_wasm_dialect::types::MemArg;
 use pliron::context::Context;
+use pliron::context::Ptr;
 use pliron::op::Op;
+use pliron::r#type::TypeObj;
 use wasmparser::BlockType;
+use wasmparser::ValType;
 
 use crate::func_builder::FuncBuilder;
 use crate::func_builder::FuncBuilderError;
@@ -35,15 +96,13 @@ impl<'a> OpBuilder<'a> {
     }
 
     pub fn i32const(&mut self, ctx: &mut Context, value: i32) -> Result<(), FuncBuilderError> {
-        let val = i32_attr(ctx, value);
-        let op = ConstantOp::new_unlinked(ctx, val).get_operation();
+        let op = ConstantOp::new_i32_unlinked(ctx, value).get_operation();
         self.fbuilder.push(ctx, op)?;
         Ok(())
     }
 
     pub fn i64const(&mut self, ctx: &mut Context, value: i64) -> Result<(), FuncBuilderError> {
-        let val = i64_attr(ctx, value);
-        let op = ConstantOp::new_unlinked(ctx, val).get_operation();
+        let op = ConstantOp::new_i64_unlinked(ctx, value).get_operation();
         self.fbuilder.push(ctx, op)?;
         Ok(())
     }
@@ -54,12 +113,55 @@ impl<'a> OpBuilder<'a> {
         Ok(())
     }
 
+    pub fn call_indirect(
+        &mut self,
+        ctx: &mut Context,
+        type_index: u32,
+        table_index: u32,
+        func_type: Ptr<TypeObj>,
+    ) -> Result<(), FuncBuilderError> {
+        let op =
+            CallIndirectOp::new_unlinked(ctx, type_index.into(), table_index.into(), func_type);
+        self.fbuilder.push(ctx, op.get_operation())
+    }
+
+    pub fn return_call(
+        &mut self,
+        ctx: &mut Context,
+        func_index: u32,
+    ) -> Result<(), FuncBuilderError> {
+        let op = ReturnCallOp::new_unlinked(ctx, func_index.into()).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn return_call_indirect(
+        &mut self,
+        ctx: &mut Context,
+        type_index: u32,
+        table_index: u32,
+        func_type: Ptr<TypeObj>,
+    ) -> Result<(), FuncBuilderError> {
+        let op = ReturnCallIndirectOp::new_unlinked(
+            ctx,
+            type_index.into(),
+            table_index.into(),
+            func_type,
+        );
+        self.fbuilder.push(ctx, op.get_operation())
+    }
+
     pub fn ret(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
         let op = ReturnOp::new_unlinked(ctx).get_operation();
         self.fbuilder.push(ctx, op)?;
         Ok(())
     }
 
+    pub fn unreachable(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = UnreachableOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)?;
+        Ok(())
+    }
+
     pub fn bloop(
         &mut self,
         ctx: &mut Context,
@@ -82,6 +184,21 @@ impl<'a> OpBuilder<'a> {
         Ok(())
     }
 
+    pub fn bif(
+        &mut self,
+        ctx: &mut Context,
+        block_type: &BlockType,
+    ) -> Result<(), FuncBuilderError> {
+        let ty = from_block_type(ctx, block_type);
+        let op = IfOp::new_unlinked(ctx, ty).get_operation();
+        self.fbuilder.push(ctx, op)?;
+        Ok(())
+    }
+
+    pub fn belse(&mut self, _ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        self.fbuilder.push_else()
+    }
+
     pub fn end(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
         self.fbuilder.push_end(ctx)
     }
@@ -104,6 +221,53 @@ impl<'a> OpBuilder<'a> {
         self.fbuilder.push(ctx, op.get_operation())
     }
 
+    pub fn memory_size(&mut self, ctx: &mut Context, mem: u32) -> Result<(), FuncBuilderError> {
+        let op = MemorySizeOp::new_unlinked(ctx, mem.into());
+        self.fbuilder.push(ctx, op.get_operation())
+    }
+
+    pub fn memory_grow(&mut self, ctx: &mut Context, mem: u32) -> Result<(), FuncBuilderError> {
+        let op = MemoryGrowOp::new_unlinked(ctx, mem.into());
+        self.fbuilder.push(ctx, op.get_operation())
+    }
+
+    pub fn memory_init(
+        &mut self,
+        ctx: &mut Context,
+        mem: u32,
+        data_index: u32,
+    ) -> Result<(), FuncBuilderError> {
+        let op = MemoryInitOp::new_unlinked(ctx, mem.into(), data_index.into());
+        self.fbuilder.push(ctx, op.get_operation())
+    }
+
+    pub fn data_drop(
+        &mut self,
+        ctx: &mut Context,
+        data_index: u32,
+    ) -> Result<(), FuncBuilderError> {
+        let op = DataDropOp::new_unlinked(ctx, data_index.into());
+        self.fbuilder.push(ctx, op.get_operation())
+    }
+
+    pub fn table_get(
+        &mut self,
+        ctx: &mut Context,
+        table_index: u32,
+    ) -> Result<(), FuncBuilderError> {
+        let op = TableGetOp::new_unlinked(ctx, table_index.into());
+        self.fbuilder.push(ctx, op.get_operation())
+    }
+
+    pub fn table_set(
+        &mut self,
+        ctx: &mut Context,
+        table_index: u32,
+    ) -> Result<(), FuncBuilderError> {
+        let op = TableSetOp::new_unlinked(ctx, table_index.into());
+        self.fbuilder.push(ctx, op.get_operation())
+    }
+
     pub fn local_get(
         &mut self,
         ctx: &mut Context,
@@ -132,8 +296,37 @@ impl<'a> OpBuilder<'a> {
     }
 
     pub fn i32add(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
-        let ty = i32_type(ctx);
-        let op = AddOp::new_unlinked(ctx, ty).get_operation();
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Add).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32sub(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Sub).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32mul(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Mul).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn drop(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = DropOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn select(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = SelectOp::new_unlinked(ctx, None).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn typed_select(
+        &mut self,
+        ctx: &mut Context,
+        ty: &ValType,
+    ) -> Result<(), FuncBuilderError> {
+        let ty = from_val_type(ctx, ty);
+        let op = SelectOp::new_unlinked(ctx, Some(ty)).get_operation();
         self.fbuilder.push(ctx, op)
     }
 
@@ -142,46 +335,393 @@ impl<'a> OpBuilder<'a> {
         self.fbuilder.push(ctx, op)
     }
 
-    pub fn i32wrapi64(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i32eq(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32EqOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32ne(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32NeOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32lts(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32LtSOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32ltu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32LtUOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32gts(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32GtSOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i32and(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i32gtu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32GtUOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i32geu(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i32les(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32LeSOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32leu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32LeUOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32ges(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32GeSOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32geu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32GeUOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32divs(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::DivS).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32divu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::DivU).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32rems(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::RemS).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32remu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::RemU).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32and(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::And).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32or(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Or).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32xor(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Xor).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64and(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::And).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64or(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::Or).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64xor(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::Xor).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32shl(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Shl).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32shrs(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::ShrS).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32shru(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::ShrU).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64shl(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::Shl).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64shrs(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::ShrS).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64shru(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::ShrU).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32rotl(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Rotl).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32rotr(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Rotr).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64rotl(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::Rotl).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64rotr(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::Rotr).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32extend8s(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32Extend8SOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32extend16s(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32Extend16SOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64extend8s(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64Extend8SOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64extend16s(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64Extend16SOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64extend32s(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64Extend32SOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32clz(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32ClzOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32ctz(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32CtzOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32popcnt(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I32PopcntOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64clz(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64ClzOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64ctz(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64CtzOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64popcnt(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64PopcntOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn load(
+        &mut self,
+        ctx: &mut Context,
+        ty: MemAccessOpValueType,
+        memarg: MemArg,
+    ) -> Result<(), FuncBuilderError> {
+        let op = LoadOp::new_unlinked_with_memarg(ctx, ty, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn store(
+        &mut self,
+        ctx: &mut Context,
+        ty: MemAccessOpValueType,
+        memarg: MemArg,
+    ) -> Result<(), FuncBuilderError> {
+        let op = StoreOp::new_unlinked_with_memarg(ctx, ty, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32load8s(&mut self, ctx: &mut Context, memarg: MemArg) -> Result<(), FuncBuilderError> {
+        let op = I32Load8SOp::new_unlinked_with_memarg(ctx, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32load8u(&mut self, ctx: &mut Context, memarg: MemArg) -> Result<(), FuncBuilderError> {
+        let op = I32Load8UOp::new_unlinked_with_memarg(ctx, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32load16s(
+        &mut self,
+        ctx: &mut Context,
+        memarg: MemArg,
+    ) -> Result<(), FuncBuilderError> {
+        let op = I32Load16SOp::new_unlinked_with_memarg(ctx, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i32load16u(
+        &mut self,
+        ctx: &mut Context,
+        memarg: MemArg,
+    ) -> Result<(), FuncBuilderError> {
+        let op = I32Load16UOp::new_unlinked_with_memarg(ctx, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64load8s(&mut self, ctx: &mut Context, memarg: MemArg) -> Result<(), FuncBuilderError> {
+        let op = I64Load8SOp::new_unlinked_with_memarg(ctx, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64load8u(&mut self, ctx: &mut Context, memarg: MemArg) -> Result<(), FuncBuilderError> {
+        let op = I64Load8UOp::new_unlinked_with_memarg(ctx, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64load16s(
+        &mut self,
+        ctx: &mut Context,
+        memarg: MemArg,
+    ) -> Result<(), FuncBuilderError> {
+        let op = I64Load16SOp::new_unlinked_with_memarg(ctx, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64load16u(
+        &mut self,
+        ctx: &mut Context,
+        memarg: MemArg,
+    ) -> Result<(), FuncBuilderError> {
+        let op = I64Load16UOp::new_unlinked_with_memarg(ctx, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64load32s(
+        &mut self,
+        ctx: &mut Context,
+        memarg: MemArg,
+    ) -> Result<(), FuncBuilderError> {
+        let op = I64Load32SOp::new_unlinked_with_memarg(ctx, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64load32u(
+        &mut self,
+        ctx: &mut Context,
+        memarg: MemArg,
+    ) -> Result<(), FuncBuilderError> {
+        let op = I64Load32UOp::new_unlinked_with_memarg(ctx, memarg).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
     pub fn i64add(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
-        let ty = i64_type(ctx);
-        let op = AddOp::new_unlinked(ctx, ty).get_operation();
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::Add).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64sub(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::Sub).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64mul(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::Mul).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64divs(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::DivS).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64divu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::DivU).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64rems(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::RemS).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64remu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = BinaryArithOp::new_i64_unlinked(ctx, BinaryOpcode::RemU).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64eqz(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64EqzOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64eq(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64EqOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64geu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64GeUOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64lts(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64LtSOp::new_unlinked(ctx).get_operation();
         self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64eqz(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64ltu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64LtUOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64eq(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64gts(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64GtSOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
+    }
+
+    pub fn i64gtu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64GtUOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64and(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64les(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64LeSOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64geu(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64leu(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64LeUOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64ne(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64ges(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64GeSOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
-    pub fn i64extendi32u(&mut self, ctx: &mut Context) {
-        todo!();
+    pub fn i64ne(&mut self, ctx: &mut Context) -> Result<(), FuncBuilderError> {
+        let op = I64NeOp::new_unlinked(ctx).get_operation();
+        self.fbuilder.push(ctx, op)
     }
 
     // pub fn call(&mut self, ctx: &mut Context, func_index: u32) {
@@ -190,14 +730,6 @@ impl<'a> OpBuilder<'a> {
     //     });
     // }
 
-    pub fn nop(&mut self, ctx: &mut Context) {
-        todo!();
-    }
-
-    pub fn unreachable(&mut self, ctx: &mut Context) {
-        todo!();
-    }
-
     pub fn br_if(
         &mut self,
         ctx: &mut Context,
@@ -211,4 +743,15 @@ impl<'a> OpBuilder<'a> {
         let op = BrOp::new_unlinked(ctx, relative_depth.into());
         self.fbuilder.push(ctx, op.get_operation())
     }
+
+    pub fn br_table(
+        &mut self,
+        ctx: &mut Context,
+        relative_depths: Vec<u32>,
+        default_relative_depth: u32,
+    ) -> Result<(), FuncBuilderError> {
+        let targets = relative_depths.into_iter().map(Into::into).collect();
+        let op = BrTableOp::new_unlinked(ctx, targets, default_relative_depth.into());
+        self.fbuilder.push(ctx, op.get_operation())
+    }
 }
@@ -0,0 +1,194 @@
+//! Mapping of the Wasm linear memory (little-endian, byte-addressed) onto the target memory cells.
+//!
+//! The targets store 32-bit values in memory cells ([CELL_BYTES] bytes of the Wasm memory per
+//! cell). Wasm address `a` maps to the cell `a / CELL_BYTES` and the byte `a % CELL_BYTES` of it:
+//! - [ByteLayout::byte_order] defines which byte of the cell value the Wasm byte is.
+//!   With [Endianness::Little] the byte at the lowest address is the least significant one, so an
+//!   aligned `i32.load` reads the cell value as is.
+//! - [ByteLayout::word_order] defines how an `i64` is split into two cells.
+//!   With [Endianness::Little] the low 32 bits are stored in the cell with the lower address.
+//! - 8/16-bit accesses read (and for stores, read-modify-write) the containing cell, shifting by
+//!   [ByteLayout::byte_shift] and masking.
+//!
+//! Load/store lowering and data segment emission must use the same [ByteLayout] so that the
+//! sub-word accesses and `memory.copy` see the same bytes as the Wasm program.
+
+/// Number of Wasm memory bytes stored in a single target memory cell
+pub const CELL_BYTES: u32 = 4;
+
+/// Byte (or word) order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// The least significant part first (at the lowest address)
+    Little,
+    /// The most significant part first (at the lowest address)
+    Big,
+}
+
+/// Partial write of a memory cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellWrite {
+    /// Index of the cell (Wasm address / [CELL_BYTES])
+    pub cell: u32,
+    /// Value to write (only the bits set in `mask` are meaningful)
+    pub value: u32,
+    /// Bits of the cell to overwrite. The rest of the cell must be preserved.
+    pub mask: u32,
+}
+
+/// Byte layout of the Wasm memory in the target memory cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteLayout {
+    /// Order of the Wasm bytes in a cell value
+    pub byte_order: Endianness,
+    /// Order of the 32-bit halves of an `i64` in the cells
+    pub word_order: Endianness,
+}
+
+impl Default for ByteLayout {
+    /// Matches the Wasm semantics: little-endian bytes and words
+    fn default() -> Self {
+        Self {
+            byte_order: Endianness::Little,
+            word_order: Endianness::Little,
+        }
+    }
+}
+
+impl ByteLayout {
+    /// Index of the cell containing the byte at the given Wasm address
+    pub fn cell_index(&self, addr: u32) -> u32 {
+        addr / CELL_BYTES
+    }
+
+    /// Position of the byte at the given Wasm address in its cell
+    pub fn byte_offset(&self, addr: u32) -> u32 {
+        addr % CELL_BYTES
+    }
+
+    /// Bit shift that moves the byte at the given offset (in the cell) to the lowest byte of the
+    /// cell value
+    pub fn byte_shift(&self, byte_offset: u32) -> u32 {
+        match self.byte_order {
+            Endianness::Little => 8 * byte_offset,
+            Endianness::Big => 8 * (CELL_BYTES - 1 - byte_offset),
+        }
+    }
+
+    /// Cell writes storing the bytes at the given Wasm address (e.g. a data segment).
+    /// The first and the last cells are partial if `addr` or the end are not cell-aligned.
+    pub fn pack(&self, addr: u32, bytes: &[u8]) -> Vec<CellWrite> {
+        let mut writes: Vec<CellWrite> = Vec::new();
+        for (byte_addr, byte) in (addr..).zip(bytes) {
+            let cell = self.cell_index(byte_addr);
+            let shift = self.byte_shift(self.byte_offset(byte_addr));
+            let value = u32::from(*byte) << shift;
+            let mask = 0xff << shift;
+            match writes.last_mut() {
+                Some(last) if last.cell == cell => {
+                    last.value |= value;
+                    last.mask |= mask;
+                }
+                _ => writes.push(CellWrite { cell, value, mask }),
+            }
+        }
+        writes
+    }
+
+    /// Bytes stored in the consecutive cells (inverse of [Self::pack] for cell-aligned data)
+    pub fn unpack(&self, cells: &[u32]) -> Vec<u8> {
+        cells
+            .iter()
+            .flat_map(|cell| {
+                (0..CELL_BYTES).map(move |byte_offset| (cell >> self.byte_shift(byte_offset)) as u8)
+            })
+            .collect()
+    }
+
+    /// Split an `i64` into the values of two consecutive cells (the lower address first)
+    pub fn split_i64(&self, value: u64) -> [u32; 2] {
+        let low = value as u32;
+        let high = (value >> 32) as u32;
+        match self.word_order {
+            Endianness::Little => [low, high],
+            Endianness::Big => [high, low],
+        }
+    }
+
+    /// Join two consecutive cells (the lower address first) into an `i64`
+    pub fn join_i64(&self, cells: [u32; 2]) -> u64 {
+        let (low, high) = match self.word_order {
+            Endianness::Little => (cells[0], cells[1]),
+            Endianness::Big => (cells[1], cells[0]),
+        };
+        (u64::from(high) << 32) | u64::from(low)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned_i32_is_identity() {
+        let layout = ByteLayout::default();
+        let writes = layout.pack(8, &0x1234_5678u32.to_le_bytes());
+        assert_eq!(
+            writes,
+            vec![CellWrite {
+                cell: 2,
+                value: 0x1234_5678,
+                mask: 0xffff_ffff
+            }]
+        );
+        assert_eq!(layout.unpack(&[0x1234_5678]), 0x1234_5678u32.to_le_bytes());
+    }
+
+    #[test]
+    fn unaligned_bytes_span_partial_cells() {
+        let layout = ByteLayout::default();
+        let writes = layout.pack(3, &[0xaa, 0xbb, 0xcc]);
+        assert_eq!(
+            writes,
+            vec![
+                CellWrite {
+                    cell: 0,
+                    value: 0xaa00_0000,
+                    mask: 0xff00_0000
+                },
+                CellWrite {
+                    cell: 1,
+                    value: 0x0000_ccbb,
+                    mask: 0x0000_ffff
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn big_endian_bytes() {
+        let layout = ByteLayout {
+            byte_order: Endianness::Big,
+            ..Default::default()
+        };
+        assert_eq!(layout.byte_shift(0), 24);
+        assert_eq!(layout.pack(0, &[1, 2, 3, 4])[0].value, 0x0102_0304);
+        assert_eq!(layout.unpack(&[0x0102_0304]), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn i64_word_order() {
+        let value = 0x1122_3344_5566_7788;
+        let little = ByteLayout::default();
+        assert_eq!(little.split_i64(value), [0x5566_7788, 0x1122_3344]);
+        assert_eq!(little.join_i64(little.split_i64(value)), value);
+        // little word and byte order give the same bytes as Wasm
+        assert_eq!(little.unpack(&little.split_i64(value)), value.to_le_bytes());
+        let big = ByteLayout {
+            word_order: Endianness::Big,
+            ..Default::default()
+        };
+        assert_eq!(big.split_i64(value), [0x1122_3344, 0x5566_7788]);
+        assert_eq!(big.join_i64(big.split_i64(value)), value);
+    }
+}
@@ -0,0 +1,315 @@
+//! Structural diff of the IR before and after a pass, for finding the pass that introduced an op.
+//!
+//! The ops are grouped by the enclosing symbol op (module, function, procedure), so the diff
+//! lists the ops added (`+`), removed (`-`) and moved (`~`) per function.
+//! Wrap the passes with [IrDiffPass::wrap] (or create the pass manager with [new_pass_manager]) and set the [IR_DIFF_ENV_VAR] environment variable to
+//! print the diff after every pass (or after the passes with the name containing the env var value)
+//! to stderr.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Write;
+
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::op_cast;
+use pliron::operation::Operation;
+use pliron::pass::Pass;
+use pliron::pass::PassManager;
+use pliron::with_context::AttachContext;
+
+/// Environment variable enabling the IR diff in [IrDiffPass].
+/// Any value except `1` is a filter: only the passes with the name containing it are diffed.
+pub const IR_DIFF_ENV_VAR: &str = "OZK_IR_DIFF";
+
+/// The ops of every symbol op (e.g. function) of the IR, one line per op
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IrSnapshot {
+    /// Symbol path (e.g. `module_name::main`) -> op lines
+    groups: BTreeMap<String, Vec<String>>,
+}
+
+impl IrSnapshot {
+    /// Snapshot the op with all the nested ops
+    pub fn capture(ctx: &Context, op: Ptr<Operation>) -> Self {
+        let mut snapshot = Self::default();
+        snapshot.capture_op(ctx, op, "", 0);
+        snapshot
+    }
+
+    fn capture_op(&mut self, ctx: &Context, op: Ptr<Operation>, group: &str, indent: usize) {
+        let op_ref = op.deref(ctx);
+        if op_ref.get_num_regions() == 0 {
+            self.push_line(group, indent, op.with_ctx(ctx).to_string());
+            return;
+        }
+        let op_obj = op_ref.get_op(ctx);
+        let (nested_group, nested_indent) = match op_cast::<dyn SymbolOpInterface>(op_obj.as_ref())
+        {
+            Some(symbol_op) if group.is_empty() => (symbol_op.get_symbol_name(ctx), 0),
+            Some(symbol_op) => (format!("{group}::{}", symbol_op.get_symbol_name(ctx)), 0),
+            None => {
+                self.push_line(
+                    group,
+                    indent,
+                    format!("{} {{", op_ref.get_opid().with_ctx(ctx)),
+                );
+                (group.to_string(), indent + 1)
+            }
+        };
+        for region_idx in 0..op_ref.get_num_regions() {
+            let region = op_ref.get_region(region_idx);
+            for block in region.deref(ctx).iter(ctx) {
+                for nested_op in block.deref(ctx).iter(ctx) {
+                    self.capture_op(ctx, nested_op, &nested_group, nested_indent);
+                }
+            }
+        }
+        if nested_group == group {
+            self.push_line(group, indent, "}".to_string());
+        }
+    }
+
+    fn push_line(&mut self, group: &str, indent: usize, line: String) {
+        self.groups
+            .entry(group.to_string())
+            .or_default()
+            .push(format!("{}{line}", "  ".repeat(indent)));
+    }
+
+    /// Diff from this snapshot to the given one
+    pub fn diff(&self, after: &IrSnapshot) -> IrDiff {
+        let mut groups = Vec::new();
+        for (group, before_lines) in &self.groups {
+            match after.groups.get(group) {
+                Some(after_lines) => {
+                    let changes = diff_lines(before_lines, after_lines);
+                    if !changes.is_empty() {
+                        groups.push((group.clone(), GroupDiff::Changed(changes)));
+                    }
+                }
+                None => groups.push((group.clone(), GroupDiff::Removed)),
+            }
+        }
+        for (group, after_lines) in &after.groups {
+            if !self.groups.contains_key(group) {
+                groups.push((group.clone(), GroupDiff::Added(after_lines.len())));
+            }
+        }
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        IrDiff { groups }
+    }
+}
+
+/// A change of an op line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineChange {
+    /// The op is new
+    Added(String),
+    /// The op is gone
+    Removed(String),
+    /// The same op is at another position
+    Moved(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GroupDiff {
+    Added(usize),
+    Removed,
+    Changed(Vec<LineChange>),
+}
+
+/// Structural diff between two [IrSnapshot]s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IrDiff {
+    groups: Vec<(String, GroupDiff)>,
+}
+
+impl IrDiff {
+    /// Returns true if the snapshots have the same ops
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+impl Display for IrDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (group, group_diff) in &self.groups {
+            match group_diff {
+                GroupDiff::Added(op_count) => writeln!(f, "+ @{group} (added, {op_count} ops)")?,
+                GroupDiff::Removed => writeln!(f, "- @{group} (removed)")?,
+                GroupDiff::Changed(changes) => {
+                    writeln!(f, "@{group}")?;
+                    for change in changes {
+                        match change {
+                            LineChange::Added(line) => writeln!(f, "  + {}", line.trim())?,
+                            LineChange::Removed(line) => writeln!(f, "  - {}", line.trim())?,
+                            LineChange::Moved(line) => writeln!(f, "  ~ {}", line.trim())?,
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Line diff (longest common subsequence). The lines that are both removed and added are
+/// reported once as moved.
+fn diff_lines(before: &[String], after: &[String]) -> Vec<LineChange> {
+    let (n, m) = (before.len(), after.len());
+    // lcs[i][j] - LCS length of before[i..] and after[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if before[i] == after[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && before[i] == after[j] {
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            added.push(after[j].clone());
+            j += 1;
+        } else {
+            removed.push(before[i].clone());
+            i += 1;
+        }
+    }
+    let mut changes = Vec::new();
+    for line in removed {
+        match added.iter().position(|added_line| *added_line == line) {
+            Some(pos) => {
+                added.remove(pos);
+                changes.push(LineChange::Moved(line));
+            }
+            None => changes.push(LineChange::Removed(line)),
+        }
+    }
+    changes.extend(added.into_iter().map(LineChange::Added));
+    changes
+}
+
+/// Runs the wrapped pass and, if enabled with [IR_DIFF_ENV_VAR], prints the IR diff to stderr.
+pub struct IrDiffPass {
+    pass: Box<dyn Pass>,
+}
+
+impl IrDiffPass {
+    /// Wrap the pass
+    pub fn wrap(pass: Box<dyn Pass>) -> Box<dyn Pass> {
+        Box::new(Self { pass })
+    }
+
+    fn is_enabled(&self) -> bool {
+        match std::env::var(IR_DIFF_ENV_VAR) {
+            Ok(filter) => filter == "1" || self.pass.name().to_string().contains(filter.as_str()),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Create a pass manager running the given passes in order, each wrapped in [IrDiffPass]
+pub fn new_pass_manager(passes: Vec<Box<dyn Pass>>) -> PassManager {
+    let mut pass_manager = PassManager::new();
+    for pass in passes {
+        pass_manager.add_pass(IrDiffPass::wrap(pass));
+    }
+    pass_manager
+}
+
+impl Pass for IrDiffPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        if !self.is_enabled() {
+            return self.pass.run_on_operation(ctx, op);
+        }
+        let before = IrSnapshot::capture(ctx, op);
+        self.pass.run_on_operation(ctx, op)?;
+        let diff = before.diff(&IrSnapshot::capture(ctx, op));
+        let mut out = format!("IR diff after {} pass:\n", self.pass.name());
+        if diff.is_empty() {
+            out.push_str("  (no changes)\n");
+        } else {
+            let _ = write!(out, "{diff}");
+        }
+        eprint!("{out}");
+        Ok(())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use ozk_frontend_wasm::WasmFrontendConfig;
+    use pliron::op::Op;
+
+    use crate::wasm::canonicalize::WasmCanonicalizePass;
+
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn added_removed_moved_lines() {
+        let changes = diff_lines(&lines(&["a", "b", "c", "d"]), &lines(&["b", "a", "c", "e"]));
+        assert_eq!(
+            changes,
+            vec![
+                LineChange::Moved("b".to_string()),
+                LineChange::Removed("d".to_string()),
+                LineChange::Added("e".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn canonicalize_pass_diff() {
+        let source = wat::parse_str(
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 3
+        i32.const 0
+        i32.add
+        local.set 0
+        local.get 0
+        return)
+)"#,
+        )
+        .unwrap();
+        let mut ctx = Context::default();
+        let frontend_config = WasmFrontendConfig::default();
+        frontend_config.register(&mut ctx);
+        let module_op =
+            ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
+        let before = IrSnapshot::capture(&ctx, module_op.get_operation());
+        WasmCanonicalizePass::default()
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        let diff = before.diff(&IrSnapshot::capture(&ctx, module_op.get_operation()));
+        expect![[r#"
+            @module_name::main
+              - wasm.const 0x0: si32
+              - wasm.i32.add
+              - wasm.local.set 0
+              - wasm.local.get 0
+              + wasm.local.tee 0
+        "#]]
+        .assert_eq(&diff.to_string());
+    }
+}
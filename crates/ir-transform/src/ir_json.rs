@@ -0,0 +1,186 @@
+//! JSON dump of the IR for external tooling (analysis, visualization, diffing).
+//!
+//! Every op is serialized as
+//! `{"op": "<dialect>.<name>", "attributes": {"<key>": <value>, ..}, "regions": [[[<op>, ..], ..], ..]}`
+//! where each region is an array of blocks and each block is an array of ops.
+//! Attribute values are JSON values where the attribute type is known (strings, integers,
+//! indices, bytes and arrays of them) and their textual form otherwise. The attributes are
+//! sorted by key, so the output is stable and can be diffed.
+
+use std::collections::BTreeMap;
+
+use ozk_ozk_dialect::attributes::to_i64_checked;
+use ozk_ozk_dialect::attributes::to_u32_checked;
+use ozk_wasm_dialect::attributes::BytesAttr;
+use ozk_wasm_dialect::attributes::DataIndexAttr;
+use ozk_wasm_dialect::attributes::FuncIndexAttr;
+use ozk_wasm_dialect::attributes::GlobalIndexAttr;
+use ozk_wasm_dialect::attributes::LocalIndexAttr;
+use ozk_wasm_dialect::attributes::MemoryIndexAttr;
+use ozk_wasm_dialect::attributes::TableIndexAttr;
+use ozk_wasm_dialect::attributes::TypeIndexAttr;
+use pliron::attribute::AttrObj;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::dialects::builtin::attributes::StringAttr;
+use pliron::dialects::builtin::attributes::VecAttr;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::operation::Operation;
+use pliron::with_context::AttachContext;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Serialize)]
+struct OpJson {
+    op: String,
+    attributes: BTreeMap<String, Value>,
+    regions: Vec<Vec<Vec<OpJson>>>,
+}
+
+/// Serialize the op (with all the nested ops) as JSON.
+pub fn op_to_json(ctx: &Context, op: Ptr<Operation>) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&op_json(ctx, op))
+}
+
+fn op_json(ctx: &Context, op: Ptr<Operation>) -> OpJson {
+    let op_ref = op.deref(ctx);
+    let attributes = op_ref
+        .attributes
+        .iter()
+        .map(|(key, value)| (key.to_string(), attr_json(ctx, value)))
+        .collect();
+    let regions = (0..op_ref.get_num_regions())
+        .map(|region_idx| {
+            op_ref
+                .get_region(region_idx)
+                .deref(ctx)
+                .iter(ctx)
+                .map(|block| {
+                    block
+                        .deref(ctx)
+                        .iter(ctx)
+                        .map(|nested_op| op_json(ctx, nested_op))
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+    OpJson {
+        op: op_ref.get_opid().with_ctx(ctx).to_string(),
+        attributes,
+        regions,
+    }
+}
+
+/// The attribute as a JSON value, or its textual form if the attribute type is not known
+fn attr_json(ctx: &Context, attr: &AttrObj) -> Value {
+    if let Some(str_attr) = attr.downcast_ref::<StringAttr>() {
+        return Value::String(str_attr.clone().into());
+    }
+    if let Some(vec_attr) = attr.downcast_ref::<VecAttr>() {
+        return Value::Array(vec_attr.0.iter().map(|a| attr_json(ctx, a)).collect());
+    }
+    if attr.downcast_ref::<IntegerAttr>().is_some() {
+        if let Ok(value) = to_i64_checked(ctx, attr) {
+            return Value::from(value);
+        }
+        if let Ok(value) = to_u32_checked(ctx, attr) {
+            return Value::from(value);
+        }
+    }
+    if let Some(bytes_attr) = attr.downcast_ref::<BytesAttr>() {
+        return Value::from(bytes_attr.get_bytes());
+    }
+    if let Some(index) = index_attr_value(attr) {
+        return Value::from(index);
+    }
+    Value::String(attr.with_ctx(ctx).to_string())
+}
+
+fn index_attr_value(attr: &AttrObj) -> Option<u32> {
+    if let Some(a) = attr.downcast_ref::<LocalIndexAttr>() {
+        Some(u32::from(a.get_index()))
+    } else if let Some(a) = attr.downcast_ref::<GlobalIndexAttr>() {
+        Some(u32::from(a.get_index()))
+    } else if let Some(a) = attr.downcast_ref::<FuncIndexAttr>() {
+        Some(u32::from(a.get_index()))
+    } else if let Some(a) = attr.downcast_ref::<TypeIndexAttr>() {
+        Some(u32::from(a.get_index()))
+    } else if let Some(a) = attr.downcast_ref::<TableIndexAttr>() {
+        Some(u32::from(a.get_index()))
+    } else if let Some(a) = attr.downcast_ref::<MemoryIndexAttr>() {
+        Some(u32::from(a.get_index()))
+    } else {
+        attr.downcast_ref::<DataIndexAttr>()
+            .map(|a| u32::from(a.get_index()))
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use ozk_frontend_wasm::WasmFrontendConfig;
+    use pliron::op::Op;
+
+    use super::*;
+
+    #[test]
+    fn wasm_module_to_json() {
+        let source = wat::parse_str(
+            r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 3
+        return)
+)"#,
+        )
+        .unwrap();
+        let mut ctx = Context::default();
+        let frontend_config = WasmFrontendConfig::default();
+        frontend_config.register(&mut ctx);
+        let module_op =
+            ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
+        let json = op_to_json(&ctx, module_op.get_operation()).unwrap();
+        assert!(json.starts_with(r#"{"op":"wasm.module","attributes":{"#));
+        let func_pos = json.find(r#"{"op":"wasm.func""#).unwrap();
+        let const_pos = json.find(r#"{"op":"wasm.const""#).unwrap();
+        let return_pos = json
+            .find(r#"{"op":"wasm.return","attributes":{},"regions":[]}"#)
+            .unwrap();
+        assert!(func_pos < const_pos && const_pos < return_pos);
+        assert!(json.ends_with("]]]}]]]}"));
+    }
+
+    #[test]
+    fn structured_attributes() {
+        let source = wat::parse_str(
+            r#"
+(module
+    (start $main)
+    (func $f (param i32) (result i32)
+        local.get 0
+        return)
+    (func $main
+        i32.const 3
+        call $f
+        drop
+        return)
+)"#,
+        )
+        .unwrap();
+        let mut ctx = Context::default();
+        let frontend_config = WasmFrontendConfig::default();
+        frontend_config.register(&mut ctx);
+        let module_op =
+            ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
+        let json = op_to_json(&ctx, module_op.get_operation()).unwrap();
+        assert!(json.contains(r#""module.func_indices":["f","main"]"#));
+        assert!(json.contains(r#"{"op":"wasm.local.get","attributes":{"local.get.index":0}"#));
+        assert!(json.contains(r#"{"op":"wasm.const","attributes":{"const.value":3}"#));
+        assert!(json.contains(r#"{"op":"wasm.call","attributes":{"call.func_index":0}"#));
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["op"], "wasm.module");
+    }
+}
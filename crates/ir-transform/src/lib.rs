@@ -22,8 +22,13 @@ mod and_minus_8;
 mod locals_to_mem;
 mod save_stack_pub_inputs;
 
+pub mod byte_layout;
+pub mod ir_diff;
+pub mod ir_json;
 pub mod miden;
+pub mod runtime_helpers;
 pub mod triton;
+pub mod u64_emulation;
 pub mod valida;
 pub mod wasm;
 
@@ -10,19 +10,52 @@ use pliron::operation::Operation;
 use pliron::pass::Pass;
 use pliron::rewrite::RewritePatternSet;
 
+use crate::byte_layout::ByteLayout;
+use crate::u64_emulation::U64Emulation;
+
 pub mod call_op_lowering;
 
 use self::arith_op_lowering::ArithOpLowering;
+use self::arith_op_lowering::BitCountOpLowering;
+use self::arith_op_lowering::BitwiseOpLowering;
+use self::arith_op_lowering::CheckedArithOpLowering;
+use self::arith_op_lowering::CmpOpLowering;
+use self::arith_op_lowering::I64CmpOpLowering;
+use self::arith_op_lowering::IntDivOpLowering;
+use self::arith_op_lowering::SelectOpLowering;
+use self::arith_op_lowering::ShiftOpLowering;
+use self::arith_op_lowering::SignExtOpLowering;
+use self::assert_op_lowering::AssertOpLowering;
+use self::clock_op_lowering::ClockOpLowering;
 use self::constant_op_lowering::ConstantOpLowering;
+use self::debug_print_op_lowering::DebugPrintOpLowering;
+use self::drop_op_lowering::DropOpLowering;
+use self::mem_op_lowering::MemOpLowering;
+use self::trap_op_lowering::TrapOpLowering;
 
 mod cf_lowering;
+pub use cf_lowering::MidenControlFlow;
 pub use cf_lowering::WasmToMidenCFLoweringPass;
 
 pub mod arith_op_lowering;
+pub mod assert_op_lowering;
+pub mod clock_op_lowering;
 pub mod constant_op_lowering;
+pub mod debug_print_op_lowering;
+pub mod drop_op_lowering;
+pub mod mem_op_lowering;
+pub mod trap_op_lowering;
 
 #[derive(Default)]
-pub struct WasmToMidenArithLoweringPass;
+pub struct WasmToMidenArithLoweringPass {
+    u64_emulation: U64Emulation,
+}
+
+impl WasmToMidenArithLoweringPass {
+    pub fn new(u64_emulation: U64Emulation) -> Self {
+        Self { u64_emulation }
+    }
+}
 
 impl Pass for WasmToMidenArithLoweringPass {
     fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
@@ -31,7 +64,44 @@ impl Pass for WasmToMidenArithLoweringPass {
         // TODO: set illegal ops
         let mut patterns = RewritePatternSet::default();
         patterns.add(Box::<ConstantOpLowering>::default());
-        patterns.add(Box::<ArithOpLowering>::default());
+        patterns.add(Box::new(ArithOpLowering::new(self.u64_emulation)));
+        patterns.add(Box::<CheckedArithOpLowering>::default());
+        patterns.add(Box::<IntDivOpLowering>::default());
+        patterns.add(Box::<BitwiseOpLowering>::default());
+        patterns.add(Box::<ShiftOpLowering>::default());
+        patterns.add(Box::<SignExtOpLowering>::default());
+        patterns.add(Box::<BitCountOpLowering>::default());
+        patterns.add(Box::<CmpOpLowering>::default());
+        patterns.add(Box::<I64CmpOpLowering>::default());
+        patterns.add(Box::<SelectOpLowering>::default());
+        patterns.add(Box::<DropOpLowering>::default());
+        patterns.add(Box::<ClockOpLowering>::default());
+        patterns.add(Box::<DebugPrintOpLowering>::default());
+        patterns.add(Box::<AssertOpLowering>::default());
+        patterns.add(Box::<TrapOpLowering>::default());
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+/// Lowers the loads and stores (see [MemOpLowering]). Runs after the passes that emit them
+/// (globals, reserved slots, partial loads).
+pub struct WasmToMidenMemLoweringPass {
+    byte_layout: ByteLayout,
+}
+
+impl WasmToMidenMemLoweringPass {
+    pub fn new(byte_layout: ByteLayout) -> Self {
+        Self { byte_layout }
+    }
+}
+
+impl Pass for WasmToMidenMemLoweringPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut target = ConversionTarget::default();
+        target.add_legal_dialect(MIDEN_DIALECT(ctx));
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::new(MemOpLowering::new(self.byte_layout)));
         apply_partial_conversion(ctx, op, target, patterns)?;
         Ok(())
     }
@@ -1,27 +1,935 @@
 use anyhow::anyhow;
+use miden::attributes::FieldElemAttr;
 use ozk_miden_dialect as miden;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::ord_n::Ord16;
 use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::i64_type;
 use ozk_wasm_dialect as wasm;
+use ozk_wasm_dialect::types::BinaryOpcode;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::op::Op;
 use pliron::operation::Operation;
 use pliron::pattern_match::PatternRewriter;
 use pliron::pattern_match::RewritePattern;
+use pliron::with_context::AttachContext;
 
+use crate::u64_emulation::U64Emulation;
+
+/// `(p - 1) / 2` of the Miden field modulus p, the largest non-negative i64
+const HALF_MODULUS: u64 = 0x7fff_ffff_8000_0000;
+
+/// Lowers the `add`, `sub` and `mul` ops. For i32 `add` is a field addition,
+/// `sub` and `mul` are the wrapping u32 Miden ops.
+/// An i64 value is a single field element, the i64 ops are the field ops
+/// emulated according to [U64Emulation]:
+/// - [U64Emulation::Unchecked] - the result wraps around the field modulus;
+/// - [U64Emulation::Checked] - `add` fails if the result wraps around the field modulus,
+/// `sub` fails on borrow and `mul` fails if any of the operands is not a u32 (so that
+/// the product always fits into the field).
 #[derive(Default)]
-pub struct ArithOpLowering {}
+pub struct ArithOpLowering {
+    u64_emulation: U64Emulation,
+}
+
+impl ArithOpLowering {
+    pub fn new(u64_emulation: U64Emulation) -> Self {
+        Self { u64_emulation }
+    }
+
+    fn rewrite_i64(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        opcode: BinaryOpcode,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let is_add = opcode == BinaryOpcode::Add;
+        let is_sub = opcode == BinaryOpcode::Sub;
+        let field_op = if is_add {
+            miden::ops::AddOp::new_unlinked(ctx).get_operation()
+        } else if is_sub {
+            miden::ops::SubOp::new_unlinked(ctx).get_operation()
+        } else {
+            miden::ops::MulOp::new_unlinked(ctx).get_operation()
+        };
+        if self.u64_emulation == U64Emulation::Unchecked {
+            rewriter.replace_op_with(ctx, op, field_op)?;
+            return Ok(());
+        }
+        rewriter.set_insertion_point(op);
+        if is_add {
+            // a b -> a (a + b) -> (a + b) (a + b) a -> (a + b) (a + b >= a)
+            rewriter.insert_before(
+                ctx,
+                miden::ops::DupOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+            )?;
+            rewriter.insert_before(ctx, field_op)?;
+            rewriter.insert_before(
+                ctx,
+                miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+            )?;
+            rewriter.insert_before(
+                ctx,
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+            )?;
+            rewriter.insert_before(ctx, miden::ops::GteOp::new_unlinked(ctx).get_operation())?;
+            let assert_op = miden::ops::AssertOp::new_unlinked(ctx).get_operation();
+            rewriter.replace_op_with(ctx, op, assert_op)?;
+        } else if is_sub {
+            // a b -> a b (a >= b) -> (a - b)
+            rewriter.insert_before(
+                ctx,
+                miden::ops::DupOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+            )?;
+            rewriter.insert_before(
+                ctx,
+                miden::ops::DupOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+            )?;
+            rewriter.insert_before(ctx, miden::ops::GteOp::new_unlinked(ctx).get_operation())?;
+            rewriter.insert_before(ctx, miden::ops::AssertOp::new_unlinked(ctx).get_operation())?;
+            rewriter.replace_op_with(ctx, op, field_op)?;
+        } else {
+            rewriter.insert_before(
+                ctx,
+                miden::ops::U32Assert2Op::new_unlinked(ctx).get_operation(),
+            )?;
+            rewriter.replace_op_with(ctx, op, field_op)?;
+        }
+        Ok(())
+    }
+}
 
 impl RewritePattern for ArithOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(binary_opcode(ctx, op).map_or(false, |opcode| {
+            matches!(
+                opcode,
+                BinaryOpcode::Add | BinaryOpcode::Sub | BinaryOpcode::Mul
+            )
+        }))
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(binary_op) = opop.downcast_ref::<wasm::ops::BinaryArithOp>() else {
+            return Ok(());
+        };
+        let opcode = binary_op.get_opcode(ctx);
+        let op_ty = binary_op.get_type(ctx);
+        if op_ty == i64_type(ctx) {
+            return self.rewrite_i64(ctx, op, opcode, rewriter);
+        }
+        if op_ty != i32_type(ctx) {
+            return Err(anyhow!(
+                "{} is not supported by Miden (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        let miden_op = if opcode == BinaryOpcode::Add {
+            miden::ops::AddOp::new_unlinked(ctx).get_operation()
+        } else if opcode == BinaryOpcode::Sub {
+            miden::ops::U32WrappingSubOp::new_unlinked(ctx).get_operation()
+        } else {
+            miden::ops::U32WrappingMulOp::new_unlinked(ctx).get_operation()
+        };
+        rewriter.replace_op_with(ctx, op, miden_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers the unsigned i32 division ops to the checked u32 Miden ops that fail on zero divisor
+/// (the Wasm trap). Miden has no signed division, `div_s` and `rem_s` divide the absolute values
+/// and negate the result if the signs differ (`div_s`) or the dividend is negative (`rem_s`),
+/// the result is the u32 two's complement. `div_s` fails on `i32::MIN / -1` (the Wasm overflow
+/// trap). The unsigned i64 division converts the operands to the u32 limbs of their two's
+/// complement and calls the `std::math::u64` procedure of the Miden stdlib. It takes the quotient
+/// and the remainder from the advice provider and checks `q * b + r == a` and `r < b` (fails on
+/// zero divisor). The signed i64 division divides the absolute values the same way and negates
+/// the result in the field, `i64::MIN` is out of the supported i64 range (see [i64_sign_ops]).
+#[derive(Default)]
+pub struct IntDivOpLowering {}
+
+impl RewritePattern for IntDivOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(binary_opcode(ctx, op).map_or(false, BinaryOpcode::is_division))
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(binary_op) = opop.downcast_ref::<wasm::ops::BinaryArithOp>() else {
+            return Ok(());
+        };
+        let opcode = binary_op.get_opcode(ctx);
+        let is_unsigned = matches!(opcode, BinaryOpcode::DivU | BinaryOpcode::RemU);
+        if binary_op.get_type(ctx) == i64_type(ctx) {
+            let u64_op = |ctx: &mut Context| {
+                if matches!(opcode, BinaryOpcode::DivU | BinaryOpcode::DivS) {
+                    miden::ops::U64UncheckedDivOp::new_unlinked(ctx).get_operation()
+                } else {
+                    miden::ops::U64UncheckedModOp::new_unlinked(ctx).get_operation()
+                }
+            };
+            if is_unsigned {
+                // a b -> b a -> b a_lo a_hi -> a_lo a_hi b -> a_lo a_hi b_lo b_hi -> c_lo c_hi -> c
+                let mut ops =
+                    vec![miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation()];
+                ops.extend(i64_to_limbs_ops(ctx));
+                ops.extend([
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                ]);
+                ops.extend(i64_to_limbs_ops(ctx));
+                ops.push(u64_op(ctx));
+                ops.extend(i64_from_limbs_ops(ctx));
+                replace_with_ops(ctx, op, rewriter, ops)?;
+                return Ok(());
+            }
+            // a b -> a b sb -> a sb b -> a sb |b| -> |b| sb a -> |b| sb a sa -> |b| sb sa a
+            // -> |b| sb sa |a|
+            let mut ops = i64_sign_ops(ctx);
+            ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation());
+            ops.extend(i64_neg_if_ops(ctx));
+            ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation());
+            ops.extend(i64_sign_ops(ctx));
+            ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation());
+            ops.extend(i64_neg_if_ops(ctx));
+            ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation());
+            if opcode == BinaryOpcode::DivS {
+                // -> |b| |a| sa sb -> |b| |a| s
+                ops.push(miden::ops::NeqOp::new_unlinked(ctx).get_operation());
+            } else {
+                // -> |b| |a| sa sb -> |b| |a| sa
+                ops.push(miden::ops::DropOp::new_unlinked(ctx).get_operation());
+            }
+            // -> s |a| |b| -> s |b| |a| -> s |b| a_lo a_hi -> s |b| a_hi a_lo -> s a_lo a_hi |b|
+            // -> s a_lo a_hi b_lo b_hi -> s c_lo c_hi -> s c -> s r (r = -c if s is 1) -> r
+            ops.extend([
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                miden::ops::U32SplitOp::new_unlinked(ctx).get_operation(),
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                miden::ops::U32SplitOp::new_unlinked(ctx).get_operation(),
+                u64_op(ctx),
+            ]);
+            ops.extend(join_u64_limbs_ops(ctx));
+            ops.extend(i64_neg_if_ops(ctx));
+            ops.extend([
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                miden::ops::DropOp::new_unlinked(ctx).get_operation(),
+            ]);
+            replace_with_ops(ctx, op, rewriter, ops)?;
+            return Ok(());
+        }
+        if binary_op.get_type(ctx) != i32_type(ctx) {
+            return Err(anyhow!(
+                "{} is not supported by Miden (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        if is_unsigned {
+            let miden_op = if opcode == BinaryOpcode::DivU {
+                miden::ops::U32CheckedDivOp::new_unlinked(ctx).get_operation()
+            } else {
+                miden::ops::U32CheckedModOp::new_unlinked(ctx).get_operation()
+            };
+            rewriter.replace_op_with(ctx, op, miden_op)?;
+            return Ok(());
+        }
+        // a b -> a b_mask |b| -> |b| b_mask a -> |b| b_mask a_mask |a|
+        let mut ops = i32_abs_ops(ctx);
+        ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation());
+        ops.extend(i32_abs_ops(ctx));
+        // -> |b| |a| a_mask b_mask
+        ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation());
+        if opcode == BinaryOpcode::DivS {
+            // -> |b| |a| sign_mask -> sign_mask |a| |b| -> sign_mask q
+            ops.extend([
+                miden::ops::U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                miden::ops::U32CheckedDivOp::new_unlinked(ctx).get_operation(),
+            ]);
+            ops.extend(div_s_overflow_check_ops(ctx));
+        } else {
+            // -> |b| |a| a_mask -> a_mask |a| |b| -> a_mask r
+            ops.extend([
+                miden::ops::DropOp::new_unlinked(ctx).get_operation(),
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                miden::ops::U32CheckedModOp::new_unlinked(ctx).get_operation(),
+            ]);
+        }
+        // (v ^ mask) - mask negates v if the mask is all ones
+        ops.extend([
+            miden::ops::DupOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+            miden::ops::U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+            miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        ]);
+        rewriter.set_insertion_point(op);
+        for new_op in ops {
+            rewriter.insert_before(ctx, new_op)?;
+        }
+        let sub_op = miden::ops::U32WrappingSubOp::new_unlinked(ctx).get_operation();
+        rewriter.replace_op_with(ctx, op, sub_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers the overflow-checked ozk ops (see [crate::wasm::checked_arith]) to the checked u32
+/// Miden ops that fail on overflow.
+#[derive(Default)]
+pub struct CheckedArithOpLowering {}
+
+impl RewritePattern for CheckedArithOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<ozk::U32CheckedAddOp>().is_some()
+            || opop.downcast_ref::<ozk::U32CheckedSubOp>().is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let miden_op = if opop.downcast_ref::<ozk::U32CheckedAddOp>().is_some() {
+            miden::ops::U32CheckedAddOp::new_unlinked(ctx).get_operation()
+        } else {
+            miden::ops::U32CheckedSubOp::new_unlinked(ctx).get_operation()
+        };
+        rewriter.replace_op_with(ctx, op, miden_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers the i32 bitwise ops to the checked u32 Miden ops.
+/// The i64 operands are converted to the u32 limbs of their two's complement,
+/// the limbs are combined pairwise and the result is converted back.
+#[derive(Default)]
+pub struct BitwiseOpLowering {}
+
+impl RewritePattern for BitwiseOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(binary_opcode(ctx, op).map_or(false, BinaryOpcode::is_bitwise))
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(binary_op) = opop.downcast_ref::<wasm::ops::BinaryArithOp>() else {
+            return Ok(());
+        };
+        let opcode = binary_op.get_opcode(ctx);
+        let u32_op = |ctx: &mut Context| {
+            if opcode == BinaryOpcode::And {
+                miden::ops::U32CheckedAndOp::new_unlinked(ctx).get_operation()
+            } else if opcode == BinaryOpcode::Or {
+                miden::ops::U32CheckedOrOp::new_unlinked(ctx).get_operation()
+            } else {
+                miden::ops::U32CheckedXorOp::new_unlinked(ctx).get_operation()
+            }
+        };
+        if binary_op.get_type(ctx) == i64_type(ctx) {
+            // a b -> a b_lo b_hi -> b_hi b_lo a -> b_hi b_lo a_lo a_hi -> b_hi a_hi a_lo b_lo
+            // -> b_hi a_hi lo -> lo a_hi b_hi -> lo hi
+            let mut ops = i64_to_limbs_ops(ctx);
+            ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation());
+            ops.extend(i64_to_limbs_ops(ctx));
+            ops.extend([
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                u32_op(ctx),
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                u32_op(ctx),
+            ]);
+            ops.extend(i64_from_limbs_ops(ctx));
+            replace_with_ops(ctx, op, rewriter, ops)?;
+            return Ok(());
+        }
+        if binary_op.get_type(ctx) != i32_type(ctx) {
+            return Err(anyhow!(
+                "{} is not supported by Miden (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        let miden_op = u32_op(ctx);
+        rewriter.replace_op_with(ctx, op, miden_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers the i32 `shl`, `shr_u`, `rotl` and `rotr` ops to the checked u32 Miden shifts and
+/// rotations. The shift amount is masked to the low 5 bits first (Wasm semantics),
+/// the Miden ops fail on the amounts above 31.
+/// The i64 shifts and rotations call the `std::math::u64` procedures of the Miden stdlib on the
+/// u32 limbs of the two's complement with the amount masked to the low 6 bits.
+/// Miden has no arithmetic shift, the i64 `shr_s` shifts `!v` for a negative `v`
+/// (`v >> n == !(!v >> n)`) and the non-negative values as is.
+/// The i32 `shr_s` is rejected.
+#[derive(Default)]
+pub struct ShiftOpLowering {}
+
+impl RewritePattern for ShiftOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(binary_opcode(ctx, op).map_or(false, BinaryOpcode::is_shift))
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(binary_op) = opop.downcast_ref::<wasm::ops::BinaryArithOp>() else {
+            return Ok(());
+        };
+        let opcode = binary_op.get_opcode(ctx);
+        if binary_op.get_type(ctx) == i64_type(ctx) {
+            // a n -> a n_lo n_hi -> a n_lo -> a k -> k a
+            let mut ops = vec![
+                miden::ops::U32SplitOp::new_unlinked(ctx).get_operation(),
+                miden::ops::DropOp::new_unlinked(ctx).get_operation(),
+                u32_constant(ctx, 63),
+                miden::ops::U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+            ];
+            if opcode == BinaryOpcode::ShrS {
+                // -> k a s -> k s a -> k s b (b = !a if negative)
+                ops.extend(i64_sign_ops(ctx));
+                ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation());
+                ops.extend(i64_not_if_ops(ctx));
+                // -> k b s -> s b k -> s k b -> s k b_lo b_hi -> s k b_hi b_lo -> s b_lo b_hi k
+                // -> s c_lo c_hi -> s c
+                ops.extend([
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                    miden::ops::U32SplitOp::new_unlinked(ctx).get_operation(),
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                    miden::ops::U64UncheckedShrOp::new_unlinked(ctx).get_operation(),
+                ]);
+                ops.extend(join_u64_limbs_ops(ctx));
+                // -> s r (r = !c if negative) -> r
+                ops.extend(i64_not_if_ops(ctx));
+                ops.extend([
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                    miden::ops::DropOp::new_unlinked(ctx).get_operation(),
+                ]);
+            } else {
+                // -> k a_lo a_hi -> k a_hi a_lo -> a_lo a_hi k -> c_lo c_hi -> c
+                ops.extend(i64_to_limbs_ops(ctx));
+                ops.extend([
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                    if opcode == BinaryOpcode::Shl {
+                        miden::ops::U64UncheckedShlOp::new_unlinked(ctx).get_operation()
+                    } else if opcode == BinaryOpcode::ShrU {
+                        miden::ops::U64UncheckedShrOp::new_unlinked(ctx).get_operation()
+                    } else if opcode == BinaryOpcode::Rotl {
+                        miden::ops::U64UncheckedRotlOp::new_unlinked(ctx).get_operation()
+                    } else {
+                        miden::ops::U64UncheckedRotrOp::new_unlinked(ctx).get_operation()
+                    },
+                ]);
+                ops.extend(i64_from_limbs_ops(ctx));
+            }
+            replace_with_ops(ctx, op, rewriter, ops)?;
+            return Ok(());
+        }
+        if opcode == BinaryOpcode::ShrS || binary_op.get_type(ctx) != i32_type(ctx) {
+            return Err(anyhow!(
+                "{} is not supported by Miden (no arithmetic shift, \
+                 only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        let shift_op = if opcode == BinaryOpcode::Shl {
+            miden::ops::U32CheckedShlOp::new_unlinked(ctx).get_operation()
+        } else if opcode == BinaryOpcode::ShrU {
+            miden::ops::U32CheckedShrOp::new_unlinked(ctx).get_operation()
+        } else if opcode == BinaryOpcode::Rotl {
+            miden::ops::U32CheckedRotlOp::new_unlinked(ctx).get_operation()
+        } else {
+            miden::ops::U32CheckedRotrOp::new_unlinked(ctx).get_operation()
+        };
+        let mask = FieldElemAttr::from_u32(ctx, 31);
+        let mask_op = miden::ops::ConstantOp::new_unlinked(ctx, mask);
+        let and_op = miden::ops::U32CheckedAndOp::new_unlinked(ctx);
+        rewriter.set_insertion_point(op);
+        rewriter.insert_before(ctx, mask_op.get_operation())?;
+        rewriter.insert_before(ctx, and_op.get_operation())?;
+        rewriter.replace_op_with(ctx, op, shift_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers the i32 `extend8_s` and `extend16_s` ops without branching: the low bits are masked
+/// and `(v ^ sign_bit) - sign_bit` (wrapping) fills the high bits with the sign bit.
+/// The i64 values do not fit into a u32 and are not supported.
+#[derive(Default)]
+pub struct SignExtOpLowering {}
+
+impl RewritePattern for SignExtOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<wasm::ops::I32Extend8SOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32Extend16SOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64Extend8SOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64Extend16SOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64Extend32SOp>().is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let (mask, sign_bit) = if opop.downcast_ref::<wasm::ops::I32Extend8SOp>().is_some() {
+            (0xff, 0x80)
+        } else if opop.downcast_ref::<wasm::ops::I32Extend16SOp>().is_some() {
+            (0xffff, 0x8000)
+        } else {
+            return Err(anyhow!(
+                "{} is not supported by Miden (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        };
+        let mask = FieldElemAttr::from_u32(ctx, mask);
+        let sign_bit = FieldElemAttr::from_u32(ctx, sign_bit);
+        let ops = vec![
+            miden::ops::ConstantOp::new_unlinked(ctx, mask.clone()).get_operation(),
+            miden::ops::U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+            miden::ops::ConstantOp::new_unlinked(ctx, sign_bit.clone()).get_operation(),
+            miden::ops::U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+            miden::ops::ConstantOp::new_unlinked(ctx, sign_bit).get_operation(),
+        ];
+        rewriter.set_insertion_point(op);
+        for new_op in ops {
+            rewriter.insert_before(ctx, new_op)?;
+        }
+        let sub_op = miden::ops::U32WrappingSubOp::new_unlinked(ctx);
+        rewriter.replace_op_with(ctx, op, sub_op.get_operation())?;
+        Ok(())
+    }
+}
+
+/// Lowers the i32 `clz`, `ctz` and `popcnt` ops. Miden has no bit counting instructions, so
+/// `popcnt` is the branch-free SWAR sequence (the bits are summed in 2-, 4- and 8-bit fields
+/// and the bytes are added up by the multiplication). `clz` smears the highest set bit to
+/// the right and counts the zeros left, `ctz` counts the ones of `!x & (x - 1)`.
+/// The i64 values do not fit into a u32 and are not supported.
+#[derive(Default)]
+pub struct BitCountOpLowering {}
+
+impl RewritePattern for BitCountOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<wasm::ops::I32ClzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32CtzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32PopcntOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64ClzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64CtzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64PopcntOp>().is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let mut ops = if opop.downcast_ref::<wasm::ops::I32ClzOp>().is_some() {
+            let mut ops = Vec::new();
+            for shift in [1, 2, 4, 8, 16] {
+                ops.extend(vec![
+                    miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+                    u32_constant(ctx, shift),
+                    miden::ops::U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+                    miden::ops::U32CheckedOrOp::new_unlinked(ctx).get_operation(),
+                ]);
+            }
+            ops.extend(vec![
+                u32_constant(ctx, u32::MAX),
+                miden::ops::U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+            ]);
+            ops
+        } else if opop.downcast_ref::<wasm::ops::I32CtzOp>().is_some() {
+            vec![
+                miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+                u32_constant(ctx, 1),
+                miden::ops::U32WrappingSubOp::new_unlinked(ctx).get_operation(),
+                miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                u32_constant(ctx, u32::MAX),
+                miden::ops::U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+                miden::ops::U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+            ]
+        } else if opop.downcast_ref::<wasm::ops::I32PopcntOp>().is_some() {
+            Vec::new()
+        } else {
+            return Err(anyhow!(
+                "{} is not supported by Miden (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        };
+        ops.extend(popcnt_ops(ctx));
+        #[allow(clippy::unwrap_used)] // popcnt_ops is not empty
+        let last_op = ops.pop().unwrap();
+        rewriter.set_insertion_point(op);
+        for new_op in ops {
+            rewriter.insert_before(ctx, new_op)?;
+        }
+        rewriter.replace_op_with(ctx, op, last_op)?;
+        Ok(())
+    }
+}
+
+/// The opcode of a [wasm::ops::BinaryArithOp]
+fn binary_opcode(ctx: &Context, op: Ptr<Operation>) -> Option<BinaryOpcode> {
+    op.deref(ctx)
+        .get_op(ctx)
+        .downcast_ref::<wasm::ops::BinaryArithOp>()
+        .map(|binary_op| binary_op.get_opcode(ctx))
+}
+
+/// Ops that join the u32 limbs on top of the stack (the high limb on top) into a u64 value,
+/// the limbs must be of a non-negative i64: `lo hi -> hi * 2^32 + lo`
+fn join_u64_limbs_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let limb_base = FieldElemAttr::from_u64(ctx, 1 << 32);
+    vec![
+        miden::ops::ConstantOp::new_unlinked(ctx, limb_base).get_operation(),
+        miden::ops::MulOp::new_unlinked(ctx).get_operation(),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Ops that push the sign of the i64 on top of the stack: `v -> v s`, `s` is 1 if `v` is negative.
+/// A negative i64 wraps around the field modulus p (see [FieldElemAttr::from_integer_attr]),
+/// the values above `(p - 1) / 2` are negative. The i64 values must be in
+/// `[-(p - 1) / 2, (p - 1) / 2]`, the larger ones are ambiguous.
+fn i64_sign_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let half_modulus = FieldElemAttr::from_u64(ctx, HALF_MODULUS);
+    vec![
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        miden::ops::ConstantOp::new_unlinked(ctx, half_modulus).get_operation(),
+        miden::ops::GtOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Ops that add `(p - 1) / 2` to the i64 on top of the stack, the non-negative values land in
+/// `[(p - 1) / 2, p - 1]` and the negative ones wrap around to `[0, (p - 1) / 2)`, so the field
+/// order of such values is the signed order of the i64 values (see [i64_sign_ops])
+fn i64_signed_order_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let half_modulus = FieldElemAttr::from_u64(ctx, HALF_MODULUS);
+    vec![
+        miden::ops::ConstantOp::new_unlinked(ctx, half_modulus).get_operation(),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Ops that replace the i64 on top of the stack with the u32 limbs of its two's complement
+/// (the high limb on top): `v -> lo hi`. A negative `v` is `p + v` in the field,
+/// its two's complement `2^64 + v` is `(p + v - 1) + 2^32`.
+fn i64_to_limbs_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let mut ops = i64_sign_ops(ctx);
+    // v s -> s v -> s (v - s) -> s lo hi -> s hi lo -> lo hi s -> lo (hi + s)
+    ops.extend([
+        miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        miden::ops::SubOp::new_unlinked(ctx).get_operation(),
+        miden::ops::U32SplitOp::new_unlinked(ctx).get_operation(),
+        miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+    ]);
+    ops
+}
+
+/// Ops that replace the u32 limbs of an i64 two's complement on top of the stack (the high limb
+/// on top) with the i64, the inverse of [i64_to_limbs_ops]: `lo hi -> v`
+fn i64_from_limbs_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let limb_base = FieldElemAttr::from_u64(ctx, 1 << 32);
+    // lo hi -> lo hi s -> lo s s hi -> lo s hi s -> lo s (hi - s) -> lo (s + (hi - s) * 2^32)
+    // -> v
+    vec![
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        u32_constant(ctx, 31),
+        miden::ops::U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+        miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        miden::ops::SubOp::new_unlinked(ctx).get_operation(),
+        miden::ops::ConstantOp::new_unlinked(ctx, limb_base).get_operation(),
+        miden::ops::MulOp::new_unlinked(ctx).get_operation(),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Ops that negate the i64 on top of the stack if the flag below it is 1:
+/// `s v -> s (v * (1 - 2 * s))`
+fn i64_neg_if_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+        u32_constant(ctx, 1),
+        miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        miden::ops::SubOp::new_unlinked(ctx).get_operation(),
+        miden::ops::MulOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Ops that replace the i64 on top of the stack with its bitwise not (`-v - 1`) if the flag below
+/// it is 1: `s v -> s (v - s * (2 * v + 1))`
+fn i64_not_if_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+        u32_constant(ctx, 1),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+        miden::ops::MulOp::new_unlinked(ctx).get_operation(),
+        miden::ops::SubOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Insert the `ops` before `op` and replace `op` with the last one
+fn replace_with_ops(
+    ctx: &mut Context,
+    op: Ptr<Operation>,
+    rewriter: &mut dyn PatternRewriter,
+    mut ops: Vec<Ptr<Operation>>,
+) -> Result<(), anyhow::Error> {
+    let last_op = ops
+        .pop()
+        .ok_or_else(|| anyhow!("no ops to replace {} with", op.with_ctx(ctx)))?;
+    rewriter.set_insertion_point(op);
+    for new_op in ops {
+        rewriter.insert_before(ctx, new_op)?;
+    }
+    rewriter.replace_op_with(ctx, op, last_op)?;
+    Ok(())
+}
+
+fn u32_constant(ctx: &mut Context, value: u32) -> Ptr<Operation> {
+    let value = FieldElemAttr::from_u32(ctx, value);
+    miden::ops::ConstantOp::new_unlinked(ctx, value).get_operation()
+}
+
+/// Ops that replace the i32 on top of the stack with its u32 (two's complement) value.
+/// A negative i32 constant wraps around the field modulus (see
+/// [FieldElemAttr::from_integer_attr]), adding 2^32 to the values above `u32::MAX` maps
+/// `p - v` to `2^32 - v`.
+fn i32_to_u32_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let limb_base = FieldElemAttr::from_u64(ctx, 1 << 32);
+    vec![
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        u32_constant(ctx, u32::MAX),
+        miden::ops::GtOp::new_unlinked(ctx).get_operation(),
+        miden::ops::ConstantOp::new_unlinked(ctx, limb_base).get_operation(),
+        miden::ops::MulOp::new_unlinked(ctx).get_operation(),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Ops that replace the i32 on top of the stack with its u32 (two's complement) value with the
+/// sign bit flipped, the unsigned order of such values is the signed order of the i32 values
+fn i32_signed_order_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let mut ops = i32_to_u32_ops(ctx);
+    ops.extend([
+        u32_constant(ctx, 1 << 31),
+        miden::ops::U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+    ]);
+    ops
+}
+
+/// Ops that replace the i32 on top of the stack with its absolute value (u32) and the sign mask
+/// below it (all ones if the value is negative, zero otherwise): `v -> mask |v|`
+fn i32_abs_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    let mut ops = i32_to_u32_ops(ctx);
+    ops.extend([
+        // v -> v (v >> 31) -> v (0 - (v >> 31))
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        u32_constant(ctx, 31),
+        miden::ops::U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+        u32_constant(ctx, 0),
+        miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        miden::ops::U32WrappingSubOp::new_unlinked(ctx).get_operation(),
+        // v mask -> v mask mask -> mask mask v -> mask (v ^ mask) -> mask ((v ^ mask) - mask)
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+        miden::ops::U32CheckedXorOp::new_unlinked(ctx).get_operation(),
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        miden::ops::U32WrappingSubOp::new_unlinked(ctx).get_operation(),
+    ]);
+    ops
+}
+
+/// Ops that fail on the `div_s` overflow, the quotient of the absolute values is 2^31 and the
+/// sign mask is zero (`i32::MIN / -1`): `mask q -> mask q`
+fn div_s_overflow_check_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        u32_constant(ctx, 1 << 31),
+        miden::ops::EqOp::new_unlinked(ctx).get_operation(),
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+        u32_constant(ctx, 0),
+        miden::ops::EqOp::new_unlinked(ctx).get_operation(),
+        miden::ops::MulOp::new_unlinked(ctx).get_operation(),
+        // 1 - overflow
+        u32_constant(ctx, 1),
+        miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        miden::ops::SubOp::new_unlinked(ctx).get_operation(),
+        miden::ops::AssertOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Ops that replace the u32 on top of the stack with the number of its set bits
+fn popcnt_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        // x - ((x >> 1) & 0x55555555)
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        u32_constant(ctx, 1),
+        miden::ops::U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+        u32_constant(ctx, 0x5555_5555),
+        miden::ops::U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+        miden::ops::U32WrappingSubOp::new_unlinked(ctx).get_operation(),
+        // (x & 0x33333333) + ((x >> 2) & 0x33333333)
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        u32_constant(ctx, 0x3333_3333),
+        miden::ops::U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+        miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+        u32_constant(ctx, 2),
+        miden::ops::U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+        u32_constant(ctx, 0x3333_3333),
+        miden::ops::U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+        // (x + (x >> 4)) & 0x0f0f0f0f
+        miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+        u32_constant(ctx, 4),
+        miden::ops::U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+        miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+        u32_constant(ctx, 0x0f0f_0f0f),
+        miden::ops::U32CheckedAndOp::new_unlinked(ctx).get_operation(),
+        // the sum of the bytes ends up in the highest byte
+        u32_constant(ctx, 0x0101_0101),
+        miden::ops::U32WrappingMulOp::new_unlinked(ctx).get_operation(),
+        u32_constant(ctx, 24),
+        miden::ops::U32CheckedShrOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+/// Lowers the i32 comparison ops to the checked u32 Miden comparisons
+/// (`eqz` compares with zero). The signed comparisons map both operands to the u32 two's
+/// complement and flip their sign bits, which turns the signed order into the unsigned one.
+#[derive(Default)]
+pub struct CmpOpLowering {}
+
+impl RewritePattern for CmpOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<wasm::ops::I32EqzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32EqOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32NeOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LtSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LtUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GtSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GtUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LeSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LeUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GeSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GeUOp>().is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let cmp_op = if opop.downcast_ref::<wasm::ops::I32EqzOp>().is_some() {
+            let zero = FieldElemAttr::from_u32(ctx, 0);
+            let zero_op = miden::ops::ConstantOp::new_unlinked(ctx, zero);
+            rewriter.set_insertion_point(op);
+            rewriter.insert_before(ctx, zero_op.get_operation())?;
+            miden::ops::U32CheckedEqOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I32EqOp>().is_some() {
+            miden::ops::U32CheckedEqOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I32NeOp>().is_some() {
+            miden::ops::U32CheckedNeqOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I32LtUOp>().is_some() {
+            miden::ops::U32CheckedLtOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I32GtUOp>().is_some() {
+            miden::ops::U32CheckedGtOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I32LeUOp>().is_some() {
+            miden::ops::U32CheckedLteOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I32GeUOp>().is_some() {
+            miden::ops::U32CheckedGteOp::new_unlinked(ctx).get_operation()
+        } else {
+            let cmp_op = if opop.downcast_ref::<wasm::ops::I32LtSOp>().is_some() {
+                miden::ops::U32CheckedLtOp::new_unlinked(ctx).get_operation()
+            } else if opop.downcast_ref::<wasm::ops::I32GtSOp>().is_some() {
+                miden::ops::U32CheckedGtOp::new_unlinked(ctx).get_operation()
+            } else if opop.downcast_ref::<wasm::ops::I32LeSOp>().is_some() {
+                miden::ops::U32CheckedLteOp::new_unlinked(ctx).get_operation()
+            } else {
+                miden::ops::U32CheckedGteOp::new_unlinked(ctx).get_operation()
+            };
+            // a b -> a b' -> b' a -> b' a' -> a' b'
+            let mut ops = i32_signed_order_ops(ctx);
+            ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation());
+            ops.extend(i32_signed_order_ops(ctx));
+            ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation());
+            rewriter.set_insertion_point(op);
+            for new_op in ops {
+                rewriter.insert_before(ctx, new_op)?;
+            }
+            cmp_op
+        };
+        rewriter.replace_op_with(ctx, op, cmp_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers `select` to `cdrop`. Wasm picks the first value on a non-zero condition,
+/// while `cdrop` picks the top one (the second value) on 1, so the condition
+/// is inverted with `eq.0` which also makes it binary.
+/// An i64 value is a single field element as well, so i64 is lowered the same way.
+#[derive(Default)]
+pub struct SelectOpLowering {}
+
+impl RewritePattern for SelectOpLowering {
     fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
         Ok(op
             .deref(ctx)
             .get_op(ctx)
-            .downcast_ref::<wasm::ops::AddOp>()
+            .downcast_ref::<wasm::ops::SelectOp>()
             .is_some())
     }
 
-    #[allow(clippy::unwrap_used)]
     fn rewrite(
         &self,
         ctx: &mut Context,
@@ -29,15 +937,102 @@ impl RewritePattern for ArithOpLowering {
         rewriter: &mut dyn PatternRewriter,
     ) -> Result<(), anyhow::Error> {
         let opop = &op.deref(ctx).get_op(ctx);
-        if let Some(add_op) = opop.downcast_ref::<wasm::ops::AddOp>() {
-            let add_op_ty = add_op.get_type(ctx);
-            if add_op_ty == i32_type(ctx) {
-                let miden_op = miden::ops::AddOp::new_unlinked(ctx);
-                rewriter.replace_op_with(ctx, op, miden_op.get_operation())?;
-            } else {
-                return Err(anyhow!("only 32-bit integers are supported"));
+        let Some(select_op) = opop.downcast_ref::<wasm::ops::SelectOp>() else {
+            return Ok(());
+        };
+        if let Some(ty) = select_op.get_type(ctx) {
+            if ty != i32_type(ctx) && ty != i64_type(ctx) {
+                return Err(anyhow!(
+                    "{} is not supported by Miden (only integers are supported)",
+                    op.with_ctx(ctx)
+                ));
             }
         }
+        let zero = FieldElemAttr::from_u32(ctx, 0);
+        let zero_op = miden::ops::ConstantOp::new_unlinked(ctx, zero);
+        rewriter.set_insertion_point(op);
+        rewriter.insert_before(ctx, zero_op.get_operation())?;
+        let eq_op = miden::ops::EqOp::new_unlinked(ctx);
+        rewriter.insert_before(ctx, eq_op.get_operation())?;
+        let cdrop_op = miden::ops::CDropOp::new_unlinked(ctx);
+        rewriter.replace_op_with(ctx, op, cdrop_op.get_operation())?;
+        Ok(())
+    }
+}
+
+/// Lowers the i64 comparison ops to the Miden field element comparisons
+/// (`eqz` compares with zero). An i64 value is a single field element in Miden, the
+/// non-negative values keep their order, so the unsigned comparisons hold for them.
+/// The negative i64 values wrap around the field modulus (see
+/// [FieldElemAttr::from_integer_attr]) and stay above the non-negative ones, as in the
+/// two's complement. The signed comparisons add `(p - 1) / 2` to both operands first, which
+/// moves the negative values below the non-negative ones.
+#[derive(Default)]
+pub struct I64CmpOpLowering {}
+
+impl RewritePattern for I64CmpOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<wasm::ops::I64EqzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64EqOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64NeOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64LtSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64LtUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64GtSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64GtUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64LeSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64LeUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64GeSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64GeUOp>().is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let cmp_op = if opop.downcast_ref::<wasm::ops::I64EqzOp>().is_some() {
+            let zero = FieldElemAttr::from_u32(ctx, 0);
+            let zero_op = miden::ops::ConstantOp::new_unlinked(ctx, zero);
+            rewriter.set_insertion_point(op);
+            rewriter.insert_before(ctx, zero_op.get_operation())?;
+            miden::ops::EqOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I64EqOp>().is_some() {
+            miden::ops::EqOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I64NeOp>().is_some() {
+            miden::ops::NeqOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I64LtUOp>().is_some() {
+            miden::ops::LtOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I64GtUOp>().is_some() {
+            miden::ops::GtOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I64LeUOp>().is_some() {
+            miden::ops::LteOp::new_unlinked(ctx).get_operation()
+        } else if opop.downcast_ref::<wasm::ops::I64GeUOp>().is_some() {
+            miden::ops::GteOp::new_unlinked(ctx).get_operation()
+        } else {
+            let cmp_op = if opop.downcast_ref::<wasm::ops::I64LtSOp>().is_some() {
+                miden::ops::LtOp::new_unlinked(ctx).get_operation()
+            } else if opop.downcast_ref::<wasm::ops::I64GtSOp>().is_some() {
+                miden::ops::GtOp::new_unlinked(ctx).get_operation()
+            } else if opop.downcast_ref::<wasm::ops::I64LeSOp>().is_some() {
+                miden::ops::LteOp::new_unlinked(ctx).get_operation()
+            } else {
+                miden::ops::GteOp::new_unlinked(ctx).get_operation()
+            };
+            // a b -> a b' -> b' a -> b' a' -> a' b'
+            let mut ops = i64_signed_order_ops(ctx);
+            ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation());
+            ops.extend(i64_signed_order_ops(ctx));
+            ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation());
+            rewriter.set_insertion_point(op);
+            for new_op in ops {
+                rewriter.insert_before(ctx, new_op)?;
+            }
+            cmp_op
+        };
+        rewriter.replace_op_with(ctx, op, cmp_op)?;
         Ok(())
     }
 }
@@ -0,0 +1,40 @@
+use ozk_miden_dialect as miden;
+use ozk_miden_dialect::attributes::FieldElemAttr;
+use ozk_ozk_dialect as ozk;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+
+/// Miden `assert` expects exactly 1, while any non-zero value passes the check,
+/// so the value is converted with `neq.0` first.
+#[derive(Default)]
+pub struct AssertOpLowering {}
+
+impl RewritePattern for AssertOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(op
+            .deref(ctx)
+            .get_op(ctx)
+            .downcast_ref::<ozk::ops::AssertOp>()
+            .is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let zero = FieldElemAttr::from_u32(ctx, 0);
+        let zero_op = miden::ops::ConstantOp::new_unlinked(ctx, zero);
+        rewriter.insert_before(ctx, zero_op.get_operation())?;
+        let neq_op = miden::ops::NeqOp::new_unlinked(ctx);
+        rewriter.insert_before(ctx, neq_op.get_operation())?;
+        let assert_op = miden::ops::AssertOp::new_unlinked(ctx);
+        rewriter.replace_op_with(ctx, op, assert_op.get_operation())?;
+        Ok(())
+    }
+}
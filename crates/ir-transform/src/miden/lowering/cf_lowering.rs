@@ -1,38 +1,74 @@
+use anyhow::anyhow;
 use bounded_vec::NonEmptyVec;
 use derive_more::From;
+use ozk_miden_dialect::attributes::FieldElemAttr;
 use ozk_miden_dialect::ops as miden;
+use ozk_ozk_dialect::ord_n::Ord16;
 use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::ops::BrIfCondition;
+use pliron::basic_block::BasicBlock;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::dialect_conversion::apply_partial_conversion;
 use pliron::dialect_conversion::ConversionTarget;
 use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
 use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
 use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
 use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
 use pliron::pass::Pass;
 use pliron::pattern_match::PatternRewriter;
 use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
 use pliron::rewrite::RewritePatternSet;
+use pliron::with_context::AttachContext;
+
+/// How the Wasm blocks, loops and branches are lowered to Miden
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MidenControlFlow {
+    /// Outline the blocks and loops into procs, as for the targets without structured
+    /// control flow
+    #[default]
+    Outline,
+    /// Keep the control flow structured: inline the blocks, turn the loops into `while.true`
+    /// and run the ops after a branch in `if.true` only if the branch is not taken. No extra
+    /// procs and calls, but the branch flag is kept on the stack and checked at the end of
+    /// every construct a branch exits.
+    Structured,
+}
 
 #[derive(Default)]
-pub struct WasmToMidenCFLoweringPass;
+pub struct WasmToMidenCFLoweringPass {
+    control_flow: MidenControlFlow,
+}
+
+impl WasmToMidenCFLoweringPass {
+    pub fn new(control_flow: MidenControlFlow) -> Self {
+        Self { control_flow }
+    }
+}
 
 impl Pass for WasmToMidenCFLoweringPass {
     fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
         let target = ConversionTarget::default();
         // TODO: set illegal ops
         let mut patterns = RewritePatternSet::default();
-        patterns.add(Box::<ControlFlowLowering>::default());
+        patterns.add(Box::new(ControlFlowLowering {
+            control_flow: self.control_flow,
+        }));
         apply_partial_conversion(ctx, op, target, patterns)?;
         Ok(())
     }
 }
 /// Converts Wasm module into Miden program
 /// converting Wasm blocks/loops and branching ops into Miden functions
-#[derive(Default)]
-struct ControlFlowLowering;
+/// (or Miden control flow constructs, see [MidenControlFlow])
+struct ControlFlowLowering {
+    control_flow: MidenControlFlow,
+}
 
 impl RewritePattern for ControlFlowLowering {
     fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
@@ -67,15 +103,23 @@ impl RewritePattern for ControlFlowLowering {
             funcs.push(func_op);
         }
         let main_proc_op = miden::ProcOp::new_unlinked(ctx, "ozk_miden_main_proc");
-        let start_func_call_op =
-            miden::ExecOp::new_unlinked(ctx, module_op.get_start_func_sym(ctx));
+        let start_func_sym = module_op.get_start_func_sym(ctx)?;
+        let start_func_call_op = miden::ExecOp::new_unlinked(ctx, start_func_sym);
         start_func_call_op
             .get_operation()
             .insert_at_back(main_proc_op.get_entry_block(ctx), ctx);
         let prog_op = miden::ProgramOp::new(ctx, main_proc_op);
         // TODO: make a new pass for module->prog conversion
         // plus, handle there imports and all other module stuff
+        let structured = self.control_flow == MidenControlFlow::Structured;
         for func_op in funcs {
+            lower_returns(ctx, &func_op, structured, rewriter)?;
+            if structured {
+                lower_branches(ctx, &func_op, rewriter)?;
+            } else {
+                reject_branches(ctx, &func_op)?;
+            }
+            lower_if_ops(ctx, func_op.get_entry_block(ctx), rewriter)?;
             let root_proc_op = miden::ProcOp::new_unlinked(ctx, &func_op.get_symbol_name(ctx));
             let root_proc_bb = root_proc_op.get_entry_block(ctx);
             prog_op.add_proc_op(ctx, root_proc_op);
@@ -97,16 +141,8 @@ impl RewritePattern for ControlFlowLowering {
                         prog_op.add_proc_op(ctx, *proc_op);
                     }
                 }
-                if op.downcast_ref::<wasm::ReturnOp>().is_some() {
-                    // return in the entry block means that the rest of the ops
-                    // are unreachable and can be removed
-                    rewriter.erase_op(ctx, op.get_operation())?;
-                    // TODO: erase the rest of the ops in this block
-                    break;
-                } else {
-                    op.get_operation().unlink(ctx);
-                    op.get_operation().insert_at_back(root_proc_bb, ctx);
-                };
+                op.get_operation().unlink(ctx);
+                op.get_operation().insert_at_back(root_proc_bb, ctx);
             }
             rewriter.erase_op(ctx, func_op.get_operation())?;
         }
@@ -115,6 +151,434 @@ impl RewritePattern for ControlFlowLowering {
     }
 }
 
+/// Control-flow construct enclosing the ops being lowered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ControlFlowContext {
+    Proc,
+    Block,
+    Loop,
+    If,
+}
+
+/// Removes the `wasm.return` ops (and the unreachable ops that follow them) that return at the
+/// end of the proc: the ones in the function body and the ones in the blocks/loops that are the
+/// last op of the function (falling through the block end reaches the proc end).
+/// Miden procs can only return at their `end`, so the early returns from the nested blocks/loops
+/// are either kept for [lower_branches] (`keep_early_returns`) or not supported.
+fn lower_returns(
+    ctx: &mut Context,
+    func_op: &wasm::FuncOp,
+    keep_early_returns: bool,
+    rewriter: &mut dyn PatternRewriter,
+) -> Result<(), anyhow::Error> {
+    let mut cf_stack = vec![ControlFlowContext::Proc];
+    lower_returns_in_block(
+        ctx,
+        &func_op.get_symbol_name(ctx),
+        func_op.get_entry_block(ctx),
+        true,
+        keep_early_returns,
+        &mut cf_stack,
+        rewriter,
+    )
+}
+
+fn lower_returns_in_block(
+    ctx: &mut Context,
+    func_name: &str,
+    block: Ptr<BasicBlock>,
+    ends_proc: bool,
+    keep_early_returns: bool,
+    cf_stack: &mut Vec<ControlFlowContext>,
+    rewriter: &mut dyn PatternRewriter,
+) -> Result<(), anyhow::Error> {
+    let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+    for (idx, op) in ops.iter().enumerate() {
+        let op_obj = op.deref(ctx).get_op(ctx);
+        if op_obj.downcast_ref::<wasm::ReturnOp>().is_some() {
+            if !ends_proc && keep_early_returns {
+                return Ok(());
+            }
+            if !ends_proc {
+                return Err(anyhow!(
+                    "early return from {} in function {func_name} is not supported by Miden",
+                    cf_stack
+                        .iter()
+                        .map(|cf| format!("{cf:?}").to_lowercase())
+                        .collect::<Vec<_>>()
+                        .join(" > ")
+                ));
+            }
+            for unreachable_op in &ops[idx..] {
+                rewriter.erase_op(ctx, *unreachable_op)?;
+            }
+            return Ok(());
+        }
+        let is_last = idx + 1 == ops.len();
+        let nested = if let Some(block_op) = op_obj.downcast_ref::<wasm::BlockOp>() {
+            vec![(ControlFlowContext::Block, block_op.get_block(ctx))]
+        } else if let Some(loop_op) = op_obj.downcast_ref::<wasm::LoopOp>() {
+            vec![(ControlFlowContext::Loop, loop_op.get_block(ctx))]
+        } else if let Some(if_op) = op_obj.downcast_ref::<wasm::IfOp>() {
+            vec![
+                (ControlFlowContext::If, if_op.get_then_block(ctx)),
+                (ControlFlowContext::If, if_op.get_else_block(ctx)),
+            ]
+        } else {
+            Vec::new()
+        };
+        for (cf, nested_block) in nested {
+            cf_stack.push(cf);
+            lower_returns_in_block(
+                ctx,
+                func_name,
+                nested_block,
+                ends_proc && is_last,
+                keep_early_returns,
+                cf_stack,
+                rewriter,
+            )?;
+            cf_stack.pop();
+        }
+    }
+    Ok(())
+}
+
+/// The blocks and loops are outlined into procs in the [MidenControlFlow::Outline] mode and a
+/// proc can't be branched out of, so the branches are only lowered in the
+/// [MidenControlFlow::Structured] mode.
+fn reject_branches(ctx: &Context, func_op: &wasm::FuncOp) -> Result<(), anyhow::Error> {
+    let mut branch_ops = Vec::new();
+    func_op
+        .get_operation()
+        .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+            let opop = op.deref(ctx).get_op(ctx);
+            if opop.downcast_ref::<wasm::BrOp>().is_some()
+                || opop.downcast_ref::<wasm::BrIfOp>().is_some()
+                || opop.downcast_ref::<wasm::BrTableOp>().is_some()
+            {
+                branch_ops.push(opop.get_opid().with_ctx(ctx).to_string());
+            }
+            WalkResult::Advance
+        });
+    if let Some(branch_op) = branch_ops.first() {
+        return Err(anyhow!(
+            "{branch_op} in function {} is only supported by Miden with the structured control \
+             flow (MidenControlFlow::Structured)",
+            func_op.get_symbol_name(ctx)
+        ));
+    }
+    Ok(())
+}
+
+/// Lowers the branches keeping the control flow structured (see [MidenControlFlow::Structured]).
+/// The ops after a `br_if` move to the else block of an `if.true` (the then block pushes the
+/// branch flag). A taken branch pushes the flag: the number of the constructs (blocks, loops,
+/// ifs) it exits, counting the one of the branch, so 1 is a branch to the end of the innermost
+/// block (or the start of the innermost loop). After a construct that leaves the flag, it is
+/// decremented and the following ops run only if it's 0, i.e. the branch targeted this
+/// construct or no branch was taken and the ops that fell through the construct end pushed 0.
+/// The blocks are inlined and the loops become `while.true` repeating while the body leaves
+/// the flag 1. An early `wasm.return` is a branch to the function body.
+/// The flag is on the top of the stack, so the operand stack at a branch must hold only the
+/// values passed to the target (as in the rustc output), the other values would be left
+/// below the flag.
+fn lower_branches(
+    ctx: &mut Context,
+    func_op: &wasm::FuncOp,
+    rewriter: &mut dyn PatternRewriter,
+) -> Result<(), anyhow::Error> {
+    let body = func_op.get_entry_block(ctx);
+    if lower_branches_in_block(ctx, body, 0, rewriter)?.is_some() {
+        // the branches left to the function body end are returns
+        miden::DropOp::new_unlinked(ctx)
+            .get_operation()
+            .insert_at_back(body, ctx);
+    }
+    Ok(())
+}
+
+/// Lowers the branches in the ops of the block, `depth` is the number of the constructs around
+/// the block in the function. Returns the maximum flag if the lowered ops leave the branch flag
+/// on the stack (on every path), or `None` if there are no branches out of the nested
+/// constructs.
+fn lower_branches_in_block(
+    ctx: &mut Context,
+    block: Ptr<BasicBlock>,
+    depth: u32,
+    rewriter: &mut dyn PatternRewriter,
+) -> Result<Option<u32>, anyhow::Error> {
+    let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+    for (idx, op) in ops.iter().copied().enumerate() {
+        let rest = &ops[idx + 1..];
+        let op_obj = op.deref(ctx).get_op(ctx);
+        let branch_flag = if let Some(br_op) = op_obj.downcast_ref::<wasm::BrOp>() {
+            Some(u32::from(br_op.get_relative_depth(ctx)) + 1)
+        } else if op_obj.downcast_ref::<wasm::ReturnOp>().is_some() {
+            Some(depth + 1)
+        } else {
+            None
+        };
+        if let Some(flag) = branch_flag {
+            // the ops after the branch are unreachable
+            for unreachable_op in &ops[idx..] {
+                rewriter.erase_op(ctx, *unreachable_op)?;
+            }
+            push_flag(ctx, block, flag);
+            return Ok(Some(flag));
+        }
+        if let Some(br_if_op) = op_obj.downcast_ref::<wasm::BrIfOp>() {
+            let flag = u32::from(br_if_op.get_relative_depth(ctx)) + 1;
+            let cmp_op = match br_if_op.get_condition(ctx) {
+                // an i64 value is a single field element as well
+                BrIfCondition::I32NonZero | BrIfCondition::I64NonZero => {
+                    miden::NeqOp::new_unlinked(ctx).get_operation()
+                }
+                BrIfCondition::I32Zero | BrIfCondition::I64Zero => {
+                    miden::EqOp::new_unlinked(ctx).get_operation()
+                }
+            };
+            let zero = FieldElemAttr::from_u32(ctx, 0);
+            miden::ConstantOp::new_unlinked(ctx, zero)
+                .get_operation()
+                .insert_before(ctx, op);
+            cmp_op.insert_before(ctx, op);
+            let if_op = miden::IfOp::new_unlinked(ctx);
+            push_flag(ctx, if_op.get_then_block(ctx), flag);
+            let rest_flag = guard_ops(ctx, rest, if_op.get_else_block(ctx), depth, rewriter)?;
+            if_op.get_operation().insert_before(ctx, op);
+            rewriter.erase_op(ctx, op)?;
+            return Ok(Some(flag.max(rest_flag)));
+        }
+        let construct_flag = if let Some(block_op) = op_obj.downcast_ref::<wasm::BlockOp>() {
+            check_no_params(ctx, block_op.get_type(ctx), "block")?;
+            let body = block_op.get_block(ctx);
+            let body_flag = lower_branches_in_block(ctx, body, depth + 1, rewriter)?;
+            let body_ops: Vec<Ptr<Operation>> = body.deref(ctx).iter(ctx).collect();
+            for body_op in body_ops {
+                body_op.unlink(ctx);
+                body_op.insert_before(ctx, op);
+            }
+            rewriter.erase_op(ctx, op)?;
+            body_flag
+        } else if let Some(loop_op) = op_obj.downcast_ref::<wasm::LoopOp>() {
+            check_no_params(ctx, loop_op.get_type(ctx), "loop")?;
+            let body = loop_op.get_block(ctx);
+            let body_flag = lower_branches_in_block(ctx, body, depth + 1, rewriter)?;
+            let body_ops: Vec<Ptr<Operation>> = body.deref(ctx).iter(ctx).collect();
+            if body_flag.is_some() {
+                // the flag of the previous iteration (0 for the first one) is dropped on entry,
+                // the flag of the last iteration is left after the loop (never 1)
+                let while_op = miden::WhileOp::new_unlinked(ctx);
+                let while_body = while_op.get_body_block(ctx);
+                miden::DropOp::new_unlinked(ctx)
+                    .get_operation()
+                    .insert_at_back(while_body, ctx);
+                for body_op in body_ops {
+                    body_op.unlink(ctx);
+                    body_op.insert_at_back(while_body, ctx);
+                }
+                let one = FieldElemAttr::from_u32(ctx, 1);
+                for repeat_op in [
+                    miden::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+                    miden::ConstantOp::new_unlinked(ctx, one.clone()).get_operation(),
+                    miden::EqOp::new_unlinked(ctx).get_operation(),
+                ] {
+                    repeat_op.insert_at_back(while_body, ctx);
+                }
+                let zero = FieldElemAttr::from_u32(ctx, 0);
+                for loop_entry_op in [
+                    miden::ConstantOp::new_unlinked(ctx, zero).get_operation(),
+                    miden::ConstantOp::new_unlinked(ctx, one).get_operation(),
+                    while_op.get_operation(),
+                ] {
+                    loop_entry_op.insert_before(ctx, op);
+                }
+            } else {
+                // no branches to the loop start, the body runs once
+                for body_op in body_ops {
+                    body_op.unlink(ctx);
+                    body_op.insert_before(ctx, op);
+                }
+            }
+            rewriter.erase_op(ctx, op)?;
+            body_flag
+        } else if let Some(if_op) = op_obj.downcast_ref::<wasm::IfOp>() {
+            check_no_params(ctx, if_op.get_type(ctx), "if")?;
+            let then_block = if_op.get_then_block(ctx);
+            let else_block = if_op.get_else_block(ctx);
+            let then_flag = lower_branches_in_block(ctx, then_block, depth + 1, rewriter)?;
+            let else_flag = lower_branches_in_block(ctx, else_block, depth + 1, rewriter)?;
+            if then_flag.is_some() || else_flag.is_some() {
+                for (flag, branch_block) in [(then_flag, then_block), (else_flag, else_block)] {
+                    if flag.is_none() {
+                        push_flag(ctx, branch_block, 0);
+                    }
+                }
+            }
+            then_flag.max(else_flag)
+        } else {
+            None
+        };
+        let Some(construct_flag) = construct_flag else {
+            continue;
+        };
+        let after_construct = |ctx: &mut Context, new_op: Ptr<Operation>| match rest.first() {
+            Some(next_op) => new_op.insert_before(ctx, *next_op),
+            None => new_op.insert_at_back(block, ctx),
+        };
+        if construct_flag <= 1 {
+            // every branch targets the construct, the following ops always run
+            let drop_op = miden::DropOp::new_unlinked(ctx).get_operation();
+            after_construct(ctx, drop_op);
+            continue;
+        }
+        // decrement the flag (unless it's 0) and run the following ops only if it's 0
+        let guard_op = miden::IfOp::new_unlinked(ctx);
+        let guard_block = guard_op.get_then_block(ctx);
+        miden::DropOp::new_unlinked(ctx)
+            .get_operation()
+            .insert_at_back(guard_block, ctx);
+        let zero = FieldElemAttr::from_u32(ctx, 0);
+        let guard_ops_list = [
+            miden::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+            miden::ConstantOp::new_unlinked(ctx, zero.clone()).get_operation(),
+            miden::NeqOp::new_unlinked(ctx).get_operation(),
+            miden::SubOp::new_unlinked(ctx).get_operation(),
+            miden::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+            miden::ConstantOp::new_unlinked(ctx, zero).get_operation(),
+            miden::EqOp::new_unlinked(ctx).get_operation(),
+            guard_op.get_operation(),
+        ];
+        for new_op in guard_ops_list {
+            after_construct(ctx, new_op);
+        }
+        let rest_flag = guard_ops(ctx, rest, guard_block, depth, rewriter)?;
+        return Ok(Some((construct_flag - 1).max(rest_flag)));
+    }
+    Ok(None)
+}
+
+/// Moves the ops to the back of the block and lowers the branches in them, the lowered ops
+/// always leave the branch flag (0 if there are no branches). Returns the maximum flag.
+fn guard_ops(
+    ctx: &mut Context,
+    ops: &[Ptr<Operation>],
+    block: Ptr<BasicBlock>,
+    depth: u32,
+    rewriter: &mut dyn PatternRewriter,
+) -> Result<u32, anyhow::Error> {
+    for op in ops {
+        op.unlink(ctx);
+        op.insert_at_back(block, ctx);
+    }
+    let flag = lower_branches_in_block(ctx, block, depth, rewriter)?;
+    if flag.is_none() {
+        push_flag(ctx, block, 0);
+    }
+    Ok(flag.unwrap_or(0))
+}
+
+fn push_flag(ctx: &mut Context, block: Ptr<BasicBlock>, flag: u32) {
+    let flag = FieldElemAttr::from_u32(ctx, flag);
+    miden::ConstantOp::new_unlinked(ctx, flag)
+        .get_operation()
+        .insert_at_back(block, ctx);
+}
+
+/// The params of a construct are below the branch flag of the branches to its start
+fn check_no_params(ctx: &Context, ty: Ptr<TypeObj>, construct: &str) -> Result<(), anyhow::Error> {
+    let has_params = ty
+        .deref(ctx)
+        .downcast_ref::<FunctionType>()
+        .map_or(false, |func_type| !func_type.get_inputs().is_empty());
+    if has_params {
+        return Err(anyhow!("{construct} with params is not supported by Miden"));
+    }
+    Ok(())
+}
+
+/// Replaces the `wasm.if` ops (including the ones nested in blocks/loops, other ifs and the
+/// Miden constructs of [lower_branches]) with
+/// `miden.if.true`. Any non-zero i32 is true in Wasm, while Miden expects 0 or 1, so the
+/// condition is converted with `neq.0`. Miden blocks can't be empty, so an `if` with only
+/// the else branch becomes `eq.0` and the else ops in the then block, and an `if` without
+/// any ops just drops the condition.
+fn lower_if_ops(
+    ctx: &mut Context,
+    block: Ptr<BasicBlock>,
+    rewriter: &mut dyn PatternRewriter,
+) -> Result<(), anyhow::Error> {
+    let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+    for op in ops {
+        let op_obj = op.deref(ctx).get_op(ctx);
+        if let Some(block_op) = op_obj.downcast_ref::<wasm::BlockOp>() {
+            lower_if_ops(ctx, block_op.get_block(ctx), rewriter)?;
+            continue;
+        }
+        if let Some(loop_op) = op_obj.downcast_ref::<wasm::LoopOp>() {
+            lower_if_ops(ctx, loop_op.get_block(ctx), rewriter)?;
+            continue;
+        }
+        if let Some(miden_if_op) = op_obj.downcast_ref::<miden::IfOp>() {
+            lower_if_ops(ctx, miden_if_op.get_then_block(ctx), rewriter)?;
+            lower_if_ops(ctx, miden_if_op.get_else_block(ctx), rewriter)?;
+            continue;
+        }
+        if let Some(while_op) = op_obj.downcast_ref::<miden::WhileOp>() {
+            lower_if_ops(ctx, while_op.get_body_block(ctx), rewriter)?;
+            continue;
+        }
+        let Some(wasm_if_op) = op_obj.downcast_ref::<wasm::IfOp>() else {
+            continue;
+        };
+        let then_block = wasm_if_op.get_then_block(ctx);
+        let else_block = wasm_if_op.get_else_block(ctx);
+        lower_if_ops(ctx, then_block, rewriter)?;
+        lower_if_ops(ctx, else_block, rewriter)?;
+        let then_ops: Vec<Ptr<Operation>> = then_block.deref(ctx).iter(ctx).collect();
+        let else_ops: Vec<Ptr<Operation>> = else_block.deref(ctx).iter(ctx).collect();
+        if then_ops.is_empty() && else_ops.is_empty() {
+            miden::DropOp::new_unlinked(ctx)
+                .get_operation()
+                .insert_before(ctx, op);
+            rewriter.erase_op(ctx, op)?;
+            continue;
+        }
+        let zero = FieldElemAttr::from_u32(ctx, 0);
+        miden::ConstantOp::new_unlinked(ctx, zero)
+            .get_operation()
+            .insert_before(ctx, op);
+        let (cmp_op, then_ops, else_ops) = if then_ops.is_empty() {
+            (
+                miden::EqOp::new_unlinked(ctx).get_operation(),
+                else_ops,
+                then_ops,
+            )
+        } else {
+            (
+                miden::NeqOp::new_unlinked(ctx).get_operation(),
+                then_ops,
+                else_ops,
+            )
+        };
+        cmp_op.insert_before(ctx, op);
+        let miden_if_op = miden::IfOp::new_unlinked(ctx);
+        for (ops, miden_block) in [
+            (then_ops, miden_if_op.get_then_block(ctx)),
+            (else_ops, miden_if_op.get_else_block(ctx)),
+        ] {
+            for branch_op in ops {
+                branch_op.unlink(ctx);
+                branch_op.insert_at_back(miden_block, ctx);
+            }
+        }
+        miden_if_op.get_operation().insert_before(ctx, op);
+        rewriter.erase_op(ctx, op)?;
+    }
+    Ok(())
+}
+
 #[derive(From)]
 enum WasmStructuredOp<'a> {
     Block(&'a wasm::BlockOp),
@@ -0,0 +1,33 @@
+use ozk_miden_dialect as miden;
+use ozk_ozk_dialect as ozk;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+
+/// Miden has no debug output, the value is dropped
+#[derive(Default)]
+pub struct DebugPrintOpLowering {}
+
+impl RewritePattern for DebugPrintOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(op
+            .deref(ctx)
+            .get_op(ctx)
+            .downcast_ref::<ozk::ops::DebugPrintOp>()
+            .is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let drop_op = miden::ops::DropOp::new_unlinked(ctx);
+        rewriter.replace_op_with(ctx, op, drop_op.get_operation())?;
+        Ok(())
+    }
+}
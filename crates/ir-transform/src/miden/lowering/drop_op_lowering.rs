@@ -0,0 +1,34 @@
+use ozk_miden_dialect as miden;
+use ozk_wasm_dialect as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+
+/// Any Wasm value (including i64) is a single field element in Miden,
+/// so `drop` is lowered to `drop` as is.
+#[derive(Default)]
+pub struct DropOpLowering {}
+
+impl RewritePattern for DropOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(op
+            .deref(ctx)
+            .get_op(ctx)
+            .downcast_ref::<wasm::ops::DropOp>()
+            .is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let drop_op = miden::ops::DropOp::new_unlinked(ctx);
+        rewriter.replace_op_with(ctx, op, drop_op.get_operation())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+use anyhow::anyhow;
+use miden::attributes::FieldElemAttr;
+use ozk_miden_dialect as miden;
+use ozk_ozk_dialect::ord_n::Ord16;
+use ozk_wasm_dialect as wasm;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::types::MemArg;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::with_context::AttachContext;
+
+use crate::byte_layout::ByteLayout;
+use crate::byte_layout::Endianness;
+use crate::byte_layout::CELL_BYTES;
+
+/// Lowers `wasm.load`/`wasm.store` to `mem_load`/`mem_store`. The memory cell of the Wasm
+/// bytes `a..a + CELL_BYTES` (`a` cell-aligned) is the first element of the Miden memory word
+/// at the address `a`, so the byte address is used as is. An i64 takes two cells in the
+/// [ByteLayout::word_order]. The accesses must be cell-aligned, the narrower ones are lowered
+/// to the aligned loads by [crate::wasm::partial_loads] beforehand.
+pub struct MemOpLowering {
+    byte_layout: ByteLayout,
+}
+
+impl MemOpLowering {
+    pub fn new(byte_layout: ByteLayout) -> Self {
+        Self { byte_layout }
+    }
+}
+
+impl RewritePattern for MemOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<wasm::ops::LoadOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::StoreOp>().is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let ops = if let Some(load_op) = opop.downcast_ref::<wasm::ops::LoadOp>() {
+            let memarg = load_op.get_memarg(ctx);
+            check_memarg(ctx, op, memarg)?;
+            let mut ops = offset_ops(ctx, memarg);
+            ops.extend(self.load_ops(ctx, load_op.get_value_type(ctx)));
+            ops
+        } else if let Some(store_op) = opop.downcast_ref::<wasm::ops::StoreOp>() {
+            let memarg = store_op.get_memarg(ctx);
+            check_memarg(ctx, op, memarg)?;
+            self.store_ops(ctx, store_op.get_value_type(ctx), memarg)
+        } else {
+            return Ok(());
+        };
+        rewriter.set_insertion_point(op);
+        for new_op in ops {
+            rewriter.insert_before(ctx, new_op)?;
+        }
+        rewriter.erase_op(ctx, op)?;
+        Ok(())
+    }
+}
+
+impl MemOpLowering {
+    /// addr -> value
+    fn load_ops(&self, ctx: &mut Context, ty: MemAccessOpValueType) -> Vec<Ptr<Operation>> {
+        match ty {
+            MemAccessOpValueType::I32 => {
+                vec![miden::ops::MemLoadOp::new_unlinked(ctx).get_operation()]
+            }
+            MemAccessOpValueType::I64 => {
+                // addr -> addr first -> first addr -> first second
+                let mut ops = vec![
+                    miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+                    miden::ops::MemLoadOp::new_unlinked(ctx).get_operation(),
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                    cell_bytes_op(ctx),
+                    miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+                    miden::ops::MemLoadOp::new_unlinked(ctx).get_operation(),
+                ];
+                if self.byte_layout.word_order == Endianness::Big {
+                    ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation());
+                }
+                // low high -> high * 2^32 + low
+                let limb_base = FieldElemAttr::from_u64(ctx, 1 << 32);
+                ops.extend([
+                    miden::ops::ConstantOp::new_unlinked(ctx, limb_base).get_operation(),
+                    miden::ops::MulOp::new_unlinked(ctx).get_operation(),
+                    miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+                ]);
+                ops
+            }
+        }
+    }
+
+    /// addr value ->
+    fn store_ops(
+        &self,
+        ctx: &mut Context,
+        ty: MemAccessOpValueType,
+        memarg: MemArg,
+    ) -> Vec<Ptr<Operation>> {
+        // the address is below the value
+        let mut ops = vec![miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation()];
+        ops.extend(offset_ops(ctx, memarg));
+        match ty {
+            MemAccessOpValueType::I32 => {
+                ops.push(miden::ops::MemStoreOp::new_unlinked(ctx).get_operation());
+            }
+            MemAccessOpValueType::I64 => {
+                // value addr -> addr value -> addr low high
+                ops.extend([
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                    miden::ops::U32SplitOp::new_unlinked(ctx).get_operation(),
+                ]);
+                if self.byte_layout.word_order == Endianness::Big {
+                    // -> addr high low
+                    ops.push(miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation());
+                }
+                // addr first second -> second first addr -> second first addr addr
+                // -> second addr addr first -> second addr first addr -> second addr
+                // -> second addr+4 ->
+                ops.extend([
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                    miden::ops::DupOp::new_unlinked(ctx, Ord16::ST0).get_operation(),
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST2).get_operation(),
+                    miden::ops::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                    miden::ops::MemStoreOp::new_unlinked(ctx).get_operation(),
+                    cell_bytes_op(ctx),
+                    miden::ops::AddOp::new_unlinked(ctx).get_operation(),
+                    miden::ops::MemStoreOp::new_unlinked(ctx).get_operation(),
+                ]);
+            }
+        }
+        ops
+    }
+}
+
+/// The other memories are moved into the memory 0 by [crate::wasm::multi_memory], the
+/// declared alignment must cover a cell
+fn check_memarg(ctx: &Context, op: Ptr<Operation>, memarg: MemArg) -> Result<(), anyhow::Error> {
+    if u32::from(memarg.memory) != 0 {
+        return Err(anyhow!(
+            "{} accesses the memory {}, expected the memories to be moved into the memory 0",
+            op.with_ctx(ctx),
+            memarg.memory
+        ));
+    }
+    if memarg.align < CELL_BYTES.trailing_zeros() {
+        return Err(anyhow!(
+            "{} is not supported by Miden (the unaligned loads and stores of whole cells)",
+            op.with_ctx(ctx)
+        ));
+    }
+    Ok(())
+}
+
+/// addr -> addr + offset, fails if the effective address overflows (out of bounds in Wasm)
+fn offset_ops(ctx: &mut Context, memarg: MemArg) -> Vec<Ptr<Operation>> {
+    if memarg.offset == 0 {
+        return Vec::new();
+    }
+    let offset = FieldElemAttr::from_u32(ctx, memarg.offset);
+    vec![
+        miden::ops::ConstantOp::new_unlinked(ctx, offset).get_operation(),
+        miden::ops::U32CheckedAddOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+fn cell_bytes_op(ctx: &mut Context) -> Ptr<Operation> {
+    let cell_bytes = FieldElemAttr::from_u32(ctx, CELL_BYTES);
+    miden::ops::ConstantOp::new_unlinked(ctx, cell_bytes).get_operation()
+}
@@ -0,0 +1,38 @@
+use ozk_miden_dialect as miden;
+use ozk_miden_dialect::attributes::FieldElemAttr;
+use ozk_ozk_dialect as ozk;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+
+/// Lowers `wasm.unreachable` and `ozk.trap` to a failing `assert` on 0.
+/// Miden (v0.5) `assert` doesn't take an error code, the failure is reported as a failed
+/// assertion at the cycle of the trap.
+#[derive(Default)]
+pub struct TrapOpLowering {}
+
+impl RewritePattern for TrapOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<wasm::UnreachableOp>().is_some()
+            || opop.downcast_ref::<ozk::ops::TrapOp>().is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let zero = FieldElemAttr::from_u32(ctx, 0);
+        let zero_op = miden::ops::ConstantOp::new_unlinked(ctx, zero);
+        rewriter.insert_before(ctx, zero_op.get_operation())?;
+        let assert_op = miden::ops::AssertOp::new_unlinked(ctx);
+        rewriter.replace_op_with(ctx, op, assert_op.get_operation())?;
+        Ok(())
+    }
+}
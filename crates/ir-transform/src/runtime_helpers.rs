@@ -0,0 +1,39 @@
+//! Runtime helpers, the functions the passes insert into the program (not present in the source).
+//! They are part of the trusted code of the compiled program, so the artifact lists the ones
+//! that were included (see `ozk_artifact::RuntimeHelper`).
+
+use crate::wasm::br_propagation::NEXT_BR_PROPAGATION_FUNC_NAME;
+use crate::wasm::memory_size::INIT_MEMORY_SIZE_FUNC_NAME;
+use crate::wasm::memory_size::MEMORY_GROW_FUNC_NAME;
+use crate::wasm::reserved_slots::BR_PROPAGATION_SLOT;
+use crate::wasm::reserved_slots::MEMORY_SIZE_SLOT;
+
+/// A runtime helper function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeHelperDef {
+    /// Symbol of the helper function
+    pub name: &'static str,
+    /// Named reserved slots (see [crate::wasm::reserved_slots]) the helper uses
+    pub reserved_slots: &'static [&'static str],
+}
+
+/// All the runtime helpers the passes can insert
+pub const RUNTIME_HELPERS: &[RuntimeHelperDef] = &[
+    RuntimeHelperDef {
+        name: NEXT_BR_PROPAGATION_FUNC_NAME,
+        reserved_slots: &[BR_PROPAGATION_SLOT],
+    },
+    RuntimeHelperDef {
+        name: INIT_MEMORY_SIZE_FUNC_NAME,
+        reserved_slots: &[MEMORY_SIZE_SLOT],
+    },
+    RuntimeHelperDef {
+        name: MEMORY_GROW_FUNC_NAME,
+        reserved_slots: &[MEMORY_SIZE_SLOT],
+    },
+];
+
+/// Returns the runtime helper with the given function symbol
+pub fn find_runtime_helper(name: &str) -> Option<&'static RuntimeHelperDef> {
+    RUNTIME_HELPERS.iter().find(|helper| helper.name == name)
+}
@@ -2,22 +2,37 @@
 #![allow(clippy::unwrap_used)]
 #![allow(clippy::panic)]
 
+use ozk_frontend_wasm::func_builder::FuncBuilder;
 use ozk_frontend_wasm::WasmFrontendConfig;
+use ozk_ozk_dialect::types::FuncSym;
 use ozk_wasm_dialect as wasm;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::dialects::builtin;
 use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
 use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
 use pliron::operation::Operation;
 use pliron::pass::Pass;
+use pliron::r#type::TypeObj;
 use pliron::with_context::AttachContext;
 
+/// Frontend config with all the optional Wasm features enabled
+fn frontend_config() -> WasmFrontendConfig {
+    WasmFrontendConfig {
+        sign_extension: true,
+        tail_call: true,
+        multi_memory: true,
+        ..Default::default()
+    }
+}
+
 pub fn check_wasm_pass<T: Pass>(pass: &T, wat: &str, expected: expect_test::Expect) {
     let source = wat::parse_str(wat).unwrap();
     let mut ctx = Context::default();
-    let frontend_config = WasmFrontendConfig::default();
+    let frontend_config = frontend_config();
     ozk_wasm_dialect::register(&mut ctx);
     ozk_ozk_dialect::register(&mut ctx);
     frontend_config.register(&mut ctx);
@@ -28,6 +43,40 @@ pub fn check_wasm_pass<T: Pass>(pass: &T, wat: &str, expected: expect_test::Expe
     expected.assert_eq(wasm_module_op.with_ctx(&ctx).to_string().as_str());
 }
 
+/// Parse the WAT into a Wasm module
+pub fn parse_wasm_module(wat: &str) -> (Context, wasm::ops::ModuleOp) {
+    let source = wat::parse_str(wat).unwrap();
+    parse_wasm_binary(&source)
+}
+
+/// Parse the Wasm binary (e.g. a Rust-to-Wasm test bundle) into a Wasm module
+pub fn parse_wasm_binary(source: &[u8]) -> (Context, wasm::ops::ModuleOp) {
+    let mut ctx = Context::default();
+    let frontend_config = frontend_config();
+    ozk_wasm_dialect::register(&mut ctx);
+    ozk_ozk_dialect::register(&mut ctx);
+    frontend_config.register(&mut ctx);
+    let wasm_module_op =
+        ozk_frontend_wasm::parse_module(&mut ctx, source, &frontend_config).unwrap();
+    (ctx, wasm_module_op)
+}
+
+/// Run the pass on the Wasm module wrapped in a builtin module and return the Wasm module
+pub fn run_wasm_pass_wrapped<T: Pass>(pass: &T, wat: &str) -> (Context, wasm::ops::ModuleOp) {
+    let source = wat::parse_str(wat).unwrap();
+    let mut ctx = Context::default();
+    let frontend_config = frontend_config();
+    ozk_wasm_dialect::register(&mut ctx);
+    ozk_ozk_dialect::register(&mut ctx);
+    frontend_config.register(&mut ctx);
+    let wasm_module_op =
+        ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
+    let wrapper_module = wrap_in_builtin_module(&mut ctx, wasm_module_op);
+    pass.run_on_operation(&mut ctx, wrapper_module.get_operation())
+        .unwrap();
+    (ctx, wasm_module_op)
+}
+
 pub fn check_wasm_valida_passes(
     passes: Vec<Box<dyn Pass>>,
     wat: &str,
@@ -35,7 +84,7 @@ pub fn check_wasm_valida_passes(
 ) {
     let source = wat::parse_str(wat).unwrap();
     let mut ctx = Context::default();
-    let frontend_config = WasmFrontendConfig::default();
+    let frontend_config = frontend_config();
     ozk_wasm_dialect::register(&mut ctx);
     ozk_ozk_dialect::register(&mut ctx);
     ozk_valida_dialect::register(&mut ctx);
@@ -78,3 +127,163 @@ fn unwrap_from_builtin_module(
         .unwrap();
     inner_module
 }
+
+/// Wasm module built directly from the dialect ops (no WAT and no frontend), so that a single
+/// pass (or a single rewrite pattern) can be tested on the exact IR it expects, including
+/// the ops the frontend never emits (e.g. [ozk_ozk_dialect::ops::ReservedGetOp]).
+/// The first function is the start function.
+pub struct ModuleFixture {
+    ctx: Context,
+    funcs: Vec<wasm::ops::FuncOp>,
+}
+
+impl ModuleFixture {
+    /// New fixture with the Wasm and ozk dialects registered
+    pub fn new() -> Self {
+        let mut ctx = Context::default();
+        ozk_wasm_dialect::register(&mut ctx);
+        ozk_ozk_dialect::register(&mut ctx);
+        Self {
+            ctx,
+            funcs: Vec::new(),
+        }
+    }
+
+    pub fn ctx(&mut self) -> &mut Context {
+        &mut self.ctx
+    }
+
+    /// Adds the function with the body built by `body` (the final `return` is not added)
+    pub fn func(
+        &mut self,
+        name: &str,
+        params: Vec<Ptr<TypeObj>>,
+        results: Vec<Ptr<TypeObj>>,
+        body: impl FnOnce(&mut FuncFixture),
+    ) -> &mut Self {
+        let mut builder = FuncBuilder::new(&mut self.ctx, FuncSym::from(name));
+        let sig = FunctionType::get(&mut self.ctx, params, results);
+        builder.set_signature(sig);
+        let mut func_fixture = FuncFixture {
+            ctx: &mut self.ctx,
+            builder,
+        };
+        body(&mut func_fixture);
+        let builder = func_fixture.builder;
+        let func_op = builder.build(&mut self.ctx).unwrap();
+        self.funcs.push(func_op);
+        self
+    }
+
+    /// Builds the module
+    pub fn build(self) -> (Context, wasm::ops::ModuleOp) {
+        let mut ctx = self.ctx;
+        let start_func_sym = FuncSym::from(self.funcs.first().unwrap().get_symbol_name(&ctx));
+        let module_op = wasm::ops::ModuleOp::new(
+            &mut ctx,
+            "module_name",
+            start_func_sym,
+            vec![],
+            self.funcs,
+            vec![],
+            vec![],
+        );
+        (ctx, module_op)
+    }
+
+    /// Builds the module, runs the pass on it and compares the printed module with `expected`
+    pub fn check_pass<T: Pass>(self, pass: &T, expected: expect_test::Expect) {
+        let (mut ctx, module_op) = self.build();
+        pass.run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        expected.assert_eq(module_op.with_ctx(&ctx).to_string().as_str());
+    }
+}
+
+/// Function body of the [ModuleFixture]. The ops are pushed in the Wasm order, the nested
+/// blocks are built by the closures, so they are always closed.
+pub struct FuncFixture<'a> {
+    ctx: &'a mut Context,
+    builder: FuncBuilder,
+}
+
+impl<'a> FuncFixture<'a> {
+    pub fn ctx(&mut self) -> &mut Context {
+        self.ctx
+    }
+
+    /// Declares a local of the given type
+    pub fn local(&mut self, ty: Ptr<TypeObj>) -> &mut Self {
+        self.builder.declare_local(1, ty);
+        self
+    }
+
+    /// Pushes the op built by `op`
+    pub fn op(&mut self, op: impl FnOnce(&mut Context) -> Ptr<Operation>) -> &mut Self {
+        let op = op(self.ctx);
+        self.builder.push(self.ctx, op).unwrap();
+        self
+    }
+
+    pub fn i32_const(&mut self, value: i32) -> &mut Self {
+        self.op(|ctx| wasm::ops::ConstantOp::new_i32_unlinked(ctx, value).get_operation())
+    }
+
+    pub fn local_get(&mut self, index: u32) -> &mut Self {
+        self.op(|ctx| wasm::ops::LocalGetOp::new_unlinked(ctx, index).get_operation())
+    }
+
+    pub fn local_set(&mut self, index: u32) -> &mut Self {
+        self.op(|ctx| wasm::ops::LocalSetOp::new_unlinked(ctx, index).get_operation())
+    }
+
+    pub fn call(&mut self, func_index: u32) -> &mut Self {
+        self.op(|ctx| wasm::ops::CallOp::new_unlinked(ctx, func_index.into()).get_operation())
+    }
+
+    pub fn ret(&mut self) -> &mut Self {
+        self.op(|ctx| wasm::ops::ReturnOp::new_unlinked(ctx).get_operation())
+    }
+
+    /// Pushes a `block` with the given results and the body built by `body`
+    pub fn block(
+        &mut self,
+        results: Vec<Ptr<TypeObj>>,
+        body: impl FnOnce(&mut FuncFixture),
+    ) -> &mut Self {
+        let ty = FunctionType::get(self.ctx, vec![], results);
+        self.op(|ctx| wasm::ops::BlockOp::new_unlinked(ctx, ty).get_operation());
+        body(self);
+        self.builder.push_end(self.ctx).unwrap();
+        self
+    }
+
+    /// Pushes a `loop` with the given results and the body built by `body`
+    pub fn loop_block(
+        &mut self,
+        results: Vec<Ptr<TypeObj>>,
+        body: impl FnOnce(&mut FuncFixture),
+    ) -> &mut Self {
+        let ty = FunctionType::get(self.ctx, vec![], results);
+        self.op(|ctx| wasm::ops::LoopOp::new_unlinked(ctx, ty).get_operation());
+        body(self);
+        self.builder.push_end(self.ctx).unwrap();
+        self
+    }
+
+    /// Pushes an `if` with the given results and the branches built by `then` and `els`
+    pub fn if_else(
+        &mut self,
+        results: Vec<Ptr<TypeObj>>,
+        then: impl FnOnce(&mut FuncFixture),
+        els: impl FnOnce(&mut FuncFixture),
+    ) -> &mut Self {
+        let ty = FunctionType::get(self.ctx, vec![], results);
+        self.op(|ctx| wasm::ops::IfOp::new_unlinked(ctx, ty).get_operation());
+        then(self);
+        self.builder.push_else().unwrap();
+        els(self);
+        self.builder.push_end(self.ctx).unwrap();
+        self
+    }
+}
@@ -0,0 +1,14 @@
+//! 64-bit integer emulation strategies for the targets with a narrower native word.
+
+/// How the i64 arithmetic is emulated on a target without native 64-bit integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum U64Emulation {
+    /// No overflow checks, the results that don't fit silently wrap around the
+    /// target's native modulus (e.g. the field modulus in Miden). Faster, but
+    /// diverges from Wasm on overflow.
+    Unchecked,
+    /// Check the carries (borrows, operand ranges) and trap instead of producing a
+    /// result that diverges from Wasm.
+    #[default]
+    Checked,
+}
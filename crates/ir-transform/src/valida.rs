@@ -1,6 +1,9 @@
 use ozk_valida_dialect::types::FramePointer;
 use ozk_wasm_dialect::types::StackDepth;
 
+use crate::byte_layout::CELL_BYTES;
+
+pub mod cleanup;
 pub mod lowering;
 pub mod track_pc;
 
@@ -8,3 +11,52 @@ pub fn fp_from_wasm_stack(stack_depth: StackDepth) -> FramePointer {
     let v: i32 = stack_depth.into();
     (-v * 4).into()
 }
+
+/// Valida calling convention, the offsets are relative to the callee's fp:
+/// ```text
+/// 12 + 4 * (n - 1)  arg n - 1 (the deepest on the caller's stack)
+/// ...
+/// 12                arg 0 (the top of the caller's stack)
+///  8                return value (if no args, otherwise in the last arg)
+///  4                return fp
+///  0                return address
+/// -4                local 0
+/// ...               locals, the tracked Wasm stack and the scratch cells
+/// ```
+/// The callee frame is placed below the caller frame ([ValidaCallConv::frame_size]), so that
+/// the callee can't overwrite the caller's locals and stack.
+pub struct ValidaCallConv;
+
+impl ValidaCallConv {
+    /// Offset of the return address
+    pub const RETURN_ADDRESS_OFFSET: i32 = 0;
+    /// Offset of the caller's fp
+    pub const RETURN_FP_OFFSET: i32 = 4;
+    /// Offset of the return value of a function without args
+    pub const RETURN_VALUE_OFFSET: i32 = 8;
+    /// Size of the linkage area (return address, return fp and return value) in bytes
+    pub const LINKAGE_BYTES: u32 = 12;
+    /// Cells above the tracked stack that the arith lowering uses for the intermediate values
+    pub const SCRATCH_CELLS: u32 = 2;
+
+    /// Offset of the arg with the given index
+    pub fn arg_offset(arg_idx: i32) -> i32 {
+        Self::LINKAGE_BYTES as i32 + arg_idx * CELL_BYTES as i32
+    }
+
+    /// Offset of the return value of a function with the given number of args
+    pub fn return_value_offset(num_args: i32) -> i32 {
+        Self::RETURN_VALUE_OFFSET + num_args * CELL_BYTES as i32
+    }
+
+    /// Offset of the local with the given index (the parameters are counted in the index, as in Wasm)
+    pub fn local_offset(local_idx: i32) -> i32 {
+        -(local_idx + 1) * CELL_BYTES as i32
+    }
+
+    /// The frame size in bytes: the cells below fp (the locals, the deepest tracked stack and
+    /// the scratch cells above it) and the linkage area of the callee.
+    pub fn frame_size(max_stack_depth: u32, locals_cells: u32) -> u32 {
+        (max_stack_depth.max(locals_cells) + Self::SCRATCH_CELLS) * CELL_BYTES + Self::LINKAGE_BYTES
+    }
+}
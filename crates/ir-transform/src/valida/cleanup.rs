@@ -0,0 +1,256 @@
+//! Peephole cleanups of the lowered Valida functions
+
+use ozk_valida_dialect as valida;
+use ozk_valida_dialect::op_interfaces::HasOperands;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Shortens the instruction sequences left by the op-by-op lowering. Must run before the
+/// program counters are tracked.
+/// - `imm32 a` followed by `sw b a` (a constant copied from a stack cell) is fused into
+///   `imm32 b` when the cell `a` is not read afterwards.
+#[derive(Default)]
+pub struct ValidaCleanupPass;
+
+impl Pass for ValidaCleanupPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut func_ops = Vec::new();
+        op.walk_only::<valida::ops::FuncOp>(ctx, WalkOrder::PostOrder, &mut |func_op| {
+            func_ops.push(*func_op);
+            WalkResult::Advance
+        });
+        for func_op in func_ops {
+            fuse_imm32_sw(ctx, &func_op);
+        }
+        Ok(())
+    }
+}
+
+fn fuse_imm32_sw(ctx: &mut Context, func_op: &valida::ops::FuncOp) {
+    let mut ops: Vec<Ptr<Operation>> = func_op.op_iter(ctx).collect();
+    let mut idx = 0;
+    while idx + 1 < ops.len() {
+        let imm32_op = ops[idx].deref(ctx).get_op(ctx);
+        let sw_op = ops[idx + 1].deref(ctx).get_op(ctx);
+        let (Some(imm32_op), Some(sw_op)) = (
+            imm32_op.downcast_ref::<valida::ops::Imm32Op>(),
+            sw_op.downcast_ref::<valida::ops::SwOp>(),
+        ) else {
+            idx += 1;
+            continue;
+        };
+        let mut imm32_operands = imm32_op.get_operands(ctx);
+        let sw_operands = sw_op.get_operands(ctx);
+        let cell = imm32_operands.a().as_i32();
+        if sw_operands.c().as_i32() != cell || !is_dead_cell(ctx, &ops[idx + 2..], cell) {
+            idx += 1;
+            continue;
+        }
+        // the fused imm32 may be followed by another copy of the same constant
+        imm32_operands.set_a(sw_operands.b().as_i32());
+        imm32_op.set_operands(ctx, imm32_operands);
+        ops.remove(idx + 1).unlink(ctx);
+    }
+}
+
+/// The cells accessed by an op
+enum CellAccess {
+    /// Reads the cells and then writes the cell
+    ReadWrite(Vec<i32>, i32),
+    /// Leaves the function, the cells above fp (linkage area, args) are read by the caller
+    Return,
+}
+
+/// `None` if unknown (branches, labels, calls, etc.)
+fn cell_access(ctx: &Context, op: Ptr<Operation>) -> Option<CellAccess> {
+    let opop = op.deref(ctx).get_op(ctx);
+    if let Some(imm32_op) = opop.downcast_ref::<valida::ops::Imm32Op>() {
+        let operands = imm32_op.get_operands(ctx);
+        return Some(CellAccess::ReadWrite(vec![], operands.a().as_i32()));
+    }
+    if let Some(sw_op) = opop.downcast_ref::<valida::ops::SwOp>() {
+        let operands = sw_op.get_operands(ctx);
+        return Some(CellAccess::ReadWrite(
+            vec![operands.c().as_i32()],
+            operands.b().as_i32(),
+        ));
+    }
+    if let Some(jalv_op) = opop.downcast_ref::<valida::ops::JalvOp>() {
+        return jalv_op
+            .is_return_pseudo_op(ctx)
+            .then_some(CellAccess::Return);
+    }
+    // `[a] = [b] op [c]`
+    let operands = if let Some(op) = opop.downcast_ref::<valida::ops::AddOp>() {
+        op.get_operands(ctx)
+    } else if let Some(op) = opop.downcast_ref::<valida::ops::SubOp>() {
+        op.get_operands(ctx)
+    } else if let Some(op) = opop.downcast_ref::<valida::ops::MulOp>() {
+        op.get_operands(ctx)
+    } else if let Some(op) = opop.downcast_ref::<valida::ops::DivOp>() {
+        op.get_operands(ctx)
+    } else if let Some(op) = opop.downcast_ref::<valida::ops::AndOp>() {
+        op.get_operands(ctx)
+    } else if let Some(op) = opop.downcast_ref::<valida::ops::OrOp>() {
+        op.get_operands(ctx)
+    } else if let Some(op) = opop.downcast_ref::<valida::ops::XorOp>() {
+        op.get_operands(ctx)
+    } else if let Some(op) = opop.downcast_ref::<valida::ops::ShlOp>() {
+        op.get_operands(ctx)
+    } else if let Some(op) = opop.downcast_ref::<valida::ops::ShrOp>() {
+        op.get_operands(ctx)
+    } else if let Some(op) = opop.downcast_ref::<valida::ops::LtOp>() {
+        op.get_operands(ctx)
+    } else {
+        return None;
+    };
+    Some(CellAccess::ReadWrite(
+        vec![operands.b().as_i32(), operands.c().as_i32()],
+        operands.a().as_i32(),
+    ))
+}
+
+/// Is the cell overwritten (or the function left) before it is read by the ops?
+/// Conservatively false on the branches, labels and calls.
+fn is_dead_cell(ctx: &Context, ops: &[Ptr<Operation>], cell: i32) -> bool {
+    for op in ops {
+        match cell_access(ctx, *op) {
+            Some(CellAccess::ReadWrite(reads, write)) => {
+                if reads.contains(&cell) {
+                    return false;
+                }
+                if write == cell {
+                    return true;
+                }
+            }
+            Some(CellAccess::Return) => return cell < 0,
+            None => return false,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_valida_passes;
+    use crate::valida::lowering::arith_op_lowering::WasmToValidaArithLoweringPass;
+    use crate::valida::lowering::func_lowering::WasmToValidaFuncLoweringPass;
+    use crate::wasm::track_stack_depth::WasmTrackStackDepthPass;
+
+    use super::*;
+
+    #[test]
+    fn imm32_sw_fused() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+                Box::<ValidaCleanupPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 3
+        i32.const 7
+        local.set 0
+        local.get 0
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -8(fp) 0 0 0 3
+                        valida.imm32 8(fp) 0 0 0 7
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
+
+    #[test]
+    fn imm32_sw_fused_up_to_arith_use() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+                Box::<ValidaCleanupPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 5
+        local.set 0
+        local.get 0
+        i32.const 1
+        i32.add
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -8(fp) 0 0 0 5
+                        valida.imm32 -12(fp) 0 0 0 1
+                        valida.add -8(fp) -12(fp) -8(fp) 0 0
+                        valida.sw 0 8(fp) -8(fp) 0 0
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
+
+    #[test]
+    fn imm32_sw_not_fused_across_unknown_op() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+                Box::<ValidaCleanupPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 3
+        local.set 0
+        unreachable
+        local.get 0
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -8(fp) 0 0 0 3
+                        valida.sw 0 -4(fp) -8(fp) 0 0
+                        valida.trap
+                        valida.sw 0 -8(fp) -4(fp) 0 0
+                        valida.sw 0 8(fp) -8(fp) 0 0
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
+}
@@ -2,22 +2,25 @@
 #![allow(dead_code)]
 
 use anyhow::anyhow;
+use apint::ApInt;
+use ozk_ozk_dialect as ozk;
 use ozk_valida_dialect as valida;
 use ozk_wasm_dialect as wasm;
 use ozk_wasm_dialect::op_interfaces::TrackedStackDepth;
+use ozk_wasm_dialect::types::BinaryOpcode;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::dialect_conversion::apply_partial_conversion;
 use pliron::dialect_conversion::ConversionTarget;
 use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::op::op_cast;
 use pliron::op::Op;
 use pliron::operation::Operation;
 use pliron::pass::Pass;
 use pliron::pattern_match::PatternRewriter;
 use pliron::pattern_match::RewritePattern;
 use pliron::rewrite::RewritePatternSet;
-use valida::types::Mersenne31;
-use valida::types::Operands;
+use pliron::with_context::AttachContext;
 
 use crate::valida::fp_from_wasm_stack;
 
@@ -31,6 +34,18 @@ impl Pass for WasmToValidaArithLoweringPass {
         let mut patterns = RewritePatternSet::default();
         patterns.add(Box::<ConstantOpLowering>::default());
         patterns.add(Box::<ArithOpLowering>::default());
+        patterns.add(Box::<IntDivOpLowering>::default());
+        patterns.add(Box::<BitwiseOpLowering>::default());
+        patterns.add(Box::<ShiftOpLowering>::default());
+        patterns.add(Box::<RotateOpLowering>::default());
+        patterns.add(Box::<SignExtOpLowering>::default());
+        patterns.add(Box::<BitCountOpLowering>::default());
+        patterns.add(Box::<CmpOpLowering>::default());
+        patterns.add(Box::<SelectOpLowering>::default());
+        patterns.add(Box::<DropOpLowering>::default());
+        patterns.add(Box::<DebugPrintOpLowering>::default());
+        patterns.add(Box::<HaltOpLowering>::default());
+        patterns.add(Box::<TrapOpLowering>::default());
         apply_partial_conversion(ctx, op, target, patterns)?;
         Ok(())
     }
@@ -59,18 +74,17 @@ impl RewritePattern for ConstantOpLowering {
         if let Some(const_op) = opop.downcast_ref::<wasm::ops::ConstantOp>() {
             let value = const_op.get_value(ctx);
             if let Ok(value_attr) = value.downcast::<IntegerAttr>() {
-                // TODO: Note that because a full 32-bit value does not fit within one field element, we assume that values have been decomposed into 4 8-byte elements
-                let value: Mersenne31 = value_attr.as_ref().try_into()?;
+                let op_str = op.with_ctx(ctx).to_string();
+                // TODO: wide (i64) constants need two cells
+                let value = ApInt::from(value_attr.as_ref().clone())
+                    .try_to_i32()
+                    .map_err(|e| {
+                        anyhow!("cannot lower {op_str}: value does not fit in 32 bits: {e:?}")
+                    })?;
                 let wasm_stack_depth_before_op = const_op.get_stack_depth(ctx);
                 let a_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next());
-                let a = a_fp.into();
-                let b = 0;
-                let c = 0;
-                let d = 0;
-                let imm_op = valida::ops::Imm32Op::new_unlinked(
-                    ctx,
-                    Operands::from_i32(a, b, c, d, value.as_i32()),
-                );
+                let imm_op = valida::ops::Imm32Op::new_checked(ctx, a_fp, value as u32)
+                    .map_err(|e| anyhow!("cannot lower {op_str}: {e}"))?;
                 rewriter.replace_op_with(ctx, op, imm_op.get_operation())?;
             } else {
                 return Err(anyhow!("only integer constants are supported"));
@@ -80,37 +94,1087 @@ impl RewritePattern for ConstantOpLowering {
     }
 }
 
+/// Lowers the i32 `add`, `sub` and `mul` ops to the Valida U32 ops.
+/// The i64 values take two cells and are not supported yet.
 #[derive(Default)]
 pub struct ArithOpLowering {}
 
 impl RewritePattern for ArithOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(binary_opcode(ctx, op).map_or(false, |opcode| {
+            matches!(
+                opcode,
+                BinaryOpcode::Add | BinaryOpcode::Sub | BinaryOpcode::Mul
+            )
+        }))
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = &op.deref(ctx).get_op(ctx);
+        let Some(binary_op) = opop.downcast_ref::<wasm::ops::BinaryArithOp>() else {
+            return Ok(());
+        };
+        let opcode = binary_op.get_opcode(ctx);
+        if binary_op.get_type(ctx) != ozk::types::i32_type(ctx) {
+            return Err(anyhow!(
+                "{} is not supported by Valida (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        let wasm_stack_depth_before_op = op_cast::<dyn TrackedStackDepth>(opop.as_ref())
+            .ok_or_else(|| anyhow!("expected the stack depth to be tracked"))?
+            .get_stack_depth(ctx);
+        // wasm pops 2 values and pushes 1,
+        // so the result ends up on the first argument stack slot
+        let result_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let arg1_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let arg2_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.top()).into();
+        let valida_op = if opcode == BinaryOpcode::Add {
+            // commutative, the operands are kept in the top-first order
+            valida::ops::AddOp::new(ctx, result_fp, arg2_fp, arg1_fp).get_operation()
+        } else if opcode == BinaryOpcode::Sub {
+            valida::ops::SubOp::new(ctx, result_fp, arg1_fp, arg2_fp).get_operation()
+        } else {
+            valida::ops::MulOp::new(ctx, result_fp, arg1_fp, arg2_fp).get_operation()
+        };
+        rewriter.replace_op_with(ctx, op, valida_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers the i32 division ops, `rem_u` is computed as `a - (a / b) * b` using the free stack
+/// slot above the operands for the intermediate values. Valida has no signed division,
+/// `div_s` and `rem_s` divide the absolute values and negate the result if the signs differ
+/// (`div_s`) or the dividend is negative (`rem_s`). The sign bit `s` of a value `v` gives
+/// `|v| = v - 2 * s * v` (wrapping), so the absolute values and the result take the two free
+/// stack slots only. Valida has no trap on zero divisor, the division by zero fails at the check
+/// inserted by the function lowering before every `div`
+/// (see [crate::valida::lowering::func_lowering]). The `div_s` overflow (`i32::MIN / -1`) is
+/// turned into a division by zero. The i64 values take two cells and are not supported yet.
+#[derive(Default)]
+pub struct IntDivOpLowering {}
+
+impl RewritePattern for IntDivOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(binary_opcode(ctx, op).map_or(false, BinaryOpcode::is_division))
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        let Some(binary_op) = opop.downcast_ref::<wasm::ops::BinaryArithOp>() else {
+            return Ok(());
+        };
+        let opcode = binary_op.get_opcode(ctx);
+        if binary_op.get_type(ctx) != ozk::types::i32_type(ctx) {
+            return Err(anyhow!(
+                "{} is not supported by Valida (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        let wasm_stack_depth_before_op = op_cast::<dyn TrackedStackDepth>(opop.as_ref())
+            .ok_or_else(|| anyhow!("expected the stack depth to be tracked"))?
+            .get_stack_depth(ctx);
+        // the dividend is below the divisor, the result ends up on the dividend stack slot
+        let result_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let dividend_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let divisor_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.top()).into();
+        let tmp1_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next());
+        let tmp2_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next().next());
+        let tmp1: i32 = tmp1_fp.into();
+        let tmp2: i32 = tmp2_fp.into();
+        let op_str = op.with_ctx(ctx).to_string();
+        let imm = |ctx: &mut Context, value: u32| -> Result<Ptr<Operation>, anyhow::Error> {
+            Ok(valida::ops::Imm32Op::new_checked(ctx, tmp1_fp, value)
+                .map_err(|e| anyhow!("cannot lower {op_str}: {e}"))?
+                .get_operation())
+        };
+        let mut ops = Vec::new();
+        let is_signed = matches!(opcode, BinaryOpcode::DivS | BinaryOpcode::RemS);
+        if is_signed {
+            // the sign bit of the result in tmp2
+            ops.extend(vec![
+                imm(ctx, 31)?,
+                valida::ops::ShrOp::new(ctx, tmp2, dividend_fp, tmp1).get_operation(),
+            ]);
+            if opcode == BinaryOpcode::DivS {
+                ops.extend(vec![
+                    valida::ops::ShrOp::new(ctx, tmp1, divisor_fp, tmp1).get_operation(),
+                    valida::ops::XorOp::new(ctx, tmp2, tmp2, tmp1).get_operation(),
+                ]);
+            }
+            for value_fp in [divisor_fp, dividend_fp] {
+                // v - 2 * (v >> 31) * v
+                ops.extend(vec![
+                    imm(ctx, 31)?,
+                    valida::ops::ShrOp::new(ctx, tmp1, value_fp, tmp1).get_operation(),
+                    valida::ops::AddOp::new(ctx, tmp1, tmp1, tmp1).get_operation(),
+                    valida::ops::MulOp::new(ctx, tmp1, tmp1, value_fp).get_operation(),
+                    valida::ops::SubOp::new(ctx, value_fp, value_fp, tmp1).get_operation(),
+                ]);
+            }
+        }
+        if matches!(opcode, BinaryOpcode::DivU | BinaryOpcode::DivS) {
+            ops.push(
+                valida::ops::DivOp::new(ctx, result_fp, dividend_fp, divisor_fp).get_operation(),
+            );
+        } else {
+            ops.extend(vec![
+                valida::ops::DivOp::new(ctx, tmp1, dividend_fp, divisor_fp).get_operation(),
+                valida::ops::MulOp::new(ctx, tmp1, tmp1, divisor_fp).get_operation(),
+                valida::ops::SubOp::new(ctx, result_fp, dividend_fp, tmp1).get_operation(),
+            ]);
+        }
+        if opcode == BinaryOpcode::DivS {
+            // the quotient must be below 2^31 (2^31 + 1 for a negative result),
+            // otherwise it's divided by zero
+            ops.extend(vec![
+                imm(ctx, 1 << 31)?,
+                valida::ops::AddOp::new(ctx, tmp1, tmp1, tmp2).get_operation(),
+                valida::ops::LtOp::new(ctx, tmp1, result_fp, tmp1).get_operation(),
+                valida::ops::DivOp::new(ctx, result_fp, result_fp, tmp1).get_operation(),
+            ]);
+        }
+        if is_signed {
+            ops.extend(vec![
+                valida::ops::AddOp::new(ctx, tmp1, tmp2, tmp2).get_operation(),
+                valida::ops::MulOp::new(ctx, tmp1, tmp1, result_fp).get_operation(),
+                valida::ops::SubOp::new(ctx, result_fp, result_fp, tmp1).get_operation(),
+            ]);
+        }
+        let last_op = ops
+            .pop()
+            .ok_or_else(|| anyhow!("cannot lower {op_str}: no ops"))?;
+        rewriter.set_insertion_point(op);
+        for new_op in ops {
+            rewriter.insert_before(ctx, new_op)?;
+        }
+        rewriter.replace_op_with(ctx, op, last_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers the i32 bitwise ops to the Valida U32 bitwise ops.
+/// The i64 values take two cells and are not supported yet.
+#[derive(Default)]
+pub struct BitwiseOpLowering {}
+
+impl RewritePattern for BitwiseOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(binary_opcode(ctx, op).map_or(false, BinaryOpcode::is_bitwise))
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        let Some(binary_op) = opop.downcast_ref::<wasm::ops::BinaryArithOp>() else {
+            return Ok(());
+        };
+        if binary_op.get_type(ctx) != ozk::types::i32_type(ctx) {
+            return Err(anyhow!(
+                "{} is not supported by Valida (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        let opcode = binary_op.get_opcode(ctx);
+        let wasm_stack_depth_before_op = op_cast::<dyn TrackedStackDepth>(opop.as_ref())
+            .ok_or_else(|| anyhow!("expected the stack depth to be tracked"))?
+            .get_stack_depth(ctx);
+        // the result ends up on the first argument stack slot
+        let result_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let arg1_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let arg2_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.top()).into();
+        let valida_op = if opcode == BinaryOpcode::And {
+            valida::ops::AndOp::new(ctx, result_fp, arg1_fp, arg2_fp).get_operation()
+        } else if opcode == BinaryOpcode::Or {
+            valida::ops::OrOp::new(ctx, result_fp, arg1_fp, arg2_fp).get_operation()
+        } else {
+            valida::ops::XorOp::new(ctx, result_fp, arg1_fp, arg2_fp).get_operation()
+        };
+        rewriter.replace_op_with(ctx, op, valida_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers the i32 `shl` and `shr_u` ops to the Valida U32 shifts. The shift amount is masked to
+/// the low 5 bits first (Wasm semantics) in the free stack slot above the operands.
+/// Valida has no arithmetic shift and the i64 values take two cells, such ops are rejected.
+#[derive(Default)]
+pub struct ShiftOpLowering {}
+
+impl RewritePattern for ShiftOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(binary_opcode(ctx, op).map_or(false, |opcode| {
+            matches!(
+                opcode,
+                BinaryOpcode::Shl | BinaryOpcode::ShrS | BinaryOpcode::ShrU
+            )
+        }))
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        let Some(binary_op) = opop.downcast_ref::<wasm::ops::BinaryArithOp>() else {
+            return Ok(());
+        };
+        let opcode = binary_op.get_opcode(ctx);
+        let is_i32 = binary_op.get_type(ctx) == ozk::types::i32_type(ctx);
+        let is_shl = is_i32 && opcode == BinaryOpcode::Shl;
+        let is_shr_u = is_i32 && opcode == BinaryOpcode::ShrU;
+        if !is_shl && !is_shr_u {
+            return Err(anyhow!(
+                "{} is not supported by Valida (no arithmetic shift, \
+                 only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        let wasm_stack_depth_before_op = op_cast::<dyn TrackedStackDepth>(opop.as_ref())
+            .ok_or_else(|| anyhow!("expected the stack depth to be tracked"))?
+            .get_stack_depth(ctx);
+        // the value is below the shift amount, the result ends up on the value stack slot
+        let result_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let value_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let amount_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.top()).into();
+        let mask_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next());
+        let op_str = op.with_ctx(ctx).to_string();
+        let mask_op = valida::ops::Imm32Op::new_checked(ctx, mask_fp, 31)
+            .map_err(|e| anyhow!("cannot lower {op_str}: {e}"))?;
+        let mask_fp: i32 = mask_fp.into();
+        let and_op = valida::ops::AndOp::new(ctx, mask_fp, amount_fp, mask_fp);
+        let shift_op = if is_shl {
+            valida::ops::ShlOp::new(ctx, result_fp, value_fp, mask_fp).get_operation()
+        } else {
+            valida::ops::ShrOp::new(ctx, result_fp, value_fp, mask_fp).get_operation()
+        };
+        rewriter.set_insertion_point(op);
+        rewriter.insert_before(ctx, mask_op.get_operation())?;
+        rewriter.insert_before(ctx, and_op.get_operation())?;
+        rewriter.replace_op_with(ctx, op, shift_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers the i32 `rotl` and `rotr` ops to a pair of the Valida U32 shifts joined with OR:
+/// `rotl(x, k) = (x << (k & 31)) | (x >> ((32 - (k & 31)) & 31))` (`rotr` swaps the shifts).
+/// The intermediate values are kept in the two free stack slots above the operands.
+/// The i64 values take two cells, such ops are rejected.
+#[derive(Default)]
+pub struct RotateOpLowering {}
+
+impl RewritePattern for RotateOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(binary_opcode(ctx, op).map_or(false, |opcode| {
+            matches!(opcode, BinaryOpcode::Rotl | BinaryOpcode::Rotr)
+        }))
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        let Some(binary_op) = opop.downcast_ref::<wasm::ops::BinaryArithOp>() else {
+            return Ok(());
+        };
+        let opcode = binary_op.get_opcode(ctx);
+        let is_i32 = binary_op.get_type(ctx) == ozk::types::i32_type(ctx);
+        let is_rotl = is_i32 && opcode == BinaryOpcode::Rotl;
+        let is_rotr = is_i32 && opcode == BinaryOpcode::Rotr;
+        if !is_rotl && !is_rotr {
+            return Err(anyhow!(
+                "{} is not supported by Valida (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        let wasm_stack_depth_before_op = op_cast::<dyn TrackedStackDepth>(opop.as_ref())
+            .ok_or_else(|| anyhow!("expected the stack depth to be tracked"))?
+            .get_stack_depth(ctx);
+        // the value is below the rotation amount, the result ends up on the value stack slot
+        let result_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let value_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let amount_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.top()).into();
+        let tmp1_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next());
+        let tmp2_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next().next());
+        let op_str = op.with_ctx(ctx).to_string();
+        let mask_op = valida::ops::Imm32Op::new_checked(ctx, tmp1_fp, 31)
+            .map_err(|e| anyhow!("cannot lower {op_str}: {e}"))?;
+        let bits_op = valida::ops::Imm32Op::new_checked(ctx, tmp2_fp, 32)
+            .map_err(|e| anyhow!("cannot lower {op_str}: {e}"))?;
+        let tmp1_fp: i32 = tmp1_fp.into();
+        let tmp2_fp: i32 = tmp2_fp.into();
+        let mut ops = vec![
+            mask_op.get_operation(),
+            // k & 31
+            valida::ops::AndOp::new(ctx, amount_fp, amount_fp, tmp1_fp).get_operation(),
+            bits_op.get_operation(),
+            // (32 - (k & 31)) & 31
+            valida::ops::SubOp::new(ctx, tmp2_fp, tmp2_fp, amount_fp).get_operation(),
+            valida::ops::AndOp::new(ctx, tmp2_fp, tmp2_fp, tmp1_fp).get_operation(),
+        ];
+        if is_rotl {
+            ops.push(valida::ops::ShlOp::new(ctx, tmp1_fp, value_fp, amount_fp).get_operation());
+            ops.push(valida::ops::ShrOp::new(ctx, tmp2_fp, value_fp, tmp2_fp).get_operation());
+        } else {
+            ops.push(valida::ops::ShrOp::new(ctx, tmp1_fp, value_fp, amount_fp).get_operation());
+            ops.push(valida::ops::ShlOp::new(ctx, tmp2_fp, value_fp, tmp2_fp).get_operation());
+        }
+        let or_op = valida::ops::OrOp::new(ctx, result_fp, tmp1_fp, tmp2_fp);
+        rewriter.set_insertion_point(op);
+        for new_op in ops {
+            rewriter.insert_before(ctx, new_op)?;
+        }
+        rewriter.replace_op_with(ctx, op, or_op.get_operation())?;
+        Ok(())
+    }
+}
+
+/// Lowers the i32 `extend8_s` and `extend16_s` ops (in place) without branching: the low bits
+/// are masked and `(v ^ sign_bit) - sign_bit` (wrapping) fills the high bits with the sign bit.
+/// The constants are kept in the free stack slot above the operand.
+/// The i64 values take two cells, such ops are rejected.
+#[derive(Default)]
+pub struct SignExtOpLowering {}
+
+impl RewritePattern for SignExtOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<wasm::ops::I32Extend8SOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32Extend16SOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64Extend8SOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64Extend16SOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64Extend32SOp>().is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        let (mask, sign_bit) = if opop.downcast_ref::<wasm::ops::I32Extend8SOp>().is_some() {
+            (0xff, 0x80)
+        } else if opop.downcast_ref::<wasm::ops::I32Extend16SOp>().is_some() {
+            (0xffff, 0x8000)
+        } else {
+            return Err(anyhow!(
+                "{} is not supported by Valida (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        };
+        let wasm_stack_depth_before_op = op_cast::<dyn TrackedStackDepth>(opop.as_ref())
+            .ok_or_else(|| anyhow!("expected the stack depth to be tracked"))?
+            .get_stack_depth(ctx);
+        let value_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.top()).into();
+        let tmp_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next());
+        let op_str = op.with_ctx(ctx).to_string();
+        let mask_op = valida::ops::Imm32Op::new_checked(ctx, tmp_fp, mask)
+            .map_err(|e| anyhow!("cannot lower {op_str}: {e}"))?;
+        let sign_bit_op = valida::ops::Imm32Op::new_checked(ctx, tmp_fp, sign_bit)
+            .map_err(|e| anyhow!("cannot lower {op_str}: {e}"))?;
+        let tmp_fp: i32 = tmp_fp.into();
+        let ops = vec![
+            mask_op.get_operation(),
+            valida::ops::AndOp::new(ctx, value_fp, value_fp, tmp_fp).get_operation(),
+            sign_bit_op.get_operation(),
+            valida::ops::XorOp::new(ctx, value_fp, value_fp, tmp_fp).get_operation(),
+        ];
+        let sub_op = valida::ops::SubOp::new(ctx, value_fp, value_fp, tmp_fp);
+        rewriter.set_insertion_point(op);
+        for new_op in ops {
+            rewriter.insert_before(ctx, new_op)?;
+        }
+        rewriter.replace_op_with(ctx, op, sub_op.get_operation())?;
+        Ok(())
+    }
+}
+
+/// Lowers the i32 `clz`, `ctz` and `popcnt` ops in place. Valida has no bit counting
+/// instructions, so `popcnt` is the branch-free SWAR sequence (the bits are summed in 2-, 4-
+/// and 8-bit fields and the bytes are added up by the multiplication). `clz` smears the
+/// highest set bit to the right and counts the zeros left, `ctz` counts the ones of
+/// `!x & (x - 1)`. The intermediate values are kept in the two free stack slots above the
+/// operand. The i64 values take two cells, such ops are rejected.
+#[derive(Default)]
+pub struct BitCountOpLowering {}
+
+impl RewritePattern for BitCountOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<wasm::ops::I32ClzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32CtzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32PopcntOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64ClzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64CtzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I64PopcntOp>().is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        let is_clz = opop.downcast_ref::<wasm::ops::I32ClzOp>().is_some();
+        let is_ctz = opop.downcast_ref::<wasm::ops::I32CtzOp>().is_some();
+        if !is_clz && !is_ctz && opop.downcast_ref::<wasm::ops::I32PopcntOp>().is_none() {
+            return Err(anyhow!(
+                "{} is not supported by Valida (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        let wasm_stack_depth_before_op = op_cast::<dyn TrackedStackDepth>(opop.as_ref())
+            .ok_or_else(|| anyhow!("expected the stack depth to be tracked"))?
+            .get_stack_depth(ctx);
+        let x: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.top()).into();
+        let tmp1_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next());
+        let tmp2_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next().next());
+        let tmp1: i32 = tmp1_fp.into();
+        let tmp2: i32 = tmp2_fp.into();
+        let op_str = op.with_ctx(ctx).to_string();
+        let imm = |ctx: &mut Context, value: u32| -> Result<Ptr<Operation>, anyhow::Error> {
+            Ok(valida::ops::Imm32Op::new_checked(ctx, tmp2_fp, value)
+                .map_err(|e| anyhow!("cannot lower {op_str}: {e}"))?
+                .get_operation())
+        };
+        let mut ops = Vec::new();
+        if is_clz {
+            for shift in [1, 2, 4, 8, 16] {
+                ops.extend(vec![
+                    imm(ctx, shift)?,
+                    valida::ops::ShrOp::new(ctx, tmp1, x, tmp2).get_operation(),
+                    valida::ops::OrOp::new(ctx, x, x, tmp1).get_operation(),
+                ]);
+            }
+            ops.extend(vec![
+                imm(ctx, u32::MAX)?,
+                valida::ops::XorOp::new(ctx, x, x, tmp2).get_operation(),
+            ]);
+        } else if is_ctz {
+            ops.extend(vec![
+                imm(ctx, 1)?,
+                valida::ops::SubOp::new(ctx, tmp1, x, tmp2).get_operation(),
+                imm(ctx, u32::MAX)?,
+                valida::ops::XorOp::new(ctx, x, x, tmp2).get_operation(),
+                valida::ops::AndOp::new(ctx, x, x, tmp1).get_operation(),
+            ]);
+        }
+        ops.extend(vec![
+            // x - ((x >> 1) & 0x55555555)
+            imm(ctx, 1)?,
+            valida::ops::ShrOp::new(ctx, tmp1, x, tmp2).get_operation(),
+            imm(ctx, 0x5555_5555)?,
+            valida::ops::AndOp::new(ctx, tmp1, tmp1, tmp2).get_operation(),
+            valida::ops::SubOp::new(ctx, x, x, tmp1).get_operation(),
+            // (x & 0x33333333) + ((x >> 2) & 0x33333333)
+            imm(ctx, 2)?,
+            valida::ops::ShrOp::new(ctx, tmp1, x, tmp2).get_operation(),
+            imm(ctx, 0x3333_3333)?,
+            valida::ops::AndOp::new(ctx, tmp1, tmp1, tmp2).get_operation(),
+            valida::ops::AndOp::new(ctx, x, x, tmp2).get_operation(),
+            valida::ops::AddOp::new(ctx, x, x, tmp1).get_operation(),
+            // (x + (x >> 4)) & 0x0f0f0f0f
+            imm(ctx, 4)?,
+            valida::ops::ShrOp::new(ctx, tmp1, x, tmp2).get_operation(),
+            valida::ops::AddOp::new(ctx, x, x, tmp1).get_operation(),
+            imm(ctx, 0x0f0f_0f0f)?,
+            valida::ops::AndOp::new(ctx, x, x, tmp2).get_operation(),
+            // the sum of the bytes ends up in the highest byte
+            imm(ctx, 0x0101_0101)?,
+            valida::ops::MulOp::new(ctx, x, x, tmp2).get_operation(),
+            imm(ctx, 24)?,
+        ]);
+        let shr_op = valida::ops::ShrOp::new(ctx, x, x, tmp2);
+        rewriter.set_insertion_point(op);
+        for new_op in ops {
+            rewriter.insert_before(ctx, new_op)?;
+        }
+        rewriter.replace_op_with(ctx, op, shr_op.get_operation())?;
+        Ok(())
+    }
+}
+
+/// The opcode of a [wasm::ops::BinaryArithOp]
+fn binary_opcode(ctx: &Context, op: Ptr<Operation>) -> Option<BinaryOpcode> {
+    op.deref(ctx)
+        .get_op(ctx)
+        .downcast_ref::<wasm::ops::BinaryArithOp>()
+        .map(|binary_op| binary_op.get_opcode(ctx))
+}
+
+fn is_i64_cmp_op(opop: &dyn Op) -> bool {
+    opop.downcast_ref::<wasm::ops::I64EqzOp>().is_some()
+        || opop.downcast_ref::<wasm::ops::I64EqOp>().is_some()
+        || opop.downcast_ref::<wasm::ops::I64NeOp>().is_some()
+        || opop.downcast_ref::<wasm::ops::I64LtSOp>().is_some()
+        || opop.downcast_ref::<wasm::ops::I64LtUOp>().is_some()
+        || opop.downcast_ref::<wasm::ops::I64GtSOp>().is_some()
+        || opop.downcast_ref::<wasm::ops::I64GtUOp>().is_some()
+        || opop.downcast_ref::<wasm::ops::I64LeSOp>().is_some()
+        || opop.downcast_ref::<wasm::ops::I64LeUOp>().is_some()
+        || opop.downcast_ref::<wasm::ops::I64GeSOp>().is_some()
+        || opop.downcast_ref::<wasm::ops::I64GeUOp>().is_some()
+}
+
+/// Lowers the i32 comparison ops to the Valida U32 `lt` (the only comparison):
+/// `a > b` is `b < a`, `a >= b` is `1 - (a < b)`, `a != b` is `(a < b) + (b < a)`,
+/// `a == b` is `1 - (a != b)` and `eqz(a)` is `1 - (0 < a)`.
+/// The signed comparisons flip the sign bits of the operands (in place) first.
+/// The intermediate values are kept in the two free stack slots above the operands.
+/// The i64 values take two cells, such ops are rejected.
+#[derive(Default)]
+pub struct CmpOpLowering {}
+
+impl RewritePattern for CmpOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<wasm::ops::I32EqzOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32EqOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32NeOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LtSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LtUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GtSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GtUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LeSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LeUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GeSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GeUOp>().is_some()
+            || is_i64_cmp_op(opop.as_ref()))
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        if is_i64_cmp_op(opop.as_ref()) {
+            return Err(anyhow!(
+                "{} is not supported by Valida (only 32-bit integers are supported)",
+                op.with_ctx(ctx)
+            ));
+        }
+        let wasm_stack_depth_before_op = op_cast::<dyn TrackedStackDepth>(opop.as_ref())
+            .ok_or_else(|| anyhow!("expected the stack depth to be tracked"))?
+            .get_stack_depth(ctx);
+        let is_eqz = opop.downcast_ref::<wasm::ops::I32EqzOp>().is_some();
+        let is_eq = opop.downcast_ref::<wasm::ops::I32EqOp>().is_some();
+        let is_ne = opop.downcast_ref::<wasm::ops::I32NeOp>().is_some();
+        let is_signed = opop.downcast_ref::<wasm::ops::I32LtSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GtSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LeSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GeSOp>().is_some();
+        // a <op> b is computed as (b < a) if swapped and as 1 - (..) if negated
+        let is_swapped = opop.downcast_ref::<wasm::ops::I32GtSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GtUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LeSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LeUOp>().is_some();
+        let is_negated = is_eqz
+            || is_eq
+            || opop.downcast_ref::<wasm::ops::I32LeSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32LeUOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GeSOp>().is_some()
+            || opop.downcast_ref::<wasm::ops::I32GeUOp>().is_some();
+        // eqz has a single operand on top, the result ends up on the first operand stack slot
+        let (result_fp, a_fp, b_fp): (i32, i32, i32) = if is_eqz {
+            let a_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.top()).into();
+            (a_fp, a_fp, a_fp)
+        } else {
+            (
+                fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into(),
+                fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into(),
+                fp_from_wasm_stack(wasm_stack_depth_before_op.top()).into(),
+            )
+        };
+        let tmp1_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next());
+        let tmp2_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next().next());
+        let op_str = op.with_ctx(ctx).to_string();
+        let imm32 = |ctx: &mut Context, fp, value| {
+            valida::ops::Imm32Op::new_checked(ctx, fp, value)
+                .map(|imm32_op| imm32_op.get_operation())
+                .map_err(|e| anyhow!("cannot lower {op_str}: {e}"))
+        };
+        let mut ops = Vec::new();
+        let positive_fp: i32 = if is_negated {
+            tmp1_fp.into()
+        } else {
+            result_fp
+        };
+        if is_eqz {
+            ops.push(imm32(ctx, tmp1_fp, 0)?);
+            let tmp1_fp: i32 = tmp1_fp.into();
+            ops.push(valida::ops::LtOp::new(ctx, positive_fp, tmp1_fp, a_fp).get_operation());
+        } else if is_eq || is_ne {
+            let tmp2_fp: i32 = tmp2_fp.into();
+            let tmp1_fp: i32 = tmp1_fp.into();
+            ops.push(valida::ops::LtOp::new(ctx, tmp1_fp, a_fp, b_fp).get_operation());
+            ops.push(valida::ops::LtOp::new(ctx, tmp2_fp, b_fp, a_fp).get_operation());
+            ops.push(valida::ops::AddOp::new(ctx, positive_fp, tmp1_fp, tmp2_fp).get_operation());
+        } else {
+            if is_signed {
+                ops.push(imm32(ctx, tmp1_fp, 0x8000_0000)?);
+                let tmp1_fp: i32 = tmp1_fp.into();
+                ops.push(valida::ops::XorOp::new(ctx, a_fp, a_fp, tmp1_fp).get_operation());
+                ops.push(valida::ops::XorOp::new(ctx, b_fp, b_fp, tmp1_fp).get_operation());
+            }
+            let (lhs_fp, rhs_fp) = if is_swapped {
+                (b_fp, a_fp)
+            } else {
+                (a_fp, b_fp)
+            };
+            ops.push(valida::ops::LtOp::new(ctx, positive_fp, lhs_fp, rhs_fp).get_operation());
+        }
+        if is_negated {
+            ops.push(imm32(ctx, tmp2_fp, 1)?);
+            let tmp2_fp: i32 = tmp2_fp.into();
+            ops.push(valida::ops::SubOp::new(ctx, result_fp, tmp2_fp, positive_fp).get_operation());
+        }
+        #[allow(clippy::unwrap_used)] // at least the comparison op is there
+        let cmp_op = ops.pop().unwrap();
+        rewriter.set_insertion_point(op);
+        for new_op in ops {
+            rewriter.insert_before(ctx, new_op)?;
+        }
+        rewriter.replace_op_with(ctx, op, cmp_op)?;
+        Ok(())
+    }
+}
+
+/// Lowers `select` without branching (Valida has no conditional move):
+/// `select(a, b, c) = b + (0 < c) * (a - b)` (wrapping), the result ends up on the first value
+/// stack slot. The intermediate values are kept in the two free stack slots above the operands.
+/// The i64 values take two cells, the typed i64 `select` is rejected.
+#[derive(Default)]
+pub struct SelectOpLowering {}
+
+impl RewritePattern for SelectOpLowering {
     fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
         Ok(op
             .deref(ctx)
             .get_op(ctx)
-            .downcast_ref::<wasm::ops::AddOp>()
+            .downcast_ref::<wasm::ops::SelectOp>()
             .is_some())
     }
 
-    #[allow(clippy::unwrap_used)]
     fn rewrite(
         &self,
         ctx: &mut Context,
         op: Ptr<Operation>,
         rewriter: &mut dyn PatternRewriter,
     ) -> Result<(), anyhow::Error> {
-        let opop = &op.deref(ctx).get_op(ctx);
-        if let Some(wasm_add_op) = opop.downcast_ref::<wasm::ops::AddOp>() {
-            let wasm_stack_depth_before_op = wasm_add_op.get_stack_depth(ctx);
-            // add wasm pops 2 values and pushes 1,
-            // so the result ends up on the first argument stack slot
-            let result_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1());
-            let arg1_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.top());
-            let arg2_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1());
-            let add_op =
-                valida::ops::AddOp::new(ctx, result_fp.into(), arg1_fp.into(), arg2_fp.into());
-            rewriter.replace_op_with(ctx, op, add_op.get_operation())?;
+        let opop = op.deref(ctx).get_op(ctx);
+        let Some(select_op) = opop.downcast_ref::<wasm::ops::SelectOp>() else {
+            return Ok(());
+        };
+        if let Some(ty) = select_op.get_type(ctx) {
+            if ty != ozk::types::i32_type(ctx) {
+                return Err(anyhow!(
+                    "{} is not supported by Valida (only 32-bit integers are supported)",
+                    op.with_ctx(ctx)
+                ));
+            }
+        }
+        let wasm_stack_depth_before_op = select_op.get_stack_depth(ctx);
+        // wasm pops the condition and 2 values and pushes 1,
+        // so the result ends up on the first value stack slot
+        let a_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1().minus1()).into();
+        let b_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.minus1()).into();
+        let cond_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.top()).into();
+        let tmp1_fp = fp_from_wasm_stack(wasm_stack_depth_before_op.next());
+        let tmp2_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op.next().next()).into();
+        let op_str = op.with_ctx(ctx).to_string();
+        let zero_op = valida::ops::Imm32Op::new_checked(ctx, tmp1_fp, 0)
+            .map_err(|e| anyhow!("cannot lower {op_str}: {e}"))?;
+        let tmp1_fp: i32 = tmp1_fp.into();
+        let ops = vec![
+            zero_op.get_operation(),
+            // 0 < c
+            valida::ops::LtOp::new(ctx, tmp1_fp, tmp1_fp, cond_fp).get_operation(),
+            // (0 < c) * (a - b)
+            valida::ops::SubOp::new(ctx, tmp2_fp, a_fp, b_fp).get_operation(),
+            valida::ops::MulOp::new(ctx, tmp2_fp, tmp2_fp, tmp1_fp).get_operation(),
+        ];
+        let add_op = valida::ops::AddOp::new(ctx, a_fp, b_fp, tmp2_fp);
+        rewriter.set_insertion_point(op);
+        for new_op in ops {
+            rewriter.insert_before(ctx, new_op)?;
         }
+        rewriter.replace_op_with(ctx, op, add_op.get_operation())?;
+        Ok(())
+    }
+}
+
+/// The stack slots are addressed by the tracked Wasm stack depth (fp offsets), so dropping
+/// a value only lowers the depth for the following ops and the op itself is removed.
+#[derive(Default)]
+pub struct DropOpLowering {}
+
+impl RewritePattern for DropOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(op
+            .deref(ctx)
+            .get_op(ctx)
+            .downcast_ref::<wasm::ops::DropOp>()
+            .is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        rewriter.erase_op(ctx, op)?;
         Ok(())
     }
 }
+
+/// Valida has no debug output, the op is removed.
+/// The value stays in its (now unused) stack slot since the stack slots are addressed by the
+/// tracked Wasm stack depth.
+#[derive(Default)]
+pub struct DebugPrintOpLowering {}
+
+impl RewritePattern for DebugPrintOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(op
+            .deref(ctx)
+            .get_op(ctx)
+            .downcast_ref::<ozk::ops::DebugPrintOp>()
+            .is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        rewriter.erase_op(ctx, op)?;
+        Ok(())
+    }
+}
+
+/// Valida has no exit code, the program just exits
+#[derive(Default)]
+pub struct HaltOpLowering {}
+
+impl RewritePattern for HaltOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        Ok(op
+            .deref(ctx)
+            .get_op(ctx)
+            .downcast_ref::<ozk::ops::HaltOp>()
+            .is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let exit_op = valida::ops::ExitOp::new_unlinked(ctx);
+        rewriter.replace_op_with(ctx, op, exit_op.get_operation())?;
+        Ok(())
+    }
+}
+
+/// `wasm.unreachable` and `ozk.trap` fail the execution
+#[derive(Default)]
+pub struct TrapOpLowering {}
+
+impl RewritePattern for TrapOpLowering {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<wasm::ops::UnreachableOp>().is_some()
+            || opop.downcast_ref::<ozk::ops::TrapOp>().is_some())
+    }
+
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let trap_op = valida::ops::TrapOp::new_unlinked(ctx);
+        rewriter.replace_op_with(ctx, op, trap_op.get_operation())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_valida_passes;
+    use crate::valida::lowering::func_lowering::WasmToValidaFuncLoweringPass;
+    use crate::wasm::track_stack_depth::WasmTrackStackDepthPass;
+
+    use super::*;
+
+    #[test]
+    fn signed_cmp_flips_sign_bits() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const -1
+        i32.const 1
+        i32.lt_s
+        local.set 0
+        local.get 0
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -8(fp) 255 255 255 255
+                        valida.imm32 -12(fp) 0 0 0 1
+                        valida.imm32 -16(fp) 128 0 0 0
+                        valida.xor -8(fp) -8(fp) -16(fp) 0 0
+                        valida.xor -12(fp) -12(fp) -16(fp) 0 0
+                        valida.lt -8(fp) -8(fp) -12(fp) 0 0
+                        valida.sw 0 -4(fp) -8(fp) 0 0
+                        valida.sw 0 -8(fp) -4(fp) 0 0
+                        valida.sw 0 8(fp) -8(fp) 0 0
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
+
+    #[test]
+    fn drop_removed() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 3
+        i32.const 5
+        drop
+        local.set 0
+        local.get 0
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -8(fp) 0 0 0 3
+                        valida.imm32 -12(fp) 0 0 0 5
+                        valida.sw 0 -4(fp) -8(fp) 0 0
+                        valida.sw 0 -8(fp) -4(fp) 0 0
+                        valida.sw 0 8(fp) -8(fp) 0 0
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
+
+    #[test]
+    fn select_without_branching() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 10
+        i32.const 20
+        i32.const 1
+        select
+        local.set 0
+        local.get 0
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -8(fp) 0 0 0 10
+                        valida.imm32 -12(fp) 0 0 0 20
+                        valida.imm32 -16(fp) 0 0 0 1
+                        valida.imm32 -20(fp) 0 0 0 0
+                        valida.lt -20(fp) -20(fp) -16(fp) 0 0
+                        valida.sub -24(fp) -8(fp) -12(fp) 0 0
+                        valida.mul -24(fp) -24(fp) -20(fp) 0 0
+                        valida.add -8(fp) -12(fp) -24(fp) 0 0
+                        valida.sw 0 -4(fp) -8(fp) 0 0
+                        valida.sw 0 -8(fp) -4(fp) 0 0
+                        valida.sw 0 8(fp) -8(fp) 0 0
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
+
+    #[test]
+    fn unreachable_to_trap() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 3
+        local.set 0
+        unreachable
+        local.get 0
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -8(fp) 0 0 0 3
+                        valida.sw 0 -4(fp) -8(fp) 0 0
+                        valida.trap
+                        valida.sw 0 -8(fp) -4(fp) 0 0
+                        valida.sw 0 8(fp) -8(fp) 0 0
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
+
+    #[test]
+    fn rem_u_checks_zero_divisor() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 17
+        i32.const 5
+        i32.rem_u
+        local.set 0
+        local.get 0
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -8(fp) 0 0 0 17
+                        valida.imm32 -12(fp) 0 0 0 5
+                        valida.beqsym main_div0_trap -12(fp) 0 0 1
+                        valida.beqsym main_div0_ok -12(fp) -12 0 0
+                        valida.label main_div0_trap
+                        valida.trap
+                        valida.label main_div0_ok
+                        valida.div -16(fp) -8(fp) -12(fp) 0 0
+                        valida.mul -16(fp) -16(fp) -12(fp) 0 0
+                        valida.sub -8(fp) -8(fp) -16(fp) 0 0
+                        valida.sw 0 -4(fp) -8(fp) 0 0
+                        valida.sw 0 -8(fp) -4(fp) 0 0
+                        valida.sw 0 8(fp) -8(fp) 0 0
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
+
+    #[test]
+    fn sign_ext_without_branching() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 128
+        i32.extend8_s
+        local.set 0
+        local.get 0
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -8(fp) 0 0 0 128
+                        valida.imm32 -12(fp) 0 0 0 255
+                        valida.and -8(fp) -8(fp) -12(fp) 0 0
+                        valida.imm32 -12(fp) 0 0 0 128
+                        valida.xor -8(fp) -8(fp) -12(fp) 0 0
+                        valida.sub -8(fp) -8(fp) -12(fp) 0 0
+                        valida.sw 0 -4(fp) -8(fp) 0 0
+                        valida.sw 0 -8(fp) -4(fp) 0 0
+                        valida.sw 0 8(fp) -8(fp) 0 0
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
+}
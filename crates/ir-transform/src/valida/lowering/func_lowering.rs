@@ -1,12 +1,19 @@
+use std::collections::BTreeSet;
+
+use anyhow::anyhow;
 use anyhow::Ok;
 use ozk_ozk_dialect as ozk;
+use ozk_ozk_dialect::types::FuncSym;
 use ozk_valida_dialect as valida;
 use ozk_wasm_dialect as wasm;
+use pliron::basic_block::BasicBlock;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::dialect_conversion::apply_partial_conversion;
 use pliron::dialect_conversion::ConversionTarget;
 use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::linked_list::ContainsLinkedList;
 use pliron::op::Op;
 use pliron::operation::Operation;
 use pliron::operation::WalkOrder;
@@ -14,14 +21,22 @@ use pliron::operation::WalkResult;
 use pliron::pass::Pass;
 use pliron::pattern_match::PatternRewriter;
 use pliron::pattern_match::RewritePattern;
+use pliron::r#type::TypeObj;
 use pliron::rewrite::RewritePatternSet;
+use pliron::with_context::AttachContext;
+use valida::op_interfaces::HasOperands;
 use valida::types::Operands;
 use wasm::op_interfaces::TrackedStackDepth;
+use wasm::ops::BrIfCondition;
 use wasm::ops::LocalGetOp;
 use wasm::ops::LocalSetOp;
 use wasm::ops::ReturnOp;
+use wasm::types::RelativeDepth;
+use wasm::types::StackDepth;
 
 use crate::valida::fp_from_wasm_stack;
+use crate::valida::ValidaCallConv;
+use crate::wasm::call_depth::CallGraph;
 
 #[derive(Default)]
 pub struct WasmToValidaFuncLoweringPass;
@@ -30,15 +45,23 @@ impl Pass for WasmToValidaFuncLoweringPass {
     fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
         let target = ConversionTarget::default();
         // TODO: set illegal ops
+        let mut recursive_funcs = BTreeSet::new();
+        op.walk_only::<wasm::ops::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            recursive_funcs.extend(CallGraph::new(ctx, module_op).recursive_funcs());
+            WalkResult::Advance
+        });
         let mut patterns = RewritePatternSet::default();
-        patterns.add(Box::<FuncOpLowering>::default());
+        patterns.add(Box::new(FuncOpLowering { recursive_funcs }));
         apply_partial_conversion(ctx, op, target, patterns)?;
         Ok(())
     }
 }
 
 #[derive(Default)]
-pub struct FuncOpLowering {}
+pub struct FuncOpLowering {
+    /// The functions that can call themselves, their frames must not overlap
+    recursive_funcs: BTreeSet<FuncSym>,
+}
 
 impl RewritePattern for FuncOpLowering {
     fn match_and_rewrite(
@@ -52,11 +75,23 @@ impl RewritePattern for FuncOpLowering {
             return Ok(false);
         };
 
+        reject_host_calls(wasm_func_op, ctx)?;
+        reject_indirect_calls(wasm_func_op, ctx)?;
+        reject_memory_ops(wasm_func_op, ctx)?;
+        let frame_size = frame_size(wasm_func_op, ctx)?;
         convert_func_arg_and_locals(wasm_func_op, ctx, rewriter)?;
         convert_return_ops(wasm_func_op, ctx, rewriter)?;
-        convert_call_ops(wasm_func_op, ctx, rewriter)?;
+        convert_call_ops(wasm_func_op, frame_size, ctx, rewriter)?;
+        self.check_frame_advance(wasm_func_op, frame_size, ctx)?;
+        // reuses the frame of the current function, so it's not subject to the check above
+        convert_return_call_ops(wasm_func_op, frame_size, ctx, rewriter)?;
+        insert_div_zero_checks(wasm_func_op, ctx);
+        // before the ifs are flattened, the branches out of them need their labels
+        convert_branch_ops(wasm_func_op, ctx, rewriter)?;
+        convert_if_ops(wasm_func_op, ctx, rewriter)?;
 
         let func_op = valida::ops::FuncOp::new_unlinked(ctx, wasm_func_op.get_symbol_name(ctx));
+        func_op.set_frame_size(ctx, frame_size);
         for op in wasm_func_op.op_iter(ctx) {
             op.unlink(ctx);
             op.insert_at_back(func_op.get_entry_block(ctx), ctx);
@@ -66,8 +101,139 @@ impl RewritePattern for FuncOpLowering {
     }
 }
 
+impl FuncOpLowering {
+    /// Checks that every call advances fp past the caller's frame, so that the callee's frame
+    /// (another activation of the same function in case of recursion) doesn't overwrite it.
+    fn check_frame_advance(
+        &self,
+        wasm_func_op: &wasm::ops::FuncOp,
+        frame_size: u32,
+        ctx: &Context,
+    ) -> Result<(), anyhow::Error> {
+        let func_sym = FuncSym::from(wasm_func_op.get_symbol_name(ctx));
+        let mut result = Ok(());
+        wasm_func_op
+            .get_operation()
+            .walk_only::<valida::ops::JalSymOp>(ctx, WalkOrder::PostOrder, &mut |jalsym_op| {
+                let fp_advance = -jalsym_op.get_operands(ctx).c().as_i32();
+                if fp_advance < frame_size as i32 && result.is_ok() {
+                    let recursive = if self.recursive_funcs.contains(&func_sym) {
+                        " recursive"
+                    } else {
+                        ""
+                    };
+                    result = Err(anyhow!(
+                        "call to {} from the{recursive} function {} advances fp by {fp_advance} \
+                         bytes, less than the caller's frame size {frame_size}",
+                        jalsym_op.get_target_sym(ctx),
+                        func_sym.as_ref(),
+                    ));
+                }
+                WalkResult::Advance
+            });
+        result
+    }
+}
+
+/// The host functions (I/O) are not implemented on Valida yet
+fn reject_host_calls(wasm_func_op: &wasm::ops::FuncOp, ctx: &Context) -> Result<(), anyhow::Error> {
+    let mut host_funcs = Vec::new();
+    wasm_func_op
+        .get_operation()
+        .walk_only::<ozk::ops::HostCallOp>(ctx, WalkOrder::PostOrder, &mut |call_op| {
+            host_funcs.push(call_op.get_func_sym(ctx));
+            WalkResult::Advance
+        });
+    if let Some(host_func) = host_funcs.first() {
+        return Err(anyhow!(
+            "call to the host function {host_func} from {} is not supported by Valida",
+            wasm_func_op.get_symbol_name(ctx)
+        ));
+    }
+    Ok(())
+}
+
+/// The indirect calls dispatch on the table index kept in a reserved slot (see
+/// [crate::wasm::call_indirect]), which Valida doesn't resolve yet
+fn reject_indirect_calls(
+    wasm_func_op: &wasm::ops::FuncOp,
+    ctx: &Context,
+) -> Result<(), anyhow::Error> {
+    let mut indirect_calls = Vec::new();
+    wasm_func_op
+        .get_operation()
+        .walk_only::<wasm::ops::CallIndirectOp>(ctx, WalkOrder::PostOrder, &mut |_| {
+            indirect_calls.push("call_indirect");
+            WalkResult::Advance
+        });
+    wasm_func_op
+        .get_operation()
+        .walk_only::<wasm::ops::ReturnCallIndirectOp>(ctx, WalkOrder::PostOrder, &mut |_| {
+            indirect_calls.push("return_call_indirect");
+            WalkResult::Advance
+        });
+    if let Some(indirect_call) = indirect_calls.first() {
+        return Err(anyhow!(
+            "{indirect_call} in {} is not supported by Valida (no function tables)",
+            wasm_func_op.get_symbol_name(ctx)
+        ));
+    }
+    Ok(())
+}
+
+/// Valida has no memory layout for the Wasm memory yet, so the loads and stores (including the
+/// ones emitted for the data segments) are not lowered
+fn reject_memory_ops(wasm_func_op: &wasm::ops::FuncOp, ctx: &Context) -> Result<(), anyhow::Error> {
+    let mut mem_ops = Vec::new();
+    wasm_func_op
+        .get_operation()
+        .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+            let opop = op.deref(ctx).get_op(ctx);
+            if opop.downcast_ref::<wasm::ops::LoadOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::StoreOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::I32Load8SOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::I32Load8UOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::I32Load16SOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::I32Load16UOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::I64Load8SOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::I64Load8UOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::I64Load16SOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::I64Load16UOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::I64Load32SOp>().is_some()
+                || opop.downcast_ref::<wasm::ops::I64Load32UOp>().is_some()
+            {
+                mem_ops.push(op);
+            }
+            WalkResult::Advance
+        });
+    if let Some(mem_op) = mem_ops.first() {
+        return Err(anyhow!(
+            "{} in {} is not supported by Valida (no loads and stores of the Wasm memory)",
+            mem_op.deref(ctx).get_opid().with_ctx(ctx),
+            wasm_func_op.get_symbol_name(ctx)
+        ));
+    }
+    Ok(())
+}
+
+/// See [ValidaCallConv::frame_size]
+fn frame_size(wasm_func_op: &wasm::ops::FuncOp, ctx: &Context) -> Result<u32, anyhow::Error> {
+    let max_stack_depth = wasm_func_op.get_max_stack_depth(ctx).ok_or_else(|| {
+        anyhow!(
+            "expected the max stack depth of {} to be tracked",
+            wasm_func_op.get_symbol_name(ctx)
+        )
+    })?;
+    let max_stack_depth = i32::from(max_stack_depth) as u32;
+    // the locals are addressed by their index (the parameters included)
+    let locals_cells =
+        (wasm_func_op.get_type(ctx).get_inputs().len() + wasm_func_op.get_locals(ctx).len()) as u32;
+    Ok(ValidaCallConv::frame_size(max_stack_depth, locals_cells))
+}
+
 fn convert_call_ops(
     wasm_func_op: &wasm::ops::FuncOp,
+    frame_size: u32,
     ctx: &mut Context,
     rewriter: &mut dyn PatternRewriter,
 ) -> Result<(), anyhow::Error> {
@@ -82,33 +248,527 @@ fn convert_call_ops(
     );
     for call_op in call_ops {
         let wasm_stack_depth_before_op = call_op.get_stack_depth(ctx);
-        let fp_last_stack_height: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op).into();
-        // 12 is the stack frame size (return value + return fp + return address)
-        // Call convention for wasm:
-        // arg1
-        // arg2
-        // Return value (if no args, otherwise in arg1)
-        // Return FP
-        // Return address (current FP for callee)
-        // Local 1
-        // ...
-        // Local n
-        let fp_for_return_address = fp_last_stack_height - 12;
-        let return_fp_value = fp_for_return_address + 4;
-        let fp_to_restore_after_call = fp_last_stack_height - 12;
+        let func_type = call_op.get_func_type(ctx);
+        let num_args = func_type.get_inputs().len() as i32;
+        // see [ValidaCallConv] for the callee frame layout
+        let callee_fp = -(frame_size as i32) - num_args * 4;
+        let mut ops = Vec::new();
+        for arg_idx in 0..num_args {
+            let arg_depth = i32::from(wasm_stack_depth_before_op) - arg_idx;
+            let arg_fp: i32 = fp_from_wasm_stack(arg_depth.into()).into();
+            let sw_op = valida::ops::SwOp::new(
+                ctx,
+                callee_fp + ValidaCallConv::arg_offset(arg_idx),
+                arg_fp,
+            );
+            ops.push(sw_op.get_operation());
+        }
         let imm32_op = valida::ops::Imm32Op::new_unlinked(
             ctx,
-            Operands::from_i32(return_fp_value, 0, 0, 0, -fp_to_restore_after_call),
+            Operands::from_i32(
+                callee_fp + ValidaCallConv::RETURN_FP_OFFSET,
+                0,
+                0,
+                0,
+                -callee_fp,
+            ),
         );
+        ops.push(imm32_op.get_operation());
+        let jalsym_op =
+            valida::ops::JalSymOp::new(ctx, callee_fp, callee_fp, call_op.get_func_sym(ctx));
+        ops.push(jalsym_op.get_operation());
         rewriter.set_insertion_point(call_op.get_operation());
-        rewriter.insert_before(ctx, imm32_op.get_operation())?;
-        let jalsym_op = valida::ops::JalSymOp::new(
-            ctx,
-            fp_for_return_address,
-            fp_for_return_address,
-            call_op.get_func_sym(ctx),
-        );
-        rewriter.replace_op_with(ctx, call_op.get_operation(), jalsym_op.get_operation())?;
+        for op in ops {
+            rewriter.insert_before(ctx, op)?;
+        }
+        if func_type.get_results().is_empty() {
+            rewriter.erase_op(ctx, call_op.get_operation())?;
+        } else {
+            // copy the return value to the caller's stack in place of the args
+            let result_depth = i32::from(wasm_stack_depth_before_op) - num_args + 1;
+            let result_fp: i32 = fp_from_wasm_stack(result_depth.into()).into();
+            let sw_op = valida::ops::SwOp::new(
+                ctx,
+                result_fp,
+                callee_fp + ValidaCallConv::return_value_offset(num_args),
+            );
+            rewriter.replace_op_with(ctx, call_op.get_operation(), sw_op.get_operation())?;
+        }
+    }
+    Ok(())
+}
+
+/// Lowers the tail calls so that the callee takes over the frame of the current function and
+/// returns directly to its caller. The callee's linkage area and args are first built below
+/// the current frame (the same place as for a regular call), then moved up to the current
+/// function's linkage area, shifted by the difference in the number of args so that the
+/// callee's return value lands where the caller expects the current function's one.
+fn convert_return_call_ops(
+    wasm_func_op: &wasm::ops::FuncOp,
+    frame_size: u32,
+    ctx: &mut Context,
+    rewriter: &mut dyn PatternRewriter,
+) -> Result<(), anyhow::Error> {
+    let mut return_call_ops = Vec::new();
+    wasm_func_op
+        .get_operation()
+        .walk_only::<ozk::ops::ReturnCallOp>(ctx, WalkOrder::PostOrder, &mut |op| {
+            return_call_ops.push(*op);
+            WalkResult::Advance
+        });
+    let num_func_args = wasm_func_op.get_type(ctx).get_inputs().len() as i32;
+    for return_call_op in return_call_ops {
+        let func_sym = return_call_op.get_func_sym(ctx);
+        let wasm_stack_depth_before_op = return_call_op.get_stack_depth(ctx);
+        let num_args = return_call_op.get_func_type(ctx).get_inputs().len() as i32;
+        // the callee's fp relative to the current one
+        let callee_fp = (num_func_args - num_args) * 4;
+        let tmp_fp = -(frame_size as i32) - num_args * 4;
+        let mut ops = Vec::new();
+        for arg_idx in 0..num_args {
+            let arg_depth = i32::from(wasm_stack_depth_before_op) - arg_idx;
+            let arg_fp: i32 = fp_from_wasm_stack(arg_depth.into()).into();
+            let sw_op =
+                valida::ops::SwOp::new(ctx, tmp_fp + ValidaCallConv::arg_offset(arg_idx), arg_fp);
+            ops.push(sw_op.get_operation());
+        }
+        // the return address and the return fp of the current function
+        for offset in [
+            ValidaCallConv::RETURN_ADDRESS_OFFSET,
+            ValidaCallConv::RETURN_FP_OFFSET,
+        ] {
+            ops.push(valida::ops::SwOp::new(ctx, tmp_fp + offset, offset).get_operation());
+        }
+        if callee_fp != 0 {
+            // the return fp is relative to the callee's fp, use the cell at 8 for the shift
+            // since it's not moved (the callee writes its return value there, if at all)
+            let shift_fp = tmp_fp + 8;
+            let imm32_op =
+                valida::ops::Imm32Op::new_checked(ctx, shift_fp.into(), callee_fp.unsigned_abs())
+                    .map_err(|e| anyhow!("cannot lower the tail call to {func_sym}: {e}"))?;
+            ops.push(imm32_op.get_operation());
+            let op = if callee_fp > 0 {
+                valida::ops::SubOp::new(ctx, tmp_fp + 4, tmp_fp + 4, shift_fp).get_operation()
+            } else {
+                valida::ops::AddOp::new(ctx, tmp_fp + 4, tmp_fp + 4, shift_fp).get_operation()
+            };
+            ops.push(op);
+        }
+        // the destination is above the source, so move the highest cells first
+        for cell_idx in (0..3 + num_args).rev() {
+            if cell_idx == 2 {
+                continue;
+            }
+            let sw_op =
+                valida::ops::SwOp::new(ctx, callee_fp + cell_idx * 4, tmp_fp + cell_idx * 4);
+            ops.push(sw_op.get_operation());
+        }
+        // the pc + 1 is never used, store it in the callee's locals area
+        let jalsym_op = valida::ops::JalSymOp::new(ctx, callee_fp - 4, callee_fp, func_sym);
+        ops.push(jalsym_op.get_operation());
+        rewriter.set_insertion_point(return_call_op.get_operation());
+        for op in ops {
+            rewriter.insert_before(ctx, op)?;
+        }
+        rewriter.erase_op(ctx, return_call_op.get_operation())?;
+    }
+    Ok(())
+}
+
+/// Where a branch jumps to: the end of a block or `if`, or the start of a loop
+struct BranchTarget {
+    /// `None` for the function body, a branch to it returns
+    label: Option<String>,
+    /// The stack depth at the target
+    depth: i32,
+    /// The number of values passed to the target (on top of the stack at the branch)
+    arity: i32,
+}
+
+impl BranchTarget {
+    /// Are the passed values in other cells at the target than at the branch (at `depth`)?
+    fn moves_values(&self, depth: i32) -> bool {
+        self.arity > 0 && self.depth != depth
+    }
+}
+
+/// Flattens the blocks and loops into labels and lowers the branches to them into the jumps.
+/// The values passed to the target are copied from the top of the stack at the branch to the
+/// top of the stack at the target, the values below them are discarded. A branch to the
+/// function body is a return. Valida has only `beq`, so a branch if non-zero jumps over the
+/// jump to the target if the condition is 0, and a `br_table` compares the index with each
+/// case position. The `if` ops are flattened by [convert_if_ops], the branches to their end
+/// jump to its labels.
+fn convert_branch_ops(
+    wasm_func_op: &wasm::ops::FuncOp,
+    ctx: &mut Context,
+    rewriter: &mut dyn PatternRewriter,
+) -> Result<(), anyhow::Error> {
+    // the `if` labels are numbered in the same order as in [convert_if_ops]
+    let mut if_ops = Vec::new();
+    wasm_func_op.get_operation().walk_only::<wasm::ops::IfOp>(
+        ctx,
+        WalkOrder::PostOrder,
+        &mut |op| {
+            if_ops.push(op.get_operation());
+            WalkResult::Advance
+        },
+    );
+    let func_type = wasm_func_op.get_type(ctx);
+    let mut lowering = BranchLowering {
+        func_name: wasm_func_op.get_symbol_name(ctx),
+        num_func_args: func_type.get_inputs().len() as i32,
+        if_ops,
+        num_blocks: 0,
+        num_loops: 0,
+        num_branches: 0,
+    };
+    let mut targets = vec![BranchTarget {
+        label: None,
+        depth: 0,
+        arity: func_type.get_results().len() as i32,
+    }];
+    lowering.lower_block(
+        ctx,
+        wasm_func_op.get_entry_block(ctx),
+        &mut targets,
+        rewriter,
+    )
+}
+
+struct BranchLowering {
+    func_name: String,
+    num_func_args: i32,
+    /// The `if` ops in the order of their labels
+    if_ops: Vec<Ptr<Operation>>,
+    num_blocks: usize,
+    num_loops: usize,
+    num_branches: usize,
+}
+
+impl BranchLowering {
+    /// Lowers the branches in the ops of the block, `targets` are the constructs around it
+    /// (the innermost last)
+    fn lower_block(
+        &mut self,
+        ctx: &mut Context,
+        block: Ptr<BasicBlock>,
+        targets: &mut Vec<BranchTarget>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+        for op in ops {
+            let opop = op.deref(ctx).get_op(ctx);
+            if let Some(block_op) = opop.downcast_ref::<wasm::ops::BlockOp>() {
+                let (params, results) = construct_arity(ctx, block_op.get_type(ctx))?;
+                let depth = i32::from(block_op.get_stack_depth(ctx));
+                let label = format!("{}_block{}_end", self.func_name, self.num_blocks);
+                self.num_blocks += 1;
+                targets.push(BranchTarget {
+                    label: Some(label.clone()),
+                    depth: depth - params + results,
+                    arity: results,
+                });
+                self.lower_block(ctx, block_op.get_block(ctx), targets, rewriter)?;
+                targets.pop();
+                let body_ops: Vec<Ptr<Operation>> =
+                    block_op.get_block(ctx).deref(ctx).iter(ctx).collect();
+                for body_op in body_ops {
+                    body_op.unlink(ctx);
+                    body_op.insert_before(ctx, op);
+                }
+                valida::ops::LabelOp::new_unlinked(ctx, label)
+                    .get_operation()
+                    .insert_before(ctx, op);
+                rewriter.erase_op(ctx, op)?;
+            } else if let Some(loop_op) = opop.downcast_ref::<wasm::ops::LoopOp>() {
+                let (params, _) = construct_arity(ctx, loop_op.get_type(ctx))?;
+                let label = format!("{}_loop{}_start", self.func_name, self.num_loops);
+                self.num_loops += 1;
+                targets.push(BranchTarget {
+                    label: Some(label.clone()),
+                    depth: i32::from(loop_op.get_stack_depth(ctx)),
+                    arity: params,
+                });
+                self.lower_block(ctx, loop_op.get_block(ctx), targets, rewriter)?;
+                targets.pop();
+                valida::ops::LabelOp::new_unlinked(ctx, label)
+                    .get_operation()
+                    .insert_before(ctx, op);
+                let body_ops: Vec<Ptr<Operation>> =
+                    loop_op.get_block(ctx).deref(ctx).iter(ctx).collect();
+                for body_op in body_ops {
+                    body_op.unlink(ctx);
+                    body_op.insert_before(ctx, op);
+                }
+                rewriter.erase_op(ctx, op)?;
+            } else if let Some(if_op) = opop.downcast_ref::<wasm::ops::IfOp>() {
+                let (params, results) = construct_arity(ctx, if_op.get_type(ctx))?;
+                // the condition is popped before the branches
+                let depth = i32::from(if_op.get_stack_depth(ctx)) - 1;
+                let if_idx = self
+                    .if_ops
+                    .iter()
+                    .position(|if_op| *if_op == op)
+                    .ok_or_else(|| anyhow!("unknown if op in the function {}", self.func_name))?;
+                targets.push(BranchTarget {
+                    label: Some(format!("{}_if{if_idx}_end", self.func_name)),
+                    depth: depth - params + results,
+                    arity: results,
+                });
+                self.lower_block(ctx, if_op.get_then_block(ctx), targets, rewriter)?;
+                self.lower_block(ctx, if_op.get_else_block(ctx), targets, rewriter)?;
+                targets.pop();
+            } else if let Some(br_op) = opop.downcast_ref::<wasm::ops::BrOp>() {
+                let target = get_target(targets, br_op.get_relative_depth(ctx))?;
+                let depth = i32::from(br_op.get_stack_depth(ctx));
+                for new_op in self.jump_to_target(ctx, target, depth) {
+                    new_op.insert_before(ctx, op);
+                }
+                rewriter.erase_op(ctx, op)?;
+            } else if let Some(br_if_op) = opop.downcast_ref::<wasm::ops::BrIfOp>() {
+                let target = get_target(targets, br_if_op.get_relative_depth(ctx))?;
+                // the condition is on top of the passed values
+                let depth = i32::from(br_if_op.get_stack_depth(ctx));
+                let cond_fp: i32 = fp_from_wasm_stack(depth.into()).into();
+                let condition = br_if_op.get_condition(ctx);
+                if condition.is_i64() {
+                    return Err(anyhow!(
+                        "br_if on an i64 value in the function {} is not supported by Valida, \
+                         the i64 values take two cells",
+                        self.func_name
+                    ));
+                }
+                let direct_label = target
+                    .label
+                    .clone()
+                    .filter(|_| !target.moves_values(depth - 1));
+                let new_ops = match (condition, direct_label) {
+                    // nothing to copy, jump straight to the target
+                    (BrIfCondition::I32Zero, Some(label)) => {
+                        vec![valida::ops::BeqSymOp::new_imm(ctx, label, cond_fp, 0).get_operation()]
+                    }
+                    (BrIfCondition::I32Zero, None) => {
+                        let take_label = self.next_branch_label("take");
+                        let skip_label = self.next_branch_label("skip");
+                        let mut new_ops = vec![
+                            valida::ops::BeqSymOp::new_imm(ctx, take_label.clone(), cond_fp, 0)
+                                .get_operation(),
+                            valida::ops::BeqSymOp::new_always(ctx, skip_label.clone(), cond_fp)
+                                .get_operation(),
+                            valida::ops::LabelOp::new_unlinked(ctx, take_label).get_operation(),
+                        ];
+                        new_ops.extend(self.jump_to_target(ctx, target, depth - 1));
+                        new_ops.push(
+                            valida::ops::LabelOp::new_unlinked(ctx, skip_label).get_operation(),
+                        );
+                        new_ops
+                    }
+                    (
+                        BrIfCondition::I32NonZero
+                        | BrIfCondition::I64NonZero
+                        | BrIfCondition::I64Zero,
+                        _,
+                    ) => {
+                        let skip_label = self.next_branch_label("skip");
+                        let mut new_ops = vec![valida::ops::BeqSymOp::new_imm(
+                            ctx,
+                            skip_label.clone(),
+                            cond_fp,
+                            0,
+                        )
+                        .get_operation()];
+                        new_ops.extend(self.jump_to_target(ctx, target, depth - 1));
+                        new_ops.push(
+                            valida::ops::LabelOp::new_unlinked(ctx, skip_label).get_operation(),
+                        );
+                        new_ops
+                    }
+                };
+                for new_op in new_ops {
+                    new_op.insert_before(ctx, op);
+                }
+                rewriter.erase_op(ctx, op)?;
+            } else if let Some(br_table_op) = opop.downcast_ref::<wasm::ops::BrTableOp>() {
+                // the index is on top of the passed values
+                let depth = i32::from(br_table_op.get_stack_depth(ctx));
+                let index_fp: i32 = fp_from_wasm_stack(depth.into()).into();
+                let mut new_ops = Vec::new();
+                // the cases that have to copy the values before the jump
+                let mut case_jumps = Vec::new();
+                for (index, relative_depth) in br_table_op.get_targets(ctx).into_iter().enumerate()
+                {
+                    let target = get_target(targets, relative_depth)?;
+                    let label = match &target.label {
+                        Some(label) if !target.moves_values(depth - 1) => label.clone(),
+                        Some(_) | None => {
+                            let case_label = self.next_branch_label("case");
+                            case_jumps.push((case_label.clone(), relative_depth));
+                            case_label
+                        }
+                    };
+                    new_ops.push(
+                        valida::ops::BeqSymOp::new_imm(ctx, label, index_fp, index as i32)
+                            .get_operation(),
+                    );
+                }
+                let default = get_target(targets, br_table_op.get_default(ctx))?;
+                new_ops.extend(self.jump_to_target(ctx, default, depth - 1));
+                for (case_label, relative_depth) in case_jumps {
+                    new_ops
+                        .push(valida::ops::LabelOp::new_unlinked(ctx, case_label).get_operation());
+                    let target = get_target(targets, relative_depth)?;
+                    new_ops.extend(self.jump_to_target(ctx, target, depth - 1));
+                }
+                for new_op in new_ops {
+                    new_op.insert_before(ctx, op);
+                }
+                rewriter.erase_op(ctx, op)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies the values passed to the target from the top of the stack at `depth` and jumps
+    /// to the target (or returns)
+    fn jump_to_target(
+        &self,
+        ctx: &mut Context,
+        target: &BranchTarget,
+        depth: i32,
+    ) -> Vec<Ptr<Operation>> {
+        let top_fp: i32 = fp_from_wasm_stack(depth.into()).into();
+        let Some(label) = &target.label else {
+            return return_ops(ctx, self.num_func_args, depth.into());
+        };
+        let mut ops = Vec::new();
+        // the target cells are not above the source ones, copy the deepest value first
+        if target.moves_values(depth) {
+            for idx in 0..target.arity {
+                let from_depth = depth - target.arity + 1 + idx;
+                let to_depth = target.depth - target.arity + 1 + idx;
+                let sw_op = valida::ops::SwOp::new(
+                    ctx,
+                    fp_from_wasm_stack(to_depth.into()).into(),
+                    fp_from_wasm_stack(from_depth.into()).into(),
+                );
+                ops.push(sw_op.get_operation());
+            }
+        }
+        ops.push(valida::ops::BeqSymOp::new_always(ctx, label.clone(), top_fp).get_operation());
+        ops
+    }
+
+    fn next_branch_label(&mut self, kind: &str) -> String {
+        let label = format!("{}_br{}_{kind}", self.func_name, self.num_branches);
+        self.num_branches += 1;
+        label
+    }
+}
+
+/// The target of the branch with the given relative depth
+fn get_target(
+    targets: &[BranchTarget],
+    relative_depth: RelativeDepth,
+) -> Result<&BranchTarget, anyhow::Error> {
+    let relative_depth = u32::from(relative_depth) as usize;
+    targets
+        .len()
+        .checked_sub(relative_depth + 1)
+        .map(|idx| &targets[idx])
+        .ok_or_else(|| anyhow!("branch out of the function (relative depth {relative_depth})"))
+}
+
+/// The number of the params and results of a block, loop or `if`
+fn construct_arity(ctx: &Context, ty: Ptr<TypeObj>) -> Result<(i32, i32), anyhow::Error> {
+    let ty_ref = ty.deref(ctx);
+    let func_type = ty_ref
+        .downcast_ref::<FunctionType>()
+        .ok_or_else(|| anyhow!("expected a function type of the block"))?;
+    Ok((
+        func_type.get_inputs().len() as i32,
+        func_type.get_results().len() as i32,
+    ))
+}
+
+/// Valida `div` doesn't fail on zero divisor, so every `div` (lowered from the Wasm division by
+/// [crate::valida::lowering::arith_op_lowering::IntDivOpLowering]) is preceded by the Wasm trap:
+/// `beqsym trap divisor 0`, `beqsym ok` (unconditional), `label trap`, `trap`, `label ok`.
+fn insert_div_zero_checks(wasm_func_op: &wasm::ops::FuncOp, ctx: &mut Context) {
+    let mut div_ops = Vec::new();
+    wasm_func_op
+        .get_operation()
+        .walk_only::<valida::ops::DivOp>(ctx, WalkOrder::PostOrder, &mut |op| {
+            div_ops.push(*op);
+            WalkResult::Advance
+        });
+    let func_name = wasm_func_op.get_symbol_name(ctx);
+    for (idx, div_op) in div_ops.into_iter().enumerate() {
+        let trap_label = format!("{func_name}_div{idx}_trap");
+        let ok_label = format!("{func_name}_div{idx}_ok");
+        let divisor_fp = div_op.get_operands(ctx).c().as_i32();
+        let check_ops = vec![
+            valida::ops::BeqSymOp::new_imm(ctx, trap_label.clone(), divisor_fp, 0).get_operation(),
+            valida::ops::BeqSymOp::new_always(ctx, ok_label.clone(), divisor_fp).get_operation(),
+            valida::ops::LabelOp::new_unlinked(ctx, trap_label).get_operation(),
+            valida::ops::TrapOp::new_unlinked(ctx).get_operation(),
+            valida::ops::LabelOp::new_unlinked(ctx, ok_label).get_operation(),
+        ];
+        for check_op in check_ops {
+            check_op.insert_before(ctx, div_op.get_operation());
+        }
+    }
+}
+
+/// Flattens the `wasm.if` ops into the conditional jumps over the labeled branches:
+/// `beqsym else cond 0`, then ops, `beqsym end` (unconditional), `label else`, else ops, `label end`.
+fn convert_if_ops(
+    wasm_func_op: &wasm::ops::FuncOp,
+    ctx: &mut Context,
+    rewriter: &mut dyn PatternRewriter,
+) -> Result<(), anyhow::Error> {
+    let mut if_ops = Vec::new();
+    wasm_func_op.get_operation().walk_only::<wasm::ops::IfOp>(
+        ctx,
+        WalkOrder::PostOrder,
+        &mut |op| {
+            if_ops.push(*op);
+            WalkResult::Advance
+        },
+    );
+    let func_name = wasm_func_op.get_symbol_name(ctx);
+    for (idx, if_op) in if_ops.into_iter().enumerate() {
+        let else_label = format!("{func_name}_if{idx}_else");
+        let end_label = format!("{func_name}_if{idx}_end");
+        // the condition is on top of the stack before the op
+        let cond_fp: i32 = fp_from_wasm_stack(if_op.get_stack_depth(ctx)).into();
+        let op = if_op.get_operation();
+        valida::ops::BeqSymOp::new_imm(ctx, else_label.clone(), cond_fp, 0)
+            .get_operation()
+            .insert_before(ctx, op);
+        let then_ops: Vec<Ptr<Operation>> =
+            if_op.get_then_block(ctx).deref(ctx).iter(ctx).collect();
+        for then_op in then_ops {
+            then_op.unlink(ctx);
+            then_op.insert_before(ctx, op);
+        }
+        // any cell is equal to itself, the value in it is irrelevant
+        valida::ops::BeqSymOp::new_always(ctx, end_label.clone(), cond_fp)
+            .get_operation()
+            .insert_before(ctx, op);
+        valida::ops::LabelOp::new_unlinked(ctx, else_label)
+            .get_operation()
+            .insert_before(ctx, op);
+        let else_ops: Vec<Ptr<Operation>> =
+            if_op.get_else_block(ctx).deref(ctx).iter(ctx).collect();
+        for else_op in else_ops {
+            else_op.unlink(ctx);
+            else_op.insert_before(ctx, op);
+        }
+        valida::ops::LabelOp::new_unlinked(ctx, end_label)
+            .get_operation()
+            .insert_before(ctx, op);
+        rewriter.erase_op(ctx, op)?;
     }
     Ok(())
 }
@@ -129,27 +789,37 @@ fn convert_return_ops(
         // TODO: check func signature if there is a return value (after I/O is implemented)
         // if wasm_func_op.get_type_typed(ctx).get_results().len() == 1 {
         let wasm_stack_depth_before_op = return_op.get_stack_depth(ctx);
-        let last_stack_value_fp_offset = fp_from_wasm_stack(wasm_stack_depth_before_op);
-        // let return_value_fp_offset = 4;
         let func_arg_num: i32 = wasm_func_op.get_type(ctx).get_inputs().len() as i32;
-        let return_value_fp_offset = 8 + func_arg_num * 4; // Arg 1 cell, or new cell after
-        let sw_op = valida::ops::SwOp::new(
-            ctx,
-            return_value_fp_offset,
-            last_stack_value_fp_offset.into(),
-        );
-        rewriter.set_insertion_point(return_op.get_operation());
-        rewriter.insert_before(ctx, sw_op.get_operation())?;
         // } else {
         //     todo!("wasm.func -> valida: multiple return values are not supported yet");
         // }
         // let c = 12 - (-func_arg_num + wasm_func_op.get_type(ctx).get_results().len() as i32) * 4;
-        let ret_op = valida::ops::JalvOp::new_return_pseudo_op(ctx);
-        rewriter.replace_op_with(ctx, return_op.get_operation(), ret_op.get_operation())?;
+        rewriter.set_insertion_point(return_op.get_operation());
+        for op in return_ops(ctx, func_arg_num, wasm_stack_depth_before_op) {
+            rewriter.insert_before(ctx, op)?;
+        }
+        rewriter.erase_op(ctx, return_op.get_operation())?;
     }
     Ok(())
 }
 
+/// Copies the value on top of the stack at `stack_depth` to the return value cell and returns
+fn return_ops(
+    ctx: &mut Context,
+    func_arg_num: i32,
+    stack_depth: StackDepth,
+) -> Vec<Ptr<Operation>> {
+    let last_stack_value_fp_offset = fp_from_wasm_stack(stack_depth);
+    let return_value_fp_offset = ValidaCallConv::return_value_offset(func_arg_num);
+    let sw_op = valida::ops::SwOp::new(
+        ctx,
+        return_value_fp_offset,
+        last_stack_value_fp_offset.into(),
+    );
+    let ret_op = valida::ops::JalvOp::new_return_pseudo_op(ctx);
+    vec![sw_op.get_operation(), ret_op.get_operation()]
+}
+
 fn convert_func_arg_and_locals(
     wasm_func_op: &wasm::ops::FuncOp,
     ctx: &mut Context,
@@ -162,7 +832,6 @@ fn convert_func_arg_and_locals(
             local_get_ops.push(*op);
             WalkResult::Advance
         });
-    let fp_func_first_arg: i32 = 12;
     for local_get_op in local_get_ops {
         let zero_based_index: i32 = u32::from(local_get_op.get_index(ctx)) as i32;
         let wasm_stack_depth_before_op = local_get_op.get_stack_depth(ctx);
@@ -170,10 +839,10 @@ fn convert_func_arg_and_locals(
         let from_fp: i32 =
             if zero_based_index < wasm_func_op.get_type(ctx).get_inputs().len() as i32 {
                 // this is function paramter
-                fp_func_first_arg + zero_based_index * 4
+                ValidaCallConv::arg_offset(zero_based_index)
             } else {
                 // this is a local variable
-                -(zero_based_index + 1) * 4
+                ValidaCallConv::local_offset(zero_based_index)
             };
         let sw_op = valida::ops::SwOp::new(ctx, to_fp, from_fp);
         rewriter.replace_op_with(ctx, local_get_op.get_operation(), sw_op.get_operation())?;
@@ -190,7 +859,7 @@ fn convert_func_arg_and_locals(
         let zero_based_index: i32 = u32::from(local_set_op.get_index(ctx)) as i32;
         let wasm_stack_depth_before_op = local_set_op.get_stack_depth(ctx);
         let from_fp: i32 = fp_from_wasm_stack(wasm_stack_depth_before_op).into();
-        let to_fp: i32 = -(zero_based_index + 1) * 4;
+        let to_fp: i32 = ValidaCallConv::local_offset(zero_based_index);
         let sw_op = valida::ops::SwOp::new(ctx, to_fp, from_fp);
         rewriter.replace_op_with(ctx, local_set_op.get_operation(), sw_op.get_operation())?;
     }
@@ -236,7 +905,7 @@ mod tests {
                       entry():
                         valida.sw 0 -4(fp) 12(fp) 0 0
                         valida.sw 0 -8(fp) 16(fp) 0 0
-                        wasm.add
+                        wasm.i32.add
                         valida.sw 0 16(fp) -4(fp) 0 0
                         valida.jalv -4(fp) 0(fp) 4(fp) 0 0
                     }
@@ -252,6 +921,46 @@ mod tests {
         )
     }
 
+    #[test]
+    fn if_else_lowering() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main (result i32)
+        i32.const 1
+        (if (result i32)
+            (then
+                i32.const 7)
+            (else
+                i32.const 9))
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_3_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -4(fp) 0 0 0 1
+                        valida.beqsym main_if0_else -4(fp) 0 0 1
+                        valida.imm32 -4(fp) 0 0 0 7
+                        valida.beqsym main_if0_end -4(fp) -4 0 0
+                        valida.label main_if0_else
+                        valida.imm32 -4(fp) 0 0 0 9
+                        valida.label main_if0_end
+                        valida.sw 0 8(fp) -4(fp) 0 0
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
+
     #[test]
     fn smoke_local_var_access() {
         check_wasm_valida_passes(
@@ -287,4 +996,40 @@ mod tests {
                 }"#]],
         )
     }
+
+    #[test]
+    fn wide_const_split_into_bytes() {
+        check_wasm_valida_passes(
+            vec![
+                Box::new(WasmTrackStackDepthPass::new_reserve_space_for_locals()),
+                Box::<WasmToValidaArithLoweringPass>::default(),
+                Box::<WasmToValidaFuncLoweringPass>::default(),
+            ],
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 305419896
+        i32.const -1
+        local.set 0
+        local.get 0
+        return)
+)
+        "#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    valida.func @main {
+                      entry():
+                        valida.imm32 -8(fp) 18 52 86 120
+                        valida.imm32 -12(fp) 255 255 255 255
+                        valida.sw 0 -4(fp) -12(fp) 0 0
+                        valida.sw 0 -12(fp) -4(fp) 0 0
+                        valida.sw 0 8(fp) -12(fp) 0 0
+                        valida.jalv -4(fp) 0(fp) 4(fp) 0 0
+                    }
+                }"#]],
+        )
+    }
 }
@@ -52,6 +52,7 @@ impl RewritePattern for ModuleLowering {
         let Some(wasm_module_op) = opop.downcast_ref::<wasm::ops::ModuleOp>() else {
             panic!("expected ModuleOp");
         };
+        let main_func_sym = wasm_module_op.get_start_func_sym(ctx)?;
         let mut func_ops = Vec::new();
         for func_op in wasm_module_op.get_body(ctx, 0).deref(ctx).iter(ctx) {
             func_ops.push(func_op);
@@ -60,7 +61,6 @@ impl RewritePattern for ModuleLowering {
         for op in &func_ops {
             op.unlink(ctx);
         }
-        let main_func_sym = wasm_module_op.get_start_func_sym(ctx);
         let entry_block = build_prog_entry_block(ctx, main_func_sym.into());
         let prog_op = valida::ops::ProgramOp::new(ctx, entry_block, func_ops);
         rewriter.replace_op_with(ctx, wasm_module_op.get_operation(), prog_op.get_operation())?;
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::anyhow;
 use ozk_valida_dialect as valida;
 use pliron::context::Context;
@@ -70,6 +72,35 @@ impl RewritePattern for ValidaResolveTargetSymToPc {
             rewriter.replace_op_with(ctx, jalsym_op.get_operation(), jal_op.get_operation())?;
         }
 
+        let mut label_ops = Vec::new();
+        program_op
+            .get_operation()
+            .walk_only::<valida::ops::LabelOp>(ctx, WalkOrder::PostOrder, &mut |op| {
+                label_ops.push(*op);
+                WalkResult::Advance
+            });
+        let label_pcs: HashMap<String, i32> = label_ops
+            .into_iter()
+            .map(|label_op| (label_op.get_name(ctx), label_op.get_pc(ctx).into()))
+            .collect();
+        let mut beqsym_ops = Vec::new();
+        program_op
+            .get_operation()
+            .walk_only::<valida::ops::BeqSymOp>(ctx, WalkOrder::PostOrder, &mut |op| {
+                beqsym_ops.push(*op);
+                WalkResult::Advance
+            });
+        for beqsym_op in beqsym_ops {
+            let label = beqsym_op.get_target_label(ctx);
+            let a = label_pcs
+                .get(&label)
+                .ok_or_else(|| anyhow!("not found label: {}", label))?;
+            let mut operands = beqsym_op.get_operands(ctx);
+            operands.set_a(*a);
+            let beq_op = valida::ops::BeqOp::from_operands(ctx, operands);
+            rewriter.replace_op_with(ctx, beqsym_op.get_operation(), beq_op.get_operation())?;
+        }
+
         Ok(true)
     }
 }
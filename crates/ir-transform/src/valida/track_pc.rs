@@ -134,8 +134,11 @@ mod tests {
                       entry():
                         valida.imm32 -4(fp) 0 0 0 3
                         valida.imm32 -8(fp) 0 0 0 4
-                        valida.imm32 -16(fp) 0 0 0 20
-                        valida.jalsym -20(fp) add -20 0 0
+                        valida.sw 0 -24(fp) -8(fp) 0 0
+                        valida.sw 0 -20(fp) -4(fp) 0 0
+                        valida.imm32 -32(fp) 0 0 0 36
+                        valida.jalsym -36(fp) add -36 0 0
+                        valida.sw 0 -4(fp) -20(fp) 0 0
                         valida.sw 0 8(fp) -4(fp) 0 0
                         valida.jalv -4(fp) 0(fp) 4(fp) 0 0
                     }
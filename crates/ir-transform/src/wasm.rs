@@ -1,6 +1,36 @@
 //! Wasm conversions
 
+pub mod br_if_fusion;
+pub mod br_propagation;
+pub mod br_table;
+pub mod call_depth;
+pub mod call_indirect;
+pub mod canonicalize;
+pub mod checked_arith;
+pub mod const_func_call;
+pub mod data_init;
 pub mod explicit_func_args_pass;
+pub mod foreign_imports;
+pub mod globals_init;
 pub mod globals_to_mem;
+pub mod import_globals;
+pub mod intrinsics;
+pub mod link_check;
+pub mod link_runtime;
+pub mod mem_check;
+pub mod memory_size;
+pub mod multi_memory;
+pub mod no_io;
+pub mod outline;
+pub mod partial_loads;
+pub mod passive_data;
+pub mod prologue;
+pub mod rename_symbols;
+pub mod reserved_slots;
 pub mod resolve_call_op;
+pub mod return_call;
+pub mod single_func;
+pub mod stack_check;
+pub mod tables_to_mem;
 pub mod track_stack_depth;
+pub mod wasi_shim;
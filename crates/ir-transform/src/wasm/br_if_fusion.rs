@@ -0,0 +1,159 @@
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::ops::BrIfCondition;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::operation::Operation;
+use pliron::pass::Pass;
+
+use super::canonicalize::CanonicalizationPattern;
+use super::canonicalize::WasmCanonicalizePass;
+
+/// Fuses the `eqz` ops preceding a `br_if` into the branch condition, e.g.
+/// `i64.eqz; i32.eqz; br_if` -> `br_if` on non-zero i64.
+/// Should run before the lowering so that the targets emit a single conditional branch.
+pub struct WasmBrIfFusionPass {
+    canonicalize: WasmCanonicalizePass,
+}
+
+impl Default for WasmBrIfFusionPass {
+    fn default() -> Self {
+        let mut canonicalize = WasmCanonicalizePass::new_empty();
+        canonicalize.add_pattern(Box::<FuseEqzBrIf>::default());
+        Self { canonicalize }
+    }
+}
+
+impl Pass for WasmBrIfFusionPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        self.canonicalize.run_on_operation(ctx, op)
+    }
+}
+
+/// `i32.eqz; br_if` -> `br_if` with the inverted i32 condition,
+/// `i64.eqz; br_if` -> `br_if` with the (inverted) i64 condition
+#[derive(Default)]
+pub struct FuseEqzBrIf;
+
+impl CanonicalizationPattern for FuseEqzBrIf {
+    fn rewrite_block(
+        &self,
+        ctx: &mut Context,
+        block: Ptr<BasicBlock>,
+    ) -> Result<bool, anyhow::Error> {
+        let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+        for pair in ops.windows(2) {
+            let (eqz_op, br_if_op) = (pair[0], pair[1]);
+            let Some(br_if_op) = br_if_op
+                .deref(ctx)
+                .get_op(ctx)
+                .downcast_ref::<wasm::BrIfOp>()
+                .cloned() else {
+                continue;
+            };
+            let condition = br_if_op.get_condition(ctx);
+            if condition.is_i64() {
+                continue;
+            }
+            let eqz = eqz_op.deref(ctx).get_op(ctx);
+            let fused = if eqz.downcast_ref::<wasm::I32EqzOp>().is_some() {
+                condition.inverted()
+            } else if eqz.downcast_ref::<wasm::I64EqzOp>().is_some() {
+                match condition {
+                    BrIfCondition::I32NonZero => BrIfCondition::I64Zero,
+                    BrIfCondition::I32Zero | BrIfCondition::I64NonZero | BrIfCondition::I64Zero => {
+                        BrIfCondition::I64NonZero
+                    }
+                }
+            } else {
+                continue;
+            };
+            br_if_op.set_condition(ctx, fused);
+            eqz_op.unlink(ctx);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_pass;
+
+    use super::*;
+
+    #[test]
+    fn i64_eqz_i32_eqz_br_if() {
+        check_wasm_pass(
+            &WasmBrIfFusionPass::default(),
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i64)
+        local.get 0
+        i64.eqz
+        i32.eqz
+        br_if 0
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      // locals: [0: si64]
+                      entry():
+                        wasm.local.get 0
+                        wasm.br_if 0 if i64.nez
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn i32_eqz_br_if() {
+        check_wasm_pass(
+            &WasmBrIfFusionPass::default(),
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32 i64)
+        local.get 0
+        i32.eqz
+        br_if 0
+        local.get 0
+        i32.eqz
+        i32.eqz
+        br_if 0
+        local.get 1
+        i64.eqz
+        br_if 0
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      // locals: [0: si32, 1: si64]
+                      entry():
+                        wasm.local.get 0
+                        wasm.br_if 0 if i32.eqz
+                        wasm.local.get 0
+                        wasm.br_if 0
+                        wasm.local.get 1
+                        wasm.br_if 0 if i64.eqz
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Runtime support for the br-propagation of the flattened (outlined) blocks.
+//!
+//! When a block is outlined into a function, a `br` out of it can no longer jump to the
+//! enclosing block's end. Instead, the branch depth is stored in a reserved slot and every
+//! caller checks the remaining depth after the call, decrementing it on the way out.
+//! The slot is a reserved global on Triton and a named reserved slot
+//! (see [crate::wasm::reserved_slots]) on Miden,
+//! while the sequence of ops that manipulates it is shared between the targets.
+
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::BinaryOpcode;
+use ozk_wasm_dialect::types::FuncIndex;
+use ozk_wasm_dialect::types::GlobalIndex;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+
+use crate::wasm::outline::append_function_with_body;
+use crate::wasm::reserved_slots::BR_PROPAGATION_SLOT;
+
+/// Symbol of the runtime function that decrements the br-propagation depth
+/// and returns the remaining depth
+pub const NEXT_BR_PROPAGATION_FUNC_NAME: &str = "next_br_propagation";
+
+/// Where the br-propagation depth (i32) is kept at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrPropagationStorage {
+    /// Reserved global variable (Triton)
+    Global(GlobalIndex),
+    /// Named reserved slot [BR_PROPAGATION_SLOT] resolved by the target's memory layout (Miden)
+    Reserved,
+}
+
+impl BrPropagationStorage {
+    /// Ops that pop the i32 value from the stack and store it as the br-propagation depth
+    pub fn store_ops(&self, ctx: &mut Context) -> Vec<Ptr<Operation>> {
+        match self {
+            BrPropagationStorage::Global(index) => {
+                vec![wasm::GlobalSetOp::new_unlinked(ctx, *index).get_operation()]
+            }
+            BrPropagationStorage::Reserved => {
+                vec![ozk::ReservedSetOp::new_unlinked(ctx, BR_PROPAGATION_SLOT).get_operation()]
+            }
+        }
+    }
+
+    /// Ops that push the br-propagation depth (i32) on the stack
+    pub fn load_ops(&self, ctx: &mut Context) -> Vec<Ptr<Operation>> {
+        match self {
+            BrPropagationStorage::Global(index) => {
+                vec![wasm::GlobalGetOp::new_unlinked(ctx, u32::from(*index)).get_operation()]
+            }
+            BrPropagationStorage::Reserved => {
+                vec![ozk::ReservedGetOp::new_unlinked(ctx, BR_PROPAGATION_SLOT).get_operation()]
+            }
+        }
+    }
+
+    /// Ops that start the br-propagation out of `depth` outlined blocks
+    /// (a `br` with `depth` relative depth inside an outlined block)
+    pub fn start_ops(&self, ctx: &mut Context, depth: u32) -> Vec<Ptr<Operation>> {
+        let mut ops = vec![wasm::ConstantOp::new_i32_unlinked(ctx, depth as i32).get_operation()];
+        ops.extend(self.store_ops(ctx));
+        ops
+    }
+}
+
+/// Returns the index of the [NEXT_BR_PROPAGATION_FUNC_NAME] function in the module,
+/// appending it to the module if it's not there yet.
+/// The function has no parameters, decrements the br-propagation depth (unless it's zero)
+/// and returns the remaining depth.
+/// The caller (enclosing block) should exit if the returned depth is non-zero.
+pub fn get_or_insert_next_br_propagation_func(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    storage: BrPropagationStorage,
+) -> FuncIndex {
+    let func_sym = FuncSym::from(NEXT_BR_PROPAGATION_FUNC_NAME);
+    if let Some(func_index) = module_op.get_func_index(ctx, func_sym) {
+        return func_index;
+    }
+    let mut ops = Vec::new();
+    // depth + (depth == 0) - 1, i.e. saturating decrement without branching
+    ops.extend(storage.load_ops(ctx));
+    ops.extend(storage.load_ops(ctx));
+    ops.push(wasm::I32EqzOp::new_unlinked(ctx).get_operation());
+    let i32_ty = i32_type(ctx);
+    ops.push(wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Add).get_operation());
+    ops.push(wasm::ConstantOp::new_i32_unlinked(ctx, -1).get_operation());
+    ops.push(wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Add).get_operation());
+    ops.extend(storage.store_ops(ctx));
+    ops.extend(storage.load_ops(ctx));
+    ops.push(wasm::ReturnOp::new_unlinked(ctx).get_operation());
+    let ty = FunctionType::get(ctx, vec![], vec![i32_ty]);
+    append_function_with_body(ctx, module_op, NEXT_BR_PROPAGATION_FUNC_NAME, ty, ops).1
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    const WAT: &str = r#"
+(module
+    (start $main)
+    (func $main
+        return)
+)
+"#;
+
+    #[test]
+    fn next_br_propagation_reserved() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT);
+        let storage = BrPropagationStorage::Reserved;
+        let func_index = get_or_insert_next_br_propagation_func(&mut ctx, &module_op, storage);
+        assert_eq!(
+            get_or_insert_next_br_propagation_func(&mut ctx, &module_op, storage),
+            func_index
+        );
+        expect![[r#"
+            wasm.module @module_name {
+              block_1_0():
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.return
+                }
+                wasm.func @next_br_propagation() -> (si32) {
+                  entry():
+                    ozk.reserved_get br_propagation
+                    ozk.reserved_get br_propagation
+                    wasm.i32.eqz
+                    wasm.i32.add
+                    wasm.const 0xffffffff: si32
+                    wasm.i32.add
+                    ozk.reserved_set br_propagation
+                    ozk.reserved_get br_propagation
+                    wasm.return
+                }
+            }"#]]
+        .assert_eq(&module_op.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn next_br_propagation_global() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT);
+        let storage = BrPropagationStorage::Global(GlobalIndex::from(0));
+        get_or_insert_next_br_propagation_func(&mut ctx, &module_op, storage);
+        expect![[r#"
+            wasm.module @module_name {
+              block_1_0():
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.return
+                }
+                wasm.func @next_br_propagation() -> (si32) {
+                  entry():
+                    wasm.global.get 0
+                    wasm.global.get 0
+                    wasm.i32.eqz
+                    wasm.i32.add
+                    wasm.const 0xffffffff: si32
+                    wasm.i32.add
+                    wasm.global.set 0
+                    wasm.global.get 0
+                    wasm.return
+                }
+            }"#]]
+        .assert_eq(&module_op.with_ctx(&ctx).to_string());
+    }
+}
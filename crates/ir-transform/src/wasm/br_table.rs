@@ -0,0 +1,124 @@
+use ozk_ozk_dialect::ops as ozk;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+use super::reserved_slots::SCRATCH_SLOT;
+
+/// Rewrites `br_table` into a chain of comparisons of the index with each target position
+/// followed by a `br_if` to that target, and a `br` to the default target, so the targets
+/// only have to lower `br_if` and `br`. The index is kept in the [SCRATCH_SLOT] reserved slot.
+#[derive(Default)]
+pub struct WasmBrTableToBrIfPass;
+
+impl Pass for WasmBrTableToBrIfPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut br_table_ops = Vec::new();
+        op.walk_only::<wasm::BrTableOp>(ctx, WalkOrder::PostOrder, &mut |br_table_op| {
+            br_table_ops.push(*br_table_op);
+            WalkResult::Advance
+        });
+        for br_table_op in br_table_ops {
+            let op = br_table_op.get_operation();
+            let targets = br_table_op.get_targets(ctx);
+            let mut chain = Vec::new();
+            if targets.is_empty() {
+                chain.push(wasm::DropOp::new_unlinked(ctx).get_operation());
+            } else {
+                chain.push(ozk::ReservedSetOp::new_unlinked(ctx, SCRATCH_SLOT).get_operation());
+                for (index, target) in targets.into_iter().enumerate() {
+                    chain.extend([
+                        ozk::ReservedGetOp::new_unlinked(ctx, SCRATCH_SLOT).get_operation(),
+                        wasm::ConstantOp::new_i32_unlinked(ctx, index as i32).get_operation(),
+                        wasm::I32EqOp::new_unlinked(ctx).get_operation(),
+                        wasm::BrIfOp::new_unlinked(ctx, target).get_operation(),
+                    ]);
+                }
+            }
+            let default = br_table_op.get_default(ctx);
+            chain.push(wasm::BrOp::new_unlinked(ctx, default).get_operation());
+            for chain_op in chain {
+                chain_op.insert_before(ctx, op);
+            }
+            op.unlink(ctx);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_pass;
+
+    use super::*;
+
+    #[test]
+    fn br_table_to_br_if_chain() {
+        check_wasm_pass(
+            &WasmBrTableToBrIfPass,
+            r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 1
+        br_table 0 0 0
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.const 0x1: si32
+                        ozk.reserved_set scratch
+                        ozk.reserved_get scratch
+                        wasm.const 0x0: si32
+                        wasm.i32.eq
+                        wasm.br_if 0
+                        ozk.reserved_get scratch
+                        wasm.const 0x1: si32
+                        wasm.i32.eq
+                        wasm.br_if 0
+                        wasm.br 0
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn br_table_default_only() {
+        check_wasm_pass(
+            &WasmBrTableToBrIfPass,
+            r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 1
+        br_table 0
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.const 0x1: si32
+                        wasm.drop
+                        wasm.br 0
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+}
@@ -0,0 +1,495 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use anyhow::anyhow;
+use ozk_ozk_dialect as ozk;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::ElemSegment;
+use ozk_wasm_dialect::types::FuncIndex;
+use ozk_wasm_dialect::types::TableIndex;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Worst-case call depth of a function (the number of nested calls it can make)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallDepth {
+    /// The depth is bounded, `chain` is the deepest call chain starting with the function
+    Bounded { depth: u32, chain: Vec<FuncSym> },
+    /// The function can reach a recursive call, `cycle` is the call chain from the function
+    /// up to (and including) the first repeated function
+    Unbounded { cycle: Vec<FuncSym> },
+}
+
+/// Calls between the defined functions of a module (including the functions outlined from blocks).
+/// The calls to the imported functions are counted as a single call level.
+pub struct CallGraph {
+    funcs: BTreeMap<FuncSym, FuncCalls>,
+}
+
+/// The callees of a function and the nesting of its blocks and loops, each nested block or loop
+/// is a call level if the blocks are outlined into functions (e.g. on Triton)
+#[derive(Default)]
+struct FuncCalls {
+    /// The deepest nesting of the blocks and loops in the function
+    max_nesting: u32,
+    /// The callees with the deepest nesting of the blocks and loops around the calls to them
+    callees: BTreeMap<FuncSym, u32>,
+}
+
+impl FuncCalls {
+    fn add_callee(&mut self, func_sym: FuncSym, nesting: u32) {
+        let callee_nesting = self.callees.entry(func_sym).or_insert(nesting);
+        *callee_nesting = (*callee_nesting).max(nesting);
+    }
+}
+
+impl CallGraph {
+    /// Build the call graph of the module from `wasm.call`, `wasm.return_call`, `ozk.call`,
+    /// `ozk.return_call` and `ozk.host_call` ops. The `call_indirect` ops can call any function
+    /// of the elem segments of their table. Each nested `wasm.block` and `wasm.loop` is counted
+    /// as a call level (the blocks are outlined into functions).
+    pub fn new(ctx: &Context, module_op: &wasm::ModuleOp) -> Self {
+        Self::build(ctx, module_op, true)
+    }
+
+    /// Same as [CallGraph::new], but the blocks and loops are kept in the function (structured
+    /// control flow) and are not counted as call levels
+    pub fn new_structured(ctx: &Context, module_op: &wasm::ModuleOp) -> Self {
+        Self::build(ctx, module_op, false)
+    }
+
+    fn build(ctx: &Context, module_op: &wasm::ModuleOp, outlined_blocks: bool) -> Self {
+        let elem_segments = module_op.get_elem_segments(ctx);
+        let mut funcs = BTreeMap::new();
+        for op in module_op.get_body(ctx, 0).deref(ctx).iter(ctx) {
+            let Some(func_op) = op.deref(ctx).get_op(ctx).downcast_ref::<wasm::FuncOp>().cloned() else {
+                continue;
+            };
+            let mut func_calls = FuncCalls::default();
+            let mut visitor = CallVisitor {
+                module_op,
+                elem_segments: &elem_segments,
+                outlined_blocks,
+                func_calls: &mut func_calls,
+            };
+            visitor.visit_block(ctx, func_op.get_entry_block(ctx), 0);
+            funcs.insert(FuncSym::from(func_op.get_symbol_name(ctx)), func_calls);
+        }
+        Self { funcs }
+    }
+
+    /// The functions that can call themselves (directly or through the other functions)
+    pub fn recursive_funcs(&self) -> BTreeSet<FuncSym> {
+        self.funcs
+            .keys()
+            .filter(|func_sym| self.calls_itself(func_sym))
+            .cloned()
+            .collect()
+    }
+
+    fn calls_itself(&self, func_sym: &FuncSym) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut pending: Vec<&FuncSym> = self.callee_syms(func_sym).collect();
+        while let Some(callee) = pending.pop() {
+            if callee == func_sym {
+                return true;
+            }
+            if visited.insert(callee) {
+                pending.extend(self.callee_syms(callee));
+            }
+        }
+        false
+    }
+
+    fn callee_syms<'a>(&'a self, func_sym: &FuncSym) -> impl Iterator<Item = &'a FuncSym> {
+        self.funcs
+            .get(func_sym)
+            .into_iter()
+            .flat_map(|func_calls| func_calls.callees.keys())
+    }
+
+    /// Worst-case call depth of the function
+    pub fn call_depth(&self, func_sym: &FuncSym) -> CallDepth {
+        self.call_depth_from(func_sym, &mut Vec::new(), &mut BTreeMap::new())
+    }
+
+    fn call_depth_from(
+        &self,
+        func_sym: &FuncSym,
+        stack: &mut Vec<FuncSym>,
+        memo: &mut BTreeMap<FuncSym, CallDepth>,
+    ) -> CallDepth {
+        if let Some(depth) = memo.get(func_sym) {
+            return depth.clone();
+        }
+        if stack.contains(func_sym) {
+            let mut cycle = stack.clone();
+            cycle.push(func_sym.clone());
+            return CallDepth::Unbounded { cycle };
+        }
+        let Some(func_calls) = self.funcs.get(func_sym) else {
+            // imported function
+            return CallDepth::Bounded {
+                depth: 0,
+                chain: vec![func_sym.clone()],
+            };
+        };
+        stack.push(func_sym.clone());
+        let mut result = CallDepth::Bounded {
+            depth: func_calls.max_nesting,
+            chain: vec![func_sym.clone()],
+        };
+        for (callee, nesting) in &func_calls.callees {
+            match self.call_depth_from(callee, stack, memo) {
+                CallDepth::Bounded { depth, chain } => {
+                    if let CallDepth::Bounded {
+                        depth: max_depth, ..
+                    } = result
+                    {
+                        let callee_depth = nesting + 1 + depth;
+                        if callee_depth > max_depth {
+                            let mut deeper_chain = vec![func_sym.clone()];
+                            deeper_chain.extend(chain);
+                            result = CallDepth::Bounded {
+                                depth: callee_depth,
+                                chain: deeper_chain,
+                            };
+                        }
+                    }
+                }
+                unbounded @ CallDepth::Unbounded { .. } => {
+                    result = unbounded;
+                    break;
+                }
+            }
+        }
+        stack.pop();
+        if matches!(result, CallDepth::Bounded { .. }) {
+            memo.insert(func_sym.clone(), result.clone());
+        }
+        result
+    }
+}
+
+/// Collects the calls of a function, tracking the nesting of the blocks and loops
+struct CallVisitor<'a> {
+    module_op: &'a wasm::ModuleOp,
+    elem_segments: &'a [ElemSegment],
+    outlined_blocks: bool,
+    func_calls: &'a mut FuncCalls,
+}
+
+impl CallVisitor<'_> {
+    fn visit_block(&mut self, ctx: &Context, block: Ptr<BasicBlock>, nesting: u32) {
+        for op in block.deref(ctx).iter(ctx) {
+            self.visit_op(ctx, op, nesting);
+        }
+    }
+
+    fn visit_op(&mut self, ctx: &Context, op: Ptr<Operation>, nesting: u32) {
+        let opop = op.deref(ctx).get_op(ctx);
+        let nested_nesting = if self.outlined_blocks {
+            nesting + 1
+        } else {
+            nesting
+        };
+        if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+            self.func_calls.max_nesting = self.func_calls.max_nesting.max(nested_nesting);
+            self.visit_block(ctx, block_op.get_block(ctx), nested_nesting);
+        } else if let Some(loop_op) = opop.downcast_ref::<wasm::LoopOp>() {
+            self.func_calls.max_nesting = self.func_calls.max_nesting.max(nested_nesting);
+            self.visit_block(ctx, loop_op.get_block(ctx), nested_nesting);
+        } else if let Some(if_op) = opop.downcast_ref::<wasm::IfOp>() {
+            self.visit_block(ctx, if_op.get_then_block(ctx), nesting);
+            self.visit_block(ctx, if_op.get_else_block(ctx), nesting);
+        } else if let Some(call_op) = opop.downcast_ref::<wasm::CallOp>() {
+            self.add_callee_index(ctx, call_op.get_func_index(ctx), nesting);
+        } else if let Some(call_op) = opop.downcast_ref::<wasm::ReturnCallOp>() {
+            self.add_callee_index(ctx, call_op.get_func_index(ctx), nesting);
+        } else if let Some(call_op) = opop.downcast_ref::<wasm::CallIndirectOp>() {
+            self.add_table_callees(ctx, call_op.get_table_index(ctx), nesting);
+        } else if let Some(call_op) = opop.downcast_ref::<wasm::ReturnCallIndirectOp>() {
+            self.add_table_callees(ctx, call_op.get_table_index(ctx), nesting);
+        } else if let Some(call_op) = opop.downcast_ref::<ozk::ops::CallOp>() {
+            self.func_calls
+                .add_callee(FuncSym::from(call_op.get_func_sym(ctx)), nesting);
+        } else if let Some(call_op) = opop.downcast_ref::<ozk::ops::ReturnCallOp>() {
+            self.func_calls
+                .add_callee(FuncSym::from(call_op.get_func_sym(ctx)), nesting);
+        } else if let Some(call_op) = opop.downcast_ref::<ozk::ops::HostCallOp>() {
+            self.func_calls
+                .add_callee(FuncSym::from(call_op.get_func_sym(ctx)), nesting);
+        }
+    }
+
+    fn add_callee_index(&mut self, ctx: &Context, func_index: FuncIndex, nesting: u32) {
+        if let Some(func_sym) = self.module_op.get_func_sym(ctx, func_index) {
+            self.func_calls.add_callee(func_sym, nesting);
+        }
+    }
+
+    /// Any function placed into the table can be called
+    fn add_table_callees(&mut self, ctx: &Context, table_index: TableIndex, nesting: u32) {
+        for elem_segment in self.elem_segments {
+            if elem_segment.table_index != table_index {
+                continue;
+            }
+            for func_index in &elem_segment.func_indices {
+                self.add_callee_index(ctx, *func_index, nesting);
+            }
+        }
+    }
+}
+
+/// Worst-case call depth of the module (of its start function)
+pub fn module_call_depth(ctx: &Context, module_op: &wasm::ModuleOp) -> Option<CallDepth> {
+    let start_func_sym = module_op.try_get_start_func_sym(ctx)?;
+    Some(CallGraph::new(ctx, module_op).call_depth(&start_func_sym))
+}
+
+/// Fails if the worst-case call depth of the module exceeds the limit (e.g. Triton jump stack).
+/// Deeply nested outlined blocks and user recursion are the usual causes.
+pub struct WasmCallDepthLimitPass {
+    max_depth: u32,
+    outlined_blocks: bool,
+}
+
+impl WasmCallDepthLimitPass {
+    /// Create a pass allowing at most `max_depth` nested calls, counting each nested block and
+    /// loop as a call (see [CallGraph::new])
+    pub fn new(max_depth: u32) -> Self {
+        Self {
+            max_depth,
+            outlined_blocks: true,
+        }
+    }
+
+    /// Same as [WasmCallDepthLimitPass::new], but the blocks and loops are not counted as calls
+    /// (see [CallGraph::new_structured])
+    pub fn new_structured(max_depth: u32) -> Self {
+        Self {
+            max_depth,
+            outlined_blocks: false,
+        }
+    }
+}
+
+fn format_chain(chain: &[FuncSym]) -> String {
+    chain
+        .iter()
+        .map(|func_sym| func_sym.as_ref())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+impl Pass for WasmCallDepthLimitPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            let Some(start_func_sym) = module_op.try_get_start_func_sym(ctx) else {
+                continue;
+            };
+            let call_graph = if self.outlined_blocks {
+                CallGraph::new(ctx, &module_op)
+            } else {
+                CallGraph::new_structured(ctx, &module_op)
+            };
+            match call_graph.call_depth(&start_func_sym) {
+                CallDepth::Bounded { depth, chain } if depth > self.max_depth => {
+                    return Err(anyhow!(
+                        "call depth {depth} exceeds the limit of {} (deepest call chain: {}), \
+                         consider inlining the functions of the chain or unrolling the loops \
+                         outlined into functions",
+                        self.max_depth,
+                        format_chain(&chain)
+                    ));
+                }
+                CallDepth::Unbounded { cycle } => {
+                    return Err(anyhow!(
+                        "call depth is unbounded (limit {}) due to the recursive call chain: {}, \
+                         consider rewriting the recursion as a loop",
+                        self.max_depth,
+                        format_chain(&cycle)
+                    ));
+                }
+                CallDepth::Bounded { .. } => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use crate::tests_util::parse_wasm_module;
+    use crate::tests_util::run_wasm_pass_wrapped;
+
+    use super::*;
+
+    const NESTED_CALLS_WAT: &str = r#"
+(module
+    (import "env" "ozk_stdlib_pub_input" (func $pub_input (result i64)))
+    (start $main)
+    (func $leaf (result i64)
+        call $pub_input
+        return)
+    (func $mid (result i64)
+        call $leaf
+        return)
+    (func $main
+        (local i64)
+        call $mid
+        call $leaf
+        i64.add
+        local.set 0
+        return)
+)
+"#;
+
+    fn syms(syms: &[&str]) -> Vec<FuncSym> {
+        syms.iter().map(|sym| FuncSym::from(*sym)).collect()
+    }
+
+    #[test]
+    fn nested_calls_depth() {
+        let (ctx, module_op) =
+            run_wasm_pass_wrapped(&WasmCallDepthLimitPass::new(3), NESTED_CALLS_WAT);
+        assert_eq!(
+            module_call_depth(&ctx, &module_op),
+            Some(CallDepth::Bounded {
+                depth: 3,
+                chain: syms(&["main", "mid", "leaf", "ozk_stdlib_pub_input"]),
+            })
+        );
+    }
+
+    #[test]
+    fn depth_over_limit() {
+        let (mut ctx, module_op) =
+            run_wasm_pass_wrapped(&WasmCallDepthLimitPass::new(3), NESTED_CALLS_WAT);
+        let err = WasmCallDepthLimitPass::new(2)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("call depth 3 exceeds the limit of 2"));
+        assert!(err.contains("main -> mid -> leaf -> ozk_stdlib_pub_input"));
+        assert!(err.contains("inlining"));
+    }
+
+    #[test]
+    fn recursion_is_unbounded() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (start $main)
+    (func $helper
+        call $recursive
+        return)
+    (func $recursive
+        call $helper
+        return)
+    (func $main
+        call $recursive
+        return)
+)
+"#,
+        );
+        assert_eq!(
+            module_call_depth(&ctx, &module_op),
+            Some(CallDepth::Unbounded {
+                cycle: syms(&["main", "recursive", "helper", "recursive"]),
+            })
+        );
+        let err = WasmCallDepthLimitPass::new(u32::MAX)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("recursive call chain: main -> recursive -> helper -> recursive"));
+        assert_eq!(
+            CallGraph::new(&ctx, &module_op).recursive_funcs(),
+            BTreeSet::from_iter(syms(&["helper", "recursive"]))
+        );
+    }
+
+    #[test]
+    fn nested_blocks_are_call_levels() {
+        let (ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (start $main)
+    (func $leaf
+        (block
+            (loop))
+        return)
+    (func $main
+        (block
+            (block
+                call $leaf))
+        return)
+)
+"#,
+        );
+        assert_eq!(
+            module_call_depth(&ctx, &module_op),
+            Some(CallDepth::Bounded {
+                depth: 5,
+                chain: syms(&["main", "leaf"]),
+            })
+        );
+        assert_eq!(
+            CallGraph::new_structured(&ctx, &module_op).call_depth(&FuncSym::from("main")),
+            CallDepth::Bounded {
+                depth: 1,
+                chain: syms(&["main", "leaf"]),
+            }
+        );
+    }
+
+    #[test]
+    fn call_indirect_calls_table_funcs() {
+        let (ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (type $nullary (func))
+    (table 2 funcref)
+    (elem (i32.const 0) $shallow $deep)
+    (start $main)
+    (func $leaf
+        return)
+    (func $shallow
+        return)
+    (func $deep
+        call $leaf
+        return)
+    (func $main
+        i32.const 1
+        call_indirect (type $nullary)
+        return)
+)
+"#,
+        );
+        assert_eq!(
+            module_call_depth(&ctx, &module_op),
+            Some(CallDepth::Bounded {
+                depth: 2,
+                chain: syms(&["main", "deep", "leaf"]),
+            })
+        );
+    }
+}
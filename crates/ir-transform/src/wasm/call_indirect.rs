@@ -0,0 +1,264 @@
+use anyhow::anyhow;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::FuncIndex;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+use super::reserved_slots::SCRATCH_SLOT;
+
+/// Rewrites `call_indirect` into a chain of nested `if`s that compare the index with each
+/// table position holding a function of the expected type and call that function directly.
+/// An index of a null entry, a function of another type or out of the table bounds fails
+/// the execution. The tables that aren't changed at runtime have their contents known here, so
+/// the dispatch doesn't have to read them from memory. The modules using `table.get`/`table.set`
+/// have their tables in memory (see [super::tables_to_mem]) and dispatch on the entry instead.
+/// The index is kept in the [SCRATCH_SLOT] reserved slot.
+#[derive(Default)]
+pub struct WasmCallIndirectToCallPass;
+
+impl Pass for WasmCallIndirectToCallPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            lower_call_indirect_ops(ctx, &module_op)?;
+        }
+        Ok(())
+    }
+}
+
+fn lower_call_indirect_ops(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+) -> Result<(), anyhow::Error> {
+    let mut call_indirect_ops = Vec::new();
+    module_op.get_operation().walk_only::<wasm::CallIndirectOp>(
+        ctx,
+        WalkOrder::PostOrder,
+        &mut |call_indirect_op| {
+            call_indirect_ops.push(*call_indirect_op);
+            WalkResult::Advance
+        },
+    );
+    for call_indirect_op in call_indirect_ops {
+        let op = call_indirect_op.get_operation();
+        let table_index = call_indirect_op.get_table_index(ctx);
+        let func_type = call_indirect_op.get_func_type(ctx);
+        let entries = module_op.get_table_entries(ctx, table_index);
+        if entries.is_empty() {
+            return Err(anyhow!(
+                "call_indirect refers to an undeclared or empty table {table_index}"
+            ));
+        }
+        let mut targets: Vec<(i32, FuncIndex)> = Vec::new();
+        for (position, entry) in entries.into_iter().enumerate() {
+            let Some(func_index) = entry else {
+                continue;
+            };
+            let callee_type = module_op
+                .get_func_type(ctx, func_index)
+                .ok_or_else(|| anyhow!("table entry refers to an unknown function {func_index}"))?;
+            if callee_type.get_inputs() == func_type.get_inputs()
+                && callee_type.get_results() == func_type.get_results()
+            {
+                targets.push((position as i32, func_index));
+            }
+        }
+        insert_dispatch(ctx, op, &func_type, targets);
+        op.unlink(ctx);
+    }
+    Ok(())
+}
+
+/// Inserts before `op` the chain of nested `if`s that pops the key and calls the function of
+/// the target with this key. No matching target fails the execution.
+pub(crate) fn insert_dispatch(
+    ctx: &mut Context,
+    op: Ptr<Operation>,
+    func_type: &FunctionType,
+    targets: Vec<(i32, FuncIndex)>,
+) {
+    let if_type = FunctionType::get(
+        ctx,
+        func_type.get_inputs().clone(),
+        func_type.get_results().clone(),
+    );
+    ozk::ReservedSetOp::new_unlinked(ctx, SCRATCH_SLOT)
+        .get_operation()
+        .insert_before(ctx, op);
+    // build the chain from the innermost `else` (no match) outwards
+    let mut fallback = failure_ops(ctx);
+    for (key, func_index) in targets.into_iter().rev() {
+        let if_op = wasm::IfOp::new_unlinked(ctx, if_type);
+        wasm::CallOp::new_unlinked(ctx, func_index)
+            .get_operation()
+            .insert_at_back(if_op.get_then_block(ctx), ctx);
+        append_ops(ctx, if_op.get_else_block(ctx), fallback);
+        fallback = vec![
+            ozk::ReservedGetOp::new_unlinked(ctx, SCRATCH_SLOT).get_operation(),
+            wasm::ConstantOp::new_i32_unlinked(ctx, key).get_operation(),
+            wasm::I32EqOp::new_unlinked(ctx).get_operation(),
+            if_op.get_operation(),
+        ];
+    }
+    for chain_op in fallback {
+        chain_op.insert_before(ctx, op);
+    }
+}
+
+/// Ops that fail the execution
+fn failure_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        wasm::ConstantOp::new_i32_unlinked(ctx, 0).get_operation(),
+        ozk::AssertOp::new_unlinked(ctx).get_operation(),
+    ]
+}
+
+fn append_ops(ctx: &mut Context, block: Ptr<BasicBlock>, ops: Vec<Ptr<Operation>>) {
+    for op in ops {
+        op.insert_at_back(block, ctx);
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use expect_test::Expect;
+    use ozk_ozk_dialect::types::FuncSym;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    fn check_main(wat: &str, expected: Expect) {
+        let (mut ctx, module_op) = parse_wasm_module(wat);
+        WasmCallIndirectToCallPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        expected.assert_eq(&main_func.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn call_indirect_to_if_chain() {
+        check_main(
+            r#"
+(module
+    (type $unary (func (param i32) (result i32)))
+    (table 4 funcref)
+    (elem (i32.const 1) $inc $other $dec)
+    (start $main)
+    (func $inc (param i32) (result i32)
+        local.get 0
+        i32.const 1
+        i32.add
+        return)
+    (func $dec (param i32) (result i32)
+        local.get 0
+        i32.const 1
+        i32.sub
+        return)
+    (func $other (result i32)
+        i32.const 7
+        return)
+    (func $main
+        i32.const 9
+        i32.const 3
+        call_indirect (type $unary)
+        drop
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.const 0x9: si32
+                    wasm.const 0x3: si32
+                    ozk.reserved_set scratch
+                    ozk.reserved_get scratch
+                    wasm.const 0x1: si32
+                    wasm.i32.eq
+                    wasm.if (si32) -> (si32) {
+                      then():
+                        wasm.call 0
+                    } else {
+                      else():
+                        ozk.reserved_get scratch
+                        wasm.const 0x3: si32
+                        wasm.i32.eq
+                        wasm.if (si32) -> (si32) {
+                          then():
+                            wasm.call 1
+                        } else {
+                          else():
+                            wasm.const 0x0: si32
+                            ozk.assert
+                        }
+                    }
+                    wasm.drop
+                    wasm.return
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn call_indirect_without_matching_entries() {
+        check_main(
+            r#"
+(module
+    (type $nullary (func))
+    (table 1 funcref)
+    (start $main)
+    (func $main
+        i32.const 0
+        call_indirect (type $nullary)
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.const 0x0: si32
+                    ozk.reserved_set scratch
+                    wasm.const 0x0: si32
+                    ozk.assert
+                    wasm.return
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn call_indirect_without_table() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (type $nullary (func))
+    (table 0 funcref)
+    (start $main)
+    (func $main
+        i32.const 0
+        call_indirect (type $nullary)
+        return)
+)
+"#,
+        );
+        let err = WasmCallIndirectToCallPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(err.to_string().contains("empty table 0"), "{err}");
+    }
+}
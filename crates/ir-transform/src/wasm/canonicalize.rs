@@ -0,0 +1,365 @@
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::BinaryOpcode;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// A canonicalization pattern for the ops of a single block.
+pub trait CanonicalizationPattern {
+    /// Rewrite the ops of the given block. Return true if the block was changed.
+    fn rewrite_block(
+        &self,
+        ctx: &mut Context,
+        block: Ptr<BasicBlock>,
+    ) -> Result<bool, anyhow::Error>;
+}
+
+/// Runs the registered canonicalization patterns on every block until none of them
+/// changes the IR (fixpoint).
+pub struct WasmCanonicalizePass {
+    patterns: Vec<Box<dyn CanonicalizationPattern>>,
+}
+
+impl Default for WasmCanonicalizePass {
+    fn default() -> Self {
+        let mut pass = Self::new_empty();
+        pass.add_pattern(Box::<RemoveAddZero>::default());
+        pass.add_pattern(Box::<EqzEqzToNe>::default());
+        pass.add_pattern(Box::<DoubleLocalSetToDrop>::default());
+        pass.add_pattern(Box::<LocalTeeToSetGet>::default());
+        pass
+    }
+}
+
+impl WasmCanonicalizePass {
+    /// Create a pass without any patterns
+    pub fn new_empty() -> Self {
+        Self {
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Register a canonicalization pattern
+    pub fn add_pattern(&mut self, pattern: Box<dyn CanonicalizationPattern>) {
+        self.patterns.push(pattern);
+    }
+}
+
+impl Pass for WasmCanonicalizePass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        loop {
+            let mut changed = false;
+            for block in collect_blocks(ctx, op) {
+                for pattern in &self.patterns {
+                    changed |= pattern.rewrite_block(ctx, block)?;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn collect_blocks(ctx: &Context, op: Ptr<Operation>) -> Vec<Ptr<BasicBlock>> {
+    let mut blocks = Vec::new();
+    op.walk_only::<wasm::FuncOp>(ctx, WalkOrder::PostOrder, &mut |func_op| {
+        blocks.push(func_op.get_entry_block(ctx));
+        WalkResult::Advance
+    });
+    op.walk_only::<wasm::BlockOp>(ctx, WalkOrder::PostOrder, &mut |block_op| {
+        blocks.push(block_op.get_block(ctx));
+        WalkResult::Advance
+    });
+    op.walk_only::<wasm::LoopOp>(ctx, WalkOrder::PostOrder, &mut |loop_op| {
+        blocks.push(loop_op.get_block(ctx));
+        WalkResult::Advance
+    });
+    op.walk_only::<wasm::IfOp>(ctx, WalkOrder::PostOrder, &mut |if_op| {
+        blocks.push(if_op.get_then_block(ctx));
+        blocks.push(if_op.get_else_block(ctx));
+        WalkResult::Advance
+    });
+    blocks
+}
+
+fn block_ops(ctx: &Context, block: Ptr<BasicBlock>) -> Vec<Ptr<Operation>> {
+    block.deref(ctx).iter(ctx).collect()
+}
+
+fn is_op<T: Op>(ctx: &Context, op: Ptr<Operation>) -> bool {
+    op.deref(ctx).get_op(ctx).downcast_ref::<T>().is_some()
+}
+
+fn is_zero_const(ctx: &Context, op: Ptr<Operation>) -> bool {
+    let Some(const_op) = op.deref(ctx).get_op(ctx).downcast_ref::<wasm::ConstantOp>().cloned() else {
+        return false;
+    };
+    matches!(const_op.get_i64(ctx), Ok(0)) || matches!(const_op.get_u32(ctx), Ok(0))
+}
+
+/// `const 0; add` -> nothing
+#[derive(Default)]
+pub struct RemoveAddZero;
+
+impl CanonicalizationPattern for RemoveAddZero {
+    fn rewrite_block(
+        &self,
+        ctx: &mut Context,
+        block: Ptr<BasicBlock>,
+    ) -> Result<bool, anyhow::Error> {
+        let ops = block_ops(ctx, block);
+        for pair in ops.windows(2) {
+            let (const_op, add_op) = (pair[0], pair[1]);
+            if is_zero_const(ctx, const_op)
+                && add_op
+                    .deref(ctx)
+                    .get_op(ctx)
+                    .downcast_ref::<wasm::BinaryArithOp>()
+                    .map_or(false, |op| op.get_opcode(ctx) == BinaryOpcode::Add)
+            {
+                Operation::erase(const_op, ctx);
+                Operation::erase(add_op, ctx);
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// `eqz; i32.eqz` -> `const 0; ne`, the value is normalized to 0 or 1 with a single op
+#[derive(Default)]
+pub struct EqzEqzToNe;
+
+impl CanonicalizationPattern for EqzEqzToNe {
+    fn rewrite_block(
+        &self,
+        ctx: &mut Context,
+        block: Ptr<BasicBlock>,
+    ) -> Result<bool, anyhow::Error> {
+        let ops = block_ops(ctx, block);
+        for pair in ops.windows(2) {
+            let (eqz_op, outer_eqz_op) = (pair[0], pair[1]);
+            if !is_op::<wasm::I32EqzOp>(ctx, outer_eqz_op) {
+                continue;
+            }
+            let (const_op, ne_op) = if is_op::<wasm::I32EqzOp>(ctx, eqz_op) {
+                (
+                    wasm::ConstantOp::new_i32_unlinked(ctx, 0).get_operation(),
+                    wasm::I32NeOp::new_unlinked(ctx).get_operation(),
+                )
+            } else if is_op::<wasm::I64EqzOp>(ctx, eqz_op) {
+                (
+                    wasm::ConstantOp::new_i64_unlinked(ctx, 0).get_operation(),
+                    wasm::I64NeOp::new_unlinked(ctx).get_operation(),
+                )
+            } else {
+                continue;
+            };
+            const_op.insert_before(ctx, eqz_op);
+            ne_op.insert_before(ctx, eqz_op);
+            Operation::erase(eqz_op, ctx);
+            Operation::erase(outer_eqz_op, ctx);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+/// `local.set i; local.set i` -> `drop; local.set i`, the first stored value is overwritten
+#[derive(Default)]
+pub struct DoubleLocalSetToDrop;
+
+impl CanonicalizationPattern for DoubleLocalSetToDrop {
+    fn rewrite_block(
+        &self,
+        ctx: &mut Context,
+        block: Ptr<BasicBlock>,
+    ) -> Result<bool, anyhow::Error> {
+        let ops = block_ops(ctx, block);
+        for pair in ops.windows(2) {
+            let (first_set_op, second_set_op) = (pair[0], pair[1]);
+            let local_index = |op: Ptr<Operation>| {
+                op.deref(ctx)
+                    .get_op(ctx)
+                    .downcast_ref::<wasm::LocalSetOp>()
+                    .map(|local_set_op| local_set_op.get_index(ctx))
+            };
+            let (Some(first_index), Some(second_index)) =
+                (local_index(first_set_op), local_index(second_set_op))
+            else {
+                continue;
+            };
+            if first_index == second_index {
+                let drop_op = wasm::DropOp::new_unlinked(ctx);
+                drop_op.get_operation().insert_before(ctx, first_set_op);
+                Operation::erase(first_set_op, ctx);
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// `local.tee i` -> `local.set i; local.get i`, the backends lower only the latter
+#[derive(Default)]
+pub struct LocalTeeToSetGet;
+
+impl CanonicalizationPattern for LocalTeeToSetGet {
+    fn rewrite_block(
+        &self,
+        ctx: &mut Context,
+        block: Ptr<BasicBlock>,
+    ) -> Result<bool, anyhow::Error> {
+        let ops = block_ops(ctx, block);
+        for tee_op in ops {
+            let Some(local_tee_op) = tee_op
+                .deref(ctx)
+                .get_op(ctx)
+                .downcast_ref::<wasm::LocalTeeOp>()
+                .cloned() else {
+                continue;
+            };
+            let index = local_tee_op.get_index(ctx);
+            let set_op = wasm::LocalSetOp::new_unlinked(ctx, index.into());
+            set_op.get_operation().insert_before(ctx, tee_op);
+            let get_op = wasm::LocalGetOp::new_unlinked(ctx, index.into());
+            get_op.get_operation().insert_before(ctx, tee_op);
+            Operation::erase(tee_op, ctx);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_pass;
+
+    use super::*;
+
+    #[test]
+    fn add_zero_and_tee() {
+        check_wasm_pass(
+            &WasmCanonicalizePass::default(),
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 3
+        i32.const 0
+        i32.add
+        local.tee 0
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      // locals: [0: si32]
+                      entry():
+                        wasm.const 0x3: si32
+                        wasm.local.set 0
+                        wasm.local.get 0
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn eqz_eqz_and_double_set() {
+        check_wasm_pass(
+            &WasmCanonicalizePass::default(),
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 3
+        i32.eqz
+        i32.eqz
+        i32.const 5
+        local.set 0
+        local.set 0
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      // locals: [0: si32]
+                      entry():
+                        wasm.const 0x3: si32
+                        wasm.const 0x0: si32
+                        wasm.i32.ne
+                        wasm.drop
+                        wasm.local.set 0
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn tee_in_if() {
+        check_wasm_pass(
+            &WasmCanonicalizePass::default(),
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 1
+        (if
+            (then
+                i32.const 3
+                local.tee 0
+                drop)
+            (else
+                i32.const 4
+                local.tee 0
+                drop))
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      // locals: [0: si32]
+                      entry():
+                        wasm.const 0x1: si32
+                        wasm.if () -> () {
+                          then():
+                            wasm.const 0x3: si32
+                            wasm.local.set 0
+                            wasm.local.get 0
+                            wasm.drop
+                        } else {
+                          else():
+                            wasm.const 0x4: si32
+                            wasm.local.set 0
+                            wasm.local.get 0
+                            wasm.drop
+                        }
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+}
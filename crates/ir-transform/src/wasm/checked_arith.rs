@@ -0,0 +1,385 @@
+//! Recognition of the u32 overflow checks emitted by Rust (debug builds or `overflow-checks`)
+//! for `a + b` and `a - b`. The check branches to a panic path that ends with a trap:
+//!
+//! ```wat
+//! block
+//!   local.get $a
+//!   local.get $b
+//!   i32.add
+//!   local.tee $sum
+//!   local.get $a
+//!   i32.lt_u      ;; the wrapped sum is below an operand
+//!   br_if 0
+//!   ...
+//! end
+//! call $core::panicking::panic_const_add_overflow
+//! unreachable
+//! ```
+//!
+//! The comparison and the branch are replaced with the checked op that traps on overflow,
+//! which is a single native instruction on the targets with the u32 range checks.
+
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::ops::BrIfCondition;
+use ozk_wasm_dialect::types::BinaryOpcode;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Replaces the u32 overflow checks of `i32.add` and `i32.sub` that branch to a panic path
+/// with [ozk::U32CheckedAddOp] and [ozk::U32CheckedSubOp]. A panic path is the code after
+/// the branch target that only calls functions and ends with `unreachable`. The checks that
+/// branch elsewhere (e.g. `checked_add` returning `None`) are left as is.
+/// Must run before the control flow is lowered.
+#[derive(Default)]
+pub struct WasmCheckedArithPass;
+
+impl Pass for WasmCheckedArithPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut func_ops = Vec::new();
+        op.walk_only::<wasm::FuncOp>(ctx, WalkOrder::PostOrder, &mut |func_op| {
+            func_ops.push(*func_op);
+            WalkResult::Advance
+        });
+        for func_op in func_ops {
+            let entry_block = func_op.get_entry_block(ctx);
+            rewrite_block(ctx, entry_block, &mut Vec::new());
+        }
+        Ok(())
+    }
+}
+
+/// Rewrites the checks in the block and the nested blocks. `labels` tells for each enclosing
+/// label (innermost last) if a branch to it goes to a panic path.
+fn rewrite_block(ctx: &mut Context, block: Ptr<BasicBlock>, labels: &mut Vec<bool>) {
+    let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+    for (i, op) in ops.iter().enumerate() {
+        let opop = op.deref(ctx).get_op(ctx);
+        let nested_blocks = if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+            vec![block_op.get_block(ctx)]
+        } else if let Some(if_op) = opop.downcast_ref::<wasm::IfOp>() {
+            vec![if_op.get_then_block(ctx), if_op.get_else_block(ctx)]
+        } else if let Some(loop_op) = opop.downcast_ref::<wasm::LoopOp>() {
+            // a branch to a loop goes to its beginning
+            let loop_block = loop_op.get_block(ctx);
+            labels.push(false);
+            rewrite_block(ctx, loop_block, labels);
+            labels.pop();
+            continue;
+        } else {
+            continue;
+        };
+        labels.push(is_panic_path(ctx, &ops[i + 1..]));
+        for nested_block in nested_blocks {
+            rewrite_block(ctx, nested_block, labels);
+        }
+        labels.pop();
+    }
+    while rewrite_checked_add(ctx, block, labels) || rewrite_checked_sub(ctx, block, labels) {}
+}
+
+/// Calls (with their arguments) ending with `unreachable`
+fn is_panic_path(ctx: &Context, ops: &[Ptr<Operation>]) -> bool {
+    for op in ops {
+        let opop = op.deref(ctx).get_op(ctx);
+        if opop.downcast_ref::<wasm::UnreachableOp>().is_some() {
+            return true;
+        }
+        if opop.downcast_ref::<wasm::CallOp>().is_none()
+            && opop.downcast_ref::<wasm::ConstantOp>().is_none()
+            && opop.downcast_ref::<wasm::LocalGetOp>().is_none()
+        {
+            return false;
+        }
+    }
+    false
+}
+
+/// The `br_if` op that branches on a non-zero i32 to a panic path
+fn is_panic_branch(ctx: &Context, op: Ptr<Operation>, labels: &[bool]) -> bool {
+    let opop = op.deref(ctx).get_op(ctx);
+    let Some(br_if_op) = opop.downcast_ref::<wasm::BrIfOp>() else {
+        return false;
+    };
+    if br_if_op.get_condition(ctx) != BrIfCondition::I32NonZero {
+        return false;
+    }
+    let depth = u32::from(br_if_op.get_relative_depth(ctx)) as usize;
+    // beyond the labels is the function itself (return)
+    depth < labels.len() && labels[labels.len() - 1 - depth]
+}
+
+fn local_get_index(ctx: &Context, op: Ptr<Operation>) -> Option<u32> {
+    op.deref(ctx)
+        .get_op(ctx)
+        .downcast_ref::<wasm::LocalGetOp>()
+        .map(|local_get_op| u32::from(local_get_op.get_index(ctx)))
+}
+
+fn is_op<T: Op>(ctx: &Context, op: Ptr<Operation>) -> bool {
+    op.deref(ctx).get_op(ctx).downcast_ref::<T>().is_some()
+}
+
+/// Pushes a single value without popping any
+fn is_push(ctx: &Context, op: Ptr<Operation>) -> bool {
+    is_op::<wasm::LocalGetOp>(ctx, op) || is_op::<wasm::ConstantOp>(ctx, op)
+}
+
+/// `i32.add; local.tee $s; local.get $x; i32.lt_u; br_if` -> `ozk.u32checked_add; local.set $s`,
+/// where `$x` is one of the operands of the `add`
+fn rewrite_checked_add(ctx: &mut Context, block: Ptr<BasicBlock>, labels: &[bool]) -> bool {
+    let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+    let i32_ty = i32_type(ctx);
+    for (i, window) in ops.windows(5).enumerate() {
+        let [add_op, tee_op, get_op, lt_op, br_if_op] =
+            [window[0], window[1], window[2], window[3], window[4]];
+        let is_i32_add = add_op
+            .deref(ctx)
+            .get_op(ctx)
+            .downcast_ref::<wasm::BinaryArithOp>()
+            .map_or(false, |op| {
+                op.get_opcode(ctx) == BinaryOpcode::Add && op.get_type(ctx) == i32_ty
+            });
+        let Some(sum_index) = tee_op
+            .deref(ctx)
+            .get_op(ctx)
+            .downcast_ref::<wasm::LocalTeeOp>()
+            .map(|op| u32::from(op.get_index(ctx))) else {
+            continue;
+        };
+        let Some(operand_index) = local_get_index(ctx, get_op) else {
+            continue;
+        };
+        if !is_i32_add
+            || operand_index == sum_index
+            || !is_op::<wasm::I32LtUOp>(ctx, lt_op)
+            || !is_panic_branch(ctx, br_if_op, labels)
+        {
+            continue;
+        }
+        // `local.get $x` is the second operand or the first one followed by a single push
+        let is_rhs = i >= 1 && local_get_index(ctx, ops[i - 1]) == Some(operand_index);
+        let is_lhs = i >= 2
+            && local_get_index(ctx, ops[i - 2]) == Some(operand_index)
+            && is_push(ctx, ops[i - 1]);
+        if !is_rhs && !is_lhs {
+            continue;
+        }
+        let checked_add_op = ozk::U32CheckedAddOp::new_unlinked(ctx).get_operation();
+        checked_add_op.insert_before(ctx, add_op);
+        let set_op = wasm::LocalSetOp::new_unlinked(ctx, sum_index).get_operation();
+        set_op.insert_before(ctx, add_op);
+        for op in [add_op, tee_op, get_op, lt_op, br_if_op] {
+            op.unlink(ctx);
+        }
+        return true;
+    }
+    false
+}
+
+/// `local.get $a; local.get $b; i32.lt_u; br_if; local.get $a; local.get $b; i32.sub` ->
+/// `local.get $a; local.get $b; ozk.u32checked_sub`
+fn rewrite_checked_sub(ctx: &mut Context, block: Ptr<BasicBlock>, labels: &[bool]) -> bool {
+    let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+    let i32_ty = i32_type(ctx);
+    for window in ops.windows(7) {
+        let Some(a) = local_get_index(ctx, window[0]) else {
+            continue;
+        };
+        let Some(b) = local_get_index(ctx, window[1]) else {
+            continue;
+        };
+        let is_i32_sub = window[6]
+            .deref(ctx)
+            .get_op(ctx)
+            .downcast_ref::<wasm::BinaryArithOp>()
+            .map_or(false, |op| {
+                op.get_opcode(ctx) == BinaryOpcode::Sub && op.get_type(ctx) == i32_ty
+            });
+        if !is_op::<wasm::I32LtUOp>(ctx, window[2])
+            || !is_panic_branch(ctx, window[3], labels)
+            || local_get_index(ctx, window[4]) != Some(a)
+            || local_get_index(ctx, window[5]) != Some(b)
+            || !is_i32_sub
+        {
+            continue;
+        }
+        let checked_sub_op = ozk::U32CheckedSubOp::new_unlinked(ctx).get_operation();
+        checked_sub_op.insert_before(ctx, window[6]);
+        for op in &window[..4] {
+            op.unlink(ctx);
+        }
+        window[6].unlink(ctx);
+        return true;
+    }
+    false
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use expect_test::Expect;
+    use ozk_ozk_dialect::types::FuncSym;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    fn check_main(wat: &str, expected: Expect) {
+        let (mut ctx, module_op) = parse_wasm_module(wat);
+        WasmCheckedArithPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        expected.assert_eq(&main_func.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn add_overflow_check() {
+        check_main(
+            r#"
+(module
+    (func $panic_add_overflow
+        unreachable)
+    (start $main)
+    (func $main
+        (local i32 i32 i32)
+        block
+            local.get 0
+            local.get 1
+            i32.add
+            local.tee 2
+            local.get 0
+            i32.lt_u
+            br_if 0
+            local.get 2
+            drop
+            return
+        end
+        call $panic_add_overflow
+        unreachable)
+)
+"#,
+            expect![[r#"
+                wasm.func @main() -> () {
+                  // locals: [0: si32, 1: si32, 2: si32]
+                  entry():
+                    wasm.block () -> () {
+                      entry():
+                        wasm.local.get 0
+                        wasm.local.get 1
+                        ozk.u32checked_add
+                        wasm.local.set 2
+                        wasm.local.get 2
+                        wasm.drop
+                        wasm.return
+                    }
+                    wasm.call 0
+                    wasm.unreachable
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn sub_overflow_check() {
+        check_main(
+            r#"
+(module
+    (func $panic_sub_overflow
+        unreachable)
+    (start $main)
+    (func $main
+        (local i32 i32)
+        block
+            local.get 0
+            local.get 1
+            i32.lt_u
+            br_if 0
+            local.get 0
+            local.get 1
+            i32.sub
+            drop
+            return
+        end
+        call $panic_sub_overflow
+        unreachable)
+)
+"#,
+            expect![[r#"
+                wasm.func @main() -> () {
+                  // locals: [0: si32, 1: si32]
+                  entry():
+                    wasm.block () -> () {
+                      entry():
+                        wasm.local.get 0
+                        wasm.local.get 1
+                        ozk.u32checked_sub
+                        wasm.drop
+                        wasm.return
+                    }
+                    wasm.call 0
+                    wasm.unreachable
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn checked_add_returning_none_is_kept() {
+        check_main(
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32 i32 i32)
+        block
+            local.get 0
+            local.get 1
+            i32.add
+            local.tee 2
+            local.get 0
+            i32.lt_u
+            br_if 0
+            local.get 2
+            drop
+            return
+        end
+        i32.const 0
+        drop
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.func @main() -> () {
+                  // locals: [0: si32, 1: si32, 2: si32]
+                  entry():
+                    wasm.block () -> () {
+                      entry():
+                        wasm.local.get 0
+                        wasm.local.get 1
+                        wasm.i32.add
+                        wasm.local.tee 2
+                        wasm.local.get 0
+                        wasm.i32.lt_u
+                        wasm.br_if 0
+                        wasm.local.get 2
+                        wasm.drop
+                        wasm.return
+                    }
+                    wasm.const 0x0: si32
+                    wasm.drop
+                    wasm.return
+                }"#]],
+        );
+    }
+}
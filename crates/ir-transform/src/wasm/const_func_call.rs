@@ -0,0 +1,484 @@
+use std::collections::HashMap;
+
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::i64_type;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::BinaryOpcode;
+use ozk_wasm_dialect::types::FuncIndex;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::attr_interfaces::TypedAttrInterface;
+use pliron::dialects::builtin::attributes::IntegerAttr;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+use pliron::r#type::TypeObj;
+
+/// Replaces calls to the functions that always return the same constant with the constant
+/// and removes such functions once they are not called anymore.
+/// A function is constant if it takes no arguments and its body is straight-line code of
+/// constants, integer arithmetic, locals and calls to the other constant functions
+/// (e.g. `call $get_base; i32.const 2; i32.mul; return`). The constant functions are found
+/// interprocedurally, until no more function becomes constant.
+#[derive(Default)]
+pub struct WasmConstFuncCallToConstPass;
+
+impl Pass for WasmConstFuncCallToConstPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let int_types = IntTypes::new(ctx);
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            let const_funcs = const_funcs(ctx, &module_op, &int_types);
+            replace_const_func_calls(ctx, &module_op, &const_funcs);
+            remove_uncalled_const_funcs(ctx, &module_op, &const_funcs)?;
+        }
+        Ok(())
+    }
+}
+
+/// Value of an i32 or i64 constant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConstValue {
+    I32(i32),
+    I64(i64),
+}
+
+/// The integer types the constants are evaluated for
+struct IntTypes {
+    i32: Ptr<TypeObj>,
+    i64: Ptr<TypeObj>,
+}
+
+impl IntTypes {
+    fn new(ctx: &mut Context) -> Self {
+        Self {
+            i32: i32_type(ctx),
+            i64: i64_type(ctx),
+        }
+    }
+
+    /// The initial value of a local of the given type
+    fn zero(&self, ty: Ptr<TypeObj>) -> Option<ConstValue> {
+        if ty == self.i32 {
+            Some(ConstValue::I32(0))
+        } else if ty == self.i64 {
+            Some(ConstValue::I64(0))
+        } else {
+            None
+        }
+    }
+
+    fn const_value(&self, ctx: &Context, const_op: &wasm::ConstantOp) -> Option<ConstValue> {
+        let value = const_op.get_value(ctx);
+        let ty = value.downcast_ref::<IntegerAttr>()?.get_type();
+        if ty == self.i32 {
+            const_op.get_i32(ctx).ok().map(ConstValue::I32)
+        } else if ty == self.i64 {
+            const_op.get_i64(ctx).ok().map(ConstValue::I64)
+        } else {
+            None
+        }
+    }
+
+    fn has_type(&self, value: ConstValue, ty: Ptr<TypeObj>) -> bool {
+        match value {
+            ConstValue::I32(_) => ty == self.i32,
+            ConstValue::I64(_) => ty == self.i64,
+        }
+    }
+}
+
+fn defined_funcs(ctx: &Context, module_op: &wasm::ModuleOp) -> Vec<wasm::FuncOp> {
+    module_op
+        .get_body(ctx, 0)
+        .deref(ctx)
+        .iter(ctx)
+        .filter_map(|op| {
+            op.deref(ctx)
+                .get_op(ctx)
+                .downcast_ref::<wasm::FuncOp>()
+                .cloned()
+        })
+        .collect()
+}
+
+fn call_ops(ctx: &Context, module_op: &wasm::ModuleOp) -> Vec<wasm::CallOp> {
+    let mut call_ops = Vec::new();
+    module_op.get_operation().walk_only::<wasm::CallOp>(
+        ctx,
+        WalkOrder::PostOrder,
+        &mut |call_op| {
+            call_ops.push(*call_op);
+            WalkResult::Advance
+        },
+    );
+    call_ops
+}
+
+/// Evaluates the body of the function, returns the constant it returns if the function takes
+/// no arguments and the body has no other effects than computing it. The calls are evaluated
+/// with the already known `const_funcs`.
+fn eval_const_result(
+    ctx: &Context,
+    func_op: &wasm::FuncOp,
+    int_types: &IntTypes,
+    const_funcs: &HashMap<FuncIndex, ConstValue>,
+) -> Option<ConstValue> {
+    let func_type = func_op.get_type(ctx);
+    if !func_type.get_inputs().is_empty() {
+        return None;
+    }
+    let results = func_type.get_results();
+    let [result_ty] = results.as_slice() else {
+        return None;
+    };
+    let mut locals = func_op
+        .get_locals(ctx)
+        .into_iter()
+        .map(|ty| int_types.zero(ty))
+        .collect::<Option<Vec<ConstValue>>>()?;
+    let mut stack: Vec<ConstValue> = Vec::new();
+    let mut returned = false;
+    for op in func_op.op_iter(ctx) {
+        let opop = op.deref(ctx).get_op(ctx);
+        if let Some(const_op) = opop.downcast_ref::<wasm::ConstantOp>() {
+            stack.push(int_types.const_value(ctx, const_op)?);
+        } else if let Some(binary_op) = opop.downcast_ref::<wasm::BinaryArithOp>() {
+            let rhs = stack.pop()?;
+            let lhs = stack.pop()?;
+            stack.push(eval_binary(binary_op.get_opcode(ctx), lhs, rhs)?);
+        } else if let Some(call_op) = opop.downcast_ref::<wasm::CallOp>() {
+            stack.push(*const_funcs.get(&call_op.get_func_index(ctx))?);
+        } else if let Some(local_get_op) = opop.downcast_ref::<wasm::LocalGetOp>() {
+            let index = u32::from(local_get_op.get_index(ctx)) as usize;
+            stack.push(*locals.get(index)?);
+        } else if let Some(local_set_op) = opop.downcast_ref::<wasm::LocalSetOp>() {
+            let index = u32::from(local_set_op.get_index(ctx)) as usize;
+            *locals.get_mut(index)? = stack.pop()?;
+        } else if let Some(local_tee_op) = opop.downcast_ref::<wasm::LocalTeeOp>() {
+            let index = u32::from(local_tee_op.get_index(ctx)) as usize;
+            *locals.get_mut(index)? = *stack.last()?;
+        } else if opop.downcast_ref::<wasm::DropOp>().is_some() {
+            stack.pop()?;
+        } else if opop.downcast_ref::<wasm::ReturnOp>().is_some() {
+            returned = true;
+            break;
+        } else {
+            return None;
+        }
+    }
+    // the values below the result are discarded by `return` only
+    if !returned && stack.len() != 1 {
+        return None;
+    }
+    stack
+        .last()
+        .copied()
+        .filter(|value| int_types.has_type(*value, *result_ty))
+}
+
+/// Returns the result of the op, or None if it traps or the operand types differ
+fn eval_binary(opcode: BinaryOpcode, lhs: ConstValue, rhs: ConstValue) -> Option<ConstValue> {
+    match (lhs, rhs) {
+        (ConstValue::I32(a), ConstValue::I32(b)) => eval_i32(opcode, a, b).map(ConstValue::I32),
+        (ConstValue::I64(a), ConstValue::I64(b)) => eval_i64(opcode, a, b).map(ConstValue::I64),
+        (ConstValue::I32(_), ConstValue::I64(_)) | (ConstValue::I64(_), ConstValue::I32(_)) => None,
+    }
+}
+
+fn eval_i32(opcode: BinaryOpcode, a: i32, b: i32) -> Option<i32> {
+    // the shift and rotation amounts are taken modulo the bit width
+    let amount = (b as u32) % i32::BITS;
+    match opcode {
+        BinaryOpcode::Add => Some(a.wrapping_add(b)),
+        BinaryOpcode::Sub => Some(a.wrapping_sub(b)),
+        BinaryOpcode::Mul => Some(a.wrapping_mul(b)),
+        BinaryOpcode::DivS => a.checked_div(b),
+        BinaryOpcode::DivU => (a as u32).checked_div(b as u32).map(|r| r as i32),
+        BinaryOpcode::RemS => {
+            if b == 0 {
+                None
+            } else {
+                Some(a.wrapping_rem(b))
+            }
+        }
+        BinaryOpcode::RemU => (a as u32).checked_rem(b as u32).map(|r| r as i32),
+        BinaryOpcode::And => Some(a & b),
+        BinaryOpcode::Or => Some(a | b),
+        BinaryOpcode::Xor => Some(a ^ b),
+        BinaryOpcode::Shl => Some(a << amount),
+        BinaryOpcode::ShrS => Some(a >> amount),
+        BinaryOpcode::ShrU => Some(((a as u32) >> amount) as i32),
+        BinaryOpcode::Rotl => Some(a.rotate_left(amount)),
+        BinaryOpcode::Rotr => Some(a.rotate_right(amount)),
+    }
+}
+
+fn eval_i64(opcode: BinaryOpcode, a: i64, b: i64) -> Option<i64> {
+    // the shift and rotation amounts are taken modulo the bit width
+    let amount = ((b as u64) % u64::from(i64::BITS)) as u32;
+    match opcode {
+        BinaryOpcode::Add => Some(a.wrapping_add(b)),
+        BinaryOpcode::Sub => Some(a.wrapping_sub(b)),
+        BinaryOpcode::Mul => Some(a.wrapping_mul(b)),
+        BinaryOpcode::DivS => a.checked_div(b),
+        BinaryOpcode::DivU => (a as u64).checked_div(b as u64).map(|r| r as i64),
+        BinaryOpcode::RemS => {
+            if b == 0 {
+                None
+            } else {
+                Some(a.wrapping_rem(b))
+            }
+        }
+        BinaryOpcode::RemU => (a as u64).checked_rem(b as u64).map(|r| r as i64),
+        BinaryOpcode::And => Some(a & b),
+        BinaryOpcode::Or => Some(a | b),
+        BinaryOpcode::Xor => Some(a ^ b),
+        BinaryOpcode::Shl => Some(a << amount),
+        BinaryOpcode::ShrS => Some(a >> amount),
+        BinaryOpcode::ShrU => Some(((a as u64) >> amount) as i64),
+        BinaryOpcode::Rotl => Some(a.rotate_left(amount)),
+        BinaryOpcode::Rotr => Some(a.rotate_right(amount)),
+    }
+}
+
+/// Finds the constant functions, each round evaluates the functions with the constant functions
+/// found so far, until no new one is found
+fn const_funcs(
+    ctx: &Context,
+    module_op: &wasm::ModuleOp,
+    int_types: &IntTypes,
+) -> HashMap<FuncIndex, ConstValue> {
+    let funcs: Vec<(FuncIndex, wasm::FuncOp)> = defined_funcs(ctx, module_op)
+        .into_iter()
+        .filter_map(|func_op| {
+            module_op
+                .get_func_index(ctx, FuncSym::from(func_op.get_symbol_name(ctx)))
+                .map(|func_index| (func_index, func_op))
+        })
+        .collect();
+    let mut const_funcs = HashMap::new();
+    loop {
+        let mut found = false;
+        for (func_index, func_op) in &funcs {
+            if const_funcs.contains_key(func_index) {
+                continue;
+            }
+            if let Some(value) = eval_const_result(ctx, func_op, int_types, &const_funcs) {
+                const_funcs.insert(*func_index, value);
+                found = true;
+            }
+        }
+        if !found {
+            return const_funcs;
+        }
+    }
+}
+
+fn replace_const_func_calls(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    const_funcs: &HashMap<FuncIndex, ConstValue>,
+) {
+    for call_op in call_ops(ctx, module_op) {
+        let Some(value) = const_funcs.get(&call_op.get_func_index(ctx)) else {
+            continue;
+        };
+        let const_op = match *value {
+            ConstValue::I32(value) => wasm::ConstantOp::new_i32_unlinked(ctx, value),
+            ConstValue::I64(value) => wasm::ConstantOp::new_i64_unlinked(ctx, value),
+        };
+        const_op
+            .get_operation()
+            .insert_before(ctx, call_op.get_operation());
+        call_op.get_operation().unlink(ctx);
+    }
+}
+
+fn remove_uncalled_const_funcs(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    const_funcs: &HashMap<FuncIndex, ConstValue>,
+) -> Result<(), anyhow::Error> {
+    let start_func_sym = module_op.try_get_start_func_sym(ctx);
+    // `const_funcs` is keyed by the indices before any removal, they shift on removal
+    let removable: Vec<(FuncSym, wasm::FuncOp)> = defined_funcs(ctx, module_op)
+        .into_iter()
+        .map(|func_op| (FuncSym::from(func_op.get_symbol_name(ctx)), func_op))
+        .filter(|(func_sym, _)| {
+            start_func_sym.as_ref() != Some(func_sym)
+                && module_op
+                    .get_func_index(ctx, func_sym.clone())
+                    .map_or(false, |func_index| const_funcs.contains_key(&func_index))
+        })
+        .collect();
+    for (func_sym, func_op) in removable {
+        let Some(func_index) = module_op.get_func_index(ctx, func_sym) else {
+            continue;
+        };
+        if !module_op.is_func_called(ctx, func_index) {
+            module_op.remove_function(ctx, func_op)?;
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use ozk_rust_wasm_tests_helper::build_rust_wasm_tests;
+    use ozk_rust_wasm_tests_helper::BuildProfile;
+    use ozk_rust_wasm_tests_helper::RustWasmBuildOptions;
+
+    use crate::tests_util::check_wasm_pass;
+    use crate::tests_util::parse_wasm_binary;
+
+    use super::*;
+
+    #[test]
+    fn const_func_calls() {
+        check_wasm_pass(
+            &WasmConstFuncCallToConstPass,
+            r#"
+(module
+    (start $main)
+    (func $get_config (result i32)
+        i32.const 42
+        return)
+    (func $get_config_indirect (result i32)
+        call $get_config
+        return)
+    (func $main
+        i32.const 1
+        call $get_config_indirect
+        i32.add
+        call $get_config
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_3_0():
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.const 0x1: si32
+                        wasm.const 0x2a: si32
+                        wasm.i32.add
+                        wasm.const 0x2a: si32
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn folded_const_funcs() {
+        check_wasm_pass(
+            &WasmConstFuncCallToConstPass,
+            r#"
+(module
+    (start $main)
+    (func $base (result i64)
+        i64.const 3
+        return)
+    (func $multiplier (result i64)
+        (local i64)
+        call $base
+        local.set 0
+        local.get 0
+        i64.const 7
+        i64.mul
+        i64.const 1
+        i64.add
+        return)
+    (func $not_const (param i32) (result i32)
+        local.get 0
+        return)
+    (func $traps (result i32)
+        i32.const 1
+        i32.const 0
+        i32.div_u
+        return)
+    (func $main
+        call $multiplier
+        drop
+        i32.const 5
+        call $not_const
+        call $traps
+        i32.add
+        drop
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_5_0():
+                    wasm.func @not_const(si32) -> (si32) {
+                      entry():
+                        wasm.local.get 0
+                        wasm.return
+                    }
+                    wasm.func @traps() -> (si32) {
+                      entry():
+                        wasm.const 0x1: si32
+                        wasm.const 0x0: si32
+                        wasm.i32.div_u
+                        wasm.return
+                    }
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.const 0x16: si64
+                        wasm.drop
+                        wasm.const 0x5: si32
+                        wasm.call 0
+                        wasm.call 1
+                        wasm.i32.add
+                        wasm.drop
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn const_funcs_bundle() {
+        let input = vec![10];
+        let secret_input = vec![];
+        let expected_output = vec![13, 220];
+        let native_output = ozk_rust_wasm_tests_helper::wrap_main_with_io(
+            &ozk_rust_wasm_tests_consts::consts::apply_fees,
+        )(input, secret_input);
+        assert_eq!(native_output, expected_output);
+        // the optimized build folds the calls itself
+        let options = RustWasmBuildOptions {
+            profile: BuildProfile::Dev,
+            ..Default::default()
+        };
+        let wasm_bytes = build_rust_wasm_tests("consts-bin", "consts", &options).unwrap();
+        let (mut ctx, module_op) = parse_wasm_binary(&wasm_bytes);
+        let const_func_syms = [FuncSym::from("base_fee"), FuncSym::from("fee_multiplier")];
+        for func_sym in &const_func_syms {
+            let func_index = module_op.get_func_index(&ctx, func_sym.clone()).unwrap();
+            assert!(module_op.is_func_called(&ctx, func_index), "{func_sym:?}");
+        }
+        WasmConstFuncCallToConstPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        let func_syms = module_op.get_func_syms(&ctx);
+        for func_sym in &const_func_syms {
+            assert!(!func_syms.contains(func_sym), "{func_sym:?}");
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Initialization of the linear memory with the active data segments ([wasm::DataOp]).
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::types::BinaryOpcode;
+use ozk_wasm_dialect::types::PrologueStage;
+use ozk_wasm_dialect::types::WASM_PAGE_SIZE;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+use crate::byte_layout::ByteLayout;
+use crate::byte_layout::CELL_BYTES;
+
+/// Symbol of the function that copies the data segments into the memory (a prologue function)
+pub const INIT_DATA_FUNC_NAME: &str = "init_data";
+
+/// Replaces the data segments with the [INIT_DATA_FUNC_NAME] prologue function that stores
+/// their bytes into the memory with `i32.store`, a cell at a time. The stores follow the Wasm
+/// semantics, so the target's load/store lowering places the bytes according to its
+/// [ByteLayout]. Must run before the prologue is emitted and the stores are lowered.
+#[derive(Default)]
+pub struct WasmDataInitPass;
+
+impl Pass for WasmDataInitPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            lower_data_ops(ctx, &module_op)?;
+        }
+        Ok(())
+    }
+}
+
+fn lower_data_ops(ctx: &mut Context, module_op: &wasm::ModuleOp) -> Result<(), anyhow::Error> {
+    let data_ops = module_op.get_data_ops(ctx);
+    if data_ops.is_empty() {
+        return Ok(());
+    }
+    let limits = module_op
+        .get_memory_limits(ctx)
+        .first()
+        .copied()
+        .ok_or_else(|| anyhow!("data segment without a declared memory"))?;
+    let memory_bytes = limits.minimum as u64 * WASM_PAGE_SIZE as u64;
+    let mut ops = Vec::new();
+    for data_op in data_ops {
+        let offset = data_op.get_offset(ctx);
+        let bytes = data_op.get_bytes(ctx);
+        if offset as u64 + bytes.len() as u64 > memory_bytes {
+            return Err(anyhow!(
+                "data segment at {offset} of {} bytes is out of the memory bounds \
+                 ({memory_bytes} bytes)",
+                bytes.len()
+            ));
+        }
+        ops.extend(bytes_write_ops(ctx, offset, &bytes));
+        data_op.get_operation().unlink(ctx);
+    }
+    ops.push(wasm::ReturnOp::new_unlinked(ctx).get_operation());
+    let entry_block = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+    for op in ops {
+        op.insert_at_back(entry_block, ctx);
+    }
+    let ty = FunctionType::get(ctx, vec![], vec![]);
+    let func_op = wasm::FuncOp::new_unlinked_with_block(
+        ctx,
+        FuncSym::from(INIT_DATA_FUNC_NAME),
+        ty,
+        entry_block,
+        vec![],
+    );
+    module_op.add_prologue_function(ctx, func_op, PrologueStage::MemoryInit);
+    Ok(())
+}
+
+/// Stores the bytes at the address, a cell at a time. Skips the zero cells, the memory is
+/// zero-initialized.
+pub(crate) fn bytes_write_ops(ctx: &mut Context, addr: u32, bytes: &[u8]) -> Vec<Ptr<Operation>> {
+    // the Wasm memory semantics, not the target's layout
+    let wasm_layout = ByteLayout::default();
+    let mut ops = Vec::new();
+    for write in wasm_layout.pack(addr, bytes) {
+        if write.value == 0 && write.mask == u32::MAX {
+            continue;
+        }
+        ops.extend(cell_write_ops(
+            ctx,
+            write.cell * CELL_BYTES,
+            write.value,
+            write.mask,
+        ));
+    }
+    ops
+}
+
+/// Stores the bits of `value` set in `mask` into the cell at `addr`, keeping the rest of it
+fn cell_write_ops(ctx: &mut Context, addr: u32, value: u32, mask: u32) -> Vec<Ptr<Operation>> {
+    let mut ops = vec![wasm::ConstantOp::new_i32_unlinked(ctx, addr as i32).get_operation()];
+    if mask != u32::MAX {
+        ops.extend([
+            wasm::ConstantOp::new_i32_unlinked(ctx, addr as i32).get_operation(),
+            wasm::LoadOp::new_unlinked(ctx, MemAccessOpValueType::I32).get_operation(),
+            wasm::ConstantOp::new_i32_unlinked(ctx, !mask as i32).get_operation(),
+            wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::And).get_operation(),
+            wasm::ConstantOp::new_i32_unlinked(ctx, value as i32).get_operation(),
+            wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Or).get_operation(),
+        ]);
+    } else {
+        ops.push(wasm::ConstantOp::new_i32_unlinked(ctx, value as i32).get_operation());
+    }
+    ops.push(wasm::StoreOp::new_unlinked(ctx, MemAccessOpValueType::I32).get_operation());
+    ops
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    #[test]
+    fn data_segments_to_prologue_func() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (memory 1)
+    (data (i32.const 8) "\01\02\03\04\00\00\00\00\05")
+    (data (i32.const 18) "\ff")
+    (start $main)
+    (func $main
+        return)
+)
+"#,
+        );
+        WasmDataInitPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        assert!(module_op.get_data_ops(&ctx).is_empty());
+        assert_eq!(
+            module_op.get_prologue_functions(&ctx),
+            vec![FuncSym::from(INIT_DATA_FUNC_NAME)]
+        );
+        expect![[r#"
+            wasm.module @module_name {
+              block_1_0():
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.return
+                }
+                wasm.func @init_data() -> () {
+                  entry():
+                    wasm.const 0x8: si32
+                    wasm.const 0x4030201: si32
+                    wasm.store I32
+                    wasm.const 0x10: si32
+                    wasm.const 0x10: si32
+                    wasm.load I32
+                    wasm.const 0xffffff00: si32
+                    wasm.i32.and
+                    wasm.const 0x5: si32
+                    wasm.i32.or
+                    wasm.store I32
+                    wasm.const 0x10: si32
+                    wasm.const 0x10: si32
+                    wasm.load I32
+                    wasm.const 0xff00ffff: si32
+                    wasm.i32.and
+                    wasm.const 0xff0000: si32
+                    wasm.i32.or
+                    wasm.store I32
+                    wasm.return
+                }
+            }"#]]
+        .assert_eq(&module_op.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn data_segment_out_of_bounds() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        return)
+)
+"#,
+        );
+        // the validator doesn't check the bounds (it's an instantiation error), put one in
+        let data_op = wasm::DataOp::new_unlinked(&mut ctx, WASM_PAGE_SIZE - 1, vec![1, 2]);
+        module_op.append_data(&mut ctx, data_op);
+        let err = WasmDataInitPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("out of the memory bounds"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Import module of the ozk runtime (stdlib) functions and intrinsics
+pub const ENV_IMPORT_MODULE: &str = "env";
+
+/// Fails if the program calls functions imported from a module other than [ENV_IMPORT_MODULE]
+/// (e.g. `wasi_snapshot_preview1`), listing such imports and their callers.
+/// The backends do not implement them, they have to be mapped to the ozk runtime functions
+/// (see `WasmFrontendConfig::import_func_remaps` and
+/// [WasmWasiShimPass](super::wasi_shim::WasmWasiShimPass)) or removed from the program.
+/// The imports that are never called are ignored.
+#[derive(Default)]
+pub struct WasmForeignImportsCheckPass;
+
+impl Pass for WasmForeignImportsCheckPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            let foreign_imports = called_foreign_imports(ctx, &module_op);
+            if !foreign_imports.is_empty() {
+                let imports = foreign_imports
+                    .iter()
+                    .map(|(import, callers)| {
+                        let callers = callers
+                            .iter()
+                            .map(|caller| caller.as_ref())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        format!("{import} (called from {callers})")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(anyhow!(
+                    "unresolved imports from modules other than `{ENV_IMPORT_MODULE}`: {imports}. \
+                     Remap them to the ozk runtime functions or remove their calls"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the called functions imported from the modules other than [ENV_IMPORT_MODULE]
+/// (as `module.name`) with their callers
+pub fn called_foreign_imports(
+    ctx: &Context,
+    module_op: &wasm::ModuleOp,
+) -> BTreeMap<String, Vec<FuncSym>> {
+    let import_func_modules = module_op.get_import_func_modules(ctx);
+    let func_syms = module_op.get_func_syms(ctx);
+    let mut foreign_imports: BTreeMap<String, Vec<FuncSym>> = BTreeMap::new();
+    for op in module_op.get_body(ctx, 0).deref(ctx).iter(ctx) {
+        let Some(func_op) = op.deref(ctx).get_op(ctx).downcast_ref::<wasm::FuncOp>().cloned() else {
+            continue;
+        };
+        let caller = FuncSym::from(func_op.get_symbol_name(ctx));
+        func_op.get_operation().walk_only::<wasm::CallOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |call_op| {
+                let index = usize::from(call_op.get_func_index(ctx));
+                if let (Some(import_module), Some(func_sym)) =
+                    (import_func_modules.get(index), func_syms.get(index))
+                {
+                    if import_module != ENV_IMPORT_MODULE {
+                        let callers = foreign_imports
+                            .entry(format!("{import_module}.{}", func_sym.as_ref()))
+                            .or_default();
+                        if !callers.contains(&caller) {
+                            callers.push(caller.clone());
+                        }
+                    }
+                }
+                WalkResult::Advance
+            },
+        );
+    }
+    foreign_imports
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use crate::tests_util::parse_wasm_module;
+    use crate::tests_util::run_wasm_pass_wrapped;
+
+    use super::*;
+
+    #[test]
+    fn uncalled_foreign_import() {
+        run_wasm_pass_wrapped(
+            &WasmForeignImportsCheckPass,
+            r#"
+(module
+    (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+    (import "env" "ozk_stdlib_pub_input" (func $pub_input (result i64)))
+    (start $main)
+    (func $main
+        (local i64)
+        call $pub_input
+        local.set 0
+        return)
+)
+"#,
+        );
+    }
+
+    #[test]
+    fn called_foreign_import() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+    (start $main)
+    (func $exit
+        i32.const 1
+        call $proc_exit
+        return)
+    (func $main
+        call $exit
+        i32.const 0
+        call $proc_exit
+        return)
+)
+"#,
+        );
+        let err = WasmForeignImportsCheckPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("wasi_snapshot_preview1.proc_exit (called from exit, main)"));
+    }
+}
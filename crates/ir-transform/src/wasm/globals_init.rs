@@ -0,0 +1,201 @@
+//! Initialization of the globals with the initial values of their declarations
+//! ([wasm::GlobalOp]).
+
+use std::collections::BTreeSet;
+
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::GlobalIndex;
+use ozk_wasm_dialect::types::GlobalInit;
+use ozk_wasm_dialect::types::PrologueStage;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Symbol of the function that sets the initial values of the globals (a prologue function)
+pub const INIT_GLOBALS_FUNC_NAME: &str = "init_globals";
+
+/// Replaces the global declarations with the [INIT_GLOBALS_FUNC_NAME] prologue function that
+/// sets the globals to their initial values with `global.set`, in the order of the global
+/// indices. Skips the globals that are never accessed (nor initialize an accessed one) and the
+/// zero initial values, the targets keep the globals in the zero-initialized memory. Must run
+/// before the prologue is emitted and the globals are lowered, after the imported globals are
+/// bound (see [super::import_globals]).
+#[derive(Default)]
+pub struct WasmGlobalsInitPass;
+
+impl Pass for WasmGlobalsInitPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            lower_global_ops(ctx, &module_op);
+        }
+        Ok(())
+    }
+}
+
+fn lower_global_ops(ctx: &mut Context, module_op: &wasm::ModuleOp) {
+    let global_ops = module_op.get_global_ops(ctx);
+    if global_ops.is_empty() {
+        return;
+    }
+    // the defined globals follow the imported ones in the index space
+    let import_count = module_op.get_import_globals(ctx).len();
+    let index_of = |index: usize| GlobalIndex::from((import_count + index) as u32);
+    let mut accessed = accessed_globals(ctx, module_op);
+    // the globals initialized with the accessed ones (declared before them)
+    for (index, global_op) in global_ops.iter().enumerate().rev() {
+        if let GlobalInit::GetGlobal(init_index) = global_op.get_init(ctx) {
+            if accessed.contains(&index_of(index)) {
+                accessed.insert(init_index);
+            }
+        }
+    }
+    let mut ops = Vec::new();
+    for (index, global_op) in global_ops.iter().enumerate() {
+        let global_index = index_of(index);
+        if !accessed.contains(&global_index) {
+            continue;
+        }
+        let value_op = match global_op.get_init(ctx) {
+            GlobalInit::I32Const(0) | GlobalInit::I64Const(0) => continue,
+            GlobalInit::I32Const(value) => {
+                wasm::ConstantOp::new_i32_unlinked(ctx, value).get_operation()
+            }
+            GlobalInit::I64Const(value) => {
+                wasm::ConstantOp::new_i64_unlinked(ctx, value).get_operation()
+            }
+            GlobalInit::GetGlobal(init_index) => {
+                wasm::GlobalGetOp::new_unlinked(ctx, u32::from(init_index)).get_operation()
+            }
+            GlobalInit::F32Const(_)
+            | GlobalInit::F64Const(_)
+            | GlobalInit::V128Const(_)
+            | GlobalInit::RefNullConst
+            | GlobalInit::RefFunc(_)
+            | GlobalInit::Import => continue,
+        };
+        ops.push(value_op);
+        ops.push(wasm::GlobalSetOp::new_unlinked(ctx, global_index).get_operation());
+    }
+    for global_op in global_ops {
+        global_op.get_operation().unlink(ctx);
+    }
+    if ops.is_empty() {
+        return;
+    }
+    ops.push(wasm::ReturnOp::new_unlinked(ctx).get_operation());
+    let entry_block = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+    for op in ops {
+        op.insert_at_back(entry_block, ctx);
+    }
+    let ty = FunctionType::get(ctx, vec![], vec![]);
+    let func_op = wasm::FuncOp::new_unlinked_with_block(
+        ctx,
+        FuncSym::from(INIT_GLOBALS_FUNC_NAME),
+        ty,
+        entry_block,
+        vec![],
+    );
+    module_op.add_prologue_function(ctx, func_op, PrologueStage::GlobalsInit);
+}
+
+/// The globals read or set by the functions
+fn accessed_globals(ctx: &Context, module_op: &wasm::ModuleOp) -> BTreeSet<GlobalIndex> {
+    let mut accessed = BTreeSet::new();
+    module_op
+        .get_operation()
+        .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+            let opop = op.deref(ctx).get_op(ctx);
+            if let Some(global_get_op) = opop.downcast_ref::<wasm::GlobalGetOp>() {
+                accessed.insert(global_get_op.get_index(ctx));
+            } else if let Some(global_set_op) = opop.downcast_ref::<wasm::GlobalSetOp>() {
+                accessed.insert(global_set_op.get_index(ctx));
+            }
+            WalkResult::Advance
+        });
+    accessed
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    #[test]
+    fn globals_to_prologue_func() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (global $__stack_pointer (mut i32) (i32.const 1048576))
+    (global $unused i32 (i32.const 7))
+    (global $zero (mut i32) (i32.const 0))
+    (global $seed (mut i64) (i64.const -5))
+    (start $main)
+    (func $main
+        global.get $__stack_pointer
+        global.set $zero
+        global.get $seed
+        drop
+        return)
+)
+"#,
+        );
+        WasmGlobalsInitPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        assert!(module_op.get_global_ops(&ctx).is_empty());
+        assert_eq!(
+            module_op.get_prologue_functions(&ctx),
+            vec![FuncSym::from(INIT_GLOBALS_FUNC_NAME)]
+        );
+        let init_func = module_op
+            .get_func(&ctx, &FuncSym::from(INIT_GLOBALS_FUNC_NAME))
+            .unwrap();
+        expect![[r#"
+            wasm.func @init_globals() -> () {
+              entry():
+                wasm.const 0x100000: si32
+                wasm.global.set 0
+                wasm.const 0xfffffffffffffffb: si64
+                wasm.global.set 3
+                wasm.return
+            }"#]]
+        .assert_eq(&init_func.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn unused_globals_no_prologue_func() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (global $__stack_pointer (mut i32) (i32.const 1048576))
+    (start $main)
+    (func $main
+        return)
+)
+"#,
+        );
+        WasmGlobalsInitPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        assert!(module_op.get_global_ops(&ctx).is_empty());
+        assert!(module_op.get_prologue_functions(&ctx).is_empty());
+    }
+}
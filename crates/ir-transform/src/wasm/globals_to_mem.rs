@@ -165,6 +165,7 @@ mod tests {
             expect![[r#"
                 wasm.module @module_name {
                   block_1_0():
+                    wasm.global mut si32 = i32.const 42
                     wasm.func @main() -> () {
                       entry():
                         wasm.const 0x9: si32
@@ -178,4 +179,65 @@ mod tests {
                 }"#]],
         );
     }
+
+    #[test]
+    fn globals_layout_across_calls() {
+        // global i is an i64 cell at `start_addr - i * 8`, the initial values
+        // are stored by the prologue function of WasmGlobalsInitPass
+        let pass = WasmGlobalsToMemPass {
+            start_addr: 0x1000.into(),
+        };
+        check_wasm_pass(
+            &pass,
+            r#"
+(module
+    (type (;2;) (func))
+    (global $counter (mut i64) (i64.const 40))
+    (global $step i64 (i64.const 1))
+    (global $flag (mut i32) (i32.const 0))
+    (export "main" (func $main))
+    (start $main)
+    (func $inc
+        global.get $counter
+        global.get $step
+        i64.add
+        global.set $counter
+        return)
+    (func $main
+        call $inc
+        i32.const 1
+        global.set $flag
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_2_0():
+                    wasm.global mut si64 = i64.const 40
+                    wasm.global si64 = i64.const 1
+                    wasm.global mut si32 = i32.const 0
+                    wasm.func @inc() -> () {
+                      entry():
+                        wasm.const 0x1000: si32
+                        wasm.load I64
+                        wasm.const 0xff8: si32
+                        wasm.load I64
+                        wasm.i64.add
+                        wasm.const 0x1000: si32
+                        ozk.swap 1
+                        wasm.store I64
+                        wasm.return
+                    }
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.call 0
+                        wasm.const 0x1: si32
+                        wasm.const 0xff0: si32
+                        ozk.swap 1
+                        wasm.store I64
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
 }
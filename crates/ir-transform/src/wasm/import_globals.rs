@@ -0,0 +1,329 @@
+//! Binding of the imported globals ([wasm::ModuleOp::get_import_globals]) to the values
+//! provided by the host.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::ord_n::Ord16;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::i64_type;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::GlobalIndex;
+use ozk_wasm_dialect::types::GlobalInit;
+use ozk_wasm_dialect::types::ImportGlobal;
+use ozk_wasm_dialect::types::MemAddress;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// The value of an imported global provided by the host
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportGlobalBinding {
+    /// The initial value (truncated for an i32 global), the global becomes a defined one
+    Const(i64),
+    /// The address of the value in the linear memory, the global is read and set with the
+    /// loads and stores
+    MemCell(MemAddress),
+}
+
+/// Binds the imported globals by their module and name. A constant binding turns the import
+/// into a defined global (placed before the other defined ones), a memory cell binding turns
+/// its `global.get/set` into the loads and stores of the cell. The global indices are
+/// compacted afterwards. Fails if an unbound imported global is accessed.
+/// Must run before the globals are initialized (see [super::globals_init]).
+#[derive(Default)]
+pub struct WasmImportGlobalsPass {
+    bindings: BTreeMap<(String, String), ImportGlobalBinding>,
+}
+
+impl WasmImportGlobalsPass {
+    /// `bindings` by the import module and global name
+    pub fn new(bindings: BTreeMap<(String, String), ImportGlobalBinding>) -> Self {
+        Self { bindings }
+    }
+}
+
+impl Pass for WasmImportGlobalsPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            self.bind_import_globals(ctx, &module_op)?;
+        }
+        Ok(())
+    }
+}
+
+impl WasmImportGlobalsPass {
+    fn bind_import_globals(
+        &self,
+        ctx: &mut Context,
+        module_op: &wasm::ModuleOp,
+    ) -> Result<(), anyhow::Error> {
+        let import_globals = module_op.get_import_globals(ctx);
+        if import_globals.is_empty() {
+            return Ok(());
+        }
+        let global_ops = module_op.get_global_ops(ctx);
+        let mut get_ops = Vec::new();
+        let mut set_ops = Vec::new();
+        module_op
+            .get_operation()
+            .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+                let opop = op.deref(ctx).get_op(ctx);
+                if let Some(global_get_op) = opop.downcast_ref::<wasm::GlobalGetOp>() {
+                    get_ops.push(*global_get_op);
+                } else if let Some(global_set_op) = opop.downcast_ref::<wasm::GlobalSetOp>() {
+                    set_ops.push(*global_set_op);
+                }
+                WalkResult::Advance
+            });
+        let mut accessed: BTreeSet<GlobalIndex> = get_ops
+            .iter()
+            .map(|op| op.get_index(ctx))
+            .chain(set_ops.iter().map(|op| op.get_index(ctx)))
+            .collect();
+        accessed.extend(global_ops.iter().filter_map(|op| match op.get_init(ctx) {
+            GlobalInit::GetGlobal(init_index) => Some(init_index),
+            _ => None,
+        }));
+
+        // new index of every global (imported and defined), `None` for the memory cells
+        let mut mapping: Vec<Option<GlobalIndex>> = Vec::new();
+        let mut mem_cells: BTreeMap<GlobalIndex, (MemAddress, wasm::MemAccessOpValueType)> =
+            BTreeMap::new();
+        let mut const_globals = Vec::new();
+        for (index, import_global) in import_globals.iter().enumerate() {
+            let global_index = GlobalIndex::from(index as u32);
+            let label = (import_global.module.clone(), import_global.name.clone());
+            match self.bindings.get(&label) {
+                Some(ImportGlobalBinding::Const(value)) => {
+                    let init = const_init(ctx, import_global, *value)?;
+                    const_globals.push(wasm::GlobalOp::new_unlinked(
+                        ctx,
+                        import_global.ty,
+                        import_global.mutable,
+                        init,
+                    ));
+                    mapping.push(Some(GlobalIndex::from(const_globals.len() as u32 - 1)));
+                }
+                Some(ImportGlobalBinding::MemCell(address)) => {
+                    let value_type = mem_access_type(ctx, import_global)?;
+                    mem_cells.insert(global_index, (*address, value_type));
+                    mapping.push(None);
+                }
+                None if accessed.contains(&global_index) => {
+                    return Err(anyhow!(
+                        "imported global {}.{} is not bound",
+                        import_global.module,
+                        import_global.name
+                    ));
+                }
+                None => mapping.push(None),
+            }
+        }
+        for index in 0..global_ops.len() {
+            mapping.push(Some(GlobalIndex::from(
+                (const_globals.len() + index) as u32,
+            )));
+        }
+        let new_index = |global_index: GlobalIndex| mapping[u32::from(global_index) as usize];
+        for global_op in &global_ops {
+            if let GlobalInit::GetGlobal(init_index) = global_op.get_init(ctx) {
+                if mem_cells.contains_key(&init_index) {
+                    return Err(anyhow!(
+                        "global initializer reads the imported global {} bound to a memory cell",
+                        u32::from(init_index)
+                    ));
+                }
+            }
+        }
+
+        for get_op in get_ops {
+            let index = get_op.get_index(ctx);
+            let ops = match (mem_cells.get(&index), new_index(index)) {
+                (Some((address, value_type)), _) => vec![
+                    wasm::ConstantOp::new_i32_unlinked(ctx, u32::from(*address) as i32)
+                        .get_operation(),
+                    wasm::LoadOp::new_unlinked(ctx, *value_type).get_operation(),
+                ],
+                (None, Some(new_index)) if new_index != index => {
+                    vec![wasm::GlobalGetOp::new_unlinked(ctx, u32::from(new_index)).get_operation()]
+                }
+                (None, _) => continue,
+            };
+            for op in ops {
+                op.insert_before(ctx, get_op.get_operation());
+            }
+            get_op.get_operation().unlink(ctx);
+        }
+        for set_op in set_ops {
+            let index = set_op.get_index(ctx);
+            let ops = match (mem_cells.get(&index), new_index(index)) {
+                (Some((address, value_type)), _) => vec![
+                    wasm::ConstantOp::new_i32_unlinked(ctx, u32::from(*address) as i32)
+                        .get_operation(),
+                    ozk::SwapOp::new_unlinked(ctx, Ord16::ST1).get_operation(),
+                    wasm::StoreOp::new_unlinked(ctx, *value_type).get_operation(),
+                ],
+                (None, Some(new_index)) if new_index != index => {
+                    vec![wasm::GlobalSetOp::new_unlinked(ctx, new_index).get_operation()]
+                }
+                (None, _) => continue,
+            };
+            for op in ops {
+                op.insert_before(ctx, set_op.get_operation());
+            }
+            set_op.get_operation().unlink(ctx);
+        }
+
+        // the constants first, then the defined globals with the remapped initializers
+        let mut new_global_ops = const_globals;
+        for global_op in global_ops {
+            global_op.get_operation().unlink(ctx);
+            let new_global_op = match global_op.get_init(ctx) {
+                GlobalInit::GetGlobal(init_index) => {
+                    let init_index = new_index(init_index)
+                        .ok_or_else(|| anyhow!("global initializer reads an unbound import"))?;
+                    wasm::GlobalOp::new_unlinked(
+                        ctx,
+                        global_op.get_type(ctx),
+                        global_op.is_mutable(ctx),
+                        GlobalInit::GetGlobal(init_index),
+                    )
+                }
+                _ => global_op,
+            };
+            new_global_ops.push(new_global_op);
+        }
+        for global_op in new_global_ops {
+            module_op.append_global(ctx, global_op);
+        }
+        module_op.set_import_globals(ctx, Vec::new());
+        Ok(())
+    }
+}
+
+fn const_init(
+    ctx: &Context,
+    import_global: &ImportGlobal,
+    value: i64,
+) -> Result<GlobalInit, anyhow::Error> {
+    if import_global.ty == i32_type(ctx) {
+        Ok(GlobalInit::I32Const(value as i32))
+    } else if import_global.ty == i64_type(ctx) {
+        Ok(GlobalInit::I64Const(value))
+    } else {
+        Err(unsupported_type(import_global))
+    }
+}
+
+fn mem_access_type(
+    ctx: &Context,
+    import_global: &ImportGlobal,
+) -> Result<wasm::MemAccessOpValueType, anyhow::Error> {
+    if import_global.ty == i32_type(ctx) {
+        Ok(wasm::MemAccessOpValueType::I32)
+    } else if import_global.ty == i64_type(ctx) {
+        Ok(wasm::MemAccessOpValueType::I64)
+    } else {
+        Err(unsupported_type(import_global))
+    }
+}
+
+fn unsupported_type(import_global: &ImportGlobal) -> anyhow::Error {
+    anyhow!(
+        "imported global {}.{}: only i32 and i64 globals are supported",
+        import_global.module,
+        import_global.name
+    )
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    const WAT: &str = r#"
+(module
+    (import "env" "base" (global $base i32))
+    (import "env" "counter" (global $counter (mut i64)))
+    (import "env" "unused" (global $unused i32))
+    (global $g (mut i32) (global.get $base))
+    (start $main)
+    (func $main
+        global.get $base
+        global.set $g
+        global.get $counter
+        i64.const 1
+        i64.add
+        global.set $counter
+        return)
+)
+"#;
+
+    #[test]
+    fn bind_const_and_mem_cell() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT);
+        WasmImportGlobalsPass::new(BTreeMap::from([
+            (
+                ("env".to_string(), "base".to_string()),
+                ImportGlobalBinding::Const(7),
+            ),
+            (
+                ("env".to_string(), "counter".to_string()),
+                ImportGlobalBinding::MemCell(MemAddress::from(0x100)),
+            ),
+        ]))
+        .run_on_operation(&mut ctx, module_op.get_operation())
+        .unwrap();
+        assert!(module_op.get_import_globals(&ctx).is_empty());
+        expect![[r#"
+            wasm.module @module_name {
+              block_1_0():
+                wasm.global si32 = i32.const 7
+                wasm.global mut si32 = global.get 0
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.global.get 0
+                    wasm.global.set 1
+                    wasm.const 0x100: si32
+                    wasm.load I64
+                    wasm.const 0x1: si64
+                    wasm.i64.add
+                    wasm.const 0x100: si32
+                    ozk.swap 1
+                    wasm.store I64
+                    wasm.return
+                }
+            }"#]]
+        .assert_eq(&module_op.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn unbound_import_global() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT);
+        let err = WasmImportGlobalsPass::new(BTreeMap::from([(
+            ("env".to_string(), "base".to_string()),
+            ImportGlobalBinding::Const(7),
+        )]))
+        .run_on_operation(&mut ctx, module_op.get_operation())
+        .unwrap_err();
+        assert_eq!(err.to_string(), "imported global env.counter is not bound");
+    }
+}
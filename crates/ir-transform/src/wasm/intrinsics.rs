@@ -0,0 +1,253 @@
+use anyhow::anyhow;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Import name of the cycle counter intrinsic (`ozk_stdlib::clock()`)
+pub const CLOCK_INTRINSIC_NAME: &str = "ozk_stdlib_clock";
+
+/// Import name of the debug output intrinsic (`ozk_stdlib::debug_print()`)
+pub const DEBUG_PRINT_INTRINSIC_NAME: &str = "ozk_stdlib_debug_print";
+
+/// Import name of the assert intrinsic (`ozk_stdlib::assert()`)
+pub const ASSERT_INTRINSIC_NAME: &str = "ozk_stdlib_assert";
+
+/// Replaces the calls to the intrinsic imports with the corresponding ozk ops
+/// and removes the intrinsic imports (shifting the function indices).
+/// Fails if the program uses an intrinsic the target does not support.
+/// The debug output is supported by every target (lowered to a debug write or a no-op).
+pub struct WasmIntrinsicsToOzkPass {
+    target_name: &'static str,
+    clock_supported: bool,
+    assert_supported: bool,
+}
+
+impl WasmIntrinsicsToOzkPass {
+    /// Create a pass for the target with the given name and capabilities
+    pub fn new(target_name: &'static str, clock_supported: bool, assert_supported: bool) -> Self {
+        Self {
+            target_name,
+            clock_supported,
+            assert_supported,
+        }
+    }
+}
+
+impl Pass for WasmIntrinsicsToOzkPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            let mut call_ops = Vec::new();
+            module_op.get_operation().walk_only::<wasm::CallOp>(
+                ctx,
+                WalkOrder::PostOrder,
+                &mut |call_op| {
+                    call_ops.push(*call_op);
+                    WalkResult::Advance
+                },
+            );
+            for call_op in call_ops {
+                let Some(func_sym) = module_op.get_func_sym(ctx, call_op.get_func_index(ctx)) else {
+                    continue;
+                };
+                let intrinsic_op = match func_sym.as_ref() {
+                    CLOCK_INTRINSIC_NAME => {
+                        if !self.clock_supported {
+                            return Err(anyhow!(
+                                "{CLOCK_INTRINSIC_NAME} (cycle counter) is not supported by {}",
+                                self.target_name
+                            ));
+                        }
+                        ozk::ClockOp::new_unlinked(ctx).get_operation()
+                    }
+                    DEBUG_PRINT_INTRINSIC_NAME => {
+                        ozk::DebugPrintOp::new_unlinked(ctx).get_operation()
+                    }
+                    ASSERT_INTRINSIC_NAME => {
+                        if !self.assert_supported {
+                            return Err(anyhow!(
+                                "{ASSERT_INTRINSIC_NAME} is not supported by {}",
+                                self.target_name
+                            ));
+                        }
+                        ozk::AssertOp::new_unlinked(ctx).get_operation()
+                    }
+                    _ => continue,
+                };
+                intrinsic_op.insert_before(ctx, call_op.get_operation());
+                call_op.get_operation().unlink(ctx);
+            }
+            // the intrinsic imports are not called anymore
+            for intrinsic_name in [
+                CLOCK_INTRINSIC_NAME,
+                DEBUG_PRINT_INTRINSIC_NAME,
+                ASSERT_INTRINSIC_NAME,
+            ] {
+                let func_sym = FuncSym::from(intrinsic_name);
+                if module_op.get_func_index(ctx, func_sym.clone()).is_some() {
+                    module_op.remove_import(ctx, &func_sym)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_pass;
+    use crate::tests_util::run_wasm_pass_wrapped;
+
+    use super::*;
+
+    const CLOCK_WAT: &str = r#"
+(module
+    (import "env" "ozk_stdlib_clock" (func $clock (result i64)))
+    (start $main)
+    (func $main
+        call $clock
+        return)
+)
+"#;
+
+    #[test]
+    fn clock_call() {
+        check_wasm_pass(
+            &WasmIntrinsicsToOzkPass::new("test", true, true),
+            CLOCK_WAT,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      entry():
+                        ozk.clock
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn debug_print_call() {
+        check_wasm_pass(
+            &WasmIntrinsicsToOzkPass::new("test", false, false),
+            r#"
+(module
+    (import "env" "ozk_stdlib_debug_print" (func $debug_print (param i64)))
+    (start $main)
+    (func $main
+        i64.const 7
+        call $debug_print
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.const 0x7: si64
+                        ozk.debug_print
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn assert_call() {
+        check_wasm_pass(
+            &WasmIntrinsicsToOzkPass::new("test", false, true),
+            r#"
+(module
+    (import "env" "ozk_stdlib_assert" (func $assert (param i32)))
+    (start $main)
+    (func $main
+        i32.const 1
+        call $assert
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.const 0x1: si32
+                        ozk.assert
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn intrinsic_imports_removed() {
+        let (ctx, module_op) = run_wasm_pass_wrapped(
+            &WasmIntrinsicsToOzkPass::new("test", true, true),
+            r#"
+(module
+    (import "env" "ozk_stdlib_clock" (func $clock (result i64)))
+    (import "env" "ozk_stdlib_pub_input" (func $pub_input (result i64)))
+    (import "env" "ozk_stdlib_debug_print" (func $debug_print (param i64)))
+    (start $main)
+    (func $main
+        call $clock
+        call $debug_print
+        call $pub_input
+        call $helper
+        return)
+    (func $helper (param i64))
+)
+"#,
+        );
+        let func_syms: Vec<String> = module_op
+            .get_func_syms(&ctx)
+            .into_iter()
+            .map(|func_sym| func_sym.as_ref().to_string())
+            .collect();
+        assert_eq!(func_syms, vec!["ozk_stdlib_pub_input", "main", "helper"]);
+        assert_eq!(module_op.get_import_func_modules(&ctx), vec!["env"]);
+        let mut call_indices = Vec::new();
+        module_op.get_operation().walk_only::<wasm::CallOp>(
+            &ctx,
+            WalkOrder::PostOrder,
+            &mut |call_op| {
+                call_indices.push(u32::from(call_op.get_func_index(&ctx)));
+                WalkResult::Advance
+            },
+        );
+        assert_eq!(call_indices, vec![0, 2]);
+    }
+
+    #[test]
+    fn clock_unsupported() {
+        let source = wat::parse_str(CLOCK_WAT).unwrap();
+        let mut ctx = Context::default();
+        let frontend_config = ozk_frontend_wasm::WasmFrontendConfig::default();
+        ozk_wasm_dialect::register(&mut ctx);
+        ozk_ozk_dialect::register(&mut ctx);
+        frontend_config.register(&mut ctx);
+        let module_op =
+            ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
+        let err = WasmIntrinsicsToOzkPass::new("test", false, false)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(err.to_string().contains("not supported by test"));
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt::Display;
+
+use ozk_ozk_dialect as ozk;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::error::CompilerError;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// A call that does not resolve to a function (defined or imported) of the module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedCall {
+    /// The function containing the call
+    pub caller: FuncSym,
+    /// The callee as referenced by the call (function index or symbol)
+    pub callee: String,
+}
+
+impl Display for UnresolvedCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (called from {})", self.callee, self.caller.as_ref())
+    }
+}
+
+/// Checks that every `wasm.call` (by function index) and `ozk.call` (by symbol) resolves to
+/// a function defined or imported in the module.
+/// Fails with the list of the unresolved calls and their callers. Should run after all the
+/// Wasm-level transformations, before the calls are lowered to the target.
+#[derive(Default)]
+pub struct WasmLinkCheckPass;
+
+impl Pass for WasmLinkCheckPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            let unresolved = unresolved_calls(ctx, &module_op);
+            if !unresolved.is_empty() {
+                return Err(CompilerError::VerificationError {
+                    msg: format!(
+                        "Unresolved calls in module {}: {}",
+                        module_op.get_symbol_name(ctx),
+                        unresolved
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the calls in the module that do not resolve to a defined or imported function
+pub fn unresolved_calls(ctx: &Context, module_op: &wasm::ModuleOp) -> Vec<UnresolvedCall> {
+    let func_syms = module_op.get_func_syms(ctx);
+    let import_count = module_op.get_import_func_types(ctx).len();
+    let resolves = |func_sym: &FuncSym| {
+        func_syms
+            .iter()
+            .position(|sym| sym == func_sym)
+            .map_or(false, |index| {
+                index < import_count || module_op.get_func(ctx, func_sym).is_some()
+            })
+    };
+    let mut unresolved = Vec::new();
+    for op in module_op.get_body(ctx, 0).deref(ctx).iter(ctx) {
+        let Some(func_op) = op.deref(ctx).get_op(ctx).downcast_ref::<wasm::FuncOp>().cloned() else {
+            continue;
+        };
+        let caller = FuncSym::from(func_op.get_symbol_name(ctx));
+        func_op.get_operation().walk_only::<wasm::CallOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |call_op| {
+                let func_index = call_op.get_func_index(ctx);
+                let is_resolved = module_op
+                    .get_func_sym(ctx, func_index)
+                    .map_or(false, |func_sym| resolves(&func_sym));
+                if !is_resolved {
+                    unresolved.push(UnresolvedCall {
+                        caller: caller.clone(),
+                        callee: format!("function index {func_index}"),
+                    });
+                }
+                WalkResult::Advance
+            },
+        );
+        func_op.get_operation().walk_only::<ozk::ops::CallOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |call_op| {
+                let func_sym = FuncSym::from(call_op.get_func_sym(ctx));
+                if !resolves(&func_sym) {
+                    unresolved.push(UnresolvedCall {
+                        caller: caller.clone(),
+                        callee: format!("function {}", func_sym.as_ref()),
+                    });
+                }
+                WalkResult::Advance
+            },
+        );
+        func_op.get_operation().walk_only::<ozk::ops::ReturnCallOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |call_op| {
+                let func_sym = FuncSym::from(call_op.get_func_sym(ctx));
+                if !resolves(&func_sym) {
+                    unresolved.push(UnresolvedCall {
+                        caller: caller.clone(),
+                        callee: format!("function {}", func_sym.as_ref()),
+                    });
+                }
+                WalkResult::Advance
+            },
+        );
+        func_op.get_operation().walk_only::<ozk::ops::HostCallOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |call_op| {
+                let func_sym = FuncSym::from(call_op.get_func_sym(ctx));
+                if !resolves(&func_sym) {
+                    unresolved.push(UnresolvedCall {
+                        caller: caller.clone(),
+                        callee: format!("host function {}", func_sym.as_ref()),
+                    });
+                }
+                WalkResult::Advance
+            },
+        );
+    }
+    unresolved
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use ozk_frontend_wasm::WasmFrontendConfig;
+
+    use crate::tests_util::run_wasm_pass_wrapped;
+    use crate::wasm::resolve_call_op::WasmCallOpToOzkCallOpPass;
+
+    use super::*;
+
+    const WAT: &str = r#"
+(module
+    (type (;0;) (func (param i64)))
+    (import "env" "ozk_stdlib_pub_output" (func $pub_output (type 0)))
+    (start $main)
+    (func $add (param i64 i64) (result i64)
+        local.get 0
+        local.get 1
+        i64.add
+        return)
+    (func $main
+        i64.const 1
+        i64.const 2
+        call $add
+        call $pub_output
+        return)
+)
+"#;
+
+    #[test]
+    fn resolved_calls() {
+        let (ctx, module_op) = run_wasm_pass_wrapped(&WasmLinkCheckPass, WAT);
+        assert!(unresolved_calls(&ctx, &module_op).is_empty());
+        let (ctx, module_op) = run_wasm_pass_wrapped(&WasmCallOpToOzkCallOpPass, WAT);
+        assert!(unresolved_calls(&ctx, &module_op).is_empty());
+    }
+
+    #[test]
+    fn unresolved_call_index() {
+        let source = wat::parse_str(WAT).unwrap();
+        let mut ctx = Context::default();
+        let frontend_config = WasmFrontendConfig::default();
+        ozk_wasm_dialect::register(&mut ctx);
+        ozk_ozk_dialect::register(&mut ctx);
+        frontend_config.register(&mut ctx);
+        let module_op =
+            ozk_frontend_wasm::parse_module(&mut ctx, &source, &frontend_config).unwrap();
+        let mut call_ops = Vec::new();
+        module_op.get_operation().walk_only::<wasm::CallOp>(
+            &ctx,
+            WalkOrder::PostOrder,
+            &mut |call_op| {
+                call_ops.push(*call_op);
+                WalkResult::Advance
+            },
+        );
+        call_ops[0].set_func_index(&mut ctx, 9.into());
+        assert_eq!(
+            unresolved_calls(&ctx, &module_op),
+            vec![UnresolvedCall {
+                caller: FuncSym::from("main"),
+                callee: "function index 9".to_string(),
+            }]
+        );
+        let err = WasmLinkCheckPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("function index 9 (called from main)"));
+    }
+}
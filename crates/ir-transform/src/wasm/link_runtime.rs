@@ -0,0 +1,324 @@
+//! Linking of the target runtime into the program. The runtime is the set of helper functions
+//! the passes call, written as a Wasm module in WAT and embedded in the backend crate. It is
+//! translated by the frontend into the program's context, so it's readable and can be tested
+//! like any Wasm module. The passes call a runtime function through an import from the
+//! [RUNTIME_IMPORT_MODULE] module and the linker replaces the import with the runtime's
+//! function of the same name (and the runtime functions it calls).
+
+use anyhow::anyhow;
+use ozk_frontend_wasm::WasmFrontendConfig;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::FuncIndex;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Import module name of the runtime functions
+pub const RUNTIME_IMPORT_MODULE: &str = "ozk_runtime";
+
+/// Replaces the imports from the [RUNTIME_IMPORT_MODULE] with the functions of the runtime
+/// module. The runtime functions may call each other and import the program's functions (e.g.
+/// the intrinsics) by name. They access the program's memory 0 and may not use globals.
+/// The frontend requires a start function, the runtime's one is linked only if it is called.
+/// Must run before the imports are checked.
+pub struct WasmLinkRuntimePass {
+    runtime_wat: &'static str,
+}
+
+impl WasmLinkRuntimePass {
+    /// `runtime_wat` is the runtime module in WAT
+    pub fn new(runtime_wat: &'static str) -> Self {
+        Self { runtime_wat }
+    }
+}
+
+impl Pass for WasmLinkRuntimePass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            self.link_runtime(ctx, &module_op)?;
+        }
+        Ok(())
+    }
+}
+
+impl WasmLinkRuntimePass {
+    fn link_runtime(
+        &self,
+        ctx: &mut Context,
+        module_op: &wasm::ModuleOp,
+    ) -> Result<(), anyhow::Error> {
+        let func_syms = module_op.get_func_syms(ctx);
+        let runtime_imports: Vec<FuncSym> = module_op
+            .get_import_func_modules(ctx)
+            .iter()
+            .zip(&func_syms)
+            .filter(|(module, _)| module.as_str() == RUNTIME_IMPORT_MODULE)
+            .map(|(_, func_sym)| func_sym.clone())
+            .collect();
+        if runtime_imports.is_empty() {
+            return Ok(());
+        }
+        let runtime_op = self.translate_runtime(ctx)?;
+        let runtime_syms = runtime_op.get_func_syms(ctx);
+        let runtime_import_count = runtime_op.get_import_func_types(ctx).len();
+
+        // the imported runtime functions and the ones they call
+        let mut linked_syms: Vec<FuncSym> = Vec::new();
+        let mut worklist = runtime_imports.clone();
+        while let Some(func_sym) = worklist.pop() {
+            if linked_syms.contains(&func_sym) {
+                continue;
+            }
+            let func_op = runtime_op
+                .get_func(ctx, &func_sym)
+                .ok_or_else(|| anyhow!("runtime function {} is not defined", func_sym.as_ref()))?;
+            for call_op in call_ops(ctx, &func_op) {
+                let index = usize::from(call_op.get_func_index(ctx));
+                if index >= runtime_import_count {
+                    worklist.push(runtime_syms[index].clone());
+                }
+            }
+            if uses_globals(ctx, &func_op) {
+                return Err(anyhow!(
+                    "runtime function {} uses globals",
+                    func_sym.as_ref()
+                ));
+            }
+            if module_op.get_func(ctx, &func_sym).is_some() {
+                return Err(anyhow!(
+                    "runtime function {} clashes with the program's function",
+                    func_sym.as_ref()
+                ));
+            }
+            linked_syms.push(func_sym);
+        }
+        linked_syms.sort_by_key(|func_sym| runtime_op.get_func_index(ctx, func_sym.clone()));
+
+        // runtime function index -> program function index
+        let mut mapping: Vec<Option<FuncIndex>> = vec![None; runtime_syms.len()];
+        for (index, func_sym) in runtime_syms[..runtime_import_count].iter().enumerate() {
+            let program_index =
+                module_op
+                    .get_func_index(ctx, func_sym.clone())
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "runtime import {} is not a function of the program",
+                            func_sym.as_ref()
+                        )
+                    })?;
+            mapping[index] = Some(program_index);
+        }
+        for (linked_index, func_sym) in linked_syms.iter().enumerate() {
+            if let Some(index) = runtime_op.get_func_index(ctx, func_sym.clone()) {
+                mapping[usize::from(index)] = Some(FuncIndex::from(func_syms.len() + linked_index));
+            }
+        }
+        let mut linked_funcs = Vec::new();
+        for func_sym in &linked_syms {
+            let Some(func_op) = runtime_op.get_func(ctx, func_sym) else {
+                continue;
+            };
+            for call_op in call_ops(ctx, &func_op) {
+                let index = usize::from(call_op.get_func_index(ctx));
+                if let Some(Some(program_index)) = mapping.get(index) {
+                    call_op.set_func_index(ctx, *program_index);
+                }
+            }
+            linked_funcs.push(func_op);
+        }
+        for func_op in linked_funcs {
+            func_op.get_operation().unlink(ctx);
+            module_op.append_function(ctx, func_op);
+        }
+
+        // call the linked functions instead of the imports and drop the imports
+        let linked_index = |func_sym: &FuncSym| {
+            linked_syms
+                .iter()
+                .position(|sym| sym == func_sym)
+                .map(|index| FuncIndex::from(func_syms.len() + index))
+        };
+        let import_indices: Vec<(FuncIndex, FuncIndex)> = runtime_imports
+            .iter()
+            .filter_map(|func_sym| {
+                let import_index = module_op.get_func_index(ctx, func_sym.clone())?;
+                Some((import_index, linked_index(func_sym)?))
+            })
+            .collect();
+        let redirected = |func_index: FuncIndex| {
+            import_indices
+                .iter()
+                .find(|(import_index, _)| *import_index == func_index)
+                .map_or(func_index, |(_, linked_index)| *linked_index)
+        };
+        let mut program_call_ops = Vec::new();
+        module_op.get_operation().walk_only::<wasm::CallOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |call_op| {
+                program_call_ops.push(*call_op);
+                WalkResult::Advance
+            },
+        );
+        for call_op in program_call_ops {
+            let func_index = call_op.get_func_index(ctx);
+            call_op.set_func_index(ctx, redirected(func_index));
+        }
+        let mut elem_segments = module_op.get_elem_segments(ctx);
+        for func_index in elem_segments
+            .iter_mut()
+            .flat_map(|seg| seg.func_indices.iter_mut())
+        {
+            *func_index = redirected(*func_index);
+        }
+        let table_sizes = module_op.get_table_sizes(ctx);
+        module_op.set_tables(ctx, table_sizes, elem_segments);
+        for func_sym in &runtime_imports {
+            module_op.remove_import(ctx, func_sym)?;
+        }
+        Ok(())
+    }
+
+    fn translate_runtime(&self, ctx: &mut Context) -> Result<wasm::ModuleOp, anyhow::Error> {
+        let wasm = wat::parse_str(self.runtime_wat)
+            .map_err(|err| anyhow!("runtime module parsing failed: {err}"))?;
+        let config = WasmFrontendConfig::default();
+        ozk_frontend_wasm::parse_module(ctx, &wasm, &config)
+            .map_err(|err| anyhow!("runtime module translation failed: {err}"))
+    }
+}
+
+fn call_ops(ctx: &Context, func_op: &wasm::FuncOp) -> Vec<wasm::CallOp> {
+    let mut call_ops = Vec::new();
+    func_op
+        .get_operation()
+        .walk_only::<wasm::CallOp>(ctx, WalkOrder::PostOrder, &mut |call_op| {
+            call_ops.push(*call_op);
+            WalkResult::Advance
+        });
+    call_ops
+}
+
+fn uses_globals(ctx: &Context, func_op: &wasm::FuncOp) -> bool {
+    let mut uses_globals = false;
+    func_op
+        .get_operation()
+        .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+            let opop = op.deref(ctx).get_op(ctx);
+            if opop.downcast_ref::<wasm::GlobalGetOp>().is_some()
+                || opop.downcast_ref::<wasm::GlobalSetOp>().is_some()
+            {
+                uses_globals = true;
+            }
+            WalkResult::Advance
+        });
+    uses_globals
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_pass;
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    const RUNTIME_WAT: &str = r#"
+(module
+    (start $start)
+    (func $start
+        return)
+    (func $add_self (param i32) (result i32)
+        local.get 0
+        local.get 0
+        i32.add
+        return)
+    (func $double (param i32) (result i32)
+        local.get 0
+        call $add_self
+        return)
+    (func $unused
+        return)
+)
+"#;
+
+    #[test]
+    fn runtime_import_linked() {
+        check_wasm_pass(
+            &WasmLinkRuntimePass::new(RUNTIME_WAT),
+            r#"
+(module
+    (import "ozk_runtime" "double" (func $double (param i32) (result i32)))
+    (start $main)
+    (func $main
+        i32.const 3
+        call $double
+        drop
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.const 0x3: si32
+                        wasm.call 2
+                        wasm.drop
+                        wasm.return
+                    }
+                    wasm.func @add_self(si32) -> (si32) {
+                      entry():
+                        wasm.local.get 0
+                        wasm.local.get 0
+                        wasm.i32.add
+                        wasm.return
+                    }
+                    wasm.func @double(si32) -> (si32) {
+                      entry():
+                        wasm.local.get 0
+                        wasm.call 1
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn runtime_function_undefined() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (import "ozk_runtime" "triple" (func $triple (param i32) (result i32)))
+    (start $main)
+    (func $main
+        i32.const 3
+        call $triple
+        drop
+        return)
+)
+"#,
+        );
+        let err = WasmLinkRuntimePass::new(RUNTIME_WAT)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("runtime function triple is not defined"),
+            "{err}"
+        );
+    }
+}
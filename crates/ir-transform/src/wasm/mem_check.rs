@@ -0,0 +1,355 @@
+//! Address-sanitizer-like debug instrumentation of the memory accesses. Out-of-bounds accesses
+//! don't trap on the targets (the Wasm memory is mapped onto a larger target memory), so
+//! a memory bug in the source program shows up only as a wrong output. With this pass every
+//! load and store checks that the accessed bytes are within the current memory size (see
+//! [crate::wasm::memory_size]) and otherwise writes [OUT_OF_BOUNDS_LOAD_CODE] or
+//! [OUT_OF_BOUNDS_STORE_CODE] followed by the address to the debug output and aborts.
+
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::i64_type;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::types::BinaryOpcode;
+use ozk_wasm_dialect::types::FuncIndex;
+use ozk_wasm_dialect::types::MemArg;
+use ozk_wasm_dialect::types::MemoryIndex;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+use crate::wasm::outline::append_function_with_body;
+
+/// Symbol of the function checking the bounds of a memory access
+pub const MEM_CHECK_FUNC_NAME: &str = "mem_check";
+/// Written to the debug output before aborting on an out-of-bounds load
+pub const OUT_OF_BOUNDS_LOAD_CODE: u32 = 0xa5a0_0001;
+/// Written to the debug output before aborting on an out-of-bounds store
+pub const OUT_OF_BOUNDS_STORE_CODE: u32 = 0xa5a0_0002;
+
+/// Inserts the bounds check before every load and store (a call to the [MEM_CHECK_FUNC_NAME]
+/// function) of the memory 0, the accesses of the other memories are not checked. Optional,
+/// for debugging. Emits `memory.size`, so it must run before it's lowered and before the
+/// memories are placed in the target memory.
+#[derive(Default)]
+pub struct WasmMemoryCheckPass;
+
+impl Pass for WasmMemoryCheckPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            instrument_module(ctx, &module_op);
+        }
+        Ok(())
+    }
+}
+
+/// A load or store to instrument
+struct MemAccess {
+    op: Ptr<Operation>,
+    /// The offset of the last accessed byte from the address on the stack
+    last_byte_offset: u32,
+    /// The type of the stored value, `None` for loads
+    store_value_type: Option<MemAccessOpValueType>,
+}
+
+fn instrument_module(ctx: &mut Context, module_op: &wasm::ModuleOp) {
+    let mut func_ops = Vec::new();
+    module_op.get_operation().walk_only::<wasm::FuncOp>(
+        ctx,
+        WalkOrder::PostOrder,
+        &mut |func_op| {
+            func_ops.push(*func_op);
+            WalkResult::Advance
+        },
+    );
+    let mut check_func_index = None;
+    for func_op in func_ops {
+        let accesses = collect_mem_accesses(ctx, &func_op);
+        if accesses.is_empty() {
+            continue;
+        }
+        let check_func_index =
+            *check_func_index.get_or_insert_with(|| insert_mem_check_func(ctx, module_op));
+        // the stored value is kept in a local while its address is checked
+        let mut i32_value_local = None;
+        let mut i64_value_local = None;
+        for access in accesses {
+            let code = if access.store_value_type.is_some() {
+                OUT_OF_BOUNDS_STORE_CODE
+            } else {
+                OUT_OF_BOUNDS_LOAD_CODE
+            };
+            let mut ops = vec![
+                wasm::ConstantOp::new_i32_unlinked(ctx, access.last_byte_offset as i32)
+                    .get_operation(),
+                wasm::ConstantOp::new_i32_unlinked(ctx, code as i32).get_operation(),
+                wasm::CallOp::new_unlinked(ctx, check_func_index).get_operation(),
+            ];
+            if let Some(value_type) = access.store_value_type {
+                let (value_local, ty) = match value_type {
+                    MemAccessOpValueType::I32 => (&mut i32_value_local, i32_type(ctx)),
+                    MemAccessOpValueType::I64 => (&mut i64_value_local, i64_type(ctx)),
+                };
+                let value_local = *value_local.get_or_insert_with(|| func_op.add_local(ctx, ty));
+                ops.insert(
+                    0,
+                    wasm::LocalSetOp::new_unlinked(ctx, value_local).get_operation(),
+                );
+                ops.push(wasm::LocalGetOp::new_unlinked(ctx, value_local).get_operation());
+            }
+            for op in ops {
+                op.insert_before(ctx, access.op);
+            }
+        }
+    }
+}
+
+fn collect_mem_accesses(ctx: &Context, func_op: &wasm::FuncOp) -> Vec<MemAccess> {
+    let mut accesses = Vec::new();
+    func_op
+        .get_operation()
+        .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+            let opop = op.deref(ctx).get_op(ctx);
+            let access = if let Some(load_op) = opop.downcast_ref::<wasm::LoadOp>() {
+                let width = load_op.get_value_type(ctx).width_bytes();
+                Some((load_op.get_memarg(ctx), width, None))
+            } else if let Some(store_op) = opop.downcast_ref::<wasm::StoreOp>() {
+                let value_type = store_op.get_value_type(ctx);
+                Some((
+                    store_op.get_memarg(ctx),
+                    value_type.width_bytes(),
+                    Some(value_type),
+                ))
+            } else {
+                partial_load_memarg(ctx, op).map(|(memarg, width)| (memarg, width, None))
+            };
+            // only the memory 0 has its size tracked (see [crate::wasm::multi_memory])
+            if let Some((memarg, width, store_value_type)) =
+                access.filter(|(memarg, _, _)| u32::from(memarg.memory) == 0)
+            {
+                accesses.push(MemAccess {
+                    op,
+                    last_byte_offset: last_byte_offset(memarg, width),
+                    store_value_type,
+                });
+            }
+            WalkResult::Advance
+        });
+    accesses
+}
+
+/// The memarg and the width of a partial-width load
+fn partial_load_memarg(ctx: &Context, op: Ptr<Operation>) -> Option<(MemArg, u32)> {
+    let opop = op.deref(ctx).get_op(ctx);
+    macro_rules! match_partial_loads {
+        ($($op:ident),*) => {
+            $(
+                if let Some(load_op) = opop.downcast_ref::<wasm::$op>() {
+                    return Some((load_op.get_memarg(ctx), wasm::$op::WIDTH_BYTES));
+                }
+            )*
+        };
+    }
+    match_partial_loads!(
+        I32Load8SOp,
+        I32Load8UOp,
+        I32Load16SOp,
+        I32Load16UOp,
+        I64Load8SOp,
+        I64Load8UOp,
+        I64Load16SOp,
+        I64Load16UOp,
+        I64Load32SOp,
+        I64Load32UOp
+    );
+    None
+}
+
+/// The offset of the last accessed byte. Saturates, since no memory can hold such an access
+/// (except the full 4 GiB one, where every address is in bounds anyway).
+fn last_byte_offset(memarg: MemArg, width: u32) -> u32 {
+    (memarg.offset as u64 + width as u64 - 1).min(u32::MAX as u64) as u32
+}
+
+/// The ops pushing the address of the last byte of the memory (wraps to `u32::MAX` for the
+/// 4 GiB memory, the empty memory is checked separately)
+fn last_memory_address_ops(ctx: &mut Context) -> Vec<Ptr<Operation>> {
+    vec![
+        wasm::MemorySizeOp::new_unlinked(ctx, MemoryIndex::from(0)).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 16).get_operation(),
+        wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Shl).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, 1).get_operation(),
+        wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Sub).get_operation(),
+    ]
+}
+
+/// Appends the function that takes the address, the offset of the last accessed byte and the
+/// diagnostic code, aborts if the access is out of bounds and returns the address otherwise.
+fn insert_mem_check_func(ctx: &mut Context, module_op: &wasm::ModuleOp) -> FuncIndex {
+    let i32_ty = i32_type(ctx);
+    let mut ops = vec![
+        // no memory
+        wasm::MemorySizeOp::new_unlinked(ctx, MemoryIndex::from(0)).get_operation(),
+        wasm::I32EqzOp::new_unlinked(ctx).get_operation(),
+        // the access is larger than the memory
+        wasm::LocalGetOp::new_unlinked(ctx, 1).get_operation(),
+    ];
+    ops.extend(last_memory_address_ops(ctx));
+    ops.extend([
+        wasm::I32GtUOp::new_unlinked(ctx).get_operation(),
+        wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Or).get_operation(),
+        // address + last byte offset > last memory address, without the overflow
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+    ]);
+    ops.extend(last_memory_address_ops(ctx));
+    ops.extend([
+        wasm::LocalGetOp::new_unlinked(ctx, 1).get_operation(),
+        wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Sub).get_operation(),
+        wasm::I32GtUOp::new_unlinked(ctx).get_operation(),
+        wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Or).get_operation(),
+    ]);
+    let if_type = FunctionType::get(ctx, vec![], vec![]);
+    let if_op = wasm::IfOp::new_unlinked(ctx, if_type);
+    let abort_ops = vec![
+        wasm::LocalGetOp::new_unlinked(ctx, 2).get_operation(),
+        ozk::DebugPrintOp::new_unlinked(ctx).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+        ozk::DebugPrintOp::new_unlinked(ctx).get_operation(),
+        ozk::TrapOp::new_unlinked(ctx).get_operation(),
+    ];
+    for op in abort_ops {
+        op.insert_at_back(if_op.get_then_block(ctx), ctx);
+    }
+    ops.push(if_op.get_operation());
+    ops.push(wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation());
+    ops.push(wasm::ReturnOp::new_unlinked(ctx).get_operation());
+    let ty = FunctionType::get(ctx, vec![i32_ty, i32_ty, i32_ty], vec![i32_ty]);
+    append_function_with_body(ctx, module_op, MEM_CHECK_FUNC_NAME, ty, ops).1
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use ozk_ozk_dialect::types::FuncSym;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    #[test]
+    fn load_and_store_checks() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 8
+        i64.const 42
+        i64.store offset=4
+        i32.const 16
+        i32.load8_u
+        drop
+        return)
+)
+"#,
+        );
+        WasmMemoryCheckPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        assert_eq!(main_func.get_locals(&ctx).len(), 1);
+        expect![[r#"
+            wasm.module @module_name {
+              block_1_0():
+                wasm.func @main() -> () {
+                  // locals: [0: si64]
+                  entry():
+                    wasm.const 0x8: si32
+                    wasm.const 0x2a: si64
+                    wasm.local.set 0
+                    wasm.const 0xb: si32
+                    wasm.const 0xa5a00002: si32
+                    wasm.call 1
+                    wasm.local.get 0
+                    wasm.store I64 offset=4
+                    wasm.const 0x10: si32
+                    wasm.const 0x0: si32
+                    wasm.const 0xa5a00001: si32
+                    wasm.call 1
+                    wasm.i32.load8_u
+                    wasm.drop
+                    wasm.return
+                }
+                wasm.func @mem_check(si32, si32, si32) -> (si32) {
+                  entry():
+                    wasm.memory.size 0
+                    wasm.i32.eqz
+                    wasm.local.get 1
+                    wasm.memory.size 0
+                    wasm.const 0x10: si32
+                    wasm.i32.shl
+                    wasm.const 0x1: si32
+                    wasm.i32.sub
+                    wasm.i32.gt_u
+                    wasm.i32.or
+                    wasm.local.get 0
+                    wasm.memory.size 0
+                    wasm.const 0x10: si32
+                    wasm.i32.shl
+                    wasm.const 0x1: si32
+                    wasm.i32.sub
+                    wasm.local.get 1
+                    wasm.i32.sub
+                    wasm.i32.gt_u
+                    wasm.i32.or
+                    wasm.if () -> () {
+                      then():
+                        wasm.local.get 2
+                        ozk.debug_print
+                        wasm.local.get 0
+                        ozk.debug_print
+                        ozk.trap
+                    } else {
+                      else():
+                    }
+                    wasm.local.get 0
+                    wasm.return
+                }
+            }"#]]
+        .assert_eq(&module_op.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn no_accesses_no_check_func() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        return)
+)
+"#,
+        );
+        WasmMemoryCheckPass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        assert!(module_op
+            .get_func(&ctx, &FuncSym::from(MEM_CHECK_FUNC_NAME))
+            .is_none());
+    }
+}
@@ -0,0 +1,372 @@
+//! Lowering of `memory.size` and `memory.grow`. The linear memory of the targets is fixed,
+//! so only the size reported to the program is tracked (in pages) in the [MEMORY_SIZE_SLOT]
+//! reserved slot. It starts at the memory's minimum and `memory.grow` increases it up to the
+//! memory's maximum (or [MAX_WASM32_PAGES]), capped at the pages the target reserves for the
+//! memory.
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::BinaryOpcode;
+use ozk_wasm_dialect::types::FuncIndex;
+use ozk_wasm_dialect::types::MemoryLimits;
+use ozk_wasm_dialect::types::PrologueStage;
+use ozk_wasm_dialect::types::MAX_WASM32_PAGES;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+use super::reserved_slots::MEMORY_SIZE_SLOT;
+
+/// Symbol of the function that sets the initial memory size (a prologue function)
+pub const INIT_MEMORY_SIZE_FUNC_NAME: &str = "init_memory_size";
+/// Symbol of the function implementing `memory.grow`
+pub const MEMORY_GROW_FUNC_NAME: &str = "memory_grow";
+
+/// Replaces `memory.size` with the read of the [MEMORY_SIZE_SLOT] and `memory.grow` with the
+/// call to the [MEMORY_GROW_FUNC_NAME] function. Adds the [INIT_MEMORY_SIZE_FUNC_NAME]
+/// prologue function, so it must run before the prologue is emitted.
+pub struct WasmMemorySizeToSlotPass {
+    /// The number of pages the target memory layout reserves for the memory
+    max_pages: u32,
+}
+
+impl WasmMemorySizeToSlotPass {
+    /// `max_pages` is the number of pages the target memory layout reserves for the memory,
+    /// `memory.grow` fails (returns -1) beyond it
+    pub fn new(max_pages: u32) -> Self {
+        Self { max_pages }
+    }
+}
+
+impl Default for WasmMemorySizeToSlotPass {
+    fn default() -> Self {
+        Self::new(MAX_WASM32_PAGES)
+    }
+}
+
+impl Pass for WasmMemorySizeToSlotPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            lower_memory_size_ops(ctx, &module_op, self.max_pages)?;
+        }
+        Ok(())
+    }
+}
+
+fn lower_memory_size_ops(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    max_pages: u32,
+) -> Result<(), anyhow::Error> {
+    let mut size_ops = Vec::new();
+    let mut grow_ops = Vec::new();
+    module_op
+        .get_operation()
+        .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+            let opop = op.deref(ctx).get_op(ctx);
+            if let Some(size_op) = opop.downcast_ref::<wasm::MemorySizeOp>() {
+                size_ops.push(*size_op);
+            } else if let Some(grow_op) = opop.downcast_ref::<wasm::MemoryGrowOp>() {
+                grow_ops.push(*grow_op);
+            }
+            WalkResult::Advance
+        });
+    if size_ops.is_empty() && grow_ops.is_empty() {
+        return Ok(());
+    }
+    let memory_indices = size_ops
+        .iter()
+        .map(|op| op.get_memory_index(ctx))
+        .chain(grow_ops.iter().map(|op| op.get_memory_index(ctx)));
+    for memory_index in memory_indices {
+        if u32::from(memory_index) != 0 {
+            return Err(anyhow!(
+                "only the memory 0 is supported, got {memory_index}"
+            ));
+        }
+    }
+    let limits = module_op
+        .get_memory_limits(ctx)
+        .first()
+        .copied()
+        .ok_or_else(|| anyhow!("memory.size/memory.grow without a declared memory"))?;
+    if limits.minimum > max_pages {
+        return Err(anyhow!(
+            "the memory of {} pages does not fit into the {max_pages} pages reserved by the target",
+            limits.minimum
+        ));
+    }
+    for size_op in size_ops {
+        let op = size_op.get_operation();
+        ozk::ReservedGetOp::new_unlinked(ctx, MEMORY_SIZE_SLOT)
+            .get_operation()
+            .insert_before(ctx, op);
+        op.unlink(ctx);
+    }
+    if !grow_ops.is_empty() {
+        let grow_func_index =
+            insert_memory_grow_func(ctx, module_op, limits.maximum_or_default().min(max_pages));
+        for grow_op in grow_ops {
+            let op = grow_op.get_operation();
+            wasm::CallOp::new_unlinked(ctx, grow_func_index)
+                .get_operation()
+                .insert_before(ctx, op);
+            op.unlink(ctx);
+        }
+    }
+    insert_init_memory_size_func(ctx, module_op, limits);
+    Ok(())
+}
+
+/// Adds the prologue function that sets the memory size to the memory's minimum
+fn insert_init_memory_size_func(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    limits: MemoryLimits,
+) {
+    let entry_block = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+    let ops = vec![
+        wasm::ConstantOp::new_i32_unlinked(ctx, limits.minimum as i32).get_operation(),
+        ozk::ReservedSetOp::new_unlinked(ctx, MEMORY_SIZE_SLOT).get_operation(),
+        wasm::ReturnOp::new_unlinked(ctx).get_operation(),
+    ];
+    for op in ops {
+        op.insert_at_back(entry_block, ctx);
+    }
+    let ty = FunctionType::get(ctx, vec![], vec![]);
+    let func_op = wasm::FuncOp::new_unlinked_with_block(
+        ctx,
+        FuncSym::from(INIT_MEMORY_SIZE_FUNC_NAME),
+        ty,
+        entry_block,
+        vec![],
+    );
+    module_op.add_prologue_function(ctx, func_op, PrologueStage::MemoryInit);
+}
+
+/// Appends the function that takes the number of pages to grow the memory by and returns
+/// the previous size, or -1 (without changing the size) if the size would exceed `max_pages`.
+fn insert_memory_grow_func(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    max_pages: u32,
+) -> FuncIndex {
+    let i32_ty = i32_type(ctx);
+    let entry_block = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+    // delta > maximum - size (the size never exceeds the maximum)
+    let mut ops = vec![
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+        wasm::ConstantOp::new_i32_unlinked(ctx, max_pages as i32).get_operation(),
+        ozk::ReservedGetOp::new_unlinked(ctx, MEMORY_SIZE_SLOT).get_operation(),
+        wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Sub).get_operation(),
+        wasm::I32GtUOp::new_unlinked(ctx).get_operation(),
+    ];
+    let if_type = FunctionType::get(ctx, vec![], vec![i32_ty]);
+    let if_op = wasm::IfOp::new_unlinked(ctx, if_type);
+    wasm::ConstantOp::new_i32_unlinked(ctx, -1)
+        .get_operation()
+        .insert_at_back(if_op.get_then_block(ctx), ctx);
+    let else_ops = vec![
+        ozk::ReservedGetOp::new_unlinked(ctx, MEMORY_SIZE_SLOT).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+        wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Add).get_operation(),
+        ozk::ReservedSetOp::new_unlinked(ctx, MEMORY_SIZE_SLOT).get_operation(),
+        // the previous size
+        ozk::ReservedGetOp::new_unlinked(ctx, MEMORY_SIZE_SLOT).get_operation(),
+        wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+        wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Sub).get_operation(),
+    ];
+    for op in else_ops {
+        op.insert_at_back(if_op.get_else_block(ctx), ctx);
+    }
+    ops.push(if_op.get_operation());
+    ops.push(wasm::ReturnOp::new_unlinked(ctx).get_operation());
+    for op in ops {
+        op.insert_at_back(entry_block, ctx);
+    }
+    let ty = FunctionType::get(ctx, vec![i32_ty], vec![i32_ty]);
+    let func_op = wasm::FuncOp::new_unlinked_with_block(
+        ctx,
+        FuncSym::from(MEMORY_GROW_FUNC_NAME),
+        ty,
+        entry_block,
+        vec![],
+    );
+    module_op.append_function(ctx, func_op)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use ozk_wasm_dialect::types::MemoryIndex;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    #[test]
+    fn memory_size_and_grow() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (memory 2 16)
+    (start $main)
+    (func $main
+        i32.const 1
+        memory.grow
+        drop
+        memory.size
+        drop
+        return)
+)
+"#,
+        );
+        WasmMemorySizeToSlotPass::default()
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        assert_eq!(
+            module_op.get_prologue_functions(&ctx),
+            vec![FuncSym::from(INIT_MEMORY_SIZE_FUNC_NAME)]
+        );
+        expect![[r#"
+            wasm.module @module_name {
+              block_1_0():
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.const 0x1: si32
+                    wasm.call 1
+                    wasm.drop
+                    ozk.reserved_get memory_size
+                    wasm.drop
+                    wasm.return
+                }
+                wasm.func @memory_grow(si32) -> (si32) {
+                  entry():
+                    wasm.local.get 0
+                    wasm.const 0x10: si32
+                    ozk.reserved_get memory_size
+                    wasm.i32.sub
+                    wasm.i32.gt_u
+                    wasm.if () -> (si32) {
+                      then():
+                        wasm.const 0xffffffff: si32
+                    } else {
+                      else():
+                        ozk.reserved_get memory_size
+                        wasm.local.get 0
+                        wasm.i32.add
+                        ozk.reserved_set memory_size
+                        ozk.reserved_get memory_size
+                        wasm.local.get 0
+                        wasm.i32.sub
+                    }
+                    wasm.return
+                }
+                wasm.func @init_memory_size() -> () {
+                  entry():
+                    wasm.const 0x2: si32
+                    ozk.reserved_set memory_size
+                    wasm.return
+                }
+            }"#]]
+        .assert_eq(&module_op.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn memory_size_without_memory() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (start $main)
+    (func $main
+        return)
+)
+"#,
+        );
+        // a module without a memory can't have memory ops, so put one in
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        let return_op = main_func.op_iter(&ctx).next().unwrap();
+        wasm::MemorySizeOp::new_unlinked(&mut ctx, MemoryIndex::from(0))
+            .get_operation()
+            .insert_before(&mut ctx, return_op);
+        let err = WasmMemorySizeToSlotPass::default()
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("without a declared memory"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn memory_grow_capped_at_reserved_pages() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (memory 2)
+    (start $main)
+    (func $main
+        i32.const 1
+        memory.grow
+        drop
+        return)
+)
+"#,
+        );
+        WasmMemorySizeToSlotPass::new(4)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        let grow_func = module_op
+            .get_func(&ctx, &FuncSym::from(MEMORY_GROW_FUNC_NAME))
+            .unwrap();
+        let max_pages_op = grow_func.op_iter(&ctx).nth(1).unwrap();
+        let max_pages = max_pages_op
+            .deref(&ctx)
+            .get_op(&ctx)
+            .downcast_ref::<wasm::ConstantOp>()
+            .unwrap()
+            .get_i64(&ctx)
+            .unwrap();
+        assert_eq!(max_pages, 4);
+    }
+
+    #[test]
+    fn memory_larger_than_reserved_pages() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (memory 8)
+    (start $main)
+    (func $main
+        memory.size
+        drop
+        return)
+)
+"#,
+        );
+        let err = WasmMemorySizeToSlotPass::new(4)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("does not fit into the 4 pages"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Placement of the multiple linear memories (the multi-memory proposal) in the target memory.
+//! The targets have a single memory, so the memories other than 0 are mapped at the addresses
+//! provided by the target's memory layout and their loads and stores become the accesses of
+//! the memory 0 with the offset moved by the memory's address.
+
+use anyhow::anyhow;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::MemAddress;
+use ozk_wasm_dialect::types::MemArg;
+use ozk_wasm_dialect::types::MemoryIndex;
+use ozk_wasm_dialect::types::WASM_PAGE_SIZE;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// The distance between the memories in the default layout (256 MiB)
+pub const DEFAULT_MEMORY_SPACING: u32 = 0x1000_0000;
+
+/// The default addresses of the memories 1 to 7, [DEFAULT_MEMORY_SPACING] apart
+pub fn default_memory_addresses() -> Vec<MemAddress> {
+    (1..8)
+        .map(|index| MemAddress::from(index * DEFAULT_MEMORY_SPACING))
+        .collect()
+}
+
+/// Moves the loads and stores of the memories other than 0 to their addresses in the target
+/// memory, leaving the module with the memory 0 only. Fails if a declared memory has no
+/// address, if the memories (by their minimum size) overlap or if `memory.init` targets
+/// a memory other than 0 (`memory.size` and `memory.grow` of such memories are rejected when
+/// lowered, see [super::memory_size]). Must run before the partial loads are lowered.
+pub struct WasmMultiMemoryPass {
+    memory_addresses: Vec<MemAddress>,
+}
+
+impl WasmMultiMemoryPass {
+    /// `memory_addresses` are the addresses of the memories 1, 2, etc. (the memory 0 stays
+    /// at 0)
+    pub fn new(memory_addresses: Vec<MemAddress>) -> Self {
+        Self { memory_addresses }
+    }
+}
+
+impl Default for WasmMultiMemoryPass {
+    fn default() -> Self {
+        Self::new(default_memory_addresses())
+    }
+}
+
+impl Pass for WasmMultiMemoryPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            self.place_memories(ctx, &module_op)?;
+        }
+        Ok(())
+    }
+}
+
+impl WasmMultiMemoryPass {
+    fn place_memories(
+        &self,
+        ctx: &mut Context,
+        module_op: &wasm::ModuleOp,
+    ) -> Result<(), anyhow::Error> {
+        let memory_limits = module_op.get_memory_limits(ctx);
+        if memory_limits.len() <= 1 {
+            return Ok(());
+        }
+        let mut memory_addresses = vec![0];
+        for index in 1..memory_limits.len() {
+            let address = self
+                .memory_addresses
+                .get(index - 1)
+                .ok_or_else(|| anyhow!("memory {index} has no address in the target memory"))?;
+            memory_addresses.push(u32::from(*address));
+        }
+        // (start, end, memory index) of the memories in the target memory
+        let mut ranges: Vec<(u64, u64, usize)> = memory_addresses
+            .iter()
+            .zip(&memory_limits)
+            .enumerate()
+            .map(|(index, (address, limits))| {
+                let start = *address as u64;
+                (
+                    start,
+                    start + limits.minimum as u64 * WASM_PAGE_SIZE as u64,
+                    index,
+                )
+            })
+            .collect();
+        ranges.sort();
+        for pair in ranges.windows(2) {
+            if pair[0].1 > pair[1].0 {
+                return Err(anyhow!(
+                    "memories {} and {} overlap in the target memory",
+                    pair[0].2,
+                    pair[1].2
+                ));
+            }
+        }
+
+        let mut access_ops = Vec::new();
+        let mut init_memories = Vec::new();
+        module_op
+            .get_operation()
+            .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+                if let Some(memarg) = memory_access_memarg(ctx, op) {
+                    if u32::from(memarg.memory) != 0 {
+                        access_ops.push((op, memarg));
+                    }
+                } else if let Some(init_op) = op
+                    .deref(ctx)
+                    .get_op(ctx)
+                    .downcast_ref::<wasm::MemoryInitOp>()
+                {
+                    init_memories.push(init_op.get_memory_index(ctx));
+                }
+                WalkResult::Advance
+            });
+        if let Some(memory) = init_memories.iter().find(|memory| u32::from(**memory) != 0) {
+            return Err(anyhow!(
+                "memory.init of the memory {memory}, only the memory 0 is supported"
+            ));
+        }
+        for (op, memarg) in access_ops {
+            let memory = u32::from(memarg.memory);
+            let address = memory_addresses
+                .get(memory as usize)
+                .ok_or_else(|| anyhow!("access to the undeclared memory {memory}"))?;
+            let offset = memarg.offset.checked_add(*address).ok_or_else(|| {
+                anyhow!(
+                    "offset {} of the memory {memory} access is out of the target memory",
+                    memarg.offset
+                )
+            })?;
+            set_memory_access_memarg(
+                ctx,
+                op,
+                MemArg {
+                    offset,
+                    memory: MemoryIndex::from(0),
+                    ..memarg
+                },
+            );
+        }
+        module_op.set_memory_limits(ctx, vec![memory_limits[0]]);
+        Ok(())
+    }
+}
+
+macro_rules! for_memory_access_ops {
+    ($m:ident) => {
+        $m!(
+            LoadOp,
+            StoreOp,
+            I32Load8SOp,
+            I32Load8UOp,
+            I32Load16SOp,
+            I32Load16UOp,
+            I64Load8SOp,
+            I64Load8UOp,
+            I64Load16SOp,
+            I64Load16UOp,
+            I64Load32SOp,
+            I64Load32UOp
+        )
+    };
+}
+
+/// The memarg of a load or store, `None` for the other ops
+fn memory_access_memarg(ctx: &Context, op: Ptr<Operation>) -> Option<MemArg> {
+    let opop = op.deref(ctx).get_op(ctx);
+    macro_rules! match_memory_accesses {
+        ($($op:ident),*) => {
+            $(
+                if let Some(access_op) = opop.downcast_ref::<wasm::$op>() {
+                    return Some(access_op.get_memarg(ctx));
+                }
+            )*
+        };
+    }
+    for_memory_access_ops!(match_memory_accesses);
+    None
+}
+
+fn set_memory_access_memarg(ctx: &mut Context, op: Ptr<Operation>, memarg: MemArg) {
+    let opop = op.deref(ctx).get_op(ctx);
+    macro_rules! match_memory_accesses {
+        ($($op:ident),*) => {
+            $(
+                if let Some(access_op) = opop.downcast_ref::<wasm::$op>() {
+                    access_op.set_memarg(ctx, memarg);
+                    return;
+                }
+            )*
+        };
+    }
+    for_memory_access_ops!(match_memory_accesses);
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    #[test]
+    fn memories_moved_to_addresses() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (memory $main 1)
+    (memory $scratch 2)
+    (start $main)
+    (func $main
+        i32.const 4
+        i32.load $scratch offset=8
+        i32.const 0
+        i32.load8_u $main
+        i32.add
+        i32.store $scratch
+        return)
+)
+"#,
+        );
+        WasmMultiMemoryPass::new(vec![MemAddress::from(0x10_0000)])
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        assert_eq!(module_op.get_memory_limits(&ctx).len(), 1);
+        expect![[r#"
+            wasm.module @module_name {
+              block_1_0():
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.const 0x4: si32
+                    wasm.load I32 offset=1048584
+                    wasm.const 0x0: si32
+                    wasm.i32.load8_u
+                    wasm.i32.add
+                    wasm.store I32 offset=1048576
+                    wasm.return
+                }
+            }"#]]
+        .assert_eq(&module_op.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn overlapping_memories() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (memory 2)
+    (memory 1)
+    (start $main)
+    (func $main
+        return)
+)
+"#,
+        );
+        let err = WasmMultiMemoryPass::new(vec![MemAddress::from(0x1_0000)])
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "memories 0 and 1 overlap in the target memory"
+        );
+    }
+}
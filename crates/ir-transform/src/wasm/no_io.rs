@@ -0,0 +1,292 @@
+//! The no-io mode: the program's result is committed with a digest instead of the explicit
+//! public outputs. The program designates the bytes to commit with `ozk_stdlib::commit()`,
+//! they are absorbed into the digest state (in the target memory outside of the Wasm memory)
+//! by the target runtime (see [super::link_runtime]) and at the exit of the start function the
+//! digest is written as the only public output.
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::i64_type;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::MemAddress;
+use ozk_wasm_dialect::types::PrologueStage;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+use super::foreign_imports::ENV_IMPORT_MODULE;
+use super::link_runtime::RUNTIME_IMPORT_MODULE;
+
+/// Import name of the commit intrinsic (`ozk_stdlib::commit()`)
+pub const COMMIT_INTRINSIC_NAME: &str = "ozk_stdlib_commit";
+/// Import name of the public output (`ozk_stdlib::pub_output()`)
+pub const PUB_OUTPUT_FUNC_NAME: &str = "ozk_stdlib_pub_output";
+/// Symbol of the function that initializes the digest state (a prologue function)
+pub const INIT_DIGEST_FUNC_NAME: &str = "init_digest";
+/// Symbol of the runtime function that sets the digest state to the initial value
+pub const DIGEST_INIT_FUNC_NAME: &str = "digest_init";
+/// Symbol of the runtime function that absorbs the bytes into the digest state
+pub const DIGEST_UPDATE_FUNC_NAME: &str = "digest_update";
+/// Symbol of the runtime function that returns the digest
+pub const DIGEST_FINAL_FUNC_NAME: &str = "digest_final";
+
+/// Turns the program into a no-io one: replaces the calls to [COMMIT_INTRINSIC_NAME] (the
+/// address and the number of bytes) with the calls to the [DIGEST_UPDATE_FUNC_NAME] runtime
+/// function and writes the digest with [PUB_OUTPUT_FUNC_NAME] before every return of the start
+/// function. The bytes are absorbed when committed, so the later changes of the committed
+/// memory are not in the digest. Fails if the program writes the public outputs itself.
+/// The runtime functions (imported from [RUNTIME_IMPORT_MODULE]) take the address of the
+/// digest state (8 bytes). Adds the [INIT_DIGEST_FUNC_NAME] prologue function, so it must run
+/// before the runtime is linked and the prologue is emitted.
+pub struct WasmNoIoPass {
+    digest_addr: MemAddress,
+}
+
+impl WasmNoIoPass {
+    /// `digest_addr` is the address of the digest state in the target memory
+    pub fn new(digest_addr: MemAddress) -> Self {
+        Self { digest_addr }
+    }
+}
+
+impl Pass for WasmNoIoPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            self.commit_digest(ctx, &module_op)?;
+        }
+        Ok(())
+    }
+}
+
+impl WasmNoIoPass {
+    fn commit_digest(
+        &self,
+        ctx: &mut Context,
+        module_op: &wasm::ModuleOp,
+    ) -> Result<(), anyhow::Error> {
+        let start_func_sym = module_op
+            .try_get_start_func_sym(ctx)
+            .ok_or_else(|| anyhow!("no-io program requires a start function"))?;
+        let start_func = module_op
+            .get_func(ctx, &start_func_sym)
+            .ok_or_else(|| anyhow!("start function {start_func_sym:?} is not defined"))?;
+        let commit_index = module_op.get_func_index(ctx, FuncSym::from(COMMIT_INTRINSIC_NAME));
+        let pub_output_index = module_op.get_func_index(ctx, FuncSym::from(PUB_OUTPUT_FUNC_NAME));
+        let mut commit_ops = Vec::new();
+        let mut pub_output_called = false;
+        module_op.get_operation().walk_only::<wasm::CallOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |call_op| {
+                let func_index = Some(call_op.get_func_index(ctx));
+                if func_index == commit_index {
+                    commit_ops.push(*call_op);
+                } else if func_index == pub_output_index {
+                    pub_output_called = true;
+                }
+                WalkResult::Advance
+            },
+        );
+        if pub_output_called {
+            return Err(anyhow!(
+                "no-io program writes the public outputs ({PUB_OUTPUT_FUNC_NAME}), its only \
+                 public output is the digest of the committed bytes"
+            ));
+        }
+
+        let i32_ty = i32_type(ctx);
+        let i64_ty = i64_type(ctx);
+        // before the runtime imports, appending an import shifts the indices of the functions
+        let pub_output_index = match pub_output_index {
+            Some(func_index) => func_index,
+            None => {
+                let pub_output_ty = FunctionType::get(ctx, vec![i64_ty], vec![]);
+                module_op.append_import(
+                    ctx,
+                    FuncSym::from(PUB_OUTPUT_FUNC_NAME),
+                    pub_output_ty,
+                    ENV_IMPORT_MODULE,
+                )
+            }
+        };
+        // provided by the target runtime
+        let digest_init_ty = FunctionType::get(ctx, vec![i32_ty], vec![]);
+        let digest_init_index = module_op.append_import(
+            ctx,
+            FuncSym::from(DIGEST_INIT_FUNC_NAME),
+            digest_init_ty,
+            RUNTIME_IMPORT_MODULE,
+        );
+        let digest_update_ty = FunctionType::get(ctx, vec![i32_ty; 3], vec![]);
+        let digest_update_index = module_op.append_import(
+            ctx,
+            FuncSym::from(DIGEST_UPDATE_FUNC_NAME),
+            digest_update_ty,
+            RUNTIME_IMPORT_MODULE,
+        );
+        let digest_final_ty = FunctionType::get(ctx, vec![i32_ty], vec![i64_ty]);
+        let digest_final_index = module_op.append_import(
+            ctx,
+            FuncSym::from(DIGEST_FINAL_FUNC_NAME),
+            digest_final_ty,
+            RUNTIME_IMPORT_MODULE,
+        );
+
+        let digest_addr = u32::from(self.digest_addr) as i32;
+        for commit_op in commit_ops {
+            for op in [
+                wasm::ConstantOp::new_i32_unlinked(ctx, digest_addr).get_operation(),
+                wasm::CallOp::new_unlinked(ctx, digest_update_index).get_operation(),
+            ] {
+                op.insert_before(ctx, commit_op.get_operation());
+            }
+            commit_op.get_operation().unlink(ctx);
+        }
+
+        let mut return_ops = Vec::new();
+        start_func.get_operation().walk_only::<wasm::ReturnOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |return_op| {
+                return_ops.push(*return_op);
+                WalkResult::Advance
+            },
+        );
+        for return_op in return_ops {
+            for op in [
+                wasm::ConstantOp::new_i32_unlinked(ctx, digest_addr).get_operation(),
+                wasm::CallOp::new_unlinked(ctx, digest_final_index).get_operation(),
+                wasm::CallOp::new_unlinked(ctx, pub_output_index).get_operation(),
+            ] {
+                op.insert_before(ctx, return_op.get_operation());
+            }
+        }
+
+        let entry_block = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+        for op in [
+            wasm::ConstantOp::new_i32_unlinked(ctx, digest_addr).get_operation(),
+            wasm::CallOp::new_unlinked(ctx, digest_init_index).get_operation(),
+            wasm::ReturnOp::new_unlinked(ctx).get_operation(),
+        ] {
+            op.insert_at_back(entry_block, ctx);
+        }
+        let ty = FunctionType::get(ctx, vec![], vec![]);
+        let func_op = wasm::FuncOp::new_unlinked_with_block(
+            ctx,
+            FuncSym::from(INIT_DIGEST_FUNC_NAME),
+            ty,
+            entry_block,
+            vec![],
+        );
+        module_op.add_prologue_function(ctx, func_op, PrologueStage::User);
+
+        if commit_index.is_some() {
+            module_op.remove_import(ctx, &FuncSym::from(COMMIT_INTRINSIC_NAME))?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    #[test]
+    fn commit_to_digest() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (import "env" "ozk_stdlib_commit" (func $commit (param i32 i32)))
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 16
+        i32.const 8
+        call $commit
+        return)
+)
+"#,
+        );
+        WasmNoIoPass::new(MemAddress::from(0x1000))
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        assert_eq!(
+            module_op.get_import_func_modules(&ctx),
+            vec![
+                ENV_IMPORT_MODULE.to_string(),
+                RUNTIME_IMPORT_MODULE.to_string(),
+                RUNTIME_IMPORT_MODULE.to_string(),
+                RUNTIME_IMPORT_MODULE.to_string(),
+            ]
+        );
+        assert_eq!(
+            module_op.get_prologue_functions(&ctx),
+            vec![FuncSym::from(INIT_DIGEST_FUNC_NAME)]
+        );
+        expect![[r#"
+            wasm.module @module_name {
+              block_1_0():
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.const 0x10: si32
+                    wasm.const 0x8: si32
+                    wasm.const 0x1000: si32
+                    wasm.call 2
+                    wasm.const 0x1000: si32
+                    wasm.call 3
+                    wasm.call 0
+                    wasm.return
+                }
+                wasm.func @init_digest() -> () {
+                  entry():
+                    wasm.const 0x1000: si32
+                    wasm.call 1
+                    wasm.return
+                }
+            }"#]]
+        .assert_eq(&module_op.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn pub_output_not_allowed() {
+        let (mut ctx, module_op) = parse_wasm_module(
+            r#"
+(module
+    (import "env" "ozk_stdlib_pub_output" (func $pub_output (param i64)))
+    (start $main)
+    (func $main
+        i64.const 1
+        call $pub_output
+        return)
+)
+"#,
+        );
+        let err = WasmNoIoPass::new(MemAddress::from(0x1000))
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("no-io program writes the public outputs"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use anyhow::anyhow;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::FuncIndex;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::r#type::TypeObj;
+use pliron::with_context::AttachContext;
+
+/// Move the body of the given `wasm.block` op into a new function of the `module_op`
+/// and replace the op with a call to the new function.
+/// The function symbol is derived from `name_hint` and made unique in the module.
+/// The new function is appended to the module, so its symbol and function index are registered.
+///
+/// The outlined body must not access the locals of the enclosing function or branch out of the op.
+pub fn outline_into_function(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    op: Ptr<Operation>,
+    name_hint: &str,
+) -> Result<wasm::FuncOp, anyhow::Error> {
+    let (block, ty) = get_body_block_and_type(ctx, op)?;
+    let mut body_ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
+    for body_op in &body_ops {
+        body_op.unlink(ctx);
+    }
+    body_ops.push(wasm::ReturnOp::new_unlinked(ctx).get_operation());
+    let (func_op, func_index) = append_function_with_body(ctx, module_op, name_hint, ty, body_ops);
+    let call_op = wasm::CallOp::new_unlinked(ctx, func_index);
+    call_op.get_operation().insert_before(ctx, op);
+    Operation::erase(op, ctx);
+    Ok(func_op)
+}
+
+/// Append a new function with the given ops as its entry block to the `module_op`.
+/// The function symbol is derived from `name_hint` and made unique in the module.
+/// Returns the new function and its index.
+pub fn append_function_with_body(
+    ctx: &mut Context,
+    module_op: &wasm::ModuleOp,
+    name_hint: &str,
+    ty: Ptr<TypeObj>,
+    ops: Vec<Ptr<Operation>>,
+) -> (wasm::FuncOp, FuncIndex) {
+    let func_sym = unique_func_sym(ctx, module_op, name_hint);
+    let entry_block = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+    for op in ops {
+        op.insert_at_back(entry_block, ctx);
+    }
+    let func_op = wasm::FuncOp::new_unlinked_with_block(ctx, func_sym, ty, entry_block, vec![]);
+    let func_index = module_op.append_function(ctx, func_op);
+    (func_op, func_index)
+}
+
+fn get_body_block_and_type(
+    ctx: &Context,
+    op: Ptr<Operation>,
+) -> Result<(Ptr<BasicBlock>, Ptr<TypeObj>), anyhow::Error> {
+    let opop = op.deref(ctx).get_op(ctx);
+    if let Some(block_op) = opop.downcast_ref::<wasm::BlockOp>() {
+        Ok((block_op.get_block(ctx), block_op.get_type(ctx)))
+    } else {
+        Err(anyhow!(
+            "cannot outline op {}, expected wasm.block",
+            op.deref(ctx).with_ctx(ctx)
+        ))
+    }
+}
+
+fn unique_func_sym(ctx: &Context, module_op: &wasm::ModuleOp, name_hint: &str) -> FuncSym {
+    let mut func_sym = FuncSym::from(name_hint);
+    let mut suffix = 0;
+    while module_op.get_func_index(ctx, func_sym.clone()).is_some() {
+        suffix += 1;
+        func_sym = format!("{name_hint}_{suffix}").into();
+    }
+    func_sym
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+    use pliron::operation::WalkOrder;
+    use pliron::operation::WalkResult;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    const WAT: &str = r#"
+(module
+    (start $main)
+    (func $block (result i32)
+        i32.const 0
+        return)
+    (func $main
+        (block
+            i32.const 1
+            drop)
+        return)
+)
+"#;
+
+    #[test]
+    fn outline_block() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT);
+        let mut block_ops = Vec::new();
+        module_op.get_operation().walk_only::<wasm::BlockOp>(
+            &ctx,
+            WalkOrder::PostOrder,
+            &mut |block_op| {
+                block_ops.push(block_op.get_operation());
+                WalkResult::Advance
+            },
+        );
+        let func_op = outline_into_function(&mut ctx, &module_op, block_ops[0], "block").unwrap();
+        let func_sym = FuncSym::from(func_op.get_symbol_name(&ctx));
+        assert_eq!(func_sym, FuncSym::from("block_1"));
+        assert_eq!(
+            module_op.get_func_syms(&ctx),
+            vec![
+                FuncSym::from("block"),
+                FuncSym::from("main"),
+                FuncSym::from("block_1")
+            ]
+        );
+        let func_index = module_op.get_func_index(&ctx, func_sym).unwrap();
+        assert_eq!(func_index, FuncIndex::from(2));
+        assert!(module_op.is_func_called(&ctx, func_index));
+        expect![[r#"
+            wasm.module @module_name {
+              block_1_0():
+                wasm.func @block() -> (si32) {
+                  entry():
+                    wasm.const 0x0: si32
+                    wasm.return
+                }
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.call 2
+                    wasm.return
+                }
+                wasm.func @block_1() -> () {
+                  entry():
+                    wasm.const 0x1: si32
+                    wasm.drop
+                    wasm.return
+                }
+            }"#]]
+        .assert_eq(&module_op.with_ctx(&ctx).to_string());
+    }
+}
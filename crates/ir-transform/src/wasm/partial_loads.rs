@@ -0,0 +1,410 @@
+//! Lowering of the partial-width loads (`i32.load8_s`, `i64.load16_u`, etc.) to the aligned
+//! full-width `i32` loads of the containing memory cells (see [crate::byte_layout]).
+
+use ozk_ozk_dialect::ops as ozk;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::BinaryOpcode;
+use ozk_wasm_dialect::types::MemArg;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+use super::reserved_slots::SCRATCH_SLOT;
+use crate::byte_layout::ByteLayout;
+use crate::byte_layout::Endianness;
+use crate::byte_layout::CELL_BYTES;
+
+/// Replaces the partial-width loads with the byte extraction from the cell loaded with
+/// `wasm.load I32` at the cell-aligned address. A 16-bit value is assembled from its bytes, so
+/// it may span two cells. The sign extension is done by the `extend*_s` ops. The address is
+/// kept in the [SCRATCH_SLOT] reserved slot, so this pass must run before the reserved slots
+/// are resolved.
+pub struct WasmPartialLoadsPass {
+    byte_layout: ByteLayout,
+}
+
+impl WasmPartialLoadsPass {
+    pub fn new(byte_layout: ByteLayout) -> Self {
+        Self { byte_layout }
+    }
+}
+
+impl Pass for WasmPartialLoadsPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut load_ops = Vec::new();
+        op.walk(ctx, WalkOrder::PostOrder, &mut |op| {
+            if partial_load_kind(ctx, op).is_some() {
+                load_ops.push(op);
+            }
+            WalkResult::Advance
+        });
+        for load_op in load_ops {
+            #[allow(clippy::unwrap_used)] // collected above
+            let (kind, memarg) = partial_load_kind(ctx, load_op).unwrap();
+            let new_ops = self.lower(ctx, kind, memarg);
+            for new_op in new_ops {
+                new_op.insert_before(ctx, load_op);
+            }
+            load_op.unlink(ctx);
+        }
+        Ok(())
+    }
+}
+
+/// Width of the loaded value and the extension op (if it's a signed load)
+#[derive(Debug, Clone, Copy)]
+enum PartialLoadKind {
+    Load8(Option<SignExt>),
+    Load16(Option<SignExt>),
+    Load32(Option<SignExt>),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SignExt {
+    I32Extend8S,
+    I32Extend16S,
+    I64Extend8S,
+    I64Extend16S,
+    I64Extend32S,
+}
+
+/// The kind and the memarg of the partial-width load op
+fn partial_load_kind(ctx: &Context, op: Ptr<Operation>) -> Option<(PartialLoadKind, MemArg)> {
+    let opop = op.deref(ctx).get_op(ctx);
+    macro_rules! partial_load {
+        ($op:ty, $kind:expr) => {
+            if let Some(load_op) = opop.downcast_ref::<$op>() {
+                return Some(($kind, load_op.get_memarg(ctx)));
+            }
+        };
+    }
+    partial_load!(
+        wasm::I32Load8SOp,
+        PartialLoadKind::Load8(Some(SignExt::I32Extend8S))
+    );
+    partial_load!(wasm::I32Load8UOp, PartialLoadKind::Load8(None));
+    partial_load!(wasm::I64Load8UOp, PartialLoadKind::Load8(None));
+    partial_load!(
+        wasm::I32Load16SOp,
+        PartialLoadKind::Load16(Some(SignExt::I32Extend16S))
+    );
+    partial_load!(wasm::I32Load16UOp, PartialLoadKind::Load16(None));
+    partial_load!(wasm::I64Load16UOp, PartialLoadKind::Load16(None));
+    partial_load!(
+        wasm::I64Load8SOp,
+        PartialLoadKind::Load8(Some(SignExt::I64Extend8S))
+    );
+    partial_load!(
+        wasm::I64Load16SOp,
+        PartialLoadKind::Load16(Some(SignExt::I64Extend16S))
+    );
+    partial_load!(
+        wasm::I64Load32SOp,
+        PartialLoadKind::Load32(Some(SignExt::I64Extend32S))
+    );
+    partial_load!(wasm::I64Load32UOp, PartialLoadKind::Load32(None));
+    None
+}
+
+impl WasmPartialLoadsPass {
+    /// Ops that pop the address and push the loaded value. The zero-extended value is the same
+    /// for i32 and i64, so it is computed with the i32 ops. The static offset of the memarg is
+    /// added to the byte addresses (wrapping, as the rest of the i32 address arithmetic).
+    fn lower(
+        &self,
+        ctx: &mut Context,
+        kind: PartialLoadKind,
+        memarg: MemArg,
+    ) -> Vec<Ptr<Operation>> {
+        let offset = memarg.offset as i32;
+        let (mut ops, sign_ext) = match kind {
+            PartialLoadKind::Load8(sign_ext) => {
+                let mut ops =
+                    vec![ozk::ReservedSetOp::new_unlinked(ctx, SCRATCH_SLOT).get_operation()];
+                ops.extend(self.load_byte(ctx, offset));
+                (ops, sign_ext)
+            }
+            PartialLoadKind::Load16(sign_ext) => {
+                let mut ops =
+                    vec![ozk::ReservedSetOp::new_unlinked(ctx, SCRATCH_SLOT).get_operation()];
+                // the Wasm memory is little-endian: low byte | high byte << 8
+                ops.extend(self.load_byte(ctx, offset));
+                ops.extend(self.load_byte(ctx, offset.wrapping_add(1)));
+                ops.extend(vec![
+                    wasm::ConstantOp::new_i32_unlinked(ctx, 8).get_operation(),
+                    wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Shl).get_operation(),
+                    wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Or).get_operation(),
+                ]);
+                (ops, sign_ext)
+            }
+            PartialLoadKind::Load32(sign_ext) => {
+                // a full cell, the same as `i32.load` with this memarg
+                let memarg = MemArg {
+                    align: memarg.align.min(MemArg::natural(CELL_BYTES).align),
+                    ..memarg
+                };
+                let ops = vec![wasm::LoadOp::new_unlinked_with_memarg(
+                    ctx,
+                    wasm::MemAccessOpValueType::I32,
+                    memarg,
+                )
+                .get_operation()];
+                (ops, sign_ext)
+            }
+        };
+        if let Some(sign_ext) = sign_ext {
+            let sign_ext_op = match sign_ext {
+                SignExt::I32Extend8S => wasm::I32Extend8SOp::new_unlinked(ctx).get_operation(),
+                SignExt::I32Extend16S => wasm::I32Extend16SOp::new_unlinked(ctx).get_operation(),
+                SignExt::I64Extend8S => wasm::I64Extend8SOp::new_unlinked(ctx).get_operation(),
+                SignExt::I64Extend16S => wasm::I64Extend16SOp::new_unlinked(ctx).get_operation(),
+                SignExt::I64Extend32S => wasm::I64Extend32SOp::new_unlinked(ctx).get_operation(),
+            };
+            ops.push(sign_ext_op);
+        }
+        ops
+    }
+
+    /// Ops that push the byte at the address in the [SCRATCH_SLOT] plus `offset`
+    /// zero-extended to i32
+    fn load_byte(&self, ctx: &mut Context, offset: i32) -> Vec<Ptr<Operation>> {
+        let mut ops = self.byte_address(ctx, offset);
+        // the cell containing the byte
+        ops.extend(vec![
+            wasm::ConstantOp::new_i32_unlinked(ctx, !(CELL_BYTES as i32 - 1)).get_operation(),
+            wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::And).get_operation(),
+            wasm::LoadOp::new_unlinked(ctx, wasm::MemAccessOpValueType::I32).get_operation(),
+        ]);
+        // the byte position in the cell
+        ops.extend(self.byte_address(ctx, offset));
+        ops.extend(vec![
+            wasm::ConstantOp::new_i32_unlinked(ctx, CELL_BYTES as i32 - 1).get_operation(),
+            wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::And).get_operation(),
+        ]);
+        if self.byte_layout.byte_order == Endianness::Big {
+            // the byte at the lowest address is the most significant one
+            ops.extend(vec![
+                wasm::ConstantOp::new_i32_unlinked(ctx, CELL_BYTES as i32 - 1).get_operation(),
+                wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Xor).get_operation(),
+            ]);
+        }
+        // shift by 8 * position and take the lowest byte
+        ops.extend(vec![
+            wasm::ConstantOp::new_i32_unlinked(ctx, 3).get_operation(),
+            wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Shl).get_operation(),
+            wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::ShrU).get_operation(),
+            wasm::ConstantOp::new_i32_unlinked(ctx, 0xff).get_operation(),
+            wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::And).get_operation(),
+        ]);
+        ops
+    }
+
+    /// Ops that push the address in the [SCRATCH_SLOT] plus `offset`
+    fn byte_address(&self, ctx: &mut Context, offset: i32) -> Vec<Ptr<Operation>> {
+        let mut ops = vec![ozk::ReservedGetOp::new_unlinked(ctx, SCRATCH_SLOT).get_operation()];
+        if offset != 0 {
+            ops.push(wasm::ConstantOp::new_i32_unlinked(ctx, offset).get_operation());
+            ops.push(wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Add).get_operation());
+        }
+        ops
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use expect_test::Expect;
+    use ozk_ozk_dialect::types::FuncSym;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    fn check_main(byte_layout: ByteLayout, wat: &str, expected: Expect) {
+        let (mut ctx, module_op) = parse_wasm_module(wat);
+        WasmPartialLoadsPass::new(byte_layout)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        expected.assert_eq(&main_func.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn load8_s() {
+        check_main(
+            ByteLayout::default(),
+            r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 5
+        i32.load8_s
+        drop
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.const 0x5: si32
+                    ozk.reserved_set scratch
+                    ozk.reserved_get scratch
+                    wasm.const 0xfffffffc: si32
+                    wasm.i32.and
+                    wasm.load I32
+                    ozk.reserved_get scratch
+                    wasm.const 0x3: si32
+                    wasm.i32.and
+                    wasm.const 0x3: si32
+                    wasm.i32.shl
+                    wasm.i32.shr_u
+                    wasm.const 0xff: si32
+                    wasm.i32.and
+                    wasm.i32.extend8_s
+                    wasm.drop
+                    wasm.return
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn load16_u_big_endian_bytes() {
+        check_main(
+            ByteLayout {
+                byte_order: Endianness::Big,
+                ..Default::default()
+            },
+            r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 6
+        i32.load16_u
+        drop
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.const 0x6: si32
+                    ozk.reserved_set scratch
+                    ozk.reserved_get scratch
+                    wasm.const 0xfffffffc: si32
+                    wasm.i32.and
+                    wasm.load I32
+                    ozk.reserved_get scratch
+                    wasm.const 0x3: si32
+                    wasm.i32.and
+                    wasm.const 0x3: si32
+                    wasm.i32.xor
+                    wasm.const 0x3: si32
+                    wasm.i32.shl
+                    wasm.i32.shr_u
+                    wasm.const 0xff: si32
+                    wasm.i32.and
+                    ozk.reserved_get scratch
+                    wasm.const 0x1: si32
+                    wasm.i32.add
+                    wasm.const 0xfffffffc: si32
+                    wasm.i32.and
+                    wasm.load I32
+                    ozk.reserved_get scratch
+                    wasm.const 0x1: si32
+                    wasm.i32.add
+                    wasm.const 0x3: si32
+                    wasm.i32.and
+                    wasm.const 0x3: si32
+                    wasm.i32.xor
+                    wasm.const 0x3: si32
+                    wasm.i32.shl
+                    wasm.i32.shr_u
+                    wasm.const 0xff: si32
+                    wasm.i32.and
+                    wasm.const 0x8: si32
+                    wasm.i32.shl
+                    wasm.i32.or
+                    wasm.drop
+                    wasm.return
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn load8_u_with_offset() {
+        check_main(
+            ByteLayout::default(),
+            r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 1
+        i32.load8_u offset=6
+        drop
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.const 0x1: si32
+                    ozk.reserved_set scratch
+                    ozk.reserved_get scratch
+                    wasm.const 0x6: si32
+                    wasm.i32.add
+                    wasm.const 0xfffffffc: si32
+                    wasm.i32.and
+                    wasm.load I32
+                    ozk.reserved_get scratch
+                    wasm.const 0x6: si32
+                    wasm.i32.add
+                    wasm.const 0x3: si32
+                    wasm.i32.and
+                    wasm.const 0x3: si32
+                    wasm.i32.shl
+                    wasm.i32.shr_u
+                    wasm.const 0xff: si32
+                    wasm.i32.and
+                    wasm.drop
+                    wasm.return
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn i64_load32_s() {
+        check_main(
+            ByteLayout::default(),
+            r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 8
+        i64.load32_s offset=4
+        drop
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.func @main() -> () {
+                  entry():
+                    wasm.const 0x8: si32
+                    wasm.load I32 offset=4
+                    wasm.i64.extend32_s
+                    wasm.drop
+                    wasm.return
+                }"#]],
+        );
+    }
+}
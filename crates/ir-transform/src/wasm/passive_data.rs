@@ -0,0 +1,266 @@
+//! Lowering of the passive data segments (`memory.init`, `data.drop`). The targets have no
+//! read-only data, so the bytes of the segments are copied by a prologue function into the
+//! target memory outside of the Wasm memory, starting at the address provided by the target's
+//! memory layout. Each segment there is its length (a cell) followed by its bytes, and
+//! `memory.init` is a byte copy from it by the target runtime (see [super::link_runtime]).
+//! `data.drop` sets the length to zero.
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::types::DataIndex;
+use ozk_wasm_dialect::types::MemAddress;
+use ozk_wasm_dialect::types::PrologueStage;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+use crate::byte_layout::CELL_BYTES;
+
+use super::data_init::bytes_write_ops;
+use super::link_runtime::RUNTIME_IMPORT_MODULE;
+
+/// Symbol of the function that copies the passive data segments into the target memory
+/// (a prologue function)
+pub const INIT_PASSIVE_DATA_FUNC_NAME: &str = "init_passive_data";
+/// Symbol of the function implementing `memory.init`
+pub const MEMORY_INIT_FUNC_NAME: &str = "memory_init";
+
+/// Replaces `memory.init` with the call to the [MEMORY_INIT_FUNC_NAME] runtime function
+/// (imported from [RUNTIME_IMPORT_MODULE]) and `data.drop` with the store of the zero length.
+/// The runtime function takes the destination address, the offset in the segment, the number
+/// of bytes and the segment address. Adds the [INIT_PASSIVE_DATA_FUNC_NAME] prologue function,
+/// so it must run before the runtime is linked and the prologue is emitted.
+pub struct WasmPassiveDataPass {
+    start_addr: MemAddress,
+    max_bytes: u32,
+}
+
+impl WasmPassiveDataPass {
+    /// `max_bytes` is the size of the target memory region reserved for the segments
+    pub fn new(start_addr: MemAddress, max_bytes: u32) -> Self {
+        Self {
+            start_addr,
+            max_bytes,
+        }
+    }
+}
+
+impl Pass for WasmPassiveDataPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            self.lower_passive_data(ctx, &module_op)?;
+        }
+        Ok(())
+    }
+}
+
+impl WasmPassiveDataPass {
+    fn lower_passive_data(
+        &self,
+        ctx: &mut Context,
+        module_op: &wasm::ModuleOp,
+    ) -> Result<(), anyhow::Error> {
+        let mut init_ops = Vec::new();
+        let mut drop_ops = Vec::new();
+        module_op
+            .get_operation()
+            .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+                let opop = op.deref(ctx).get_op(ctx);
+                if let Some(init_op) = opop.downcast_ref::<wasm::MemoryInitOp>() {
+                    init_ops.push(*init_op);
+                } else if let Some(drop_op) = opop.downcast_ref::<wasm::DataDropOp>() {
+                    drop_ops.push(*drop_op);
+                }
+                WalkResult::Advance
+            });
+        if init_ops.is_empty() && drop_ops.is_empty() {
+            return Ok(());
+        }
+        let segments = module_op.get_passive_data(ctx);
+        // the address of the length of each segment, its bytes follow
+        let mut segment_addrs = Vec::new();
+        let mut addr = u32::from(self.start_addr) as u64;
+        for bytes in &segments {
+            segment_addrs.push(addr as u32);
+            let cells = 1 + (bytes.len() as u64 + CELL_BYTES as u64 - 1) / CELL_BYTES as u64;
+            addr += cells * CELL_BYTES as u64;
+        }
+        let total_bytes = addr - u32::from(self.start_addr) as u64;
+        if total_bytes > self.max_bytes as u64 {
+            return Err(anyhow!(
+                "passive data segments take {total_bytes} bytes, only {} bytes are reserved",
+                self.max_bytes
+            ));
+        }
+        let segment_addr = |data_index: DataIndex| {
+            segment_addrs
+                .get(u32::from(data_index) as usize)
+                .copied()
+                .ok_or_else(|| anyhow!("undefined data segment {data_index}"))
+        };
+
+        if !init_ops.is_empty() {
+            // provided by the target runtime
+            let i32_ty = i32_type(ctx);
+            let memory_init_ty = FunctionType::get(ctx, vec![i32_ty; 4], vec![]);
+            let memory_init_func_index = module_op.append_import(
+                ctx,
+                FuncSym::from(MEMORY_INIT_FUNC_NAME),
+                memory_init_ty,
+                RUNTIME_IMPORT_MODULE,
+            );
+            for init_op in init_ops {
+                let addr = segment_addr(init_op.get_data_index(ctx))?;
+                for op in [
+                    wasm::ConstantOp::new_i32_unlinked(ctx, addr as i32).get_operation(),
+                    wasm::CallOp::new_unlinked(ctx, memory_init_func_index).get_operation(),
+                ] {
+                    op.insert_before(ctx, init_op.get_operation());
+                }
+                init_op.get_operation().unlink(ctx);
+            }
+        }
+        for drop_op in drop_ops {
+            let addr = segment_addr(drop_op.get_data_index(ctx))?;
+            for op in [
+                wasm::ConstantOp::new_i32_unlinked(ctx, addr as i32).get_operation(),
+                wasm::ConstantOp::new_i32_unlinked(ctx, 0).get_operation(),
+                wasm::StoreOp::new_unlinked(ctx, MemAccessOpValueType::I32).get_operation(),
+            ] {
+                op.insert_before(ctx, drop_op.get_operation());
+            }
+            drop_op.get_operation().unlink(ctx);
+        }
+
+        let mut ops = Vec::new();
+        for (addr, bytes) in segment_addrs.iter().zip(&segments) {
+            let mut segment = (bytes.len() as u32).to_le_bytes().to_vec();
+            segment.extend(bytes);
+            ops.extend(bytes_write_ops(ctx, *addr, &segment));
+        }
+        ops.push(wasm::ReturnOp::new_unlinked(ctx).get_operation());
+        let entry_block = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+        for op in ops {
+            op.insert_at_back(entry_block, ctx);
+        }
+        let ty = FunctionType::get(ctx, vec![], vec![]);
+        let func_op = wasm::FuncOp::new_unlinked_with_block(
+            ctx,
+            FuncSym::from(INIT_PASSIVE_DATA_FUNC_NAME),
+            ty,
+            entry_block,
+            vec![],
+        );
+        module_op.add_prologue_function(ctx, func_op, PrologueStage::MemoryInit);
+        Ok(())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    const WAT_PASSIVE_DATA: &str = r#"
+(module
+    (memory 1)
+    (data (i32.const 16) "active")
+    (data "\01\02\03\04\05")
+    (start $main)
+    (func $main
+        i32.const 32
+        i32.const 1
+        i32.const 3
+        memory.init 1
+        data.drop 1
+        return)
+)
+"#;
+
+    #[test]
+    fn memory_init_and_data_drop() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT_PASSIVE_DATA);
+        WasmPassiveDataPass::new(MemAddress::from(0x1000), 64)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        assert_eq!(
+            module_op.get_prologue_functions(&ctx),
+            vec![FuncSym::from(INIT_PASSIVE_DATA_FUNC_NAME)]
+        );
+        assert_eq!(
+            module_op.get_import_func_modules(&ctx),
+            vec![RUNTIME_IMPORT_MODULE.to_string()]
+        );
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        expect![[r#"
+            wasm.func @main() -> () {
+              entry():
+                wasm.const 0x20: si32
+                wasm.const 0x1: si32
+                wasm.const 0x3: si32
+                wasm.const 0x1004: si32
+                wasm.call 0
+                wasm.const 0x1004: si32
+                wasm.const 0x0: si32
+                wasm.store I32
+                wasm.return
+            }"#]]
+        .assert_eq(&main_func.with_ctx(&ctx).to_string());
+        let init_func = module_op
+            .get_func(&ctx, &FuncSym::from(INIT_PASSIVE_DATA_FUNC_NAME))
+            .unwrap();
+        // the active segment is empty (zero length, skipped), the passive one is at 0x1004
+        expect![[r#"
+            wasm.func @init_passive_data() -> () {
+              entry():
+                wasm.const 0x1004: si32
+                wasm.const 0x5: si32
+                wasm.store I32
+                wasm.const 0x1008: si32
+                wasm.const 0x4030201: si32
+                wasm.store I32
+                wasm.const 0x100c: si32
+                wasm.const 0x100c: si32
+                wasm.load I32
+                wasm.const 0xffffff00: si32
+                wasm.i32.and
+                wasm.const 0x5: si32
+                wasm.i32.or
+                wasm.store I32
+                wasm.return
+            }"#]]
+        .assert_eq(&init_func.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn passive_data_over_reserved_space() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT_PASSIVE_DATA);
+        let err = WasmPassiveDataPass::new(MemAddress::from(0x1000), 8)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("only 8 bytes are reserved"),
+            "{err}"
+        );
+    }
+}
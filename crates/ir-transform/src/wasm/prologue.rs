@@ -0,0 +1,174 @@
+use anyhow::anyhow;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Emits the calls to the prologue functions (see [wasm::ModuleOp::add_prologue_function])
+/// at the beginning of the start function, ordered by their stage and then by the order
+/// they were added. The passes that add the prologue functions must run before this one.
+#[derive(Default)]
+pub struct WasmEmitProloguePass;
+
+impl Pass for WasmEmitProloguePass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            emit_prologue(ctx, &module_op)?;
+        }
+        Ok(())
+    }
+}
+
+fn emit_prologue(ctx: &mut Context, module_op: &wasm::ModuleOp) -> Result<(), anyhow::Error> {
+    let prologue_funcs = module_op.get_prologue_functions(ctx);
+    if prologue_funcs.is_empty() {
+        return Ok(());
+    }
+    let start_func_sym = module_op
+        .try_get_start_func_sym(ctx)
+        .ok_or_else(|| anyhow!("prologue functions require a start function"))?;
+    let start_func = module_op
+        .get_func(ctx, &start_func_sym)
+        .ok_or_else(|| anyhow!("start function {start_func_sym:?} is not defined"))?;
+    let entry_block = start_func.get_entry_block(ctx);
+    let first_op = entry_block.deref(ctx).iter(ctx).next();
+    for func_sym in prologue_funcs {
+        let func_index = module_op
+            .get_func_index(ctx, func_sym.clone())
+            .ok_or_else(|| anyhow!("prologue function {func_sym:?} is not defined"))?;
+        let call_op = wasm::CallOp::new_unlinked(ctx, func_index).get_operation();
+        match first_op {
+            Some(first_op) => call_op.insert_before(ctx, first_op),
+            None => call_op.insert_at_back(entry_block, ctx),
+        }
+    }
+    module_op.clear_prologue_functions(ctx);
+    Ok(())
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use ozk_ozk_dialect::types::FuncSym;
+    use ozk_wasm_dialect::types::PrologueStage;
+    use pliron::basic_block::BasicBlock;
+    use pliron::dialects::builtin::types::FunctionType;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    const WAT: &str = r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 1
+        drop
+        return)
+)
+"#;
+
+    fn add_prologue_func(
+        ctx: &mut Context,
+        module_op: &wasm::ModuleOp,
+        name: &str,
+        stage: PrologueStage,
+    ) {
+        let entry_block = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+        wasm::ReturnOp::new_unlinked(ctx)
+            .get_operation()
+            .insert_at_back(entry_block, ctx);
+        let func_type = FunctionType::get(ctx, vec![], vec![]);
+        let func_op = wasm::FuncOp::new_unlinked_with_block(
+            ctx,
+            FuncSym::from(name),
+            func_type,
+            entry_block,
+            vec![],
+        );
+        module_op.add_prologue_function(ctx, func_op, stage);
+    }
+
+    #[test]
+    fn prologue_calls_ordered_by_stage() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT);
+        // added by different passes in an arbitrary order
+        add_prologue_func(&mut ctx, &module_op, "user_a", PrologueStage::User);
+        add_prologue_func(&mut ctx, &module_op, "globals", PrologueStage::GlobalsInit);
+        add_prologue_func(&mut ctx, &module_op, "user_b", PrologueStage::User);
+        add_prologue_func(&mut ctx, &module_op, "memory_a", PrologueStage::MemoryInit);
+        add_prologue_func(&mut ctx, &module_op, "memory_b", PrologueStage::MemoryInit);
+        expect![[r#"
+            [
+                FuncSym(
+                    "memory_a",
+                ),
+                FuncSym(
+                    "memory_b",
+                ),
+                FuncSym(
+                    "globals",
+                ),
+                FuncSym(
+                    "user_a",
+                ),
+                FuncSym(
+                    "user_b",
+                ),
+            ]"#]]
+        .assert_eq(&format!("{:#?}", module_op.get_prologue_functions(&ctx)));
+        WasmEmitProloguePass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        expect![[r#"
+            wasm.func @main() -> () {
+              entry():
+                wasm.call 4
+                wasm.call 5
+                wasm.call 2
+                wasm.call 1
+                wasm.call 3
+                wasm.const 0x1: si32
+                wasm.drop
+                wasm.return
+            }"#]]
+        .assert_eq(&main_func.with_ctx(&ctx).to_string());
+        assert!(module_op.get_prologue_functions(&ctx).is_empty());
+    }
+
+    #[test]
+    fn renamed_prologue_function() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT);
+        add_prologue_func(&mut ctx, &module_op, "init", PrologueStage::GlobalsInit);
+        module_op
+            .rename_function(&mut ctx, &FuncSym::from("init"), FuncSym::from("init_0"))
+            .unwrap();
+        assert_eq!(
+            module_op.get_prologue_functions(&ctx),
+            vec![FuncSym::from("init_0")]
+        );
+    }
+
+    #[test]
+    fn prologue_function_is_not_removable() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT);
+        add_prologue_func(&mut ctx, &module_op, "init", PrologueStage::User);
+        let func_op = module_op.get_func(&ctx, &FuncSym::from("init")).unwrap();
+        let err = module_op.remove_function(&mut ctx, func_op).unwrap_err();
+        assert!(err.to_string().contains("still called"), "{err}");
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeSet;
+
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Renames the defined functions to valid target identifiers: the characters other than ASCII
+/// alphanumerics and `_` (e.g. `$`, `.` in Rust mangled names) are replaced with `_`, the names
+/// starting with a digit or colliding with the target keywords get a `_` prefix and a numeric
+/// suffix is added if the name is already taken.
+/// The original name is recorded in the function (see [wasm::FuncOp::get_original_name]).
+/// The imported functions keep their names since they are resolved by name.
+pub struct WasmRenameSymbolsPass {
+    reserved: &'static [&'static str],
+}
+
+impl WasmRenameSymbolsPass {
+    /// Create a pass avoiding the given target keywords
+    pub fn new(reserved: &'static [&'static str]) -> Self {
+        Self { reserved }
+    }
+
+    /// Valid target identifier for the name (not checked for the collisions)
+    pub fn valid_name(&self, name: &str) -> String {
+        let valid: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let starts_with_digit = valid.chars().next().map_or(true, |c| c.is_ascii_digit());
+        if starts_with_digit || self.reserved.contains(&valid.as_str()) {
+            format!("_{valid}")
+        } else {
+            valid
+        }
+    }
+}
+
+impl Pass for WasmRenameSymbolsPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            let mut taken: BTreeSet<FuncSym> = module_op.get_func_syms(ctx).into_iter().collect();
+            let mut renames = Vec::new();
+            for op in module_op.get_body(ctx, 0).deref(ctx).iter(ctx) {
+                let Some(func_op) = op.deref(ctx).get_op(ctx).downcast_ref::<wasm::FuncOp>().cloned() else {
+                    continue;
+                };
+                let name = func_op.get_symbol_name(ctx);
+                let valid_name = self.valid_name(&name);
+                if valid_name == name {
+                    continue;
+                }
+                let mut new_func_sym = FuncSym::from(valid_name.as_str());
+                let mut suffix = 1;
+                while taken.contains(&new_func_sym) {
+                    new_func_sym = FuncSym::from(format!("{valid_name}_{suffix}").as_str());
+                    suffix += 1;
+                }
+                taken.insert(new_func_sym.clone());
+                renames.push((FuncSym::from(name.as_str()), new_func_sym));
+            }
+            for (func_sym, new_func_sym) in renames {
+                module_op.rename_function(ctx, &func_sym, new_func_sym)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use crate::tests_util::run_wasm_pass_wrapped;
+
+    use super::*;
+
+    #[test]
+    fn rename_invalid_and_reserved() {
+        let (ctx, module_op) = run_wasm_pass_wrapped(
+            &WasmRenameSymbolsPass::new(&["begin", "end"]),
+            r#"
+(module
+    (start $main)
+    (func $_ZN4core3fmt$u20$Write.write (result i64)
+        i64.const 1
+        return)
+    (func $end (result i64)
+        i64.const 2
+        return)
+    (func $_end (result i64)
+        i64.const 3
+        return)
+    (func $main
+        (local i64)
+        call $_ZN4core3fmt$u20$Write.write
+        call $end
+        i64.add
+        call $_end
+        i64.add
+        local.set 0
+        return)
+)
+"#,
+        );
+        let func_syms = module_op.get_func_syms(&ctx);
+        assert_eq!(
+            func_syms,
+            vec![
+                FuncSym::from("_ZN4core3fmt_u20_Write_write"),
+                FuncSym::from("_end_1"),
+                FuncSym::from("_end"),
+                FuncSym::from("main"),
+            ]
+        );
+        let renamed = module_op.get_func(&ctx, &func_syms[1]).unwrap();
+        assert_eq!(renamed.get_original_name(&ctx), Some("end".to_string()));
+        let main = module_op.get_func(&ctx, &func_syms[3]).unwrap();
+        assert_eq!(main.get_original_name(&ctx), None);
+    }
+}
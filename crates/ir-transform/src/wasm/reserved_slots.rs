@@ -0,0 +1,159 @@
+//! Resolution of the named reserved slots ([ozk::ReservedGetOp], [ozk::ReservedSetOp])
+//! to the concrete memory addresses provided by the target's memory layout.
+//! Passes that need a runtime cell (br-propagation depth, scratch, etc.) emit the named ops,
+//! so that they don't depend on the layout.
+
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::ord_n::Ord16;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::MemAddress;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialect_conversion::apply_partial_conversion;
+use pliron::dialect_conversion::ConversionTarget;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pass::Pass;
+use pliron::pattern_match::PatternRewriter;
+use pliron::pattern_match::RewritePattern;
+use pliron::rewrite::RewritePatternSet;
+use pliron::with_context::AttachContext;
+
+/// Reserved slot of the br-propagation depth of the flattened blocks
+pub const BR_PROPAGATION_SLOT: &str = "br_propagation";
+/// Reserved slot for the temporary values of the lowering passes
+pub const SCRATCH_SLOT: &str = "scratch";
+/// Reserved slot of the current linear memory size in pages (see [crate::wasm::memory_size])
+pub const MEMORY_SIZE_SLOT: &str = "memory_size";
+
+/// Resolves the named reserved slots to the memory addresses (i32 cells)
+pub struct WasmResolveReservedSlotsPass {
+    slots: BTreeMap<String, MemAddress>,
+}
+
+impl WasmResolveReservedSlotsPass {
+    pub fn new(slots: BTreeMap<String, MemAddress>) -> Self {
+        Self { slots }
+    }
+}
+
+impl Pass for WasmResolveReservedSlotsPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let target = ConversionTarget::default();
+        let mut patterns = RewritePatternSet::default();
+        patterns.add(Box::new(ResolveReservedSlot::new(self.slots.clone())));
+        apply_partial_conversion(ctx, op, target, patterns)?;
+        Ok(())
+    }
+}
+
+pub struct ResolveReservedSlot {
+    slots: BTreeMap<String, MemAddress>,
+}
+
+impl ResolveReservedSlot {
+    pub fn new(slots: BTreeMap<String, MemAddress>) -> Self {
+        Self { slots }
+    }
+
+    fn address(&self, slot_name: &str) -> Result<i32, anyhow::Error> {
+        self.slots
+            .get(slot_name)
+            .map(|address| u32::from(*address) as i32)
+            .ok_or_else(|| anyhow!("reserved slot {slot_name} is not in the memory layout"))
+    }
+}
+
+impl RewritePattern for ResolveReservedSlot {
+    fn match_op(&self, ctx: &Context, op: Ptr<Operation>) -> Result<bool, anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        Ok(opop.downcast_ref::<ozk::ReservedGetOp>().is_some()
+            || opop.downcast_ref::<ozk::ReservedSetOp>().is_some())
+    }
+
+    #[allow(clippy::panic)]
+    fn rewrite(
+        &self,
+        ctx: &mut Context,
+        op: Ptr<Operation>,
+        rewriter: &mut dyn PatternRewriter,
+    ) -> Result<(), anyhow::Error> {
+        let opop = op.deref(ctx).get_op(ctx);
+        if let Some(get_op) = opop.downcast_ref::<ozk::ReservedGetOp>() {
+            let address = self.address(&get_op.get_slot_name(ctx))?;
+            let constant_op = wasm::ConstantOp::new_i32_unlinked(ctx, address);
+            rewriter.insert_before(ctx, constant_op.get_operation())?;
+            let load_op = wasm::LoadOp::new_unlinked(ctx, wasm::MemAccessOpValueType::I32);
+            rewriter.replace_op_with(ctx, op, load_op.get_operation())?;
+        } else if let Some(set_op) = opop.downcast_ref::<ozk::ReservedSetOp>() {
+            let address = self.address(&set_op.get_slot_name(ctx))?;
+            let constant_op = wasm::ConstantOp::new_i32_unlinked(ctx, address);
+            rewriter.insert_before(ctx, constant_op.get_operation())?;
+            let swap_op = ozk::SwapOp::new_unlinked(ctx, Ord16::ST1);
+            rewriter.insert_before(ctx, swap_op.get_operation())?;
+            let store_op = wasm::StoreOp::new_unlinked(ctx, wasm::MemAccessOpValueType::I32);
+            rewriter.replace_op_with(ctx, op, store_op.get_operation())?;
+        } else {
+            panic!("unexpected op {}", op.deref(ctx).with_ctx(ctx));
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+
+    use crate::tests_util::ModuleFixture;
+
+    use super::*;
+
+    fn slot_ops_module(slot_name: &str) -> ModuleFixture {
+        let mut module = ModuleFixture::new();
+        module.func("main", vec![], vec![], |f| {
+            f.op(|ctx| ozk::ReservedGetOp::new_unlinked(ctx, slot_name).get_operation())
+                .op(|ctx| ozk::ReservedSetOp::new_unlinked(ctx, slot_name).get_operation())
+                .ret();
+        });
+        module
+    }
+
+    #[test]
+    fn resolve_reserved_slots() {
+        let pass = WasmResolveReservedSlotsPass::new(BTreeMap::from([(
+            SCRATCH_SLOT.to_string(),
+            MemAddress::from(256),
+        )]));
+        slot_ops_module(SCRATCH_SLOT).check_pass(
+            &pass,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.const 0x100: si32
+                        wasm.load I32
+                        wasm.const 0x100: si32
+                        ozk.swap 1
+                        wasm.store I32
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn unknown_reserved_slot() {
+        let (mut ctx, module_op) = slot_ops_module("unknown").build();
+        let pass = WasmResolveReservedSlotsPass::new(BTreeMap::new());
+        let err = pass
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(err.to_string().contains("reserved slot unknown"), "{err}");
+    }
+}
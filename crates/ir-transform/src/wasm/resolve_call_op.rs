@@ -5,6 +5,7 @@ use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::dialect_conversion::apply_partial_conversion;
 use pliron::dialect_conversion::ConversionTarget;
+use pliron::error::CompilerError;
 use pliron::op::Op;
 use pliron::operation::Operation;
 use pliron::operation::WalkOrder;
@@ -14,6 +15,12 @@ use pliron::pattern_match::PatternRewriter;
 use pliron::pattern_match::RewritePattern;
 use pliron::rewrite::RewritePatternSet;
 
+/// Replaces the index-based `wasm.call` ops with the symbol-based ozk ops, so that the backend
+/// patterns don't depend on the function index space and the import table:
+/// the calls to the functions defined in the module become [ozk::ops::CallOp] and the calls to
+/// the imported (host) functions, e.g. the I/O, become [ozk::ops::HostCallOp].
+/// The tail calls (`wasm.return_call`) become [ozk::ops::ReturnCallOp], the tail calls to the
+/// host functions become the host call followed by `wasm.return`.
 #[derive(Default)]
 pub struct WasmCallOpToOzkCallOpPass;
 
@@ -51,17 +58,104 @@ impl RewritePattern for WasmCallOpToOzkCallOp {
             },
         );
 
+        let import_count = module_op.get_import_func_types(ctx).len();
         for wasm_call_op in wasm_call_ops {
-            #[allow(clippy::expect_used)]
-            let func_sym = module_op
-                .get_func_sym(ctx, wasm_call_op.get_func_index(ctx))
-                .expect("func_sym not found");
-            #[allow(clippy::expect_used)]
-            let func_op = module_op.get_func(ctx, &func_sym).expect("func not found");
-            let call_op = ozk::ops::CallOp::new_unlinked(ctx, func_sym, func_op.get_type(ctx));
-            rewriter.replace_op_with(ctx, wasm_call_op.get_operation(), call_op.get_operation())?;
+            let func_index = wasm_call_op.get_func_index(ctx);
+            let (Some(func_sym), Some(func_type)) = (
+                module_op.get_func_sym(ctx, func_index),
+                module_op.get_func_type(ctx, func_index),
+            ) else {
+                return Err(CompilerError::VerificationError {
+                    msg: format!("Call to an unknown function {func_index:?}"),
+                }
+                .into());
+            };
+            let call_op = if usize::from(func_index) < import_count {
+                ozk::ops::HostCallOp::new_unlinked(ctx, func_sym, func_type).get_operation()
+            } else {
+                ozk::ops::CallOp::new_unlinked(ctx, func_sym, func_type).get_operation()
+            };
+            rewriter.replace_op_with(ctx, wasm_call_op.get_operation(), call_op)?;
+        }
+
+        let mut wasm_return_call_ops = Vec::new();
+        module_op
+            .get_operation()
+            .walk_only::<wasm::ops::ReturnCallOp>(ctx, WalkOrder::PostOrder, &mut |op| {
+                wasm_return_call_ops.push(*op);
+                WalkResult::Advance
+            });
+        for wasm_return_call_op in wasm_return_call_ops {
+            let func_index = wasm_return_call_op.get_func_index(ctx);
+            let (Some(func_sym), Some(func_type)) = (
+                module_op.get_func_sym(ctx, func_index),
+                module_op.get_func_type(ctx, func_index),
+            ) else {
+                return Err(CompilerError::VerificationError {
+                    msg: format!("Tail call to an unknown function {func_index:?}"),
+                }
+                .into());
+            };
+            if usize::from(func_index) < import_count {
+                // the host function has no frame to reuse, call it and return
+                let call_op =
+                    ozk::ops::HostCallOp::new_unlinked(ctx, func_sym, func_type).get_operation();
+                rewriter.set_insertion_point(wasm_return_call_op.get_operation());
+                rewriter.insert_before(ctx, call_op)?;
+                let return_op = wasm::ops::ReturnOp::new_unlinked(ctx).get_operation();
+                rewriter.replace_op_with(ctx, wasm_return_call_op.get_operation(), return_op)?;
+            } else {
+                let call_op =
+                    ozk::ops::ReturnCallOp::new_unlinked(ctx, func_sym, func_type).get_operation();
+                rewriter.replace_op_with(ctx, wasm_return_call_op.get_operation(), call_op)?;
+            }
         }
 
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_pass;
+
+    use super::*;
+
+    #[test]
+    fn host_and_user_calls() {
+        check_wasm_pass(
+            &WasmCallOpToOzkCallOpPass,
+            r#"
+(module
+    (import "env" "ozk_stdlib_pub_output" (func $pub_output (param i64)))
+    (start $main)
+    (func $one (result i64)
+        i64.const 1
+        return)
+    (func $main
+        call $one
+        call $pub_output
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_2_0():
+                    wasm.func @one() -> (si64) {
+                      entry():
+                        wasm.const 0x1: si64
+                        wasm.return
+                    }
+                    wasm.func @main() -> () {
+                      entry():
+                        ozk.call one
+                        ozk.host_call ozk_stdlib_pub_output
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Lowering of the tail calls for the targets that don't grow the call stack on calls.
+
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Replaces `return_call` with `call` followed by `return` and `return_call_indirect` with
+/// `call_indirect` followed by `return`. Only for the targets where a call doesn't consume
+/// the target's stack (e.g. Miden procedures are inlined with `exec`), so an unbounded chain
+/// of tail calls doesn't run out of it. Must run before `call_indirect` is lowered.
+#[derive(Default)]
+pub struct WasmReturnCallToCallPass;
+
+impl Pass for WasmReturnCallToCallPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut return_call_ops = Vec::new();
+        let mut return_call_indirect_ops = Vec::new();
+        op.walk(ctx, WalkOrder::PostOrder, &mut |op| {
+            let opop = op.deref(ctx).get_op(ctx);
+            if let Some(return_call_op) = opop.downcast_ref::<wasm::ReturnCallOp>() {
+                return_call_ops.push(*return_call_op);
+            } else if let Some(return_call_indirect_op) =
+                opop.downcast_ref::<wasm::ReturnCallIndirectOp>()
+            {
+                return_call_indirect_ops.push(*return_call_indirect_op);
+            }
+            WalkResult::Advance
+        });
+        for return_call_op in return_call_ops {
+            let func_index = return_call_op.get_func_index(ctx);
+            let call_op = wasm::CallOp::new_unlinked(ctx, func_index).get_operation();
+            replace_with_call_and_return(ctx, return_call_op.get_operation(), call_op);
+        }
+        for return_call_indirect_op in return_call_indirect_ops {
+            let type_index = return_call_indirect_op.get_type_index(ctx);
+            let table_index = return_call_indirect_op.get_table_index(ctx);
+            let func_type = return_call_indirect_op.get_func_type_attr(ctx);
+            let call_op =
+                wasm::CallIndirectOp::new_unlinked(ctx, type_index, table_index, func_type)
+                    .get_operation();
+            replace_with_call_and_return(ctx, return_call_indirect_op.get_operation(), call_op);
+        }
+        Ok(())
+    }
+}
+
+fn replace_with_call_and_return(ctx: &mut Context, op: Ptr<Operation>, call_op: Ptr<Operation>) {
+    call_op.insert_before(ctx, op);
+    wasm::ReturnOp::new_unlinked(ctx)
+        .get_operation()
+        .insert_before(ctx, op);
+    op.unlink(ctx);
+}
+
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_pass;
+
+    use super::*;
+
+    #[test]
+    fn return_calls_to_call_and_return() {
+        check_wasm_pass(
+            &WasmReturnCallToCallPass,
+            r#"
+(module
+    (type $unary (func (param i32) (result i32)))
+    (table 1 funcref)
+    (elem (i32.const 0) $inc)
+    (start $main)
+    (func $inc (type $unary)
+        local.get 0
+        i32.const 1
+        i32.add
+        return)
+    (func $direct (type $unary)
+        local.get 0
+        return_call $inc)
+    (func $indirect (type $unary)
+        local.get 0
+        i32.const 0
+        return_call_indirect (type $unary))
+    (func $main
+        i32.const 1
+        call $direct
+        call $indirect
+        drop
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_4_0():
+                    wasm.func @inc(si32) -> (si32) {
+                      entry():
+                        wasm.local.get 0
+                        wasm.const 0x1: si32
+                        wasm.i32.add
+                        wasm.return
+                    }
+                    wasm.func @direct(si32) -> (si32) {
+                      entry():
+                        wasm.local.get 0
+                        wasm.call 0
+                        wasm.return
+                    }
+                    wasm.func @indirect(si32) -> (si32) {
+                      entry():
+                        wasm.local.get 0
+                        wasm.const 0x0: si32
+                        wasm.call_indirect 0 (type 0)
+                        wasm.return
+                    }
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.const 0x1: si32
+                        wasm.call 1
+                        wasm.call 2
+                        wasm.drop
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::anyhow;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin;
+use pliron::dialects::builtin::op_interfaces::SingleBlockRegionInterface;
+use pliron::linked_list::ContainsLinkedList;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::pass::PassManager;
+
+/// Name of the module created for the function compiled in isolation
+pub const SINGLE_FUNC_MODULE_NAME: &str = "single_func";
+
+/// Put the function into a new module (as the start function), run the target passes on it
+/// and return the lowered module op (e.g. a target program op).
+/// Used to unit-test the lowering of a particular construct without crafting a whole module.
+/// The function must not be linked to a module (see [wasm::ModuleOp::remove_function])
+/// and must not call other functions.
+pub fn run_passes_on_func(
+    ctx: &mut Context,
+    func_op: wasm::FuncOp,
+    pass_manager: &PassManager,
+) -> Result<Ptr<Operation>, anyhow::Error> {
+    let module_op = wasm::ModuleOp::new_single_func(ctx, SINGLE_FUNC_MODULE_NAME, func_op);
+    run_passes_on_module(ctx, module_op, pass_manager)
+}
+
+/// Run the target passes on the module and return the lowered module op
+/// (e.g. a target program op).
+pub fn run_passes_on_module(
+    ctx: &mut Context,
+    module_op: wasm::ModuleOp,
+    pass_manager: &PassManager,
+) -> Result<Ptr<Operation>, anyhow::Error> {
+    // we need to wrap the wasm in an op because passes cannot replace the root op
+    let wrapper_module = builtin::ops::ModuleOp::new(ctx, "wrapper");
+    module_op
+        .get_operation()
+        .insert_at_back(wrapper_module.get_body(ctx, 0), ctx);
+    pass_manager.run(ctx, wrapper_module.get_operation())?;
+    wrapper_module
+        .get_body(ctx, 0)
+        .deref(ctx)
+        .iter(ctx)
+        .next()
+        .ok_or_else(|| anyhow!("the passes removed the module"))
+}
@@ -0,0 +1,245 @@
+//! Debug instrumentation detecting the overflow of the shadow stack (the stack in the linear
+//! memory that Rust and C keep for the locals whose address is taken). The stack grows down
+//! from the address in the stack pointer global (`__stack_pointer`), nothing stops it from
+//! running into the data below it (or wrapping around the address 0), and the resulting memory
+//! corruption shows up only as a wrong output. With this pass every move of the stack pointer
+//! checks it against the [StackBounds] and otherwise writes [STACK_OVERFLOW_CODE] followed by
+//! the stack pointer to the debug output and aborts.
+
+use ozk_ozk_dialect::ops as ozk;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::types::BinaryOpcode;
+use ozk_wasm_dialect::types::FuncIndex;
+use ozk_wasm_dialect::types::GlobalIndex;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+use crate::wasm::outline::append_function_with_body;
+
+/// Symbol of the function checking the stack pointer
+pub const STACK_CHECK_FUNC_NAME: &str = "stack_check";
+/// Written to the debug output before aborting on a stack pointer out of the [StackBounds]
+pub const STACK_OVERFLOW_CODE: u32 = 0xa5a0_0003;
+
+/// The shadow stack: the global holding the stack pointer and the addresses it may take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackBounds {
+    /// The stack pointer global
+    pub stack_pointer: GlobalIndex,
+    /// The lowest valid stack pointer (the stack is full)
+    pub bottom: u32,
+    /// The highest valid stack pointer (the initial one, the stack is empty)
+    pub top: u32,
+}
+
+impl Default for StackBounds {
+    /// The layout emitted by `rust-lld` for `wasm32` by default (`--stack-first`): the
+    /// `__stack_pointer` is the first global and the 1 MiB stack is at the start of the memory.
+    fn default() -> Self {
+        Self {
+            stack_pointer: GlobalIndex::from(0),
+            bottom: 0,
+            top: 1024 * 1024,
+        }
+    }
+}
+
+/// Checks the new value before every `global.set` of the stack pointer (i.e. in the prologue
+/// and the epilogue of every function with a stack frame) with a call to the
+/// [STACK_CHECK_FUNC_NAME] function. Catches the overflow in the function allocating the frame
+/// that doesn't fit, before it writes anything into it. Optional, for debugging. Must run before
+/// the globals are lowered.
+pub struct WasmStackCheckPass {
+    bounds: StackBounds,
+}
+
+impl WasmStackCheckPass {
+    pub fn new(bounds: StackBounds) -> Self {
+        Self { bounds }
+    }
+}
+
+impl Pass for WasmStackCheckPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            self.instrument_module(ctx, &module_op);
+        }
+        Ok(())
+    }
+}
+
+impl WasmStackCheckPass {
+    fn instrument_module(&self, ctx: &mut Context, module_op: &wasm::ModuleOp) {
+        let mut sp_set_ops = Vec::new();
+        module_op.get_operation().walk_only::<wasm::GlobalSetOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |global_set_op| {
+                if global_set_op.get_index(ctx) == self.bounds.stack_pointer {
+                    sp_set_ops.push(*global_set_op);
+                }
+                WalkResult::Advance
+            },
+        );
+        if sp_set_ops.is_empty() {
+            return;
+        }
+        let check_func_index = self.insert_stack_check_func(ctx, module_op);
+        for sp_set_op in sp_set_ops {
+            wasm::CallOp::new_unlinked(ctx, check_func_index)
+                .get_operation()
+                .insert_before(ctx, sp_set_op.get_operation());
+        }
+    }
+
+    /// Appends the function that takes the new stack pointer, aborts if it's out of bounds
+    /// and returns it otherwise.
+    fn insert_stack_check_func(&self, ctx: &mut Context, module_op: &wasm::ModuleOp) -> FuncIndex {
+        let i32_ty = i32_type(ctx);
+        let mut ops = vec![
+            wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+            wasm::ConstantOp::new_i32_unlinked(ctx, self.bounds.bottom as i32).get_operation(),
+            wasm::I32LtUOp::new_unlinked(ctx).get_operation(),
+            // a wrap around the address 0 ends up here too
+            wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+            wasm::ConstantOp::new_i32_unlinked(ctx, self.bounds.top as i32).get_operation(),
+            wasm::I32GtUOp::new_unlinked(ctx).get_operation(),
+            wasm::BinaryArithOp::new_i32_unlinked(ctx, BinaryOpcode::Or).get_operation(),
+        ];
+        let if_type = FunctionType::get(ctx, vec![], vec![]);
+        let if_op = wasm::IfOp::new_unlinked(ctx, if_type);
+        let abort_ops = vec![
+            wasm::ConstantOp::new_i32_unlinked(ctx, STACK_OVERFLOW_CODE as i32).get_operation(),
+            ozk::DebugPrintOp::new_unlinked(ctx).get_operation(),
+            wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation(),
+            ozk::DebugPrintOp::new_unlinked(ctx).get_operation(),
+            ozk::TrapOp::new_unlinked(ctx).get_operation(),
+        ];
+        for op in abort_ops {
+            op.insert_at_back(if_op.get_then_block(ctx), ctx);
+        }
+        ops.push(if_op.get_operation());
+        ops.push(wasm::LocalGetOp::new_unlinked(ctx, 0).get_operation());
+        ops.push(wasm::ReturnOp::new_unlinked(ctx).get_operation());
+        let ty = FunctionType::get(ctx, vec![i32_ty], vec![i32_ty]);
+        append_function_with_body(ctx, module_op, STACK_CHECK_FUNC_NAME, ty, ops).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_pass;
+
+    use super::*;
+
+    #[test]
+    fn stack_pointer_sets_checked() {
+        check_wasm_pass(
+            &WasmStackCheckPass::new(StackBounds {
+                stack_pointer: GlobalIndex::from(0),
+                bottom: 0x100,
+                top: 0x1000,
+            }),
+            r#"
+(module
+    (global $__stack_pointer (mut i32) (i32.const 4096))
+    (global $counter (mut i32) (i32.const 0))
+    (start $main)
+    (func $main
+        global.get $__stack_pointer
+        i32.const 16
+        i32.sub
+        global.set $__stack_pointer
+        i32.const 1
+        global.set $counter
+        global.get $__stack_pointer
+        i32.const 16
+        i32.add
+        global.set $__stack_pointer
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.global mut si32 = i32.const 4096
+                    wasm.global mut si32 = i32.const 0
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.global.get 0
+                        wasm.const 0x10: si32
+                        wasm.i32.sub
+                        wasm.call 1
+                        wasm.global.set 0
+                        wasm.const 0x1: si32
+                        wasm.global.set 1
+                        wasm.global.get 0
+                        wasm.const 0x10: si32
+                        wasm.i32.add
+                        wasm.call 1
+                        wasm.global.set 0
+                        wasm.return
+                    }
+                    wasm.func @stack_check(si32) -> (si32) {
+                      entry():
+                        wasm.local.get 0
+                        wasm.const 0x100: si32
+                        wasm.i32.lt_u
+                        wasm.local.get 0
+                        wasm.const 0x1000: si32
+                        wasm.i32.gt_u
+                        wasm.i32.or
+                        wasm.if () -> () {
+                          then():
+                            wasm.const 0xa5a00003: si32
+                            ozk.debug_print
+                            wasm.local.get 0
+                            ozk.debug_print
+                            ozk.trap
+                        } else {
+                          else():
+                        }
+                        wasm.local.get 0
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn no_stack_pointer_no_check_func() {
+        check_wasm_pass(
+            &WasmStackCheckPass::new(StackBounds::default()),
+            r#"
+(module
+    (start $main)
+    (func $main
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      entry():
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+}
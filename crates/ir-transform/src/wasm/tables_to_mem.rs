@@ -0,0 +1,362 @@
+//! Lowering of the tables that are read or changed at runtime (`table.get`, `table.set`),
+//! e.g. the vtables emitted by Rust. A function reference is an i32 key, the function index
+//! plus one (zero is the null reference, so the zero-initialized memory is a table of nulls).
+//! The tables are copied by a prologue function into the target memory outside of the Wasm
+//! memory, an entry per cell, starting at the address provided by the target's memory layout.
+//! The entries are accessed by the target runtime (see [super::link_runtime]).
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::types::i32_type;
+use ozk_ozk_dialect::types::FuncSym;
+use ozk_wasm_dialect::ops as wasm;
+use ozk_wasm_dialect::ops::MemAccessOpValueType;
+use ozk_wasm_dialect::types::FuncIndex;
+use ozk_wasm_dialect::types::MemAddress;
+use ozk_wasm_dialect::types::PrologueStage;
+use ozk_wasm_dialect::types::TableIndex;
+use pliron::basic_block::BasicBlock;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+use crate::byte_layout::CELL_BYTES;
+
+use super::call_indirect::insert_dispatch;
+use super::link_runtime::RUNTIME_IMPORT_MODULE;
+
+/// Symbol of the function that copies the tables into the target memory (a prologue function)
+pub const INIT_TABLES_FUNC_NAME: &str = "init_tables";
+/// Symbol of the runtime function implementing `table.get`
+pub const TABLE_GET_FUNC_NAME: &str = "table_get";
+/// Symbol of the runtime function implementing `table.set`
+pub const TABLE_SET_FUNC_NAME: &str = "table_set";
+
+/// In the modules using `table.get` or `table.set`, replaces them with the calls to the
+/// [TABLE_GET_FUNC_NAME] and [TABLE_SET_FUNC_NAME] runtime functions (imported from
+/// [RUNTIME_IMPORT_MODULE]) and `call_indirect` with the dispatch on the entry read by
+/// [TABLE_GET_FUNC_NAME]. The runtime functions take the index (and the reference to set),
+/// the table address and the table size. Adds the [INIT_TABLES_FUNC_NAME] prologue function,
+/// so it must run before the `call_indirect` lowering, the runtime is linked and the prologue
+/// is emitted.
+pub struct WasmTablesToMemPass {
+    start_addr: MemAddress,
+    max_bytes: u32,
+}
+
+impl WasmTablesToMemPass {
+    /// `max_bytes` is the size of the target memory region reserved for the tables
+    pub fn new(start_addr: MemAddress, max_bytes: u32) -> Self {
+        Self {
+            start_addr,
+            max_bytes,
+        }
+    }
+}
+
+impl Pass for WasmTablesToMemPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            self.lower_tables(ctx, &module_op)?;
+        }
+        Ok(())
+    }
+}
+
+impl WasmTablesToMemPass {
+    fn lower_tables(
+        &self,
+        ctx: &mut Context,
+        module_op: &wasm::ModuleOp,
+    ) -> Result<(), anyhow::Error> {
+        let mut get_ops = Vec::new();
+        let mut set_ops = Vec::new();
+        let mut call_indirect_ops = Vec::new();
+        module_op
+            .get_operation()
+            .walk(ctx, WalkOrder::PostOrder, &mut |op| {
+                let opop = op.deref(ctx).get_op(ctx);
+                if let Some(get_op) = opop.downcast_ref::<wasm::TableGetOp>() {
+                    get_ops.push(*get_op);
+                } else if let Some(set_op) = opop.downcast_ref::<wasm::TableSetOp>() {
+                    set_ops.push(*set_op);
+                } else if let Some(call_indirect_op) = opop.downcast_ref::<wasm::CallIndirectOp>() {
+                    call_indirect_ops.push(*call_indirect_op);
+                }
+                WalkResult::Advance
+            });
+        if get_ops.is_empty() && set_ops.is_empty() {
+            return Ok(());
+        }
+        let i32_ty = i32_type(ctx);
+        // the imports shift the function indices, so they go first
+        let table_get_func_index = if !get_ops.is_empty() || !call_indirect_ops.is_empty() {
+            let ty = FunctionType::get(ctx, vec![i32_ty; 3], vec![i32_ty]);
+            Some(module_op.append_import(
+                ctx,
+                FuncSym::from(TABLE_GET_FUNC_NAME),
+                ty,
+                RUNTIME_IMPORT_MODULE,
+            ))
+        } else {
+            None
+        };
+        let table_set_func_index = if !set_ops.is_empty() {
+            let ty = FunctionType::get(ctx, vec![i32_ty; 4], vec![]);
+            Some(module_op.append_import(
+                ctx,
+                FuncSym::from(TABLE_SET_FUNC_NAME),
+                ty,
+                RUNTIME_IMPORT_MODULE,
+            ))
+        } else {
+            None
+        };
+
+        // (address, size) of each table
+        let mut tables = Vec::new();
+        let mut addr = u32::from(self.start_addr) as u64;
+        for size in module_op.get_table_sizes(ctx) {
+            tables.push((addr as u32, size));
+            addr += size as u64 * CELL_BYTES as u64;
+        }
+        let total_bytes = addr - u32::from(self.start_addr) as u64;
+        if total_bytes > self.max_bytes as u64 {
+            return Err(anyhow!(
+                "tables take {total_bytes} bytes, only {} bytes are reserved",
+                self.max_bytes
+            ));
+        }
+        let table_of = |table_index: TableIndex| {
+            tables
+                .get(u32::from(table_index) as usize)
+                .copied()
+                .ok_or_else(|| anyhow!("undefined table {table_index}"))
+        };
+        let access_ops = |ctx: &mut Context, (addr, size): (u32, u32), func_index: FuncIndex| {
+            vec![
+                wasm::ConstantOp::new_i32_unlinked(ctx, addr as i32).get_operation(),
+                wasm::ConstantOp::new_i32_unlinked(ctx, size as i32).get_operation(),
+                wasm::CallOp::new_unlinked(ctx, func_index).get_operation(),
+            ]
+        };
+
+        if let Some(table_get_func_index) = table_get_func_index {
+            for get_op in get_ops {
+                let table = table_of(get_op.get_table_index(ctx))?;
+                for op in access_ops(ctx, table, table_get_func_index) {
+                    op.insert_before(ctx, get_op.get_operation());
+                }
+                get_op.get_operation().unlink(ctx);
+            }
+            // any function in the element segments can end up in any table
+            let mut ref_funcs: Vec<FuncIndex> = module_op
+                .get_elem_segments(ctx)
+                .into_iter()
+                .flat_map(|seg| seg.func_indices)
+                .collect();
+            ref_funcs.sort();
+            ref_funcs.dedup();
+            for call_indirect_op in call_indirect_ops {
+                let op = call_indirect_op.get_operation();
+                let table = table_of(call_indirect_op.get_table_index(ctx))?;
+                let func_type = call_indirect_op.get_func_type(ctx);
+                let mut targets = Vec::new();
+                for func_index in &ref_funcs {
+                    let callee_type =
+                        module_op.get_func_type(ctx, *func_index).ok_or_else(|| {
+                            anyhow!("table entry refers to an unknown function {func_index}")
+                        })?;
+                    if callee_type.get_inputs() == func_type.get_inputs()
+                        && callee_type.get_results() == func_type.get_results()
+                    {
+                        targets.push((func_ref(*func_index), *func_index));
+                    }
+                }
+                for access_op in access_ops(ctx, table, table_get_func_index) {
+                    access_op.insert_before(ctx, op);
+                }
+                insert_dispatch(ctx, op, &func_type, targets);
+                op.unlink(ctx);
+            }
+        }
+        if let Some(table_set_func_index) = table_set_func_index {
+            for set_op in set_ops {
+                let table = table_of(set_op.get_table_index(ctx))?;
+                for op in access_ops(ctx, table, table_set_func_index) {
+                    op.insert_before(ctx, set_op.get_operation());
+                }
+                set_op.get_operation().unlink(ctx);
+            }
+        }
+
+        let mut ops = Vec::new();
+        for (table_index, (addr, _)) in tables.iter().enumerate() {
+            let entries = module_op.get_table_entries(ctx, TableIndex::from(table_index as u32));
+            for (position, entry) in entries.into_iter().enumerate() {
+                let Some(func_index) = entry else {
+                    continue;
+                };
+                let entry_addr = addr + position as u32 * CELL_BYTES;
+                ops.extend([
+                    wasm::ConstantOp::new_i32_unlinked(ctx, entry_addr as i32).get_operation(),
+                    wasm::ConstantOp::new_i32_unlinked(ctx, func_ref(func_index)).get_operation(),
+                    wasm::StoreOp::new_unlinked(ctx, MemAccessOpValueType::I32).get_operation(),
+                ]);
+            }
+        }
+        ops.push(wasm::ReturnOp::new_unlinked(ctx).get_operation());
+        let entry_block = BasicBlock::new(ctx, Some("entry".to_string()), vec![]);
+        for op in ops {
+            op.insert_at_back(entry_block, ctx);
+        }
+        let ty = FunctionType::get(ctx, vec![], vec![]);
+        let func_op = wasm::FuncOp::new_unlinked_with_block(
+            ctx,
+            FuncSym::from(INIT_TABLES_FUNC_NAME),
+            ty,
+            entry_block,
+            vec![],
+        );
+        module_op.add_prologue_function(ctx, func_op, PrologueStage::MemoryInit);
+        Ok(())
+    }
+}
+
+/// The reference to the function (its key in the tables)
+fn func_ref(func_index: FuncIndex) -> i32 {
+    u32::from(func_index) as i32 + 1
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+    use pliron::with_context::AttachContext;
+
+    use crate::tests_util::parse_wasm_module;
+
+    use super::*;
+
+    const WAT_TABLE_OPS: &str = r#"
+(module
+    (type $nullary (func (result i32)))
+    (table 2 funcref)
+    (elem (i32.const 0) $one $two)
+    (start $main)
+    (func $one (result i32)
+        i32.const 1
+        return)
+    (func $two (result i32)
+        i32.const 2
+        return)
+    (func $main
+        i32.const 0
+        i32.const 1
+        table.get 0
+        table.set 0
+        i32.const 0
+        call_indirect (type $nullary)
+        drop
+        return)
+)
+"#;
+
+    #[test]
+    fn table_ops_to_runtime_calls() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT_TABLE_OPS);
+        WasmTablesToMemPass::new(MemAddress::from(0x2000), 64)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap();
+        assert_eq!(
+            module_op.get_func_syms(&ctx)[..2],
+            [
+                FuncSym::from(TABLE_GET_FUNC_NAME),
+                FuncSym::from(TABLE_SET_FUNC_NAME)
+            ]
+        );
+        assert_eq!(
+            module_op.get_prologue_functions(&ctx),
+            vec![FuncSym::from(INIT_TABLES_FUNC_NAME)]
+        );
+        let main_func = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        // $one and $two are the functions 2 and 3 after the imports
+        expect![[r#"
+            wasm.func @main() -> () {
+              entry():
+                wasm.const 0x0: si32
+                wasm.const 0x1: si32
+                wasm.const 0x2000: si32
+                wasm.const 0x2: si32
+                wasm.call 0
+                wasm.const 0x2000: si32
+                wasm.const 0x2: si32
+                wasm.call 1
+                wasm.const 0x0: si32
+                wasm.const 0x2000: si32
+                wasm.const 0x2: si32
+                wasm.call 0
+                ozk.reserved_set scratch
+                ozk.reserved_get scratch
+                wasm.const 0x3: si32
+                wasm.i32.eq
+                wasm.if () -> (si32) {
+                  then():
+                    wasm.call 2
+                } else {
+                  else():
+                    ozk.reserved_get scratch
+                    wasm.const 0x4: si32
+                    wasm.i32.eq
+                    wasm.if () -> (si32) {
+                      then():
+                        wasm.call 3
+                    } else {
+                      else():
+                        wasm.const 0x0: si32
+                        ozk.assert
+                    }
+                }
+                wasm.drop
+                wasm.return
+            }"#]]
+        .assert_eq(&main_func.with_ctx(&ctx).to_string());
+        let init_func = module_op
+            .get_func(&ctx, &FuncSym::from(INIT_TABLES_FUNC_NAME))
+            .unwrap();
+        expect![[r#"
+            wasm.func @init_tables() -> () {
+              entry():
+                wasm.const 0x2000: si32
+                wasm.const 0x3: si32
+                wasm.store I32
+                wasm.const 0x2004: si32
+                wasm.const 0x4: si32
+                wasm.store I32
+                wasm.return
+            }"#]]
+        .assert_eq(&init_func.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn tables_over_reserved_space() {
+        let (mut ctx, module_op) = parse_wasm_module(WAT_TABLE_OPS);
+        let err = WasmTablesToMemPass::new(MemAddress::from(0x2000), 4)
+            .run_on_operation(&mut ctx, module_op.get_operation())
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("only 4 bytes are reserved"),
+            "{err}"
+        );
+    }
+}
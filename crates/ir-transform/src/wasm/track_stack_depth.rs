@@ -1,10 +1,15 @@
 use ozk_wasm_dialect::op_interfaces::StackDepthChange;
 use ozk_wasm_dialect::op_interfaces::TrackedStackDepth;
 use ozk_wasm_dialect::ops as wasm;
+use pliron::basic_block::BasicBlock;
 use pliron::context::Context;
 use pliron::context::Ptr;
 use pliron::dialect_conversion::apply_partial_conversion;
 use pliron::dialect_conversion::ConversionTarget;
+use pliron::dialects::builtin::op_interfaces::SymbolOpInterface;
+use pliron::dialects::builtin::types::FunctionType;
+use pliron::error::CompilerError;
+use pliron::linked_list::ContainsLinkedList;
 use pliron::op::op_cast;
 use pliron::op::Op;
 use pliron::operation::Operation;
@@ -51,7 +56,7 @@ impl RewritePattern for WasmWriteStackDepth {
         Ok(op
             .deref(ctx)
             .get_op(ctx)
-            .downcast_ref::<wasm::FuncOp>()
+            .downcast_ref::<wasm::ModuleOp>()
             .is_some())
     }
 
@@ -62,25 +67,67 @@ impl RewritePattern for WasmWriteStackDepth {
         op: Ptr<Operation>,
         _rewriter: &mut dyn PatternRewriter,
     ) -> Result<(), anyhow::Error> {
-        let Ok(func_op) = op
+        let Ok(module_op) = op
             .deref(ctx)
             .get_op(ctx)
-            .downcast::<wasm::FuncOp>() else {
+            .downcast::<wasm::ModuleOp>() else {
             panic!("unexpected op {}", op.deref(ctx).with_ctx(ctx));
         };
-        let mut stack_depth: i32 = if self.reserve_space_for_locals {
+        let mut func_ops = Vec::new();
+        module_op.get_operation().walk_only::<wasm::FuncOp>(
+            ctx,
+            WalkOrder::PostOrder,
+            &mut |func_op| {
+                func_ops.push(*func_op);
+                WalkResult::Advance
+            },
+        );
+        for func_op in func_ops {
+            self.write_func_stack_depth(ctx, &module_op, &func_op)?;
+        }
+        Ok(())
+    }
+}
+
+impl WasmWriteStackDepth {
+    fn write_func_stack_depth(
+        &self,
+        ctx: &mut Context,
+        module_op: &wasm::ModuleOp,
+        func_op: &wasm::FuncOp,
+    ) -> Result<(), anyhow::Error> {
+        let stack_depth: i32 = if self.reserve_space_for_locals {
             // reserve space for local variables
             func_op.get_locals(ctx).len() as i32
         } else {
             0
         };
-        let mut ops = Vec::new();
-        func_op
-            .get_operation()
-            .walk(ctx, WalkOrder::PostOrder, &mut |op| {
-                ops.push(op);
-                WalkResult::Advance
-            });
+        let mut max_stack_depth = stack_depth;
+        self.write_block_stack_depth(
+            ctx,
+            module_op,
+            func_op,
+            func_op.get_entry_block(ctx),
+            stack_depth,
+            &mut max_stack_depth,
+        )?;
+        func_op.set_max_stack_depth(ctx, max_stack_depth.into());
+        Ok(())
+    }
+
+    /// Record the stack depth before every op of the block (and the nested blocks),
+    /// return the stack depth at the block end. Raises `max_stack_depth` to the deepest
+    /// stack seen.
+    fn write_block_stack_depth(
+        &self,
+        ctx: &mut Context,
+        module_op: &wasm::ModuleOp,
+        func_op: &wasm::FuncOp,
+        block: Ptr<BasicBlock>,
+        mut stack_depth: i32,
+        max_stack_depth: &mut i32,
+    ) -> Result<i32, anyhow::Error> {
+        let ops: Vec<Ptr<Operation>> = block.deref(ctx).iter(ctx).collect();
         for op in ops {
             let op_op = op.deref(ctx).get_op(ctx);
             if let Some(tracked_op) = op_cast::<dyn TrackedStackDepth>(op_op.as_ref()) {
@@ -88,8 +135,395 @@ impl RewritePattern for WasmWriteStackDepth {
             }
             if let Some(stack_change_op) = op_cast::<dyn StackDepthChange>(op_op.as_ref()) {
                 stack_depth += stack_change_op.get_stack_depth_change(ctx);
+            } else if let Some(func_index) = op_op
+                .downcast_ref::<wasm::CallOp>()
+                .map(|call_op| call_op.get_func_index(ctx))
+                .or_else(|| {
+                    op_op
+                        .downcast_ref::<wasm::ReturnCallOp>()
+                        .map(|call_op| call_op.get_func_index(ctx))
+                })
+            {
+                // not resolved yet, take the callee type from the module's function table
+                let Some(func_type) = module_op.get_func_type(ctx, func_index) else {
+                    return Err(CompilerError::VerificationError {
+                        msg: format!(
+                            "Cannot determine the stack depth change of the call to an unknown function {func_index:?} in {}",
+                            func_op.get_symbol_name(ctx)
+                        ),
+                    }
+                    .into());
+                };
+                stack_depth +=
+                    func_type.get_results().len() as i32 - func_type.get_inputs().len() as i32;
+            }
+            // the nested blocks start at the depth after the op (after the `if` condition is
+            // popped), every region of the op (`if` branches) starts at the same depth
+            // and the depth after the op is given by its type (a body ending with a branch
+            // may leave any depth)
+            let op_ref = op.deref(ctx);
+            let nested_blocks: Vec<Ptr<BasicBlock>> = (0..op_ref.get_num_regions())
+                .filter_map(|idx| op_ref.get_region(idx).deref(ctx).get_head())
+                .collect();
+            drop(op_ref);
+            let mut depth_after_op = None;
+            for nested_block in nested_blocks {
+                let depth = self.write_block_stack_depth(
+                    ctx,
+                    module_op,
+                    func_op,
+                    nested_block,
+                    stack_depth,
+                    max_stack_depth,
+                )?;
+                depth_after_op.get_or_insert(depth);
             }
+            if let Some(depth) =
+                construct_depth_after(ctx, op_op.as_ref(), stack_depth).or(depth_after_op)
+            {
+                stack_depth = depth;
+            }
+            *max_stack_depth = (*max_stack_depth).max(stack_depth);
         }
-        Ok(())
+        Ok(stack_depth)
+    }
+}
+
+/// The stack depth after the block, loop or `if` op: the params are replaced with the results
+/// (`stack_depth` is the depth before the body, i.e. after the `if` condition is popped)
+fn construct_depth_after(ctx: &Context, op_op: &dyn Op, stack_depth: i32) -> Option<i32> {
+    let ty = if let Some(block_op) = op_op.downcast_ref::<wasm::BlockOp>() {
+        block_op.get_type(ctx)
+    } else if let Some(loop_op) = op_op.downcast_ref::<wasm::LoopOp>() {
+        loop_op.get_type(ctx)
+    } else if let Some(if_op) = op_op.downcast_ref::<wasm::IfOp>() {
+        if_op.get_type(ctx)
+    } else {
+        return None;
+    };
+    let ty_ref = ty.deref(ctx);
+    let func_type = ty_ref.downcast_ref::<FunctionType>()?;
+    Some(stack_depth - func_type.get_inputs().len() as i32 + func_type.get_results().len() as i32)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::expect;
+    use expect_test::Expect;
+    use ozk_ozk_dialect::types::FuncSym;
+
+    use crate::tests_util::run_wasm_pass_wrapped;
+
+    use super::*;
+
+    /// Dump every op with the recorded stack depth before it (`-` if not tracked)
+    fn dump_block_stack_depths(
+        ctx: &Context,
+        block: Ptr<BasicBlock>,
+        indent: usize,
+        out: &mut String,
+    ) {
+        for op in block.deref(ctx).iter(ctx) {
+            let opop = op.deref(ctx).get_op(ctx);
+            let depth = op_cast::<dyn TrackedStackDepth>(opop.as_ref())
+                .map(|tracked_op| i32::from(tracked_op.get_stack_depth(ctx)).to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let op_ref = op.deref(ctx);
+            let bodies: Vec<Ptr<BasicBlock>> = (0..op_ref.get_num_regions())
+                .filter_map(|idx| op_ref.get_region(idx).deref(ctx).get_head())
+                .collect();
+            let text = if bodies.is_empty() {
+                opop.with_ctx(ctx).to_string()
+            } else {
+                opop.get_opid().with_ctx(ctx).to_string()
+            };
+            writeln!(out, "{:indent$}[{depth}] {text}", "").unwrap();
+            for (idx, body) in bodies.into_iter().enumerate() {
+                if idx > 0 {
+                    writeln!(out, "{:indent$}else", "").unwrap();
+                }
+                dump_block_stack_depths(ctx, body, indent + 2, out);
+            }
+        }
+    }
+
+    fn check_stack_depths(wat: &str, expected: Expect) {
+        let (ctx, module_op) = run_wasm_pass_wrapped(
+            &WasmTrackStackDepthPass::new_reserve_space_for_locals(),
+            wat,
+        );
+        let mut out = String::new();
+        module_op.get_operation().walk_only::<wasm::FuncOp>(
+            &ctx,
+            WalkOrder::PostOrder,
+            &mut |func_op| {
+                writeln!(out, "{}:", func_op.get_symbol_name(&ctx)).unwrap();
+                dump_block_stack_depths(&ctx, func_op.get_entry_block(&ctx), 2, &mut out);
+                WalkResult::Advance
+            },
+        );
+        expected.assert_eq(&out);
+    }
+
+    #[test]
+    fn unresolved_calls_to_imported_and_defined_funcs() {
+        let (ctx, module_op) = run_wasm_pass_wrapped(
+            &WasmTrackStackDepthPass::new_reserve_space_for_locals(),
+            r#"
+(module
+    (import "env" "ozk_stdlib_pub_input" (func $pub_input (result i64)))
+    (start $main)
+    (func $add (param i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        i32.add
+        return)
+    (func $main
+        call $pub_input
+        call $pub_input
+        i32.const 1
+        i32.const 2
+        call $add
+        return)
+)"#,
+        );
+        let mut return_depths = Vec::new();
+        module_op.get_operation().walk_only::<wasm::ReturnOp>(
+            &ctx,
+            WalkOrder::PostOrder,
+            &mut |return_op| {
+                return_depths.push(i32::from(return_op.get_stack_depth(&ctx)));
+                WalkResult::Advance
+            },
+        );
+        // $add: 2 locals (params) + 1 result, $main: 2 imported results + 1 $add result
+        assert_eq!(return_depths, vec![3, 3]);
+    }
+
+    #[test]
+    fn max_stack_depth_in_func_header() {
+        let (ctx, module_op) = run_wasm_pass_wrapped(
+            &WasmTrackStackDepthPass::new_reserve_space_for_locals(),
+            r#"
+(module
+    (start $main)
+    (func $main (local i32)
+        i32.const 1
+        local.set 0
+        return)
+)"#,
+        );
+        let func_op = module_op.get_func(&ctx, &FuncSym::from("main")).unwrap();
+        expect![[r#"
+            wasm.func @main() -> () {
+              // locals: [0: si32], max stack depth: 2
+              entry():
+                wasm.const 0x1: si32
+                wasm.local.set 0
+                wasm.return
+            }"#]]
+        .assert_eq(&func_op.with_ctx(&ctx).to_string());
+    }
+
+    #[test]
+    fn golden_block_br_if() {
+        check_stack_depths(
+            r#"
+(module
+    (start $main)
+    (func $main (local i32)
+        (block
+            i32.const 1
+            local.set 0
+            local.get 0
+            br_if 0
+            i32.const 2
+            local.set 0
+        )
+        return)
+)"#,
+            expect![[r#"
+                main:
+                  [1] wasm.block
+                    [1] wasm.const 0x1: si32
+                    [2] wasm.local.set 0
+                    [1] wasm.local.get 0
+                    [2] wasm.br_if 0
+                    [1] wasm.const 0x2: si32
+                    [2] wasm.local.set 0
+                  [1] wasm.return
+            "#]],
+        );
+    }
+
+    #[test]
+    fn golden_loop_with_block_result() {
+        check_stack_depths(
+            r#"
+(module
+    (start $main)
+    (func $main (local i32)
+        (block (result i32)
+            (loop
+                local.get 0
+                i32.const 1
+                i32.add
+                local.tee 0
+                i32.const 10
+                i32.ne
+                br_if 0
+            )
+            local.get 0
+        )
+        local.set 0
+        return)
+)"#,
+            expect![[r#"
+                main:
+                  [1] wasm.block
+                    [1] wasm.loop
+                      [1] wasm.local.get 0
+                      [2] wasm.const 0x1: si32
+                      [3] wasm.i32.add
+                      [2] wasm.local.tee 0
+                      [2] wasm.const 0xa: si32
+                      [3] wasm.i32.ne
+                      [2] wasm.br_if 0
+                    [1] wasm.local.get 0
+                  [2] wasm.local.set 0
+                  [1] wasm.return
+            "#]],
+        );
+    }
+
+    #[test]
+    fn golden_block_ending_with_br() {
+        // the value below the branch values is discarded, the depth after the block is
+        // given by its type
+        check_stack_depths(
+            r#"
+(module
+    (start $main)
+    (func $main (local i32)
+        (block (result i32)
+            i32.const 1
+            i32.const 2
+            br 0)
+        local.set 0
+        return)
+)"#,
+            expect![[r#"
+                main:
+                  [1] wasm.block
+                    [1] wasm.const 0x1: si32
+                    [2] wasm.const 0x2: si32
+                    [3] wasm.br 0
+                  [2] wasm.local.set 0
+                  [1] wasm.return
+            "#]],
+        );
+    }
+
+    #[test]
+    fn golden_calls_with_args_and_results() {
+        check_stack_depths(
+            r#"
+(module
+    (start $main)
+    (func $sum3 (param i32 i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        i32.add
+        local.get 2
+        i32.add
+        return)
+    (func $consume (param i32))
+    (func $main
+        i32.const 1
+        i32.const 2
+        i32.const 3
+        call $sum3
+        call $consume
+        return)
+)"#,
+            expect![[r#"
+                sum3:
+                  [3] wasm.local.get 0
+                  [4] wasm.local.get 1
+                  [5] wasm.i32.add
+                  [4] wasm.local.get 2
+                  [5] wasm.i32.add
+                  [4] wasm.return
+                consume:
+                main:
+                  [0] wasm.const 0x1: si32
+                  [1] wasm.const 0x2: si32
+                  [2] wasm.const 0x3: si32
+                  [-] wasm.call 0
+                  [-] wasm.call 1
+                  [0] wasm.return
+            "#]],
+        );
+    }
+
+    #[test]
+    fn golden_if_else() {
+        check_stack_depths(
+            r#"
+(module
+    (start $main)
+    (func $main (local i32)
+        i32.const 1
+        (if (result i32)
+            (then
+                i32.const 2)
+            (else
+                i32.const 3
+                i32.const 4
+                i32.add))
+        local.set 0
+        return)
+)"#,
+            expect![[r#"
+                main:
+                  [1] wasm.const 0x1: si32
+                  [2] wasm.if
+                    [1] wasm.const 0x2: si32
+                  else
+                    [1] wasm.const 0x3: si32
+                    [2] wasm.const 0x4: si32
+                    [3] wasm.i32.add
+                  [2] wasm.local.set 0
+                  [1] wasm.return
+            "#]],
+        );
+    }
+
+    #[test]
+    fn max_stack_depth_recorded() {
+        let (ctx, module_op) = run_wasm_pass_wrapped(
+            &WasmTrackStackDepthPass::new_reserve_space_for_locals(),
+            r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 1
+        i32.const 2
+        i32.const 3
+        i32.add
+        i32.add
+        local.set 0
+        return)
+)"#,
+        );
+        let main_func = module_op
+            .get_func(&ctx, &ozk_ozk_dialect::types::FuncSym::from("main"))
+            .unwrap();
+        // the local and three constants
+        assert_eq!(main_func.get_max_stack_depth(&ctx).map(i32::from), Some(4));
     }
 }
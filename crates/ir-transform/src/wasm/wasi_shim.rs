@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use ozk_ozk_dialect::ops as ozk;
+use ozk_wasm_dialect::ops as wasm;
+use pliron::context::Context;
+use pliron::context::Ptr;
+use pliron::op::Op;
+use pliron::operation::Operation;
+use pliron::operation::WalkOrder;
+use pliron::operation::WalkResult;
+use pliron::pass::Pass;
+
+/// Import module of the WASI functions (`wasm32-wasi` target)
+pub const WASI_IMPORT_MODULE: &str = "wasi_snapshot_preview1";
+
+/// Replacement of a call to a WASI function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WasiShim {
+    /// Stop the program with the exit code argument (`proc_exit`)
+    Halt,
+    /// Write the arguments to the debug output and return success (`fd_write`)
+    DebugOutput,
+    /// Abort the program
+    Trap,
+}
+
+/// Replaces the calls to the WASI functions with the ozk equivalents, so that the unmodified
+/// `wasm32-wasi` builds can be compiled (for experimentation).
+/// By default `proc_exit` halts, `fd_write` writes its arguments (fd, iovs pointer, iovs count,
+/// nwritten pointer) to the debug output and the rest (`args_get`, `environ_get`, etc.) trap.
+/// The WASI functions return an i32 errno, the shims return 0 (success).
+/// Should run before [WasmForeignImportsCheckPass](super::foreign_imports::WasmForeignImportsCheckPass).
+pub struct WasmWasiShimPass {
+    shims: BTreeMap<String, WasiShim>,
+}
+
+impl Default for WasmWasiShimPass {
+    fn default() -> Self {
+        Self::new_empty()
+            .with_shim("proc_exit", WasiShim::Halt)
+            .with_shim("fd_write", WasiShim::DebugOutput)
+    }
+}
+
+impl WasmWasiShimPass {
+    /// Create a pass that traps on every WASI function call
+    pub fn new_empty() -> Self {
+        Self {
+            shims: BTreeMap::new(),
+        }
+    }
+
+    /// Set the shim for the WASI function with the given name
+    pub fn with_shim(mut self, name: &str, shim: WasiShim) -> Self {
+        self.shims.insert(name.to_string(), shim);
+        self
+    }
+}
+
+impl Pass for WasmWasiShimPass {
+    fn run_on_operation(&self, ctx: &mut Context, op: Ptr<Operation>) -> Result<(), anyhow::Error> {
+        let mut module_ops = Vec::new();
+        op.walk_only::<wasm::ModuleOp>(ctx, WalkOrder::PostOrder, &mut |module_op| {
+            module_ops.push(*module_op);
+            WalkResult::Advance
+        });
+        for module_op in module_ops {
+            let import_func_modules = module_op.get_import_func_modules(ctx);
+            let import_func_types = module_op.get_import_func_types(ctx);
+            let func_syms = module_op.get_func_syms(ctx);
+            let mut call_ops = Vec::new();
+            module_op.get_operation().walk_only::<wasm::CallOp>(
+                ctx,
+                WalkOrder::PostOrder,
+                &mut |call_op| {
+                    call_ops.push(*call_op);
+                    WalkResult::Advance
+                },
+            );
+            for call_op in call_ops {
+                let index = usize::from(call_op.get_func_index(ctx));
+                let (Some(import_module), Some(func_type), Some(func_sym)) = (
+                    import_func_modules.get(index),
+                    import_func_types.get(index),
+                    func_syms.get(index),
+                ) else {
+                    continue;
+                };
+                if import_module != WASI_IMPORT_MODULE {
+                    continue;
+                }
+                let name = func_sym.as_ref();
+                let param_count = func_type.get_inputs().len();
+                let result_count = func_type.get_results().len();
+                let shim = self.shims.get(name).copied().unwrap_or(WasiShim::Trap);
+                let mut shim_ops = Vec::new();
+                match shim {
+                    WasiShim::Halt => {
+                        if param_count != 1 || result_count != 0 {
+                            return Err(anyhow!(
+                                "{WASI_IMPORT_MODULE}.{name} cannot be shimmed with halt, \
+                                 expected a single (exit code) parameter and no results"
+                            ));
+                        }
+                        shim_ops.push(ozk::HaltOp::new_unlinked(ctx).get_operation());
+                    }
+                    WasiShim::DebugOutput | WasiShim::Trap => {
+                        for _ in 0..param_count {
+                            shim_ops.push(ozk::DebugPrintOp::new_unlinked(ctx).get_operation());
+                        }
+                        if shim == WasiShim::Trap {
+                            shim_ops.push(ozk::TrapOp::new_unlinked(ctx).get_operation());
+                        }
+                        // keep the stack depth of the call for the (unreachable after a trap)
+                        // code that follows
+                        for _ in 0..result_count {
+                            shim_ops
+                                .push(wasm::ConstantOp::new_i32_unlinked(ctx, 0).get_operation());
+                        }
+                    }
+                }
+                for shim_op in shim_ops {
+                    shim_op.insert_before(ctx, call_op.get_operation());
+                }
+                call_op.get_operation().unlink(ctx);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+
+    use expect_test::expect;
+
+    use crate::tests_util::check_wasm_pass;
+
+    use super::*;
+
+    #[test]
+    fn wasi_calls() {
+        check_wasm_pass(
+            &WasmWasiShimPass::default(),
+            r#"
+(module
+    (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "args_sizes_get" (func $args_sizes_get (param i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 1
+        i32.const 8
+        i32.const 1
+        i32.const 16
+        call $fd_write
+        local.set 0
+        i32.const 0
+        i32.const 4
+        call $args_sizes_get
+        local.set 0
+        i32.const 0
+        call $proc_exit
+        return)
+)
+"#,
+            expect![[r#"
+                wasm.module @module_name {
+                  block_1_0():
+                    wasm.func @main() -> () {
+                      // locals: [0: si32]
+                      entry():
+                        wasm.const 0x1: si32
+                        wasm.const 0x8: si32
+                        wasm.const 0x1: si32
+                        wasm.const 0x10: si32
+                        ozk.debug_print
+                        ozk.debug_print
+                        ozk.debug_print
+                        ozk.debug_print
+                        wasm.const 0x0: si32
+                        wasm.local.set 0
+                        wasm.const 0x0: si32
+                        wasm.const 0x4: si32
+                        ozk.debug_print
+                        ozk.debug_print
+                        ozk.trap
+                        wasm.const 0x0: si32
+                        wasm.local.set 0
+                        wasm.const 0x0: si32
+                        ozk.halt
+                        wasm.return
+                    }
+                }"#]],
+        );
+    }
+}
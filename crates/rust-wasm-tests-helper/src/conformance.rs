@@ -0,0 +1,291 @@
+//! Target-agnostic conformance suite.
+//! Each target implements [`ConformanceTarget`] and runs [`run_conformance_suite`] in its tests.
+//! Features that a target does not support are reported as skipped. The cases of the
+//! supported features that are known to fail (the gaps of the target) are reported as
+//! expected failures, so the suite catches both the regressions and the closed gaps.
+
+use std::fmt::Display;
+
+/// Feature covered by a conformance case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    Arithmetic,
+    ControlFlow,
+    Locals,
+    Globals,
+    Memory,
+    Io,
+    /// The cycle counter intrinsic (`ozk_stdlib_clock`)
+    Clock,
+    /// The debug output intrinsic (`ozk_stdlib_debug_print`)
+    DebugPrint,
+}
+
+/// A Wasm program with its inputs and the expected output.
+#[derive(Debug, Clone)]
+pub struct ConformanceCase {
+    pub name: &'static str,
+    pub feature: Feature,
+    pub wat: &'static str,
+    pub input: Vec<u64>,
+    pub secret_input: Vec<u64>,
+    /// Values left on the stack when the program finishes (the top of the stack first)
+    pub expected_output: Vec<u64>,
+}
+
+/// A target (backend) that can be checked against the conformance suite
+pub trait ConformanceTarget {
+    /// Target name used in the report
+    fn name(&self) -> &str;
+
+    /// Returns false if the target does not support the feature (the case is skipped)
+    fn supports(&self, feature: Feature) -> bool;
+
+    /// Names of the cases of the supported features that are known to fail
+    fn expected_failures(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Compile and run the case, returning a description of the mismatch on failure
+    fn check(&self, case: &ConformanceCase) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaseOutcome {
+    Pass,
+    Fail(String),
+    /// Failed as listed in [ConformanceTarget::expected_failures]
+    ExpectedFail(String),
+    Skip,
+}
+
+/// The outcome of every case of the suite for a target
+#[derive(Debug, Clone)]
+pub struct ConformanceReport {
+    pub target: String,
+    pub outcomes: Vec<(&'static str, Feature, CaseOutcome)>,
+}
+
+impl ConformanceReport {
+    pub fn failures(&self) -> Vec<&(&'static str, Feature, CaseOutcome)> {
+        self.outcomes
+            .iter()
+            .filter(|(_, _, outcome)| matches!(outcome, CaseOutcome::Fail(_)))
+            .collect()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failures().is_empty()
+    }
+}
+
+impl Display for ConformanceReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "conformance report for {}:", self.target)?;
+        for (name, feature, outcome) in &self.outcomes {
+            match outcome {
+                CaseOutcome::Pass => writeln!(f, "  PASS {name} ({feature:?})")?,
+                CaseOutcome::Skip => writeln!(f, "  SKIP {name} ({feature:?})")?,
+                CaseOutcome::ExpectedFail(msg) => {
+                    writeln!(f, "  XFAIL {name} ({feature:?}): {msg}")?
+                }
+                CaseOutcome::Fail(msg) => writeln!(f, "  FAIL {name} ({feature:?}): {msg}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run all the conformance cases on the target.
+/// A panic in the target is reported as a failure of the case.
+pub fn run_conformance_suite(target: &dyn ConformanceTarget) -> ConformanceReport {
+    let mut outcomes = Vec::new();
+    let expected_failures = target.expected_failures();
+    for case in conformance_cases() {
+        let outcome = if !target.supports(case.feature) {
+            CaseOutcome::Skip
+        } else {
+            let result =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| target.check(&case)))
+                    .unwrap_or_else(|_| Err("panicked".to_string()));
+            match (result, expected_failures.contains(&case.name)) {
+                (Ok(()), false) => CaseOutcome::Pass,
+                (Ok(()), true) => {
+                    CaseOutcome::Fail("passed, remove it from the expected failures".to_string())
+                }
+                (Err(msg), false) => CaseOutcome::Fail(msg),
+                (Err(msg), true) => CaseOutcome::ExpectedFail(msg),
+            }
+        };
+        outcomes.push((case.name, case.feature, outcome));
+    }
+    ConformanceReport {
+        target: target.name().to_string(),
+        outcomes,
+    }
+}
+
+/// All the conformance cases
+pub fn conformance_cases() -> Vec<ConformanceCase> {
+    vec![
+        ConformanceCase {
+            name: "i32_add",
+            feature: Feature::Arithmetic,
+            wat: r#"
+(module
+    (start $main)
+    (func $main
+        i32.const 3
+        i32.const 4
+        i32.add
+        return)
+)"#,
+            input: vec![],
+            secret_input: vec![],
+            expected_output: vec![7],
+        },
+        ConformanceCase {
+            name: "func_call",
+            feature: Feature::ControlFlow,
+            wat: r#"
+(module
+    (start $main)
+    (func $add (param i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        i32.add
+        return)
+    (func $main
+        i32.const 3
+        i32.const 4
+        call $add
+        return)
+)"#,
+            input: vec![],
+            secret_input: vec![],
+            expected_output: vec![7],
+        },
+        ConformanceCase {
+            name: "block_br",
+            feature: Feature::ControlFlow,
+            wat: r#"
+(module
+    (start $main)
+    (func $main
+        block (result i32)
+            i32.const 3
+            br 0
+        end
+        return)
+)"#,
+            input: vec![],
+            secret_input: vec![],
+            expected_output: vec![3],
+        },
+        ConformanceCase {
+            name: "locals_set_get",
+            feature: Feature::Locals,
+            wat: r#"
+(module
+    (start $main)
+    (func $main
+        (local i32)
+        i32.const 9
+        local.set 0
+        local.get 0
+        return)
+)"#,
+            input: vec![],
+            secret_input: vec![],
+            expected_output: vec![9],
+        },
+        ConformanceCase {
+            name: "globals_set_get",
+            feature: Feature::Globals,
+            wat: r#"
+(module
+    (global $g (mut i32) i32.const 0)
+    (start $main)
+    (func $main
+        i32.const 9
+        global.set $g
+        global.get $g
+        return)
+)"#,
+            input: vec![],
+            secret_input: vec![],
+            expected_output: vec![9],
+        },
+        ConformanceCase {
+            name: "memory_store_load",
+            feature: Feature::Memory,
+            wat: r#"
+(module
+    (memory 1)
+    (start $main)
+    (func $main
+        i32.const 16
+        i32.const 9
+        i32.store
+        i32.const 16
+        i32.load
+        return)
+)"#,
+            input: vec![],
+            secret_input: vec![],
+            expected_output: vec![9],
+        },
+        ConformanceCase {
+            name: "pub_input_to_output",
+            feature: Feature::Io,
+            wat: r#"
+(module
+    (type (;0;) (func (result i64)))
+    (type (;1;) (func (param i64)))
+    (import "env" "ozk_stdlib_pub_input" (func $ozk_stdlib_pub_input (type 0)))
+    (import "env" "ozk_stdlib_pub_output" (func $ozk_stdlib_pub_output (type 1)))
+    (start $main)
+    (func $main
+        call $ozk_stdlib_pub_input
+        call $ozk_stdlib_pub_output
+        return)
+)"#,
+            input: vec![5],
+            secret_input: vec![],
+            expected_output: vec![5],
+        },
+        ConformanceCase {
+            name: "clock",
+            feature: Feature::Clock,
+            wat: r#"
+(module
+    (import "env" "ozk_stdlib_clock" (func $ozk_stdlib_clock (result i64)))
+    (start $main)
+    (func $main
+        call $ozk_stdlib_clock
+        i32.const 5
+        return)
+)"#,
+            input: vec![],
+            secret_input: vec![],
+            expected_output: vec![5],
+        },
+        ConformanceCase {
+            name: "debug_print",
+            feature: Feature::DebugPrint,
+            wat: r#"
+(module
+    (import "env" "ozk_stdlib_debug_print" (func $ozk_stdlib_debug_print (param i64)))
+    (start $main)
+    (func $main
+        i64.const 7
+        call $ozk_stdlib_debug_print
+        i32.const 5
+        return)
+)"#,
+            input: vec![],
+            secret_input: vec![],
+            expected_output: vec![5],
+        },
+    ]
+}
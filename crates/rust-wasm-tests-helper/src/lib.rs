@@ -18,8 +18,14 @@
 #![deny(clippy::unimplemented)]
 #![deny(clippy::panic)]
 
+pub mod conformance;
+
 extern crate ozk_rust_wasm_tests_add;
+extern crate ozk_rust_wasm_tests_assert;
+extern crate ozk_rust_wasm_tests_checked_math;
+extern crate ozk_rust_wasm_tests_consts;
 extern crate ozk_rust_wasm_tests_fib;
+extern crate ozk_rust_wasm_tests_sort;
 
 #[allow(clippy::type_complexity)]
 pub fn wrap_main_with_io(
@@ -32,35 +38,152 @@ pub fn wrap_main_with_io(
     })
 }
 
-#[allow(clippy::unwrap_used)]
+/// Cargo build profile of the Rust-to-Wasm test bundle
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum BuildProfile {
+    Dev,
+    #[default]
+    Release,
+    /// A custom profile defined in the bundle's manifest
+    Custom(String),
+}
+
+impl BuildProfile {
+    fn name(&self) -> &str {
+        match self {
+            BuildProfile::Dev => "dev",
+            BuildProfile::Release => "release",
+            BuildProfile::Custom(name) => name,
+        }
+    }
+
+    /// The directory (in the target dir) with the build artifacts
+    fn dir_name(&self) -> &str {
+        match self {
+            BuildProfile::Dev => "debug",
+            BuildProfile::Release => "release",
+            BuildProfile::Custom(name) => name,
+        }
+    }
+}
+
+/// Extra cargo options for building the Rust-to-Wasm test bundle
+#[derive(Debug, Clone, Default)]
+pub struct RustWasmBuildOptions {
+    pub profile: BuildProfile,
+    /// The package to build if the bundle is a workspace with multiple local crates (`--package`)
+    pub package: Option<String>,
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+    /// Any other cargo flags, passed as is
+    pub extra_args: Vec<String>,
+}
+
+/// Failed build of the Rust-to-Wasm test bundle
+#[derive(Debug)]
+pub enum RustWasmBuildError {
+    /// Cargo could not be started
+    Spawn(std::io::Error),
+    /// Cargo exited with an error
+    BuildFailed {
+        /// Exit code (None if killed by a signal)
+        exit_code: Option<i32>,
+        /// `error...` lines of the cargo output
+        errors: Vec<String>,
+        /// Full cargo output
+        stderr: String,
+    },
+    /// The built Wasm binary could not be read
+    ReadWasm {
+        path: std::path::PathBuf,
+        error: std::io::Error,
+    },
+}
+
+impl std::fmt::Display for RustWasmBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RustWasmBuildError::Spawn(e) => write!(f, "failed to run cargo: {e}"),
+            RustWasmBuildError::BuildFailed {
+                exit_code,
+                errors,
+                stderr,
+            } => {
+                writeln!(f, "cargo build failed (exit code {exit_code:?}):")?;
+                if errors.is_empty() {
+                    write!(f, "{stderr}")
+                } else {
+                    write!(f, "{}", errors.join("\n"))
+                }
+            }
+            RustWasmBuildError::ReadWasm { path, error } => {
+                write!(f, "failed to read {}: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for RustWasmBuildError {}
+
+/// Build the bin of the Rust-to-Wasm test bundle (in `crates/rust-wasm-tests`) with the default
+/// options and return the Wasm binary. Panics if the build fails.
+#[allow(clippy::panic)]
 pub fn compile_rust_wasm_tests(bundle_name: &str, bin_name: &str) -> Vec<u8> {
+    build_rust_wasm_tests(bundle_name, bin_name, &RustWasmBuildOptions::default())
+        .unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Build the bin of the Rust-to-Wasm test bundle (in `crates/rust-wasm-tests`) and return the
+/// Wasm binary.
+pub fn build_rust_wasm_tests(
+    bundle_name: &str,
+    bin_name: &str,
+    options: &RustWasmBuildOptions,
+) -> Result<Vec<u8>, RustWasmBuildError> {
     // TODO: make it relative to this crate (not the one it is called from)
     let manifest_path = format!("../rust-wasm-tests/{}/Cargo.toml", bundle_name);
-    // let pwd = std::process::Command::new("pwd").output().unwrap();
-    // dbg!(&pwd);
     let target_dir = format!("/tmp/ozk-rust-wasm-tests/{}", bundle_name);
-    let comp_status = std::process::Command::new("cargo")
-        .arg("build")
+    let mut cmd = std::process::Command::new("cargo");
+    cmd.arg("build")
         .arg("--manifest-path")
         .arg(manifest_path)
-        .arg("--release")
-        // .arg("--bin")
-        // .arg(bin_name)
+        .arg("--profile")
+        .arg(options.profile.name())
         .arg("--bins")
         .arg("--target=wasm32-unknown-unknown")
         .arg("--target-dir")
-        .arg(target_dir.clone())
-        .status()
-        .unwrap();
-    dbg!(&comp_status);
-    assert!(comp_status.success());
+        .arg(&target_dir);
+    if let Some(package) = &options.package {
+        cmd.arg("--package").arg(package);
+    }
+    if !options.features.is_empty() {
+        cmd.arg("--features").arg(options.features.join(","));
+    }
+    if options.no_default_features {
+        cmd.arg("--no-default-features");
+    }
+    cmd.args(&options.extra_args);
+    let output = cmd.output().map_err(RustWasmBuildError::Spawn)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let errors = stderr
+            .lines()
+            .filter(|line| line.starts_with("error"))
+            .map(str::to_string)
+            .collect();
+        return Err(RustWasmBuildError::BuildFailed {
+            exit_code: output.status.code(),
+            errors,
+            stderr,
+        });
+    }
     let target_bin_file_path = std::path::Path::new(&target_dir)
         .join("wasm32-unknown-unknown")
-        .join("release")
+        .join(options.profile.dir_name())
         .join(bin_name)
         .with_extension("wasm");
-    let mut target_bin_file = std::fs::File::open(target_bin_file_path).unwrap();
-    let mut wasm_bytes = vec![];
-    std::io::Read::read_to_end(&mut target_bin_file, &mut wasm_bytes).unwrap();
-    wasm_bytes
+    std::fs::read(&target_bin_file_path).map_err(|error| RustWasmBuildError::ReadWasm {
+        path: target_bin_file_path,
+        error,
+    })
 }
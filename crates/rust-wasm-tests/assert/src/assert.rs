@@ -0,0 +1,18 @@
+use ozk_stdlib::*;
+
+#[inline(never)]
+#[no_mangle]
+fn add(a: u64, b: u64) -> u64 {
+    a + b
+}
+
+/// In-circuit test of `add`: the expected sum is the last public input
+#[no_mangle]
+pub fn main_assert() {
+    let a = pub_input();
+    let b = pub_input();
+    let sum = add(a, b);
+    assert_pub_input_eq(sum);
+    assert_eq(sum, add(b, a));
+    pub_output(sum);
+}
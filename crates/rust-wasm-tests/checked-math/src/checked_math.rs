@@ -0,0 +1,22 @@
+use ozk_stdlib::*;
+
+#[inline(never)]
+#[no_mangle]
+fn add_u32(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[inline(never)]
+#[no_mangle]
+fn sub_u32(a: u32, b: u32) -> u32 {
+    a - b
+}
+
+/// Panics on overflow if built with the overflow checks (e.g. the dev profile)
+#[no_mangle]
+pub fn main_checked_math() {
+    let a = pub_input() as u32;
+    let b = pub_input() as u32;
+    let c = secret_input() as u32;
+    pub_output(sub_u32(add_u32(a, b), c) as u64);
+}
@@ -0,0 +1,24 @@
+use ozk_stdlib::*;
+
+/// The configuration constants are returned by the functions that are kept as calls in the Wasm
+#[inline(never)]
+#[no_mangle]
+fn base_fee() -> u64 {
+    3
+}
+
+/// Computed from another configuration function
+#[inline(never)]
+#[no_mangle]
+fn fee_multiplier() -> u64 {
+    base_fee() * 7 + 1
+}
+
+/// Reads the amount, outputs the amount with the base fee added and the amount multiplied by
+/// the fee multiplier
+#[no_mangle]
+pub fn apply_fees() {
+    let amount = pub_input();
+    pub_output(amount + base_fee());
+    pub_output(amount * fee_multiplier());
+}
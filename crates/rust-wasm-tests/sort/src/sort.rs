@@ -0,0 +1,45 @@
+use ozk_stdlib::*;
+
+const MAX_LEN: usize = 8;
+
+/// Weights of the sorted values (read from a data segment)
+static WEIGHTS: [u64; MAX_LEN] = [1, 2, 3, 5, 8, 13, 21, 34];
+
+#[inline(never)]
+#[no_mangle]
+fn insertion_sort(values: &mut [u64]) {
+    for i in 1..values.len() {
+        let mut j = i;
+        while j > 0 && values[j - 1] > values[j] {
+            values.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+#[inline(never)]
+#[no_mangle]
+fn weighted_sum(values: &[u64]) -> u64 {
+    values
+        .iter()
+        .zip(WEIGHTS.iter())
+        .map(|(value, weight)| value * weight)
+        .sum()
+}
+
+/// Reads the number of values (at most 8) and the values, outputs the sorted values
+/// and their weighted sum
+#[no_mangle]
+pub fn sort_and_sum() {
+    let len = (pub_input() as usize).min(MAX_LEN);
+    let mut values = [0u64; MAX_LEN];
+    for value in values.iter_mut().take(len) {
+        *value = pub_input();
+    }
+    let values = &mut values[..len];
+    insertion_sort(values);
+    for value in values.iter() {
+        pub_output(*value);
+    }
+    pub_output(weighted_sum(values));
+}
@@ -6,8 +6,14 @@ thread_local! {
     static PUB_INPUT: RefCell<Vec<u64>> = RefCell::new(vec![]);
     static PUB_OUTPUT: RefCell<Vec<u64>> = RefCell::new(vec![]);
     static SECRET_INPUT: RefCell<Vec<u64>> = RefCell::new(vec![]);
+    static DEBUG_OUTPUT: RefCell<Vec<u64>> = RefCell::new(vec![]);
+    static DIGEST: RefCell<u64> = RefCell::new(FNV_OFFSET_BASIS);
 }
 
+// 64-bit FNV-1a, the digest computed by the target runtime
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
 pub fn init_io(pub_input: Vec<u64>, secret_input: Vec<u64>) {
     let mut pub_input_reversed = pub_input;
     pub_input_reversed.reverse();
@@ -22,12 +28,29 @@ pub fn init_io(pub_input: Vec<u64>, secret_input: Vec<u64>) {
     PUB_OUTPUT.with(|v| {
         *v.borrow_mut() = vec![];
     });
+    DEBUG_OUTPUT.with(|v| {
+        *v.borrow_mut() = vec![];
+    });
+    DIGEST.with(|v| {
+        *v.borrow_mut() = FNV_OFFSET_BASIS;
+    });
 }
 
 pub fn get_pub_output() -> Vec<u64> {
     PUB_OUTPUT.with(|v| v.borrow().clone())
 }
 
+/// Values written with `debug_print` since the last [init_io] call
+pub fn get_debug_output() -> Vec<u64> {
+    DEBUG_OUTPUT.with(|v| v.borrow().clone())
+}
+
+/// Digest of the bytes committed with `commit` since the last [init_io] call, the only public
+/// output of a no-io program
+pub fn get_digest() -> u64 {
+    DIGEST.with(|v| *v.borrow())
+}
+
 pub(crate) fn pub_input() -> u64 {
     #[allow(clippy::unwrap_used)]
     PUB_INPUT.with(|v| v.borrow_mut().pop().unwrap())
@@ -41,3 +64,25 @@ pub(crate) fn secret_input() -> u64 {
     #[allow(clippy::unwrap_used)]
     SECRET_INPUT.with(|v| v.borrow_mut().pop().unwrap())
 }
+
+pub(crate) fn commit(bytes: &[u8]) {
+    DIGEST.with(|v| {
+        let mut digest = v.borrow_mut();
+        for byte in bytes {
+            *digest = (*digest ^ *byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    });
+}
+
+pub(crate) fn clock() -> u64 {
+    0
+}
+
+pub(crate) fn assert(cond: bool) {
+    assert!(cond, "ozk_stdlib::assert failed");
+}
+
+#[cfg(feature = "debug")]
+pub(crate) fn debug_print(x: u64) {
+    DEBUG_OUTPUT.with(|v| v.borrow_mut().push(x));
+}
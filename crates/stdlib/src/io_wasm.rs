@@ -2,6 +2,11 @@ extern "C" {
     fn ozk_stdlib_pub_input() -> u64;
     fn ozk_stdlib_pub_output(x: u64);
     fn ozk_stdlib_secret_input() -> u64;
+    fn ozk_stdlib_clock() -> u64;
+    fn ozk_stdlib_assert(cond: u32);
+    fn ozk_stdlib_commit(addr: u32, len: u32);
+    #[cfg(feature = "debug")]
+    fn ozk_stdlib_debug_print(x: u64);
 }
 
 pub fn pub_input() -> u64 {
@@ -15,3 +20,20 @@ pub fn pub_output(x: u64) {
 pub fn secret_input() -> u64 {
     unsafe { ozk_stdlib_secret_input() }
 }
+
+pub fn clock() -> u64 {
+    unsafe { ozk_stdlib_clock() }
+}
+
+pub fn assert(cond: bool) {
+    unsafe { ozk_stdlib_assert(cond as u32) }
+}
+
+pub fn commit(bytes: &[u8]) {
+    unsafe { ozk_stdlib_commit(bytes.as_ptr() as u32, bytes.len() as u32) }
+}
+
+#[cfg(feature = "debug")]
+pub fn debug_print(x: u64) {
+    unsafe { ozk_stdlib_debug_print(x) }
+}
@@ -82,3 +82,90 @@ pub fn secret_input() -> u64 {
     #[cfg(target_arch = "wasm32")]
     return io_wasm::secret_input();
 }
+
+/// Current VM cycle (clock) count, for coarse profiling.
+/// Only the targets that expose the cycle counter support it (e.g. Miden),
+/// compilation fails for the others. Natively it always returns 0.
+#[no_mangle]
+pub fn clock() -> u64 {
+    #[cfg(feature = "std")]
+    #[cfg(not(target_arch = "wasm32"))]
+    return io_native::clock();
+
+    #[cfg(target_arch = "wasm32")]
+    return io_wasm::clock();
+}
+
+/// Abort the program (the proof can't be generated) if `cond` is false.
+/// Natively it panics.
+#[no_mangle]
+pub fn assert(cond: bool) {
+    #[cfg(feature = "std")]
+    #[cfg(not(target_arch = "wasm32"))]
+    return io_native::assert(cond);
+
+    #[cfg(target_arch = "wasm32")]
+    return io_wasm::assert(cond);
+}
+
+/// Commit the bytes to the digest that is the only public output of a no-io program (compiled
+/// in the no-io mode, e.g. `MidenTargetConfig::with_no_io`). The bytes are absorbed when
+/// committed, later changes are not in the digest. Natively the digest is computed as well
+/// (see `io_native::get_digest`).
+#[no_mangle]
+pub fn commit(bytes: &[u8]) {
+    #[cfg(feature = "std")]
+    #[cfg(not(target_arch = "wasm32"))]
+    return io_native::commit(bytes);
+
+    #[cfg(target_arch = "wasm32")]
+    return io_wasm::commit(bytes);
+}
+
+/// Abort the program if `a` is not equal to `b`, see [assert].
+#[no_mangle]
+pub fn assert_eq(a: u64, b: u64) {
+    assert(a == b);
+}
+
+/// Abort the program if `value` is not equal to the next public input, see [assert].
+/// The expected values are passed as the public inputs, so a program can check
+/// its computed values in-circuit (e.g. an in-circuit unit test).
+#[no_mangle]
+pub fn assert_pub_input_eq(value: u64) {
+    assert_eq(value, pub_input());
+}
+
+/// Write the value to the debug output (a separate tape, not part of the public output).
+/// Compiled out unless the "debug" feature is enabled, so it can be left in the proving builds.
+/// Natively the values are collected (see `io_native::get_debug_output`), the targets without
+/// a debug output drop them.
+#[no_mangle]
+#[allow(unused_variables)]
+pub fn debug_print(x: u64) {
+    #[cfg(feature = "debug")]
+    #[cfg(feature = "std")]
+    #[cfg(not(target_arch = "wasm32"))]
+    io_native::debug_print(x);
+
+    #[cfg(feature = "debug")]
+    #[cfg(target_arch = "wasm32")]
+    io_wasm::debug_print(x);
+}
+
+/// Write the values to the debug output, see [debug_print].
+///
+/// # Example
+///
+/// ```
+/// let (a, b) = (1u64, 2u32);
+/// ozk_stdlib::debug_println!(a, b);
+/// ```
+#[macro_export]
+macro_rules! debug_println {
+    ($($value:expr),* $(,)?) => {
+        $(
+            $crate::debug_print($value as u64);
+        )*
+    };
+}
@@ -0,0 +1,302 @@
+//! Renders the ABI of the targets (memory layout, frame layout, I/O conventions) as Markdown.
+//! The addresses, offsets and sizes are taken from the code objects the compiler uses
+//! ([MidenMemoryLayout], [ValidaCallConv], the default target configs). The I/O conventions are
+//! written by hand and have to be updated along with the I/O lowering, the snapshot test of the
+//! rendered document shows every change of it.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use ozk_codegen_midenvm::MidenMemoryLayout;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use ozk_codegen_valida::emulator::ENTRY_FP;
+use ozk_codegen_valida::ValidaTargetConfig;
+use ozk_ir_transform::byte_layout::ByteLayout;
+use ozk_ir_transform::byte_layout::CELL_BYTES;
+use ozk_ir_transform::valida::fp_from_wasm_stack;
+use ozk_ir_transform::valida::ValidaCallConv;
+
+/// Options of the `abi` task
+#[derive(Debug)]
+pub struct AbiOptions {
+    /// File to write the document to (stdout if not set)
+    pub out: Option<PathBuf>,
+}
+
+impl AbiOptions {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = AbiOptions { out: None };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--out" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("missing value of {arg}"))?;
+                    let cwd = std::env::current_dir()
+                        .map_err(|e| format!("failed to get the current directory: {e}"))?;
+                    options.out = Some(cwd.join(value));
+                }
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Render the ABI document and write it
+pub fn run(options: &AbiOptions) -> Result<(), String> {
+    let doc = render().map_err(|e| format!("failed to render the ABI: {e}"))?;
+    match &options.out {
+        Some(path) => std::fs::write(path, doc)
+            .map_err(|e| format!("failed to write {}: {e}", path.display())),
+        None => {
+            print!("{doc}");
+            Ok(())
+        }
+    }
+}
+
+fn render() -> Result<String, std::fmt::Error> {
+    let mut doc = String::new();
+    writeln!(doc, "# OmniZK target ABI")?;
+    writeln!(doc)?;
+    writeln!(doc, "Generated by `cargo xtask abi`, do not edit.")?;
+    writeln!(doc)?;
+    let miden_config = MidenTargetConfig::default();
+    render_miden(&mut doc, &miden_config.memory_layout)?;
+    let valida_config = ValidaTargetConfig::default();
+    render_valida(&mut doc, &valida_config.byte_layout)?;
+    Ok(doc)
+}
+
+fn render_miden(doc: &mut String, layout: &MidenMemoryLayout) -> std::fmt::Result {
+    writeln!(doc, "## Miden")?;
+    writeln!(doc)?;
+    writeln!(doc, "### I/O")?;
+    writeln!(doc)?;
+    writeln!(doc, "- Public inputs are passed on the operand stack.")?;
+    writeln!(doc, "- Secret inputs are passed on the advice stack.")?;
+    writeln!(doc, "- Public outputs are left on the operand stack.")?;
+    writeln!(doc)?;
+    writeln!(doc, "### Memory regions")?;
+    writeln!(doc)?;
+    writeln!(
+        doc,
+        "The Wasm memory 0 starts at address 0, the reserved regions are allocated downwards \
+         from the end of the memory."
+    )?;
+    writeln!(doc)?;
+    writeln!(doc, "| Region | Start address |")?;
+    writeln!(doc, "|---|---|")?;
+    let mut regions: Vec<(String, i64)> = layout.regions().into_iter().collect();
+    regions.sort_by(|(_, a), (_, b)| b.cmp(a));
+    for (name, address) in regions {
+        writeln!(doc, "| {name} | {address:#x} |")?;
+    }
+    writeln!(doc)?;
+    writeln!(
+        doc,
+        "Reserved sizes: {} bytes of passive data segments, {} bytes of tables.",
+        layout.max_passive_data_bytes, layout.max_table_bytes
+    )?;
+    writeln!(doc)?;
+    writeln!(doc, "### Reserved slots")?;
+    writeln!(doc)?;
+    writeln!(doc, "| Slot | Address |")?;
+    writeln!(doc, "|---|---|")?;
+    for (name, address) in layout.reserved_slots() {
+        writeln!(doc, "| {name} | {:#x} |", u32::from(address))?;
+    }
+    writeln!(doc)?;
+    render_byte_layout(doc, &layout.byte_layout)
+}
+
+fn render_valida(doc: &mut String, byte_layout: &ByteLayout) -> std::fmt::Result {
+    writeln!(doc, "## Valida")?;
+    writeln!(doc)?;
+    writeln!(doc, "### I/O")?;
+    writeln!(doc)?;
+    writeln!(
+        doc,
+        "- The start function is called with fp = {ENTRY_FP:#x} and its return value is the \
+         program output."
+    )?;
+    writeln!(doc, "- Public and secret inputs are not supported yet.")?;
+    writeln!(doc)?;
+    writeln!(doc, "### Call frame")?;
+    writeln!(doc)?;
+    let (num_params, num_locals, num_stack_cells) = (2, 1, 2);
+    writeln!(
+        doc,
+        "Offsets are relative to the callee's fp, the memory cells are {CELL_BYTES} bytes. \
+         The frame of a function with {num_params} params, {num_locals} local and at most \
+         {num_stack_cells} values on the Wasm stack:"
+    )?;
+    writeln!(doc)?;
+    writeln!(doc, "```text")?;
+    render_valida_frame(doc, num_params, num_locals, num_stack_cells)?;
+    writeln!(doc, "```")?;
+    writeln!(doc)?;
+    render_byte_layout(doc, byte_layout)
+}
+
+fn render_valida_frame(
+    doc: &mut String,
+    num_params: i32,
+    num_locals: i32,
+    num_stack_cells: i32,
+) -> std::fmt::Result {
+    let row = |doc: &mut String, offset: i32, cell: &str| writeln!(doc, "{offset:>5} | {cell}");
+    let return_value_offset = ValidaCallConv::return_value_offset(num_params);
+    for param_idx in (0..num_params).rev() {
+        let offset = ValidaCallConv::arg_offset(param_idx);
+        if offset == return_value_offset {
+            row(doc, offset, &format!("param {param_idx}, return value"))?;
+        } else {
+            row(doc, offset, &format!("param {param_idx}"))?;
+        }
+    }
+    if num_params == 0 {
+        row(doc, return_value_offset, "return value")?;
+    }
+    row(doc, ValidaCallConv::RETURN_FP_OFFSET, "return fp")?;
+    row(doc, ValidaCallConv::RETURN_ADDRESS_OFFSET, "return address")?;
+    // the params have the local cells too (for `local.set`)
+    let locals_cells = num_params + num_locals;
+    for local_idx in 0..locals_cells {
+        row(
+            doc,
+            ValidaCallConv::local_offset(local_idx),
+            &format!("local {local_idx}"),
+        )?;
+    }
+    // the tracked stack depth counts the local cells
+    let max_stack_depth = locals_cells + num_stack_cells;
+    for depth in locals_cells + 1..=max_stack_depth {
+        let fp: i32 = fp_from_wasm_stack(depth.into()).into();
+        row(doc, fp, &format!("Wasm stack {}", depth - locals_cells))?;
+    }
+    for scratch_idx in 1..=ValidaCallConv::SCRATCH_CELLS as i32 {
+        let fp: i32 = fp_from_wasm_stack((max_stack_depth + scratch_idx).into()).into();
+        row(doc, fp, &format!("scratch {}", scratch_idx - 1))?;
+    }
+    let frame_size = ValidaCallConv::frame_size(max_stack_depth as u32, locals_cells as u32);
+    writeln!(
+        doc,
+        "      | callee linkage area ({} bytes)",
+        ValidaCallConv::LINKAGE_BYTES
+    )?;
+    writeln!(
+        doc,
+        "{:>5} | frame end, callee fp = fp - {frame_size} - {CELL_BYTES} * callee args",
+        -(frame_size as i32)
+    )
+}
+
+fn render_byte_layout(doc: &mut String, byte_layout: &ByteLayout) -> std::fmt::Result {
+    writeln!(doc, "### Wasm memory")?;
+    writeln!(doc)?;
+    writeln!(
+        doc,
+        "{CELL_BYTES} bytes of the Wasm memory per cell, {:?}-endian bytes in a cell, \
+         {:?}-endian 32-bit words of an i64.",
+        byte_layout.byte_order, byte_layout.word_order
+    )?;
+    writeln!(doc)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    #[test]
+    fn render_snapshot() {
+        expect![[r#"
+            # OmniZK target ABI
+
+            Generated by `cargo xtask abi`, do not edit.
+
+            ## Miden
+
+            ### I/O
+
+            - Public inputs are passed on the operand stack.
+            - Secret inputs are passed on the advice stack.
+            - Public outputs are left on the operand stack.
+
+            ### Memory regions
+
+            The Wasm memory 0 starts at address 0, the reserved regions are allocated downwards from the end of the memory.
+
+            | Region | Start address |
+            |---|---|
+            | pub_inputs | 0x7fffffff |
+            | pub_outputs | 0x7fffffff |
+            | globals | 0x7fffbfff |
+            | br_propagation | 0x7fff9fff |
+            | scratch | 0x7fff9ff7 |
+            | memory_size | 0x7fff9fef |
+            | passive_data | 0x7ffe9fe4 |
+            | tables | 0x7ffe5fe4 |
+            | digest | 0x7ffe5fd8 |
+            | memory_7 | 0x70000000 |
+            | memory_6 | 0x60000000 |
+            | memory_5 | 0x50000000 |
+            | memory_4 | 0x40000000 |
+            | memory_3 | 0x30000000 |
+            | memory_2 | 0x20000000 |
+            | memory_1 | 0x10000000 |
+
+            Reserved sizes: 65536 bytes of passive data segments, 16384 bytes of tables.
+
+            ### Reserved slots
+
+            | Slot | Address |
+            |---|---|
+            | br_propagation | 0x7fff9fff |
+            | memory_size | 0x7fff9fef |
+            | scratch | 0x7fff9ff7 |
+
+            ### Wasm memory
+
+            4 bytes of the Wasm memory per cell, Little-endian bytes in a cell, Little-endian 32-bit words of an i64.
+
+            ## Valida
+
+            ### I/O
+
+            - The start function is called with fp = 0x1000 and its return value is the program output.
+            - Public and secret inputs are not supported yet.
+
+            ### Call frame
+
+            Offsets are relative to the callee's fp, the memory cells are 4 bytes. The frame of a function with 2 params, 1 local and at most 2 values on the Wasm stack:
+
+            ```text
+               16 | param 1, return value
+               12 | param 0
+                4 | return fp
+                0 | return address
+               -4 | local 0
+               -8 | local 1
+              -12 | local 2
+              -16 | Wasm stack 1
+              -20 | Wasm stack 2
+              -24 | scratch 0
+              -28 | scratch 1
+                  | callee linkage area (12 bytes)
+              -40 | frame end, callee fp = fp - 40 - 4 * callee args
+            ```
+
+            ### Wasm memory
+
+            4 bytes of the Wasm memory per cell, Little-endian bytes in a cell, Little-endian 32-bit words of an i64.
+
+        "#]]
+        .assert_eq(&render().unwrap());
+    }
+}
@@ -0,0 +1,377 @@
+//! Compilation and execution benchmarks of the Rust-to-Wasm test programs. For every program
+//! and target the report has the time of each compilation stage (the frontend, the IR passes
+//! and the emission) and of the execution, and the cost of the emitted program (instruction
+//! count, executed VM cycles). A failed compilation or execution is reported instead of the
+//! numbers, so the report always covers all the programs and targets and the reports of the
+//! different compiler versions can be compared to track the regressions.
+
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use ozk_artifact::CompilerInfo;
+use ozk_artifact::Target;
+use ozk_codegen_midenvm::MidenTargetConfig;
+use ozk_codegen_valida::ValidaTargetConfig;
+use ozk_frontend_wasm::WasmFrontendConfig;
+use ozk_ir_transform::miden::lowering::MidenControlFlow;
+use ozk_ir_transform::wasm::single_func::run_passes_on_module;
+use ozk_miden_dialect::ops as miden;
+use ozk_rust_wasm_tests_helper::build_rust_wasm_tests;
+use ozk_rust_wasm_tests_helper::BuildProfile;
+use ozk_rust_wasm_tests_helper::RustWasmBuildOptions;
+use ozk_valida_dialect::ops as valida;
+use pliron::context::Context;
+use pliron::op::Op;
+use serde::Serialize;
+
+/// A benchmarked program: the bin of a Rust-to-Wasm test bundle and its inputs
+struct BenchProgram {
+    name: &'static str,
+    bundle_name: &'static str,
+    bin_name: &'static str,
+    profile: BuildProfile,
+    input: Vec<u64>,
+    secret_input: Vec<u64>,
+}
+
+/// The programs of the Rust-to-Wasm test bundles (`crates/rust-wasm-tests`) with the inputs
+/// of their tests
+fn bench_programs() -> Vec<BenchProgram> {
+    let program = |name, bundle_name, bin_name, input, secret_input| BenchProgram {
+        name,
+        bundle_name,
+        bin_name,
+        profile: BuildProfile::Release,
+        input,
+        secret_input,
+    };
+    vec![
+        program("fib", "fib-bin", "fib", vec![25], vec![]),
+        program("add", "add-bin", "add", vec![11, 7], vec![3]),
+        program("sort", "sort-bin", "sort", vec![4, 9, 3, 7, 1], vec![]),
+        program("assert", "assert-bin", "assert", vec![11, 7, 18], vec![]),
+        BenchProgram {
+            profile: BuildProfile::Dev,
+            ..program(
+                "checked-math",
+                "checked-math-bin",
+                "checked_math",
+                vec![7, 3],
+                vec![4],
+            )
+        },
+    ]
+}
+
+/// Options of the `bench` task
+#[derive(Debug)]
+pub struct BenchOptions {
+    /// File to write the report to (stdout if not set)
+    out: Option<PathBuf>,
+    /// Names of the programs to benchmark (all if empty)
+    programs: Vec<String>,
+    targets: Vec<Target>,
+    /// Number of the compilations (and executions) per program and target, the fastest
+    /// one is reported
+    runs: u32,
+    /// Lowering of the blocks and loops for Miden, the reports of both lowerings compare
+    /// the structured control flow with the outlined procs
+    miden_control_flow: MidenControlFlow,
+}
+
+impl BenchOptions {
+    /// Parse the task arguments
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = BenchOptions {
+            out: None,
+            programs: Vec::new(),
+            targets: vec![Target::Miden, Target::Valida],
+            runs: 1,
+            miden_control_flow: MidenControlFlow::default(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("missing value of {arg}"));
+            match arg.as_str() {
+                "--out" => {
+                    // relative to the directory the task is run from
+                    let cwd = std::env::current_dir()
+                        .map_err(|e| format!("failed to get the current directory: {e}"))?;
+                    options.out = Some(cwd.join(value()?));
+                }
+                "--programs" => {
+                    let known: Vec<&str> = bench_programs().iter().map(|p| p.name).collect();
+                    options.programs = value()?.split(',').map(str::to_string).collect();
+                    if let Some(unknown) = options
+                        .programs
+                        .iter()
+                        .find(|name| !known.contains(&name.as_str()))
+                    {
+                        return Err(format!(
+                            "unknown program {unknown}, expected one of {known:?}"
+                        ));
+                    }
+                }
+                "--targets" => {
+                    options.targets = value()?
+                        .split(',')
+                        .map(|target| match target {
+                            "miden" => Ok(Target::Miden),
+                            "valida" => Ok(Target::Valida),
+                            _ => Err(format!(
+                                "unsupported target {target}, expected miden or valida"
+                            )),
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "--runs" => {
+                    options.runs = value()?
+                        .parse()
+                        .ok()
+                        .filter(|runs| *runs > 0)
+                        .ok_or_else(|| "--runs expects a positive number".to_string())?;
+                }
+                "--miden-control-flow" => {
+                    options.miden_control_flow = match value()?.as_str() {
+                        "outline" => MidenControlFlow::Outline,
+                        "structured" => MidenControlFlow::Structured,
+                        other => {
+                            return Err(format!(
+                                "unknown control flow {other}, expected outline or structured"
+                            ))
+                        }
+                    };
+                }
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// The benchmark report (the JSON output)
+#[derive(Debug, Serialize)]
+struct BenchReport {
+    compiler: CompilerInfo,
+    /// Seconds since the Unix epoch
+    timestamp: u64,
+    runs: u32,
+    miden_control_flow: String,
+    results: Vec<BenchResult>,
+}
+
+/// The numbers of a program compiled for a target
+#[derive(Debug, Serialize)]
+struct BenchResult {
+    program: String,
+    target: Target,
+    /// Size of the Wasm binary in bytes
+    wasm_size: usize,
+    /// Compilation and execution times (of the fastest run)
+    times: Option<StageTimes>,
+    cost: Option<ProgramCost>,
+    /// Why the program could not be compiled or executed
+    error: Option<String>,
+}
+
+/// Times in microseconds
+#[derive(Debug, Clone, Default, Serialize)]
+struct StageTimes {
+    frontend_us: u128,
+    passes_us: u128,
+    emit_us: u128,
+    compile_us: u128,
+    execution_us: u128,
+}
+
+impl StageTimes {
+    fn new(frontend: Duration, passes: Duration, emit: Duration, execution: Duration) -> Self {
+        StageTimes {
+            frontend_us: frontend.as_micros(),
+            passes_us: passes.as_micros(),
+            emit_us: emit.as_micros(),
+            compile_us: (frontend + passes + emit).as_micros(),
+            execution_us: execution.as_micros(),
+        }
+    }
+}
+
+/// The cost of the emitted program
+#[derive(Debug, Clone, Serialize)]
+struct ProgramCost {
+    instructions: usize,
+    /// VM cycles of the execution (if the target counts them)
+    cycles: Option<u64>,
+}
+
+/// Run the benchmarks and write the report
+pub fn run(options: &BenchOptions) -> Result<(), String> {
+    let mut results = Vec::new();
+    for program in bench_programs() {
+        if !options.programs.is_empty() && !options.programs.iter().any(|p| p == program.name) {
+            continue;
+        }
+        let build_options = RustWasmBuildOptions {
+            profile: program.profile.clone(),
+            ..Default::default()
+        };
+        eprintln!("building {}", program.name);
+        let wasm = build_rust_wasm_tests(program.bundle_name, program.bin_name, &build_options)
+            .map_err(|e| format!("failed to build {}: {e}", program.name))?;
+        for target in &options.targets {
+            eprintln!("benchmarking {} on {target:?}", program.name);
+            results.push(bench_program(&program, &wasm, *target, options));
+        }
+    }
+    let report = BenchReport {
+        compiler: CompilerInfo::current(),
+        timestamp: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        runs: options.runs,
+        miden_control_flow: format!("{:?}", options.miden_control_flow).to_lowercase(),
+        results,
+    };
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| format!("failed to serialize the report: {e}"))?;
+    match &options.out {
+        Some(path) => std::fs::write(path, json)
+            .map_err(|e| format!("failed to write {}: {e}", path.display())),
+        None => {
+            println!("{json}");
+            Ok(())
+        }
+    }
+}
+
+fn bench_program(
+    program: &BenchProgram,
+    wasm: &[u8],
+    target: Target,
+    options: &BenchOptions,
+) -> BenchResult {
+    let mut fastest: Option<(StageTimes, ProgramCost)> = None;
+    let mut error = None;
+    for _ in 0..options.runs {
+        let run = match target {
+            Target::Miden => bench_miden(program, wasm, options.miden_control_flow),
+            Target::Valida => bench_valida(wasm),
+            Target::Triton => Err("the Triton backend is not supported".to_string()),
+        };
+        match run {
+            Ok((times, cost)) => {
+                let is_faster = fastest.as_ref().map_or(true, |(fastest_times, _)| {
+                    times.compile_us + times.execution_us
+                        < fastest_times.compile_us + fastest_times.execution_us
+                });
+                if is_faster {
+                    fastest = Some((times, cost));
+                }
+            }
+            Err(e) => {
+                error = Some(e);
+                break;
+            }
+        }
+    }
+    let (times, cost) = match (error.is_none(), fastest) {
+        (true, Some((times, cost))) => (Some(times), Some(cost)),
+        _ => (None, None),
+    };
+    BenchResult {
+        program: program.name.to_string(),
+        target,
+        wasm_size: wasm.len(),
+        times,
+        cost,
+        error,
+    }
+}
+
+fn bench_miden(
+    program: &BenchProgram,
+    wasm: &[u8],
+    control_flow: MidenControlFlow,
+) -> Result<(StageTimes, ProgramCost), String> {
+    let mut ctx = Context::default();
+    let target_config = MidenTargetConfig::default().with_control_flow(control_flow);
+    target_config.register(&mut ctx);
+    let start = Instant::now();
+    let module_op = ozk_frontend_wasm::translate(&mut ctx, wasm, &WasmFrontendConfig::default())
+        .map_err(|e| e.to_string())?;
+    let frontend = start.elapsed();
+    let start = Instant::now();
+    let op = run_passes_on_module(&mut ctx, module_op, &target_config.pass_manager)
+        .map_err(|e| e.to_string())?;
+    let passes = start.elapsed();
+    let Ok(prog_op) = op
+        .deref(&ctx)
+        .get_op(&ctx)
+        .downcast::<miden::ProgramOp>() else {
+        return Err("the passes did not produce a miden.program".to_string());
+    };
+    let start = Instant::now();
+    let inst_buf = ozk_codegen_midenvm::emit_prog(&ctx, &prog_op, &target_config)
+        .map_err(|e| e.to_string())?;
+    let source = inst_buf.pretty_print();
+    let emit = start.elapsed();
+    let start = Instant::now();
+    let cycles = ozk_codegen_midenvm::vm::count_cycles(
+        &source,
+        program.input.clone(),
+        program.secret_input.clone(),
+    )
+    .map_err(|e| e.to_string())?;
+    let execution = start.elapsed();
+    Ok((
+        StageTimes::new(frontend, passes, emit, execution),
+        ProgramCost {
+            instructions: inst_buf.inst_count(),
+            cycles: Some(cycles as u64),
+        },
+    ))
+}
+
+/// The programs get no inputs, the Valida backend has no public input yet
+fn bench_valida(wasm: &[u8]) -> Result<(StageTimes, ProgramCost), String> {
+    let mut ctx = Context::default();
+    let target_config = ValidaTargetConfig::default();
+    target_config.register(&mut ctx);
+    let start = Instant::now();
+    let module_op = ozk_frontend_wasm::translate(&mut ctx, wasm, &WasmFrontendConfig::default())
+        .map_err(|e| e.to_string())?;
+    let frontend = start.elapsed();
+    let start = Instant::now();
+    let op = run_passes_on_module(&mut ctx, module_op, &target_config.pass_manager)
+        .map_err(|e| e.to_string())?;
+    let passes = start.elapsed();
+    let Ok(prog_op) = op
+        .deref(&ctx)
+        .get_op(&ctx)
+        .downcast::<valida::ProgramOp>() else {
+        return Err("the passes did not produce a valida.program".to_string());
+    };
+    let start = Instant::now();
+    let mut builder = ozk_codegen_valida::ValidaInstrBuilder::default();
+    ozk_codegen_valida::emit_op(&ctx, prog_op.get_operation(), &mut builder);
+    let instructions = builder.build();
+    let emit = start.elapsed();
+    let inst_count = instructions.len();
+    let start = Instant::now();
+    ozk_codegen_valida::emulator::run_program(
+        instructions,
+        valida_machine::PublicMemory::default(),
+    )
+    .map_err(|e| e.to_string())?;
+    let execution = start.elapsed();
+    Ok((
+        StageTimes::new(frontend, passes, emit, execution),
+        ProgramCost {
+            instructions: inst_count,
+            cycles: None,
+        },
+    ))
+}
@@ -0,0 +1,56 @@
+//! Development tasks, run with `cargo xtask <task>`:
+//!
+//! - `bench [--out <file>] [--programs <name,..>] [--targets <miden,valida>] [--runs <n>]
+//!   [--miden-control-flow <outline|structured>]` compiles (and runs) the Rust-to-Wasm test
+//!   programs for the targets and prints the compile time per stage and the cost of the
+//!   emitted programs as JSON (see [bench]).
+//! - `abi [--out <file>]` renders the ABI of the targets (memory regions, call frame, I/O
+//!   conventions) from the compiler's layout definitions as Markdown (see [abi]).
+
+// Coding conventions
+#![deny(unsafe_code)]
+#![deny(non_upper_case_globals)]
+#![deny(non_camel_case_types)]
+#![deny(non_snake_case)]
+#![deny(unused_mut)]
+#![deny(dead_code)]
+#![deny(unused_imports)]
+// Clippy exclusions
+#![deny(rustdoc::broken_intra_doc_links)]
+#![deny(clippy::wildcard_enum_match_arm)]
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::todo)]
+#![deny(clippy::unimplemented)]
+#![deny(clippy::panic)]
+
+mod abi;
+mod bench;
+
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: cargo xtask bench [--out <file>] [--programs <name,..>] \
+                     [--targets <miden,valida>] [--runs <n>] \
+                     [--miden-control-flow <outline|structured>]\n       \
+                     cargo xtask abi [--out <file>]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("bench") => bench::BenchOptions::parse(&args[1..]).and_then(|options| {
+            // the Rust-to-Wasm test bundles are found relative to the current directory
+            std::env::set_current_dir(env!("CARGO_MANIFEST_DIR"))
+                .map_err(|e| format!("failed to change the directory: {e}"))?;
+            bench::run(&options)
+        }),
+        Some("abi") => abi::AbiOptions::parse(&args[1..]).and_then(|options| abi::run(&options)),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}